@@ -10,6 +10,7 @@ interface ActionPanelProps {
   currentBet: number;
   myBet: number;
   myStack: number;
+  chipUnit?: number;
   onAction: (action: string, amount?: number) => void;
   onChainConfirmed?: boolean;
   canStartHand?: boolean;
@@ -25,6 +26,7 @@ export function ActionPanel({
   currentBet,
   myBet,
   myStack,
+  chipUnit = 1,
   onAction,
   onChainConfirmed,
   canStartHand = true,
@@ -36,7 +38,9 @@ export function ActionPanel({
   const [betAmount, setBetAmount] = useState(0);
 
   const callAmount = Math.max(currentBet - myBet, 0);
-  const minBet = Math.max(currentBet * 2, 1);
+  // Bets and raises go on chain in whole chips of the table's unit.
+  const toChips = (amount: number) => Math.floor(amount / chipUnit) * chipUnit;
+  const minBet = Math.max(toChips(currentBet * 2), chipUnit);
   const maxBet = myStack;
 
   if (phase === "waiting") {
@@ -194,6 +198,7 @@ export function ActionPanel({
             type="range"
             min={minBet}
             max={maxBet}
+            step={chipUnit}
             value={betAmount || minBet}
             onChange={(e) => setBetAmount(Number(e.target.value))}
             className="flex-1"
@@ -204,8 +209,8 @@ export function ActionPanel({
           />
           <div className="flex gap-1">
             {[
-              { label: "50%", value: Math.floor(myStack * 0.5) },
-              { label: "75%", value: Math.floor(myStack * 0.75) },
+              { label: "50%", value: toChips(myStack * 0.5) },
+              { label: "75%", value: toChips(myStack * 0.75) },
               { label: "MAX", value: myStack },
             ].map((preset) => (
               <button
@@ -204,6 +204,7 @@ export function Table({ tableId, initialPlayMode }: TableProps) {
           currentTurn: toNumber(parsed.current_turn, prev.currentTurn),
           dealerSeat: toNumber(parsed.dealer_seat, prev.dealerSeat),
           handNumber: toNumber(parsed.hand_number, prev.handNumber),
+          chipUnit: toNumber(parsed.chip_unit, prev.chipUnit),
           players: mergedPlayers,
         };
       });
@@ -314,7 +315,9 @@ export function Table({ tableId, initialPlayMode }: TableProps) {
       return;
     }
 
-    const key = `${game.handNumber}:${onChainPhase}`;
+    // A run-out stays in one phase while each street is revealed.
+    const boardCount = game.boardCards.length;
+    const key = `${game.handNumber}:${onChainPhase}:${onChainPhase === "RunOut" ? boardCount : ""}`;
     let next: (() => Promise<void>) | null = null;
 
     switch (onChainPhase) {
@@ -327,6 +330,9 @@ export function Table({ tableId, initialPlayMode }: TableProps) {
       case "DealingRiver":
         next = async () => handleReveal("river");
         break;
+      case "RunOut":
+        next = async () => handleReveal(boardCount === 0 ? "flop" : boardCount === 3 ? "turn" : "river");
+        break;
       case "Showdown":
         next = handleShowdown;
         break;
@@ -348,7 +354,7 @@ export function Table({ tableId, initialPlayMode }: TableProps) {
     }
     autoStreetRef.current = key;
     void next();
-  }, [game.handNumber, handleReveal, handleShowdown, loading, onChainPhase, wallet]);
+  }, [game.boardCards.length, game.handNumber, handleReveal, handleShowdown, loading, onChainPhase, wallet]);
 
   const dealerLine = getDealerLine({
     loading,
@@ -590,6 +596,7 @@ export function Table({ tableId, initialPlayMode }: TableProps) {
                   currentBet={displayCurrentBet}
                   myBet={displayMyBet}
                   myStack={displayMyStack}
+                  chipUnit={game.chipUnit}
                   onAction={handleAction}
                   onChainConfirmed={game.onChainConfirmed}
                   canStartHand={canStartHand}
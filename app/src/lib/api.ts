@@ -55,6 +55,37 @@ export interface ParsedTableStateResponse {
   parsed: Record<string, unknown> | null;
 }
 
+export type WaitingOn =
+  | { waiting_on: "next_hand"; seated: number }
+  | { waiting_on: "player"; seat: number; address: string }
+  | { waiting_on: "committee"; proof: "deal" | "showdown" }
+  | { waiting_on: "reveal"; street: "flop" | "turn" | "river" }
+  | { waiting_on: "dispute" };
+
+export type ReadyStateResponse = WaitingOn & {
+  table_id: number;
+  phase: string;
+  hand_number: number;
+  timeout_ledger: number | null;
+  latest_ledger: number | null;
+  last_event: { name: string; ledger: number } | null;
+};
+
+export interface PositionSeat {
+  seat_index: number;
+  address: string;
+}
+
+export interface TablePositionsResponse {
+  table_id: number;
+  phase: string;
+  hand_number: number;
+  dealer: PositionSeat | null;
+  small_blind: PositionSeat | null;
+  big_blind: PositionSeat | null;
+  next_to_act: PositionSeat | null;
+}
+
 export interface PlayerCardsResponse {
   card1: number;
   card2: number;
@@ -65,19 +96,29 @@ export interface PlayerCardsResponse {
 export interface CommitteeStatusResponse {
   nodes: number;
   healthy: boolean[];
+  identity_verified: (boolean | null)[];
+  attested: (boolean | null)[];
   status: string;
 }
 
+export interface TableNamespace {
+  name: string;
+  contract: string;
+  table_id_base: number;
+}
+
 export interface ChainConfigResponse {
   rpc_url: string;
   network_passphrase: string;
   poker_table_contract: string;
+  table_namespaces: TableNamespace[];
 }
 
 export interface CreateTableResponse {
   table_id: number;
   max_players: number;
   joined_wallets: number;
+  private: boolean;
 }
 
 export interface JoinTableResponse {
@@ -90,6 +131,7 @@ export interface JoinTableResponse {
 
 export interface OpenTableInfo {
   table_id: number;
+  namespace: string;
   phase: string;
   max_players: number;
   joined_wallets: number;
@@ -121,6 +163,19 @@ export interface AuthSigner {
 
 let lastNonce = 0;
 
+/** A player action the table contract rejected (HTTP 409). */
+export class ContractActionError extends Error {
+  constructor(
+    message: string,
+    /** `PokerTableError` variant, e.g. "NotYourTurn" or "RaiseTooSmall". */
+    readonly errorName: string,
+    readonly code: number
+  ) {
+    super(message);
+    this.name = "ContractActionError";
+  }
+}
+
 async function readApiError(res: Response, fallback: string): Promise<string> {
   try {
     const text = await res.text();
@@ -240,12 +295,16 @@ export async function createTable(
   auth: AuthSigner,
   maxPlayers: number,
   solo = false,
-  buyIn?: string
+  buyIn?: string,
+  inviteCode?: string,
+  namespace?: string
 ): Promise<CreateTableResponse> {
   const payload: {
     max_players: number;
     solo: boolean;
     buy_in?: string;
+    invite_code?: string;
+    namespace?: string;
   } = {
     max_players: maxPlayers,
     solo,
@@ -253,6 +312,12 @@ export async function createTable(
   if (buyIn) {
     payload.buy_in = buyIn;
   }
+  if (inviteCode) {
+    payload.invite_code = inviteCode;
+  }
+  if (namespace) {
+    payload.namespace = namespace;
+  }
 
   const res = await authedFetch(
     `${API_BASE}/api/tables/create`,
@@ -273,14 +338,20 @@ export async function createTable(
   return res.json();
 }
 
+function inviteHeaders(inviteCode?: string): Record<string, string> {
+  return inviteCode ? { "x-invite-code": inviteCode } : {};
+}
+
 export async function joinTable(
   tableId: number,
-  auth: AuthSigner
+  auth: AuthSigner,
+  inviteCode?: string
 ): Promise<JoinTableResponse> {
   const res = await authedFetch(
     `${API_BASE}/api/table/${tableId}/join`,
     {
       method: "POST",
+      headers: inviteHeaders(inviteCode),
     },
     tableId,
     "join_table",
@@ -309,9 +380,12 @@ export async function getChainConfig(): Promise<ChainConfigResponse> {
 }
 
 export async function getTableLobby(
-  tableId: number
+  tableId: number,
+  inviteCode?: string
 ): Promise<TableLobbyResponse> {
-  const res = await fetch(`${API_BASE}/api/table/${tableId}/lobby`);
+  const res = await fetch(`${API_BASE}/api/table/${tableId}/lobby`, {
+    headers: inviteHeaders(inviteCode),
+  });
   if (!res.ok) {
     throw new Error(await readApiError(res, `Lobby lookup failed: ${res.status}`));
   }
@@ -370,6 +444,14 @@ export async function playerAction(
     `player_action:${action}`,
     auth
   );
+  if (res.status === 409) {
+    const body = (await res.clone().json().catch(() => null)) as
+      | { error?: string; name?: string; code?: number }
+      | null;
+    if (body?.name && body.code !== undefined) {
+      throw new ContractActionError(body.error || body.name, body.name, body.code);
+    }
+  }
   if (!res.ok) {
     throw new Error(await readApiError(res, `Player action failed: ${res.status}`));
   }
@@ -394,6 +476,23 @@ export async function getPlayerCards(
   return res.json();
 }
 
+export async function getHandBundle(
+  tableId: number,
+  auth: AuthSigner
+): Promise<Blob> {
+  const res = await authedFetch(
+    `${API_BASE}/api/table/${tableId}/hand-bundle`,
+    {},
+    tableId,
+    "hand_bundle",
+    auth
+  );
+  if (!res.ok) {
+    throw new Error(await readApiError(res, `Failed to get hand bundle: ${res.status}`));
+  }
+  return res.blob();
+}
+
 export async function getTableState(
   tableId: number
 ): Promise<TableStateResponse> {
@@ -418,6 +517,26 @@ export async function getParsedTableState(
   }
 }
 
+export async function getReadyState(
+  tableId: number
+): Promise<ReadyStateResponse> {
+  const res = await fetch(`${API_BASE}/api/table/${tableId}/ready-state`);
+  if (!res.ok) {
+    throw new Error(await readApiError(res, `Failed to get ready state: ${res.status}`));
+  }
+  return res.json();
+}
+
+export async function getTablePositions(
+  tableId: number
+): Promise<TablePositionsResponse> {
+  const res = await fetch(`${API_BASE}/api/table/${tableId}/positions`);
+  if (!res.ok) {
+    throw new Error(await readApiError(res, `Failed to get positions: ${res.status}`));
+  }
+  return res.json();
+}
+
 export async function getCommitteeStatus(): Promise<CommitteeStatusResponse> {
   const res = await fetch(`${API_BASE}/api/committee/status`);
   if (!res.ok) throw new Error(`Failed to get status: ${res.status}`);
@@ -61,6 +61,9 @@ export function getDealerLine(opts: {
   if (opts.onChainPhase === "DealingRiver") {
     return "Betting round complete. Dealer is revealing the river...";
   }
+  if (opts.onChainPhase === "RunOut") {
+    return "Everyone is all-in. Dealer is running out the board...";
+  }
   if (opts.onChainPhase === "Showdown") {
     return "Betting complete. Dealer is resolving showdown...";
   }
@@ -27,6 +27,8 @@ export interface GameState {
   currentTurn: number;
   dealerSeat: number;
   handNumber: number;
+  /** Token units in one chip; bets and raises are whole chips. */
+  chipUnit: number;
   lastTxHash?: string;
   proofSize?: number;
   onChainConfirmed: boolean;
@@ -42,6 +44,7 @@ export function createInitialState(tableId: number): GameState {
     currentTurn: 0,
     dealerSeat: 0,
     handNumber: 0,
+    chipUnit: 1,
     onChainConfirmed: false,
   };
 }
@@ -9,7 +9,7 @@ import {
   xdr,
 } from "@stellar/stellar-sdk";
 import type { WalletSession } from "./freighter";
-import { getChainConfig } from "./api";
+import { getChainConfig, type TableNamespace } from "./api";
 
 type BettingAction = "fold" | "check" | "call" | "bet" | "raise" | "allin" | "all_in";
 
@@ -18,6 +18,7 @@ let cachedChainConfig:
       rpcUrl: string;
       networkPassphrase: string;
       pokerTableContract: string;
+      tableNamespaces: TableNamespace[];
     }
   | null = null;
 
@@ -28,10 +29,28 @@ async function getConfig() {
     rpcUrl: cfg.rpc_url,
     networkPassphrase: cfg.network_passphrase,
     pokerTableContract: cfg.poker_table_contract,
+    tableNamespaces: cfg.table_namespaces ?? [],
   };
   return cachedChainConfig;
 }
 
+// Coordinator table IDs from a namespace's base up belong to its contract.
+function resolveTable(
+  cfg: NonNullable<typeof cachedChainConfig>,
+  tableId: number
+): { contract: string; onchainId: number } {
+  let match: TableNamespace | undefined;
+  for (const ns of cfg.tableNamespaces) {
+    if (ns.table_id_base <= tableId && (!match || ns.table_id_base > match.table_id_base)) {
+      match = ns;
+    }
+  }
+  if (!match) {
+    return { contract: cfg.pokerTableContract, onchainId: tableId };
+  }
+  return { contract: match.contract, onchainId: tableId - match.table_id_base };
+}
+
 function toActionScVal(action: BettingAction, amount?: number): xdr.ScVal {
   const normalized = action.trim().toLowerCase() as BettingAction;
   let variant: string;
@@ -78,19 +97,23 @@ function toActionScVal(action: BettingAction, amount?: number): xdr.ScVal {
 
 async function submitWalletTx(
   wallet: WalletSession,
+  tableId: number,
   method: string,
-  args: xdr.ScVal[]
+  args: (onchainTableId: xdr.ScVal) => xdr.ScVal[]
 ): Promise<string | undefined> {
   const cfg = await getConfig();
+  const table = resolveTable(cfg, tableId);
   const server = new rpc.Server(cfg.rpcUrl, { allowHttp: cfg.rpcUrl.startsWith("http://") });
   const account = await server.getAccount(wallet.address);
-  const contract = new Contract(cfg.pokerTableContract);
+  const contract = new Contract(table.contract);
 
   const tx = new TransactionBuilder(account, {
     fee: BASE_FEE,
     networkPassphrase: cfg.networkPassphrase,
   })
-    .addOperation(contract.call(method, ...args))
+    .addOperation(
+      contract.call(method, ...args(nativeToScVal(table.onchainId, { type: "u32" })))
+    )
     .setTimeout(60)
     .build();
 
@@ -134,8 +157,8 @@ export async function joinTableOnChain(
   tableId: number,
   buyIn: bigint
 ): Promise<string | undefined> {
-  return submitWalletTx(wallet, "join_table", [
-    nativeToScVal(tableId, { type: "u32" }),
+  return submitWalletTx(wallet, tableId, "join_table", (onchainTableId) => [
+    onchainTableId,
     new Address(wallet.address).toScVal(),
     nativeToScVal(buyIn, { type: "i128" }),
   ]);
@@ -147,8 +170,8 @@ export async function playerActionOnChain(
   action: BettingAction,
   amount?: number
 ): Promise<string | undefined> {
-  return submitWalletTx(wallet, "player_action", [
-    nativeToScVal(tableId, { type: "u32" }),
+  return submitWalletTx(wallet, tableId, "player_action", (onchainTableId) => [
+    onchainTableId,
     new Address(wallet.address).toScVal(),
     toActionScVal(action, amount),
   ]);
@@ -81,10 +81,8 @@ export function usePokerActions(config: PokerActionsConfig) {
     try {
       const tableState = await api.getParsedTableState(tableId);
       const minBuyInRaw =
-        tableState.parsed &&
-        typeof tableState.parsed === "object" &&
-        "config" in tableState.parsed
-          ? (tableState.parsed.config as { min_buy_in?: unknown })?.min_buy_in
+        tableState.parsed && typeof tableState.parsed === "object"
+          ? tableState.parsed.min_buy_in
           : undefined;
       const buyIn = toBigInt(minBuyInRaw, BigInt("1000000000"));
 
@@ -1,7 +1,7 @@
 #![no_std]
 #![allow(deprecated)]
 
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Symbol, Vec};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, Symbol, Vec};
 
 /// Committee Registry contract.
 ///
@@ -34,6 +34,25 @@ pub struct CommitteeEpoch {
     pub end_ledger: u32, // 0 = no end (current epoch)
 }
 
+/// What the committee failed to deliver before its deadline.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommitteeFailure {
+    MissedDeal,
+    MissedReveal,
+    MissedShowdown,
+}
+
+/// Why a member was reported: the failure and the hand it happened in, at
+/// the reporting table.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlashReason {
+    pub failure: CommitteeFailure,
+    pub table_id: u32,
+    pub hand_number: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub enum RegistryKey {
@@ -44,6 +63,8 @@ pub enum RegistryKey {
     CurrentEpoch,
     Epoch(u32),
     SlashEvent(u32), // slash event counter
+    IdentityKey(Address),
+    AttestationHash(Address),
 }
 
 #[contractimpl]
@@ -101,6 +122,52 @@ impl CommitteeRegistryContract {
             .publish((Symbol::new(&env, "member_registered"),), member);
     }
 
+    /// Register or rotate the Ed25519 identity key of a member's MPC node.
+    /// The coordinator and peers authenticate the node against this key.
+    pub fn set_identity_key(env: Env, member: Address, identity_key: BytesN<32>) {
+        member.require_auth();
+
+        let m: CommitteeMember = env
+            .storage()
+            .persistent()
+            .get(&RegistryKey::Member(member.clone()))
+            .expect("not a member");
+        assert!(m.active, "member not active");
+
+        env.storage()
+            .persistent()
+            .set(&RegistryKey::IdentityKey(member.clone()), &identity_key);
+
+        env.events().publish(
+            (Symbol::new(&env, "identity_key_set"),),
+            (member, identity_key),
+        );
+    }
+
+    /// Register the SHA-256 of the launch measurement a member's node runs
+    /// under in TEE mode. Players and the coordinator compare it with the
+    /// measurement in the node's attestation quote.
+    pub fn set_attestation_hash(env: Env, member: Address, measurement_hash: BytesN<32>) {
+        member.require_auth();
+
+        let m: CommitteeMember = env
+            .storage()
+            .persistent()
+            .get(&RegistryKey::Member(member.clone()))
+            .expect("not a member");
+        assert!(m.active, "member not active");
+
+        env.storage().persistent().set(
+            &RegistryKey::AttestationHash(member.clone()),
+            &measurement_hash,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "attestation_hash_set"),),
+            (member, measurement_hash),
+        );
+    }
+
     /// Withdraw stake and deregister (only when not in active epoch).
     pub fn deregister_member(env: Env, member: Address) -> i128 {
         member.require_auth();
@@ -212,7 +279,7 @@ impl CommitteeRegistryContract {
 
     /// Trigger a slashing event against a committee member.
     /// Called by PokerTable contract when committee fails to act within timeout.
-    pub fn report_slash(env: Env, reporter: Address, member: Address, reason: Symbol) {
+    pub fn report_slash(env: Env, reporter: Address, member: Address, reason: SlashReason) {
         reporter.require_auth();
 
         // In production, verify reporter is an authorized PokerTable contract
@@ -262,6 +329,20 @@ impl CommitteeRegistryContract {
             .get(&RegistryKey::Epoch(epoch_id))
     }
 
+    /// View a member node's registered identity key.
+    pub fn get_identity_key(env: Env, member: Address) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&RegistryKey::IdentityKey(member))
+    }
+
+    /// View a member node's registered attestation measurement hash.
+    pub fn get_attestation_hash(env: Env, member: Address) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&RegistryKey::AttestationHash(member))
+    }
+
     /// View a member's state.
     pub fn get_member(env: Env, member: Address) -> CommitteeMember {
         env.storage()
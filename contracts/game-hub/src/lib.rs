@@ -1,64 +1,172 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractevent, contractimpl, Address, Env};
+use game_hub_interface::GameHub;
+use soroban_sdk::{contract, contractevent, contractimpl, contracttype, Address, Env, Vec};
+
+pub use game_hub_interface::{HandResult, SettlementReport, TableStakes};
+
+const TTL_THRESHOLD: u32 = 17_280; // ~1 day
+const TTL_EXTEND: u32 = 518_400; // ~30 days
 
 /// Mock Game Hub contract matching the Stellar Game Studio interface.
 /// In production, the real Game Hub lives at CB4VZAT2U3UC6XFK3N23SKRF2NDCMP3QHJYMCHHFMZO7MRQO6DQ2EMYG
 #[contract]
 pub struct MockGameHub;
 
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Accumulated points of a player.
+    Points(Address),
+    /// Every player who has been given a result, in first-seen order.
+    Players,
+}
+
 #[contractevent]
 pub struct GameStarted {
     pub session_id: u32,
     pub game_id: Address,
-    pub player1: Address,
-    pub player2: Address,
-    pub player1_points: i128,
-    pub player2_points: i128,
+    pub players: Vec<Address>,
+    pub stakes: Vec<i128>,
+    pub table_stakes: TableStakes,
+}
+
+#[contractevent]
+pub struct HandResultRecorded {
+    pub session_id: u32,
+    pub results: Vec<HandResult>,
 }
 
 #[contractevent]
 pub struct GameEnded {
     pub session_id: u32,
-    pub player1_won: bool,
+    pub results: Vec<(Address, i128)>,
+}
+
+#[contractevent]
+pub struct SettlementReported {
+    pub session_id: u32,
+    pub report: SettlementReport,
 }
 
 #[contractimpl]
-impl MockGameHub {
-    pub fn start_game(
+impl GameHub for MockGameHub {
+    /// Start a session for any number of players; `stakes[i]` belongs to
+    /// `players[i]`.
+    fn start_game(
         env: Env,
         game_id: Address,
         session_id: u32,
-        player1: Address,
-        player2: Address,
-        player1_points: i128,
-        player2_points: i128,
+        players: Vec<Address>,
+        stakes: Vec<i128>,
+        table_stakes: TableStakes,
     ) {
         GameStarted {
             session_id,
             game_id,
-            player1,
-            player2,
-            player1_points,
-            player2_points,
+            players,
+            stakes,
+            table_stakes,
         }
         .publish(&env);
-        env.storage().instance().extend_ttl(17_280, 518_400);
+        env.storage()
+            .instance()
+            .extend_ttl(TTL_THRESHOLD, TTL_EXTEND);
     }
 
-    pub fn end_game(env: Env, session_id: u32, player1_won: bool) {
+    /// Add each player's profit or loss over a hand to their points.
+    fn hand_result(env: Env, session_id: u32, results: Vec<HandResult>) {
+        let storage = env.storage().persistent();
+        let mut players: Vec<Address> = storage
+            .get(&DataKey::Players)
+            .unwrap_or_else(|| Vec::new(&env));
+        for result in results.iter() {
+            let key = DataKey::Points(result.player.clone());
+            let points: Option<i128> = storage.get(&key);
+            if points.is_none() {
+                players.push_back(result.player.clone());
+            }
+            storage.set(&key, &(points.unwrap_or(0) + result.net));
+            storage.extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND);
+        }
+        storage.set(&DataKey::Players, &players);
+        storage.extend_ttl(&DataKey::Players, TTL_THRESHOLD, TTL_EXTEND);
+
+        HandResultRecorded {
+            session_id,
+            results,
+        }
+        .publish(&env);
+    }
+
+    /// End a session. Points come from `hand_result`, so the results here
+    /// are only published.
+    fn end_game(env: Env, session_id: u32, results: Vec<(Address, i128)>) {
         GameEnded {
             session_id,
-            player1_won,
+            results,
+        }
+        .publish(&env);
+    }
+
+    /// Record the payout breakdown of a session for indexers.
+    fn report_settlement(env: Env, report: SettlementReport) {
+        SettlementReported {
+            session_id: report.session_id,
+            report,
         }
         .publish(&env);
     }
 }
 
+#[contractimpl]
+impl MockGameHub {
+    /// Points accumulated by `player` over every ended session.
+    pub fn get_points(env: Env, player: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Points(player))
+            .unwrap_or(0)
+    }
+
+    /// Up to `limit` players with the most points, highest first. Ties keep
+    /// the order players were first seen in.
+    pub fn get_leaderboard(env: Env, limit: u32) -> Vec<(Address, i128)> {
+        let storage = env.storage().persistent();
+        let players: Vec<Address> = storage
+            .get(&DataKey::Players)
+            .unwrap_or_else(|| Vec::new(&env));
+        let mut board: Vec<(Address, i128)> = Vec::new(&env);
+        for player in players.iter() {
+            let points: i128 = storage.get(&DataKey::Points(player.clone())).unwrap_or(0);
+            // Insertion sort: the mock only sees a handful of players.
+            let mut at = board.len();
+            while at > 0 && board.get_unchecked(at - 1).1 < points {
+                at -= 1;
+            }
+            if at < limit {
+                board.insert(at, (player, points));
+                if board.len() > limit {
+                    board.pop_back();
+                }
+            }
+        }
+        board
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{testutils::Address as _, vec};
+
+    fn result(player: &Address, stake: i128, net: i128) -> HandResult {
+        HandResult {
+            player: player.clone(),
+            stake,
+            net,
+        }
+    }
 
     #[test]
     fn test_start_and_end_game() {
@@ -68,7 +176,86 @@ mod test {
         let game_id = Address::generate(&env);
         let player1 = Address::generate(&env);
         let player2 = Address::generate(&env);
-        client.start_game(&game_id, &1, &player1, &player2, &1000, &1000);
-        client.end_game(&1, &true);
+        let player3 = Address::generate(&env);
+        client.start_game(
+            &game_id,
+            &1,
+            &vec![&env, player1.clone(), player2.clone(), player3.clone()],
+            &vec![&env, 1000, 1000, 500],
+            &TableStakes {
+                small_blind: 5,
+                big_blind: 10,
+                min_buy_in: 100,
+                max_buy_in: 1000,
+            },
+        );
+        client.hand_result(
+            &1,
+            &vec![
+                &env,
+                result(&player1, 1000, 150),
+                result(&player2, 1000, -100),
+                result(&player3, 500, -50),
+            ],
+        );
+        client.end_game(
+            &1,
+            &vec![
+                &env,
+                (player1.clone(), 150),
+                (player2.clone(), -100),
+                (player3.clone(), -50),
+            ],
+        );
+        client.report_settlement(&SettlementReport {
+            session_id: 1,
+            hand_number: 1,
+            pot: 300,
+            rake: 0,
+            deltas: vec![&env, (player1, 150), (player2, -100), (player3, -50)],
+        });
+    }
+
+    #[test]
+    fn test_points_accumulate_into_leaderboard() {
+        let env = Env::default();
+        let contract_id = env.register(MockGameHub, ());
+        let client = MockGameHubClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let player3 = Address::generate(&env);
+
+        client.hand_result(
+            &1,
+            &vec![&env, result(&player1, 100, 30), result(&player2, 100, -30)],
+        );
+        client.hand_result(
+            &2,
+            &vec![
+                &env,
+                result(&player1, 130, -50),
+                result(&player2, 70, 10),
+                result(&player3, 200, 40),
+            ],
+        );
+        // Ending a session does not count its results a second time.
+        client.end_game(&2, &vec![&env, (player3.clone(), 40)]);
+
+        assert_eq!(client.get_points(&player1), -20);
+        assert_eq!(client.get_points(&player2), -20);
+        assert_eq!(client.get_points(&player3), 40);
+        assert_eq!(client.get_points(&Address::generate(&env)), 0);
+
+        assert_eq!(
+            client.get_leaderboard(&10),
+            vec![
+                &env,
+                (player3.clone(), 40),
+                (player1.clone(), -20),
+                (player2, -20)
+            ]
+        );
+        assert_eq!(client.get_leaderboard(&1), vec![&env, (player3, 40)]);
+        assert!(client.get_leaderboard(&0).is_empty());
     }
 }
@@ -1,6 +1,8 @@
-use soroban_sdk::{Address, Env, Symbol};
+use soroban_sdk::{Address, Env};
 
+use crate::events::{ActionTaken, PhaseChange};
 use crate::game;
+use crate::timeout;
 use crate::types::*;
 
 /// Process a player's betting action.
@@ -16,26 +18,32 @@ pub fn process_action(
         return Err(PokerTableError::NotYourTurn);
     }
 
-    let mut p = table
-        .players
-        .get(seat)
-        .ok_or(PokerTableError::InvalidPlayerIndex)?;
+    let mut p = game::player_at(table, seat)?;
     if p.folded {
         return Err(PokerTableError::PlayerAlreadyFolded);
     }
     if p.all_in {
         return Err(PokerTableError::PlayerAlreadyAllIn);
     }
+    timeout::draw_time_bank(env, table, &mut p);
+    p.missed_turns = 0;
+    p.last_active_hand = table.hand_number;
 
+    let pot_before = table.pot;
     let current_bet = max_bet_this_round(table)?;
+    // A raise has to be at least as big as the last full bet or raise, and a
+    // player who has acted since then may only raise again once someone else
+    // makes a full raise. An all-in short of that does not reopen the betting.
+    let mut full_raise = false;
 
     match action {
         Action::Fold => {
             p.folded = true;
-            table.players.set(seat, p);
+            table.players.set(seat, Some(p));
 
             // Check if only one player remains
             if game::active_player_count(table) == 1 {
+                action_taken(table, player, seat, action, 0, None).publish(env);
                 game::settle_fold_win(env, table)?;
                 return Ok(());
             }
@@ -44,6 +52,7 @@ pub fn process_action(
             if p.bet_this_round != current_bet {
                 return Err(PokerTableError::MustCallOrFold);
             }
+            table.players.set(seat, Some(p));
         }
         Action::Call => {
             let to_call = current_bet - p.bet_this_round;
@@ -59,7 +68,7 @@ pub fn process_action(
             if p.stack == 0 {
                 p.all_in = true;
             }
-            table.players.set(seat, p);
+            table.players.set(seat, Some(p));
         }
         Action::Bet(amount) => {
             if current_bet != 0 {
@@ -68,6 +77,7 @@ pub fn process_action(
             if *amount < table.config.big_blind {
                 return Err(PokerTableError::BetTooSmall);
             }
+            check_whole_chips(table, *amount)?;
             if *amount > p.stack {
                 return Err(PokerTableError::NotEnoughChips);
             }
@@ -79,14 +89,23 @@ pub fn process_action(
             if p.stack == 0 {
                 p.all_in = true;
             }
-            table.players.set(seat, p);
+            table.players.set(seat, Some(p));
+            table.min_raise = *amount;
+            full_raise = true;
         }
         Action::Raise(amount) => {
+            if p.acted {
+                return Err(PokerTableError::BettingNotReopened);
+            }
             let to_call = current_bet - p.bet_this_round;
             let total_needed = to_call + *amount;
-            if *amount < table.config.big_blind {
+            if *amount < table.min_raise {
                 return Err(PokerTableError::RaiseTooSmall);
             }
+            // Bets and raises come in whole chips, so pots do not fill with
+            // dust the UI cannot show. A raise over an odd all-in is judged
+            // by the total it makes, not by the increment.
+            check_whole_chips(table, current_bet + *amount)?;
             if total_needed > p.stack {
                 return Err(PokerTableError::NotEnoughChips);
             }
@@ -98,108 +117,205 @@ pub fn process_action(
             if p.stack == 0 {
                 p.all_in = true;
             }
-            table.players.set(seat, p);
+            table.players.set(seat, Some(p));
+            table.min_raise = *amount;
+            full_raise = true;
         }
         Action::AllIn => {
             let amount = p.stack;
+            let raise = p.bet_this_round + amount - current_bet;
+            if raise > 0 && p.acted {
+                return Err(PokerTableError::BettingNotReopened);
+            }
             p.bet_this_round += amount;
             table.pot += amount;
             p.stack = 0;
             p.all_in = true;
-            table.players.set(seat, p);
+            table.players.set(seat, Some(p));
+            if raise >= table.min_raise {
+                table.min_raise = raise;
+                full_raise = true;
+            }
         }
     }
 
+    mark_acted(table, seat, full_raise)?;
+
     table.last_action_ledger = env.ledger().sequence();
 
-    // Advance turn
-    advance_turn(env, table)
+    // The turn passes on unless every bet is matched. The event goes out
+    // before the round ends so replays see it ahead of the phase change.
+    let next_turn = if is_round_complete(table)? {
+        None
+    } else {
+        Some(next_to_act(table, seat)?)
+    };
+    let amount = table.pot - pot_before;
+    action_taken(table, player, seat, action, amount, next_turn).publish(env);
+
+    match next_turn {
+        Some(next) => table.current_turn = next,
+        None => advance_to_next_phase(env, table)?,
+    }
+    Ok(())
 }
 
-/// Reset betting state for a new round.
-pub fn reset_round(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
+/// Reject a bet that is not a whole number of the table's `chip_unit`.
+fn check_whole_chips(table: &TableState, amount: i128) -> Result<(), PokerTableError> {
+    if amount % table.config.chip_unit != 0 {
+        return Err(PokerTableError::NotWholeChips);
+    }
+    Ok(())
+}
+
+/// The `ActionTaken` event for `seat` putting `amount` into the pot, with
+/// the pot as it stands afterwards.
+fn action_taken(
+    table: &TableState,
+    player: &Address,
+    seat: u32,
+    action: &Action,
+    amount: i128,
+    next_turn: Option<u32>,
+) -> ActionTaken {
+    ActionTaken {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        phase: table.phase.clone(),
+        player: player.clone(),
+        seat,
+        action: action.clone(),
+        amount,
+        pot: table.pot,
+        next_turn,
+    }
+}
+
+/// Clear every player's bet and action for a new round.
+fn clear_bets(table: &mut TableState) {
     for i in 0..table.players.len() {
-        let mut p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+        let Some(mut p) = table.players.get(i).flatten() else {
+            continue;
+        };
         p.bet_this_round = 0;
-        table.players.set(i, p);
+        p.acted = false;
+        table.players.set(i, Some(p));
     }
+    table.min_raise = table.config.big_blind;
+}
+
+/// Reset betting state for a new round.
+pub fn reset_round(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
+    clear_bets(table);
 
     // First active player after dealer acts first post-flop
-    let num_players = table.players.len() as u32;
-    if num_players == 0 {
+    let num_seats = table.players.len();
+    if num_seats == 0 {
         return Err(PokerTableError::NeedAtLeastTwoPlayers);
     }
-    let mut seat = (table.dealer_seat + 1) % num_players;
-    for _ in 0..num_players {
-        let p = table
-            .players
-            .get(seat)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        if !p.folded && !p.all_in {
+    open_round(env, table, (table.dealer_seat + 1) % num_seats)
+}
+
+/// Give the turn to the first player from `seat` onward who can still act.
+pub fn open_round(env: &Env, table: &mut TableState, seat: u32) -> Result<(), PokerTableError> {
+    // No one is left to bet against — run the board out.
+    if betting_closed(table)? {
+        return advance_to_next_phase(env, table);
+    }
+
+    let num_seats = table.players.len();
+    let mut seat = seat;
+    for _ in 0..num_seats {
+        if can_act(table, seat) {
             table.current_turn = seat;
             return Ok(());
         }
-        seat = (seat + 1) % num_players;
+        seat = (seat + 1) % num_seats;
     }
 
     // All players are all-in or folded — skip to next deal phase
     advance_to_next_phase(env, table)
 }
 
-/// Advance to the next player's turn, or end the betting round.
-fn advance_turn(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
-    let num_players = table.players.len() as u32;
-    if num_players == 0 {
+/// The first seat after `seat` with a player who can still act, or the
+/// seat after it if there is none.
+pub fn next_to_act(table: &TableState, seat: u32) -> Result<u32, PokerTableError> {
+    let num_seats = table.players.len();
+    if num_seats == 0 {
         return Err(PokerTableError::NeedAtLeastTwoPlayers);
     }
-    let mut next = (table.current_turn + 1) % num_players;
-
-    // Find next active player
-    for _ in 0..num_players {
-        let p = table
-            .players
-            .get(next)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        if !p.folded && !p.all_in {
+    let mut next = (seat + 1) % num_seats;
+    for _ in 0..num_seats {
+        if can_act(table, next) {
             break;
         }
-        next = (next + 1) % num_players;
+        next = (next + 1) % num_seats;
     }
+    Ok(next)
+}
 
-    // Check if betting round is complete
-    if is_round_complete(table)? {
-        advance_to_next_phase(env, table)?;
-    } else {
-        table.current_turn = next;
+/// Whether betting is over for the hand: at most one player can still act,
+/// and they have nothing to call.
+fn betting_closed(table: &TableState) -> Result<bool, PokerTableError> {
+    let current_bet = max_bet_this_round(table)?;
+    let mut can_act = table
+        .players
+        .iter()
+        .flatten()
+        .filter(|p| !p.folded && !p.all_in);
+    Ok(match (can_act.next(), can_act.next()) {
+        (None, _) => true,
+        (Some(p), None) => p.bet_this_round >= current_bet,
+        _ => false,
+    })
+}
+
+/// After a street of a run-out is revealed, wait for the next street, or
+/// go to showdown once the board is complete.
+pub fn run_out_revealed(env: &Env, table: &mut TableState) {
+    clear_bets(table);
+    table.last_action_ledger = env.ledger().sequence();
+    if table.board_cards.len() < 5 {
+        return;
     }
-    Ok(())
+    table.phase = GamePhase::Showdown;
+    PhaseChange {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        phase: table.phase.clone(),
+    }
+    .publish(env);
 }
 
-/// Check if all active players have matched the current bet.
-fn is_round_complete(table: &TableState) -> Result<bool, PokerTableError> {
+/// Whether `seat` holds a player who still has decisions to make this hand.
+fn can_act(table: &TableState, seat: u32) -> bool {
+    match table.players.get(seat).flatten() {
+        Some(p) => !p.folded && !p.all_in,
+        None => false,
+    }
+}
+
+/// Whether the betting round is over: nobody is left to bet against, or
+/// everyone who can still act has acted and matched the current bet. The
+/// blinds are not actions, so the big blind keeps its option on a limp.
+pub fn is_round_complete(table: &TableState) -> Result<bool, PokerTableError> {
+    if betting_closed(table)? {
+        return Ok(true);
+    }
     let current_bet = max_bet_this_round(table)?;
-    for i in 0..table.players.len() {
-        let p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+    for p in table.players.iter().flatten() {
         if p.folded || p.all_in {
             continue;
         }
-        if p.bet_this_round != current_bet {
+        if !p.acted || p.bet_this_round != current_bet {
             return Ok(false);
         }
     }
-
-    // All active non-all-in players have matched the current bet
     Ok(true)
 }
 
 /// Advance to the next game phase.
-fn advance_to_next_phase(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
+pub fn advance_to_next_phase(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
     // If only one player left, settle immediately
     if game::active_player_count(table) == 1 {
         game::settle_fold_win(env, table)?;
@@ -207,6 +323,10 @@ fn advance_to_next_phase(env: &Env, table: &mut TableState) -> Result<(), PokerT
     }
 
     table.phase = match table.phase {
+        // Nobody can bet on later streets: reveal them back to back.
+        GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn if betting_closed(table)? => {
+            GamePhase::RunOut
+        }
         GamePhase::Preflop => GamePhase::DealingFlop,
         GamePhase::Flop => GamePhase::DealingTurn,
         GamePhase::Turn => GamePhase::DealingRiver,
@@ -215,19 +335,36 @@ fn advance_to_next_phase(env: &Env, table: &mut TableState) -> Result<(), PokerT
     };
     table.last_action_ledger = env.ledger().sequence();
 
-    env.events().publish(
-        (Symbol::new(env, "phase_change"), table.id),
-        table.phase.clone(),
-    );
+    PhaseChange {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        phase: table.phase.clone(),
+    }
+    .publish(env);
     Ok(())
 }
 
-fn find_player_seat(table: &TableState, player: &Address) -> Result<u32, PokerTableError> {
+/// Record that `seat` has acted. A full bet or raise reopens the betting for
+/// everyone else.
+fn mark_acted(table: &mut TableState, seat: u32, full_raise: bool) -> Result<(), PokerTableError> {
     for i in 0..table.players.len() {
-        let p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+        let Some(mut p) = table.players.get(i).flatten() else {
+            continue;
+        };
+        if i == seat {
+            p.acted = true;
+        } else if full_raise {
+            p.acted = false;
+        } else {
+            continue;
+        }
+        table.players.set(i, Some(p));
+    }
+    Ok(())
+}
+
+fn find_player_seat(table: &TableState, player: &Address) -> Result<u32, PokerTableError> {
+    for p in table.players.iter().flatten() {
         if p.address == *player {
             return Ok(p.seat_index);
         }
@@ -237,11 +374,7 @@ fn find_player_seat(table: &TableState, player: &Address) -> Result<u32, PokerTa
 
 fn max_bet_this_round(table: &TableState) -> Result<i128, PokerTableError> {
     let mut max_bet: i128 = 0;
-    for i in 0..table.players.len() {
-        let p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+    for p in table.players.iter().flatten() {
         if p.bet_this_round > max_bet {
             max_bet = p.bet_this_round;
         }
@@ -0,0 +1,336 @@
+//! Property tests for the betting engine.
+//!
+//! Each case seats 2-6 players with random (often short) stacks and plays one
+//! hand from a random sequence of legal actions, checking after every step
+//! that chips are conserved, no stack goes negative, the pot equals what the
+//! players put in, the seat to act can act, and a betting round ends exactly
+//! when every player still able to bet has matched the high bet.
+
+use crate::types::*;
+use crate::{pot, public_inputs, PokerTableContract, PokerTableContractClient};
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, Address, Bytes, BytesN, Env, Vec};
+
+const SMALL_BLIND: i128 = 5;
+const BIG_BLIND: i128 = 10;
+
+/// One generated hand: the buy-ins, and a `(choice, amount)` seed per
+/// action that is mapped onto the actions legal at that point.
+#[derive(Debug)]
+struct Case {
+    stacks: std::vec::Vec<i128>,
+    actions: std::vec::Vec<(u8, u16)>,
+}
+
+fn cases() -> impl Strategy<Value = Case> {
+    (
+        prop::collection::vec(1i128..=400, 2..=6),
+        prop::collection::vec(any::<(u8, u16)>(), 1..60),
+    )
+        .prop_map(|(stacks, actions)| Case { stacks, actions })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn random_hands_keep_betting_invariants(case in cases()) {
+        play(&case);
+    }
+}
+
+struct Table {
+    env: Env,
+    client: PokerTableContractClient<'static>,
+    committee: Address,
+    id: u32,
+    players: std::vec::Vec<Address>,
+    total: i128,
+}
+
+impl Table {
+    fn new(stacks: &[i128]) -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = PokerTableContractClient::new(&env, &env.register(PokerTableContract, ()));
+        let admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(admin.clone());
+        let minter = soroban_sdk::token::StellarAssetClient::new(&env, &token.address());
+        let committee = Address::generate(&env);
+        let id = client.create_table(
+            &admin,
+            &TableConfig {
+                token: token.address(),
+                min_buy_in: 1,
+                max_buy_in: 10_000,
+                small_blind: SMALL_BLIND,
+                big_blind: BIG_BLIND,
+                max_players: 6,
+                deal_timeout_ledgers: 100,
+                reveal_timeout_ledgers: 100,
+                showdown_timeout_ledgers: 100,
+                betting_timeout_ledgers: 100,
+                time_bank_ledgers: 0,
+                committee: Vec::from_array(&env, [committee.clone()]),
+                committee_threshold: 1,
+                verifier: env.register(crate::verifier::ZkVerifierContract, ()),
+                game_hub: env.register(crate::game_hub::GameHubContract, ()),
+                committee_registry: env.register(crate::registry::CommitteeRegistryContract, ()),
+                rake_bps: 0,
+                rake_cap: 0,
+                chip_unit: 1,
+                jackpot_bps: 0,
+            },
+        );
+        let mut players = std::vec::Vec::new();
+        for &stack in stacks {
+            let player = Address::generate(&env);
+            minter.mint(&player, &stack);
+            client.join_table(&id, &player, &stack);
+            players.push(player);
+        }
+        Table {
+            env,
+            client,
+            committee,
+            id,
+            players,
+            total: stacks.iter().sum(),
+        }
+    }
+
+    fn state(&self) -> TableState {
+        self.client.get_table(&self.id)
+    }
+
+    fn commit_deal(&self) {
+        let n = self.players.len() as u32;
+        let mut commitments = Vec::new(&self.env);
+        let mut dealt = Vec::new(&self.env);
+        for i in 0..n {
+            commitments.push_back(BytesN::from_array(&self.env, &[2; 32]));
+            dealt.push_back(2 * i);
+            dealt.push_back(2 * i + 1);
+        }
+        let deck_root = BytesN::from_array(&self.env, &[1; 32]);
+        let nonce = self.state().session_id;
+        self.client.commit_deal(
+            &self.id,
+            &self.committee,
+            &deck_root,
+            &commitments,
+            &dealt,
+            &Bytes::new(&self.env),
+            &public_inputs::encode_deal(&self.env, nonce, &deck_root, &commitments, &dealt),
+        );
+    }
+
+    /// Reveal `count` board cards from the deck positions after the hole
+    /// cards, using the position as the card.
+    fn reveal(&self, count: u32) {
+        let next = 2 * self.players.len() as u32 + self.state().board_cards.len();
+        let cards: Vec<u32> = (next..next + count).fold(Vec::new(&self.env), |mut v, i| {
+            v.push_back(i);
+            v
+        });
+        let state = self.state();
+        self.client.reveal_board(
+            &self.id,
+            &self.committee,
+            &cards,
+            &cards,
+            &Bytes::new(&self.env),
+            &public_inputs::encode_reveal(
+                &self.env,
+                state.session_id,
+                &state.deck_root,
+                &cards,
+                &cards,
+            ),
+        );
+    }
+
+    fn showdown(&self, state: &TableState) {
+        let mut hole_cards = Vec::new(&self.env);
+        for p in state.players.iter().flatten() {
+            hole_cards.push_back((2 * p.seat_index, 2 * p.seat_index + 1));
+        }
+        self.client.submit_showdown(
+            &self.id,
+            &self.committee,
+            &public_inputs::shown_hole_cards(&self.env, state, &hole_cards),
+            &Bytes::new(&self.env),
+            &public_inputs::encode_showdown(&self.env, state, &hole_cards),
+        );
+    }
+}
+
+fn max_bet(state: &TableState) -> i128 {
+    state
+        .players
+        .iter()
+        .flatten()
+        .map(|p| p.bet_this_round)
+        .max()
+        .unwrap_or(0)
+}
+
+fn is_betting(phase: &GamePhase) -> bool {
+    matches!(
+        phase,
+        GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River
+    )
+}
+
+/// Every player who can still bet has matched the high bet.
+fn all_matched(state: &TableState) -> bool {
+    let high = max_bet(state);
+    state
+        .players
+        .iter()
+        .flatten()
+        .filter(|p| !p.folded && !p.all_in)
+        .all(|p| p.bet_this_round == high)
+}
+
+/// The betting round is over: every bet is matched, and everyone who can
+/// still bet has acted unless they are the last one who can.
+fn round_over(state: &TableState) -> bool {
+    let can_act = || {
+        state
+            .players
+            .iter()
+            .flatten()
+            .filter(|p| !p.folded && !p.all_in)
+    };
+    all_matched(state) && (can_act().count() <= 1 || can_act().all(|p| p.acted))
+}
+
+/// Map an action seed onto one of the actions the seat to act may take.
+fn legal_action(state: &TableState, (choice, amount): (u8, u16)) -> Action {
+    let p = state.players.get(state.current_turn).unwrap().unwrap();
+    let to_call = max_bet(state) - p.bet_this_round;
+    let sized = |lo: i128, hi: i128| lo + i128::from(amount) % (hi - lo + 1);
+
+    let mut actions = std::vec![Action::Fold];
+    // All-in for more than the call is a raise, which a seat that has acted
+    // may only make after a full raise.
+    if !p.acted || p.stack <= to_call {
+        actions.push(Action::AllIn);
+    }
+    if to_call == 0 {
+        actions.push(Action::Check);
+    } else {
+        actions.push(Action::Call);
+    }
+    if max_bet(state) == 0 && p.stack >= BIG_BLIND {
+        actions.push(Action::Bet(sized(BIG_BLIND, p.stack)));
+    }
+    if !p.acted && to_call + state.min_raise <= p.stack {
+        actions.push(Action::Raise(sized(state.min_raise, p.stack - to_call)));
+    }
+    actions.swap_remove(choice as usize % actions.len())
+}
+
+fn check_invariants(env: &Env, table: &Table, state: &TableState) {
+    let stacks: i128 = state.players.iter().flatten().map(|p| p.stack).sum();
+    assert_eq!(stacks + state.pot, table.total, "chips not conserved");
+    for p in state.players.iter().flatten() {
+        assert!(p.stack >= 0, "seat {} has a negative stack", p.seat_index);
+        if !p.folded && state.phase != GamePhase::Settlement {
+            assert_eq!(
+                p.all_in,
+                p.stack == 0,
+                "seat {} all-in flag disagrees with its stack",
+                p.seat_index
+            );
+        }
+    }
+
+    if state.phase != GamePhase::Settlement {
+        let contributed: i128 = state
+            .players
+            .iter()
+            .flatten()
+            .map(|p| p.hand_start_stack - p.stack)
+            .sum();
+        assert_eq!(state.pot, contributed, "pot differs from contributions");
+
+        let pots = pot::calculate_side_pots(env, state).expect("side pots");
+        let mut in_pots = 0;
+        for side_pot in pots.iter() {
+            assert!(side_pot.amount > 0, "empty side pot");
+            in_pots += side_pot.amount;
+            for seat in side_pot.eligible_players.iter() {
+                assert!(
+                    !state.players.get(seat).unwrap().unwrap().folded,
+                    "folded seat {} eligible",
+                    seat
+                );
+            }
+        }
+        assert_eq!(in_pots, state.pot, "side pots do not add up to the pot");
+    }
+
+    if is_betting(&state.phase) {
+        let p = state.players.get(state.current_turn).unwrap().unwrap();
+        assert!(
+            !p.folded && !p.all_in,
+            "seat {} to act cannot act",
+            state.current_turn
+        );
+    }
+}
+
+fn play(case: &Case) {
+    let table = Table::new(&case.stacks);
+    table.client.start_hand(&table.id);
+    table.commit_deal();
+
+    let mut actions = case.actions.iter();
+    loop {
+        let state = table.state();
+        check_invariants(&table.env, &table, &state);
+        match state.phase {
+            GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
+                let Some(&seed) = actions.next() else {
+                    return;
+                };
+                let action = legal_action(&state, seed);
+                let seat = state.current_turn;
+                let result = table.client.try_player_action(
+                    &table.id,
+                    &table.players[seat as usize],
+                    &action,
+                );
+                assert!(
+                    result.is_ok(),
+                    "seat {} {:?} rejected: {:?}",
+                    seat,
+                    action,
+                    result
+                );
+
+                let after = table.state();
+                if after.phase == state.phase {
+                    assert!(
+                        !round_over(&after),
+                        "round did not end once everyone had acted and matched"
+                    );
+                } else if after.phase != GamePhase::Settlement {
+                    assert!(all_matched(&after), "round ended with bets unmatched");
+                }
+            }
+            GamePhase::DealingFlop => table.reveal(3),
+            GamePhase::DealingTurn | GamePhase::DealingRiver => table.reveal(1),
+            GamePhase::RunOut if state.board_cards.is_empty() => table.reveal(3),
+            GamePhase::RunOut => table.reveal(1),
+            GamePhase::Showdown => table.showdown(&state),
+            GamePhase::Settlement => {
+                assert_eq!(state.pot, 0, "pot left after settlement");
+                return;
+            }
+            other => panic!("hand stuck in {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Events the table publishes.
+//!
+//! Every event's topics are its name in snake case followed by the table ID,
+//! so an indexer can follow one table with a `["*", table_id]` filter. The
+//! other fields make up the data, a map keyed by field name.
+
+use soroban_sdk::{contractevent, Address, BytesN, Vec};
+
+use crate::types::{Action, GamePhase};
+
+/// A table was created.
+#[contractevent]
+pub struct TableCreated {
+    #[topic]
+    pub table_id: u32,
+    pub admin: Address,
+    pub token: Address,
+    pub small_blind: i128,
+    pub big_blind: i128,
+    pub min_buy_in: i128,
+    pub max_buy_in: i128,
+    pub max_players: u32,
+}
+
+/// The admin stopped play at the table.
+#[contractevent]
+pub struct TablePaused {
+    #[topic]
+    pub table_id: u32,
+}
+
+/// The admin let play at the table go on.
+#[contractevent]
+pub struct TableResumed {
+    #[topic]
+    pub table_id: u32,
+}
+
+/// The admin closed the table for good, handing `refunded` chips and
+/// unclaimed jackpot awards back to the players, and `rake` and the
+/// `jackpot` nobody had won to themselves.
+#[contractevent]
+pub struct TableClosed {
+    #[topic]
+    pub table_id: u32,
+    pub refunded: i128,
+    pub rake: i128,
+    pub jackpot: i128,
+}
+
+/// The table's committee was replaced between hands: from now on any of
+/// `committee` may submit proofs made by `threshold` of them.
+#[contractevent]
+pub struct CommitteeRotated {
+    #[topic]
+    pub table_id: u32,
+    pub committee: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// The admin scheduled `verifier` to check the table's proofs from the
+/// first hand started at or after `effective_ledger`.
+#[contractevent]
+pub struct VerifierScheduled {
+    #[topic]
+    pub table_id: u32,
+    pub verifier: Address,
+    pub effective_ledger: u32,
+}
+
+/// A scheduled verifier took over as a hand started.
+#[contractevent]
+pub struct VerifierRotated {
+    #[topic]
+    pub table_id: u32,
+    pub verifier: Address,
+}
+
+/// A player took `seat` with `buy_in` chips.
+#[contractevent]
+pub struct PlayerJoined {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+    pub seat: u32,
+    pub buy_in: i128,
+}
+
+/// A player left `seat`, taking `withdrawn` chips with them.
+#[contractevent]
+pub struct PlayerLeft {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+    pub seat: u32,
+    pub withdrawn: i128,
+}
+
+/// A player who had been idle too long was removed from `seat`, and their
+/// `refunded` chips sent back to them.
+#[contractevent]
+pub struct PlayerKicked {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+    pub seat: u32,
+    pub refunded: i128,
+}
+
+/// A player will not be dealt in until they sit in again.
+#[contractevent]
+pub struct PlayerSatOut {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+    pub seat: u32,
+}
+
+/// A player will be dealt in again from the next hand on.
+#[contractevent]
+pub struct PlayerSatIn {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+    pub seat: u32,
+}
+
+/// A player let `key` sign their betting actions until `expires_ledger`.
+#[contractevent]
+pub struct SessionKeyRegistered {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+    pub key: Address,
+    pub expires_ledger: u32,
+}
+
+/// A player's session key can no longer act for them.
+#[contractevent]
+pub struct SessionKeyRevoked {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+}
+
+/// A hand began. `seats` holds `(seat, player, stack)` for everyone seated,
+/// with stacks as they were before the blinds.
+#[contractevent]
+pub struct HandStarted {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub dealer_seat: u32,
+    pub small_blind_seat: u32,
+    pub big_blind_seat: u32,
+    pub small_blind: i128,
+    pub big_blind: i128,
+    pub seats: Vec<(u32, Address, i128)>,
+}
+
+/// The player in `seat` voted to cancel the hand; see `vote_cancel_hand`.
+#[contractevent]
+pub struct CancelVoted {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub player: Address,
+    pub seat: u32,
+}
+
+/// The player in `seat` straddled for `amount`, twice the big blind.
+#[contractevent]
+pub struct StraddlePosted {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub player: Address,
+    pub seat: u32,
+    pub amount: i128,
+}
+
+/// The committee dealt the hole cards. `proof_digest` is the SHA-256 of the
+/// deal proof.
+#[contractevent]
+pub struct DealCommitted {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub deck_root: BytesN<32>,
+    pub hand_commitments: Vec<BytesN<32>>,
+    pub proof_digest: BytesN<32>,
+}
+
+/// A player acted during a betting round, putting `amount` chips in and
+/// leaving `pot` in the middle. `next_turn` is the seat to act next, or
+/// `None` when the action closed the round or ended the hand.
+#[contractevent]
+pub struct ActionTaken {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub phase: GamePhase,
+    pub player: Address,
+    pub seat: u32,
+    pub action: Action,
+    pub amount: i128,
+    pub pot: i128,
+    pub next_turn: Option<u32>,
+}
+
+/// Betting closed and the hand moved on to `phase`.
+#[contractevent]
+pub struct PhaseChange {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub phase: GamePhase,
+}
+
+/// The committee revealed board `cards` from deck positions `indices`,
+/// opening the betting round `phase`.
+#[contractevent]
+pub struct BoardRevealed {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub phase: GamePhase,
+    pub cards: Vec<u32>,
+    pub indices: Vec<u32>,
+    pub proof_digest: BytesN<32>,
+}
+
+/// The committee revealed the hole cards of the players dealt in, in seat
+/// order: `None` for those who folded or mucked.
+#[contractevent]
+pub struct ShowdownRevealed {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub hole_cards: Vec<Option<(u32, u32)>>,
+    pub proof_digest: BytesN<32>,
+}
+
+/// `amount` of pot `pot_index` (0 is the main pot) went to the player in
+/// `seat`.
+#[contractevent]
+pub struct PotAwarded {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub pot_index: u32,
+    pub player: Address,
+    pub seat: u32,
+    pub amount: i128,
+}
+
+/// A showdown settled a `pot` of which `rake` went to the table. `winner` is
+/// the main pot's winner; on a split, the one first left of the button.
+#[contractevent]
+pub struct HandSettled {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub winner: Address,
+    pub pot: i128,
+    pub rake: i128,
+}
+
+/// Everyone else folded, and the player in `seat` took the `pot` less `rake`.
+#[contractevent]
+pub struct FoldWin {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub winner: Address,
+    pub seat: u32,
+    pub pot: i128,
+    pub rake: i128,
+}
+
+/// The hand was called off and `refunded` chips went back to the players.
+#[contractevent]
+pub struct HandCancelled {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub refunded: i128,
+}
+
+/// The best hand to lose the main pot was four of a kind or better: its
+/// player was awarded `loser_amount` of the bad-beat jackpot and the player
+/// who beat it `winner_amount`, both to claim with `claim_jackpot`.
+#[contractevent]
+pub struct JackpotAwarded {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub loser: Address,
+    pub loser_amount: i128,
+    pub winner: Address,
+    pub winner_amount: i128,
+}
+
+/// `player` was paid the `amount` of jackpot awarded to them.
+#[contractevent]
+pub struct JackpotClaimed {
+    #[topic]
+    pub table_id: u32,
+    pub player: Address,
+    pub amount: i128,
+}
+
+/// `amount` of rake from the hand was added to the table's balance.
+#[contractevent]
+pub struct RakeCollected {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub amount: i128,
+}
+
+/// The admin paid `amount` of collected rake out to `to`.
+#[contractevent]
+pub struct RakeWithdrawn {
+    #[topic]
+    pub table_id: u32,
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// The player in `seat` took `used` ledgers past the betting timeout out of
+/// their time bank, leaving `remaining` for the rest of the hand.
+#[contractevent]
+pub struct TimeBankUsed {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub player: Address,
+    pub seat: u32,
+    pub used: u32,
+    pub remaining: u32,
+}
+
+/// The player in `seat` ran out of time and was folded.
+#[contractevent]
+pub struct TimeoutFold {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub player: Address,
+    pub seat: u32,
+}
+
+/// The committee missed its deadline in `phase` with `pot` in the middle.
+#[contractevent]
+pub struct CommitteeTimeout {
+    #[topic]
+    pub table_id: u32,
+    pub hand_number: u32,
+    pub phase: GamePhase,
+    pub pot: i128,
+}
@@ -1,43 +1,62 @@
-use soroban_sdk::{Env, Symbol, Vec};
+use soroban_sdk::{Address, Env, Vec};
 
-use crate::game_hub;
+use crate::events::{
+    FoldWin, HandCancelled, HandSettled, JackpotAwarded, PotAwarded, RakeCollected,
+};
+use crate::game_hub::{self, HandResult, SettlementReport};
+use crate::pot;
+use crate::public_inputs;
 use crate::types::*;
 
+/// The worst hand whose loss at showdown wins the bad-beat jackpot.
+const BAD_BEAT_MIN_CATEGORY: u32 = stellar_zk_cards::HandCategory::FourOfAKind as u32;
+/// Shares of the jackpot, in basis points, for the player whose hand was
+/// beaten and the player who beat it. The rest seeds the next jackpot.
+const BAD_BEAT_LOSER_BPS: i128 = 5_000;
+const BAD_BEAT_WINNER_BPS: i128 = 2_500;
+
 /// Initialize state for a new hand.
 pub fn start_new_hand(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
     table.hand_number += 1;
 
-    // Rotate dealer button
-    let num_players = table.players.len() as u32;
-    if num_players < 2 {
+    if seated_in_count(table) < 2 {
         return Err(PokerTableError::NeedAtLeastTwoPlayers);
     }
-    table.dealer_seat = (table.dealer_seat + 1) % num_players;
+    let (dealer, sb_seat, bb_seat) = next_positions(table)?;
 
-    // Reset player states
+    // Reset player states. Players sitting out are dealt out by folding
+    // them up front: they post no blinds, never get a turn and are in no pot.
     for i in 0..table.players.len() {
-        let mut p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        p.folded = false;
+        let Some(mut p) = table.players.get(i).flatten() else {
+            continue;
+        };
+        p.folded = p.sitting_out;
         p.all_in = false;
         p.bet_this_round = 0;
-        table.players.set(i, p);
+        p.acted = false;
+        p.hand_start_stack = p.stack;
+        p.time_bank_ledgers = table.config.time_bank_ledgers;
+        table.players.set(i, Some(p));
     }
 
-    // Post blinds
-    let sb_seat = (table.dealer_seat + 1) % num_players;
-    let bb_seat = (table.dealer_seat + 2) % num_players;
-
-    post_blind(table, sb_seat, table.config.small_blind)?;
+    // Move the button and post blinds
+    table.dealer_seat = dealer;
+    table.small_blind_seat = sb_seat;
+    table.big_blind_seat = bb_seat;
+    if small_blind_is_live(table)? {
+        post_blind(table, sb_seat, table.config.small_blind)?;
+    }
     post_blind(table, bb_seat, table.config.big_blind)?;
+    table.min_raise = table.config.big_blind;
 
     // Clear board state
     table.board_cards = Vec::new(env);
     table.dealt_indices = Vec::new(env);
     table.hand_commitments = Vec::new(env);
     table.side_pots = Vec::new(env);
+    table.proof_digests = Vec::new(env);
+    table.straddle_seat = None;
+    table.cancel_votes = Vec::new(env);
 
     // Transition to dealing phase (committee will shuffle + deal)
     table.phase = GamePhase::Dealing;
@@ -45,33 +64,172 @@ pub fn start_new_hand(env: &Env, table: &mut TableState) -> Result<(), PokerTabl
     Ok(())
 }
 
-fn post_blind(table: &mut TableState, seat: u32, amount: i128) -> Result<(), PokerTableError> {
-    let mut player = table
+/// Button, small blind and big blind seats for the next hand.
+///
+/// The big blind moves to the next player dealt in after last hand's, so
+/// nobody who is playing skips it. The small blind falls on last hand's big
+/// blind seat and the button on last hand's small blind seat, even when the
+/// player there has left or sits out: that makes a dead small blind or a
+/// dead button rather than anyone paying the blinds twice in a row.
+///
+/// Heads-up the button posts the small blind and the other player the big
+/// blind, so the button acts first preflop and last on every later street.
+fn next_positions(table: &TableState) -> Result<(u32, u32, u32), PokerTableError> {
+    let num_seats = table.players.len();
+    let heads_up = seated_in_count(table) == 2;
+    if table.hand_number <= 1 {
+        let dealer = next_seated_in(table, table.dealer_seat)?;
+        if heads_up {
+            return Ok((dealer, dealer, next_seated_in(table, dealer)?));
+        }
+        let sb_seat = next_seated_in(table, dealer)?;
+        return Ok((dealer, sb_seat, next_seated_in(table, sb_seat)?));
+    }
+
+    let bb_seat = next_seated_in(table, table.big_blind_seat)?;
+    if heads_up {
+        let button = next_seated_in(table, bb_seat)?;
+        return Ok((button, button, bb_seat));
+    }
+    let sb_seat = table.big_blind_seat;
+    let mut dealer = table.small_blind_seat;
+    if dealer == bb_seat {
+        // Coming from heads-up, where last hand's small blind is this
+        // hand's big blind: the button goes right of the small blind.
+        dealer = (sb_seat + num_seats - 1) % num_seats;
+    }
+    Ok((dealer, sb_seat, bb_seat))
+}
+
+/// Whether this hand's small blind seat posts, i.e. it is not dead. Only
+/// heads-up does the button post it.
+pub fn small_blind_is_live(table: &TableState) -> Result<bool, PokerTableError> {
+    let seat = table.small_blind_seat;
+    let on_button = seat == table.dealer_seat && seated_in_count(table) != 2;
+    if on_button || seat == table.big_blind_seat {
+        return Ok(false);
+    }
+    // A seat emptied since last hand is dead too.
+    match table.players.get(seat).flatten() {
+        Some(p) => Ok(!p.sitting_out),
+        None => Ok(false),
+    }
+}
+
+/// The player in `seat`.
+pub fn player_at(table: &TableState, seat: u32) -> Result<PlayerState, PokerTableError> {
+    table
         .players
         .get(seat)
-        .ok_or(PokerTableError::InvalidPlayerIndex)?;
-    let actual = if player.stack < amount {
-        player.all_in = true;
-        player.stack
-    } else {
-        amount
-    };
+        .flatten()
+        .ok_or(PokerTableError::InvalidPlayerIndex)
+}
+
+/// Count seated players, including those sitting out.
+pub fn seated_count(table: &TableState) -> u32 {
+    table.players.iter().flatten().count() as u32
+}
+
+/// Count players who are not sitting out.
+pub fn seated_in_count(table: &TableState) -> u32 {
+    let mut count = 0u32;
+    for p in table.players.iter().flatten() {
+        if !p.sitting_out {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// The first seat after `seat` with a player who is not sitting out.
+fn next_seated_in(table: &TableState, seat: u32) -> Result<u32, PokerTableError> {
+    let num_seats = table.players.len();
+    let mut next = seat;
+    for _ in 0..num_seats {
+        next = (next + 1) % num_seats;
+        if let Some(p) = table.players.get(next).flatten() {
+            if !p.sitting_out {
+                return Ok(next);
+            }
+        }
+    }
+    Err(PokerTableError::NeedAtLeastTwoPlayers)
+}
+
+fn post_blind(table: &mut TableState, seat: u32, amount: i128) -> Result<(), PokerTableError> {
+    let mut player = player_at(table, seat)?;
+    let actual = core::cmp::min(player.stack, amount);
 
     player.stack -= actual;
+    player.all_in = player.stack == 0;
     player.bet_this_round = actual;
     table.pot += actual;
-    table.players.set(seat, player);
+    table.players.set(seat, Some(player));
     Ok(())
 }
 
+/// Post a straddle of twice the big blind for `player`, who must be the
+/// first player dealt in to the left of the big blind and not have posted a
+/// blind already. Returns their seat and the amount posted.
+pub fn post_straddle(
+    table: &mut TableState,
+    player: &Address,
+) -> Result<(u32, i128), PokerTableError> {
+    if table.straddle_seat.is_some() {
+        return Err(PokerTableError::StraddleNotAllowed);
+    }
+    let seat = next_seated_in(table, table.big_blind_seat)?;
+    let p = player_at(table, seat)?;
+    if p.address != *player || p.bet_this_round > 0 {
+        return Err(PokerTableError::StraddleNotAllowed);
+    }
+    let amount = 2 * table.config.big_blind;
+    if p.stack <= amount {
+        return Err(PokerTableError::NotEnoughChips);
+    }
+
+    post_blind(table, seat, amount)?;
+    table.min_raise = amount;
+    table.straddle_seat = Some(seat);
+    Ok((seat, amount))
+}
+
+/// Record `player`'s vote to cancel the hand. They must be dealt in and not
+/// have folded. Returns their seat and whether every player still in the
+/// hand has now voted.
+pub fn vote_cancel(
+    table: &mut TableState,
+    player: &Address,
+) -> Result<(u32, bool), PokerTableError> {
+    let p = table
+        .players
+        .iter()
+        .flatten()
+        .find(|p| p.address == *player)
+        .ok_or(PokerTableError::PlayerNotAtTable)?;
+    if p.folded {
+        return Err(PokerTableError::PlayerAlreadyFolded);
+    }
+    if table.cancel_votes.contains(p.seat_index) {
+        return Err(PokerTableError::AlreadyVotedToCancel);
+    }
+    table.cancel_votes.push_back(p.seat_index);
+
+    let unanimous = table
+        .players
+        .iter()
+        .flatten()
+        .filter(|p| !p.folded)
+        .all(|p| table.cancel_votes.contains(p.seat_index));
+    Ok((p.seat_index, unanimous))
+}
+
 /// Count players still active (not folded).
 pub fn active_player_count(table: &TableState) -> u32 {
     let mut count = 0u32;
-    for i in 0..table.players.len() {
-        if let Some(p) = table.players.get(i) {
-            if !p.folded {
-                count += 1;
-            }
+    for p in table.players.iter().flatten() {
+        if !p.folded {
+            count += 1;
         }
     }
     count
@@ -82,26 +240,28 @@ pub fn last_player_standing(table: &TableState) -> Option<u32> {
     if active_player_count(table) != 1 {
         return None;
     }
-    for i in 0..table.players.len() {
-        if let Some(p) = table.players.get(i) {
-            if !p.folded {
-                return Some(p.seat_index);
-            }
+    for p in table.players.iter().flatten() {
+        if !p.folded {
+            return Some(p.seat_index);
         }
     }
     None
 }
 
-/// Settle the showdown: evaluate hands and distribute pot.
+/// Settle the showdown: evaluate hands and award the main pot and each side
+/// pot to the best hand among the players eligible for it. Tied hands split
+/// a pot evenly, with odd chips to the first winner left of the button.
+///
+/// `hole_cards` and `winner` are in deal order, as the showdown proof gives
+/// them. A player whose cards the proof hides mucked and cannot win a pot
+/// anyone else is eligible for. The proven winner must be still in, and no
+/// one still in may hold a better hand.
 pub fn settle_showdown(
     env: &Env,
     table: &mut TableState,
     hole_cards: &Vec<(u32, u32)>,
+    winner: u32,
 ) -> Result<(), PokerTableError> {
-    // Collect active players' hands
-    let mut best_rank: u32 = 0;
-    let mut winner_seat: u32 = 0;
-
     let board = &table.board_cards;
     if board.len() != 5 {
         return Err(PokerTableError::BoardNotComplete);
@@ -115,19 +275,21 @@ pub fn settle_showdown(
         board.get(4).ok_or(PokerTableError::BoardNotComplete)?,
     ];
 
-    let mut active_idx = 0u32;
-    for i in 0..table.players.len() {
-        let p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+    // (seat, hand score) for each player still in the hand who showed, in
+    // seat order.
+    let mut scores: Vec<(u32, u32)> = Vec::new(env);
+    let mut winner_score = None;
+    for (deal_idx, p) in table.players.iter().flatten().enumerate() {
         if p.folded {
             continue;
         }
 
         let (c1, c2) = hole_cards
-            .get(active_idx)
+            .get(deal_idx as u32)
             .ok_or(PokerTableError::InvalidHoleCards)?;
+        if c1 == public_inputs::HIDDEN_CARD {
+            continue;
+        }
         let cards: [u32; 7] = [
             c1,
             c2,
@@ -139,60 +301,368 @@ pub fn settle_showdown(
         ];
 
         let rank = stellar_zk_cards::evaluate_hand(&cards);
-        if rank.score > best_rank {
-            best_rank = rank.score;
-            winner_seat = p.seat_index;
+        scores.push_back((p.seat_index, rank.score));
+        if deal_idx as u32 == winner {
+            winner_score = Some(rank.score);
         }
-
-        active_idx += 1;
+    }
+    let winner_score = winner_score.ok_or(PokerTableError::ShowdownProofVerificationFailed)?;
+    if scores.iter().any(|(_, score)| score > winner_score) {
+        return Err(PokerTableError::ShowdownProofVerificationFailed);
     }
 
-    // Award pot to winner
     let winnings = table.pot;
-    let mut winner = table
-        .players
-        .get(winner_seat)
-        .ok_or(PokerTableError::InvalidPlayerIndex)?;
-    winner.stack += winnings;
-    table.players.set(winner_seat, winner.clone());
+    let rake = pot::rake_for(&table.config, winnings);
+    let jackpot = pot::jackpot_for(&table.config, winnings);
+    let mut pots = pot::calculate_side_pots(env, table)?;
+    pot::deduct_rake(&mut pots, winnings, rake + jackpot)?;
+    // Reported in `hand_settled`; on a split, the winner left of the button.
+    let mut main_winner: Option<Address> = None;
+    // The main pot's winners and, of them, the one left of the button.
+    let mut main_pot: Option<(Vec<u32>, u32)> = None;
+    let mut winner_seats: Vec<u32> = Vec::new(env);
+    let mut payouts: Vec<PotPayout> = Vec::new(env);
+    for (pot_index, side_pot) in pots.iter().enumerate() {
+        // Every eligible player with the best score shares the pot.
+        let mut best_score = 0u32;
+        let mut winners: Vec<u32> = Vec::new(env);
+        for (seat, score) in scores.iter() {
+            if !side_pot.eligible_players.contains(seat) {
+                continue;
+            }
+            if winners.is_empty() || score > best_score {
+                best_score = score;
+                winners = Vec::from_array(env, [seat]);
+            } else if score == best_score {
+                winners.push_back(seat);
+            }
+        }
+        // A pot no one showed for has only a mucked player in it.
+        if winners.is_empty() && side_pot.eligible_players.len() == 1 {
+            winners = side_pot.eligible_players.clone();
+        }
+        let odd_chip_seat =
+            first_left_of_dealer(table, &winners).ok_or(PokerTableError::InvalidPlayerIndex)?;
+        let share = side_pot.amount / winners.len() as i128;
+        let odd_chips = side_pot.amount % winners.len() as i128;
+        if pot_index == 0 {
+            main_pot = Some((winners.clone(), odd_chip_seat));
+        }
+
+        for winner_seat in winners.iter() {
+            let amount = if winner_seat == odd_chip_seat {
+                share + odd_chips
+            } else {
+                share
+            };
+            let mut winner = player_at(table, winner_seat)?;
+            winner.stack += amount;
+            table.players.set(winner_seat, Some(winner.clone()));
+
+            PotAwarded {
+                table_id: table.id,
+                hand_number: table.hand_number,
+                pot_index: pot_index as u32,
+                player: winner.address.clone(),
+                seat: winner_seat,
+                amount,
+            }
+            .publish(env);
+            payouts.push_back(PotPayout {
+                pot_index: pot_index as u32,
+                seat: winner_seat,
+                player: winner.address.clone(),
+                amount,
+            });
+            if winner_seat == odd_chip_seat {
+                main_winner.get_or_insert(winner.address);
+            }
+            if !winner_seats.contains(winner_seat) {
+                winner_seats.push_back(winner_seat);
+            }
+        }
+    }
+    let main_winner = main_winner.ok_or(PokerTableError::InvalidPlayerIndex)?;
+    let (main_pot_winners, main_winner_seat) =
+        main_pot.ok_or(PokerTableError::InvalidPlayerIndex)?;
+    table.side_pots = pots;
     table.pot = 0;
 
     table.phase = GamePhase::Settlement;
     table.last_action_ledger = env.ledger().sequence();
 
-    // Notify game hub: player1_won = true if winner is seat 0 (player1)
-    let player1_won = winner_seat == 0;
-    game_hub::notify_end(env, &table.config.game_hub, table.session_id, player1_won);
-
-    env.events().publish(
-        (Symbol::new(env, "hand_settled"), table.id),
-        (winner.address.clone(), winnings),
+    collect_rake(env, table, rake);
+    collect_jackpot(env, table, jackpot);
+    award_bad_beat(env, table, &scores, &main_pot_winners, main_winner_seat)?;
+    notify_hand_end(env, table, winnings, rake);
+    record_hand(env, table, winner_seats, winnings, rake, true);
+    record_settlement(
+        env,
+        table,
+        SettlementSource::Showdown,
+        winnings,
+        rake,
+        jackpot,
+        payouts,
     );
+
+    HandSettled {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        winner: main_winner,
+        pot: winnings,
+        rake,
+    }
+    .publish(env);
     Ok(())
 }
 
+/// The seat among `seats` that sits first to the left of the button.
+fn first_left_of_dealer(table: &TableState, seats: &Vec<u32>) -> Option<u32> {
+    let num_seats = table.players.len();
+    seats
+        .iter()
+        .min_by_key(|seat| (seat + num_seats - (table.dealer_seat + 1) % num_seats) % num_seats)
+}
+
 /// Award pot to last player standing (all others folded).
 pub fn settle_fold_win(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
     if let Some(winner_seat) = last_player_standing(table) {
         let winnings = table.pot;
-        let mut winner = table
-            .players
-            .get(winner_seat)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        winner.stack += winnings;
-        table.players.set(winner_seat, winner.clone());
+        let rake = pot::rake_for(&table.config, winnings);
+        let jackpot = pot::jackpot_for(&table.config, winnings);
+        let mut winner = player_at(table, winner_seat)?;
+        winner.stack += winnings - rake - jackpot;
+        table.players.set(winner_seat, Some(winner.clone()));
         table.pot = 0;
         table.phase = GamePhase::Settlement;
         table.last_action_ledger = env.ledger().sequence();
 
-        // Notify game hub
-        let player1_won = winner_seat == 0;
-        game_hub::notify_end(env, &table.config.game_hub, table.session_id, player1_won);
-
-        env.events().publish(
-            (Symbol::new(env, "fold_win"), table.id),
-            (winner.address.clone(), winnings),
+        collect_rake(env, table, rake);
+        collect_jackpot(env, table, jackpot);
+        notify_hand_end(env, table, winnings, rake);
+        record_hand(
+            env,
+            table,
+            Vec::from_array(env, [winner_seat]),
+            winnings,
+            rake,
+            false,
+        );
+        record_settlement(
+            env,
+            table,
+            SettlementSource::Fold,
+            winnings,
+            rake,
+            jackpot,
+            Vec::from_array(
+                env,
+                [PotPayout {
+                    pot_index: 0,
+                    seat: winner_seat,
+                    player: winner.address.clone(),
+                    amount: winnings - rake - jackpot,
+                }],
+            ),
         );
+
+        FoldWin {
+            table_id: table.id,
+            hand_number: table.hand_number,
+            winner: winner.address,
+            seat: winner_seat,
+            pot: winnings,
+            rake,
+        }
+        .publish(env);
     }
     Ok(())
 }
+
+/// Call off the hand in progress: every player's stack goes back to what it
+/// was before the hand, refunding all bets, and the table waits for the
+/// next `start_hand`.
+pub fn cancel_hand(env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
+    let refunded = table.pot;
+    for i in 0..table.players.len() {
+        let Some(mut p) = table.players.get(i).flatten() else {
+            continue;
+        };
+        p.stack = p.hand_start_stack;
+        p.bet_this_round = 0;
+        p.folded = false;
+        p.all_in = false;
+        p.acted = false;
+        table.players.set(i, Some(p));
+    }
+    table.pot = 0;
+    table.side_pots = Vec::new(env);
+    table.cancel_votes = Vec::new(env);
+    table.phase = GamePhase::Waiting;
+    table.last_action_ledger = env.ledger().sequence();
+
+    notify_hand_end(env, table, 0, 0);
+
+    HandCancelled {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        refunded,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Keep a record of the settled hand for `get_hand_history`.
+fn record_hand(
+    env: &Env,
+    table: &TableState,
+    winner_seats: Vec<u32>,
+    pot: i128,
+    rake: i128,
+    showdown: bool,
+) {
+    crate::save_hand_record(
+        env,
+        table.id,
+        &HandRecord {
+            hand_number: table.hand_number,
+            board_cards: table.board_cards.clone(),
+            winner_seats,
+            pot,
+            rake,
+            showdown,
+            proof_digests: table.proof_digests.clone(),
+        },
+    );
+}
+
+/// Keep how the hand was settled for `get_last_settlement`.
+pub fn record_settlement(
+    env: &Env,
+    table: &TableState,
+    source: SettlementSource,
+    pot: i128,
+    rake: i128,
+    jackpot: i128,
+    payouts: Vec<PotPayout>,
+) {
+    crate::save_settlement(
+        env,
+        table.id,
+        &Settlement {
+            hand_number: table.hand_number,
+            source,
+            pot,
+            rake,
+            jackpot,
+            payouts,
+        },
+    );
+}
+
+/// Add the hand's rake to the table's balance for the admin to withdraw.
+fn collect_rake(env: &Env, table: &TableState, rake: i128) {
+    if rake <= 0 {
+        return;
+    }
+    let balance = crate::rake_balance(env, table.id) + rake;
+    crate::set_rake_balance(env, table.id, balance);
+    RakeCollected {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        amount: rake,
+    }
+    .publish(env);
+}
+
+/// Add the hand's contribution to the table's bad-beat jackpot.
+fn collect_jackpot(env: &Env, table: &TableState, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    let balance = crate::jackpot_balance(env, table.id) + amount;
+    crate::set_jackpot_balance(env, table.id, balance);
+}
+
+/// Award the bad-beat jackpot if the best hand shown that lost the main pot
+/// was four of a kind or better. Its player and the main pot's winner at
+/// `winner_seat` are credited their shares, to take with `claim_jackpot`.
+fn award_bad_beat(
+    env: &Env,
+    table: &TableState,
+    scores: &Vec<(u32, u32)>,
+    main_pot_winners: &Vec<u32>,
+    winner_seat: u32,
+) -> Result<(), PokerTableError> {
+    let mut beaten: Option<(u32, u32)> = None;
+    for (seat, score) in scores.iter() {
+        let category = stellar_zk_cards::HandRank { score }.category();
+        if main_pot_winners.contains(seat) || category < BAD_BEAT_MIN_CATEGORY {
+            continue;
+        }
+        if beaten.is_none_or(|(_, best)| score > best) {
+            beaten = Some((seat, score));
+        }
+    }
+    let Some((loser_seat, _)) = beaten else {
+        return Ok(());
+    };
+
+    let balance = crate::jackpot_balance(env, table.id);
+    let loser_amount = balance * BAD_BEAT_LOSER_BPS / 10_000;
+    let winner_amount = balance * BAD_BEAT_WINNER_BPS / 10_000;
+    if loser_amount <= 0 {
+        return Ok(());
+    }
+    crate::set_jackpot_balance(env, table.id, balance - loser_amount - winner_amount);
+
+    let loser = player_at(table, loser_seat)?.address;
+    let winner = player_at(table, winner_seat)?.address;
+    let mut claims = crate::jackpot_claims(env, table.id);
+    for (player, amount) in [(&loser, loser_amount), (&winner, winner_amount)] {
+        let owed = claims.get(player.clone()).unwrap_or(0) + amount;
+        claims.set(player.clone(), owed);
+    }
+    crate::set_jackpot_claims(env, table.id, &claims);
+
+    JackpotAwarded {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        loser,
+        loser_amount,
+        winner,
+        winner_amount,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Report every player's profit or loss over the hand, and the settlement
+/// of `pot` less `rake`, to the game hub.
+pub fn notify_hand_end(env: &Env, table: &TableState, pot: i128, rake: i128) {
+    let mut hand_results = Vec::new(env);
+    let mut results = Vec::new(env);
+    for p in table.players.iter().flatten() {
+        let net = p.stack - p.hand_start_stack;
+        hand_results.push_back(HandResult {
+            player: p.address.clone(),
+            stake: p.hand_start_stack,
+            net,
+        });
+        results.push_back((p.address, net));
+    }
+    game_hub::notify_hand_result(env, &table.config.game_hub, table.session_id, &hand_results);
+    game_hub::notify_end(env, &table.config.game_hub, table.session_id, &results);
+    game_hub::notify_settlement(
+        env,
+        &table.config.game_hub,
+        &SettlementReport {
+            session_id: table.session_id,
+            hand_number: table.hand_number,
+            pot,
+            rake,
+            deltas: results,
+        },
+    );
+}
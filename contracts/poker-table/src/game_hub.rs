@@ -1,22 +1,36 @@
-use soroban_sdk::{contractclient, Address, Env};
-
-/// Game Hub contract client interface.
-/// In production, calls the Stellar Game Studio Game Hub at
-/// CB4VZAT2U3UC6XFK3N23SKRF2NDCMP3QHJYMCHHFMZO7MRQO6DQ2EMYG.
-/// For tests, use the mock in contracts/game-hub/.
-#[contractclient(name = "GameHubClient")]
-pub trait GameHub {
+use game_hub_interface::GameHubClient;
+use soroban_sdk::{Address, Env, Vec};
+
+pub use game_hub_interface::{HandResult, SettlementReport, TableStakes};
+
+#[cfg(test)]
+use soroban_sdk::{contract, contractimpl};
+
+/// Mock Game Hub for tests. In production, calls go to the Stellar Game
+/// Studio Game Hub (see `game_hub_interface`); contracts/game-hub/ has a
+/// deployable mock.
+#[cfg(test)]
+#[contract]
+pub struct GameHubContract;
+
+#[cfg(test)]
+#[contractimpl]
+impl game_hub_interface::GameHub for GameHubContract {
     fn start_game(
-        env: Env,
-        game_id: Address,
-        session_id: u32,
-        player1: Address,
-        player2: Address,
-        player1_points: i128,
-        player2_points: i128,
-    );
-
-    fn end_game(env: Env, session_id: u32, player1_won: bool);
+        _env: Env,
+        _game_id: Address,
+        _session_id: u32,
+        _players: Vec<Address>,
+        _stakes: Vec<i128>,
+        _table_stakes: TableStakes,
+    ) {
+    }
+
+    fn hand_result(_env: Env, _session_id: u32, _results: Vec<HandResult>) {}
+
+    fn end_game(_env: Env, _session_id: u32, _results: Vec<(Address, i128)>) {}
+
+    fn report_settlement(_env: Env, _report: SettlementReport) {}
 }
 
 /// Notify the game hub that a new hand is starting.
@@ -25,24 +39,33 @@ pub fn notify_start(
     game_hub: &Address,
     game_id: &Address,
     session_id: u32,
-    player1: &Address,
-    player2: &Address,
-    player1_points: i128,
-    player2_points: i128,
+    players: &Vec<Address>,
+    stakes: &Vec<i128>,
+    table_stakes: &TableStakes,
 ) {
     let client = GameHubClient::new(env, game_hub);
-    client.start_game(
-        game_id,
-        &session_id,
-        player1,
-        player2,
-        &player1_points,
-        &player2_points,
-    );
+    client.start_game(game_id, &session_id, players, stakes, table_stakes);
+}
+
+/// Send the hub every player's profit or loss over a hand.
+pub fn notify_hand_result(
+    env: &Env,
+    game_hub: &Address,
+    session_id: u32,
+    results: &Vec<HandResult>,
+) {
+    let client = GameHubClient::new(env, game_hub);
+    client.hand_result(&session_id, results);
 }
 
 /// Notify the game hub that a hand has ended.
-pub fn notify_end(env: &Env, game_hub: &Address, session_id: u32, player1_won: bool) {
+pub fn notify_end(env: &Env, game_hub: &Address, session_id: u32, results: &Vec<(Address, i128)>) {
+    let client = GameHubClient::new(env, game_hub);
+    client.end_game(&session_id, results);
+}
+
+/// Send the hub the payout breakdown of a settled hand.
+pub fn notify_settlement(env: &Env, game_hub: &Address, report: &SettlementReport) {
     let client = GameHubClient::new(env, game_hub);
-    client.end_game(&session_id, &player1_won);
+    client.report_settlement(report);
 }
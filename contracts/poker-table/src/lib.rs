@@ -1,23 +1,47 @@
 #![no_std]
 #![allow(deprecated)]
 
-use soroban_sdk::{contract, contractimpl, token, Address, Bytes, BytesN, Env, Symbol, Vec};
+#[cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, contractimpl, token, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
 
 mod betting;
+#[cfg(test)]
+mod betting_props;
+pub mod events;
 mod game;
 mod game_hub;
 mod pot;
+pub mod public_inputs;
+mod registry;
 mod test;
 mod timeout;
 mod types;
 mod verifier;
 
-use types::*;
+use events::*;
+pub use types::*;
 
 /// TTL for table storage (30 days in ledgers, ~5 seconds per ledger)
 const TABLE_TTL_THRESHOLD: u32 = 17_280; // ~1 day — trigger extension when below this
 const TABLE_TTL_EXTEND: u32 = 518_400; // ~30 days
 
+/// Hands a player can go without acting before anyone may kick them.
+const KICK_IDLE_AFTER_HANDS: u32 = 3;
+
+/// XDR of `Asset::Native`, which is just its type discriminant.
+const NATIVE_ASSET_XDR: [u8; 4] = [0, 0, 0, 0];
+
+/// Most tables `get_table_page` returns at once.
+const MAX_TABLE_PAGE: u32 = 50;
+
+/// Longest a session key can act for its player (~1 day).
+const MAX_SESSION_LEDGERS: u32 = 17_280;
+
+/// Ledgers a scheduled verifier waits before it can take over (~1 day).
+pub const VERIFIER_TIMELOCK_LEDGERS: u32 = 17_280;
+
 #[contract]
 pub struct PokerTableContract;
 
@@ -47,6 +71,256 @@ fn save_table(env: &Env, table: &TableState) {
         .extend_ttl(TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
 }
 
+fn rake_balance(env: &Env, table_id: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RakeBalance(table_id))
+        .unwrap_or(0)
+}
+
+fn set_rake_balance(env: &Env, table_id: u32, balance: i128) {
+    let key = DataKey::RakeBalance(table_id);
+    env.storage().persistent().set(&key, &balance);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+}
+
+fn jackpot_balance(env: &Env, table_id: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Jackpot(table_id))
+        .unwrap_or(0)
+}
+
+fn set_jackpot_balance(env: &Env, table_id: u32, balance: i128) {
+    let key = DataKey::Jackpot(table_id);
+    env.storage().persistent().set(&key, &balance);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+}
+
+/// Jackpot awards not yet claimed, by player.
+fn jackpot_claims(env: &Env, table_id: u32) -> Map<Address, i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::JackpotClaims(table_id))
+        .unwrap_or_else(|| Map::new(env))
+}
+
+fn set_jackpot_claims(env: &Env, table_id: u32, claims: &Map<Address, i128>) {
+    let key = DataKey::JackpotClaims(table_id);
+    env.storage().persistent().set(&key, claims);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+}
+
+fn save_hand_record(env: &Env, table_id: u32, record: &HandRecord) {
+    let key = DataKey::HandHistory(table_id, record.hand_number);
+    env.storage().persistent().set(&key, record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+}
+
+fn session_key(env: &Env, table_id: u32, player: &Address) -> Option<SessionKey> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SessionKey(table_id, player.clone()))
+}
+
+fn set_session_key(env: &Env, table_id: u32, player: &Address, session_key: &SessionKey) {
+    let key = DataKey::SessionKey(table_id, player.clone());
+    env.storage().persistent().set(&key, session_key);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+}
+
+fn remove_session_key(env: &Env, table_id: u32, player: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::SessionKey(table_id, player.clone()));
+}
+
+/// `player`'s session key, unless it has expired.
+fn session_key_for(env: &Env, table_id: u32, player: &Address) -> Option<SessionKey> {
+    session_key(env, table_id, player).filter(|k| env.ledger().sequence() < k.expires_ledger)
+}
+
+/// Apply `player`'s betting action, once whoever may act for them has
+/// authorized it.
+fn act(env: &Env, table_id: u32, player: &Address, action: &Action) -> Result<(), PokerTableError> {
+    let mut table = load_table(env, table_id)?;
+    ensure_not_paused(&table)?;
+
+    if !matches!(
+        table.phase,
+        GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River
+    ) {
+        return Err(PokerTableError::NotInBettingPhase);
+    }
+
+    betting::process_action(env, &mut table, player, action)?;
+
+    save_table(env, &table);
+    Ok(())
+}
+
+fn save_settlement(env: &Env, table_id: u32, settlement: &Settlement) {
+    let key = DataKey::LastSettlement(table_id);
+    env.storage().persistent().set(&key, settlement);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+}
+
+/// Empty `seat` and pay its player their stack, dropping any session key
+/// they registered. Nobody else's seat index changes.
+fn unseat(env: &Env, table: &mut TableState, seat: u32) -> Result<i128, PokerTableError> {
+    let player = game::player_at(table, seat)?;
+    remove_session_key(env, table.id, &player.address);
+    if player.stack > 0 {
+        let token = token::Client::new(env, &table.config.token);
+        token.transfer(
+            &env.current_contract_address(),
+            &player.address,
+            &player.stack,
+        );
+    }
+    table.players.set(seat, None);
+    Ok(player.stack)
+}
+
+fn ensure_not_paused(table: &TableState) -> Result<(), PokerTableError> {
+    if table.paused {
+        return Err(PokerTableError::TablePaused);
+    }
+    Ok(())
+}
+
+/// Seat `player` in the first empty seat with `buy_in` chips, paid by a
+/// transfer they authorize or, `from_allowance`, out of what they have
+/// approved the contract to spend.
+fn join(
+    env: &Env,
+    table_id: u32,
+    player: Address,
+    buy_in: i128,
+    from_allowance: bool,
+) -> Result<u32, PokerTableError> {
+    let mut table = load_table(env, table_id)?;
+    ensure_not_paused(&table)?;
+
+    if !matches!(table.phase, GamePhase::Waiting) {
+        return Err(PokerTableError::TableNotAcceptingPlayers);
+    }
+    // Take the first empty seat.
+    let seat = (0..table.players.len())
+        .find(|&i| table.players.get(i).flatten().is_none())
+        .ok_or(PokerTableError::TableFull)?;
+    if buy_in < table.config.min_buy_in || buy_in > table.config.max_buy_in {
+        return Err(PokerTableError::InvalidBuyIn);
+    }
+
+    // Check player not already seated.
+    for p in table.players.iter().flatten() {
+        if p.address == player {
+            return Err(PokerTableError::AlreadySeated);
+        }
+    }
+
+    // Move the buy-in to the contract.
+    let token = token::Client::new(env, &table.config.token);
+    let contract = env.current_contract_address();
+    if token.balance(&player) < buy_in {
+        return Err(PokerTableError::InsufficientBalance);
+    }
+    if from_allowance {
+        if token.allowance(&player, &contract) < buy_in {
+            return Err(PokerTableError::InsufficientAllowance);
+        }
+        token.transfer_from(&contract, &player, &contract, &buy_in);
+    } else {
+        token.transfer(&player, &contract, &buy_in);
+    }
+
+    table.players.set(
+        seat,
+        Some(PlayerState {
+            address: player.clone(),
+            stack: buy_in,
+            bet_this_round: 0,
+            folded: false,
+            all_in: false,
+            sitting_out: false,
+            seat_index: seat,
+            hand_start_stack: buy_in,
+            acted: false,
+            missed_turns: 0,
+            last_active_hand: table.hand_number,
+            muck_preference: MuckPreference::Show,
+            time_bank_ledgers: table.config.time_bank_ledgers,
+        }),
+    );
+
+    save_table(env, &table);
+
+    PlayerJoined {
+        table_id,
+        player,
+        seat,
+        buy_in,
+    }
+    .publish(env);
+
+    Ok(seat)
+}
+
+fn set_sitting_out(
+    env: &Env,
+    table_id: u32,
+    player: Address,
+    sitting_out: bool,
+) -> Result<(), PokerTableError> {
+    let mut table = load_table(env, table_id)?;
+    let mut seat = None;
+    for i in 0..table.players.len() {
+        let Some(mut p) = table.players.get(i).flatten() else {
+            continue;
+        };
+        if p.address == player {
+            p.sitting_out = sitting_out;
+            p.missed_turns = 0;
+            p.last_active_hand = table.hand_number;
+            table.players.set(i, Some(p));
+            seat = Some(i);
+            break;
+        }
+    }
+    let seat = seat.ok_or(PokerTableError::PlayerNotAtTable)?;
+    save_table(env, &table);
+
+    if sitting_out {
+        PlayerSatOut {
+            table_id,
+            player,
+            seat,
+        }
+        .publish(env);
+    } else {
+        PlayerSatIn {
+            table_id,
+            player,
+            seat,
+        }
+        .publish(env);
+    }
+    Ok(())
+}
+
 fn derive_session_id(table_id: u32, hand_number: u32) -> u32 {
     // Deterministic 32-bit hash of (table_id, hand_number).
     let mut x = table_id ^ hand_number.rotate_left(16);
@@ -57,12 +331,139 @@ fn derive_session_id(table_id: u32, hand_number: u32) -> u32 {
     x
 }
 
+/// A committee needs a threshold between one and its size.
+fn check_committee(committee: &Vec<Address>, threshold: u32) -> Result<(), PokerTableError> {
+    if threshold == 0 || threshold > committee.len() {
+        return Err(PokerTableError::InvalidCommittee);
+    }
+    Ok(())
+}
+
+/// Hand the table to a new committee. Only between hands, so a hand's
+/// proofs all come from the committee that dealt it.
+fn rotate_committee(
+    env: &Env,
+    table: &mut TableState,
+    committee: Vec<Address>,
+    threshold: u32,
+) -> Result<(), PokerTableError> {
+    if !matches!(table.phase, GamePhase::Waiting | GamePhase::Settlement) {
+        return Err(PokerTableError::HandAlreadyInProgress);
+    }
+    check_committee(&committee, threshold)?;
+
+    table.committee = committee.clone();
+    table.config.committee = committee.clone();
+    table.config.committee_threshold = threshold;
+    save_table(env, table);
+
+    CommitteeRotated {
+        table_id: table.id,
+        committee,
+        threshold,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// The Stellar Asset Contract for XLM on this network. Its address follows
+/// from the network and the asset, whether or not it has been deployed.
+fn native_token(env: &Env) -> Address {
+    env.deployer()
+        .with_stellar_asset(Bytes::from_array(env, &NATIVE_ASSET_XDR))
+        .deployed_address()
+}
+
+fn summarize(table: &TableState) -> TableSummary {
+    TableSummary {
+        table_id: table.id,
+        phase: table.phase.clone(),
+        player_count: game::seated_count(table),
+        max_players: table.config.max_players,
+        small_blind: table.config.small_blind,
+        big_blind: table.config.big_blind,
+        pot: table.pot,
+    }
+}
+
+fn public_view(env: &Env, table: &TableState) -> PublicTable {
+    let mut players = Vec::new(env);
+    for seat in table.players.iter() {
+        players.push_back(seat.map(|p| PublicPlayer {
+            address: p.address,
+            seat_index: p.seat_index,
+            stack: p.stack,
+            bet_this_round: p.bet_this_round,
+            folded: p.folded,
+            all_in: p.all_in,
+            sitting_out: p.sitting_out,
+        }));
+    }
+    PublicTable {
+        table_id: table.id,
+        phase: table.phase.clone(),
+        players,
+        dealer_seat: table.dealer_seat,
+        small_blind_seat: table.small_blind_seat,
+        big_blind_seat: table.big_blind_seat,
+        current_turn: table.current_turn,
+        pot: table.pot,
+        min_raise: table.min_raise,
+        side_pots: table.side_pots.clone(),
+        board_cards: table.board_cards.clone(),
+        hand_number: table.hand_number,
+        last_action_ledger: table.last_action_ledger,
+        small_blind: table.config.small_blind,
+        big_blind: table.config.big_blind,
+        min_buy_in: table.config.min_buy_in,
+        max_buy_in: table.config.max_buy_in,
+        chip_unit: table.config.chip_unit,
+        paused: table.paused,
+    }
+}
+
+/// SHA-256 of a proof, published with the deal, reveal and showdown events
+/// so a replay can match them to the proofs in the submitting transactions.
+fn proof_digest(env: &Env, proof: &Bytes) -> BytesN<32> {
+    env.crypto().sha256(proof).to_bytes()
+}
+
 #[contractimpl]
 impl PokerTableContract {
     /// Initialize a new poker table with configuration.
-    pub fn create_table(env: Env, admin: Address, config: TableConfig) -> u32 {
+    pub fn create_table(
+        env: Env,
+        admin: Address,
+        config: TableConfig,
+    ) -> Result<u32, PokerTableError> {
         admin.require_auth();
 
+        if config.small_blind <= 0 || config.big_blind < config.small_blind {
+            return Err(PokerTableError::InvalidBlinds);
+        }
+        // Blinds in whole chips keep the smallest bet, the big blind, one.
+        if config.chip_unit <= 0
+            || config.small_blind % config.chip_unit != 0
+            || config.big_blind % config.chip_unit != 0
+        {
+            return Err(PokerTableError::InvalidChipUnit);
+        }
+        if config.min_buy_in <= 0 || config.min_buy_in > config.max_buy_in {
+            return Err(PokerTableError::InvalidBuyInRange);
+        }
+        if !(2..=public_inputs::MAX_PLAYERS).contains(&config.max_players) {
+            return Err(PokerTableError::InvalidMaxPlayers);
+        }
+        if config.rake_bps.saturating_add(config.jackpot_bps) > 10_000 || config.rake_cap < 0 {
+            return Err(PokerTableError::InvalidRake);
+        }
+        check_committee(&config.committee, config.committee_threshold)?;
+
+        let mut players = Vec::new(&env);
+        for _ in 0..config.max_players {
+            players.push_back(None);
+        }
+
         let table_id = env
             .storage()
             .instance()
@@ -74,10 +475,13 @@ impl PokerTableContract {
             admin: admin.clone(),
             config: config.clone(),
             phase: GamePhase::Waiting,
-            players: Vec::new(&env),
+            players,
             dealer_seat: 0,
+            small_blind_seat: 0,
+            big_blind_seat: 0,
             current_turn: 0,
             pot: 0,
+            min_raise: config.big_blind,
             side_pots: Vec::new(&env),
             deck_root: BytesN::from_array(&env, &[0u8; 32]),
             hand_commitments: Vec::new(&env),
@@ -87,6 +491,11 @@ impl PokerTableContract {
             last_action_ledger: env.ledger().sequence(),
             committee: config.committee,
             session_id: 0,
+            proof_digests: Vec::new(&env),
+            paused: false,
+            straddle_seat: None,
+            pending_verifier: None,
+            cancel_votes: Vec::new(&env),
         };
 
         save_table(&env, &table);
@@ -94,10 +503,35 @@ impl PokerTableContract {
             .instance()
             .set(&Symbol::new(&env, "next_id"), &(table_id + 1));
 
-        env.events()
-            .publish((Symbol::new(&env, "table_created"), table_id), admin);
+        TableCreated {
+            table_id,
+            admin,
+            token: config.token,
+            small_blind: config.small_blind,
+            big_blind: config.big_blind,
+            min_buy_in: config.min_buy_in,
+            max_buy_in: config.max_buy_in,
+            max_players: config.max_players,
+        }
+        .publish(&env);
+
+        Ok(table_id)
+    }
+
+    /// Create a table with buy-ins in native XLM: `create_table` with
+    /// `config.token` set to this network's XLM asset contract.
+    pub fn create_native_table(
+        env: Env,
+        admin: Address,
+        mut config: TableConfig,
+    ) -> Result<u32, PokerTableError> {
+        config.token = native_token(&env);
+        Self::create_table(env, admin, config)
+    }
 
-        table_id
+    /// This network's XLM asset contract, the token of native tables.
+    pub fn get_native_token(env: Env) -> Address {
+        native_token(&env)
     }
 
     /// Join a table with a buy-in deposit.
@@ -108,53 +542,20 @@ impl PokerTableContract {
         buy_in: i128,
     ) -> Result<u32, PokerTableError> {
         player.require_auth();
+        join(&env, table_id, player, buy_in, false)
+    }
 
-        let mut table = load_table(&env, table_id)?;
-
-        if !matches!(table.phase, GamePhase::Waiting) {
-            return Err(PokerTableError::TableNotAcceptingPlayers);
-        }
-        if (table.players.len() as u32) >= table.config.max_players {
-            return Err(PokerTableError::TableFull);
-        }
-        if buy_in < table.config.min_buy_in || buy_in > table.config.max_buy_in {
-            return Err(PokerTableError::InvalidBuyIn);
-        }
-
-        // Check player not already seated.
-        for i in 0..table.players.len() {
-            let p = table
-                .players
-                .get(i)
-                .ok_or(PokerTableError::InvalidPlayerIndex)?;
-            if p.address == player {
-                return Err(PokerTableError::AlreadySeated);
-            }
-        }
-
-        // Transfer buy-in to contract.
-        let token = token::Client::new(&env, &table.config.token);
-        token.transfer(&player, &env.current_contract_address(), &buy_in);
-
-        let seat = table.players.len() as u32;
-        table.players.push_back(PlayerState {
-            address: player.clone(),
-            stack: buy_in,
-            bet_this_round: 0,
-            folded: false,
-            all_in: false,
-            sitting_out: false,
-            seat_index: seat,
-        });
-
-        save_table(&env, &table);
-
-        env.events().publish(
-            (Symbol::new(&env, "player_joined"), table_id),
-            (player, seat),
-        );
-
-        Ok(seat)
+    /// Join a table with a buy-in the contract pulls with `transfer_from`,
+    /// for wallets that approve the contract up front rather than authorize
+    /// a transfer inside the call. The player still authorizes the join.
+    pub fn join_table_with_allowance(
+        env: Env,
+        table_id: u32,
+        player: Address,
+        buy_in: i128,
+    ) -> Result<u32, PokerTableError> {
+        player.require_auth();
+        join(&env, table_id, player, buy_in, true)
     }
 
     /// Leave the table and withdraw remaining stack.
@@ -168,64 +569,141 @@ impl PokerTableContract {
             return Err(PokerTableError::CannotLeaveDuringActiveHand);
         }
 
-        let mut withdrawn: i128 = 0;
-        let mut found = false;
-        let mut new_players: Vec<PlayerState> = Vec::new(&env);
-
-        for i in 0..table.players.len() {
-            let p = table
-                .players
-                .get(i)
-                .ok_or(PokerTableError::InvalidPlayerIndex)?;
-            if p.address == player {
-                found = true;
-                withdrawn = p.stack;
-                if withdrawn > 0 {
-                    let token = token::Client::new(&env, &table.config.token);
-                    token.transfer(&env.current_contract_address(), &player, &withdrawn);
-                }
-            } else {
-                new_players.push_back(p);
-            }
+        // The seat is left empty, so nobody else's seat index changes.
+        let seat = table
+            .players
+            .iter()
+            .flatten()
+            .find(|p| p.address == player)
+            .map(|p| p.seat_index)
+            .ok_or(PokerTableError::PlayerNotAtTable)?;
+        let withdrawn = unseat(&env, &mut table, seat)?;
+
+        save_table(&env, &table);
+
+        PlayerLeft {
+            table_id,
+            player,
+            seat,
+            withdrawn,
         }
+        .publish(&env);
 
-        if !found {
-            return Err(PokerTableError::PlayerNotAtTable);
+        Ok(withdrawn)
+    }
+
+    /// Remove the player in `seat` once they have gone
+    /// `KICK_IDLE_AFTER_HANDS` hands without acting, whether timed out or
+    /// sitting out, and send them their stack. Anyone may call this between
+    /// hands. Returns the chips refunded.
+    pub fn kick_idle(env: Env, table_id: u32, seat: u32) -> Result<i128, PokerTableError> {
+        let mut table = load_table(&env, table_id)?;
+
+        if !matches!(table.phase, GamePhase::Waiting | GamePhase::Settlement) {
+            return Err(PokerTableError::HandAlreadyInProgress);
         }
-        table.players = new_players;
+        let player = table
+            .players
+            .get(seat)
+            .flatten()
+            .ok_or(PokerTableError::PlayerNotAtTable)?;
+        if table.hand_number < player.last_active_hand + KICK_IDLE_AFTER_HANDS {
+            return Err(PokerTableError::PlayerNotIdle);
+        }
+        let refunded = unseat(&env, &mut table, seat)?;
 
         save_table(&env, &table);
 
-        env.events().publish(
-            (Symbol::new(&env, "player_left"), table_id),
-            (player, withdrawn),
-        );
+        PlayerKicked {
+            table_id,
+            player: player.address,
+            seat,
+            refunded,
+        }
+        .publish(&env);
 
-        Ok(withdrawn)
+        Ok(refunded)
+    }
+
+    /// Sit out from the next hand on: no blinds, no cards and no turns until
+    /// `sit_in`. The player keeps their seat and stack.
+    pub fn sit_out(env: Env, table_id: u32, player: Address) -> Result<(), PokerTableError> {
+        player.require_auth();
+        set_sitting_out(&env, table_id, player, true)
+    }
+
+    /// Be dealt in again from the next hand on.
+    pub fn sit_in(env: Env, table_id: u32, player: Address) -> Result<(), PokerTableError> {
+        player.require_auth();
+        set_sitting_out(&env, table_id, player, false)
+    }
+
+    /// Choose whether to show or muck a losing hand at showdown. A hand that
+    /// wins a pot someone else contested is always shown. Cannot be changed
+    /// once betting has closed, as the showdown proof is made for it.
+    pub fn set_muck_preference(
+        env: Env,
+        table_id: u32,
+        player: Address,
+        preference: MuckPreference,
+    ) -> Result<(), PokerTableError> {
+        player.require_auth();
+
+        let mut table = load_table(&env, table_id)?;
+        if matches!(
+            table.phase,
+            GamePhase::RunOut | GamePhase::Showdown | GamePhase::Dispute
+        ) {
+            return Err(PokerTableError::HandAlreadyInProgress);
+        }
+        let mut p = table
+            .players
+            .iter()
+            .flatten()
+            .find(|p| p.address == player)
+            .ok_or(PokerTableError::PlayerNotAtTable)?;
+        p.muck_preference = preference;
+        table.players.set(p.seat_index, Some(p));
+
+        save_table(&env, &table);
+        Ok(())
     }
 
     /// Start a new hand. Called after enough players are seated.
     pub fn start_hand(env: Env, table_id: u32) -> Result<(), PokerTableError> {
         let mut table = load_table(&env, table_id)?;
+        ensure_not_paused(&table)?;
 
         if !matches!(table.phase, GamePhase::Waiting | GamePhase::Settlement) {
             return Err(PokerTableError::HandAlreadyInProgress);
         }
-        if table.players.len() < 2 {
+        if game::seated_count(&table) < 2 {
             return Err(PokerTableError::NeedAtLeastTwoPlayers);
         }
 
+        // A scheduled verifier only ever takes over between hands.
+        if let Some(pending) = table.pending_verifier.clone() {
+            if env.ledger().sequence() >= pending.effective_ledger {
+                table.config.verifier = pending.verifier.clone();
+                table.pending_verifier = None;
+                VerifierRotated {
+                    table_id,
+                    verifier: pending.verifier,
+                }
+                .publish(&env);
+            }
+        }
+
         game::start_new_hand(&env, &mut table)?;
 
-        // Notify game hub: start_game with first 2 players.
-        let p1 = table
-            .players
-            .get(0)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        let p2 = table
-            .players
-            .get(1)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+        // Notify game hub: start_game with every seated player and their
+        // stack, and the blinds and buy-ins they play at.
+        let mut players = Vec::new(&env);
+        let mut stakes = Vec::new(&env);
+        for p in table.players.iter().flatten() {
+            players.push_back(p.address);
+            stakes.push_back(p.hand_start_stack);
+        }
 
         table.session_id = derive_session_id(table.id, table.hand_number);
         game_hub::notify_start(
@@ -233,23 +711,104 @@ impl PokerTableContract {
             &table.config.game_hub,
             &env.current_contract_address(),
             table.session_id,
-            &p1.address,
-            &p2.address,
-            p1.stack,
-            p2.stack,
+            &players,
+            &stakes,
+            &game_hub::TableStakes {
+                small_blind: table.config.small_blind,
+                big_blind: table.config.big_blind,
+                min_buy_in: table.config.min_buy_in,
+                max_buy_in: table.config.max_buy_in,
+            },
         );
 
         save_table(&env, &table);
 
-        env.events().publish(
-            (Symbol::new(&env, "hand_started"), table_id),
-            table.hand_number,
-        );
+        let mut seats = Vec::new(&env);
+        for p in table.players.iter().flatten() {
+            seats.push_back((p.seat_index, p.address, p.hand_start_stack));
+        }
+        HandStarted {
+            table_id,
+            hand_number: table.hand_number,
+            dealer_seat: table.dealer_seat,
+            small_blind_seat: table.small_blind_seat,
+            big_blind_seat: table.big_blind_seat,
+            small_blind: table.config.small_blind,
+            big_blind: table.config.big_blind,
+            seats,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Straddle: after `start_hand` and before the deal, the player first to
+    /// the left of the big blind may post twice the big blind. Preflop
+    /// betting then starts to their left, with the straddle as the bet to
+    /// call and the smallest raise.
+    pub fn post_straddle(env: Env, table_id: u32, player: Address) -> Result<(), PokerTableError> {
+        player.require_auth();
+
+        let mut table = load_table(&env, table_id)?;
+        ensure_not_paused(&table)?;
+
+        if !matches!(table.phase, GamePhase::Dealing) {
+            return Err(PokerTableError::NotInDealingPhase);
+        }
+        let (seat, amount) = game::post_straddle(&mut table, &player)?;
+
+        save_table(&env, &table);
+
+        StraddlePosted {
+            table_id,
+            hand_number: table.hand_number,
+            player,
+            seat,
+            amount,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Vote to call off the hand in progress. Once every player who has not
+    /// folded has voted, the hand is cancelled and all bets are refunded,
+    /// as when the committee times out; this spares the table waiting out a
+    /// committee whose proofs the verifier keeps rejecting.
+    pub fn vote_cancel_hand(
+        env: Env,
+        table_id: u32,
+        player: Address,
+    ) -> Result<(), PokerTableError> {
+        player.require_auth();
+
+        let mut table = load_table(&env, table_id)?;
+        ensure_not_paused(&table)?;
+
+        if matches!(
+            table.phase,
+            GamePhase::Waiting | GamePhase::Settlement | GamePhase::Dispute
+        ) {
+            return Err(PokerTableError::NoHandInProgress);
+        }
+        let (seat, unanimous) = game::vote_cancel(&mut table, &player)?;
+
+        CancelVoted {
+            table_id,
+            hand_number: table.hand_number,
+            player,
+            seat,
+        }
+        .publish(&env);
+        if unanimous {
+            game::cancel_hand(&env, &mut table)?;
+        }
 
+        save_table(&env, &table);
         Ok(())
     }
 
-    /// Committee submits deal commitment and proof.
+    /// Committee submits deal commitment and proof. Any member of the
+    /// table's committee may submit.
     pub fn commit_deal(
         env: Env,
         table_id: u32,
@@ -263,17 +822,30 @@ impl PokerTableContract {
         committee.require_auth();
 
         let mut table = load_table(&env, table_id)?;
+        ensure_not_paused(&table)?;
 
         if !matches!(table.phase, GamePhase::Dealing) {
             return Err(PokerTableError::NotInDealingPhase);
         }
-        if committee != table.committee {
+        if !table.committee.contains(&committee) {
             return Err(PokerTableError::NotAuthorizedCommittee);
         }
-        if hand_commitments.len() != table.players.len() {
+        if hand_commitments.len() != game::seated_count(&table) {
             return Err(PokerTableError::WrongCommitmentCount);
         }
 
+        // The arguments must be what the proof commits to.
+        let proven = public_inputs::parse_deal(&env, &public_inputs)?;
+        if proven.hand_nonce != table.session_id {
+            return Err(PokerTableError::ProofReplay);
+        }
+        if proven.deck_root != deck_root
+            || proven.hand_commitments != hand_commitments
+            || proven.dealt_indices != dealt_indices
+        {
+            return Err(PokerTableError::PublicInputMismatch);
+        }
+
         // Verify deal proof via ZK verifier contract.
         let verifier_client = verifier::ZkVerifierClient::new(&env, &table.config.verifier);
         if !verifier_client.verify_deal(&proof, &public_inputs, &deck_root, &hand_commitments) {
@@ -283,22 +855,31 @@ impl PokerTableContract {
         table.deck_root = deck_root;
         table.hand_commitments = hand_commitments;
         table.dealt_indices = dealt_indices;
+        table.proof_digests.push_back(proof_digest(&env, &proof));
         table.phase = GamePhase::Preflop;
         table.last_action_ledger = env.ledger().sequence();
 
-        // Set first player to act (left of big blind).
-        let num_players = table.players.len() as u32;
-        if num_players < 2 {
+        // Set first player to act (left of the big blind, or of the
+        // straddle), skipping anyone the blinds put all-in or who is sitting
+        // out.
+        let num_seats = table.players.len();
+        if num_seats < 2 {
             return Err(PokerTableError::NeedAtLeastTwoPlayers);
         }
-        table.current_turn = (table.dealer_seat + 3) % num_players;
+        let last_blind = table.straddle_seat.unwrap_or(table.big_blind_seat);
+        let first_seat = (last_blind + 1) % num_seats;
+        betting::open_round(&env, &mut table, first_seat)?;
 
         save_table(&env, &table);
 
-        env.events().publish(
-            (Symbol::new(&env, "deal_committed"), table_id),
-            table.hand_number,
-        );
+        DealCommitted {
+            table_id,
+            hand_number: table.hand_number,
+            deck_root: table.deck_root.clone(),
+            hand_commitments: table.hand_commitments.clone(),
+            proof_digest: proof_digest(&env, &proof),
+        }
+        .publish(&env);
 
         Ok(())
     }
@@ -311,22 +892,90 @@ impl PokerTableContract {
         action: Action,
     ) -> Result<(), PokerTableError> {
         player.require_auth();
+        act(&env, table_id, &player, &action)
+    }
 
-        let mut table = load_table(&env, table_id)?;
+    /// Submit `player`'s betting action signed by `session_key`, the key
+    /// they registered with `register_session_key`, while it is live.
+    pub fn session_action(
+        env: Env,
+        table_id: u32,
+        player: Address,
+        session_key: Address,
+        action: Action,
+    ) -> Result<(), PokerTableError> {
+        session_key.require_auth();
+        session_key_for(&env, table_id, &player)
+            .filter(|live| live.key == session_key)
+            .ok_or(PokerTableError::NoSessionKey)?;
+        act(&env, table_id, &player, &action)
+    }
 
-        if !matches!(
-            table.phase,
-            GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River
-        ) {
-            return Err(PokerTableError::NotInBettingPhase);
+    /// Let `session_key` sign `player`'s betting actions at the table for
+    /// the next `ledgers` ledgers, at most `MAX_SESSION_LEDGERS`, replacing
+    /// any key they registered before. Returns the ledger it expires at.
+    pub fn register_session_key(
+        env: Env,
+        table_id: u32,
+        player: Address,
+        session_key: Address,
+        ledgers: u32,
+    ) -> Result<u32, PokerTableError> {
+        player.require_auth();
+
+        let table = load_table(&env, table_id)?;
+        if !table.players.iter().flatten().any(|p| p.address == player) {
+            return Err(PokerTableError::PlayerNotAtTable);
+        }
+        if ledgers == 0 || ledgers > MAX_SESSION_LEDGERS {
+            return Err(PokerTableError::InvalidSessionDuration);
         }
+        let expires_ledger = env.ledger().sequence() + ledgers;
+        set_session_key(
+            &env,
+            table_id,
+            &player,
+            &SessionKey {
+                key: session_key.clone(),
+                expires_ledger,
+            },
+        );
 
-        betting::process_action(&env, &mut table, &player, &action)?;
+        SessionKeyRegistered {
+            table_id,
+            player,
+            key: session_key,
+            expires_ledger,
+        }
+        .publish(&env);
+        Ok(expires_ledger)
+    }
 
-        save_table(&env, &table);
+    /// Stop `player`'s session key acting for them before it expires.
+    pub fn revoke_session_key(
+        env: Env,
+        table_id: u32,
+        player: Address,
+    ) -> Result<(), PokerTableError> {
+        player.require_auth();
+        load_table(&env, table_id)?;
+        session_key(&env, table_id, &player).ok_or(PokerTableError::NoSessionKey)?;
+        remove_session_key(&env, table_id, &player);
+
+        SessionKeyRevoked { table_id, player }.publish(&env);
         Ok(())
     }
 
+    /// `player`'s live session key at the table (view function).
+    pub fn get_session_key(
+        env: Env,
+        table_id: u32,
+        player: Address,
+    ) -> Result<SessionKey, PokerTableError> {
+        load_table(&env, table_id)?;
+        session_key_for(&env, table_id, &player).ok_or(PokerTableError::NoSessionKey)
+    }
+
     /// Committee reveals board cards (flop/turn/river) with proof.
     pub fn reveal_board(
         env: Env,
@@ -340,8 +989,9 @@ impl PokerTableContract {
         committee.require_auth();
 
         let mut table = load_table(&env, table_id)?;
+        ensure_not_paused(&table)?;
 
-        if committee != table.committee {
+        if !table.committee.contains(&committee) {
             return Err(PokerTableError::NotAuthorizedCommittee);
         }
 
@@ -349,6 +999,9 @@ impl PokerTableContract {
             GamePhase::DealingFlop => 3,
             GamePhase::DealingTurn => 1,
             GamePhase::DealingRiver => 1,
+            // A run-out reveals the streets still missing one at a time.
+            GamePhase::RunOut if table.board_cards.is_empty() => 3,
+            GamePhase::RunOut => 1,
             _ => return Err(PokerTableError::NotInRevealPhase),
         };
 
@@ -356,6 +1009,16 @@ impl PokerTableContract {
             return Err(PokerTableError::WrongCardCount);
         }
 
+        // The arguments must be what the proof reveals, from this hand's deck.
+        let proven = public_inputs::parse_reveal(&env, &public_inputs)?;
+        if proven.hand_nonce != table.session_id {
+            return Err(PokerTableError::ProofReplay);
+        }
+        if proven.deck_root != table.deck_root || proven.cards != cards || proven.indices != indices
+        {
+            return Err(PokerTableError::PublicInputMismatch);
+        }
+
         // Verify reveal proof via zk-verifier.
         let verifier_client = verifier::ZkVerifierClient::new(&env, &table.config.verifier);
         if !verifier_client.verify_reveal(
@@ -368,72 +1031,129 @@ impl PokerTableContract {
             return Err(PokerTableError::RevealProofVerificationFailed);
         }
 
-        // Add revealed cards to board.
+        // Add revealed cards to board. No deck position comes out twice.
         for i in 0..cards.len() {
+            let index = indices.get(i).ok_or(PokerTableError::WrongCardCount)?;
+            if table.dealt_indices.contains(index) {
+                return Err(PokerTableError::CardAlreadyDealt);
+            }
             table
                 .board_cards
                 .push_back(cards.get(i).ok_or(PokerTableError::WrongCardCount)?);
-            table
-                .dealt_indices
-                .push_back(indices.get(i).ok_or(PokerTableError::WrongCardCount)?);
+            table.dealt_indices.push_back(index);
+        }
+        table.proof_digests.push_back(proof_digest(&env, &proof));
+
+        if table.phase == GamePhase::RunOut {
+            betting::run_out_revealed(&env, &mut table);
+        } else {
+            // Transition to next betting phase.
+            table.phase = match table.phase {
+                GamePhase::DealingFlop => GamePhase::Flop,
+                GamePhase::DealingTurn => GamePhase::Turn,
+                GamePhase::DealingRiver => GamePhase::River,
+                _ => return Err(PokerTableError::NotInRevealPhase),
+            };
+            table.last_action_ledger = env.ledger().sequence();
+
+            // Reset betting state for new round.
+            betting::reset_round(&env, &mut table)?;
         }
-
-        // Transition to next betting phase.
-        table.phase = match table.phase {
-            GamePhase::DealingFlop => GamePhase::Flop,
-            GamePhase::DealingTurn => GamePhase::Turn,
-            GamePhase::DealingRiver => GamePhase::River,
-            _ => return Err(PokerTableError::NotInRevealPhase),
-        };
-        table.last_action_ledger = env.ledger().sequence();
-
-        // Reset betting state for new round.
-        betting::reset_round(&env, &mut table)?;
 
         save_table(&env, &table);
 
-        env.events()
-            .publish((Symbol::new(&env, "board_revealed"), table_id), cards);
+        BoardRevealed {
+            table_id,
+            hand_number: table.hand_number,
+            phase: table.phase.clone(),
+            cards,
+            indices,
+            proof_digest: proof_digest(&env, &proof),
+        }
+        .publish(&env);
 
         Ok(())
     }
 
     /// Submit showdown: reveal hole cards, verify winner, settle.
+    ///
+    /// `hole_cards` holds a pair for every player dealt in, in seat order, as
+    /// the showdown proof outputs them: `public_inputs::HIDDEN_CARD` twice for
+    /// a player who folded or mucked.
     pub fn submit_showdown(
         env: Env,
         table_id: u32,
         committee: Address,
         hole_cards: Vec<(u32, u32)>,
-        _salts: Vec<(BytesN<32>, BytesN<32>)>,
         proof: Bytes,
         public_inputs: Bytes,
     ) -> Result<(), PokerTableError> {
         committee.require_auth();
 
         let mut table = load_table(&env, table_id)?;
+        ensure_not_paused(&table)?;
 
         if !matches!(table.phase, GamePhase::Showdown) {
             return Err(PokerTableError::NotInShowdownPhase);
         }
-        if committee != table.committee {
+        if !table.committee.contains(&committee) {
             return Err(PokerTableError::NotAuthorizedCommittee);
         }
 
+        // The proof must be for this hand's deal and board, and the hole
+        // cards it opens from the hand commitments are the ones settled on.
+        let proven = public_inputs::parse_showdown(&env, &public_inputs)?;
+        if proven.hand_nonce != table.session_id {
+            return Err(PokerTableError::ProofReplay);
+        }
+        let board_start = 2 * table.hand_commitments.len();
+        if proven.deck_root != table.deck_root
+            || proven.hand_commitments != table.hand_commitments
+            || proven.board_indices != table.dealt_indices.slice(board_start..)
+            || proven.hole_cards != hole_cards
+        {
+            return Err(PokerTableError::PublicInputMismatch);
+        }
+        // And for who is still in, who mucks and what each put in, which
+        // decide whose cards it shows.
+        for (deal_idx, p) in table.players.iter().flatten().enumerate() {
+            let deal_idx = deal_idx as u32;
+            if proven.contending.get(deal_idx) != Some(!p.folded)
+                || proven.muck_losing.get(deal_idx)
+                    != Some(p.muck_preference == MuckPreference::Muck)
+                || proven.contributions.get(deal_idx) != Some(p.hand_start_stack - p.stack)
+            {
+                return Err(PokerTableError::PublicInputMismatch);
+            }
+        }
+
         // Verify showdown proof via zk-verifier.
         let verifier_client = verifier::ZkVerifierClient::new(&env, &table.config.verifier);
-        // winner_index = 0 placeholder; the proof itself encodes the winner.
         if !verifier_client.verify_showdown(
             &proof,
             &public_inputs,
             &table.hand_commitments,
             &table.board_cards,
-            &0u32,
+            &proven.winner,
         ) {
             return Err(PokerTableError::ShowdownProofVerificationFailed);
         }
 
+        let mut shown = Vec::new(&env);
+        for (c1, c2) in hole_cards.iter() {
+            shown.push_back((c1 != public_inputs::HIDDEN_CARD).then_some((c1, c2)));
+        }
+        ShowdownRevealed {
+            table_id,
+            hand_number: table.hand_number,
+            hole_cards: shown,
+            proof_digest: proof_digest(&env, &proof),
+        }
+        .publish(&env);
+        table.proof_digests.push_back(proof_digest(&env, &proof));
+
         // Evaluate hands and determine winner.
-        game::settle_showdown(&env, &mut table, &hole_cards)?;
+        game::settle_showdown(&env, &mut table, &hole_cards, proven.winner)?;
 
         save_table(&env, &table);
         Ok(())
@@ -444,6 +1164,7 @@ impl PokerTableContract {
         claimer.require_auth();
 
         let mut table = load_table(&env, table_id)?;
+        ensure_not_paused(&table)?;
 
         timeout::process_timeout(&env, &mut table, &claimer)?;
 
@@ -451,11 +1172,80 @@ impl PokerTableContract {
         Ok(())
     }
 
+    /// Read the record of a settled hand (view function). Records expire
+    /// like tables do unless read.
+    pub fn get_hand_history(
+        env: Env,
+        table_id: u32,
+        hand_number: u32,
+    ) -> Result<HandRecord, PokerTableError> {
+        let key = DataKey::HandHistory(table_id, hand_number);
+        let record = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(PokerTableError::HandNotFound)?;
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+        Ok(record)
+    }
+
+    /// Read how the table's last settled hand was paid out (view function):
+    /// each player's winnings from each pot, the rake, and whether a fold,
+    /// the showdown proof or a committee timeout settled it. Cancelled hands
+    /// are not settlements and leave it unchanged.
+    pub fn get_last_settlement(env: Env, table_id: u32) -> Result<Settlement, PokerTableError> {
+        let key = DataKey::LastSettlement(table_id);
+        let settlement = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(PokerTableError::HandNotFound)?;
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, TABLE_TTL_THRESHOLD, TABLE_TTL_EXTEND);
+        Ok(settlement)
+    }
+
     /// Read current table state (view function).
     pub fn get_table(env: Env, table_id: u32) -> Result<TableState, PokerTableError> {
         load_table(&env, table_id)
     }
 
+    /// The table as a spectator may see it (view function). Frontends show
+    /// this rather than `get_table`, which carries the deal's commitments and
+    /// the committee's data.
+    pub fn get_public_table(env: Env, table_id: u32) -> Result<PublicTable, PokerTableError> {
+        Ok(public_view(&env, &load_table(&env, table_id)?))
+    }
+
+    /// A table's lobby listing (view function).
+    pub fn get_table_summary(env: Env, table_id: u32) -> Result<TableSummary, PokerTableError> {
+        Ok(summarize(&load_table(&env, table_id)?))
+    }
+
+    /// Lobby listings of the tables with IDs from `start`, at most `limit`
+    /// (capped at 50) of them (view function). Closed tables are skipped, so a
+    /// page can come back short; the next page starts after the last ID
+    /// asked for, not the last one returned.
+    pub fn get_table_page(env: Env, start: u32, limit: u32) -> Vec<TableSummary> {
+        let next_id = env
+            .storage()
+            .instance()
+            .get::<Symbol, u32>(&Symbol::new(&env, "next_id"))
+            .unwrap_or(0);
+        let end = start.saturating_add(limit.min(MAX_TABLE_PAGE)).min(next_id);
+
+        let mut page = Vec::new(&env);
+        for table_id in start..end {
+            if let Ok(table) = load_table(&env, table_id) {
+                page.push_back(summarize(&table));
+            }
+        }
+        page
+    }
+
     // ========================================================================
     // Admin Functions (Stellar Game Studio pattern)
     // ========================================================================
@@ -473,20 +1263,232 @@ impl PokerTableContract {
     }
 
     /// Update the Game Hub address for a table (admin only).
-    pub fn set_hub(
+    pub fn set_hub(env: Env, table_id: u32, new_hub: Address) -> Result<(), PokerTableError> {
+        let mut table = load_table(&env, table_id)?;
+        table.admin.require_auth();
+        table.config.game_hub = new_hub;
+        save_table(&env, &table);
+        Ok(())
+    }
+
+    /// Schedule `new_verifier` to replace the table's verifier (admin only).
+    /// It takes over at the first hand started `VERIFIER_TIMELOCK_LEDGERS`
+    /// from now, giving players time to check it or leave; scheduling again
+    /// replaces the pending one and restarts the clock.
+    pub fn schedule_verifier(
         env: Env,
         table_id: u32,
-        new_hub: Address,
+        new_verifier: Address,
     ) -> Result<(), PokerTableError> {
         let mut table = load_table(&env, table_id)?;
         table.admin.require_auth();
-        table.config.game_hub = new_hub;
+        let effective_ledger = env.ledger().sequence() + VERIFIER_TIMELOCK_LEDGERS;
+        table.pending_verifier = Some(PendingVerifier {
+            verifier: new_verifier.clone(),
+            effective_ledger,
+        });
+        save_table(&env, &table);
+        VerifierScheduled {
+            table_id,
+            verifier: new_verifier,
+            effective_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Drop a verifier scheduled with `schedule_verifier` (admin only).
+    pub fn cancel_verifier(env: Env, table_id: u32) -> Result<(), PokerTableError> {
+        let mut table = load_table(&env, table_id)?;
+        table.admin.require_auth();
+        table.pending_verifier = None;
+        save_table(&env, &table);
+        Ok(())
+    }
+
+    /// Replace the table's committee (admin only), between hands.
+    pub fn set_committee(
+        env: Env,
+        table_id: u32,
+        new_committee: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), PokerTableError> {
+        let mut table = load_table(&env, table_id)?;
+        table.admin.require_auth();
+        rotate_committee(&env, &mut table, new_committee, threshold)
+    }
+
+    /// Hand the table to the committee registry's current epoch, between
+    /// hands. Anyone can call this, so tables follow epoch changes without
+    /// their admin.
+    pub fn sync_committee(env: Env, table_id: u32) -> Result<(), PokerTableError> {
+        let mut table = load_table(&env, table_id)?;
+        let epoch = registry::current_epoch(&env, &table.config.committee_registry)
+            .ok_or(PokerTableError::NoCommitteeEpoch)?;
+        rotate_committee(&env, &mut table, epoch.members, epoch.threshold)
+    }
+
+    /// Rake the table has collected and not yet paid out.
+    pub fn get_rake_balance(env: Env, table_id: u32) -> Result<i128, PokerTableError> {
+        load_table(&env, table_id)?;
+        Ok(rake_balance(&env, table_id))
+    }
+
+    /// Pay the table's collected rake out to `to` (admin only). Returns the
+    /// amount paid.
+    pub fn withdraw_rake(env: Env, table_id: u32, to: Address) -> Result<i128, PokerTableError> {
+        let table = load_table(&env, table_id)?;
+        table.admin.require_auth();
+
+        let amount = rake_balance(&env, table_id);
+        if amount > 0 {
+            set_rake_balance(&env, table_id, 0);
+            let token = token::Client::new(&env, &table.config.token);
+            token.transfer(&env.current_contract_address(), &to, &amount);
+        }
+
+        RakeWithdrawn {
+            table_id,
+            to,
+            amount,
+        }
+        .publish(&env);
+        Ok(amount)
+    }
+
+    /// The table's bad-beat jackpot (view function).
+    pub fn get_jackpot(env: Env, table_id: u32) -> Result<i128, PokerTableError> {
+        load_table(&env, table_id)?;
+        Ok(jackpot_balance(&env, table_id))
+    }
+
+    /// Jackpot awarded to `player` and not yet claimed (view function).
+    pub fn get_jackpot_claim(
+        env: Env,
+        table_id: u32,
+        player: Address,
+    ) -> Result<i128, PokerTableError> {
+        load_table(&env, table_id)?;
+        Ok(jackpot_claims(&env, table_id).get(player).unwrap_or(0))
+    }
+
+    /// Pay `player` the bad-beat jackpot awarded to them, whether or not
+    /// they are still seated. Returns the amount paid.
+    pub fn claim_jackpot(
+        env: Env,
+        table_id: u32,
+        player: Address,
+    ) -> Result<i128, PokerTableError> {
+        player.require_auth();
+        let table = load_table(&env, table_id)?;
+
+        let mut claims = jackpot_claims(&env, table_id);
+        let amount = claims
+            .get(player.clone())
+            .ok_or(PokerTableError::NothingToClaim)?;
+        claims.remove(player.clone());
+        set_jackpot_claims(&env, table_id, &claims);
+        let token = token::Client::new(&env, &table.config.token);
+        token.transfer(&env.current_contract_address(), &player, &amount);
+
+        JackpotClaimed {
+            table_id,
+            player,
+            amount,
+        }
+        .publish(&env);
+        Ok(amount)
+    }
+
+    /// Stop play at the table (admin only), e.g. while a verifier or
+    /// committee bug is looked into. Nobody can join, start a hand, act,
+    /// prove or claim a timeout until `resume_table`; players can still
+    /// leave between hands.
+    pub fn pause_table(env: Env, table_id: u32) -> Result<(), PokerTableError> {
+        let mut table = load_table(&env, table_id)?;
+        table.admin.require_auth();
+        table.paused = true;
         save_table(&env, &table);
+        TablePaused { table_id }.publish(&env);
         Ok(())
     }
 
+    /// Let play go on after `pause_table` (admin only). Whoever is to act
+    /// gets a full timeout from now.
+    pub fn resume_table(env: Env, table_id: u32) -> Result<(), PokerTableError> {
+        let mut table = load_table(&env, table_id)?;
+        table.admin.require_auth();
+        table.paused = false;
+        table.last_action_ledger = env.ledger().sequence();
+        save_table(&env, &table);
+        TableResumed { table_id }.publish(&env);
+        Ok(())
+    }
+
+    /// Shut the table down for good (admin only). Only between hands: every
+    /// seated player gets their stack back and every unclaimed jackpot award
+    /// is paid, uncollected rake and the jackpot go to the admin and the
+    /// table is deleted. Returns what the players were paid.
+    pub fn close_table(env: Env, table_id: u32) -> Result<i128, PokerTableError> {
+        let table = load_table(&env, table_id)?;
+        table.admin.require_auth();
+
+        if !matches!(table.phase, GamePhase::Waiting | GamePhase::Settlement) {
+            return Err(PokerTableError::HandAlreadyInProgress);
+        }
+
+        let token = token::Client::new(&env, &table.config.token);
+        let mut refunded = 0;
+        for p in table.players.iter().flatten() {
+            if p.stack > 0 {
+                token.transfer(&env.current_contract_address(), &p.address, &p.stack);
+                refunded += p.stack;
+            }
+            remove_session_key(&env, table_id, &p.address);
+        }
+        for (player, amount) in jackpot_claims(&env, table_id).iter() {
+            token.transfer(&env.current_contract_address(), &player, &amount);
+            refunded += amount;
+        }
+        let rake = rake_balance(&env, table_id);
+        if rake > 0 {
+            token.transfer(&env.current_contract_address(), &table.admin, &rake);
+        }
+        let jackpot = jackpot_balance(&env, table_id);
+        if jackpot > 0 {
+            token.transfer(&env.current_contract_address(), &table.admin, &jackpot);
+        }
+
+        env.storage().persistent().remove(&DataKey::Table(table_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::RakeBalance(table_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LastSettlement(table_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Jackpot(table_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::JackpotClaims(table_id));
+
+        TableClosed {
+            table_id,
+            refunded,
+            rake,
+            jackpot,
+        }
+        .publish(&env);
+        Ok(refunded)
+    }
+
     /// Upgrade the contract WASM (admin only).
-    pub fn upgrade(env: Env, table_id: u32, new_wasm_hash: BytesN<32>) -> Result<(), PokerTableError> {
+    pub fn upgrade(
+        env: Env,
+        table_id: u32,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), PokerTableError> {
         let table = load_table(&env, table_id)?;
         table.admin.require_auth();
         env.deployer().update_current_contract_wasm(new_wasm_hash);
@@ -2,120 +2,115 @@ use soroban_sdk::{Env, Vec};
 
 use crate::types::*;
 
-/// Calculate side pots when players are all-in with different amounts.
-/// This is simplified for v1 — handles the common case of one main pot
-/// and one side pot.
-#[allow(dead_code)]
-pub fn calculate_side_pots(env: &Env, table: &TableState) -> Result<Vec<SidePot>, PokerTableError> {
-    let mut pots: Vec<SidePot> = Vec::new(env);
+/// What the player has put into the pot this hand, across every street.
+fn contributed(player: &PlayerState) -> i128 {
+    player.hand_start_stack - player.stack
+}
 
-    // Collect all-in amounts and sort
-    let mut all_in_levels: Vec<i128> = Vec::new(env);
-    for i in 0..table.players.len() {
-        let p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        if p.all_in && p.bet_this_round > 0 {
-            // Insert sorted
-            let mut inserted = false;
-            for j in 0..all_in_levels.len() {
-                let level = all_in_levels
-                    .get(j)
-                    .ok_or(PokerTableError::InvalidPlayerIndex)?;
-                if p.bet_this_round <= level {
-                    if p.bet_this_round < level {
-                        all_in_levels.insert(j, p.bet_this_round);
-                    }
-                    inserted = true;
-                    break;
-                }
+/// Split the pot into a main pot and side pots when players are all-in for
+/// different amounts.
+///
+/// Each pot takes every player's chips up to one contribution level of the
+/// players still in the hand, and only players who reached that level are
+/// eligible for it. Folded players' chips stay in the pots they reached but
+/// make them eligible for none; anything they put in above the highest level
+/// goes to the last pot. The pots add up to `table.pot`.
+pub fn calculate_side_pots(env: &Env, table: &TableState) -> Result<Vec<SidePot>, PokerTableError> {
+    // Contribution levels of the players still in the hand, ascending.
+    let mut levels: Vec<i128> = Vec::new(env);
+    for p in table.players.iter().flatten() {
+        if p.folded {
+            continue;
+        }
+        let level = contributed(&p);
+        let mut at = levels.len();
+        let mut seen = false;
+        for j in 0..levels.len() {
+            let existing = levels.get(j).ok_or(PokerTableError::InvalidPlayerIndex)?;
+            if level == existing {
+                seen = true;
+                break;
             }
-            if !inserted {
-                all_in_levels.push_back(p.bet_this_round);
+            if level < existing {
+                at = j;
+                break;
             }
         }
-    }
-
-    if all_in_levels.is_empty() {
-        // No side pots needed — single main pot
-        let mut eligible = Vec::new(env);
-        for i in 0..table.players.len() {
-            let p = table
-                .players
-                .get(i)
-                .ok_or(PokerTableError::InvalidPlayerIndex)?;
-            if !p.folded {
-                eligible.push_back(p.seat_index);
-            }
+        if !seen {
+            levels.insert(at, level);
         }
-        pots.push_back(SidePot {
-            amount: table.pot,
-            eligible_players: eligible,
-        });
-        return Ok(pots);
     }
 
-    // Build pots at each all-in level
+    let mut pots: Vec<SidePot> = Vec::new(env);
     let mut prev_level: i128 = 0;
-    for lvl_idx in 0..all_in_levels.len() {
-        let level = all_in_levels
-            .get(lvl_idx)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        let _increment = level - prev_level;
-        let mut pot_amount: i128 = 0;
+    let mut assigned: i128 = 0;
+    for level in levels.iter() {
+        let mut amount: i128 = 0;
         let mut eligible = Vec::new(env);
-
-        for i in 0..table.players.len() {
-            let p = table
-                .players
-                .get(i)
-                .ok_or(PokerTableError::InvalidPlayerIndex)?;
-            if p.folded {
-                continue;
-            }
-            let contributed = core::cmp::min(p.bet_this_round, level)
-                - core::cmp::min(p.bet_this_round, prev_level);
-            pot_amount += contributed;
-            if p.bet_this_round >= level {
+        for p in table.players.iter().flatten() {
+            let put_in = contributed(&p);
+            amount += core::cmp::min(put_in, level) - core::cmp::min(put_in, prev_level);
+            if !p.folded && put_in >= level {
                 eligible.push_back(p.seat_index);
             }
         }
-
-        if pot_amount > 0 {
+        if amount > 0 {
             pots.push_back(SidePot {
-                amount: pot_amount,
+                amount,
                 eligible_players: eligible,
             });
+            assigned += amount;
         }
         prev_level = level;
     }
 
-    // Remaining pot for players who bet more than highest all-in
-    let max_level = all_in_levels
-        .get(all_in_levels.len() - 1)
-        .ok_or(PokerTableError::InvalidPlayerIndex)?;
-    let mut remaining: i128 = 0;
-    let mut eligible = Vec::new(env);
-    for i in 0..table.players.len() {
-        let p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-        if p.folded {
-            continue;
-        }
-        if p.bet_this_round > max_level {
-            remaining += p.bet_this_round - max_level;
-            eligible.push_back(p.seat_index);
-        }
-    }
+    let remaining = table.pot - assigned;
     if remaining > 0 {
-        pots.push_back(SidePot {
-            amount: remaining,
-            eligible_players: eligible,
-        });
+        let last = pots
+            .len()
+            .checked_sub(1)
+            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+        let mut pot = pots.get(last).ok_or(PokerTableError::InvalidPlayerIndex)?;
+        pot.amount += remaining;
+        pots.set(last, pot);
     }
 
     Ok(pots)
 }
+
+/// The table's cut of a hand's `pot`: `rake_bps` of it, at most `rake_cap`.
+pub fn rake_for(config: &TableConfig, pot: i128) -> i128 {
+    let rake = pot * config.rake_bps as i128 / 10_000;
+    core::cmp::min(rake, config.rake_cap)
+}
+
+/// The part of a hand's `pot` paid into the bad-beat jackpot: `jackpot_bps`
+/// of it.
+pub fn jackpot_for(config: &TableConfig, pot: i128) -> i128 {
+    pot * config.jackpot_bps as i128 / 10_000
+}
+
+/// Take `rake`, and with it any jackpot contribution, out of `pots`, which
+/// add up to `total`, in proportion to their size. Rounding leftovers come
+/// out of the main pot.
+pub fn deduct_rake(
+    pots: &mut Vec<SidePot>,
+    total: i128,
+    rake: i128,
+) -> Result<(), PokerTableError> {
+    if rake <= 0 || total <= 0 {
+        return Ok(());
+    }
+    let mut taken: i128 = 0;
+    for i in 0..pots.len() {
+        let mut pot = pots.get(i).ok_or(PokerTableError::InvalidPlayerIndex)?;
+        let cut = pot.amount * rake / total;
+        pot.amount -= cut;
+        taken += cut;
+        pots.set(i, pot);
+    }
+    let mut main = pots.get(0).ok_or(PokerTableError::InvalidPlayerIndex)?;
+    main.amount -= rake - taken;
+    pots.set(0, main);
+    Ok(())
+}
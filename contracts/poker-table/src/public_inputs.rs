@@ -0,0 +1,404 @@
+//! Decoding of the committee proofs' public inputs, so the table acts only on
+//! values a proof commits to rather than on what is passed alongside it.
+//!
+//! `public_inputs` is a run of 32-byte big-endian field elements: a circuit's
+//! public parameters followed by its return values, as Noir flattens them.
+//! Like the coordinator, fields are counted from the end, where the circuit
+//! outputs sit.
+//!
+//! Every circuit takes the hand's nonce (the table's `session_id`) as its
+//! first public input, so a proof is only good for the hand it was made for.
+
+use soroban_sdk::{Bytes, BytesN, Env, Vec};
+
+use crate::types::PokerTableError;
+#[cfg(any(test, feature = "testutils"))]
+use crate::types::{MuckPreference, TableState};
+
+const FIELD_BYTES: u32 = 32;
+
+/// Player slots in the deal and showdown circuits, and so the most seats a
+/// table can have.
+pub const MAX_PLAYERS: u32 = 6;
+
+/// `deal_valid`: the nonce and `num_players`, then the deck root, the hand
+/// commitments and the first and second hole-card deck positions, one slot
+/// per player.
+const DEAL_FIELDS: u32 = 3 + 3 * MAX_PLAYERS;
+
+/// Card slots in the reveal circuit.
+const MAX_REVEAL: u32 = 3;
+
+/// Deck positions the reveal circuit can be told are already used.
+const MAX_USED: u32 = 16;
+
+/// `reveal_board_valid`: the nonce, the deck root, `num_revealed`, the count
+/// and slots of previously used positions, then the revealed cards and their
+/// deck positions.
+const REVEAL_FIELDS: u32 = 4 + MAX_USED + 2 * MAX_REVEAL;
+
+const BOARD_CARDS: u32 = 5;
+
+/// `showdown_valid`: the nonce, `num_active_players`, the hand commitments,
+/// the board positions, the deck root, and per player whether they are still
+/// in, whether they muck a losing hand and what they put in; then the first
+/// and second hole cards of each player and the winner.
+const SHOWDOWN_FIELDS: u32 = 3 + BOARD_CARDS + 6 * MAX_PLAYERS + 1;
+
+/// What the showdown circuit outputs for a hole card it does not show.
+pub const HIDDEN_CARD: u32 = 52;
+
+/// What a deal proof commits to.
+pub struct DealInputs {
+    pub hand_nonce: u32,
+    pub deck_root: BytesN<32>,
+    pub hand_commitments: Vec<BytesN<32>>,
+    /// Both hole-card deck positions of each player in turn.
+    pub dealt_indices: Vec<u32>,
+}
+
+pub fn parse_deal(env: &Env, inputs: &Bytes) -> Result<DealInputs, PokerTableError> {
+    let nonce = first_field(inputs, DEAL_FIELDS)?;
+    let start = nonce + 1;
+    let num_players = field_u32(inputs, start)?;
+    if num_players > MAX_PLAYERS {
+        return Err(PokerTableError::PublicInputMismatch);
+    }
+
+    let mut hand_commitments = Vec::new(env);
+    let mut dealt_indices = Vec::new(env);
+    for p in 0..num_players {
+        hand_commitments.push_back(field(env, inputs, start + 2 + p));
+        dealt_indices.push_back(field_u32(inputs, start + 2 + MAX_PLAYERS + p)?);
+        dealt_indices.push_back(field_u32(inputs, start + 2 + 2 * MAX_PLAYERS + p)?);
+    }
+    Ok(DealInputs {
+        hand_nonce: field_u32(inputs, nonce)?,
+        deck_root: field(env, inputs, start + 1),
+        hand_commitments,
+        dealt_indices,
+    })
+}
+
+/// What a reveal proof commits to.
+pub struct RevealInputs {
+    pub hand_nonce: u32,
+    pub deck_root: BytesN<32>,
+    pub cards: Vec<u32>,
+    pub indices: Vec<u32>,
+}
+
+pub fn parse_reveal(env: &Env, inputs: &Bytes) -> Result<RevealInputs, PokerTableError> {
+    let nonce = first_field(inputs, REVEAL_FIELDS)?;
+    let start = nonce + 1;
+    let num_revealed = field_u32(inputs, start + 1)?;
+    if num_revealed > MAX_REVEAL {
+        return Err(PokerTableError::PublicInputMismatch);
+    }
+
+    let outputs = start + 3 + MAX_USED;
+    let mut cards = Vec::new(env);
+    let mut indices = Vec::new(env);
+    for i in 0..num_revealed {
+        cards.push_back(field_u32(inputs, outputs + i)?);
+        indices.push_back(field_u32(inputs, outputs + MAX_REVEAL + i)?);
+    }
+    Ok(RevealInputs {
+        hand_nonce: field_u32(inputs, nonce)?,
+        deck_root: field(env, inputs, start),
+        cards,
+        indices,
+    })
+}
+
+/// What a showdown proof commits to.
+pub struct ShowdownInputs {
+    pub hand_nonce: u32,
+    pub deck_root: BytesN<32>,
+    pub hand_commitments: Vec<BytesN<32>>,
+    pub board_indices: Vec<u32>,
+    /// Per player dealt in, in deal order: whether they are still in the
+    /// hand, whether they muck a losing hand, and the chips they put in.
+    pub contending: Vec<bool>,
+    pub muck_losing: Vec<bool>,
+    pub contributions: Vec<i128>,
+    /// Both hole cards of each player dealt in, in deal order, or
+    /// `HIDDEN_CARD` twice for a player whose hand is not shown.
+    pub hole_cards: Vec<(u32, u32)>,
+    /// Deal-order position of the first player still in with the best hand.
+    pub winner: u32,
+}
+
+pub fn parse_showdown(env: &Env, inputs: &Bytes) -> Result<ShowdownInputs, PokerTableError> {
+    let nonce = first_field(inputs, SHOWDOWN_FIELDS)?;
+    let start = nonce + 1;
+    let num_players = field_u32(inputs, start)?;
+    if num_players > MAX_PLAYERS {
+        return Err(PokerTableError::PublicInputMismatch);
+    }
+    let winner = field_u32(inputs, start + SHOWDOWN_FIELDS - 2)?;
+    if winner >= num_players {
+        return Err(PokerTableError::PublicInputMismatch);
+    }
+
+    let board = start + 1 + MAX_PLAYERS;
+    let mut board_indices = Vec::new(env);
+    for i in 0..BOARD_CARDS {
+        board_indices.push_back(field_u32(inputs, board + i)?);
+    }
+
+    let seats = board + BOARD_CARDS + 1;
+    let outputs = seats + 3 * MAX_PLAYERS;
+    let mut hand_commitments = Vec::new(env);
+    let mut contending = Vec::new(env);
+    let mut muck_losing = Vec::new(env);
+    let mut contributions: Vec<i128> = Vec::new(env);
+    let mut hole_cards = Vec::new(env);
+    for p in 0..num_players {
+        hand_commitments.push_back(field(env, inputs, start + 1 + p));
+        contending.push_back(field_bool(inputs, seats + p)?);
+        muck_losing.push_back(field_bool(inputs, seats + MAX_PLAYERS + p)?);
+        contributions.push_back(field_u64(inputs, seats + 2 * MAX_PLAYERS + p)?.into());
+        hole_cards.push_back((
+            field_u32(inputs, outputs + p)?,
+            field_u32(inputs, outputs + MAX_PLAYERS + p)?,
+        ));
+    }
+    Ok(ShowdownInputs {
+        hand_nonce: field_u32(inputs, nonce)?,
+        deck_root: field(env, inputs, board + BOARD_CARDS),
+        hand_commitments,
+        board_indices,
+        contending,
+        muck_losing,
+        contributions,
+        hole_cards,
+        winner,
+    })
+}
+
+/// Index of the first of the last `count` fields in `inputs`.
+fn first_field(inputs: &Bytes, count: u32) -> Result<u32, PokerTableError> {
+    if inputs.len() % FIELD_BYTES != 0 || inputs.len() / FIELD_BYTES < count {
+        return Err(PokerTableError::PublicInputMismatch);
+    }
+    Ok(inputs.len() / FIELD_BYTES - count)
+}
+
+fn field_bytes(inputs: &Bytes, index: u32) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    inputs
+        .slice(index * FIELD_BYTES..(index + 1) * FIELD_BYTES)
+        .copy_into_slice(&mut buf);
+    buf
+}
+
+fn field(env: &Env, inputs: &Bytes, index: u32) -> BytesN<32> {
+    BytesN::from_array(env, &field_bytes(inputs, index))
+}
+
+/// A field holding a `u32`, as the circuits' card values and deck positions.
+fn field_u32(inputs: &Bytes, index: u32) -> Result<u32, PokerTableError> {
+    let buf = field_bytes(inputs, index);
+    if buf[..28].iter().any(|&b| b != 0) {
+        return Err(PokerTableError::PublicInputMismatch);
+    }
+    Ok(u32::from_be_bytes([buf[28], buf[29], buf[30], buf[31]]))
+}
+
+/// A field holding a `u64`, as the showdown circuit's chip counts.
+fn field_u64(inputs: &Bytes, index: u32) -> Result<u64, PokerTableError> {
+    let buf = field_bytes(inputs, index);
+    if buf[..24].iter().any(|&b| b != 0) {
+        return Err(PokerTableError::PublicInputMismatch);
+    }
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&buf[24..]);
+    Ok(u64::from_be_bytes(value))
+}
+
+/// A field holding a `bool`, which Noir encodes as 0 or 1.
+fn field_bool(inputs: &Bytes, index: u32) -> Result<bool, PokerTableError> {
+    match field_u32(inputs, index)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(PokerTableError::PublicInputMismatch),
+    }
+}
+
+/// Public inputs of a deal proof committing to these values, for tests that
+/// play the committee.
+#[cfg(any(test, feature = "testutils"))]
+pub fn encode_deal(
+    env: &Env,
+    hand_nonce: u32,
+    deck_root: &BytesN<32>,
+    hand_commitments: &Vec<BytesN<32>>,
+    dealt_indices: &Vec<u32>,
+) -> Bytes {
+    let num_players = hand_commitments.len();
+    let mut inputs = Bytes::new(env);
+    push_u32(&mut inputs, hand_nonce);
+    push_u32(&mut inputs, num_players);
+    inputs.extend_from_array(&deck_root.to_array());
+    for p in 0..MAX_PLAYERS {
+        match hand_commitments.get(p) {
+            Some(commitment) => inputs.extend_from_array(&commitment.to_array()),
+            None => push_u32(&mut inputs, 0),
+        }
+    }
+    for card in 0..2 {
+        for p in 0..MAX_PLAYERS {
+            push_u32(&mut inputs, dealt_indices.get(2 * p + card).unwrap_or(0));
+        }
+    }
+    inputs
+}
+
+/// Public inputs of a reveal proof for `cards` at deck positions `indices`.
+#[cfg(any(test, feature = "testutils"))]
+pub fn encode_reveal(
+    env: &Env,
+    hand_nonce: u32,
+    deck_root: &BytesN<32>,
+    cards: &Vec<u32>,
+    indices: &Vec<u32>,
+) -> Bytes {
+    let mut inputs = Bytes::new(env);
+    push_u32(&mut inputs, hand_nonce);
+    inputs.extend_from_array(&deck_root.to_array());
+    push_u32(&mut inputs, cards.len());
+    for _ in 0..1 + MAX_USED {
+        push_u32(&mut inputs, 0);
+    }
+    for values in [cards, indices] {
+        for i in 0..MAX_REVEAL {
+            push_u32(&mut inputs, values.get(i).unwrap_or(0));
+        }
+    }
+    inputs
+}
+
+/// Public inputs of a showdown proof for `hole_cards` (one pair per player
+/// dealt in) on the hand on `table`, showing and naming the winner as the
+/// circuit does.
+#[cfg(any(test, feature = "testutils"))]
+pub fn encode_showdown(env: &Env, table: &TableState, hole_cards: &Vec<(u32, u32)>) -> Bytes {
+    let num_players = table.hand_commitments.len();
+    let mut inputs = Bytes::new(env);
+    push_u32(&mut inputs, table.session_id);
+    push_u32(&mut inputs, num_players);
+    for p in 0..MAX_PLAYERS {
+        match table.hand_commitments.get(p) {
+            Some(commitment) => inputs.extend_from_array(&commitment.to_array()),
+            None => push_u32(&mut inputs, 0),
+        }
+    }
+    for i in 0..BOARD_CARDS {
+        let index = table.dealt_indices.get(2 * num_players + i);
+        push_u32(&mut inputs, index.unwrap_or(0));
+    }
+    inputs.extend_from_array(&table.deck_root.to_array());
+
+    let seats = showdown_seats(env, table, hole_cards);
+    for p in 0..MAX_PLAYERS {
+        push_u32(&mut inputs, seats.get(p).map_or(0, |(c, ..)| c as u32));
+    }
+    for p in 0..MAX_PLAYERS {
+        push_u32(&mut inputs, seats.get(p).map_or(0, |(_, m, ..)| m as u32));
+    }
+    for p in 0..MAX_PLAYERS {
+        let mut buf = [0u8; 32];
+        let contribution = seats.get(p).map_or(0, |(_, _, c, _)| c as u64);
+        buf[24..].copy_from_slice(&contribution.to_be_bytes());
+        inputs.extend_from_array(&buf);
+    }
+
+    let shown = shown_hole_cards(env, table, hole_cards);
+    for p in 0..MAX_PLAYERS {
+        push_u32(&mut inputs, shown.get(p).map_or(0, |(c1, _)| c1));
+    }
+    for p in 0..MAX_PLAYERS {
+        push_u32(&mut inputs, shown.get(p).map_or(0, |(_, c2)| c2));
+    }
+
+    // The first player still in with the best hand.
+    let mut winner: Option<(u32, u32)> = None;
+    for (p, (contending, _, _, score)) in seats.iter().enumerate() {
+        if contending && winner.is_none_or(|(_, best)| score > best) {
+            winner = Some((p as u32, score));
+        }
+    }
+    push_u32(&mut inputs, winner.map_or(0, |(p, _)| p));
+    inputs
+}
+
+/// The hole cards a showdown proof for `hole_cards` on `table` shows: those
+/// of players still in who show, or who win a pot someone else contests,
+/// with `HIDDEN_CARD` for the rest.
+#[cfg(any(test, feature = "testutils"))]
+pub fn shown_hole_cards(
+    env: &Env,
+    table: &TableState,
+    hole_cards: &Vec<(u32, u32)>,
+) -> Vec<(u32, u32)> {
+    let seats = showdown_seats(env, table, hole_cards);
+    // Above the second-largest contribution, a pot has one player in it.
+    let (mut top, mut contested) = (0, 0);
+    for (contending, _, contribution, _) in seats.iter() {
+        if contending && contribution > top {
+            contested = top;
+            top = contribution;
+        } else if contending && contribution > contested {
+            contested = contribution;
+        }
+    }
+
+    let mut shown = Vec::new(env);
+    for (p, (contending, muck_losing, contribution, score)) in seats.iter().enumerate() {
+        let level = contribution.min(contested);
+        let wins_pot = seats
+            .iter()
+            .all(|(c, _, put_in, other)| !c || put_in < level || other <= score);
+        if contending && (!muck_losing || wins_pot) {
+            shown.push_back(hole_cards.get(p as u32).unwrap_or((0, 0)));
+        } else {
+            shown.push_back((HIDDEN_CARD, HIDDEN_CARD));
+        }
+    }
+    shown
+}
+
+/// What the showdown circuit is told and works out about each player dealt
+/// in, in deal order: whether they are still in, whether they muck a losing
+/// hand, what they put in, and their hand's score.
+#[cfg(any(test, feature = "testutils"))]
+fn showdown_seats(
+    env: &Env,
+    table: &TableState,
+    hole_cards: &Vec<(u32, u32)>,
+) -> Vec<(bool, bool, i128, u32)> {
+    let board = &table.board_cards;
+    let mut seats = Vec::new(env);
+    for (p, player) in table.players.iter().flatten().enumerate() {
+        let (c1, c2) = hole_cards.get(p as u32).unwrap_or((0, 0));
+        let mut cards = [c1, c2, 0, 0, 0, 0, 0];
+        for (i, card) in board.iter().enumerate() {
+            cards[2 + i] = card;
+        }
+        seats.push_back((
+            !player.folded,
+            player.muck_preference == MuckPreference::Muck,
+            player.hand_start_stack - player.stack,
+            stellar_zk_cards::evaluate_hand(&cards).score,
+        ));
+    }
+    seats
+}
+
+#[cfg(any(test, feature = "testutils"))]
+fn push_u32(inputs: &mut Bytes, value: u32) {
+    let mut buf = [0u8; 32];
+    buf[28..].copy_from_slice(&value.to_be_bytes());
+    inputs.extend_from_array(&buf);
+}
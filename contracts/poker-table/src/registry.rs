@@ -0,0 +1,107 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, Vec};
+
+use crate::types::TableState;
+
+#[cfg(test)]
+use soroban_sdk::{contract, contractimpl, symbol_short};
+
+/// Committee Registry contract interface.
+/// Matches the interface in contracts/committee-registry/src/lib.rs
+#[cfg(test)]
+#[contract]
+pub struct CommitteeRegistryContract;
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CommitteeEpoch {
+    pub epoch_id: u32,
+    pub members: Vec<Address>,
+    pub threshold: u32,
+    pub start_ledger: u32,
+    pub end_ledger: u32,
+}
+
+/// What the committee failed to deliver before its deadline.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommitteeFailure {
+    MissedDeal,
+    MissedReveal,
+    MissedShowdown,
+}
+
+/// Why a member was reported: the failure and the hand it happened in.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlashReason {
+    pub failure: CommitteeFailure,
+    pub table_id: u32,
+    pub hand_number: u32,
+}
+
+#[allow(dead_code)]
+#[contractclient(name = "CommitteeRegistryClient")]
+pub trait CommitteeRegistry {
+    fn get_current_epoch(env: Env) -> Option<CommitteeEpoch>;
+
+    fn report_slash(env: Env, reporter: Address, member: Address, reason: SlashReason);
+}
+
+/// Mock implementation for tests: there is no epoch, and every report is
+/// kept so that tests can check who was reported.
+#[cfg(test)]
+#[contractimpl]
+impl CommitteeRegistry for CommitteeRegistryContract {
+    fn get_current_epoch(_env: Env) -> Option<CommitteeEpoch> {
+        None
+    }
+
+    fn report_slash(env: Env, _reporter: Address, member: Address, reason: SlashReason) {
+        let mut reports = Self::reports(env.clone());
+        reports.push_back((member, reason));
+        env.storage()
+            .instance()
+            .set(&symbol_short!("reports"), &reports);
+    }
+}
+
+#[cfg(test)]
+#[contractimpl]
+impl CommitteeRegistryContract {
+    /// Every `report_slash` call received so far, in order.
+    pub fn reports(env: Env) -> Vec<(Address, SlashReason)> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("reports"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+/// The registry's current epoch, or `None` if it has none or cannot be
+/// reached.
+pub fn current_epoch(env: &Env, registry: &Address) -> Option<CommitteeEpoch> {
+    let client = CommitteeRegistryClient::new(env, registry);
+    match client.try_get_current_epoch() {
+        Ok(Ok(epoch)) => epoch,
+        _ => None,
+    }
+}
+
+/// Report every member of the table's committee for its `failure` in the
+/// current hand.
+///
+/// The registry is called with `try_` so that a member who has since
+/// deregistered or a broken registry cannot stop the timeout from handing
+/// the players their chips back.
+pub fn report_timeout(env: &Env, table: &TableState, failure: CommitteeFailure) {
+    let client = CommitteeRegistryClient::new(env, &table.config.committee_registry);
+    let reason = SlashReason {
+        failure,
+        table_id: table.id,
+        hand_number: table.hand_number,
+    };
+    let reporter = env.current_contract_address();
+    for member in table.committee.iter() {
+        let _ = client.try_report_slash(&reporter, &member, &reason);
+    }
+}
@@ -1,11 +1,14 @@
 #[cfg(test)]
 mod test {
+    use crate::events::*;
+    use crate::game::seated_count;
+    use crate::public_inputs;
     use crate::types::*;
-    use crate::{PokerTableContract, PokerTableContractClient};
+    use crate::{PokerTableContract, PokerTableContractClient, VERIFIER_TIMELOCK_LEDGERS};
     use soroban_sdk::{
-        testutils::{Address as _, Ledger as _},
+        testutils::{Address as _, Events as _, Ledger as _},
         token::{StellarAssetClient, TokenClient},
-        Address, BytesN, Env, Vec,
+        vec, Address, BytesN, Env, Event, Val, Vec,
     };
 
     // ---------------------------------------------------------------------------
@@ -28,8 +31,9 @@ mod test {
         committee: &Address,
         verifier: &Address,
     ) -> TableConfig {
-        // Register a mock game hub contract
+        // Register a mock game hub and committee registry
         let game_hub = env.register(crate::game_hub::GameHubContract, ());
+        let committee_registry = env.register(crate::registry::CommitteeRegistryContract, ());
         TableConfig {
             token: token.clone(),
             min_buy_in: 100,
@@ -37,10 +41,20 @@ mod test {
             small_blind: 5,
             big_blind: 10,
             max_players: 6,
-            timeout_ledgers: 100,
-            committee: committee.clone(),
+            deal_timeout_ledgers: 100,
+            reveal_timeout_ledgers: 100,
+            showdown_timeout_ledgers: 100,
+            betting_timeout_ledgers: 100,
+            time_bank_ledgers: 0,
+            committee: Vec::from_array(env, [committee.clone()]),
+            committee_threshold: 1,
             verifier: verifier.clone(),
             game_hub,
+            committee_registry,
+            rake_bps: 0,
+            rake_cap: 0,
+            chip_unit: 1,
+            jackpot_bps: 0,
         }
     }
 
@@ -54,6 +68,16 @@ mod test {
         verifier: Address,
     }
 
+    /// `event` as the table contract publishes it, to compare against
+    /// `env.events().all()`.
+    fn published(s: &TestSetup, event: &impl Event) -> (Address, Vec<Val>, Val) {
+        (
+            s.client.address.clone(),
+            event.topics(&s.env),
+            event.data(&s.env),
+        )
+    }
+
     /// Spin up an environment with a poker-table contract and a token contract.
     fn setup() -> TestSetup<'static> {
         let env = Env::default();
@@ -104,7 +128,14 @@ mod test {
             dealt_indices.push_back(i);
         }
         let proof = soroban_sdk::Bytes::new(&s.env);
-        let public_inputs = soroban_sdk::Bytes::new(&s.env);
+        let hand_nonce = s.client.get_table(&table_id).session_id;
+        let public_inputs = public_inputs::encode_deal(
+            &s.env,
+            hand_nonce,
+            &deck_root,
+            &commitments,
+            &dealt_indices,
+        );
 
         s.client.commit_deal(
             &table_id,
@@ -117,6 +148,26 @@ mod test {
         );
     }
 
+    /// Reveal `cards` from deck positions `indices`, with public inputs that
+    /// commit to them.
+    fn reveal(s: &TestSetup, table_id: u32, cards: &Vec<u32>, indices: &Vec<u32>) {
+        let table = s.client.get_table(&table_id);
+        s.client.reveal_board(
+            &table_id,
+            &s.committee,
+            cards,
+            indices,
+            &soroban_sdk::Bytes::new(&s.env),
+            &public_inputs::encode_reveal(
+                &s.env,
+                table.session_id,
+                &table.deck_root,
+                cards,
+                indices,
+            ),
+        );
+    }
+
     // ---------------------------------------------------------------------------
     // 1. Create table
     // ---------------------------------------------------------------------------
@@ -136,7 +187,8 @@ mod test {
         assert_eq!(table.config.big_blind, 10);
         assert_eq!(table.config.max_players, 6);
         assert_eq!(table.phase, GamePhase::Waiting);
-        assert_eq!(table.players.len(), 0);
+        assert_eq!(table.players.len(), 6);
+        assert_eq!(seated_count(&table), 0);
         assert_eq!(table.pot, 0);
     }
 
@@ -151,6 +203,28 @@ mod test {
         assert_eq!(id2, 2);
     }
 
+    #[test]
+    fn test_create_table_rejects_unplayable_configs() {
+        let s = setup();
+        let default = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        let cases: [(fn(&mut TableConfig), PokerTableError); 9] = [
+            (|c| c.small_blind = 0, PokerTableError::InvalidBlinds),
+            (|c| c.big_blind = 4, PokerTableError::InvalidBlinds),
+            (|c| c.chip_unit = 0, PokerTableError::InvalidChipUnit),
+            (|c| c.chip_unit = 10, PokerTableError::InvalidChipUnit),
+            (|c| c.jackpot_bps = 10_001, PokerTableError::InvalidRake),
+            (|c| c.min_buy_in = 0, PokerTableError::InvalidBuyInRange),
+            (|c| c.max_buy_in = 99, PokerTableError::InvalidBuyInRange),
+            (|c| c.max_players = 1, PokerTableError::InvalidMaxPlayers),
+            (|c| c.max_players = 7, PokerTableError::InvalidMaxPlayers),
+        ];
+        for (break_config, error) in cases {
+            let mut config = default.clone();
+            break_config(&mut config);
+            assert_eq!(s.client.try_create_table(&s.admin, &config), Err(Ok(error)));
+        }
+    }
+
     // ---------------------------------------------------------------------------
     // 2. Join table with buy-in
     // ---------------------------------------------------------------------------
@@ -165,9 +239,9 @@ mod test {
         assert_eq!(seat, 0);
 
         let table = s.client.get_table(&table_id);
-        assert_eq!(table.players.len(), 1);
+        assert_eq!(seated_count(&table), 1);
 
-        let p = table.players.get(0).unwrap();
+        let p = table.players.get(0).unwrap().unwrap();
         assert_eq!(p.address, player);
         assert_eq!(p.stack, 500);
         assert_eq!(p.seat_index, 0);
@@ -193,38 +267,97 @@ mod test {
         assert_eq!(join_player(&s, table_id, &p3, 500), 2);
 
         let table = s.client.get_table(&table_id);
-        assert_eq!(table.players.len(), 3);
+        assert_eq!(seated_count(&table), 3);
         assert_eq!(s.token.balance(&s.client.address), 1000);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #4)")]
+    fn test_native_table_takes_xlm() {
+        let s = setup();
+        let xlm = s
+            .env
+            .deployer()
+            .with_stellar_asset(soroban_sdk::Bytes::from_array(&s.env, &[0, 0, 0, 0]))
+            .deploy();
+        let config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+
+        let table_id = s.client.create_native_table(&s.admin, &config);
+        assert_eq!(s.client.get_native_token(), xlm);
+        assert_eq!(s.client.get_table(&table_id).config.token, xlm);
+    }
+
+    #[test]
+    fn test_join_table_with_allowance_pulls_the_approved_buy_in() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let player = Address::generate(&s.env);
+        s.token_admin_client.mint(&player, &800);
+        let expiration = s.env.ledger().sequence() + 1_000;
+
+        s.token
+            .approve(&player, &s.client.address, &400, &expiration);
+        assert_eq!(
+            s.client
+                .try_join_table_with_allowance(&table_id, &player, &500),
+            Err(Ok(PokerTableError::InsufficientAllowance))
+        );
+
+        s.token
+            .approve(&player, &s.client.address, &600, &expiration);
+        let seat = s.client.join_table_with_allowance(&table_id, &player, &500);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.players.get(seat).unwrap().unwrap().stack, 500);
+        assert_eq!(s.token.balance(&player), 300);
+        assert_eq!(s.token.balance(&s.client.address), 500);
+        assert_eq!(s.token.allowance(&player, &s.client.address), 100);
+    }
+
+    #[test]
     fn test_join_table_buy_in_too_low() {
         let s = setup();
         let table_id = create_default_table(&s);
         let player = Address::generate(&s.env);
-        join_player(&s, table_id, &player, 50); // min is 100
+        // min is 100
+        assert_eq!(
+            s.client.try_join_table(&table_id, &player, &50),
+            Err(Ok(PokerTableError::InvalidBuyIn))
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #4)")]
     fn test_join_table_buy_in_too_high() {
         let s = setup();
         let table_id = create_default_table(&s);
         let player = Address::generate(&s.env);
-        join_player(&s, table_id, &player, 2000); // max is 1000
+        // max is 1000
+        assert_eq!(
+            s.client.try_join_table(&table_id, &player, &2000),
+            Err(Ok(PokerTableError::InvalidBuyIn))
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #5)")]
     fn test_join_table_already_seated() {
         let s = setup();
         let table_id = create_default_table(&s);
         let player = Address::generate(&s.env);
         join_player(&s, table_id, &player, 500);
-        // Mint more so the transfer wouldn't fail first
-        s.token_admin_client.mint(&player, &500);
-        s.client.join_table(&table_id, &player, &500);
+        assert_eq!(
+            s.client.try_join_table(&table_id, &player, &500),
+            Err(Ok(PokerTableError::AlreadySeated))
+        );
+    }
+
+    #[test]
+    fn test_join_table_needs_the_buy_in_in_hand() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let player = Address::generate(&s.env);
+        s.token_admin_client.mint(&player, &499);
+        assert_eq!(
+            s.client.try_join_table(&table_id, &player, &500),
+            Err(Ok(PokerTableError::InsufficientBalance))
+        );
     }
 
     // ---------------------------------------------------------------------------
@@ -247,10 +380,13 @@ mod test {
         assert_eq!(table.phase, GamePhase::Dealing);
         assert_eq!(table.hand_number, 1);
 
-        // Blinds should be posted (dealer rotated to seat 1, sb=seat 0, bb=seat 1
-        // for 2 players: dealer_seat = (0+1)%2 = 1, sb = (1+1)%2 = 0, bb = (1+2)%2 = 1)
-        let sb_player = table.players.get(0).unwrap();
-        let bb_player = table.players.get(1).unwrap();
+        // Blinds should be posted. Heads-up the button posts the small blind:
+        // the dealer moves to seat 1, which posts it, and seat 0 the big.
+        assert_eq!(table.dealer_seat, 1);
+        assert_eq!(table.small_blind_seat, 1);
+        assert_eq!(table.big_blind_seat, 0);
+        let sb_player = table.players.get(1).unwrap().unwrap();
+        let bb_player = table.players.get(0).unwrap().unwrap();
         assert_eq!(sb_player.bet_this_round, 5); // small blind
         assert_eq!(sb_player.stack, 495);
         assert_eq!(bb_player.bet_this_round, 10); // big blind
@@ -259,7 +395,43 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #9)")]
+    fn test_heads_up_button_acts_first_preflop_and_last_after() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+
+        for (button, big_blind) in [(1, 0), (0, 1)] {
+            s.client.start_hand(&table_id);
+            commit_mock_deal(&s, table_id, 2);
+            let table = s.client.get_table(&table_id);
+            assert_eq!(table.dealer_seat, button);
+            assert_eq!(table.small_blind_seat, button);
+            assert_eq!(table.big_blind_seat, big_blind);
+            assert_eq!(table.current_turn, button);
+
+            // The button completes, the big blind checks, and after the flop
+            // the big blind acts first.
+            try_act(&s, table_id, Action::Call).unwrap();
+            assert_eq!(s.client.get_table(&table_id).current_turn, big_blind);
+            try_act(&s, table_id, Action::Check).unwrap();
+            reveal(
+                &s,
+                table_id,
+                &vec![&s.env, 10, 20, 30],
+                &vec![&s.env, 4, 5, 6],
+            );
+            let table = s.client.get_table(&table_id);
+            assert_eq!(table.phase, GamePhase::Flop);
+            assert_eq!(table.current_turn, big_blind);
+
+            try_act(&s, table_id, Action::Bet(10)).unwrap();
+            try_act(&s, table_id, Action::Fold).unwrap();
+            assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Settlement);
+        }
+    }
+
+    #[test]
     fn test_start_hand_not_enough_players() {
         let s = setup();
         let table_id = create_default_table(&s);
@@ -267,7 +439,123 @@ mod test {
         let p1 = Address::generate(&s.env);
         join_player(&s, table_id, &p1, 500);
 
+        assert_eq!(
+            s.client.try_start_hand(&table_id),
+            Err(Ok(PokerTableError::NeedAtLeastTwoPlayers))
+        );
+    }
+
+    #[test]
+    fn test_commit_deal_rejects_values_not_in_proof() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        s.client.start_hand(&table_id);
+
+        let deck_root = BytesN::from_array(&s.env, &[1u8; 32]);
+        let commitments = vec![
+            &s.env,
+            BytesN::from_array(&s.env, &[2u8; 32]),
+            BytesN::from_array(&s.env, &[3u8; 32]),
+        ];
+        let dealt_indices = vec![&s.env, 0, 1, 2, 3];
+        let hand_nonce = s.client.get_table(&table_id).session_id;
+        let proven = public_inputs::encode_deal(
+            &s.env,
+            hand_nonce,
+            &deck_root,
+            &commitments,
+            &dealt_indices,
+        );
+        let commit = |root: &BytesN<32>, commitments: &Vec<BytesN<32>>, dealt: &Vec<u32>| {
+            s.client.try_commit_deal(
+                &table_id,
+                &s.committee,
+                root,
+                commitments,
+                dealt,
+                &soroban_sdk::Bytes::new(&s.env),
+                &proven,
+            )
+        };
+
+        let other_root = BytesN::from_array(&s.env, &[9u8; 32]);
+        let swapped = vec![
+            &s.env,
+            commitments.get(1).unwrap(),
+            commitments.get(0).unwrap(),
+        ];
+        let other_dealt = vec![&s.env, 0, 1, 2, 4];
+        for result in [
+            commit(&other_root, &commitments, &dealt_indices),
+            commit(&deck_root, &swapped, &dealt_indices),
+            commit(&deck_root, &commitments, &other_dealt),
+        ] {
+            assert!(matches!(
+                result,
+                Err(Ok(PokerTableError::PublicInputMismatch))
+            ));
+        }
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Dealing);
+
+        assert!(commit(&deck_root, &commitments, &dealt_indices).is_ok());
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Preflop);
+    }
+
+    #[test]
+    fn test_deal_proof_from_previous_hand_is_rejected() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        s.client.start_hand(&table_id);
+
+        let deck_root = BytesN::from_array(&s.env, &[1u8; 32]);
+        let commitments = vec![
+            &s.env,
+            BytesN::from_array(&s.env, &[2u8; 32]),
+            BytesN::from_array(&s.env, &[3u8; 32]),
+        ];
+        let dealt_indices = vec![&s.env, 0, 1, 2, 3];
+        let first_hand = s.client.get_table(&table_id).session_id;
+        let old_inputs = public_inputs::encode_deal(
+            &s.env,
+            first_hand,
+            &deck_root,
+            &commitments,
+            &dealt_indices,
+        );
+        let proof = soroban_sdk::Bytes::new(&s.env);
+        s.client.commit_deal(
+            &table_id,
+            &s.committee,
+            &deck_root,
+            &commitments,
+            &dealt_indices,
+            &proof,
+            &old_inputs,
+        );
+
+        // The small blind folds and the next hand starts.
+        let table = s.client.get_table(&table_id);
+        let actor = table.players.get(table.current_turn).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &actor.address, &Action::Fold);
         s.client.start_hand(&table_id);
+        assert_ne!(s.client.get_table(&table_id).session_id, first_hand);
+
+        let result = s.client.try_commit_deal(
+            &table_id,
+            &s.committee,
+            &deck_root,
+            &commitments,
+            &dealt_indices,
+            &proof,
+            &old_inputs,
+        );
+        assert!(matches!(result, Err(Ok(PokerTableError::ProofReplay))));
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Dealing);
     }
 
     // ---------------------------------------------------------------------------
@@ -312,23 +600,100 @@ mod test {
         //   So seat 1 acts first.
         let table = s.client.get_table(&table_id);
         let current = table.current_turn;
-        let acting_player = table.players.get(current).unwrap();
+        let acting_player = table.players.get(current).unwrap().unwrap();
 
         s.client
             .player_action(&table_id, &acting_player.address, &Action::Fold);
 
         let table = s.client.get_table(&table_id);
-        let folded_player = table.players.get(current).unwrap();
+        let folded_player = table.players.get(current).unwrap().unwrap();
         assert!(folded_player.folded);
     }
 
+    #[test]
+    fn test_player_action_publishes_event() {
+        let (s, table_id, _, _, _) = setup_preflop_3p();
+        let table = s.client.get_table(&table_id);
+        let actor = table.players.get(table.current_turn).unwrap().unwrap();
+
+        s.client
+            .player_action(&table_id, &actor.address, &Action::Call);
+
+        assert_eq!(
+            s.env.events().all().filter_by_contract(&s.client.address),
+            vec![
+                &s.env,
+                published(
+                    &s,
+                    &ActionTaken {
+                        table_id,
+                        hand_number: table.hand_number,
+                        phase: GamePhase::Preflop,
+                        player: actor.address.clone(),
+                        seat: table.current_turn,
+                        action: Action::Call,
+                        amount: 10,
+                        pot: 25,
+                        next_turn: Some((table.current_turn + 1) % 3),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_action_closing_the_round_has_no_next_turn() {
+        let (s, table_id, _, _, _) = setup_preflop_3p();
+        for _ in 0..2 {
+            let table = s.client.get_table(&table_id);
+            let actor = table.players.get(table.current_turn).unwrap().unwrap();
+            s.client
+                .player_action(&table_id, &actor.address, &Action::Call);
+        }
+
+        // Every bet is matched, and the big blind's check closes the round.
+        let table = s.client.get_table(&table_id);
+        let big_blind = table.players.get(table.big_blind_seat).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &big_blind.address, &Action::Check);
+
+        assert_eq!(
+            s.env.events().all().filter_by_contract(&s.client.address),
+            vec![
+                &s.env,
+                published(
+                    &s,
+                    &ActionTaken {
+                        table_id,
+                        hand_number: table.hand_number,
+                        phase: GamePhase::Preflop,
+                        player: big_blind.address.clone(),
+                        seat: table.big_blind_seat,
+                        action: Action::Check,
+                        amount: 0,
+                        pot: 30,
+                        next_turn: None,
+                    }
+                ),
+                published(
+                    &s,
+                    &PhaseChange {
+                        table_id,
+                        hand_number: table.hand_number,
+                        phase: GamePhase::DealingFlop,
+                    }
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_player_call() {
         let (s, table_id, _p1, _p2, _p3) = setup_preflop_3p();
 
         let table = s.client.get_table(&table_id);
         let current = table.current_turn;
-        let acting_player = table.players.get(current).unwrap();
+        let acting_player = table.players.get(current).unwrap().unwrap();
         let stack_before = acting_player.stack;
         let pot_before = table.pot;
 
@@ -336,8 +701,7 @@ mod test {
         // could be 0 or some blind amount depending on seat. We need to call.
         let to_call = {
             let mut max_bet: i128 = 0;
-            for i in 0..table.players.len() {
-                let p = table.players.get(i).unwrap();
+            for p in table.players.iter().flatten() {
                 if p.bet_this_round > max_bet {
                     max_bet = p.bet_this_round;
                 }
@@ -349,7 +713,7 @@ mod test {
             .player_action(&table_id, &acting_player.address, &Action::Call);
 
         let table_after = s.client.get_table(&table_id);
-        let player_after = table_after.players.get(current).unwrap();
+        let player_after = table_after.players.get(current).unwrap().unwrap();
         assert_eq!(player_after.stack, stack_before - to_call);
         assert_eq!(table_after.pot, pot_before + to_call);
     }
@@ -357,8 +721,6 @@ mod test {
     #[test]
     fn test_player_bet() {
         // To test Bet, we need a situation where current_bet == 0 (post-flop).
-        // In the current contract, the preflop round ends as soon as all active
-        // players have matching bets (SB calls -> all at BB level -> round over).
         let s = setup();
         let table_id = create_default_table(&s);
 
@@ -370,40 +732,33 @@ mod test {
         s.client.start_hand(&table_id);
         commit_mock_deal(&s, table_id, 2);
 
-        // 2-player: dealer_seat = 1, sb = seat 0, bb = seat 1
-        // commit_deal sets current_turn = (1+3)%2 = 0 (the SB)
+        // 2-player: seat 1 deals and posts the small blind, seat 0 the big;
+        // the small blind acts first preflop.
         let table = s.client.get_table(&table_id);
         let current = table.current_turn;
-        let acting = table.players.get(current).unwrap();
+        let acting = table.players.get(current).unwrap().unwrap();
 
-        // SB calls the big blind. Once bets match, round ends automatically.
+        // SB calls the big blind, which still has its option.
         s.client
             .player_action(&table_id, &acting.address, &Action::Call);
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Preflop);
 
-        // Round completes -> DealingFlop
+        // BB checks -> round completes -> DealingFlop
+        try_act(&s, table_id, Action::Check).unwrap();
         let table = s.client.get_table(&table_id);
         assert_eq!(table.phase, GamePhase::DealingFlop);
 
         // Reveal flop
         let flop_cards: Vec<u32> = Vec::from_array(&s.env, [10, 20, 30]);
         let flop_indices: Vec<u32> = Vec::from_array(&s.env, [4, 5, 6]);
-        let proof = soroban_sdk::Bytes::new(&s.env);
-        let pub_inputs = soroban_sdk::Bytes::new(&s.env);
-        s.client.reveal_board(
-            &table_id,
-            &s.committee,
-            &flop_cards,
-            &flop_indices,
-            &proof,
-            &pub_inputs,
-        );
+        reveal(&s, table_id, &flop_cards, &flop_indices);
 
         let table = s.client.get_table(&table_id);
         assert_eq!(table.phase, GamePhase::Flop);
 
         // Now current_bet is 0 after reset_round. First active player can Bet.
         let current = table.current_turn;
-        let acting = table.players.get(current).unwrap();
+        let acting = table.players.get(current).unwrap().unwrap();
         let stack_before = acting.stack;
         let pot_before = table.pot;
         let bet_amount: i128 = 20;
@@ -412,7 +767,7 @@ mod test {
             .player_action(&table_id, &acting.address, &Action::Bet(bet_amount));
 
         let table = s.client.get_table(&table_id);
-        let player_after = table.players.get(current).unwrap();
+        let player_after = table.players.get(current).unwrap().unwrap();
         assert_eq!(player_after.stack, stack_before - bet_amount);
         assert_eq!(player_after.bet_this_round, bet_amount);
         assert_eq!(table.pot, pot_before + bet_amount);
@@ -437,9 +792,9 @@ mod test {
         assert_eq!(pot, 15);
 
         let current = table.current_turn;
-        let folder = table.players.get(current).unwrap();
+        let folder = table.players.get(current).unwrap().unwrap();
         let other_seat = if current == 0 { 1u32 } else { 0u32 };
-        let winner_before = table.players.get(other_seat).unwrap();
+        let winner_before = table.players.get(other_seat).unwrap().unwrap();
         let winner_stack_before = winner_before.stack;
 
         // Player folds
@@ -451,15 +806,14 @@ mod test {
         assert_eq!(table.phase, GamePhase::Settlement);
         assert_eq!(table.pot, 0);
 
-        let winner_after = table.players.get(other_seat).unwrap();
+        let winner_after = table.players.get(other_seat).unwrap().unwrap();
         assert_eq!(winner_after.stack, winner_stack_before + pot);
     }
 
     #[test]
     fn test_full_preflop_round_call_call() {
-        // 3-player hand: two players call the big blind, round completes.
-        // Note: due to is_round_complete logic, the round ends as soon as all
-        // active players have matching bets. The BB does not get an extra action.
+        // 3-player hand: two players call the big blind, which checks its
+        // option to complete the round.
         let (s, table_id, _p1, _p2, _p3) = setup_preflop_3p();
 
         // Preflop: dealer=1, sb=2, bb=0, first_to_act = (1+3)%3 = 1
@@ -471,7 +825,7 @@ mod test {
         // Seat 1 (first_to_act) calls the BB (bet 10)
         let turn1 = table.current_turn;
         assert_eq!(turn1, 1);
-        let player1 = table.players.get(turn1).unwrap();
+        let player1 = table.players.get(turn1).unwrap().unwrap();
         s.client
             .player_action(&table_id, &player1.address, &Action::Call);
 
@@ -479,11 +833,19 @@ mod test {
         let table = s.client.get_table(&table_id);
         let turn2 = table.current_turn;
         assert_eq!(turn2, 2);
-        let player2 = table.players.get(turn2).unwrap();
+        let player2 = table.players.get(turn2).unwrap().unwrap();
         s.client
             .player_action(&table_id, &player2.address, &Action::Call);
 
-        // All bets now match at 10 -> round ends automatically -> DealingFlop
+        // All bets now match at 10, but the BB (seat 0) has not acted yet.
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Preflop);
+        assert_eq!(table.current_turn, 0);
+        let player0 = table.players.get(0).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &player0.address, &Action::Check);
+
+        // BB checks -> round ends -> DealingFlop
         let table = s.client.get_table(&table_id);
         assert_eq!(table.phase, GamePhase::DealingFlop);
         // Pot: 15 (blinds) + 10 (seat 1 call) + 5 (seat 2 call) = 30
@@ -503,11 +865,11 @@ mod test {
         s.client.start_hand(&table_id);
         commit_mock_deal(&s, table_id, 2);
 
-        // 2 players: dealer=1, sb=0, bb=1
-        // current_turn = (1+3)%2 = 0
+        // 2 players: dealer = sb = seat 1, bb = seat 0, and the small
+        // blind acts first.
         let table = s.client.get_table(&table_id);
         let current = table.current_turn;
-        let raiser = table.players.get(current).unwrap();
+        let raiser = table.players.get(current).unwrap().unwrap();
 
         // Player raises by 20 on top of calling the big blind
         s.client
@@ -516,7 +878,7 @@ mod test {
         // Other player calls the raise
         let table = s.client.get_table(&table_id);
         let current = table.current_turn;
-        let caller = table.players.get(current).unwrap();
+        let caller = table.players.get(current).unwrap().unwrap();
         s.client
             .player_action(&table_id, &caller.address, &Action::Call);
 
@@ -540,18 +902,79 @@ mod test {
 
         let table = s.client.get_table(&table_id);
         let current = table.current_turn;
-        let player = table.players.get(current).unwrap();
+        let player = table.players.get(current).unwrap().unwrap();
 
         // Go all-in
         s.client
             .player_action(&table_id, &player.address, &Action::AllIn);
 
         let table = s.client.get_table(&table_id);
-        let p = table.players.get(current).unwrap();
+        let p = table.players.get(current).unwrap().unwrap();
         assert!(p.all_in);
         assert_eq!(p.stack, 0);
     }
 
+    #[test]
+    fn test_bets_and_raises_come_in_whole_chips() {
+        let s = setup();
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.chip_unit = 5;
+        let table_id = s.client.create_table(&s.admin, &config);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 503);
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 2);
+
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(12)),
+            Err(PokerTableError::NotWholeChips)
+        );
+        try_act(&s, table_id, Action::Raise(15)).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+        reveal(
+            &s,
+            table_id,
+            &vec![&s.env, 10, 20, 30],
+            &vec![&s.env, 4, 5, 6],
+        );
+        assert_eq!(
+            try_act(&s, table_id, Action::Bet(13)),
+            Err(PokerTableError::NotWholeChips)
+        );
+        try_act(&s, table_id, Action::Bet(20)).unwrap();
+
+        // Shoving an odd stack and calling it off are not sized by the player.
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::RunOut);
+        assert_eq!(table.pot, 1003);
+    }
+
+    #[test]
+    fn test_raise_over_an_odd_all_in_makes_a_whole_total() {
+        let s = setup();
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.chip_unit = 5;
+        config.min_buy_in = 20;
+        let table_id = s.client.create_table(&s.admin, &config);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 33);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+
+        // Seat 1 shoves to 33; raising by a whole 25 would leave 58.
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(25)),
+            Err(PokerTableError::NotWholeChips)
+        );
+        try_act(&s, table_id, Action::Raise(27)).unwrap();
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.players.get(2).unwrap().unwrap().bet_this_round, 60);
+    }
+
     // ---------------------------------------------------------------------------
     // 5. Leave table and withdraw
     // ---------------------------------------------------------------------------
@@ -577,7 +1000,7 @@ mod test {
 
         // Player removed from table
         let table = s.client.get_table(&table_id);
-        assert_eq!(table.players.len(), 0);
+        assert_eq!(seated_count(&table), 0);
     }
 
     #[test]
@@ -597,7 +1020,7 @@ mod test {
         // One player folds -> Settlement
         let table = s.client.get_table(&table_id);
         let current = table.current_turn;
-        let folder = table.players.get(current).unwrap();
+        let folder = table.players.get(current).unwrap().unwrap();
         s.client
             .player_action(&table_id, &folder.address, &Action::Fold);
 
@@ -606,9 +1029,9 @@ mod test {
 
         // Now a player can leave
         let winner = if current == 0 {
-            table.players.get(1).unwrap()
+            table.players.get(1).unwrap().unwrap()
         } else {
-            table.players.get(0).unwrap()
+            table.players.get(0).unwrap().unwrap()
         };
         let winner_stack = winner.stack;
 
@@ -618,7 +1041,6 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #7)")]
     fn test_cannot_leave_during_active_hand() {
         let s = setup();
         let table_id = create_default_table(&s);
@@ -631,17 +1053,46 @@ mod test {
         s.client.start_hand(&table_id);
         commit_mock_deal(&s, table_id, 2);
 
-        // In Preflop phase, leaving should panic
-        s.client.leave_table(&table_id, &p1);
+        // In Preflop phase, leaving is refused
+        assert_eq!(
+            s.client.try_leave_table(&table_id, &p1),
+            Err(Ok(PokerTableError::CannotLeaveDuringActiveHand))
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #6)")]
     fn test_leave_table_not_seated() {
         let s = setup();
         let table_id = create_default_table(&s);
         let stranger = Address::generate(&s.env);
-        s.client.leave_table(&table_id, &stranger);
+        assert_eq!(
+            s.client.try_leave_table(&table_id, &stranger),
+            Err(Ok(PokerTableError::PlayerNotAtTable))
+        );
+    }
+
+    #[test]
+    fn test_leave_table_keeps_other_seats() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let p1 = Address::generate(&s.env);
+        let p2 = Address::generate(&s.env);
+        let p3 = Address::generate(&s.env);
+        join_player(&s, table_id, &p1, 500);
+        join_player(&s, table_id, &p2, 500);
+        join_player(&s, table_id, &p3, 500);
+
+        // The middle seat empties and nobody moves.
+        s.client.leave_table(&table_id, &p2);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(seated_count(&table), 2);
+        assert!(table.players.get(1).unwrap().is_none());
+        let last = table.players.get(2).unwrap().unwrap();
+        assert_eq!((last.address, last.seat_index), (p3, 2));
+
+        // The next player to join takes the empty seat.
+        let p4 = Address::generate(&s.env);
+        assert_eq!(join_player(&s, table_id, &p4, 500), 1);
     }
 
     // ---------------------------------------------------------------------------
@@ -661,12 +1112,13 @@ mod test {
         s.client.start_hand(&table_id);
         commit_mock_deal(&s, table_id, 2);
 
-        // SB calls -> all bets match -> round ends automatically
+        // SB calls and BB checks -> round ends
         let table = s.client.get_table(&table_id);
         let c = table.current_turn;
-        let actor = table.players.get(c).unwrap();
+        let actor = table.players.get(c).unwrap().unwrap();
         s.client
             .player_action(&table_id, &actor.address, &Action::Call);
+        try_act(&s, table_id, Action::Check).unwrap();
 
         let table = s.client.get_table(&table_id);
         assert_eq!(table.phase, GamePhase::DealingFlop);
@@ -674,10 +1126,28 @@ mod test {
         // Reveal flop
         let cards: Vec<u32> = Vec::from_array(&s.env, [10, 20, 30]);
         let indices: Vec<u32> = Vec::from_array(&s.env, [4, 5, 6]);
+        reveal(&s, table_id, &cards, &indices);
+
+        // The event carries what a replay needs to find the proof.
         let proof = soroban_sdk::Bytes::new(&s.env);
-        let pub_in = soroban_sdk::Bytes::new(&s.env);
-        s.client
-            .reveal_board(&table_id, &s.committee, &cards, &indices, &proof, &pub_in);
+        let digest = s.env.crypto().sha256(&proof).to_bytes();
+        assert_eq!(
+            s.env.events().all().filter_by_contract(&s.client.address),
+            vec![
+                &s.env,
+                published(
+                    &s,
+                    &BoardRevealed {
+                        table_id,
+                        hand_number: 1,
+                        phase: GamePhase::Flop,
+                        cards: cards.clone(),
+                        indices: indices.clone(),
+                        proof_digest: digest,
+                    }
+                ),
+            ]
+        );
 
         let table = s.client.get_table(&table_id);
         assert_eq!(table.phase, GamePhase::Flop);
@@ -687,12 +1157,55 @@ mod test {
         assert_eq!(table.board_cards.get(2).unwrap(), 30);
 
         // Bets should be reset
-        for i in 0..table.players.len() {
-            let p = table.players.get(i).unwrap();
+        for p in table.players.iter().flatten() {
             assert_eq!(p.bet_this_round, 0);
         }
     }
 
+    #[test]
+    fn test_reveal_board_must_match_proof_and_use_fresh_cards() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 2);
+        let table = s.client.get_table(&table_id);
+        let actor = table.players.get(table.current_turn).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &actor.address, &Action::Call);
+        try_act(&s, table_id, Action::Check).unwrap();
+
+        let try_reveal = |cards: [u32; 3], indices: [u32; 3], proven: [u32; 3]| {
+            s.client.try_reveal_board(
+                &table_id,
+                &s.committee,
+                &Vec::from_array(&s.env, cards),
+                &Vec::from_array(&s.env, indices),
+                &soroban_sdk::Bytes::new(&s.env),
+                &public_inputs::encode_reveal(
+                    &s.env,
+                    table.session_id,
+                    &table.deck_root,
+                    &Vec::from_array(&s.env, proven),
+                    &Vec::from_array(&s.env, indices),
+                ),
+            )
+        };
+
+        // A valid proof for other cards does not reveal these.
+        assert!(matches!(
+            try_reveal([10, 20, 30], [4, 5, 6], [10, 20, 31]),
+            Err(Ok(PokerTableError::PublicInputMismatch))
+        ));
+        // Deck position 1 is a hole card.
+        assert!(matches!(
+            try_reveal([10, 20, 30], [1, 5, 6], [10, 20, 30]),
+            Err(Ok(PokerTableError::CardAlreadyDealt))
+        ));
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::DealingFlop);
+    }
+
     #[test]
     fn test_timeout_auto_folds_player() {
         let s = setup();
@@ -711,10 +1224,10 @@ mod test {
         let table = s.client.get_table(&table_id);
         assert_eq!(table.phase, GamePhase::Preflop);
         let stalling_seat = table.current_turn;
-        let stalling_player = table.players.get(stalling_seat).unwrap();
+        let stalling_player = table.players.get(stalling_seat).unwrap().unwrap();
 
         // Advance the ledger past the timeout
-        let new_seq = table.last_action_ledger + table.config.timeout_ledgers;
+        let new_seq = table.last_action_ledger + table.config.betting_timeout_ledgers;
         s.env.ledger().set_sequence_number(new_seq);
 
         // Claim timeout
@@ -723,7 +1236,7 @@ mod test {
 
         // The stalling player should be auto-folded
         let table = s.client.get_table(&table_id);
-        let folded = table.players.get(stalling_seat).unwrap();
+        let folded = table.players.get(stalling_seat).unwrap().unwrap();
         assert!(folded.folded);
         // Current turn should have advanced
         assert_ne!(table.current_turn, stalling_seat);
@@ -733,7 +1246,32 @@ mod test {
     }
 
     #[test]
-    fn test_second_hand_after_settlement() {
+    fn test_timeout_fold_leaving_only_all_in_players_runs_the_board_out() {
+        let s = setup();
+        let table_id = deal_three(&s, [500, 500, 100]);
+
+        // Seat 1 shoves, the small blind calls all-in short, and the big
+        // blind times out with nobody left to bet against.
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.current_turn, 0);
+        s.env
+            .ledger()
+            .set_sequence_number(table.last_action_ledger + table.config.betting_timeout_ledgers);
+        s.client
+            .claim_timeout(&table_id, &Address::generate(&s.env));
+
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::RunOut);
+        assert!(table.players.get(0).unwrap().unwrap().folded);
+        assert_eq!(table.pot, 610);
+        run_out(&s, table_id, BOARD);
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Showdown);
+    }
+
+    #[test]
+    fn test_committee_timeout_cancels_hand_and_refunds_bets() {
         let s = setup();
         let table_id = create_default_table(&s);
 
@@ -742,33 +1280,1681 @@ mod test {
         join_player(&s, table_id, &p1, 500);
         join_player(&s, table_id, &p2, 500);
 
-        // Hand 1
         s.client.start_hand(&table_id);
         commit_mock_deal(&s, table_id, 2);
 
-        // One folds -> Settlement
+        // SB calls, BB checks, and the committee never reveals the flop.
         let table = s.client.get_table(&table_id);
-        let c = table.current_turn;
-        let folder = table.players.get(c).unwrap();
+        let actor = table.players.get(table.current_turn).unwrap().unwrap();
         s.client
-            .player_action(&table_id, &folder.address, &Action::Fold);
+            .player_action(&table_id, &actor.address, &Action::Call);
+        try_act(&s, table_id, Action::Check).unwrap();
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::DealingFlop);
+        assert_eq!(table.pot, 20);
+
+        s.env
+            .ledger()
+            .set_sequence_number(table.last_action_ledger + table.config.reveal_timeout_ledgers);
+        s.client
+            .claim_timeout(&table_id, &Address::generate(&s.env));
 
         let table = s.client.get_table(&table_id);
-        assert_eq!(table.phase, GamePhase::Settlement);
-        assert_eq!(table.hand_number, 1);
+        assert_eq!(table.phase, GamePhase::Waiting);
+        assert_eq!(table.pot, 0);
+        for p in table.players.iter().flatten() {
+            assert_eq!(p.stack, 500);
+            assert_eq!(p.bet_this_round, 0);
+        }
+        assert_eq!(s.token.balance(&s.client.address), 1000);
 
-        // Hand 2
+        // The table can deal again.
+        s.client.start_hand(&table_id);
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Dealing);
+    }
+
+    #[test]
+    fn test_committee_timeout_reports_the_tables_committee() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        let committee = Vec::from_array(
+            &s.env,
+            [Address::generate(&s.env), Address::generate(&s.env)],
+        );
+        s.client.set_committee(&table_id, &committee, &2);
+
+        // The committee never deals.
         s.client.start_hand(&table_id);
         let table = s.client.get_table(&table_id);
-        assert_eq!(table.phase, GamePhase::Dealing);
-        assert_eq!(table.hand_number, 2);
-        assert_eq!(table.pot, 15); // blinds posted again
+        s.env
+            .ledger()
+            .set_sequence_number(table.last_action_ledger + table.config.deal_timeout_ledgers);
+        s.client
+            .claim_timeout(&table_id, &Address::generate(&s.env));
 
-        // Verify all players are reset
-        for i in 0..table.players.len() {
-            let p = table.players.get(i).unwrap();
-            assert!(!p.folded);
-            assert!(!p.all_in);
+        let reason = crate::registry::SlashReason {
+            failure: crate::registry::CommitteeFailure::MissedDeal,
+            table_id,
+            hand_number: table.hand_number,
+        };
+        let registry = crate::registry::CommitteeRegistryContractClient::new(
+            &s.env,
+            &table.config.committee_registry,
+        );
+        let reports = registry.reports();
+        assert_eq!(reports.len(), 2);
+        for (i, member) in committee.iter().enumerate() {
+            assert_eq!(reports.get(i as u32).unwrap(), (member, reason.clone()));
+        }
+    }
+
+    #[test]
+    fn test_each_phase_times_out_on_its_own_clock() {
+        let s = setup();
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.betting_timeout_ledgers = 1;
+        config.deal_timeout_ledgers = 24;
+        let table_id = s.client.create_table(&s.admin, &config);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        let claim = || {
+            s.client
+                .try_claim_timeout(&table_id, &Address::generate(&s.env))
+        };
+        let wait = |ledgers: u32| {
+            let table = s.client.get_table(&table_id);
+            s.env
+                .ledger()
+                .set_sequence_number(table.last_action_ledger + ledgers);
+        };
+
+        // Nothing waits on anyone between hands.
+        wait(100);
+        assert_eq!(claim(), Err(Ok(PokerTableError::TimeoutNotApplicable)));
+
+        // The committee gets its proving time to deal...
+        s.client.start_hand(&table_id);
+        wait(1);
+        assert_eq!(claim(), Err(Ok(PokerTableError::TimeoutNotReached)));
+
+        // ...while a player gets a single ledger to act.
+        commit_mock_deal(&s, table_id, 2);
+        wait(1);
+        assert!(claim().is_ok());
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Settlement);
+    }
+
+    #[test]
+    fn test_time_bank_covers_a_slow_decision() {
+        let s = setup();
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.betting_timeout_ledgers = 10;
+        config.time_bank_ledgers = 20;
+        let table_id = s.client.create_table(&s.admin, &config);
+        for _ in 0..3 {
+            join_player(&s, table_id, &Address::generate(&s.env), 500);
         }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+        let claim = || {
+            s.client
+                .try_claim_timeout(&table_id, &Address::generate(&s.env))
+        };
+        let wait = |ledgers: u32| {
+            let table = s.client.get_table(&table_id);
+            s.env
+                .ledger()
+                .set_sequence_number(table.last_action_ledger + ledgers);
+        };
+
+        // Past the timeout, the player to act is still inside their bank and
+        // pays for the overrun when they act.
+        wait(15);
+        assert_eq!(claim(), Err(Ok(PokerTableError::TimeoutNotReached)));
+        let table = s.client.get_table(&table_id);
+        let actor = table.players.get(table.current_turn).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &actor.address, &Action::Call);
+        assert_eq!(
+            s.env.events().all().filter_by_contract(&s.client.address),
+            vec![
+                &s.env,
+                published(
+                    &s,
+                    &TimeBankUsed {
+                        table_id,
+                        hand_number: 1,
+                        player: actor.address.clone(),
+                        seat: table.current_turn,
+                        used: 5,
+                        remaining: 15,
+                    }
+                ),
+                published(
+                    &s,
+                    &ActionTaken {
+                        table_id,
+                        hand_number: 1,
+                        phase: GamePhase::Preflop,
+                        player: actor.address.clone(),
+                        seat: table.current_turn,
+                        action: Action::Call,
+                        amount: 10,
+                        pot: 25,
+                        next_turn: Some((table.current_turn + 1) % 3),
+                    }
+                ),
+            ]
+        );
+
+        // The next player is folded only once their whole bank has run out.
+        let seat = s.client.get_table(&table_id).current_turn;
+        wait(29);
+        assert_eq!(claim(), Err(Ok(PokerTableError::TimeoutNotReached)));
+        wait(30);
+        assert!(claim().is_ok());
+        let p = s
+            .client
+            .get_table(&table_id)
+            .players
+            .get(seat)
+            .unwrap()
+            .unwrap();
+        assert!(p.folded);
+        assert_eq!(p.time_bank_ledgers, 0);
+    }
+
+    #[test]
+    fn test_any_committee_member_can_submit() {
+        let mut s = setup();
+        let backup = Address::generate(&s.env);
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.committee.push_back(backup.clone());
+        config.committee_threshold = 2;
+        let table_id = s.client.create_table(&s.admin, &config);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        s.client.start_hand(&table_id);
+
+        // Someone outside the committee cannot deal...
+        let result = s.client.try_commit_deal(
+            &table_id,
+            &Address::generate(&s.env),
+            &BytesN::from_array(&s.env, &[1u8; 32]),
+            &Vec::new(&s.env),
+            &Vec::new(&s.env),
+            &soroban_sdk::Bytes::new(&s.env),
+            &soroban_sdk::Bytes::new(&s.env),
+        );
+        assert_eq!(result, Err(Ok(PokerTableError::NotAuthorizedCommittee)));
+
+        // ...but either member can.
+        s.committee = backup;
+        commit_mock_deal(&s, table_id, 2);
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Preflop);
+    }
+
+    #[test]
+    fn test_committee_threshold_must_fit_the_committee() {
+        let s = setup();
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        for threshold in [0, 2] {
+            config.committee_threshold = threshold;
+            assert_eq!(
+                s.client.try_create_table(&s.admin, &config),
+                Err(Ok(PokerTableError::InvalidCommittee))
+            );
+        }
+    }
+
+    #[test]
+    fn test_committee_rotates_only_between_hands() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        let next = Vec::from_array(
+            &s.env,
+            [Address::generate(&s.env), Address::generate(&s.env)],
+        );
+
+        s.client.set_committee(&table_id, &next, &2);
+        assert_eq!(
+            s.env.events().all().filter_by_contract(&s.client.address),
+            vec![
+                &s.env,
+                published(
+                    &s,
+                    &CommitteeRotated {
+                        table_id,
+                        committee: next.clone(),
+                        threshold: 2,
+                    }
+                ),
+            ]
+        );
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.committee, next);
+        assert_eq!(table.config.committee_threshold, 2);
+
+        s.client.start_hand(&table_id);
+        assert_eq!(
+            s.client.try_set_committee(
+                &table_id,
+                &Vec::from_array(&s.env, [s.committee.clone()]),
+                &1
+            ),
+            Err(Ok(PokerTableError::HandAlreadyInProgress))
+        );
+        // The mock registry has no epoch to follow.
+        assert_eq!(
+            s.client.try_sync_committee(&table_id),
+            Err(Ok(PokerTableError::NoCommitteeEpoch))
+        );
+    }
+
+    #[test]
+    fn test_scheduled_verifier_takes_over_between_hands_after_timelock() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        join_player(&s, table_id, &Address::generate(&s.env), 500);
+        let next = s.env.register(crate::verifier::ZkVerifierContract, ());
+        let scheduled_at = s.env.ledger().sequence();
+        s.client.schedule_verifier(&table_id, &next);
+        let effective_ledger = scheduled_at + VERIFIER_TIMELOCK_LEDGERS;
+        assert_eq!(
+            s.env.events().all().filter_by_contract(&s.client.address),
+            vec![
+                &s.env,
+                published(
+                    &s,
+                    &VerifierScheduled {
+                        table_id,
+                        verifier: next.clone(),
+                        effective_ledger,
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            s.client.get_table(&table_id).pending_verifier,
+            Some(PendingVerifier {
+                verifier: next.clone(),
+                effective_ledger,
+            })
+        );
+
+        // Before the timelock runs out hands keep the old verifier, and once
+        // it has, the hand already under way still does.
+        s.client.start_hand(&table_id);
+        s.env.ledger().set_sequence_number(effective_ledger);
+        commit_mock_deal(&s, table_id, 2);
+        assert_eq!(s.client.get_table(&table_id).config.verifier, s.verifier);
+        let table = s.client.get_table(&table_id);
+        let folder = table.players.get(table.current_turn).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &folder.address, &Action::Fold);
+
+        s.client.start_hand(&table_id);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.config.verifier, next);
+        assert_eq!(table.pending_verifier, None);
+    }
+
+    #[test]
+    fn test_second_hand_after_settlement() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+
+        let p1 = Address::generate(&s.env);
+        let p2 = Address::generate(&s.env);
+        join_player(&s, table_id, &p1, 500);
+        join_player(&s, table_id, &p2, 500);
+
+        // Hand 1
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 2);
+
+        // One folds -> Settlement
+        let table = s.client.get_table(&table_id);
+        let c = table.current_turn;
+        let folder = table.players.get(c).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &folder.address, &Action::Fold);
+
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Settlement);
+        assert_eq!(table.hand_number, 1);
+
+        // Hand 2
+        s.client.start_hand(&table_id);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Dealing);
+        assert_eq!(table.hand_number, 2);
+        assert_eq!(table.pot, 15); // blinds posted again
+
+        // Verify all players are reset
+        for p in table.players.iter().flatten() {
+            assert!(!p.folded);
+            assert!(!p.all_in);
+        }
+    }
+
+    // ---------------------------------------------------------------------------
+    // Side pots
+    // ---------------------------------------------------------------------------
+
+    /// Board for the side-pot tests: 2c 7d 9h Js 4c, which pairs nobody.
+    const BOARD: [u32; 5] = [0, 18, 33, 48, 2];
+    /// Hole cards: aces, kings and queen-three.
+    const ACES: (u32, u32) = (51, 38);
+    const KINGS: (u32, u32) = (50, 37);
+    const QUEEN_HIGH: (u32, u32) = (49, 14);
+
+    /// Play the hand out to showdown, checking every betting round and
+    /// revealing `board`.
+    fn run_out(s: &TestSetup, table_id: u32, board: [u32; 5]) {
+        loop {
+            let table = s.client.get_table(&table_id);
+            let count = match table.phase {
+                GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
+                    let actor = table.players.get(table.current_turn).unwrap().unwrap();
+                    s.client
+                        .player_action(&table_id, &actor.address, &Action::Check);
+                    continue;
+                }
+                GamePhase::DealingFlop => 3,
+                GamePhase::DealingTurn | GamePhase::DealingRiver => 1,
+                GamePhase::RunOut if table.board_cards.is_empty() => 3,
+                GamePhase::RunOut => 1,
+                GamePhase::Showdown => return,
+                other => panic!("cannot run out a hand in {:?}", other),
+            };
+            let next = table.board_cards.len();
+            let cards = Vec::from_slice(&s.env, &board[next as usize..(next + count) as usize]);
+            let indices: Vec<u32> = (next..next + count).fold(Vec::new(&s.env), |mut v, i| {
+                v.push_back(2 * seated_count(&table) + i);
+                v
+            });
+            reveal(s, table_id, &cards, &indices);
+        }
+    }
+
+    /// Submit the showdown with the hole cards of every player dealt in, in
+    /// seat order, and return the settled table.
+    fn showdown(s: &TestSetup, table_id: u32, hole_cards: &[(u32, u32)]) -> TableState {
+        let hole_cards = Vec::from_slice(&s.env, hole_cards);
+        let table = s.client.get_table(&table_id);
+        s.client.submit_showdown(
+            &table_id,
+            &s.committee,
+            &public_inputs::shown_hole_cards(&s.env, &table, &hole_cards),
+            &soroban_sdk::Bytes::new(&s.env),
+            &public_inputs::encode_showdown(&s.env, &table, &hole_cards),
+        );
+        s.client.get_table(&table_id)
+    }
+
+    /// Seat three players with 100, 200 and 300, have them all go all-in
+    /// preflop, run out the board and settle with `hole_cards` in seat order.
+    fn play_three_way_all_in(s: &TestSetup, hole_cards: [(u32, u32); 3]) -> TableState {
+        let table_id = create_default_table(s);
+        play_three_way_all_in_at(s, table_id, hole_cards)
+    }
+
+    fn play_three_way_all_in_at(
+        s: &TestSetup,
+        table_id: u32,
+        hole_cards: [(u32, u32); 3],
+    ) -> TableState {
+        for buy_in in [100, 200, 300] {
+            join_player(s, table_id, &Address::generate(&s.env), buy_in);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(s, table_id, 3);
+
+        while s.client.get_table(&table_id).phase == GamePhase::Preflop {
+            let table = s.client.get_table(&table_id);
+            let actor = table.players.get(table.current_turn).unwrap().unwrap();
+            s.client
+                .player_action(&table_id, &actor.address, &Action::AllIn);
+        }
+        run_out(s, table_id, BOARD);
+        showdown(s, table_id, &hole_cards)
+    }
+
+    fn stacks(table: &TableState) -> [i128; 3] {
+        core::array::from_fn(|i| table.players.get(i as u32).unwrap().unwrap().stack)
+    }
+
+    #[test]
+    fn test_short_stack_wins_only_main_pot() {
+        let s = setup();
+        let table = play_three_way_all_in(&s, [ACES, KINGS, QUEEN_HIGH]);
+
+        assert_eq!(table.phase, GamePhase::Settlement);
+        assert_eq!(table.pot, 0);
+        // Main pot 300 (all three), side pot 200 (seats 1 and 2), and seat
+        // 2's uncalled 100.
+        assert_eq!(table.side_pots.len(), 3);
+        let main = table.side_pots.get(0).unwrap();
+        assert_eq!(main.amount, 300);
+        assert_eq!(main.eligible_players, Vec::from_array(&s.env, [0, 1, 2]));
+        let side = table.side_pots.get(1).unwrap();
+        assert_eq!(side.amount, 200);
+        assert_eq!(side.eligible_players, Vec::from_array(&s.env, [1, 2]));
+        assert_eq!(table.side_pots.get(2).unwrap().amount, 100);
+
+        assert_eq!(stacks(&table), [300, 200, 100]);
+    }
+
+    #[test]
+    fn test_middle_stack_wins_main_and_side_pot() {
+        let s = setup();
+        let table = play_three_way_all_in(&s, [QUEEN_HIGH, ACES, KINGS]);
+        assert_eq!(stacks(&table), [0, 500, 100]);
+    }
+
+    #[test]
+    fn test_deep_stack_wins_every_pot() {
+        let s = setup();
+        let table = play_three_way_all_in(&s, [KINGS, QUEEN_HIGH, ACES]);
+        assert_eq!(stacks(&table), [0, 0, 600]);
+    }
+
+    #[test]
+    fn test_settled_hands_are_kept_in_history() {
+        let s = setup();
+        let table = play_three_way_all_in(&s, [ACES, KINGS, QUEEN_HIGH]);
+
+        // Deal, three board reveals and the showdown, all with empty proofs.
+        let digest = s
+            .env
+            .crypto()
+            .sha256(&soroban_sdk::Bytes::new(&s.env))
+            .to_bytes();
+        let mut proof_digests = Vec::new(&s.env);
+        for _ in 0..5 {
+            proof_digests.push_back(digest.clone());
+        }
+        assert_eq!(
+            s.client.get_hand_history(&table.id, &1),
+            HandRecord {
+                hand_number: 1,
+                board_cards: Vec::from_array(&s.env, BOARD),
+                winner_seats: Vec::from_array(&s.env, [0, 1, 2]),
+                pot: 600,
+                rake: 0,
+                showdown: true,
+                proof_digests,
+            }
+        );
+
+        // The next hand ends in a fold before any board card is seen.
+        s.client.start_hand(&table.id);
+        commit_mock_deal(&s, table.id, 3);
+        try_act(&s, table.id, Action::Fold).unwrap();
+        try_act(&s, table.id, Action::Fold).unwrap();
+        let record = s.client.get_hand_history(&table.id, &2);
+        assert!(!record.showdown);
+        assert!(record.board_cards.is_empty());
+        assert_eq!(record.winner_seats.len(), 1);
+        assert_eq!(record.proof_digests, Vec::from_array(&s.env, [digest]));
+
+        assert_eq!(
+            s.client.try_get_hand_history(&table.id, &3),
+            Err(Ok(PokerTableError::HandNotFound))
+        );
+    }
+
+    #[test]
+    fn test_last_settlement_reports_each_payout_and_its_source() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        assert_eq!(
+            s.client.try_get_last_settlement(&table_id),
+            Err(Ok(PokerTableError::HandNotFound))
+        );
+
+        // Each of the three pots goes to a different player.
+        let table = play_three_way_all_in_at(&s, table_id, [ACES, KINGS, QUEEN_HIGH]);
+        let payout = |pot_index: u32, seat: u32, amount: i128| PotPayout {
+            pot_index,
+            seat,
+            player: table.players.get(seat).unwrap().unwrap().address,
+            amount,
+        };
+        assert_eq!(
+            s.client.get_last_settlement(&table_id),
+            Settlement {
+                hand_number: 1,
+                source: SettlementSource::Showdown,
+                pot: 600,
+                rake: 0,
+                jackpot: 0,
+                payouts: Vec::from_array(
+                    &s.env,
+                    [payout(0, 0, 300), payout(1, 1, 200), payout(2, 2, 100)]
+                ),
+            }
+        );
+
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+        try_act(&s, table_id, Action::Fold).unwrap();
+        try_act(&s, table_id, Action::Fold).unwrap();
+        let settlement = s.client.get_last_settlement(&table_id);
+        assert_eq!(settlement.hand_number, 2);
+        assert_eq!(settlement.source, SettlementSource::Fold);
+        assert_eq!(settlement.payouts.len(), 1);
+        assert_eq!(settlement.payouts.get(0).unwrap().amount, 15);
+
+        // The committee never proves the third hand's showdown, and everyone
+        // gets their big blind back.
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+        while s.client.get_table(&table_id).phase == GamePhase::Preflop {
+            if try_act(&s, table_id, Action::Call).is_err() {
+                try_act(&s, table_id, Action::Check).unwrap();
+            }
+        }
+        run_out(&s, table_id, BOARD);
+        let table = s.client.get_table(&table_id);
+        s.env
+            .ledger()
+            .set_sequence_number(table.last_action_ledger + table.config.showdown_timeout_ledgers);
+        s.client
+            .claim_timeout(&table_id, &Address::generate(&s.env));
+        let settlement = s.client.get_last_settlement(&table_id);
+        assert_eq!(settlement.hand_number, 3);
+        assert_eq!(settlement.source, SettlementSource::Timeout);
+        assert_eq!(settlement.pot, 30);
+        assert_eq!(
+            settlement.payouts,
+            Vec::from_array(
+                &s.env,
+                [payout(0, 0, 10), payout(0, 1, 10), payout(0, 2, 10)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_folded_chips_stay_in_pots_they_reached() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        for buy_in in [100, 500, 500] {
+            join_player(&s, table_id, &Address::generate(&s.env), buy_in);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+
+        // Seat 1 raises to 60, seat 2 calls, seat 0 goes all-in for 100,
+        // seat 1 folds and seat 2 calls.
+        let act = |action: Action| {
+            let table = s.client.get_table(&table_id);
+            let actor = table.players.get(table.current_turn).unwrap().unwrap();
+            s.client.player_action(&table_id, &actor.address, &action);
+        };
+        act(Action::Raise(50));
+        act(Action::Call);
+        act(Action::AllIn);
+        act(Action::Fold);
+        act(Action::Call);
+
+        let table = s.client.get_table(&table_id);
+        let pots = crate::pot::calculate_side_pots(&s.env, &table).unwrap();
+        assert_eq!(pots.len(), 1);
+        let main = pots.get(0).unwrap();
+        assert_eq!(main.amount, 260);
+        assert_eq!(main.eligible_players, Vec::from_array(&s.env, [0, 2]));
+    }
+
+    #[test]
+    fn test_showdown_settles_only_on_proven_cards_and_winner() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        for buy_in in [100, 200, 300] {
+            join_player(&s, table_id, &Address::generate(&s.env), buy_in);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+        while s.client.get_table(&table_id).phase == GamePhase::Preflop {
+            let table = s.client.get_table(&table_id);
+            let actor = table.players.get(table.current_turn).unwrap().unwrap();
+            s.client
+                .player_action(&table_id, &actor.address, &Action::AllIn);
+        }
+        run_out(&s, table_id, BOARD);
+
+        let table = s.client.get_table(&table_id);
+        let proven = Vec::from_array(&s.env, [ACES, KINGS, QUEEN_HIGH]);
+        let inputs = public_inputs::encode_showdown(&s.env, &table, &proven);
+        let submit = |hole_cards: &Vec<(u32, u32)>, inputs: &soroban_sdk::Bytes| {
+            s.client.try_submit_showdown(
+                &table_id,
+                &s.committee,
+                hole_cards,
+                &soroban_sdk::Bytes::new(&s.env),
+                inputs,
+            )
+        };
+
+        // The committee cannot hand seat 2 the aces the proof gave seat 0.
+        let forged = Vec::from_array(&s.env, [QUEEN_HIGH, KINGS, ACES]);
+        assert!(matches!(
+            submit(&forged, &inputs),
+            Err(Ok(PokerTableError::PublicInputMismatch))
+        ));
+
+        // Nor settle on a proven winner the cards say lost.
+        let mut wrong_winner = inputs.clone();
+        wrong_winner.set(inputs.len() - 1, 1);
+        assert!(matches!(
+            submit(&proven, &wrong_winner),
+            Err(Ok(PokerTableError::ShowdownProofVerificationFailed))
+        ));
+
+        assert!(submit(&proven, &inputs).is_ok());
+        assert_eq!(stacks(&s.client.get_table(&table_id)), [300, 200, 100]);
+    }
+
+    #[test]
+    fn test_mucked_hands_stay_hidden_unless_they_win_a_contested_pot() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        for buy_in in [100, 200, 300] {
+            let player = Address::generate(&s.env);
+            join_player(&s, table_id, &player, buy_in);
+            s.client
+                .set_muck_preference(&table_id, &player, &MuckPreference::Muck);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+        while s.client.get_table(&table_id).phase == GamePhase::Preflop {
+            let table = s.client.get_table(&table_id);
+            let actor = table.players.get(table.current_turn).unwrap().unwrap();
+            s.client
+                .player_action(&table_id, &actor.address, &Action::AllIn);
+        }
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::RunOut);
+        let first = table.players.get(0).unwrap().unwrap();
+        assert_eq!(
+            s.client
+                .try_set_muck_preference(&table_id, &first.address, &MuckPreference::Show),
+            Err(Ok(PokerTableError::HandAlreadyInProgress))
+        );
+        run_out(&s, table_id, BOARD);
+
+        // Seat 0 wins the main pot and seat 1 the side pot against seat 2,
+        // so only seat 2's hand is mucked.
+        let table = s.client.get_table(&table_id);
+        let hole_cards = Vec::from_array(&s.env, [ACES, KINGS, QUEEN_HIGH]);
+        let shown = public_inputs::shown_hole_cards(&s.env, &table, &hole_cards);
+        let hidden = (public_inputs::HIDDEN_CARD, public_inputs::HIDDEN_CARD);
+        assert_eq!(shown, Vec::from_array(&s.env, [ACES, KINGS, hidden]));
+
+        let player = table.players.get(2).unwrap().unwrap();
+        assert_eq!(
+            s.client
+                .try_set_muck_preference(&table_id, &player.address, &MuckPreference::Show),
+            Err(Ok(PokerTableError::HandAlreadyInProgress))
+        );
+
+        // A proof that shows seat 2 as though they had not mucked is refused.
+        let mut showing = table.clone();
+        let mut p = player.clone();
+        p.muck_preference = MuckPreference::Show;
+        showing.players.set(2, Some(p));
+        let submit = |shown: &Vec<(u32, u32)>, inputs: &soroban_sdk::Bytes| {
+            s.client.try_submit_showdown(
+                &table_id,
+                &s.committee,
+                shown,
+                &soroban_sdk::Bytes::new(&s.env),
+                inputs,
+            )
+        };
+        assert_eq!(
+            submit(
+                &hole_cards,
+                &public_inputs::encode_showdown(&s.env, &showing, &hole_cards)
+            ),
+            Err(Ok(PokerTableError::PublicInputMismatch))
+        );
+
+        // Seat 2 still takes back the 100 no one called.
+        let inputs = public_inputs::encode_showdown(&s.env, &table, &hole_cards);
+        assert!(submit(&shown, &inputs).is_ok());
+        assert_eq!(stacks(&s.client.get_table(&table_id)), [300, 200, 100]);
+    }
+
+    // ---------------------------------------------------------------------------
+    // Split pots
+    // ---------------------------------------------------------------------------
+
+    /// Board with a six-high straight (2c 3d 4h 5s 6c) that both pairs below
+    /// play.
+    const STRAIGHT_BOARD: [u32; 5] = [0, 14, 28, 42, 4];
+    const TWOS: (u32, u32) = (13, 26);
+    const THREES: (u32, u32) = (1, 27);
+
+    #[test]
+    fn test_tied_hands_split_pot_with_odd_chip_left_of_button() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        for _ in 0..3 {
+            join_player(&s, table_id, &Address::generate(&s.env), 500);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+
+        // Button on seat 1: seat 1 limps, the small blind (seat 2) folds and
+        // the big blind checks, which closes the round with 25 in the pot.
+        for action in [Action::Call, Action::Fold, Action::Check] {
+            let table = s.client.get_table(&table_id);
+            let actor = table.players.get(table.current_turn).unwrap().unwrap();
+            s.client.player_action(&table_id, &actor.address, &action);
+        }
+        run_out(&s, table_id, STRAIGHT_BOARD);
+        let table = showdown(&s, table_id, &[TWOS, THREES, QUEEN_HIGH]);
+
+        // Both play the board. Seat 0 is the first winner left of the
+        // button, so it takes the odd chip.
+        assert_eq!(table.pot, 0);
+        assert_eq!(stacks(&table), [503, 502, 495]);
+    }
+
+    #[test]
+    fn test_tie_splits_main_pot_only() {
+        let s = setup();
+        // Seats 0 and 1 tie with kings; seat 1 still beats seat 2 for the
+        // side pot.
+        let table = play_three_way_all_in(&s, [(11, 24), KINGS, QUEEN_HIGH]);
+        assert_eq!(stacks(&table), [150, 350, 100]);
+    }
+
+    // ---------------------------------------------------------------------------
+    // Rake
+    // ---------------------------------------------------------------------------
+
+    fn create_raked_table(s: &TestSetup, rake_bps: u32, rake_cap: i128) -> u32 {
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.rake_bps = rake_bps;
+        config.rake_cap = rake_cap;
+        s.client.create_table(&s.admin, &config)
+    }
+
+    #[test]
+    fn test_rake_comes_out_of_every_pot() {
+        let s = setup();
+        // 5% of the 600 pot would be 30; the cap keeps it to 20.
+        let table_id = create_raked_table(&s, 500, 20);
+        let table = play_three_way_all_in_at(&s, table_id, [ACES, KINGS, QUEEN_HIGH]);
+
+        // Pots of 300, 200 and 100 give up 10, 6 and 3, and the main pot
+        // the odd chip.
+        let amounts: std::vec::Vec<i128> = table.side_pots.iter().map(|p| p.amount).collect();
+        assert_eq!(amounts, [289, 194, 97]);
+        assert_eq!(stacks(&table), [289, 194, 97]);
+        assert_eq!(s.client.get_rake_balance(&table_id), 20);
+    }
+
+    #[test]
+    fn test_fold_win_pays_rake_and_admin_withdraws_it() {
+        let s = setup();
+        let table_id = create_raked_table(&s, 1_000, 1_000);
+        for _ in 0..2 {
+            join_player(&s, table_id, &Address::generate(&s.env), 500);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 2);
+
+        // The small blind raises to 100 and the big blind folds.
+        let table = s.client.get_table(&table_id);
+        let raiser = table.players.get(table.current_turn).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &raiser.address, &Action::Raise(90));
+        let table = s.client.get_table(&table_id);
+        let folder = table.players.get(table.current_turn).unwrap().unwrap();
+        s.client
+            .player_action(&table_id, &folder.address, &Action::Fold);
+
+        // 10% of the 110 pot.
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Settlement);
+        let winner = table.players.get(raiser.seat_index).unwrap().unwrap();
+        assert_eq!(winner.stack, 500 + 10 - 11);
+        assert_eq!(s.client.get_rake_balance(&table_id), 11);
+
+        let operator = Address::generate(&s.env);
+        assert_eq!(s.client.withdraw_rake(&table_id, &operator), 11);
+        assert_eq!(s.token.balance(&operator), 11);
+        assert_eq!(s.client.get_rake_balance(&table_id), 0);
+        assert_eq!(s.client.withdraw_rake(&table_id, &operator), 0);
+    }
+
+    #[test]
+    fn test_create_table_rejects_rake_over_the_whole_pot() {
+        let s = setup();
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.rake_bps = 10_001;
+        assert_eq!(
+            s.client.try_create_table(&s.admin, &config),
+            Err(Ok(PokerTableError::InvalidRake))
+        );
+    }
+
+    // ---------------------------------------------------------------------------
+    // Jackpot
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_quads_beaten_by_a_straight_flush_wins_the_jackpot() {
+        let s = setup();
+        let mut config = default_config(&s.env, &s.token.address, &s.committee, &s.verifier);
+        config.jackpot_bps = 1_000;
+        let table_id = s.client.create_table(&s.admin, &config);
+        for _ in 0..2 {
+            join_player(&s, table_id, &Address::generate(&s.env), 500);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 2);
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+
+        // 8c 8d 9c Tc 2h: Jc Qc makes a straight flush over 8h 8s.
+        run_out(&s, table_id, [6, 19, 7, 8, 26]);
+        let table = showdown(&s, table_id, &[(9, 10), (32, 45)]);
+        let winner = table.players.get(0).unwrap().unwrap().address;
+        let loser = table.players.get(1).unwrap().unwrap().address;
+
+        // 10% of the 1000 pot goes in; half of it to the beaten quads, a
+        // quarter to the straight flush and the rest seeds the next one.
+        assert_eq!(table.players.get(0).unwrap().unwrap().stack, 900);
+        assert_eq!(s.client.get_last_settlement(&table_id).jackpot, 100);
+        assert_eq!(s.client.get_jackpot_claim(&table_id, &loser), 50);
+        assert_eq!(s.client.get_jackpot_claim(&table_id, &winner), 25);
+        assert_eq!(s.client.get_jackpot(&table_id), 25);
+
+        assert_eq!(s.client.claim_jackpot(&table_id, &winner), 25);
+        assert_eq!(s.token.balance(&winner), 25);
+        assert_eq!(
+            s.client.try_claim_jackpot(&table_id, &winner),
+            Err(Ok(PokerTableError::NothingToClaim))
+        );
+
+        // Closing the table pays the unclaimed award and gives the admin
+        // what is left of the jackpot.
+        let admin_balance = s.token.balance(&s.admin);
+        assert_eq!(s.client.close_table(&table_id), 950);
+        assert_eq!(s.token.balance(&loser), 50);
+        assert_eq!(s.token.balance(&s.admin), admin_balance + 25);
+    }
+
+    // ---------------------------------------------------------------------------
+    // Minimum raise
+    // ---------------------------------------------------------------------------
+
+    /// Seat three players with `buy_ins` and deal. The button is on seat 1,
+    /// so seat 1 acts first, then the small blind (seat 2) and the big blind
+    /// (seat 0).
+    fn deal_three(s: &TestSetup, buy_ins: [i128; 3]) -> u32 {
+        let table_id = create_default_table(s);
+        for buy_in in buy_ins {
+            join_player(s, table_id, &Address::generate(&s.env), buy_in);
+        }
+        s.client.start_hand(&table_id);
+        commit_mock_deal(s, table_id, 3);
+        table_id
+    }
+
+    /// Play `action` for the seat to act, returning the contract's error.
+    fn try_act(s: &TestSetup, table_id: u32, action: Action) -> Result<(), PokerTableError> {
+        let table = s.client.get_table(&table_id);
+        let actor = table.players.get(table.current_turn).unwrap().unwrap();
+        match s
+            .client
+            .try_player_action(&table_id, &actor.address, &action)
+        {
+            Ok(_) => Ok(()),
+            Err(Ok(e)) => Err(e),
+            Err(Err(e)) => panic!("player_action failed to invoke: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_raise_must_match_previous_raise() {
+        let s = setup();
+        let table_id = deal_three(&s, [500, 500, 500]);
+
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(5)),
+            Err(PokerTableError::RaiseTooSmall)
+        );
+        try_act(&s, table_id, Action::Raise(50)).unwrap();
+        assert_eq!(s.client.get_table(&table_id).min_raise, 50);
+
+        // The small blind has to raise by at least 50 more.
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(40)),
+            Err(PokerTableError::RaiseTooSmall)
+        );
+        try_act(&s, table_id, Action::Raise(60)).unwrap();
+        assert_eq!(s.client.get_table(&table_id).min_raise, 60);
+        try_act(&s, table_id, Action::Fold).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+
+        // The flop starts again from the big blind.
+        reveal(
+            &s,
+            table_id,
+            &Vec::from_slice(&s.env, &BOARD[..3]),
+            &Vec::from_array(&s.env, [6, 7, 8]),
+        );
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Flop);
+        assert_eq!(table.min_raise, 10);
+    }
+
+    #[test]
+    fn test_short_all_in_does_not_reopen_betting() {
+        let s = setup();
+        let table_id = deal_three(&s, [100, 500, 500]);
+
+        // Seat 1 raises to 60 and seat 2 calls. The big blind's all-in to
+        // 100 is only 40 more, short of a full raise.
+        try_act(&s, table_id, Action::Raise(50)).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        assert_eq!(s.client.get_table(&table_id).min_raise, 50);
+
+        // Seat 1 has already acted, so it may only call or fold.
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(50)),
+            Err(PokerTableError::BettingNotReopened)
+        );
+        assert_eq!(
+            try_act(&s, table_id, Action::AllIn),
+            Err(PokerTableError::BettingNotReopened)
+        );
+        try_act(&s, table_id, Action::Call).unwrap();
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(50)),
+            Err(PokerTableError::BettingNotReopened)
+        );
+        try_act(&s, table_id, Action::Call).unwrap();
+
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::DealingFlop);
+        assert_eq!(table.pot, 300);
+    }
+
+    #[test]
+    fn test_short_all_in_leaves_raise_open_to_players_yet_to_act() {
+        let s = setup();
+        let table_id = deal_three(&s, [500, 500, 100]);
+
+        // Seat 1 raises to 60; the small blind's all-in to 100 is short.
+        try_act(&s, table_id, Action::Raise(50)).unwrap();
+        try_act(&s, table_id, Action::AllIn).unwrap();
+
+        // The big blind has not acted yet, so it may still raise, by at
+        // least the last full raise.
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(40)),
+            Err(PokerTableError::RaiseTooSmall)
+        );
+        try_act(&s, table_id, Action::Raise(50)).unwrap();
+
+        // That full raise reopens the betting for seat 1.
+        try_act(&s, table_id, Action::Raise(50)).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::DealingFlop);
+        assert_eq!(table.pot, 100 + 200 + 200);
+    }
+
+    #[test]
+    fn test_full_all_in_raise_reopens_betting() {
+        let s = setup();
+        let table_id = deal_three(&s, [500, 500, 200]);
+
+        // Seat 1 raises to 60 and the small blind goes all-in to 200, a
+        // raise of 140.
+        try_act(&s, table_id, Action::Raise(50)).unwrap();
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        assert_eq!(s.client.get_table(&table_id).min_raise, 140);
+
+        try_act(&s, table_id, Action::Call).unwrap();
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(100)),
+            Err(PokerTableError::RaiseTooSmall)
+        );
+        try_act(&s, table_id, Action::Raise(140)).unwrap();
+    }
+
+    #[test]
+    fn test_call_against_an_all_in_runs_the_board_out() {
+        let s = setup();
+        let table_id = deal_three(&s, [100, 500, 500]);
+
+        // Seat 1 folds, seat 2 calls the big blind's all-in and is left as
+        // the only player with chips, so no betting is left in the hand.
+        try_act(&s, table_id, Action::Fold).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+        try_act(&s, table_id, Action::AllIn).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::RunOut);
+        assert_eq!(
+            try_act(&s, table_id, Action::Check),
+            Err(PokerTableError::NotInBettingPhase)
+        );
+
+        // Each street comes from one reveal, with no betting round between.
+        let flop = Vec::from_slice(&s.env, &BOARD[..3]);
+        let wrong = Vec::from_slice(&s.env, &BOARD[..1]);
+        assert_eq!(
+            s.client.try_reveal_board(
+                &table_id,
+                &s.committee,
+                &wrong,
+                &wrong,
+                &soroban_sdk::Bytes::new(&s.env),
+                &soroban_sdk::Bytes::new(&s.env),
+            ),
+            Err(Ok(PokerTableError::WrongCardCount))
+        );
+        reveal(&s, table_id, &flop, &Vec::from_array(&s.env, [6, 7, 8]));
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::RunOut);
+        assert!(table
+            .players
+            .iter()
+            .flatten()
+            .all(|p| p.bet_this_round == 0));
+
+        run_out(&s, table_id, BOARD);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Showdown);
+        assert_eq!(table.board_cards, Vec::from_array(&s.env, BOARD));
+        assert_eq!(table.pot, 200);
+    }
+
+    // ---------------------------------------------------------------------------
+    // Sitting out
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_sitting_out_player_is_dealt_out() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let players: std::vec::Vec<Address> = (0..3).map(|_| Address::generate(&s.env)).collect();
+        for player in &players {
+            join_player(&s, table_id, player, 500);
+        }
+        s.client.sit_out(&table_id, &players[1]);
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+
+        // The button skips seat 1 to seat 2, which heads-up posts the small
+        // blind and acts first; seat 0 posts the big blind.
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.dealer_seat, 2);
+        assert_eq!(stacks(&table), [490, 500, 495]);
+        assert!(table.players.get(1).unwrap().unwrap().folded);
+        assert_eq!(table.current_turn, 2);
+
+        // Seat 2 folds, and seat 1 is back in for the next hand, in the big
+        // blind after seat 0's small while the button stays on seat 2.
+        s.client
+            .player_action(&table_id, &players[2], &Action::Fold);
+        s.client.sit_in(&table_id, &players[1]);
+        s.client.start_hand(&table_id);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.dealer_seat, 2);
+        assert_eq!(table.small_blind_seat, 0);
+        assert_eq!(table.big_blind_seat, 1);
+        assert!(!table.players.get(1).unwrap().unwrap().folded);
+        assert_eq!(stacks(&table), [500, 490, 495]);
+    }
+
+    #[test]
+    fn test_start_hand_needs_two_players_sitting_in() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let p1 = Address::generate(&s.env);
+        let p2 = Address::generate(&s.env);
+        join_player(&s, table_id, &p1, 500);
+        join_player(&s, table_id, &p2, 500);
+        s.client.sit_out(&table_id, &p2);
+        assert_eq!(
+            s.client.try_start_hand(&table_id),
+            Err(Ok(PokerTableError::NeedAtLeastTwoPlayers))
+        );
+    }
+
+    #[test]
+    fn test_second_timeout_in_a_row_sits_player_out() {
+        let s = setup();
+        let table_id = deal_three(&s, [500, 500, 500]);
+        let time_out = || {
+            let table = s.client.get_table(&table_id);
+            s.env.ledger().set_sequence_number(
+                table.last_action_ledger + table.config.betting_timeout_ledgers,
+            );
+            s.client
+                .claim_timeout(&table_id, &Address::generate(&s.env));
+        };
+
+        // Seat 1 acts first and times out; the small blind folds too.
+        time_out();
+        let table = s.client.get_table(&table_id);
+        let stalling = table.players.get(1).unwrap().unwrap();
+        assert_eq!(stalling.missed_turns, 1);
+        assert!(!stalling.sitting_out);
+        try_act(&s, table_id, Action::Fold).unwrap();
+
+        // Next hand seat 1 is the big blind and faces a raise, which the
+        // others have matched, so folding it ends the round.
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 3);
+        try_act(&s, table_id, Action::Raise(10)).unwrap();
+        try_act(&s, table_id, Action::Call).unwrap();
+        assert_eq!(s.client.get_table(&table_id).current_turn, 1);
+        time_out();
+        assert_eq!(
+            s.env.events().all().filter_by_contract(&s.client.address),
+            vec![
+                &s.env,
+                published(
+                    &s,
+                    &TimeoutFold {
+                        table_id,
+                        hand_number: 2,
+                        player: stalling.address.clone(),
+                        seat: 1,
+                    }
+                ),
+                published(
+                    &s,
+                    &PlayerSatOut {
+                        table_id,
+                        player: stalling.address.clone(),
+                        seat: 1,
+                    }
+                ),
+                published(
+                    &s,
+                    &PhaseChange {
+                        table_id,
+                        hand_number: 2,
+                        phase: GamePhase::DealingFlop,
+                    }
+                ),
+            ]
+        );
+        let stalling = s
+            .client
+            .get_table(&table_id)
+            .players
+            .get(1)
+            .unwrap()
+            .unwrap();
+        assert!(stalling.folded);
+        assert!(stalling.sitting_out);
+
+        // Sitting back in clears the count.
+        s.client.sit_in(&table_id, &stalling.address);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.players.get(1).unwrap().unwrap().missed_turns, 0);
+    }
+
+    // ---------------------------------------------------------------------------
+    // Session keys
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_session_key_acts_until_it_expires_or_is_revoked() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let players = [Address::generate(&s.env), Address::generate(&s.env)];
+        for player in &players {
+            join_player(&s, table_id, player, 500);
+        }
+        let key = Address::generate(&s.env);
+        let register = |player: &Address, ledgers: u32| {
+            s.client
+                .try_register_session_key(&table_id, player, &key, &ledgers)
+        };
+        assert_eq!(
+            register(&Address::generate(&s.env), 100),
+            Err(Ok(PokerTableError::PlayerNotAtTable))
+        );
+        for ledgers in [0, crate::MAX_SESSION_LEDGERS + 1] {
+            assert_eq!(
+                register(&players[0], ledgers),
+                Err(Ok(PokerTableError::InvalidSessionDuration))
+            );
+        }
+        let now = s.env.ledger().sequence();
+        for player in &players {
+            assert_eq!(register(player, 100).unwrap().unwrap(), now + 100);
+        }
+
+        s.client.start_hand(&table_id);
+        commit_mock_deal(&s, table_id, 2);
+        let table = s.client.get_table(&table_id);
+        let first = table.players.get(table.current_turn).unwrap().unwrap();
+        assert_eq!(
+            s.client.try_session_action(
+                &table_id,
+                &first.address,
+                &Address::generate(&s.env),
+                &Action::Call
+            ),
+            Err(Ok(PokerTableError::NoSessionKey))
+        );
+        s.client
+            .session_action(&table_id, &first.address, &key, &Action::Call);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Preflop);
+        assert_eq!(table.current_turn, table.big_blind_seat);
+
+        // The big blind's key runs out before they take their option.
+        let second = table.players.get(table.current_turn).unwrap().unwrap();
+        s.env.ledger().set_sequence_number(now + 100);
+        assert_eq!(
+            s.client
+                .try_session_action(&table_id, &second.address, &key, &Action::Check),
+            Err(Ok(PokerTableError::NoSessionKey))
+        );
+        assert_eq!(
+            s.client.try_get_session_key(&table_id, &second.address),
+            Err(Ok(PokerTableError::NoSessionKey))
+        );
+
+        register(&second.address, 100).unwrap().unwrap();
+        s.client.revoke_session_key(&table_id, &second.address);
+        assert_eq!(
+            s.client
+                .try_session_action(&table_id, &second.address, &key, &Action::Check),
+            Err(Ok(PokerTableError::NoSessionKey))
+        );
+        s.client
+            .player_action(&table_id, &second.address, &Action::Check);
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::DealingFlop);
+    }
+
+    #[test]
+    fn test_leaving_drops_the_session_key() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let player = Address::generate(&s.env);
+        join_player(&s, table_id, &player, 500);
+        let key = Address::generate(&s.env);
+        s.client
+            .register_session_key(&table_id, &player, &key, &100);
+        assert_eq!(s.client.get_session_key(&table_id, &player).key, key);
+
+        s.client.leave_table(&table_id, &player);
+        join_player(&s, table_id, &player, 500);
+        assert_eq!(
+            s.client.try_get_session_key(&table_id, &player),
+            Err(Ok(PokerTableError::NoSessionKey))
+        );
+    }
+
+    // ---------------------------------------------------------------------------
+    // Button and blinds
+    // ---------------------------------------------------------------------------
+
+    /// Fold the seat to act until the hand is won.
+    fn fold_out(s: &TestSetup, table_id: u32) {
+        commit_mock_deal(s, table_id, seated_count(&s.client.get_table(&table_id)));
+        while s.client.get_table(&table_id).phase != GamePhase::Settlement {
+            try_act(s, table_id, Action::Fold).unwrap();
+        }
+    }
+
+    /// Start a hand and return its (button, small blind, big blind) seats
+    /// and the blinds posted.
+    fn start_and_get_blinds(s: &TestSetup, table_id: u32) -> ((u32, u32, u32), i128) {
+        s.client.start_hand(&table_id);
+        let table = s.client.get_table(&table_id);
+        (
+            (
+                table.dealer_seat,
+                table.small_blind_seat,
+                table.big_blind_seat,
+            ),
+            table.pot,
+        )
+    }
+
+    #[test]
+    fn test_sitting_out_gives_dead_small_blind_and_dead_button() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let players: std::vec::Vec<Address> = (0..4).map(|_| Address::generate(&s.env)).collect();
+        for player in &players {
+            join_player(&s, table_id, player, 500);
+        }
+
+        assert_eq!(start_and_get_blinds(&s, table_id), ((1, 2, 3), 15));
+        fold_out(&s, table_id);
+        assert_eq!(start_and_get_blinds(&s, table_id), ((2, 3, 0), 15));
+        fold_out(&s, table_id);
+
+        // Last hand's big blind sits out: the big blind still moves on one
+        // seat, and nobody posts the small blind in its place.
+        s.client.sit_out(&table_id, &players[0]);
+        assert_eq!(start_and_get_blinds(&s, table_id), ((3, 0, 1), 10));
+        fold_out(&s, table_id);
+
+        // Then the button lands on the empty seat.
+        assert_eq!(start_and_get_blinds(&s, table_id), ((0, 1, 2), 15));
+        commit_mock_deal(&s, table_id, 4);
+        assert_eq!(s.client.get_table(&table_id).current_turn, 3);
+    }
+
+    #[test]
+    fn test_big_blind_leaving_makes_small_blind_dead() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let players: std::vec::Vec<Address> = (0..4).map(|_| Address::generate(&s.env)).collect();
+        for player in &players {
+            join_player(&s, table_id, player, 500);
+        }
+        assert_eq!(start_and_get_blinds(&s, table_id), ((1, 2, 3), 15));
+        fold_out(&s, table_id);
+
+        // Seat 3 posted the big blind and leaves. Seat 0 is next to post it;
+        // last hand's small blind takes the button instead of paying again.
+        s.client.leave_table(&table_id, &players[3]);
+        assert_eq!(start_and_get_blinds(&s, table_id), ((2, 2, 0), 10));
+        fold_out(&s, table_id);
+        assert_eq!(start_and_get_blinds(&s, table_id), ((2, 0, 1), 15));
+    }
+
+    // ---------------------------------------------------------------------------
+    // Straddle
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_straddle_doubles_the_big_blind_and_moves_first_to_act() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        for _ in 0..4 {
+            join_player(&s, table_id, &Address::generate(&s.env), 500);
+        }
+        s.client.start_hand(&table_id);
+        let table = s.client.get_table(&table_id);
+        let straddler = (table.big_blind_seat + 1) % 4;
+        let player_in = |seat: u32| table.players.get(seat).unwrap().unwrap().address;
+
+        assert_eq!(
+            s.client
+                .try_post_straddle(&table_id, &player_in((straddler + 1) % 4)),
+            Err(Ok(PokerTableError::StraddleNotAllowed))
+        );
+        s.client.post_straddle(&table_id, &player_in(straddler));
+        assert_eq!(
+            s.client.try_post_straddle(&table_id, &player_in(straddler)),
+            Err(Ok(PokerTableError::StraddleNotAllowed))
+        );
+
+        commit_mock_deal(&s, table_id, 4);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.pot, 35);
+        assert_eq!(table.min_raise, 20);
+        assert_eq!(table.straddle_seat, Some(straddler));
+        assert_eq!(table.current_turn, (straddler + 1) % 4);
+
+        try_act(&s, table_id, Action::Call).unwrap();
+        let table = s.client.get_table(&table_id);
+        let caller = table.players.get((straddler + 1) % 4).unwrap().unwrap();
+        assert_eq!(caller.bet_this_round, 20);
+        assert_eq!(
+            try_act(&s, table_id, Action::Raise(10)),
+            Err(PokerTableError::RaiseTooSmall)
+        );
+    }
+
+    // ---------------------------------------------------------------------------
+    // Pausing
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_paused_table_blocks_play_but_not_leaving() {
+        let (s, table_id, p1, _, _) = setup_preflop_3p();
+        s.client.pause_table(&table_id);
+        assert!(s.client.get_table(&table_id).paused);
+
+        assert_eq!(
+            try_act(&s, table_id, Action::Call),
+            Err(PokerTableError::TablePaused)
+        );
+        let table = s.client.get_table(&table_id);
+        s.env
+            .ledger()
+            .set_sequence_number(table.last_action_ledger + table.config.betting_timeout_ledgers);
+        assert_eq!(
+            s.client
+                .try_claim_timeout(&table_id, &Address::generate(&s.env)),
+            Err(Ok(PokerTableError::TablePaused))
+        );
+
+        // Resuming restarts the clock for whoever is to act.
+        s.client.resume_table(&table_id);
+        assert_eq!(
+            s.client
+                .try_claim_timeout(&table_id, &Address::generate(&s.env)),
+            Err(Ok(PokerTableError::TimeoutNotReached))
+        );
+        while s.client.get_table(&table_id).phase == GamePhase::Preflop {
+            try_act(&s, table_id, Action::Fold).unwrap();
+        }
+
+        // Between hands nobody can sit down or deal, but players can leave.
+        s.client.pause_table(&table_id);
+        let newcomer = Address::generate(&s.env);
+        s.token_admin_client.mint(&newcomer, &500);
+        assert_eq!(
+            s.client.try_join_table(&table_id, &newcomer, &500),
+            Err(Ok(PokerTableError::TablePaused))
+        );
+        assert_eq!(
+            s.client.try_start_hand(&table_id),
+            Err(Ok(PokerTableError::TablePaused))
+        );
+        s.client.leave_table(&table_id, &p1);
+        let table = s.client.get_table(&table_id);
+        assert!(table.players.get(0).unwrap().is_none());
+    }
+
+    // ---------------------------------------------------------------------------
+    // Table listing
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_get_table_page_walks_open_tables() {
+        let s = setup();
+        let ids: std::vec::Vec<u32> = (0..3).map(|_| create_default_table(&s)).collect();
+        join_player(&s, ids[0], &Address::generate(&s.env), 500);
+        join_player(&s, ids[0], &Address::generate(&s.env), 500);
+        s.client.start_hand(&ids[0]);
+        s.client.close_table(&ids[1]);
+
+        let summary = s.client.get_table_summary(&ids[0]);
+        assert_eq!(
+            summary,
+            TableSummary {
+                table_id: ids[0],
+                phase: GamePhase::Dealing,
+                player_count: 2,
+                max_players: 6,
+                small_blind: 5,
+                big_blind: 10,
+                pot: 15,
+            }
+        );
+
+        // The closed table is skipped, and a page never runs past the last
+        // table created.
+        let all = s.client.get_table_page(&0, &10);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all.get(0).unwrap(), summary);
+        assert_eq!(all.get(1).unwrap().table_id, ids[2]);
+        assert_eq!(all.get(1).unwrap().player_count, 0);
+        assert_eq!(s.client.get_table_page(&1, &1).len(), 0);
+        assert_eq!(s.client.get_table_page(&3, &10).len(), 0);
+    }
+
+    // ---------------------------------------------------------------------------
+    // Public table
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_public_table_shows_the_hand_but_not_the_deal() {
+        let (s, table_id, p1, _, _) = setup_preflop_3p();
+        let state = s.client.get_table(&table_id);
+        let public = s.client.get_public_table(&table_id);
+
+        assert_eq!(public.table_id, table_id);
+        assert_eq!(public.phase, GamePhase::Preflop);
+        assert_eq!(public.pot, state.pot);
+        assert_eq!(public.current_turn, state.current_turn);
+        assert_eq!(public.dealer_seat, state.dealer_seat);
+        assert_eq!(public.board_cards, state.board_cards);
+        assert_eq!(public.big_blind, 10);
+        assert_eq!(public.min_buy_in, state.config.min_buy_in);
+        assert_eq!(public.players.len(), state.players.len());
+        let seat = public.players.get(0).unwrap().unwrap();
+        let player = state.players.get(0).unwrap().unwrap();
+        assert_eq!(seat.address, p1);
+        assert_eq!(seat.stack, player.stack);
+        assert_eq!(seat.bet_this_round, player.bet_this_round);
+        assert_eq!(public.players.get(3).unwrap(), None);
+
+        assert_eq!(
+            s.client.try_get_public_table(&99),
+            Err(Ok(PokerTableError::TableNotFound))
+        );
+    }
+
+    // ---------------------------------------------------------------------------
+    // Cancelling a hand
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_players_in_the_hand_can_vote_to_cancel_it() {
+        let (s, table_id, p1, p2, p3) = setup_preflop_3p();
+        let table = s.client.get_table(&table_id);
+        let folder = table
+            .players
+            .get(table.current_turn)
+            .unwrap()
+            .unwrap()
+            .address;
+        try_act(&s, table_id, Action::Fold).unwrap();
+        let voters: std::vec::Vec<Address> =
+            [p1, p2, p3].into_iter().filter(|p| *p != folder).collect();
+
+        assert_eq!(
+            s.client.try_vote_cancel_hand(&table_id, &folder),
+            Err(Ok(PokerTableError::PlayerAlreadyFolded))
+        );
+        s.client.vote_cancel_hand(&table_id, &voters[0]);
+        assert_eq!(
+            s.client.try_vote_cancel_hand(&table_id, &voters[0]),
+            Err(Ok(PokerTableError::AlreadyVotedToCancel))
+        );
+        assert_eq!(s.client.get_table(&table_id).phase, GamePhase::Preflop);
+
+        // The last vote from a player still in the hand calls it off and
+        // hands back every bet, the folded player's blind included.
+        s.client.vote_cancel_hand(&table_id, &voters[1]);
+        let table = s.client.get_table(&table_id);
+        assert_eq!(table.phase, GamePhase::Waiting);
+        assert_eq!(table.pot, 0);
+        assert!(table.cancel_votes.is_empty());
+        for p in table.players.iter().flatten() {
+            assert_eq!(p.stack, 500);
+        }
+        assert_eq!(
+            s.client.try_vote_cancel_hand(&table_id, &voters[0]),
+            Err(Ok(PokerTableError::NoHandInProgress))
+        );
+    }
+
+    // ---------------------------------------------------------------------------
+    // Idle players
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_idle_player_can_be_kicked_between_hands() {
+        let s = setup();
+        let table_id = create_default_table(&s);
+        let players: std::vec::Vec<Address> = (0..3).map(|_| Address::generate(&s.env)).collect();
+        for player in &players {
+            join_player(&s, table_id, player, 500);
+        }
+        s.client.sit_out(&table_id, &players[2]);
+        for _ in 0..2 {
+            s.client.start_hand(&table_id);
+            fold_out(&s, table_id);
+        }
+        assert_eq!(
+            s.client.try_kick_idle(&table_id, &2),
+            Err(Ok(PokerTableError::PlayerNotIdle))
+        );
+
+        s.client.start_hand(&table_id);
+        assert_eq!(
+            s.client.try_kick_idle(&table_id, &2),
+            Err(Ok(PokerTableError::HandAlreadyInProgress))
+        );
+        fold_out(&s, table_id);
+
+        // Heads-up, seats 0 and 1 take turns folding, so neither is idle.
+        assert_eq!(
+            s.client.try_kick_idle(&table_id, &0),
+            Err(Ok(PokerTableError::PlayerNotIdle))
+        );
+        assert_eq!(s.client.kick_idle(&table_id, &2), 500);
+        assert_eq!(s.token.balance(&players[2]), 500);
+        let table = s.client.get_table(&table_id);
+        assert!(table.players.get(2).unwrap().is_none());
+    }
+
+    // ---------------------------------------------------------------------------
+    // Closing a table
+    // ---------------------------------------------------------------------------
+
+    #[test]
+    fn test_close_table_refunds_everyone_and_deletes_it() {
+        let (s, table_id, p1, p2, p3) = setup_preflop_3p();
+        assert_eq!(
+            s.client.try_close_table(&table_id),
+            Err(Ok(PokerTableError::HandAlreadyInProgress))
+        );
+        while s.client.get_table(&table_id).phase == GamePhase::Preflop {
+            try_act(&s, table_id, Action::Fold).unwrap();
+        }
+
+        assert_eq!(s.client.close_table(&table_id), 1500);
+        let balances = [&p1, &p2, &p3].map(|p| s.token.balance(p));
+        assert_eq!(balances.iter().sum::<i128>(), 1500);
+        assert_eq!(s.token.balance(&s.client.address), 0);
+        assert_eq!(
+            s.client.try_get_table(&table_id),
+            Err(Ok(PokerTableError::TableNotFound))
+        );
     }
 }
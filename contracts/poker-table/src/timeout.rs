@@ -1,11 +1,64 @@
-use soroban_sdk::{Address, Env, Symbol};
+use soroban_sdk::{Address, Env, Vec};
 
+use crate::betting;
+use crate::events::{CommitteeTimeout, PlayerSatOut, TimeBankUsed, TimeoutFold};
 use crate::game;
-use crate::game_hub;
+use crate::registry::{self, CommitteeFailure};
 use crate::types::*;
 
+/// Consecutive timed-out turns after which a player is sat out.
+const AUTO_SIT_OUT_AFTER: u32 = 2;
+
+/// Ledgers whoever `phase` waits on has to act before a timeout can be
+/// claimed, or `None` when nothing in `phase` can time out.
+pub fn timeout_ledgers(config: &TableConfig, phase: &GamePhase) -> Option<u32> {
+    match phase {
+        GamePhase::Dealing => Some(config.deal_timeout_ledgers),
+        GamePhase::DealingFlop
+        | GamePhase::DealingTurn
+        | GamePhase::DealingRiver
+        | GamePhase::RunOut => Some(config.reveal_timeout_ledgers),
+        GamePhase::Showdown => Some(config.showdown_timeout_ledgers),
+        GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
+            Some(config.betting_timeout_ledgers)
+        }
+        GamePhase::Waiting | GamePhase::Settlement | GamePhase::Dispute => None,
+    }
+}
+
+/// Charge the player to act, `p`, for the ledgers they have taken past the
+/// betting timeout out of their time bank.
+pub fn draw_time_bank(env: &Env, table: &TableState, p: &mut PlayerState) {
+    let elapsed = env.ledger().sequence() - table.last_action_ledger;
+    let used = elapsed
+        .saturating_sub(table.config.betting_timeout_ledgers)
+        .min(p.time_bank_ledgers);
+    if used == 0 {
+        return;
+    }
+    p.time_bank_ledgers -= used;
+
+    TimeBankUsed {
+        table_id: table.id,
+        hand_number: table.hand_number,
+        player: p.address.clone(),
+        seat: p.seat_index,
+        used,
+        remaining: p.time_bank_ledgers,
+    }
+    .publish(env);
+}
+
+fn is_betting(phase: &GamePhase) -> bool {
+    matches!(
+        phase,
+        GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River
+    )
+}
+
 /// Process a timeout claim.
-/// Anyone can call this if enough ledgers have passed since the last action.
+/// Anyone can call this once the phase's timeout has passed since the last
+/// action.
 pub fn process_timeout(
     env: &Env,
     table: &mut TableState,
@@ -14,7 +67,13 @@ pub fn process_timeout(
     let current_ledger = env.ledger().sequence();
     let elapsed = current_ledger - table.last_action_ledger;
 
-    if elapsed < table.config.timeout_ledgers {
+    let mut limit = timeout_ledgers(&table.config, &table.phase)
+        .ok_or(PokerTableError::TimeoutNotApplicable)?;
+    // A player to act is only folded once their time bank is gone too.
+    if is_betting(&table.phase) {
+        limit = limit.saturating_add(game::player_at(table, table.current_turn)?.time_bank_ledgers);
+    }
+    if elapsed < limit {
         return Err(PokerTableError::TimeoutNotReached);
     }
 
@@ -22,63 +81,93 @@ pub fn process_timeout(
         // Player timeout during betting — auto-fold the stalling player
         GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
             let seat = table.current_turn;
-            let mut p = table
-                .players
-                .get(seat)
-                .ok_or(PokerTableError::InvalidPlayerIndex)?;
+            let mut p = game::player_at(table, seat)?;
 
             if !p.folded && !p.all_in {
+                draw_time_bank(env, table, &mut p);
                 p.folded = true;
-                table.players.set(seat, p.clone());
+                p.missed_turns += 1;
+                let sat_out = !p.sitting_out && p.missed_turns >= AUTO_SIT_OUT_AFTER;
+                if sat_out {
+                    p.sitting_out = true;
+                }
+                table.players.set(seat, Some(p.clone()));
 
-                env.events().publish(
-                    (Symbol::new(env, "timeout_fold"), table.id),
-                    p.address.clone(),
-                );
+                TimeoutFold {
+                    table_id: table.id,
+                    hand_number: table.hand_number,
+                    player: p.address.clone(),
+                    seat,
+                }
+                .publish(env);
+                if sat_out {
+                    PlayerSatOut {
+                        table_id: table.id,
+                        player: p.address.clone(),
+                        seat,
+                    }
+                    .publish(env);
+                }
 
-                // Check if only one player remains
+                // The fold can end the hand or the round, as the player's
+                // own fold would, and leave only all-in players to run out.
                 if game::active_player_count(table) == 1 {
                     game::settle_fold_win(env, table)?;
+                } else if betting::is_round_complete(table)? {
+                    betting::advance_to_next_phase(env, table)?;
                 } else {
                     // Advance to next player
-                    let num_players = table.players.len() as u32;
-                    let mut next = (seat + 1) % num_players;
-                    for _ in 0..num_players {
-                        let np = table
-                            .players
-                            .get(next)
-                            .ok_or(PokerTableError::InvalidPlayerIndex)?;
-                        if !np.folded && !np.all_in {
-                            break;
-                        }
-                        next = (next + 1) % num_players;
-                    }
-                    table.current_turn = next;
+                    table.current_turn = betting::next_to_act(table, seat)?;
                     table.last_action_ledger = current_ledger;
                 }
             }
         }
 
-        // Committee timeout during dealing/reveal — dispute, return funds
+        // Committee timeout during dealing/reveal — call the hand off and
+        // give everyone back what they bet
         GamePhase::Dealing
         | GamePhase::DealingFlop
         | GamePhase::DealingTurn
         | GamePhase::DealingRiver
-        | GamePhase::Showdown => {
-            // Committee failed to act — enter dispute phase
+        | GamePhase::RunOut => {
+            let failure = if table.phase == GamePhase::Dealing {
+                CommitteeFailure::MissedDeal
+            } else {
+                CommitteeFailure::MissedReveal
+            };
+            registry::report_timeout(env, table, failure);
+            CommitteeTimeout {
+                table_id: table.id,
+                hand_number: table.hand_number,
+                phase: table.phase.clone(),
+                pot: table.pot,
+            }
+            .publish(env);
+            game::cancel_hand(env, table)?;
+        }
+
+        // Committee timeout at showdown — dispute, return funds
+        GamePhase::Showdown => {
+            // Committee failed to act — report it and enter dispute phase
+            registry::report_timeout(env, table, CommitteeFailure::MissedShowdown);
             table.phase = GamePhase::Dispute;
             table.last_action_ledger = current_ledger;
 
-            env.events().publish(
-                (Symbol::new(env, "committee_timeout"), table.id),
-                table.hand_number,
-            );
+            CommitteeTimeout {
+                table_id: table.id,
+                hand_number: table.hand_number,
+                phase: GamePhase::Showdown,
+                pot: table.pot,
+            }
+            .publish(env);
 
             // Return all funds to players (emergency settlement)
-            emergency_refund(env, table)?;
+            let pot = table.pot;
+            let payouts = emergency_refund(env, table)?;
+            game::record_settlement(env, table, SettlementSource::Timeout, pot, 0, 0, payouts);
 
-            // Notify Game Hub that the game ended (player1_won = true as default for dispute)
-            game_hub::notify_end(env, &table.config.game_hub, table.session_id, true);
+            // Notify Game Hub that the game ended, with the refunded stacks
+            game::notify_hand_end(env, table, pot, 0);
         }
 
         _ => {
@@ -89,45 +178,38 @@ pub fn process_timeout(
 }
 
 /// Emergency refund: return all player stacks + pot split equally
-/// among non-folded players. Used when committee fails.
-fn emergency_refund(_env: &Env, table: &mut TableState) -> Result<(), PokerTableError> {
+/// among non-folded players. Used when committee fails. Returns what each
+/// player got back.
+fn emergency_refund(env: &Env, table: &mut TableState) -> Result<Vec<PotPayout>, PokerTableError> {
+    let mut payouts = Vec::new(env);
     let active = game::active_player_count(table);
     if active == 0 {
-        return Ok(());
+        return Ok(payouts);
     }
 
     let share = table.pot / (active as i128);
-    let mut distributed: i128 = 0;
+    // The remainder goes to the first active player.
+    let mut remainder = table.pot - share * (active as i128);
 
     for i in 0..table.players.len() {
-        let mut p = table
-            .players
-            .get(i)
-            .ok_or(PokerTableError::InvalidPlayerIndex)?;
+        let Some(mut p) = table.players.get(i).flatten() else {
+            continue;
+        };
         if !p.folded {
-            p.stack += share;
-            distributed += share;
-        }
-        table.players.set(i, p);
-    }
-
-    // Handle remainder (give to first active player)
-    let remainder = table.pot - distributed;
-    if remainder > 0 {
-        for i in 0..table.players.len() {
-            let mut p = table
-                .players
-                .get(i)
-                .ok_or(PokerTableError::InvalidPlayerIndex)?;
-            if !p.folded {
-                p.stack += remainder;
-                table.players.set(i, p);
-                break;
-            }
+            let amount = share + remainder;
+            remainder = 0;
+            p.stack += amount;
+            payouts.push_back(PotPayout {
+                pot_index: 0,
+                seat: i,
+                player: p.address.clone(),
+                amount,
+            });
         }
+        table.players.set(i, Some(p));
     }
 
     table.pot = 0;
     table.phase = GamePhase::Settlement;
-    Ok(())
+    Ok(payouts)
 }
@@ -8,11 +8,22 @@ pub struct TableConfig {
     pub max_buy_in: i128,
     pub small_blind: i128,
     pub big_blind: i128,
-    pub max_players: u32,     // 2-9
-    pub timeout_ledgers: u32, // Ledgers before timeout (~5 sec each)
-    pub committee: Address,   // MPC committee address
-    pub verifier: Address,    // ZK verifier contract address
-    pub game_hub: Address,    // Game hub contract for start_game/end_game
+    pub max_players: u32, // 2-6, see public_inputs::MAX_PLAYERS
+    // Ledgers (~5 sec each) before a timeout can be claimed, by phase.
+    pub deal_timeout_ledgers: u32,   // Committee dealing the hole cards
+    pub reveal_timeout_ledgers: u32, // Committee revealing a board street
+    pub showdown_timeout_ledgers: u32, // Committee proving the showdown
+    pub betting_timeout_ledgers: u32, // Player to act in a betting round
+    pub time_bank_ledgers: u32,      // Extra betting time each player gets per hand
+    pub committee: Vec<Address>,     // MPC committee members, any of whom may submit its proofs
+    pub committee_threshold: u32,    // Members needed to produce a proof (t of n)
+    pub verifier: Address,           // ZK verifier contract address
+    pub game_hub: Address,           // Game hub contract for start_game/end_game
+    pub committee_registry: Address, // Registry told when the committee misses a deadline
+    pub rake_bps: u32,               // Rake taken from each pot, in basis points
+    pub rake_cap: i128,              // Most rake taken from one hand
+    pub chip_unit: i128,             // Token units in one chip; bets and raises are whole chips
+    pub jackpot_bps: u32,            // Bad-beat jackpot share of each pot, in basis points
 }
 
 #[contracterror]
@@ -52,6 +63,29 @@ pub enum PokerTableError {
     InvalidHoleCards = 31,
     TimeoutNotReached = 32,
     TimeoutNotApplicable = 33,
+    BettingNotReopened = 34,
+    InvalidRake = 35,
+    PublicInputMismatch = 36,
+    CardAlreadyDealt = 37,
+    ProofReplay = 38,
+    HandNotFound = 39,
+    TablePaused = 40,
+    PlayerNotIdle = 41,
+    StraddleNotAllowed = 42,
+    InvalidCommittee = 43,
+    NoCommitteeEpoch = 44,
+    InvalidBlinds = 45,
+    InvalidBuyInRange = 46,
+    InvalidMaxPlayers = 47,
+    InsufficientBalance = 48,
+    InsufficientAllowance = 49,
+    NoHandInProgress = 50,
+    AlreadyVotedToCancel = 51,
+    InvalidChipUnit = 52,
+    NotWholeChips = 53,
+    NothingToClaim = 54,
+    InvalidSessionDuration = 55,
+    NoSessionKey = 56,
 }
 
 #[contracttype]
@@ -64,6 +98,20 @@ pub struct PlayerState {
     pub all_in: bool,
     pub sitting_out: bool,
     pub seat_index: u32,
+    pub hand_start_stack: i128, // Stack before blinds, for the hub's per-hand results
+    pub acted: bool,            // Acted since the last full bet or raise this round
+    pub missed_turns: u32,      // Consecutive turns lost to a timeout
+    pub last_active_hand: u32,  // Hand number when the player last acted, joined or sat in/out
+    pub muck_preference: MuckPreference,
+    pub time_bank_ledgers: u32, // Time bank left this hand, drawn once the betting timeout passes
+}
+
+/// Whether a player's hole cards go on chain at a showdown they lose.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MuckPreference {
+    Show, // Always shown
+    Muck, // Shown only when they win a pot someone else contested
 }
 
 #[contracttype]
@@ -78,6 +126,7 @@ pub enum GamePhase {
     Turn,         // Betting round: turn
     DealingRiver, // Committee revealing river
     River,        // Betting round: river
+    RunOut,       // Everyone left is all-in; committee revealing the rest of the board
     Showdown,     // Revealing hands and determining winner
     Settlement,   // Pot distributed, ready for next hand
     Dispute,      // Something went wrong; funds frozen
@@ -95,7 +144,7 @@ pub enum Action {
 }
 
 #[contracttype]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SidePot {
     pub amount: i128,
     pub eligible_players: Vec<u32>, // seat indices
@@ -108,10 +157,13 @@ pub struct TableState {
     pub admin: Address,
     pub config: TableConfig,
     pub phase: GamePhase,
-    pub players: Vec<PlayerState>,
+    pub players: Vec<Option<PlayerState>>, // One per seat; None while empty
     pub dealer_seat: u32,
+    pub small_blind_seat: u32, // May be dead; see game::small_blind_is_live
+    pub big_blind_seat: u32,
     pub current_turn: u32,
     pub pot: i128,
+    pub min_raise: i128, // Size of the last full bet or raise this round
     pub side_pots: Vec<SidePot>,
     pub deck_root: BytesN<32>,
     pub hand_commitments: Vec<BytesN<32>>,
@@ -119,12 +171,137 @@ pub struct TableState {
     pub dealt_indices: Vec<u32>, // Deck indices already dealt
     pub hand_number: u32,
     pub last_action_ledger: u32, // For timeout calculation
-    pub committee: Address,
-    pub session_id: u32, // Game hub session ID for current hand
+    pub committee: Vec<Address>,
+    pub session_id: u32, // Game hub session ID for current hand; its proofs' nonce
+    pub proof_digests: Vec<BytesN<32>>, // SHA-256 of this hand's accepted proofs, in order
+    pub paused: bool,    // Admin stopped play; see pause_table
+    pub straddle_seat: Option<u32>, // Seat that straddled this hand, if any
+    pub pending_verifier: Option<PendingVerifier>, // See schedule_verifier
+    pub cancel_votes: Vec<u32>, // Seats that voted to cancel this hand; see vote_cancel_hand
+}
+
+/// A verifier the admin has scheduled to replace the table's current one.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingVerifier {
+    pub verifier: Address,
+    pub effective_ledger: u32, // First ledger a hand can start with it
+}
+
+/// A key a player has let sign their betting actions at one table, with
+/// `session_action`, so their wallet need not approve each one.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionKey {
+    pub key: Address,
+    pub expires_ledger: u32, // First ledger it can no longer act
+}
+
+/// What a lobby needs to list a table, without its players and hand state.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableSummary {
+    pub table_id: u32,
+    pub phase: GamePhase,
+    pub player_count: u32,
+    pub max_players: u32,
+    pub small_blind: i128,
+    pub big_blind: i128,
+    pub pot: i128,
+}
+
+/// What anyone watching a table may see: the board, pot, bets, phase and
+/// turn, without the deck root, hand commitments, dealt indices or
+/// committee.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicTable {
+    pub table_id: u32,
+    pub phase: GamePhase,
+    pub players: Vec<Option<PublicPlayer>>, // One per seat; None while empty
+    pub dealer_seat: u32,
+    pub small_blind_seat: u32,
+    pub big_blind_seat: u32,
+    pub current_turn: u32,
+    pub pot: i128,
+    pub min_raise: i128,
+    pub side_pots: Vec<SidePot>,
+    pub board_cards: Vec<u32>,
+    pub hand_number: u32,
+    pub last_action_ledger: u32,
+    pub small_blind: i128,
+    pub big_blind: i128,
+    pub min_buy_in: i128,
+    pub max_buy_in: i128,
+    pub chip_unit: i128,
+    pub paused: bool,
+}
+
+/// A seated player as spectators see them.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicPlayer {
+    pub address: Address,
+    pub seat_index: u32,
+    pub stack: i128,
+    pub bet_this_round: i128,
+    pub folded: bool,
+    pub all_in: bool,
+    pub sitting_out: bool,
+}
+
+/// A settled hand, kept for players and auditors to look back on.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandRecord {
+    pub hand_number: u32,
+    pub board_cards: Vec<u32>,
+    pub winner_seats: Vec<u32>, // Every seat paid from a pot
+    pub pot: i128,
+    pub rake: i128,
+    pub showdown: bool,                 // false when everyone else folded
+    pub proof_digests: Vec<BytesN<32>>, // Deal, board reveals, then showdown
+}
+
+/// What settled a hand's pot.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SettlementSource {
+    Fold,     // Everyone else folded, or was folded by a betting timeout
+    Showdown, // The committee's showdown proof
+    Timeout,  // The committee missed the showdown; the pot was refunded
+}
+
+/// What one player was paid out of one pot.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PotPayout {
+    pub pot_index: u32, // 0 for the main pot, then each side pot
+    pub seat: u32,
+    pub player: Address,
+    pub amount: i128,
+}
+
+/// How a table's last settled hand was paid out, for `get_last_settlement`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settlement {
+    pub hand_number: u32,
+    pub source: SettlementSource,
+    pub pot: i128, // Before rake
+    pub rake: i128,
+    pub jackpot: i128, // Paid into the bad-beat jackpot
+    pub payouts: Vec<PotPayout>,
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Table(u32),
+    RakeBalance(u32),         // Rake collected by a table and not yet withdrawn
+    HandHistory(u32, u32),    // (table_id, hand_number) -> HandRecord
+    LastSettlement(u32),      // table_id -> Settlement of its last settled hand
+    Jackpot(u32),             // Bad-beat jackpot a table has built up
+    JackpotClaims(u32),       // table_id -> Map<Address, i128> of awards not yet claimed
+    SessionKey(u32, Address), // (table_id, player) -> SessionKey
 }
@@ -0,0 +1,118 @@
+//! Soroban budget report for `verify_proof`.
+//!
+//! Verifies each artifact directory's proof through the contract and prints
+//! the CPU instructions and memory it was metered for against the mainnet
+//! per-transaction limits, failing if either is exceeded:
+//!
+//! ```text
+//! cargo bench -p zk-verifier --bench verify_budget -- DIR...
+//! ```
+//!
+//! A directory holds `vk` (as passed to `set_verification_key`), `proof` and
+//! `public_inputs` (as passed to `verify_proof`). Without arguments the
+//! vendored verifier's test circuits are used, as built by its
+//! `tests/build_circuits.sh`. The contract runs natively here, so the Wasm
+//! VM's own overhead is not counted; the BN254 host functions that dominate
+//! verification are metered as on chain.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{Address, Bytes, Env};
+use zk_verifier::{CircuitType, ZkVerifierContract, ZkVerifierContractClient};
+
+/// Mainnet per-transaction limits, as in soroban-sdk's
+/// `InvocationResourceLimits::mainnet()`.
+const MAINNET_INSTRUCTIONS: i64 = 600_000_000;
+const MAINNET_MEM_BYTES: i64 = 40 * 1024 * 1024;
+
+const DEFAULT_DIRS: [&str; 2] = [
+    "../../vendor/ultrahonk-rust-verifier/ultrahonk-soroban-verifier/circuits/simple_circuit/target",
+    "../../vendor/ultrahonk-rust-verifier/ultrahonk-soroban-verifier/circuits/fib_chain/target",
+];
+
+struct Cost {
+    instructions: i64,
+    mem_bytes: i64,
+}
+
+fn read(dir: &Path, name: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(dir.join(name)).map_err(|e| format!("{}: {}", dir.join(name).display(), e))
+}
+
+fn measure(dir: &Path) -> Result<Cost, String> {
+    let env = Env::default();
+    env.ledger().set_protocol_version(25);
+    env.mock_all_auths();
+    env.cost_estimate().disable_resource_limits();
+
+    let vk = Bytes::from_slice(&env, &read(dir, "vk")?);
+    let proof = Bytes::from_slice(&env, &read(dir, "proof")?);
+    let public_inputs = Bytes::from_slice(&env, &read(dir, "public_inputs")?);
+
+    let client = ZkVerifierContractClient::new(&env, &env.register(ZkVerifierContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_verification_key(&admin, &CircuitType::DealValid, &vk);
+
+    match client.try_verify_proof(&CircuitType::DealValid, &proof, &public_inputs) {
+        Ok(Ok(true)) => {}
+        other => return Err(format!("proof did not verify: {:?}", other)),
+    }
+    let resources = env.cost_estimate().resources();
+    Ok(Cost {
+        instructions: resources.instructions,
+        mem_bytes: resources.mem_bytes,
+    })
+}
+
+fn percent(used: i64, limit: i64) -> f64 {
+    used as f64 * 100.0 / limit as f64
+}
+
+fn main() -> ExitCode {
+    // `cargo bench` passes its own flags (`--bench`) through.
+    let mut dirs: Vec<PathBuf> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(PathBuf::from)
+        .collect();
+    if dirs.is_empty() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        dirs = DEFAULT_DIRS.iter().map(|dir| root.join(dir)).collect();
+    }
+
+    let mut over_limit = false;
+    for dir in &dirs {
+        if !dir.join("proof").exists() {
+            println!("{}: no proof, skipped", dir.display());
+            continue;
+        }
+        match measure(dir) {
+            Ok(cost) => {
+                let fits = cost.instructions <= MAINNET_INSTRUCTIONS
+                    && cost.mem_bytes <= MAINNET_MEM_BYTES;
+                over_limit |= !fits;
+                println!(
+                    "{}: {} instructions ({:.1}% of mainnet), {} memory bytes ({:.1}%){}",
+                    dir.display(),
+                    cost.instructions,
+                    percent(cost.instructions, MAINNET_INSTRUCTIONS),
+                    cost.mem_bytes,
+                    percent(cost.mem_bytes, MAINNET_MEM_BYTES),
+                    if fits { "" } else { "  OVER LIMIT" }
+                );
+            }
+            Err(e) => {
+                println!("{}: {}", dir.display(), e);
+                over_limit = true;
+            }
+        }
+    }
+    if over_limit {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
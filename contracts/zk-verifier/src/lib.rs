@@ -1,10 +1,14 @@
 #![no_std]
 #![allow(deprecated)]
 
+extern crate alloc;
+
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, Address, Bytes, BytesN, Env, Symbol, Vec,
 };
-use ultrahonk_soroban_verifier::{UltraHonkVerifier, PROOF_BYTES};
+use ultrahonk_soroban_verifier::accumulator::PairingAccumulator;
+use ultrahonk_soroban_verifier::verifier::{verify_pairing_batch, VerifyError};
+use ultrahonk_soroban_verifier::UltraHonkVerifier;
 
 /// ZK Verifier contract for Stellar Poker.
 ///
@@ -15,6 +19,13 @@ use ultrahonk_soroban_verifier::{UltraHonkVerifier, PROOF_BYTES};
 #[contract]
 pub struct ZkVerifierContract;
 
+/// VK bytes that, in `testutils` builds, make `verify_proof` (and the
+/// `verify_deal`/`verify_reveal`/`verify_showdown` wrappers) accept any
+/// non-empty proof for the circuit, so integration tests can drive the
+/// contracts with canned proofs.
+#[cfg(feature = "testutils")]
+pub const TEST_MODE_VK: &[u8] = b"zk-verifier-test-mode";
+
 #[contracterror]
 #[repr(u32)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -26,6 +37,9 @@ pub enum VerifierError {
     VkParseError = 5,
     ProofSizeError = 6,
     VerificationFailed = 7,
+    EmptyBatch = 8,
+    NoDeferredBatch = 9,
+    VkMismatch = 10,
 }
 
 #[contracttype]
@@ -36,12 +50,44 @@ pub enum CircuitType {
     ShowdownValid,
 }
 
+impl CircuitType {
+    const COUNT: usize = 3;
+
+    fn index(&self) -> usize {
+        match self {
+            CircuitType::DealValid => 0,
+            CircuitType::RevealBoardValid => 1,
+            CircuitType::ShowdownValid => 2,
+        }
+    }
+}
+
+/// One entry of a `verify_batch` call.
+#[contracttype]
+#[derive(Clone)]
+pub struct BatchProof {
+    pub circuit: CircuitType,
+    pub proof: Bytes,
+    pub public_inputs: Bytes,
+}
+
+/// Proofs checked up to their final pairing, awaiting `settle_deferred`.
+#[contracttype]
+#[derive(Clone)]
+pub struct DeferredBatch {
+    /// `PairingAccumulator` bytes (P0 ‖ P1).
+    pub accumulator: BytesN<128>,
+    /// Hashes of the folded proofs.
+    pub proofs: Vec<BytesN<32>>,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum StorageKey {
     Admin,
     Vk(CircuitType),
     ProofVerified(BytesN<32>),
+    Deferred(Address),
 }
 
 #[contractimpl]
@@ -75,7 +121,10 @@ impl ZkVerifierContract {
         }
 
         // Validate the VK can be parsed before storing
-        UltraHonkVerifier::new(&env, &vk_data).map_err(|_| VerifierError::VkParseError)?;
+        if !Self::is_test_mode_vk(&env, &vk_data) {
+            UltraHonkVerifier::new(&env, &vk_data)
+                .map_err(|e| Self::verify_error(e, VerifierError::VkParseError))?;
+        }
 
         env.storage()
             .persistent()
@@ -89,7 +138,9 @@ impl ZkVerifierContract {
     /// Verify an UltraHonk proof for a given circuit type.
     ///
     /// 1. Loads the VK for the circuit type
-    /// 2. Validates proof size (14,624 bytes = 457 fields * 32)
+    /// 2. Validates proof size: the padded 458-field layout, bb's 457-field
+    ///    Solidity layout, or co-noir's 75 + 11 * log_n fields
+    ///    (90 + 12 * log_n for zero-knowledge proofs) for the VK's circuit size
     /// 3. Runs full UltraHonk verification (sumcheck + shplonk pairing)
     /// 4. Stores proof hash for auditability
     pub fn verify_proof(
@@ -98,36 +149,156 @@ impl ZkVerifierContract {
         proof: Bytes,
         public_inputs: Bytes,
     ) -> Result<bool, VerifierError> {
-        // Check proof size
-        if proof.len() as usize != PROOF_BYTES {
+        if Self::is_test_mode_vk(&env, &Self::load_vk(&env, circuit.clone())?) {
+            if proof.is_empty() {
+                return Err(VerifierError::VerificationFailed);
+            }
+            Self::record_verified(&env, Self::proof_hash(&env, &proof));
+            return Ok(true);
+        }
+        let verifier = Self::load_verifier(&env, circuit)?;
+
+        // Check proof size against the VK's circuit size
+        if !verifier.accepts_proof_len(proof.len() as usize) {
+            return Err(VerifierError::ProofSizeError);
+        }
+
+        // Run full UltraHonk verification
+        verifier
+            .verify(&proof, &public_inputs)
+            .map_err(|e| Self::verify_error(e, VerifierError::VerificationFailed))?;
+
+        Self::record_verified(&env, Self::proof_hash(&env, &proof));
+        Ok(true)
+    }
+
+    /// Run every check of `verify_proof` except the final pairing, and fold
+    /// the proof's pairing points into `owner`'s deferred batch. The batch
+    /// is checked with a single pairing by `settle_deferred`; until then the
+    /// proof is not marked verified.
+    ///
+    /// Returns the number of proofs in the batch.
+    pub fn verify_proof_deferred(
+        env: Env,
+        owner: Address,
+        circuit: CircuitType,
+        proof: Bytes,
+        public_inputs: Bytes,
+    ) -> Result<u32, VerifierError> {
+        owner.require_auth();
+        let verifier = Self::load_verifier(&env, circuit)?;
+
+        if !verifier.accepts_proof_len(proof.len() as usize) {
             return Err(VerifierError::ProofSizeError);
         }
 
-        // Load VK for this circuit
-        let vk_bytes: Bytes = env
+        let proof_acc = verifier
+            .verify_deferred(&proof, &public_inputs)
+            .map_err(|e| Self::verify_error(e, VerifierError::VerificationFailed))?;
+
+        let key = StorageKey::Deferred(owner);
+        let batch = match env.storage().persistent().get::<_, DeferredBatch>(&key) {
+            Some(mut batch) => {
+                let mut acc = PairingAccumulator::from_bytes(&batch.accumulator.to_array());
+                acc.fold(&env, proof_acc.points());
+                batch.accumulator = BytesN::from_array(&env, &acc.to_bytes());
+                batch.proofs.push_back(Self::proof_hash(&env, &proof));
+                batch
+            }
+            None => DeferredBatch {
+                accumulator: BytesN::from_array(&env, &proof_acc.to_bytes()),
+                proofs: Vec::from_array(&env, [Self::proof_hash(&env, &proof)]),
+            },
+        };
+        env.storage().persistent().set(&key, &batch);
+        Ok(batch.proofs.len())
+    }
+
+    /// Perform the single pairing check of `owner`'s deferred batch and
+    /// clear it. On success every proof in the batch is stored as verified
+    /// and emits `proof_verified`; on failure none is, and `Ok(false)` is
+    /// returned so the batch is still cleared.
+    pub fn settle_deferred(env: Env, owner: Address) -> Result<bool, VerifierError> {
+        owner.require_auth();
+        let key = StorageKey::Deferred(owner);
+        let batch: DeferredBatch = env
             .storage()
             .persistent()
-            .get(&StorageKey::Vk(circuit))
-            .ok_or(VerifierError::NoVkForCircuit)?;
+            .get(&key)
+            .ok_or(VerifierError::NoDeferredBatch)?;
+        env.storage().persistent().remove(&key);
 
-        // Parse VK and create verifier
-        let verifier =
-            UltraHonkVerifier::new(&env, &vk_bytes).map_err(|_| VerifierError::VkParseError)?;
+        if !PairingAccumulator::from_bytes(&batch.accumulator.to_array()).check(&env) {
+            return Ok(false);
+        }
+        for proof_hash in batch.proofs.iter() {
+            Self::record_verified(&env, proof_hash);
+        }
+        Ok(true)
+    }
+
+    /// Verify a proof of a circuit built in public-input commitment mode.
+    ///
+    /// Instead of the full public inputs, the caller sends `keccak256` of
+    /// the inputs the circuit folds into its commitment, and the `exposed`
+    /// 32-byte fields the caller needs to read (deck root, cards). The
+    /// verifier rebuilds the circuit's public inputs from them, so the
+    /// folded inputs never appear in calldata.
+    pub fn verify_proof_committed(
+        env: Env,
+        circuit: CircuitType,
+        proof: Bytes,
+        public_inputs_hash: BytesN<32>,
+        exposed: Bytes,
+    ) -> Result<bool, VerifierError> {
+        let verifier = Self::load_verifier(&env, circuit)?;
+
+        if !verifier.accepts_proof_len(proof.len() as usize) {
+            return Err(VerifierError::ProofSizeError);
+        }
 
-        // Run full UltraHonk verification
         verifier
-            .verify(&proof, &public_inputs)
-            .map_err(|_| VerifierError::VerificationFailed)?;
+            .verify_committed(&proof, &public_inputs_hash, &exposed)
+            .map_err(|e| Self::verify_error(e, VerifierError::VerificationFailed))?;
 
-        // Store proof hash for auditability
-        let proof_hash = env.crypto().keccak256(&proof);
-        env.storage()
-            .persistent()
-            .set(&StorageKey::ProofVerified(proof_hash.clone().into()), &true);
+        Self::record_verified(&env, Self::proof_hash(&env, &proof));
+        Ok(true)
+    }
 
-        env.events()
-            .publish((Symbol::new(&env, "proof_verified"),), proof_hash);
+    /// Verify several proofs, possibly for different circuits, with a single
+    /// final pairing check. Each circuit's VK is loaded and parsed once.
+    ///
+    /// Succeeds only if every proof verifies; a failure does not say which
+    /// proof was invalid. On success each proof hash is stored and emits
+    /// `proof_verified`, as with `verify_proof`.
+    pub fn verify_batch(env: Env, proofs: Vec<BatchProof>) -> Result<bool, VerifierError> {
+        if proofs.is_empty() {
+            return Err(VerifierError::EmptyBatch);
+        }
+
+        let mut verifiers: [Option<UltraHonkVerifier>; CircuitType::COUNT] = Default::default();
+        let mut points = alloc::vec::Vec::with_capacity(proofs.len() as usize);
+        for item in proofs.iter() {
+            let slot = &mut verifiers[item.circuit.index()];
+            if slot.is_none() {
+                *slot = Some(Self::load_verifier(&env, item.circuit.clone())?);
+            }
+            let verifier = slot.as_ref().unwrap();
 
+            if !verifier.accepts_proof_len(item.proof.len() as usize) {
+                return Err(VerifierError::ProofSizeError);
+            }
+            points.push(
+                verifier
+                    .pairing_points(&item.proof, &item.public_inputs)
+                    .map_err(|e| Self::verify_error(e, VerifierError::VerificationFailed))?,
+            );
+        }
+        verify_pairing_batch(&env, &points).map_err(|_| VerifierError::VerificationFailed)?;
+
+        for item in proofs.iter() {
+            Self::record_verified(&env, Self::proof_hash(&env, &item.proof));
+        }
         Ok(true)
     }
 
@@ -174,3 +345,52 @@ impl ZkVerifierContract {
         Self::verify_proof(env, CircuitType::ShowdownValid, proof, public_inputs)
     }
 }
+
+impl ZkVerifierContract {
+    /// The stored VK bytes for `circuit`.
+    fn load_vk(env: &Env, circuit: CircuitType) -> Result<Bytes, VerifierError> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Vk(circuit))
+            .ok_or(VerifierError::NoVkForCircuit)
+    }
+
+    /// Load and parse the stored VK for `circuit`.
+    fn load_verifier(env: &Env, circuit: CircuitType) -> Result<UltraHonkVerifier, VerifierError> {
+        UltraHonkVerifier::new(env, &Self::load_vk(env, circuit)?)
+            .map_err(|e| Self::verify_error(e, VerifierError::VkParseError))
+    }
+
+    #[cfg(feature = "testutils")]
+    fn is_test_mode_vk(env: &Env, vk_bytes: &Bytes) -> bool {
+        *vk_bytes == Bytes::from_slice(env, TEST_MODE_VK)
+    }
+
+    #[cfg(not(feature = "testutils"))]
+    fn is_test_mode_vk(_env: &Env, _vk_bytes: &Bytes) -> bool {
+        false
+    }
+
+    /// Report a VK that does not match the proof (or itself) as
+    /// `VkMismatch`, and any other verifier error as `otherwise`.
+    fn verify_error(e: VerifyError, otherwise: VerifierError) -> VerifierError {
+        match e {
+            VerifyError::VkMismatch(_) => VerifierError::VkMismatch,
+            _ => otherwise,
+        }
+    }
+
+    fn proof_hash(env: &Env, proof: &Bytes) -> BytesN<32> {
+        env.crypto().keccak256(proof).into()
+    }
+
+    /// Store the proof hash for auditability and announce it.
+    fn record_verified(env: &Env, proof_hash: BytesN<32>) {
+        env.storage()
+            .persistent()
+            .set(&StorageKey::ProofVerified(proof_hash.clone()), &true);
+
+        env.events()
+            .publish((Symbol::new(env, "proof_verified"),), proof_hash);
+    }
+}
@@ -0,0 +1,68 @@
+#![no_std]
+
+//! Interface of the Stellar Game Studio Game Hub, shared by the games that
+//! call it and by the mock in contracts/game-hub/. Games call the hub
+//! through [`GameHubClient`]; hub implementations implement [`GameHub`]
+//! with `#[contractimpl]`, so both sides compile against one definition.
+//!
+//! In production the Game Hub lives at
+//! CB4VZAT2U3UC6XFK3N23SKRF2NDCMP3QHJYMCHHFMZO7MRQO6DQ2EMYG.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Vec};
+
+/// Payout breakdown of one settled hand.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementReport {
+    pub session_id: u32,
+    pub hand_number: u32,
+    pub pot: i128,
+    pub rake: i128,
+    /// Net stack change of every seated player over the hand.
+    pub deltas: Vec<(Address, i128)>,
+}
+
+/// Blinds and buy-in range of the table a session is played at.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TableStakes {
+    pub small_blind: i128,
+    pub big_blind: i128,
+    pub min_buy_in: i128,
+    pub max_buy_in: i128,
+}
+
+/// One player's chip movement over a hand.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HandResult {
+    pub player: Address,
+    /// Stack the player started the hand with.
+    pub stake: i128,
+    /// Profit (positive) or loss (negative) over the hand, after rake.
+    pub net: i128,
+}
+
+#[contractclient(name = "GameHubClient")]
+pub trait GameHub {
+    /// `stakes[i]` is what `players[i]` brings to the game, played at
+    /// `table_stakes`.
+    fn start_game(
+        env: Env,
+        game_id: Address,
+        session_id: u32,
+        players: Vec<Address>,
+        stakes: Vec<i128>,
+        table_stakes: TableStakes,
+    );
+
+    /// Every player's profit or loss over the session's hand, sent before
+    /// `end_game`.
+    fn hand_result(env: Env, session_id: u32, results: Vec<HandResult>);
+
+    /// One `(player, net result)` entry per player of the session.
+    fn end_game(env: Env, session_id: u32, results: Vec<(Address, i128)>);
+
+    /// Payout breakdown of the session's hand, sent after `end_game`.
+    fn report_settlement(env: Env, report: SettlementReport);
+}
@@ -0,0 +1,237 @@
+//! Remote attestation of nodes running inside a TEE.
+//!
+//! A node in TEE mode asks the platform for an attestation quote whose report
+//! data commits to its node ID and identity key, and publishes it on
+//! `/health`. Since the identity handshake proves the node holds that key, a
+//! quote cannot be lifted from one node and presented by another.
+//!
+//! The measurement of the launched image is compared by hash: the SHA-256 of
+//! the raw measurement is what operators register in the committee registry
+//! and what players can check a node against. Checking the quote's signature
+//! chain back to the hardware vendor needs the vendor's collateral and is left
+//! to an external verifier.
+
+use ed25519_dalek::VerifyingKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+const DOMAIN: &str = "stellar-poker-node-attestation:v1";
+
+/// Size of a SEV-SNP attestation report.
+const SNP_REPORT_LEN: usize = 0x4a0;
+const SNP_REPORT_DATA: usize = 0x50;
+const SNP_MEASUREMENT: usize = 0x90;
+
+/// TDX quote v4: a 48-byte header followed by the TD report body.
+const TDX_HEADER_LEN: usize = 48;
+const TDX_BODY_LEN: usize = 584;
+const TDX_TEE_TYPE: u32 = 0x81;
+const TDX_MRTD: usize = TDX_HEADER_LEN + 136;
+const TDX_REPORT_DATA: usize = TDX_HEADER_LEN + 520;
+
+const MEASUREMENT_LEN: usize = 48;
+const REPORT_DATA_LEN: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TeeProvider {
+    SevSnp,
+    Tdx,
+}
+
+impl TeeProvider {
+    pub fn parse(name: &str) -> Result<TeeProvider, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "sev-snp" | "sev_guest" => Ok(TeeProvider::SevSnp),
+            "tdx" | "tdx_guest" => Ok(TeeProvider::Tdx),
+            other => Err(format!("unknown TEE provider '{}'", other)),
+        }
+    }
+}
+
+/// A node's attestation, as published on `/health`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attestation {
+    pub provider: TeeProvider,
+    /// Hex quote or report, as the platform produced it.
+    pub quote: String,
+    /// Hex SHA-256 of the launch measurement.
+    pub measurement_hash: String,
+}
+
+/// The fields of a quote this crate checks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuoteBody {
+    pub measurement: [u8; MEASUREMENT_LEN],
+    pub report_data: [u8; REPORT_DATA_LEN],
+}
+
+impl QuoteBody {
+    pub fn measurement_hash(&self) -> [u8; 32] {
+        Sha256::digest(self.measurement).into()
+    }
+}
+
+/// The report data a node's quote must carry: binds the quote to the node
+/// and its identity key.
+pub fn report_data(node_id: u32, identity_key: &VerifyingKey) -> [u8; REPORT_DATA_LEN] {
+    let mut hasher = Sha512::new();
+    hasher.update(format!("{}\n{}\n", DOMAIN, node_id).as_bytes());
+    hasher.update(identity_key.as_bytes());
+    hasher.finalize().into()
+}
+
+fn field<const N: usize>(quote: &[u8], offset: usize) -> [u8; N] {
+    let mut out = [0u8; N];
+    out.copy_from_slice(&quote[offset..offset + N]);
+    out
+}
+
+/// Pull the measurement and report data out of a raw quote.
+pub fn parse_quote(provider: TeeProvider, quote: &[u8]) -> Result<QuoteBody, String> {
+    match provider {
+        TeeProvider::SevSnp => {
+            if quote.len() < SNP_REPORT_LEN {
+                return Err(format!(
+                    "SEV-SNP report is {} bytes, expected {}",
+                    quote.len(),
+                    SNP_REPORT_LEN
+                ));
+            }
+            Ok(QuoteBody {
+                measurement: field(quote, SNP_MEASUREMENT),
+                report_data: field(quote, SNP_REPORT_DATA),
+            })
+        }
+        TeeProvider::Tdx => {
+            if quote.len() < TDX_HEADER_LEN + TDX_BODY_LEN {
+                return Err(format!("TDX quote is too short ({} bytes)", quote.len()));
+            }
+            let version = u16::from_le_bytes(field(quote, 0));
+            let tee_type = u32::from_le_bytes(field(quote, 4));
+            if version != 4 || tee_type != TDX_TEE_TYPE {
+                return Err(format!(
+                    "unsupported TDX quote (version {}, tee type {:#x})",
+                    version, tee_type
+                ));
+            }
+            Ok(QuoteBody {
+                measurement: field(quote, TDX_MRTD),
+                report_data: field(quote, TDX_REPORT_DATA),
+            })
+        }
+    }
+}
+
+/// Parse a hex measurement hash.
+pub fn parse_measurement_hash(hex_hash: &str) -> Result<[u8; 32], String> {
+    hex::decode(hex_hash.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| "measurement hash must be 32 hex-encoded bytes".to_string())
+}
+
+impl Attestation {
+    pub fn new(provider: TeeProvider, quote: &[u8]) -> Result<Attestation, String> {
+        let body = parse_quote(provider, quote)?;
+        Ok(Attestation {
+            provider,
+            quote: hex::encode(quote),
+            measurement_hash: hex::encode(body.measurement_hash()),
+        })
+    }
+
+    pub fn quote_bytes(&self) -> Result<Vec<u8>, String> {
+        hex::decode(&self.quote).map_err(|_| "attestation quote is not hex".to_string())
+    }
+
+    /// Check the quote is bound to `node_id` and `identity_key` and measures
+    /// one of the `allowed` images.
+    pub fn verify(
+        &self,
+        node_id: u32,
+        identity_key: &VerifyingKey,
+        allowed: &[[u8; 32]],
+    ) -> Result<QuoteBody, String> {
+        let body = parse_quote(self.provider, &self.quote_bytes()?)?;
+        if body.report_data != report_data(node_id, identity_key) {
+            return Err(format!(
+                "node {} quote is not bound to its identity key",
+                node_id
+            ));
+        }
+        let measurement_hash = body.measurement_hash();
+        if !allowed.contains(&measurement_hash) {
+            return Err(format!(
+                "node {} runs unregistered measurement {}",
+                node_id,
+                hex::encode(measurement_hash)
+            ));
+        }
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+
+    fn snp_report(measurement: [u8; 48], report_data: [u8; 64]) -> Vec<u8> {
+        let mut report = vec![0u8; SNP_REPORT_LEN];
+        report[0] = 2;
+        report[SNP_REPORT_DATA..SNP_REPORT_DATA + 64].copy_from_slice(&report_data);
+        report[SNP_MEASUREMENT..SNP_MEASUREMENT + 48].copy_from_slice(&measurement);
+        report
+    }
+
+    fn tdx_quote(measurement: [u8; 48], report_data: [u8; 64]) -> Vec<u8> {
+        let mut quote = vec![0u8; TDX_HEADER_LEN + TDX_BODY_LEN + 64];
+        quote[0..2].copy_from_slice(&4u16.to_le_bytes());
+        quote[4..8].copy_from_slice(&TDX_TEE_TYPE.to_le_bytes());
+        quote[TDX_MRTD..TDX_MRTD + 48].copy_from_slice(&measurement);
+        quote[TDX_REPORT_DATA..TDX_REPORT_DATA + 64].copy_from_slice(&report_data);
+        quote
+    }
+
+    #[test]
+    fn test_attestation_verify() {
+        let key = SigningKey::from_bytes(&[7u8; 32]).verifying_key();
+        let other = SigningKey::from_bytes(&[8u8; 32]).verifying_key();
+        let measurement = [3u8; 48];
+        let allowed = [Sha256::digest(measurement).into()];
+
+        for (provider, quote) in [
+            (
+                TeeProvider::SevSnp,
+                snp_report(measurement, report_data(1, &key)),
+            ),
+            (
+                TeeProvider::Tdx,
+                tdx_quote(measurement, report_data(1, &key)),
+            ),
+        ] {
+            let attestation = Attestation::new(provider, &quote).unwrap();
+            assert_eq!(attestation.measurement_hash, hex::encode(allowed[0]));
+            assert!(attestation.verify(1, &key, &allowed).is_ok());
+            assert!(attestation.verify(2, &key, &allowed).is_err());
+            assert!(attestation.verify(1, &other, &allowed).is_err());
+            assert!(attestation.verify(1, &key, &[[0u8; 32]]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_quote_rejects_malformed() {
+        let data = [0u8; 64];
+        assert!(parse_quote(TeeProvider::SevSnp, &[0u8; 100]).is_err());
+        let mut quote = tdx_quote([0u8; 48], data);
+        assert!(parse_quote(TeeProvider::Tdx, &quote).is_ok());
+        quote[0] = 3;
+        assert!(parse_quote(TeeProvider::Tdx, &quote).is_err());
+        assert_eq!(
+            TeeProvider::parse("sev_guest").unwrap(),
+            TeeProvider::SevSnp
+        );
+        assert!(TeeProvider::parse("sgx").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Signed commitments to node deal contributions.
+//!
+//! On `prepare-deal` each node commits to its secret permutation and salts
+//! with a SHA-256 digest signed by its Ed25519 key. The coordinator records
+//! the commitments with the hand; after the hand, nodes reveal their
+//! contributions and anyone can check them against the signed digests, so a
+//! node cannot later claim a different shuffle than the one it dealt with.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::now_unix_secs;
+
+const DIGEST_DOMAIN: &str = "stellar-poker-contribution-commitment:v1";
+const SIGNATURE_DOMAIN: &str = "stellar-poker-contribution-signature:v1";
+const PUBLIC_KEYS_ENV: &str = "NODE_SIGNING_PUBLIC_KEYS";
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContributionCommitment {
+    pub node_id: u32,
+    pub table_id: u32,
+    /// Hex SHA-256 over the table, node, permutation and salts.
+    pub commitment: String,
+    pub issued_at: u64,
+    /// Hex Ed25519 public key of the committing node.
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// Read the nodes' registered hex Ed25519 public keys, in node order, from
+/// the comma-separated `NODE_SIGNING_PUBLIC_KEYS`.
+pub fn registered_keys_from_env() -> Result<Option<Vec<VerifyingKey>>, String> {
+    let raw = match std::env::var(PUBLIC_KEYS_ENV) {
+        Ok(raw) if !raw.trim().is_empty() => raw,
+        _ => return Ok(None),
+    };
+    raw.split(',')
+        .map(|entry| {
+            let bytes: [u8; 32] = hex::decode(entry.trim())
+                .ok()
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| {
+                    format!("{} entries must be 32 hex-encoded bytes", PUBLIC_KEYS_ENV)
+                })?;
+            VerifyingKey::from_bytes(&bytes)
+                .map_err(|e| format!("invalid {}: {}", PUBLIC_KEYS_ENV, e))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// Digest binding a contribution to its table and node.
+pub fn contribution_digest(
+    table_id: u32,
+    node_id: u32,
+    permutation: &[u32],
+    salts: &[String],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(DIGEST_DOMAIN.as_bytes());
+    hasher.update(table_id.to_be_bytes());
+    hasher.update(node_id.to_be_bytes());
+    hasher.update((permutation.len() as u32).to_be_bytes());
+    for idx in permutation {
+        hasher.update(idx.to_be_bytes());
+    }
+    hasher.update((salts.len() as u32).to_be_bytes());
+    for salt in salts {
+        hasher.update((salt.len() as u32).to_be_bytes());
+        hasher.update(salt.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+#[derive(Clone)]
+pub struct CommitmentSigner {
+    key: SigningKey,
+}
+
+impl CommitmentSigner {
+    pub fn new(key: SigningKey) -> Self {
+        Self { key }
+    }
+
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self::new(SigningKey::from_bytes(seed))
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.key.verifying_key().to_bytes())
+    }
+
+    /// Sign an identity challenge; see [`crate::identity`].
+    pub fn sign_challenge(&self, node_id: u32, challenge: &str) -> String {
+        let message = crate::identity::challenge_message(node_id, challenge);
+        hex::encode(self.key.sign(message.as_bytes()).to_bytes())
+    }
+
+    pub fn commit(
+        &self,
+        table_id: u32,
+        node_id: u32,
+        permutation: &[u32],
+        salts: &[String],
+    ) -> ContributionCommitment {
+        let commitment = hex::encode(contribution_digest(table_id, node_id, permutation, salts));
+        let issued_at = now_unix_secs();
+        let message = signed_message(table_id, node_id, &commitment, issued_at);
+        ContributionCommitment {
+            node_id,
+            table_id,
+            commitment,
+            issued_at,
+            public_key: self.public_key_hex(),
+            signature: hex::encode(self.key.sign(message.as_bytes()).to_bytes()),
+        }
+    }
+}
+
+impl ContributionCommitment {
+    /// Check the signature, optionally requiring a specific (registered) key.
+    pub fn verify(&self, expected_key: Option<&VerifyingKey>) -> Result<(), String> {
+        let key_bytes: [u8; 32] = hex::decode(&self.public_key)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| "invalid commitment public key".to_string())?;
+        let key = VerifyingKey::from_bytes(&key_bytes)
+            .map_err(|e| format!("invalid commitment public key: {}", e))?;
+        if let Some(expected) = expected_key {
+            if expected != &key {
+                return Err(format!(
+                    "node {} commitment signed with unregistered key",
+                    self.node_id
+                ));
+            }
+        }
+        let signature: [u8; 64] = hex::decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| "invalid commitment signature".to_string())?;
+        let message = signed_message(
+            self.table_id,
+            self.node_id,
+            &self.commitment,
+            self.issued_at,
+        );
+        key.verify(message.as_bytes(), &Signature::from_bytes(&signature))
+            .map_err(|_| format!("node {} commitment signature mismatch", self.node_id))
+    }
+
+    /// Whether a revealed contribution is the one committed to.
+    pub fn matches(&self, permutation: &[u32], salts: &[String]) -> bool {
+        let digest = contribution_digest(self.table_id, self.node_id, permutation, salts);
+        hex::encode(digest) == self.commitment
+    }
+}
+
+fn signed_message(table_id: u32, node_id: u32, commitment: &str, issued_at: u64) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}",
+        SIGNATURE_DOMAIN, table_id, node_id, commitment, issued_at
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn contribution() -> (Vec<u32>, Vec<String>) {
+        let permutation: Vec<u32> = (0..52).rev().collect();
+        let salts = (0..52).map(|i| (i * 7919).to_string()).collect();
+        (permutation, salts)
+    }
+
+    #[test]
+    fn test_commit_verify_and_reveal() {
+        let signer = CommitmentSigner::new(SigningKey::from_bytes(&[3u8; 32]));
+        let (permutation, salts) = contribution();
+        let commitment = signer.commit(4, 1, &permutation, &salts);
+
+        let registered = SigningKey::from_bytes(&[3u8; 32]).verifying_key();
+        assert!(commitment.verify(None).is_ok());
+        assert!(commitment.verify(Some(&registered)).is_ok());
+        assert!(commitment.matches(&permutation, &salts));
+
+        let mut swapped = permutation.clone();
+        swapped.swap(0, 1);
+        assert!(!commitment.matches(&swapped, &salts));
+    }
+
+    #[test]
+    fn test_rejects_tampered_or_foreign_commitment() {
+        let signer = CommitmentSigner::new(SigningKey::from_bytes(&[3u8; 32]));
+        let (permutation, salts) = contribution();
+        let commitment = signer.commit(4, 1, &permutation, &salts);
+
+        let mut other_table = commitment.clone();
+        other_table.table_id = 5;
+        assert!(other_table.verify(None).is_err());
+
+        let other_key = SigningKey::from_bytes(&[9u8; 32]).verifying_key();
+        assert!(commitment.verify(Some(&other_key)).is_err());
+    }
+}
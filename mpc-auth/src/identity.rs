@@ -0,0 +1,93 @@
+//! Node identity handshake.
+//!
+//! A node's identity is the Ed25519 key it signs contribution commitments
+//! with, registered for its member address in the committee registry. To
+//! prove it holds that key, a node signs a caller-chosen challenge bound to
+//! its node ID.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+const DOMAIN: &str = "stellar-poker-node-identity:v1";
+
+/// A node's answer to an identity challenge.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IdentityProof {
+    pub node_id: u32,
+    /// Hex Ed25519 identity key.
+    pub identity_key: String,
+    pub signature: String,
+}
+
+pub(crate) fn challenge_message(node_id: u32, challenge: &str) -> String {
+    format!("{}\n{}\n{}", DOMAIN, node_id, challenge)
+}
+
+/// Parse a hex-encoded Ed25519 public key.
+pub fn parse_identity_key(hex_key: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = hex::decode(hex_key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| "identity key must be 32 hex-encoded bytes".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("invalid identity key: {}", e))
+}
+
+impl IdentityProof {
+    /// Check the proof answers `challenge` for `node_id` with `expected_key`.
+    pub fn verify(
+        &self,
+        expected_key: &VerifyingKey,
+        node_id: u32,
+        challenge: &str,
+    ) -> Result<(), String> {
+        if self.node_id != node_id {
+            return Err(format!(
+                "identity proof is for node {}, expected {}",
+                self.node_id, node_id
+            ));
+        }
+        if &parse_identity_key(&self.identity_key)? != expected_key {
+            return Err(format!(
+                "node {} presented an unregistered identity key",
+                node_id
+            ));
+        }
+        let signature: [u8; 64] = hex::decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| "invalid identity signature".to_string())?;
+        expected_key
+            .verify(
+                challenge_message(node_id, challenge).as_bytes(),
+                &Signature::from_bytes(&signature),
+            )
+            .map_err(|_| format!("node {} identity signature mismatch", node_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commitment::CommitmentSigner;
+    use ed25519_dalek::SigningKey;
+
+    fn proof(signer: &CommitmentSigner, node_id: u32, challenge: &str) -> IdentityProof {
+        IdentityProof {
+            node_id,
+            identity_key: signer.public_key_hex(),
+            signature: signer.sign_challenge(node_id, challenge),
+        }
+    }
+
+    #[test]
+    fn test_identity_challenge_roundtrip() {
+        let signer = CommitmentSigner::from_seed(&[7u8; 32]);
+        let key = parse_identity_key(&signer.public_key_hex()).unwrap();
+        assert!(proof(&signer, 2, "abc").verify(&key, 2, "abc").is_ok());
+        assert!(proof(&signer, 2, "abc").verify(&key, 2, "abd").is_err());
+        assert!(proof(&signer, 2, "abc").verify(&key, 1, "abc").is_err());
+
+        let other = SigningKey::from_bytes(&[8u8; 32]).verifying_key();
+        assert!(proof(&signer, 2, "abc").verify(&other, 2, "abc").is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! Signed-request authentication for coordinator → node and node → node calls.
+//!
+//! Every request carries a sender ID, unix timestamp, random nonce and an
+//! HMAC-SHA256 over the method, path, those fields and the SHA-256 of the
+//! body, keyed by the committee's shared `MPC_AUTH_SECRET`. Nodes reject
+//! requests outside the timestamp window and any nonce seen within it, so a
+//! captured request cannot be replayed (e.g. to re-roll a deal contribution).
+//!
+//! `perm-lookup` additionally requires a per-call coordinator signature; see
+//! [`lookup`]. Node deal contributions are committed to with node-signed
+//! digests; see [`commitment`], and can be derived from a VRF; see [`vrf`].
+//! The same key is the node's on-chain identity; see [`identity`], which
+//! nodes running in a TEE also bind their attestation quotes to; see
+//! [`attestation`].
+
+pub mod attestation;
+pub mod commitment;
+pub mod identity;
+pub mod lookup;
+pub mod vrf;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+pub const SENDER_HEADER: &str = "x-mpc-auth-sender";
+pub const TIMESTAMP_HEADER: &str = "x-mpc-auth-timestamp";
+pub const NONCE_HEADER: &str = "x-mpc-auth-nonce";
+pub const SIGNATURE_HEADER: &str = "x-mpc-auth-signature";
+
+/// Accepted clock skew between signer and verifier, in seconds.
+pub const MAX_SKEW_SECS: u64 = 60;
+
+const SECRET_ENV: &str = "MPC_AUTH_SECRET";
+const MIN_SECRET_LEN: usize = 16;
+const DOMAIN: &str = "stellar-poker-mpc-auth:v1";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Read the shared secret from `MPC_AUTH_SECRET`. `Ok(None)` when unset.
+pub fn secret_from_env() -> Result<Option<Vec<u8>>, String> {
+    match std::env::var(SECRET_ENV) {
+        Ok(secret) if !secret.trim().is_empty() => {
+            let secret = secret.trim().as_bytes().to_vec();
+            if secret.len() < MIN_SECRET_LEN {
+                return Err(format!(
+                    "{} must be at least {} bytes",
+                    SECRET_ENV, MIN_SECRET_LEN
+                ));
+            }
+            Ok(Some(secret))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestSigner {
+    sender: String,
+    secret: Vec<u8>,
+}
+
+impl RequestSigner {
+    pub fn new(sender: impl Into<String>, secret: Vec<u8>) -> Self {
+        Self {
+            sender: sender.into(),
+            secret,
+        }
+    }
+
+    /// Headers authenticating a request with the given method, URL path and body.
+    pub fn sign(&self, method: &str, path: &str, body: &[u8]) -> Vec<(&'static str, String)> {
+        let timestamp = now_unix_secs();
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let nonce = hex::encode(nonce);
+        let signature = compute_signature(
+            &self.secret,
+            method,
+            path,
+            &self.sender,
+            timestamp,
+            &nonce,
+            body,
+        );
+        vec![
+            (SENDER_HEADER, self.sender.clone()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (NONCE_HEADER, nonce),
+            (SIGNATURE_HEADER, hex::encode(signature)),
+        ]
+    }
+}
+
+/// Authentication headers extracted from an incoming request.
+pub struct SignedHeaders<'a> {
+    pub sender: &'a str,
+    pub timestamp: &'a str,
+    pub nonce: &'a str,
+    pub signature: &'a str,
+}
+
+pub struct RequestVerifier {
+    secret: Vec<u8>,
+    /// Nonces accepted within the skew window -> timestamp.
+    seen_nonces: Mutex<HashMap<String, u64>>,
+}
+
+impl RequestVerifier {
+    pub fn new(secret: Vec<u8>) -> Self {
+        Self {
+            secret,
+            seen_nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Verify a signed request. Returns the authenticated sender ID.
+    pub fn verify(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &SignedHeaders<'_>,
+        body: &[u8],
+    ) -> Result<String, String> {
+        self.verify_at(method, path, headers, body, now_unix_secs())
+    }
+
+    fn verify_at(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &SignedHeaders<'_>,
+        body: &[u8],
+        now: u64,
+    ) -> Result<String, String> {
+        let timestamp: u64 = headers
+            .timestamp
+            .parse()
+            .map_err(|_| "invalid auth timestamp".to_string())?;
+        if now.abs_diff(timestamp) > MAX_SKEW_SECS {
+            return Err("auth timestamp outside accepted window".to_string());
+        }
+        if headers.nonce.is_empty() || headers.nonce.len() > 64 {
+            return Err("invalid auth nonce".to_string());
+        }
+        let signature =
+            hex::decode(headers.signature).map_err(|_| "invalid auth signature".to_string())?;
+
+        let mut mac = new_mac(&self.secret);
+        update_mac(
+            &mut mac,
+            method,
+            path,
+            headers.sender,
+            timestamp,
+            headers.nonce,
+            body,
+        );
+        mac.verify_slice(&signature)
+            .map_err(|_| "auth signature mismatch".to_string())?;
+
+        let mut seen = self
+            .seen_nonces
+            .lock()
+            .map_err(|_| "nonce cache poisoned".to_string())?;
+        seen.retain(|_, ts| now.abs_diff(*ts) <= MAX_SKEW_SECS);
+        if seen.insert(headers.nonce.to_string(), timestamp).is_some() {
+            return Err("replayed auth nonce".to_string());
+        }
+        Ok(headers.sender.to_string())
+    }
+}
+
+fn compute_signature(
+    secret: &[u8],
+    method: &str,
+    path: &str,
+    sender: &str,
+    timestamp: u64,
+    nonce: &str,
+    body: &[u8],
+) -> Vec<u8> {
+    let mut mac = new_mac(secret);
+    update_mac(&mut mac, method, path, sender, timestamp, nonce, body);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn new_mac(secret: &[u8]) -> HmacSha256 {
+    // HMAC accepts keys of any length.
+    HmacSha256::new_from_slice(secret).expect("hmac key")
+}
+
+fn update_mac(
+    mac: &mut HmacSha256,
+    method: &str,
+    path: &str,
+    sender: &str,
+    timestamp: u64,
+    nonce: &str,
+    body: &[u8],
+) {
+    let body_hash = hex::encode(Sha256::digest(body));
+    let message = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        DOMAIN,
+        method.to_ascii_uppercase(),
+        path,
+        sender,
+        timestamp,
+        nonce,
+        body_hash
+    );
+    mac.update(message.as_bytes());
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn header<'a>(headers: &'a [(&'static str, String)], name: &str) -> &'a str {
+        headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap()
+    }
+
+    fn signed<'a>(headers: &'a [(&'static str, String)]) -> SignedHeaders<'a> {
+        SignedHeaders {
+            sender: header(headers, SENDER_HEADER),
+            timestamp: header(headers, TIMESTAMP_HEADER),
+            nonce: header(headers, NONCE_HEADER),
+            signature: header(headers, SIGNATURE_HEADER),
+        }
+    }
+
+    #[test]
+    fn test_sign_then_verify() {
+        let signer = RequestSigner::new("coordinator", SECRET.to_vec());
+        let verifier = RequestVerifier::new(SECRET.to_vec());
+        let headers = signer.sign("post", "/table/1/prepare-deal", b"{}");
+        let sender = verifier
+            .verify("POST", "/table/1/prepare-deal", &signed(&headers), b"{}")
+            .unwrap();
+        assert_eq!(sender, "coordinator");
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let signer = RequestSigner::new("node-0", SECRET.to_vec());
+        let verifier = RequestVerifier::new(SECRET.to_vec());
+        let headers = signer.sign("POST", "/session/s/shares", b"body");
+        let h = signed(&headers);
+        assert!(verifier
+            .verify("POST", "/session/s/shares", &h, b"bodY")
+            .is_err());
+        assert!(verifier
+            .verify("POST", "/session/t/shares", &h, b"body")
+            .is_err());
+        assert!(verifier
+            .verify("GET", "/session/s/shares", &h, b"body")
+            .is_err());
+        let forged = SignedHeaders {
+            sender: "coordinator",
+            ..signed(&headers)
+        };
+        assert!(verifier
+            .verify("POST", "/session/s/shares", &forged, b"body")
+            .is_err());
+
+        let other = RequestVerifier::new(b"another-secret-of-enough-len".to_vec());
+        assert!(other
+            .verify("POST", "/session/s/shares", &h, b"body")
+            .is_err());
+    }
+
+    #[test]
+    fn test_rejects_replay() {
+        let signer = RequestSigner::new("coordinator", SECRET.to_vec());
+        let verifier = RequestVerifier::new(SECRET.to_vec());
+        let headers = signer.sign("POST", "/x", b"");
+        assert!(verifier
+            .verify("POST", "/x", &signed(&headers), b"")
+            .is_ok());
+        let err = verifier
+            .verify("POST", "/x", &signed(&headers), b"")
+            .unwrap_err();
+        assert!(err.contains("replayed"));
+    }
+
+    #[test]
+    fn test_rejects_stale_timestamp() {
+        let signer = RequestSigner::new("coordinator", SECRET.to_vec());
+        let verifier = RequestVerifier::new(SECRET.to_vec());
+        let headers = signer.sign("GET", "/session/s/status", b"");
+        let now = now_unix_secs();
+        let h = signed(&headers);
+        assert!(verifier
+            .verify_at("GET", "/session/s/status", &h, b"", now + MAX_SKEW_SECS + 5)
+            .is_err());
+        assert!(verifier
+            .verify_at("GET", "/session/s/status", &h, b"", now)
+            .is_ok());
+    }
+}
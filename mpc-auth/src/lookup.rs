@@ -0,0 +1,240 @@
+//! Coordinator-signed authorizations for node `perm-lookup` calls.
+//!
+//! A lookup maps deck positions to card values, so nodes only answer when the
+//! request carries an Ed25519 authorization from the coordinator bound to the
+//! target node, table, player and exact indices, with a short expiry. Nodes
+//! hold only the coordinator's public key: a peer that knows the shared HMAC
+//! secret still cannot mint lookups.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::now_unix_secs;
+
+/// How long an issued authorization stays valid, in seconds.
+pub const LOOKUP_AUTH_TTL_SECS: u64 = 30;
+
+const SIGNING_KEY_ENV: &str = "PERM_LOOKUP_SIGNING_KEY";
+const PUBLIC_KEY_ENV: &str = "PERM_LOOKUP_PUBLIC_KEY";
+const DOMAIN: &str = "stellar-poker-perm-lookup:v1";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PermLookupAuthorization {
+    pub player: String,
+    pub expires_at: u64,
+    pub nonce: String,
+    pub signature: String,
+}
+
+/// Read the coordinator's hex-encoded Ed25519 seed from `PERM_LOOKUP_SIGNING_KEY`.
+pub fn signing_key_from_env() -> Result<Option<LookupAuthorizer>, String> {
+    read_key_env(SIGNING_KEY_ENV)?
+        .map(|seed| Ok(LookupAuthorizer::new(SigningKey::from_bytes(&seed))))
+        .transpose()
+}
+
+/// Read the coordinator's hex-encoded Ed25519 public key from `PERM_LOOKUP_PUBLIC_KEY`.
+pub fn verifying_key_from_env() -> Result<Option<LookupVerifier>, String> {
+    read_key_env(PUBLIC_KEY_ENV)?
+        .map(|bytes| {
+            VerifyingKey::from_bytes(&bytes)
+                .map(LookupVerifier::new)
+                .map_err(|e| format!("invalid {}: {}", PUBLIC_KEY_ENV, e))
+        })
+        .transpose()
+}
+
+fn read_key_env(name: &str) -> Result<Option<[u8; 32]>, String> {
+    match std::env::var(name) {
+        Ok(raw) if !raw.trim().is_empty() => {
+            let bytes = hex::decode(raw.trim()).map_err(|e| format!("invalid {}: {}", name, e))?;
+            let key: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| format!("{} must be 32 hex-encoded bytes", name))?;
+            Ok(Some(key))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[derive(Clone)]
+pub struct LookupAuthorizer {
+    key: SigningKey,
+}
+
+impl LookupAuthorizer {
+    pub fn new(key: SigningKey) -> Self {
+        Self { key }
+    }
+
+    /// Hex-encoded public key to configure on the nodes.
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.key.verifying_key().to_bytes())
+    }
+
+    pub fn authorize(
+        &self,
+        node_id: u32,
+        table_id: u32,
+        player: &str,
+        indices: &[u32],
+    ) -> PermLookupAuthorization {
+        let expires_at = now_unix_secs() + LOOKUP_AUTH_TTL_SECS;
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let nonce = hex::encode(nonce);
+        let message = lookup_message(node_id, table_id, player, indices, expires_at, &nonce);
+        PermLookupAuthorization {
+            player: player.to_string(),
+            expires_at,
+            nonce,
+            signature: hex::encode(self.key.sign(message.as_bytes()).to_bytes()),
+        }
+    }
+}
+
+pub struct LookupVerifier {
+    key: VerifyingKey,
+    /// Nonces of unexpired authorizations already used -> expiry.
+    used_nonces: Mutex<HashMap<String, u64>>,
+}
+
+impl LookupVerifier {
+    pub fn new(key: VerifyingKey) -> Self {
+        Self {
+            key,
+            used_nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check an authorization for a lookup on this node. Each one is single-use.
+    pub fn verify(
+        &self,
+        node_id: u32,
+        table_id: u32,
+        indices: &[u32],
+        auth: &PermLookupAuthorization,
+    ) -> Result<(), String> {
+        self.verify_at(node_id, table_id, indices, auth, now_unix_secs())
+    }
+
+    fn verify_at(
+        &self,
+        node_id: u32,
+        table_id: u32,
+        indices: &[u32],
+        auth: &PermLookupAuthorization,
+        now: u64,
+    ) -> Result<(), String> {
+        if auth.expires_at < now {
+            return Err("lookup authorization expired".to_string());
+        }
+        if auth.expires_at > now + LOOKUP_AUTH_TTL_SECS + crate::MAX_SKEW_SECS {
+            return Err("lookup authorization expiry too far in the future".to_string());
+        }
+        let signature: [u8; 64] = hex::decode(&auth.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| "invalid lookup authorization signature".to_string())?;
+        let message = lookup_message(
+            node_id,
+            table_id,
+            &auth.player,
+            indices,
+            auth.expires_at,
+            &auth.nonce,
+        );
+        self.key
+            .verify(message.as_bytes(), &Signature::from_bytes(&signature))
+            .map_err(|_| "lookup authorization signature mismatch".to_string())?;
+
+        let mut used = self
+            .used_nonces
+            .lock()
+            .map_err(|_| "nonce cache poisoned".to_string())?;
+        used.retain(|_, expiry| *expiry >= now);
+        if used.insert(auth.nonce.clone(), auth.expires_at).is_some() {
+            return Err("lookup authorization already used".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn lookup_message(
+    node_id: u32,
+    table_id: u32,
+    player: &str,
+    indices: &[u32],
+    expires_at: u64,
+    nonce: &str,
+) -> String {
+    let indices = indices
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        DOMAIN, node_id, table_id, player, indices, expires_at, nonce
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keys() -> (LookupAuthorizer, LookupVerifier) {
+        let signing = SigningKey::from_bytes(&[7u8; 32]);
+        let verifying = signing.verifying_key();
+        (
+            LookupAuthorizer::new(signing),
+            LookupVerifier::new(verifying),
+        )
+    }
+
+    #[test]
+    fn test_authorize_then_verify() {
+        let (authorizer, verifier) = keys();
+        let auth = authorizer.authorize(2, 5, "GPLAYER", &[3, 17]);
+        assert!(verifier.verify(2, 5, &[3, 17], &auth).is_ok());
+    }
+
+    #[test]
+    fn test_binding_to_node_table_and_indices() {
+        let (authorizer, verifier) = keys();
+        let auth = authorizer.authorize(2, 5, "GPLAYER", &[3, 17]);
+        assert!(verifier.verify(1, 5, &[3, 17], &auth).is_err());
+        assert!(verifier.verify(2, 6, &[3, 17], &auth).is_err());
+        assert!(verifier.verify(2, 5, &[3, 18], &auth).is_err());
+        assert!(verifier.verify(2, 5, &[17, 3], &auth).is_err());
+
+        let mut other_player = auth.clone();
+        other_player.player = "GOTHER".to_string();
+        assert!(verifier.verify(2, 5, &[3, 17], &other_player).is_err());
+    }
+
+    #[test]
+    fn test_single_use_and_expiry() {
+        let (authorizer, verifier) = keys();
+        let auth = authorizer.authorize(0, 1, "GPLAYER", &[9]);
+        assert!(verifier.verify(0, 1, &[9], &auth).is_ok());
+        assert!(verifier.verify(0, 1, &[9], &auth).is_err());
+
+        let late = authorizer.authorize(0, 1, "GPLAYER", &[9]);
+        assert!(verifier
+            .verify_at(0, 1, &[9], &late, late.expires_at + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_rejects_foreign_key() {
+        let (authorizer, _) = keys();
+        let other = LookupVerifier::new(SigningKey::from_bytes(&[8u8; 32]).verifying_key());
+        let auth = authorizer.authorize(0, 1, "GPLAYER", &[9]);
+        assert!(other.verify(0, 1, &[9], &auth).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! VRF-derived deal contributions.
+//!
+//! Instead of drawing its permutation and salts from the OS RNG, a node can
+//! derive them from an sr25519 VRF over (table, node, hand number). Outsiders
+//! cannot predict the result without the node's VRF secret, while the node
+//! can re-derive any contribution from its key instead of storing it, and a
+//! revealed [`VrfProof`] lets anyone check that the contribution is the one
+//! the VRF determines for that hand.
+//!
+//! The proof fixes the contribution, so it must stay secret until the hand
+//! is over.
+
+use rand::seq::SliceRandom;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use schnorrkel::context::SigningTranscript;
+use schnorrkel::vrf::{VRFPreOut, VRFProof};
+use schnorrkel::{signing_context, ExpansionMode, Keypair, MiniSecretKey, PublicKey};
+use serde::{Deserialize, Serialize};
+
+const CONTEXT: &[u8] = b"stellar-poker-contribution-vrf:v1";
+const SEED_LABEL: &[u8] = b"contribution-seed";
+
+/// A permutation and salts derived from a VRF output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivedContribution {
+    pub permutation: Vec<u32>,
+    pub salts: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VrfProof {
+    pub hand_number: u64,
+    /// Hex sr25519 public key of the node.
+    pub public_key: String,
+    /// Hex VRF pre-output.
+    pub output: String,
+    pub proof: String,
+}
+
+#[derive(Clone)]
+pub struct ContributionVrf {
+    keypair: Keypair,
+}
+
+impl ContributionVrf {
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let keypair = MiniSecretKey::from_bytes(seed)
+            .expect("32-byte seed is a valid mini secret key")
+            .expand_to_keypair(ExpansionMode::Ed25519);
+        Self { keypair }
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.keypair.public.to_bytes())
+    }
+
+    /// Derive this node's contribution for a hand, with the proof that
+    /// discloses it.
+    pub fn derive(
+        &self,
+        table_id: u32,
+        node_id: u32,
+        hand_number: u64,
+        deck_size: usize,
+    ) -> (DerivedContribution, VrfProof) {
+        let (inout, proof, _) = self
+            .keypair
+            .vrf_sign(transcript(table_id, node_id, hand_number));
+        let seed: [u8; 32] = inout.make_bytes(SEED_LABEL);
+        let proof = VrfProof {
+            hand_number,
+            public_key: self.public_key_hex(),
+            output: hex::encode(inout.to_preout().to_bytes()),
+            proof: hex::encode(proof.to_bytes()),
+        };
+        (contribution_from_seed(seed, deck_size), proof)
+    }
+}
+
+impl VrfProof {
+    /// Verify the proof for `table_id`/`node_id` and return the contribution
+    /// it determines.
+    pub fn verify(
+        &self,
+        table_id: u32,
+        node_id: u32,
+        deck_size: usize,
+    ) -> Result<DerivedContribution, String> {
+        let public_key = hex::decode(&self.public_key)
+            .ok()
+            .and_then(|b| PublicKey::from_bytes(&b).ok())
+            .ok_or_else(|| "invalid VRF public key".to_string())?;
+        let output = hex::decode(&self.output)
+            .ok()
+            .and_then(|b| VRFPreOut::from_bytes(&b).ok())
+            .ok_or_else(|| "invalid VRF output".to_string())?;
+        let proof = hex::decode(&self.proof)
+            .ok()
+            .and_then(|b| VRFProof::from_bytes(&b).ok())
+            .ok_or_else(|| "invalid VRF proof".to_string())?;
+        let (inout, _) = public_key
+            .vrf_verify(
+                transcript(table_id, node_id, self.hand_number),
+                &output,
+                &proof,
+            )
+            .map_err(|_| format!("node {} VRF proof does not verify", node_id))?;
+        Ok(contribution_from_seed(
+            inout.make_bytes(SEED_LABEL),
+            deck_size,
+        ))
+    }
+}
+
+fn transcript(table_id: u32, node_id: u32, hand_number: u64) -> impl SigningTranscript {
+    let mut message = Vec::with_capacity(16);
+    message.extend_from_slice(&table_id.to_be_bytes());
+    message.extend_from_slice(&node_id.to_be_bytes());
+    message.extend_from_slice(&hand_number.to_be_bytes());
+    signing_context(CONTEXT).bytes(&message)
+}
+
+fn contribution_from_seed(seed: [u8; 32], deck_size: usize) -> DerivedContribution {
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    let mut permutation: Vec<u32> = (0..deck_size as u32).collect();
+    permutation.shuffle(&mut rng);
+    let salts = (0..deck_size).map(|_| rng.next_u64().to_string()).collect();
+    DerivedContribution { permutation, salts }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_derive_is_deterministic_and_verifiable() {
+        let vrf = ContributionVrf::from_seed(&[5u8; 32]);
+        let (first, proof) = vrf.derive(4, 1, 7, 52);
+        let (again, _) = vrf.derive(4, 1, 7, 52);
+        assert_eq!(first, again);
+
+        let mut sorted = first.permutation.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..52).collect::<Vec<u32>>());
+        assert_eq!(first.salts.len(), 52);
+
+        assert_eq!(proof.verify(4, 1, 52).unwrap(), first);
+        assert_ne!(vrf.derive(4, 1, 8, 52).0, first);
+    }
+
+    #[test]
+    fn test_proof_is_bound_to_table_and_node() {
+        let vrf = ContributionVrf::from_seed(&[5u8; 32]);
+        let (_, proof) = vrf.derive(4, 1, 7, 52);
+        assert!(proof.verify(5, 1, 52).is_err());
+        assert!(proof.verify(4, 2, 52).is_err());
+
+        let mut other_hand = proof.clone();
+        other_hand.hand_number = 8;
+        assert!(other_hand.verify(4, 1, 52).is_err());
+    }
+}
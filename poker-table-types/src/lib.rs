@@ -0,0 +1,1030 @@
+//! The poker-table contract's types, for services that talk to it through
+//! `stellar contract invoke`.
+//!
+//! The CLI renders contract values as JSON: structs as objects, unit enum
+//! variants as their name, data variants as `{"Variant": value}`, addresses
+//! and `BytesN` as strings, `u32` as numbers and `i128` as decimal strings.
+//! These mirrors deserialize `get_table` output and serialize arguments in
+//! that format. They must track `contracts/poker-table/src/types.rs`; the
+//! integration tests check the enums against the contract.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// `i128` as the CLI writes it (a decimal string), also accepting a JSON number.
+mod int {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Signed(i64),
+        Unsigned(u64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &i128, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<i128, D::Error> {
+        match Raw::deserialize(d)? {
+            Raw::Signed(v) => Ok(v.into()),
+            Raw::Unsigned(v) => Ok(v.into()),
+            Raw::Text(s) => s
+                .parse()
+                .map_err(|_| D::Error::custom(format!("invalid i128 '{}'", s))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableConfig {
+    pub token: String,
+    #[serde(with = "int")]
+    pub min_buy_in: i128,
+    #[serde(with = "int")]
+    pub max_buy_in: i128,
+    #[serde(with = "int")]
+    pub small_blind: i128,
+    #[serde(with = "int")]
+    pub big_blind: i128,
+    pub max_players: u32,
+    /// Ledgers the committee has to deal the hole cards.
+    pub deal_timeout_ledgers: u32,
+    /// Ledgers the committee has to reveal a board street.
+    pub reveal_timeout_ledgers: u32,
+    /// Ledgers the committee has to prove the showdown.
+    pub showdown_timeout_ledgers: u32,
+    /// Ledgers a player has to act in a betting round.
+    pub betting_timeout_ledgers: u32,
+    /// Extra ledgers each player may take past the betting timeout, per hand.
+    pub time_bank_ledgers: u32,
+    /// MPC committee members, any of whom may submit its proofs.
+    pub committee: Vec<String>,
+    /// Members needed to produce a proof.
+    pub committee_threshold: u32,
+    pub verifier: String,
+    pub game_hub: String,
+    /// Registry the table reports the committee to when it misses a deadline.
+    pub committee_registry: String,
+    /// Rake taken from each pot, in basis points.
+    pub rake_bps: u32,
+    /// Most rake taken from one hand.
+    #[serde(with = "int")]
+    pub rake_cap: i128,
+    /// Token units in one chip; bets and raises are whole chips.
+    #[serde(with = "int")]
+    pub chip_unit: i128,
+    /// Bad-beat jackpot share of each pot, in basis points.
+    pub jackpot_bps: u32,
+}
+
+impl TableConfig {
+    /// Ledgers whoever `phase` waits on has before a timeout can be claimed,
+    /// as `timeout::timeout_ledgers` in the contract; `None` when nothing in
+    /// `phase` can time out.
+    pub fn timeout_ledgers(&self, phase: GamePhase) -> Option<u32> {
+        match phase {
+            GamePhase::Dealing => Some(self.deal_timeout_ledgers),
+            GamePhase::DealingFlop
+            | GamePhase::DealingTurn
+            | GamePhase::DealingRiver
+            | GamePhase::RunOut => Some(self.reveal_timeout_ledgers),
+            GamePhase::Showdown => Some(self.showdown_timeout_ledgers),
+            GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
+                Some(self.betting_timeout_ledgers)
+            }
+            GamePhase::Waiting | GamePhase::Settlement | GamePhase::Dispute => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub address: String,
+    #[serde(with = "int")]
+    pub stack: i128,
+    #[serde(with = "int")]
+    pub bet_this_round: i128,
+    pub folded: bool,
+    pub all_in: bool,
+    pub sitting_out: bool,
+    pub seat_index: u32,
+    #[serde(with = "int")]
+    pub hand_start_stack: i128,
+    /// Acted since the last full bet or raise this round; such a player may
+    /// not raise again.
+    pub acted: bool,
+    /// Consecutive turns lost to a timeout; two sit the player out.
+    pub missed_turns: u32,
+    /// Hand number when the player last acted, joined or sat in or out.
+    /// Three hands on, anyone may `kick_idle` them.
+    pub last_active_hand: u32,
+    pub muck_preference: MuckPreference,
+    /// Time bank left this hand, drawn once the betting timeout passes.
+    pub time_bank_ledgers: u32,
+}
+
+/// Whether a player's hole cards go on chain at a showdown they lose.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MuckPreference {
+    Show,
+    /// Shown only when they win a pot someone else contested.
+    Muck,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamePhase {
+    Waiting,
+    Dealing,
+    Preflop,
+    DealingFlop,
+    Flop,
+    DealingTurn,
+    Turn,
+    DealingRiver,
+    River,
+    RunOut,
+    Showdown,
+    Settlement,
+    Dispute,
+}
+
+impl GamePhase {
+    pub const ALL: [GamePhase; 13] = [
+        GamePhase::Waiting,
+        GamePhase::Dealing,
+        GamePhase::Preflop,
+        GamePhase::DealingFlop,
+        GamePhase::Flop,
+        GamePhase::DealingTurn,
+        GamePhase::Turn,
+        GamePhase::DealingRiver,
+        GamePhase::River,
+        GamePhase::RunOut,
+        GamePhase::Showdown,
+        GamePhase::Settlement,
+        GamePhase::Dispute,
+    ];
+
+    /// The variant name, as the contract and CLI spell it.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GamePhase::Waiting => "Waiting",
+            GamePhase::Dealing => "Dealing",
+            GamePhase::Preflop => "Preflop",
+            GamePhase::DealingFlop => "DealingFlop",
+            GamePhase::Flop => "Flop",
+            GamePhase::DealingTurn => "DealingTurn",
+            GamePhase::Turn => "Turn",
+            GamePhase::DealingRiver => "DealingRiver",
+            GamePhase::River => "River",
+            GamePhase::RunOut => "RunOut",
+            GamePhase::Showdown => "Showdown",
+            GamePhase::Settlement => "Settlement",
+            GamePhase::Dispute => "Dispute",
+        }
+    }
+
+    pub fn is_betting(self) -> bool {
+        matches!(
+            self,
+            GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River
+        )
+    }
+}
+
+impl fmt::Display for GamePhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(#[serde(with = "int")] i128),
+    Raise(#[serde(with = "int")] i128),
+    AllIn,
+}
+
+impl Action {
+    pub fn amount(self) -> Option<i128> {
+        match self {
+            Action::Bet(amount) | Action::Raise(amount) => Some(amount),
+            _ => None,
+        }
+    }
+
+    /// The `--action` argument for `stellar contract invoke -- player_action`.
+    pub fn to_cli_arg(self) -> String {
+        serde_json::to_string(&self).expect("actions always serialize")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SidePot {
+    #[serde(with = "int")]
+    pub amount: i128,
+    pub eligible_players: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableState {
+    pub id: u32,
+    pub admin: String,
+    pub config: TableConfig,
+    pub phase: GamePhase,
+    /// One entry per seat, `None` while the seat is empty.
+    pub players: Vec<Option<PlayerState>>,
+    pub dealer_seat: u32,
+    /// This hand's small blind seat, which may be dead; see `positions`.
+    pub small_blind_seat: u32,
+    pub big_blind_seat: u32,
+    pub current_turn: u32,
+    #[serde(with = "int")]
+    pub pot: i128,
+    /// Smallest raise allowed: the size of the last full bet or raise this
+    /// round.
+    #[serde(with = "int")]
+    pub min_raise: i128,
+    pub side_pots: Vec<SidePot>,
+    pub deck_root: String,
+    pub hand_commitments: Vec<String>,
+    pub board_cards: Vec<u32>,
+    pub dealt_indices: Vec<u32>,
+    pub hand_number: u32,
+    pub last_action_ledger: u32,
+    pub committee: Vec<String>,
+    pub session_id: u32,
+    /// SHA-256 of each proof accepted this hand, in order.
+    pub proof_digests: Vec<String>,
+    /// The admin has stopped play; see `pause_table`.
+    pub paused: bool,
+    /// Seat that straddled this hand, if any.
+    pub straddle_seat: Option<u32>,
+    /// Verifier scheduled to take over; see `schedule_verifier`.
+    pub pending_verifier: Option<PendingVerifier>,
+    /// Seats that voted to cancel this hand; see `vote_cancel_hand`.
+    pub cancel_votes: Vec<u32>,
+}
+
+/// A table's lobby listing, as `get_table_summary` and `get_table_page` return
+/// it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableSummary {
+    pub table_id: u32,
+    pub phase: GamePhase,
+    pub player_count: u32,
+    pub max_players: u32,
+    #[serde(with = "int")]
+    pub small_blind: i128,
+    #[serde(with = "int")]
+    pub big_blind: i128,
+    #[serde(with = "int")]
+    pub pot: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingVerifier {
+    pub verifier: String,
+    /// First ledger a hand can start with it.
+    pub effective_ledger: u32,
+}
+
+/// What settled a hand's pot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementSource {
+    /// Everyone else folded, or was folded by a betting timeout.
+    Fold,
+    /// The committee's showdown proof.
+    Showdown,
+    /// The committee missed the showdown and the pot was refunded.
+    Timeout,
+}
+
+/// What one player was paid out of one pot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PotPayout {
+    /// 0 for the main pot, then each side pot.
+    pub pot_index: u32,
+    pub seat: u32,
+    pub player: String,
+    #[serde(with = "int")]
+    pub amount: i128,
+}
+
+/// How a table's last settled hand was paid out, as `get_last_settlement`
+/// returns it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settlement {
+    pub hand_number: u32,
+    pub source: SettlementSource,
+    /// The pot before rake.
+    #[serde(with = "int")]
+    pub pot: i128,
+    #[serde(with = "int")]
+    pub rake: i128,
+    /// Paid into the bad-beat jackpot.
+    #[serde(with = "int")]
+    pub jackpot: i128,
+    pub payouts: Vec<PotPayout>,
+}
+
+impl Settlement {
+    /// Parse `get_last_settlement` output.
+    pub fn from_json(raw: &str) -> Result<Self, String> {
+        serde_json::from_str(raw).map_err(|e| format!("invalid settlement: {}", e))
+    }
+
+    /// What each player was paid across every pot, in the order they were
+    /// first paid.
+    pub fn winnings(&self) -> Vec<(&str, i128)> {
+        let mut winnings: Vec<(&str, i128)> = Vec::new();
+        for payout in &self.payouts {
+            match winnings.iter_mut().find(|(p, _)| *p == payout.player) {
+                Some((_, amount)) => *amount += payout.amount,
+                None => winnings.push((&payout.player, payout.amount)),
+            }
+        }
+        winnings
+    }
+}
+
+impl TableState {
+    /// Parse `get_table` output.
+    pub fn from_json(raw: &str) -> Result<Self, String> {
+        serde_json::from_str(raw).map_err(|e| format!("invalid table state: {}", e))
+    }
+
+    /// The player in `seat`, if it is taken.
+    pub fn player(&self, seat: u32) -> Option<&PlayerState> {
+        self.players.get(seat as usize)?.as_ref()
+    }
+
+    /// The players at the table, in seat order.
+    pub fn seated(&self) -> impl Iterator<Item = &PlayerState> {
+        self.players.iter().flatten()
+    }
+
+    /// The player whose turn it is, during a betting round.
+    pub fn player_to_act(&self) -> Option<&PlayerState> {
+        self.player(self.current_turn)
+    }
+
+    /// The street the next board reveal deals: `"flop"`, `"turn"` or
+    /// `"river"`, by how many board cards are out.
+    pub fn next_street(&self) -> &'static str {
+        match self.board_cards.len() {
+            0..=2 => "flop",
+            3 => "turn",
+            _ => "river",
+        }
+    }
+
+    /// Highest bet in the current betting round.
+    pub fn max_bet(&self) -> i128 {
+        self.seated().map(|p| p.bet_this_round).max().unwrap_or(0)
+    }
+
+    /// Button, blinds and turn for the hand in progress or, between hands,
+    /// for the next `start_hand`. `None` with fewer than two players dealt
+    /// in.
+    pub fn positions(&self) -> Option<Positions> {
+        let between_hands = matches!(self.phase, GamePhase::Waiting | GamePhase::Settlement);
+        // Between hands, whoever is not sitting out gets dealt in. During a
+        // hand, those dealt out were folded before putting anything in; a
+        // live small blind always has.
+        let dealt_in: Vec<bool> = self
+            .players
+            .iter()
+            .map(|seat| match seat {
+                None => false,
+                Some(p) if between_hands => !p.sitting_out,
+                Some(p) => !p.folded || p.stack != p.hand_start_stack,
+            })
+            .collect();
+        let seated_in = dealt_in.iter().filter(|&&d| d).count();
+        if seated_in < 2 {
+            return None;
+        }
+        let num_seats = self.players.len() as u32;
+        let next = |seat: u32| {
+            (1..=num_seats)
+                .map(|step| (seat + step) % num_seats)
+                .find(|&s| dealt_in[s as usize])
+                .unwrap_or(seat)
+        };
+
+        // Mirrors `game::next_positions`: `start_hand` moves the button
+        // before posting blinds, so between hands the seats are still the
+        // last hand's.
+        let (hand_number, dealer, small_blind, big_blind) = if !between_hands {
+            (
+                self.hand_number,
+                self.dealer_seat % num_seats,
+                self.small_blind_seat % num_seats,
+                self.big_blind_seat % num_seats,
+            )
+        } else if self.hand_number == 0 {
+            let dealer = next(self.dealer_seat % num_seats);
+            let small_blind = if seated_in == 2 { dealer } else { next(dealer) };
+            (1, dealer, small_blind, next(small_blind))
+        } else {
+            let big_blind = next(self.big_blind_seat % num_seats);
+            if seated_in == 2 {
+                let button = next(big_blind);
+                (self.hand_number + 1, button, button, big_blind)
+            } else {
+                let small_blind = self.big_blind_seat % num_seats;
+                let mut dealer = self.small_blind_seat % num_seats;
+                if dealer == big_blind {
+                    dealer = (small_blind + num_seats - 1) % num_seats;
+                }
+                (self.hand_number + 1, dealer, small_blind, big_blind)
+            }
+        };
+        let live_small_blind = (small_blind != dealer || seated_in == 2)
+            && small_blind != big_blind
+            && dealt_in[small_blind as usize];
+        Some(Positions {
+            hand_number,
+            dealer,
+            small_blind: live_small_blind.then_some(small_blind),
+            big_blind,
+            next_to_act: self.phase.is_betting().then_some(self.current_turn),
+        })
+    }
+}
+
+/// Seats, as indices into `TableState::players`, the way the contract assigns
+/// them: the big blind moves one player on each hand, the small blind takes
+/// last hand's big blind seat and the button last hand's small blind seat.
+/// Heads-up the button posts the small blind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Positions {
+    pub hand_number: u32,
+    /// May be on a seat whose player left or sits out.
+    pub dealer: u32,
+    /// `None` when the small blind is dead.
+    pub small_blind: Option<u32>,
+    pub big_blind: u32,
+    /// Whose turn it is; only set during a betting round.
+    pub next_to_act: Option<u32>,
+}
+
+/// Errors the contract returns, by their `Error(Contract, #code)` code.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PokerTableError {
+    TableNotFound = 1,
+    TableNotAcceptingPlayers = 2,
+    TableFull = 3,
+    InvalidBuyIn = 4,
+    AlreadySeated = 5,
+    PlayerNotAtTable = 6,
+    CannotLeaveDuringActiveHand = 7,
+    HandAlreadyInProgress = 8,
+    NeedAtLeastTwoPlayers = 9,
+    InvalidPlayerIndex = 10,
+    NotYourTurn = 11,
+    PlayerAlreadyFolded = 12,
+    PlayerAlreadyAllIn = 13,
+    MustCallOrFold = 14,
+    NothingToCall = 15,
+    CannotBetWhenOutstandingBet = 16,
+    BetTooSmall = 17,
+    RaiseTooSmall = 18,
+    NotEnoughChips = 19,
+    NotInBettingPhase = 20,
+    NotInDealingPhase = 21,
+    NotInRevealPhase = 22,
+    NotInShowdownPhase = 23,
+    WrongCommitmentCount = 24,
+    WrongCardCount = 25,
+    NotAuthorizedCommittee = 26,
+    DealProofVerificationFailed = 27,
+    RevealProofVerificationFailed = 28,
+    ShowdownProofVerificationFailed = 29,
+    BoardNotComplete = 30,
+    InvalidHoleCards = 31,
+    TimeoutNotReached = 32,
+    TimeoutNotApplicable = 33,
+    BettingNotReopened = 34,
+    InvalidRake = 35,
+    PublicInputMismatch = 36,
+    CardAlreadyDealt = 37,
+    ProofReplay = 38,
+    HandNotFound = 39,
+    TablePaused = 40,
+    PlayerNotIdle = 41,
+    StraddleNotAllowed = 42,
+    InvalidCommittee = 43,
+    NoCommitteeEpoch = 44,
+    InvalidBlinds = 45,
+    InvalidBuyInRange = 46,
+    InvalidMaxPlayers = 47,
+    InsufficientBalance = 48,
+    InsufficientAllowance = 49,
+    NoHandInProgress = 50,
+    AlreadyVotedToCancel = 51,
+    InvalidChipUnit = 52,
+    NotWholeChips = 53,
+    NothingToClaim = 54,
+    InvalidSessionDuration = 55,
+    NoSessionKey = 56,
+}
+
+impl PokerTableError {
+    pub const ALL: [PokerTableError; 56] = [
+        PokerTableError::TableNotFound,
+        PokerTableError::TableNotAcceptingPlayers,
+        PokerTableError::TableFull,
+        PokerTableError::InvalidBuyIn,
+        PokerTableError::AlreadySeated,
+        PokerTableError::PlayerNotAtTable,
+        PokerTableError::CannotLeaveDuringActiveHand,
+        PokerTableError::HandAlreadyInProgress,
+        PokerTableError::NeedAtLeastTwoPlayers,
+        PokerTableError::InvalidPlayerIndex,
+        PokerTableError::NotYourTurn,
+        PokerTableError::PlayerAlreadyFolded,
+        PokerTableError::PlayerAlreadyAllIn,
+        PokerTableError::MustCallOrFold,
+        PokerTableError::NothingToCall,
+        PokerTableError::CannotBetWhenOutstandingBet,
+        PokerTableError::BetTooSmall,
+        PokerTableError::RaiseTooSmall,
+        PokerTableError::NotEnoughChips,
+        PokerTableError::NotInBettingPhase,
+        PokerTableError::NotInDealingPhase,
+        PokerTableError::NotInRevealPhase,
+        PokerTableError::NotInShowdownPhase,
+        PokerTableError::WrongCommitmentCount,
+        PokerTableError::WrongCardCount,
+        PokerTableError::NotAuthorizedCommittee,
+        PokerTableError::DealProofVerificationFailed,
+        PokerTableError::RevealProofVerificationFailed,
+        PokerTableError::ShowdownProofVerificationFailed,
+        PokerTableError::BoardNotComplete,
+        PokerTableError::InvalidHoleCards,
+        PokerTableError::TimeoutNotReached,
+        PokerTableError::TimeoutNotApplicable,
+        PokerTableError::BettingNotReopened,
+        PokerTableError::InvalidRake,
+        PokerTableError::PublicInputMismatch,
+        PokerTableError::CardAlreadyDealt,
+        PokerTableError::ProofReplay,
+        PokerTableError::HandNotFound,
+        PokerTableError::TablePaused,
+        PokerTableError::PlayerNotIdle,
+        PokerTableError::StraddleNotAllowed,
+        PokerTableError::InvalidCommittee,
+        PokerTableError::NoCommitteeEpoch,
+        PokerTableError::InvalidBlinds,
+        PokerTableError::InvalidBuyInRange,
+        PokerTableError::InvalidMaxPlayers,
+        PokerTableError::InsufficientBalance,
+        PokerTableError::InsufficientAllowance,
+        PokerTableError::NoHandInProgress,
+        PokerTableError::AlreadyVotedToCancel,
+        PokerTableError::InvalidChipUnit,
+        PokerTableError::NotWholeChips,
+        PokerTableError::NothingToClaim,
+        PokerTableError::InvalidSessionDuration,
+        PokerTableError::NoSessionKey,
+    ];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.code() == code)
+    }
+
+    /// The variant's name, as frontends match on it.
+    pub fn name(self) -> &'static str {
+        self.describe().0
+    }
+
+    /// What went wrong, worded for the player who triggered it.
+    pub fn message(self) -> &'static str {
+        self.describe().1
+    }
+
+    fn describe(self) -> (&'static str, &'static str) {
+        match self {
+            PokerTableError::TableNotFound => ("TableNotFound", "Table not found"),
+            PokerTableError::TableNotAcceptingPlayers => (
+                "TableNotAcceptingPlayers",
+                "This table is not accepting players right now",
+            ),
+            PokerTableError::TableFull => ("TableFull", "This table is full"),
+            PokerTableError::InvalidBuyIn => {
+                ("InvalidBuyIn", "Buy-in is outside the table's limits")
+            }
+            PokerTableError::AlreadySeated => {
+                ("AlreadySeated", "You are already seated at this table")
+            }
+            PokerTableError::PlayerNotAtTable => {
+                ("PlayerNotAtTable", "You are not seated at this table")
+            }
+            PokerTableError::CannotLeaveDuringActiveHand => (
+                "CannotLeaveDuringActiveHand",
+                "You cannot leave during a hand",
+            ),
+            PokerTableError::HandAlreadyInProgress => {
+                ("HandAlreadyInProgress", "A hand is already in progress")
+            }
+            PokerTableError::NeedAtLeastTwoPlayers => (
+                "NeedAtLeastTwoPlayers",
+                "At least two players are needed to start a hand",
+            ),
+            PokerTableError::InvalidPlayerIndex => ("InvalidPlayerIndex", "Invalid player"),
+            PokerTableError::NotYourTurn => ("NotYourTurn", "It is not your turn"),
+            PokerTableError::PlayerAlreadyFolded => {
+                ("PlayerAlreadyFolded", "You have already folded")
+            }
+            PokerTableError::PlayerAlreadyAllIn => ("PlayerAlreadyAllIn", "You are already all-in"),
+            PokerTableError::MustCallOrFold => (
+                "MustCallOrFold",
+                "There is a bet to you: call, raise or fold",
+            ),
+            PokerTableError::NothingToCall => ("NothingToCall", "There is nothing to call"),
+            PokerTableError::CannotBetWhenOutstandingBet => (
+                "CannotBetWhenOutstandingBet",
+                "There is already a bet: call or raise instead",
+            ),
+            PokerTableError::BetTooSmall => ("BetTooSmall", "Bet is below the minimum"),
+            PokerTableError::RaiseTooSmall => ("RaiseTooSmall", "Raise is below the minimum"),
+            PokerTableError::NotEnoughChips => ("NotEnoughChips", "You do not have enough chips"),
+            PokerTableError::NotInBettingPhase => {
+                ("NotInBettingPhase", "Betting is closed right now")
+            }
+            PokerTableError::NotInDealingPhase => {
+                ("NotInDealingPhase", "The table is not waiting for a deal")
+            }
+            PokerTableError::NotInRevealPhase => {
+                ("NotInRevealPhase", "The table is not waiting for a reveal")
+            }
+            PokerTableError::NotInShowdownPhase => (
+                "NotInShowdownPhase",
+                "The table is not waiting for a showdown",
+            ),
+            PokerTableError::WrongCommitmentCount => {
+                ("WrongCommitmentCount", "Wrong number of hand commitments")
+            }
+            PokerTableError::WrongCardCount => ("WrongCardCount", "Wrong number of cards"),
+            PokerTableError::NotAuthorizedCommittee => (
+                "NotAuthorizedCommittee",
+                "Only the table's committee can do this",
+            ),
+            PokerTableError::DealProofVerificationFailed => (
+                "DealProofVerificationFailed",
+                "The deal proof did not verify",
+            ),
+            PokerTableError::RevealProofVerificationFailed => (
+                "RevealProofVerificationFailed",
+                "The reveal proof did not verify",
+            ),
+            PokerTableError::ShowdownProofVerificationFailed => (
+                "ShowdownProofVerificationFailed",
+                "The showdown proof did not verify",
+            ),
+            PokerTableError::BoardNotComplete => {
+                ("BoardNotComplete", "The board is not complete yet")
+            }
+            PokerTableError::InvalidHoleCards => ("InvalidHoleCards", "Invalid hole cards"),
+            PokerTableError::TimeoutNotReached => {
+                ("TimeoutNotReached", "The timeout has not been reached yet")
+            }
+            PokerTableError::TimeoutNotApplicable => {
+                ("TimeoutNotApplicable", "There is nothing to time out")
+            }
+            PokerTableError::BettingNotReopened => (
+                "BettingNotReopened",
+                "Nobody has raised since you acted, so you can only call or fold",
+            ),
+            PokerTableError::InvalidRake => ("InvalidRake", "Invalid rake configuration"),
+            PokerTableError::PublicInputMismatch => (
+                "PublicInputMismatch",
+                "The committee's values do not match its proof",
+            ),
+            PokerTableError::CardAlreadyDealt => {
+                ("CardAlreadyDealt", "That card has already been dealt")
+            }
+            PokerTableError::ProofReplay => ("ProofReplay", "That proof is for a different hand"),
+            PokerTableError::HandNotFound => ("HandNotFound", "No record of that hand"),
+            PokerTableError::TablePaused => ("TablePaused", "The table is paused"),
+            PokerTableError::PlayerNotIdle => (
+                "PlayerNotIdle",
+                "That player has acted too recently to kick",
+            ),
+            PokerTableError::StraddleNotAllowed => (
+                "StraddleNotAllowed",
+                "Only the player left of the big blind may straddle, once",
+            ),
+            PokerTableError::InvalidCommittee => (
+                "InvalidCommittee",
+                "The committee threshold must be between 1 and the number of members",
+            ),
+            PokerTableError::NoCommitteeEpoch => (
+                "NoCommitteeEpoch",
+                "The committee registry has no current epoch",
+            ),
+            PokerTableError::InvalidBlinds => (
+                "InvalidBlinds",
+                "The small blind must be positive and at most the big blind",
+            ),
+            PokerTableError::InvalidBuyInRange => (
+                "InvalidBuyInRange",
+                "The minimum buy-in must be positive and at most the maximum",
+            ),
+            PokerTableError::InvalidMaxPlayers => {
+                ("InvalidMaxPlayers", "A table seats between 2 and 9 players")
+            }
+            PokerTableError::InsufficientBalance => (
+                "InsufficientBalance",
+                "Your balance does not cover the buy-in",
+            ),
+            PokerTableError::InsufficientAllowance => (
+                "InsufficientAllowance",
+                "Approve the table contract for the whole buy-in first",
+            ),
+            PokerTableError::NoHandInProgress => {
+                ("NoHandInProgress", "There is no hand in progress to cancel")
+            }
+            PokerTableError::AlreadyVotedToCancel => (
+                "AlreadyVotedToCancel",
+                "You have already voted to cancel this hand",
+            ),
+            PokerTableError::InvalidChipUnit => (
+                "InvalidChipUnit",
+                "The chip unit must be positive and divide both blinds",
+            ),
+            PokerTableError::NotWholeChips => (
+                "NotWholeChips",
+                "Bets and raises must be whole chips of the table's unit",
+            ),
+            PokerTableError::NothingToClaim => {
+                ("NothingToClaim", "No jackpot award is waiting for you")
+            }
+            PokerTableError::InvalidSessionDuration => (
+                "InvalidSessionDuration",
+                "A session key can last from one ledger up to about a day",
+            ),
+            PokerTableError::NoSessionKey => (
+                "NoSessionKey",
+                "That session key is not registered for this player or has expired",
+            ),
+        }
+    }
+
+    /// Find the contract error in a failed invocation's stderr.
+    ///
+    /// The code is only meaningful if the table contract raised it: a failed
+    /// call into another contract (e.g. a token transfer) reports that
+    /// contract's code in the same form.
+    pub fn from_invoke_error(stderr: &str) -> Option<Self> {
+        let (_, rest) = stderr.split_once("Error(Contract, #")?;
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        Self::from_code(digits.parse().ok()?)
+    }
+}
+
+impl fmt::Display for PokerTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} (#{})", self, self.code())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TABLE: &str = r#"{
+        "id": 3,
+        "admin": "GADMIN",
+        "config": {
+            "token": "CTOKEN", "min_buy_in": "100", "max_buy_in": "1000",
+            "small_blind": "5", "big_blind": "10", "max_players": 6,
+            "deal_timeout_ledgers": 60, "reveal_timeout_ledgers": 30,
+            "showdown_timeout_ledgers": 30, "betting_timeout_ledgers": 6,
+            "time_bank_ledgers": 12, "committee": ["GCOMMITTEE", "GBACKUP"],
+            "committee_threshold": 2,
+            "verifier": "CVERIFIER", "game_hub": "CHUB",
+            "committee_registry": "CREGISTRY", "rake_bps": 500,
+            "rake_cap": "30", "chip_unit": "5", "jackpot_bps": 100
+        },
+        "phase": "Flop",
+        "players": [
+            {"address": "GA", "stack": "90", "bet_this_round": "0", "folded": false,
+             "all_in": false, "sitting_out": false, "seat_index": 0, "hand_start_stack": "100",
+             "acted": true, "missed_turns": 0, "last_active_hand": 7,
+             "muck_preference": "Show", "time_bank_ledgers": 12},
+            {"address": "GB", "stack": 90, "bet_this_round": "10", "folded": false,
+             "all_in": false, "sitting_out": false, "seat_index": 1, "hand_start_stack": "100",
+             "acted": true, "missed_turns": 0, "last_active_hand": 7,
+             "muck_preference": "Muck", "time_bank_ledgers": 5}
+        ],
+        "dealer_seat": 0,
+        "small_blind_seat": 0,
+        "big_blind_seat": 1,
+        "current_turn": 1,
+        "pot": "20",
+        "min_raise": "10",
+        "side_pots": [],
+        "deck_root": "0101010101010101010101010101010101010101010101010101010101010101",
+        "hand_commitments": ["02", "03"],
+        "board_cards": [4, 5, 6],
+        "dealt_indices": [0, 1, 2, 3, 4, 5, 6],
+        "hand_number": 7,
+        "last_action_ledger": 1234,
+        "committee": ["GCOMMITTEE", "GBACKUP"],
+        "session_id": 9,
+        "proof_digests": [],
+        "paused": false,
+        "straddle_seat": null,
+        "pending_verifier": {"verifier": "CVERIFIER2", "effective_ledger": 18514},
+        "cancel_votes": [1]
+    }"#;
+
+    #[test]
+    fn test_parses_get_table_output() {
+        let table = TableState::from_json(TABLE).unwrap();
+        assert_eq!(table.phase, GamePhase::Flop);
+        assert!(table.phase.is_betting());
+        assert_eq!(table.config.max_players, 6);
+        assert_eq!(table.config.committee.len(), 2);
+        assert_eq!(table.config.committee_threshold, 2);
+        assert_eq!(table.player(1).unwrap().stack, 90);
+        assert_eq!(
+            table.player(1).unwrap().muck_preference,
+            MuckPreference::Muck
+        );
+        assert_eq!(table.player_to_act().unwrap().address, "GB");
+        assert_eq!(table.max_bet(), 10);
+        assert_eq!(table.pot, 20);
+        assert_eq!(table.next_street(), "turn");
+    }
+
+    #[test]
+    fn test_config_round_trips_in_cli_format() {
+        let table = TableState::from_json(TABLE).unwrap();
+        let json = serde_json::to_value(&table.config).unwrap();
+        assert_eq!(json["min_buy_in"], "100");
+        assert_eq!(json["max_players"], 6);
+        assert_eq!(json["rake_cap"], "30");
+        assert_eq!(json["chip_unit"], "5");
+        let back: TableConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(back, table.config);
+    }
+
+    #[test]
+    fn test_parses_get_table_page_output() {
+        let page: Vec<TableSummary> = serde_json::from_str(
+            r#"[{"table_id": 3, "phase": "Waiting", "player_count": 2, "max_players": 6,
+                 "small_blind": "5", "big_blind": "10", "pot": "0"}]"#,
+        )
+        .unwrap();
+        assert_eq!(page[0].table_id, 3);
+        assert_eq!(page[0].phase, GamePhase::Waiting);
+        assert_eq!(page[0].big_blind, 10);
+    }
+
+    #[test]
+    fn test_parses_get_last_settlement_output() {
+        let settlement = Settlement::from_json(
+            r#"{"hand_number": 4, "source": "Showdown", "pot": "600", "rake": "0",
+                "jackpot": "0", "payouts": [
+                    {"pot_index": 0, "seat": 1, "player": "GA", "amount": "300"},
+                    {"pot_index": 1, "seat": 2, "player": "GB", "amount": "200"},
+                    {"pot_index": 2, "seat": 1, "player": "GA", "amount": "100"}
+                ]}"#,
+        )
+        .unwrap();
+        assert_eq!(settlement.source, SettlementSource::Showdown);
+        assert_eq!(settlement.winnings(), vec![("GA", 400), ("GB", 200)]);
+    }
+
+    #[test]
+    fn test_timeouts_follow_phase() {
+        let config = TableState::from_json(TABLE).unwrap().config;
+        assert_eq!(config.timeout_ledgers(GamePhase::Dealing), Some(60));
+        assert_eq!(config.timeout_ledgers(GamePhase::DealingTurn), Some(30));
+        assert_eq!(config.timeout_ledgers(GamePhase::RunOut), Some(30));
+        assert_eq!(config.timeout_ledgers(GamePhase::Showdown), Some(30));
+        assert_eq!(config.timeout_ledgers(GamePhase::River), Some(6));
+        assert_eq!(config.timeout_ledgers(GamePhase::Settlement), None);
+    }
+
+    #[test]
+    fn test_positions_follow_contract() {
+        let mut table = TableState::from_json(TABLE).unwrap();
+        // Heads-up, mid-hand: the button also posts the small blind.
+        let positions = table.positions().unwrap();
+        assert_eq!(positions.hand_number, 7);
+        assert_eq!((positions.dealer, positions.small_blind), (0, Some(0)));
+        assert_eq!(positions.big_blind, 1);
+        assert_eq!(positions.next_to_act, Some(1));
+
+        // Between hands the blinds have yet to move for the next one. Out of
+        // heads-up, last hand's big blind posts the small blind and the
+        // button stays put.
+        let mut third = table.player(0).unwrap().clone();
+        third.address = "GC".to_string();
+        third.seat_index = 2;
+        table.players.push(Some(third.clone()));
+        table.phase = GamePhase::Settlement;
+        let positions = table.positions().unwrap();
+        assert_eq!(positions.hand_number, 8);
+        assert_eq!(
+            (positions.dealer, positions.small_blind, positions.big_blind),
+            (0, Some(1), 2)
+        );
+        assert_eq!(positions.next_to_act, None);
+
+        // Players sitting out are skipped, back to heads-up.
+        let sit_out = |table: &mut TableState, seat: usize, out: bool| {
+            table.players[seat].as_mut().unwrap().sitting_out = out;
+        };
+        sit_out(&mut table, 0, true);
+        let positions = table.positions().unwrap();
+        assert_eq!(
+            (positions.dealer, positions.small_blind, positions.big_blind),
+            (1, Some(1), 2)
+        );
+        sit_out(&mut table, 2, true);
+        assert_eq!(table.positions(), None);
+        sit_out(&mut table, 0, false);
+        sit_out(&mut table, 2, false);
+
+        // Last hand's big blind sat out: its small blind is dead and the big
+        // blind still moves on one.
+        third.seat_index = 3;
+        table.players.push(Some(third));
+        (
+            table.dealer_seat,
+            table.small_blind_seat,
+            table.big_blind_seat,
+        ) = (0, 1, 2);
+        sit_out(&mut table, 2, true);
+        let positions = table.positions().unwrap();
+        assert_eq!(
+            (positions.dealer, positions.small_blind, positions.big_blind),
+            (1, None, 3)
+        );
+
+        // Likewise if they left: the seat stays, empty.
+        table.players[2] = None;
+        assert_eq!(table.positions().unwrap().big_blind, 3);
+        assert_eq!(table.seated().count(), 3);
+
+        table.players.truncate(1);
+        assert_eq!(table.positions(), None);
+    }
+
+    #[test]
+    fn test_action_cli_args() {
+        assert_eq!(Action::Check.to_cli_arg(), r#""Check""#);
+        assert_eq!(Action::Raise(250).to_cli_arg(), r#"{"Raise":"250"}"#);
+        assert_eq!(Action::AllIn.amount(), None);
+    }
+
+    #[test]
+    fn test_error_from_invoke_stderr() {
+        let stderr = "error: transaction simulation failed: HostError: Error(Contract, #11)";
+        assert_eq!(
+            PokerTableError::from_invoke_error(stderr),
+            Some(PokerTableError::NotYourTurn)
+        );
+        assert_eq!(
+            PokerTableError::from_invoke_error("Error(Contract, #99)"),
+            None
+        );
+        assert_eq!(PokerTableError::from_invoke_error("timed out"), None);
+    }
+
+    #[test]
+    fn test_error_names_match_variants() {
+        for error in PokerTableError::ALL {
+            assert_eq!(error.name(), format!("{:?}", error));
+            assert!(!error.message().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_phase_names_match_serde() {
+        for phase in GamePhase::ALL {
+            assert_eq!(
+                serde_json::to_value(phase).unwrap(),
+                serde_json::Value::from(phase.as_str())
+            );
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Proof and public-input conversion on the coordinator's submission path.
+//!
+//! Uses the synthetic keccak proofs from `tests/fixtures`; conversion cost
+//! depends only on the layout, not on the values.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use proof_format::{
+    convert_keccak_proof_to_soroban, normalize_field_value, public_inputs_to_bytes,
+};
+
+fn fixture(contents: &str) -> Vec<u8> {
+    hex::decode(contents.trim()).expect("fixture is valid hex")
+}
+
+fn proof_conversion(c: &mut Criterion) {
+    let proofs = [
+        (
+            12,
+            fixture(include_str!("../tests/fixtures/keccak_proof_log_n_12.hex")),
+        ),
+        (
+            16,
+            fixture(include_str!("../tests/fixtures/keccak_proof_log_n_16.hex")),
+        ),
+    ];
+    let mut group = c.benchmark_group("convert_keccak_proof_to_soroban");
+    for (log_n, proof) in &proofs {
+        group.bench_with_input(BenchmarkId::new("log_n", log_n), proof, |b, proof| {
+            b.iter(|| convert_keccak_proof_to_soroban(black_box(proof)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn public_inputs(c: &mut Criterion) {
+    // A deal's worth: deck root, six hand commitments and two card indices
+    // per seat, as full-width decimals the way `normalize_field_value` leaves
+    // them.
+    let inputs: Vec<String> = (0..19u64)
+        .map(|i| {
+            let word = format!("{:016x}", i.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+            normalize_field_value(&format!("0x{}", word.repeat(4))).unwrap()
+        })
+        .collect();
+    c.bench_function("public_inputs_to_bytes", |b| {
+        b.iter(|| public_inputs_to_bytes(black_box(&inputs)).unwrap())
+    });
+}
+
+criterion_group!(benches, proof_conversion, public_inputs);
+criterion_main!(benches);
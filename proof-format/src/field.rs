@@ -0,0 +1,172 @@
+use std::str::FromStr;
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+
+/// Convert a BN254 field element (decimal string) to its 32-byte big-endian encoding.
+pub fn field_to_bytes32(field_str: &str) -> Result<[u8; 32], String> {
+    let fr = Fr::from_str(field_str)
+        .map_err(|_| format!("failed to parse field element: '{}'", field_str))?;
+    let bytes = fr.into_bigint().to_bytes_be();
+    // Pad to exactly 32 bytes (should already be, but be safe)
+    if bytes.len() > 32 {
+        return Err(format!("field element too large: {} bytes", bytes.len()));
+    }
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(padded)
+}
+
+/// Convert a BN254 field element (decimal string) to a 32-byte big-endian hex string.
+/// This is needed because Soroban `BytesN<32>` expects hex-encoded bytes, but
+/// MPC proof outputs are decimal field element strings.
+pub fn field_to_bytes32_hex(field_str: &str) -> Result<String, String> {
+    field_to_bytes32(field_str).map(hex::encode)
+}
+
+/// Convert a slice of field element strings to a JSON array of hex-encoded BytesN<32>.
+pub fn fields_to_bytes32_json(fields: &[String]) -> Result<String, String> {
+    let hex_strings: Vec<String> = fields
+        .iter()
+        .map(|f| field_to_bytes32_hex(f))
+        .collect::<Result<Vec<_>, _>>()?;
+    serde_json::to_string(&hex_strings).map_err(|e| format!("failed to serialize hex array: {}", e))
+}
+
+/// Concatenate proof public inputs (field element strings) as 32-byte big-endian words.
+pub fn public_inputs_to_bytes(public_inputs: &[String]) -> Result<Vec<u8>, String> {
+    let mut all_bytes = Vec::with_capacity(public_inputs.len() * 32);
+    for pi in public_inputs {
+        let bytes =
+            field_to_bytes32(pi).map_err(|_| format!("failed to parse public input: '{}'", pi))?;
+        all_bytes.extend_from_slice(&bytes);
+    }
+    Ok(all_bytes)
+}
+
+/// Convert proof public inputs (field element strings) to concatenated 32-byte big-endian
+/// representations suitable for the on-chain verifier.
+pub fn public_inputs_to_hex(public_inputs: &[String]) -> Result<String, String> {
+    public_inputs_to_bytes(public_inputs).map(hex::encode)
+}
+
+/// Normalize a field value from co-noir output (decimal or `0x` hex) to a
+/// canonical decimal string reduced mod the BN254 scalar field.
+pub fn normalize_field_value(raw: &str) -> Result<String, String> {
+    let s = raw.trim();
+    if s.is_empty() {
+        return Err("empty field string".to_string());
+    }
+
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(s.to_string());
+    }
+
+    let hex_str = s.strip_prefix("0x").unwrap_or(s);
+    if !hex_str.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("invalid field string '{}'", raw));
+    }
+    if hex_str.len() % 2 != 0 {
+        return Err(format!("hex field has odd length '{}'", raw));
+    }
+
+    let bytes = hex::decode(hex_str).map_err(|e| format!("invalid hex field '{}': {}", raw, e))?;
+    let fr = Fr::from_be_bytes_mod_order(&bytes);
+    Ok(fr.into_bigint().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // BN254 scalar field modulus r.
+    const MODULUS: &str =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+    #[test]
+    fn test_field_to_bytes32_small_values() {
+        assert_eq!(field_to_bytes32("0").unwrap(), [0u8; 32]);
+        let one = field_to_bytes32("1").unwrap();
+        assert_eq!(one[31], 1);
+        assert_eq!(&one[..31], &[0u8; 31]);
+        assert_eq!(
+            field_to_bytes32_hex("258").unwrap(),
+            format!("{}0102", "0".repeat(60))
+        );
+    }
+
+    #[test]
+    fn test_field_to_bytes32_max_element() {
+        let max = "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        assert_eq!(
+            field_to_bytes32_hex(max).unwrap(),
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000"
+        );
+    }
+
+    #[test]
+    fn test_field_to_bytes32_rejects_invalid() {
+        assert!(field_to_bytes32("").is_err());
+        assert!(field_to_bytes32("abc").is_err());
+        assert!(field_to_bytes32("-1").is_err());
+    }
+
+    #[test]
+    fn test_field_to_bytes32_reduces_mod_r() {
+        assert_eq!(field_to_bytes32(MODULUS).unwrap(), [0u8; 32]);
+    }
+
+    #[test]
+    fn test_fields_to_bytes32_json() {
+        let json = fields_to_bytes32_json(&["1".to_string(), "2".to_string()]).unwrap();
+        let parsed: Vec<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], format!("{}01", "0".repeat(62)));
+        assert_eq!(parsed[1], format!("{}02", "0".repeat(62)));
+        assert_eq!(fields_to_bytes32_json(&[]).unwrap(), "[]");
+        assert!(fields_to_bytes32_json(&["x".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_public_inputs_to_hex() {
+        let inputs = vec!["7".to_string(), "255".to_string(), "65536".to_string()];
+        let bytes = public_inputs_to_bytes(&inputs).unwrap();
+        assert_eq!(bytes.len(), 96);
+        assert_eq!(bytes[31], 7);
+        assert_eq!(bytes[63], 255);
+        assert_eq!(&bytes[93..96], &[1, 0, 0]);
+        assert_eq!(public_inputs_to_hex(&inputs).unwrap(), hex::encode(bytes));
+        assert_eq!(public_inputs_to_hex(&[]).unwrap(), "");
+
+        let err = public_inputs_to_hex(&["1".to_string(), "oops".to_string()]).unwrap_err();
+        assert!(err.contains("oops"));
+    }
+
+    #[test]
+    fn test_normalize_field_value() {
+        assert_eq!(normalize_field_value(" 42 ").unwrap(), "42");
+        assert_eq!(normalize_field_value("0x2a").unwrap(), "42");
+        assert_eq!(normalize_field_value("2a").unwrap(), "42");
+        assert_eq!(normalize_field_value("0x0000").unwrap(), "0");
+        assert!(normalize_field_value("").is_err());
+        assert!(normalize_field_value("0x2").is_err());
+        assert!(normalize_field_value("0xzz").is_err());
+    }
+
+    #[test]
+    fn test_normalize_field_value_reduces_mod_r() {
+        let r_hex = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+        assert_eq!(normalize_field_value(r_hex).unwrap(), "0");
+        let r_plus_5 = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000006";
+        assert_eq!(normalize_field_value(r_plus_5).unwrap(), "5");
+    }
+
+    #[test]
+    fn test_normalize_then_encode_roundtrip() {
+        let hex_in = "0x0badc0ffee";
+        let dec = normalize_field_value(hex_in).unwrap();
+        let out = field_to_bytes32_hex(&dec).unwrap();
+        assert!(out.ends_with("0badc0ffee"));
+        assert_eq!(out.len(), 64);
+    }
+}
@@ -0,0 +1,292 @@
+//! Proof and public-input encoding shared by the coordinator and tooling.
+//!
+//! co-noir emits UltraHonk proofs with a keccak transcript in a variable-size
+//! layout (raw G1 coordinates, only `log_n` sumcheck rounds). The on-chain
+//! Soroban verifier expects the fixed 458-field BB layout with limb-encoded G1
+//! points. This crate owns that conversion plus the field/hex helpers used to
+//! pass public inputs and commitments to `stellar contract invoke`.
+
+mod field;
+
+pub use field::{
+    field_to_bytes32, field_to_bytes32_hex, fields_to_bytes32_json, normalize_field_value,
+    public_inputs_to_bytes, public_inputs_to_hex,
+};
+
+pub const FIELD_SIZE: usize = 32;
+pub const SOROBAN_PROOF_FIELDS: usize = 458;
+pub const SOROBAN_PROOF_BYTES: usize = SOROBAN_PROOF_FIELDS * FIELD_SIZE;
+pub const CONST_PROOF_SIZE_LOG_N: usize = 28;
+pub const BATCHED_RELATION_PARTIAL_LENGTH: usize = 8;
+pub const NUMBER_OF_ENTITIES: usize = 41;
+pub const NUM_G1_WIRE_POINTS: usize = 8;
+pub const NUM_FINAL_G1: usize = 2;
+pub const PAIRING_POINTS_SIZE: usize = 16;
+pub const MIN_LOG_N: usize = 10;
+pub const MAX_LOG_N: usize = 25;
+
+/// Number of fields in a co-noir keccak proof for a circuit of size `2^log_n`.
+///
+/// total = 16 + 16 + log_n*8 + 41 + (log_n-1)*2 + log_n + 4 = 75 + 11*log_n
+pub fn keccak_proof_fields(log_n: usize) -> usize {
+    PAIRING_POINTS_SIZE
+        + NUM_G1_WIRE_POINTS * 2
+        + log_n * BATCHED_RELATION_PARTIAL_LENGTH
+        + NUMBER_OF_ENTITIES
+        + log_n.saturating_sub(1) * 2
+        + log_n
+        + NUM_FINAL_G1 * 2
+}
+
+/// Derive `log_n` from the size of a co-noir keccak proof.
+pub fn keccak_proof_log_n(proof_len: usize) -> Result<usize, String> {
+    if proof_len % FIELD_SIZE != 0 {
+        return Err(format!("proof not 32-byte aligned: {} bytes", proof_len));
+    }
+
+    let num_fields = proof_len / FIELD_SIZE;
+    let log_n_calc = num_fields as i64 - 75;
+    if log_n_calc <= 0 || log_n_calc % 11 != 0 {
+        return Err(format!(
+            "cannot derive log_n from proof size: {} fields (remainder {})",
+            num_fields,
+            log_n_calc % 11
+        ));
+    }
+    let log_n = (log_n_calc / 11) as usize;
+
+    if !(MIN_LOG_N..=MAX_LOG_N).contains(&log_n) {
+        return Err(format!(
+            "derived log_n={} out of reasonable range [{},{}]",
+            log_n, MIN_LOG_N, MAX_LOG_N
+        ));
+    }
+
+    let expected = keccak_proof_fields(log_n);
+    if num_fields != expected {
+        return Err(format!(
+            "proof size mismatch: got {} fields, expected {} (log_n={})",
+            num_fields, expected, log_n
+        ));
+    }
+
+    Ok(log_n)
+}
+
+/// Split a 32-byte big-endian coordinate into its (lo136, hi) limb pair.
+pub fn coord_to_limbs(coord: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut lo = [0u8; 32];
+    let mut hi = [0u8; 32];
+    lo[15..].copy_from_slice(&coord[15..]); // lower 17 bytes
+    hi[17..].copy_from_slice(&coord[..15]); // upper 15 bytes
+    (lo, hi)
+}
+
+/// Inverse of [`coord_to_limbs`].
+pub fn limbs_to_coord(lo: &[u8; 32], hi: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[..15].copy_from_slice(&hi[17..]);
+    out[15..].copy_from_slice(&lo[15..]);
+    out
+}
+
+/// Convert co-noir keccak proof format to the Soroban/BB UltraHonk verifier format.
+///
+/// co-noir keccak format (variable size, raw G1 coordinates):
+///   [pairing_points(16 Fr), G1_raw(8×2), sumcheck_uni(log_n×8),
+///    sumcheck_eval(41), gemini_fold_raw((log_n-1)×2), gemini_eval(log_n),
+///    shplonk_raw(1×2), kzg_raw(1×2)]
+///
+/// Soroban verifier format (fixed 458 fields, limb-encoded G1):
+///   [pairing_points(16), G1_limb(8×4), sumcheck_uni(28×8),
+///    sumcheck_eval(41), gemini_fold_limb(27×4), gemini_eval(28),
+///    shplonk_limb(1×4), kzg_limb(1×4), log_n(1)]
+pub fn convert_keccak_proof_to_soroban(proof_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let log_n = keccak_proof_log_n(proof_bytes.len())?;
+
+    tracing::info!(
+        "Proof conversion: {} fields, derived log_n={}",
+        proof_bytes.len() / FIELD_SIZE,
+        log_n
+    );
+
+    let mut out = Vec::with_capacity(SOROBAN_PROOF_BYTES);
+    let mut offset = 0usize;
+
+    // Helper: read 32 bytes from proof
+    let read_fr = |off: &mut usize| -> &[u8] {
+        let start = *off;
+        *off += FIELD_SIZE;
+        &proof_bytes[start..start + FIELD_SIZE]
+    };
+
+    // Helper: convert raw G1 (x, y) to limb-encoded (x_lo, x_hi, y_lo, y_hi)
+    let convert_g1_raw_to_limb = |off: &mut usize, out: &mut Vec<u8>| {
+        let x = &proof_bytes[*off..*off + FIELD_SIZE];
+        *off += FIELD_SIZE;
+        let y = &proof_bytes[*off..*off + FIELD_SIZE];
+        *off += FIELD_SIZE;
+        let (x_lo, x_hi) = coord_to_limbs(x);
+        let (y_lo, y_hi) = coord_to_limbs(y);
+        out.extend_from_slice(&x_lo);
+        out.extend_from_slice(&x_hi);
+        out.extend_from_slice(&y_lo);
+        out.extend_from_slice(&y_hi);
+    };
+
+    // 1) Pairing point object: 16 Fr values — these are limb-encoded accumulator
+    //    coordinates in both formats, copy directly
+    for _ in 0..PAIRING_POINTS_SIZE {
+        out.extend_from_slice(read_fr(&mut offset));
+    }
+
+    // 2) 8 G1 wire commitments: convert from raw (x,y) to limb (x_lo,x_hi,y_lo,y_hi)
+    for _ in 0..NUM_G1_WIRE_POINTS {
+        convert_g1_raw_to_limb(&mut offset, &mut out);
+    }
+
+    // 3) Sumcheck univariates: log_n rounds → pad to CONST_PROOF_SIZE_LOG_N
+    for _ in 0..log_n {
+        for _ in 0..BATCHED_RELATION_PARTIAL_LENGTH {
+            out.extend_from_slice(read_fr(&mut offset));
+        }
+    }
+    let pad_rounds = CONST_PROOF_SIZE_LOG_N - log_n;
+    out.extend(vec![
+        0u8;
+        pad_rounds
+            * BATCHED_RELATION_PARTIAL_LENGTH
+            * FIELD_SIZE
+    ]);
+
+    // 4) Sumcheck evaluations: 41 Fr (copy directly)
+    for _ in 0..NUMBER_OF_ENTITIES {
+        out.extend_from_slice(read_fr(&mut offset));
+    }
+
+    // 5) Gemini fold comms: (log_n-1) raw G1 → limb-encode, pad to 27
+    for _ in 0..(log_n - 1) {
+        convert_g1_raw_to_limb(&mut offset, &mut out);
+    }
+    let pad_gemini = (CONST_PROOF_SIZE_LOG_N - 1) - (log_n - 1);
+    out.extend(vec![0u8; pad_gemini * 4 * FIELD_SIZE]);
+
+    // 6) Gemini a evaluations: log_n Fr → pad to CONST_PROOF_SIZE_LOG_N
+    for _ in 0..log_n {
+        out.extend_from_slice(read_fr(&mut offset));
+    }
+    out.extend(vec![0u8; (CONST_PROOF_SIZE_LOG_N - log_n) * FIELD_SIZE]);
+
+    // 7) Shplonk Q and KZG quotient: 2 raw G1 → limb-encode
+    for _ in 0..NUM_FINAL_G1 {
+        convert_g1_raw_to_limb(&mut offset, &mut out);
+    }
+
+    // 8) Append log_n as final field (big-endian u256)
+    let mut log_n_field = [0u8; 32];
+    log_n_field[24..].copy_from_slice(&(log_n as u64).to_be_bytes());
+    out.extend_from_slice(&log_n_field);
+
+    // Verify we consumed all input
+    if offset != proof_bytes.len() {
+        return Err(format!(
+            "proof conversion: consumed {} of {} bytes ({} fields leftover)",
+            offset,
+            proof_bytes.len(),
+            (proof_bytes.len() - offset) / FIELD_SIZE
+        ));
+    }
+
+    if out.len() != SOROBAN_PROOF_BYTES {
+        return Err(format!(
+            "converted proof size mismatch: got {} bytes, expected {}",
+            out.len(),
+            SOROBAN_PROOF_BYTES
+        ));
+    }
+
+    tracing::info!(
+        "Proof converted: {} bytes (keccak, log_n={}) → {} bytes (soroban)",
+        proof_bytes.len(),
+        log_n,
+        out.len()
+    );
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keccak_proof_fields_formula() {
+        for log_n in MIN_LOG_N..=MAX_LOG_N {
+            assert_eq!(keccak_proof_fields(log_n), 75 + 11 * log_n);
+        }
+    }
+
+    #[test]
+    fn test_log_n_roundtrip() {
+        for log_n in MIN_LOG_N..=MAX_LOG_N {
+            let len = keccak_proof_fields(log_n) * FIELD_SIZE;
+            assert_eq!(keccak_proof_log_n(len), Ok(log_n));
+        }
+    }
+
+    #[test]
+    fn test_log_n_rejects_unaligned() {
+        let len = keccak_proof_fields(12) * FIELD_SIZE + 1;
+        assert!(keccak_proof_log_n(len).unwrap_err().contains("aligned"));
+    }
+
+    #[test]
+    fn test_log_n_rejects_bad_remainder() {
+        let len = (keccak_proof_fields(12) + 1) * FIELD_SIZE;
+        assert!(keccak_proof_log_n(len).is_err());
+        assert!(keccak_proof_log_n(0).is_err());
+        assert!(keccak_proof_log_n(75 * FIELD_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_log_n_rejects_out_of_range() {
+        for log_n in [1usize, 9, 26, 28] {
+            let len = (75 + 11 * log_n) * FIELD_SIZE;
+            let err = keccak_proof_log_n(len).unwrap_err();
+            assert!(err.contains("out of reasonable range"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_coord_limb_roundtrip() {
+        let mut coord = [0u8; 32];
+        for (i, b) in coord.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(7).wrapping_add(3);
+        }
+        let (lo, hi) = coord_to_limbs(&coord);
+        assert_eq!(&lo[..15], &[0u8; 15]);
+        assert_eq!(&hi[..17], &[0u8; 17]);
+        assert_eq!(&lo[15..], &coord[15..]);
+        assert_eq!(&hi[17..], &coord[..15]);
+        assert_eq!(limbs_to_coord(&lo, &hi), coord);
+    }
+
+    #[test]
+    fn test_convert_rejects_invalid_sizes() {
+        assert!(convert_keccak_proof_to_soroban(&[]).is_err());
+        assert!(convert_keccak_proof_to_soroban(&[0u8; 31]).is_err());
+        assert!(convert_keccak_proof_to_soroban(&[0u8; SOROBAN_PROOF_BYTES]).is_err());
+    }
+
+    #[test]
+    fn test_convert_output_size_and_log_n_trailer() {
+        for log_n in MIN_LOG_N..=MAX_LOG_N {
+            let proof = vec![0xabu8; keccak_proof_fields(log_n) * FIELD_SIZE];
+            let out = convert_keccak_proof_to_soroban(&proof).unwrap();
+            assert_eq!(out.len(), SOROBAN_PROOF_BYTES);
+            let trailer = &out[SOROBAN_PROOF_BYTES - FIELD_SIZE..];
+            assert_eq!(&trailer[..31], &[0u8; 31]);
+            assert_eq!(trailer[31] as usize, log_n);
+        }
+    }
+}
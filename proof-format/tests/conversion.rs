@@ -0,0 +1,127 @@
+//! Fixture-based tests for the keccak → Soroban proof conversion.
+//!
+//! The fixtures under `tests/fixtures` are synthetic proofs with distinct
+//! pseudo-random words in every slot; the expected Soroban encodings were
+//! produced by an independent reference implementation of the layout.
+
+use proof_format::{
+    convert_keccak_proof_to_soroban, keccak_proof_fields, limbs_to_coord,
+    BATCHED_RELATION_PARTIAL_LENGTH, CONST_PROOF_SIZE_LOG_N, FIELD_SIZE, NUMBER_OF_ENTITIES,
+    NUM_G1_WIRE_POINTS, PAIRING_POINTS_SIZE, SOROBAN_PROOF_BYTES,
+};
+
+fn load_fixture(contents: &str) -> Vec<u8> {
+    hex::decode(contents.trim()).expect("fixture is valid hex")
+}
+
+fn field(bytes: &[u8], index: usize) -> &[u8] {
+    &bytes[index * FIELD_SIZE..(index + 1) * FIELD_SIZE]
+}
+
+fn limb_point(bytes: &[u8], index: usize) -> ([u8; 32], [u8; 32]) {
+    let word = |i: usize| -> [u8; 32] { field(bytes, i).try_into().unwrap() };
+    let x = limbs_to_coord(&word(index), &word(index + 1));
+    let y = limbs_to_coord(&word(index + 2), &word(index + 3));
+    (x, y)
+}
+
+#[test]
+fn test_fixture_log_n_12_matches_reference() {
+    let proof = load_fixture(include_str!("fixtures/keccak_proof_log_n_12.hex"));
+    let expected = load_fixture(include_str!("fixtures/soroban_proof_log_n_12.hex"));
+    assert_eq!(proof.len(), keccak_proof_fields(12) * FIELD_SIZE);
+    assert_eq!(convert_keccak_proof_to_soroban(&proof).unwrap(), expected);
+}
+
+#[test]
+fn test_fixture_log_n_16_matches_reference() {
+    let proof = load_fixture(include_str!("fixtures/keccak_proof_log_n_16.hex"));
+    let expected = load_fixture(include_str!("fixtures/soroban_proof_log_n_16.hex"));
+    assert_eq!(proof.len(), keccak_proof_fields(16) * FIELD_SIZE);
+    assert_eq!(convert_keccak_proof_to_soroban(&proof).unwrap(), expected);
+}
+
+#[test]
+fn test_fixture_sections_land_in_place() {
+    let log_n = 12;
+    let proof = load_fixture(include_str!("fixtures/keccak_proof_log_n_12.hex"));
+    let out = convert_keccak_proof_to_soroban(&proof).unwrap();
+    assert_eq!(out.len(), SOROBAN_PROOF_BYTES);
+
+    // Pairing points are copied verbatim.
+    for i in 0..PAIRING_POINTS_SIZE {
+        assert_eq!(field(&out, i), field(&proof, i));
+    }
+
+    // Wire commitments are re-encoded as limbs but decode back to the raw points.
+    let mut src = PAIRING_POINTS_SIZE;
+    let mut dst = PAIRING_POINTS_SIZE;
+    for _ in 0..NUM_G1_WIRE_POINTS {
+        let (x, y) = limb_point(&out, dst);
+        assert_eq!(&x[..], field(&proof, src));
+        assert_eq!(&y[..], field(&proof, src + 1));
+        src += 2;
+        dst += 4;
+    }
+
+    // Sumcheck univariates are copied then zero-padded to the constant size.
+    for i in 0..log_n * BATCHED_RELATION_PARTIAL_LENGTH {
+        assert_eq!(field(&out, dst + i), field(&proof, src + i));
+    }
+    let padded = CONST_PROOF_SIZE_LOG_N * BATCHED_RELATION_PARTIAL_LENGTH;
+    for i in log_n * BATCHED_RELATION_PARTIAL_LENGTH..padded {
+        assert_eq!(field(&out, dst + i), &[0u8; 32]);
+    }
+    src += log_n * BATCHED_RELATION_PARTIAL_LENGTH;
+    dst += padded;
+
+    // Sumcheck evaluations are copied verbatim.
+    for i in 0..NUMBER_OF_ENTITIES {
+        assert_eq!(field(&out, dst + i), field(&proof, src + i));
+    }
+    src += NUMBER_OF_ENTITIES;
+    dst += NUMBER_OF_ENTITIES;
+
+    // Gemini fold commitments are limb-encoded and padded with zero points.
+    for _ in 0..log_n - 1 {
+        let (x, y) = limb_point(&out, dst);
+        assert_eq!(&x[..], field(&proof, src));
+        assert_eq!(&y[..], field(&proof, src + 1));
+        src += 2;
+        dst += 4;
+    }
+    for _ in log_n - 1..CONST_PROOF_SIZE_LOG_N - 1 {
+        assert_eq!(limb_point(&out, dst), ([0u8; 32], [0u8; 32]));
+        dst += 4;
+    }
+
+    // Gemini evaluations are copied and zero-padded.
+    for i in 0..log_n {
+        assert_eq!(field(&out, dst + i), field(&proof, src + i));
+    }
+    src += log_n;
+    dst += CONST_PROOF_SIZE_LOG_N;
+
+    // Shplonk Q and KZG quotient close out the proof, followed by log_n.
+    for _ in 0..2 {
+        let (x, y) = limb_point(&out, dst);
+        assert_eq!(&x[..], field(&proof, src));
+        assert_eq!(&y[..], field(&proof, src + 1));
+        src += 2;
+        dst += 4;
+    }
+    assert_eq!(src * FIELD_SIZE, proof.len());
+    assert_eq!(field(&out, dst)[31] as usize, log_n);
+    assert_eq!((dst + 1) * FIELD_SIZE, SOROBAN_PROOF_BYTES);
+}
+
+#[test]
+fn test_truncated_fixture_is_rejected() {
+    let proof = load_fixture(include_str!("fixtures/keccak_proof_log_n_12.hex"));
+    for cut in [1, FIELD_SIZE, 11 * FIELD_SIZE - 1] {
+        assert!(convert_keccak_proof_to_soroban(&proof[..proof.len() - cut]).is_err());
+    }
+    let mut extended = proof.clone();
+    extended.extend_from_slice(&[0u8; FIELD_SIZE]);
+    assert!(convert_keccak_proof_to_soroban(&extended).is_err());
+}
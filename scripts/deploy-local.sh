@@ -163,6 +163,8 @@ stellar contract invoke \
 # 8. Upload verification keys (convert from BB format first)
 echo ""
 echo "Uploading verification keys..."
+CONVERT_VK=(cargo run --quiet --release --manifest-path "$PROJECT_DIR/Cargo.toml" \
+    -p ultrahonk_soroban_verifier --features std --bin convert-vk --)
 for circuit in deal_valid reveal_board_valid showdown_valid; do
     VK_PATH="$PROJECT_DIR/circuits/$circuit/target/vk"
     VK_COMPACT="$PROJECT_DIR/circuits/$circuit/target/vk.compact"
@@ -170,7 +172,7 @@ for circuit in deal_valid reveal_board_valid showdown_valid; do
     if [ -f "$VK_PATH" ]; then
         # Convert BB VK (3680 bytes, limb-encoded) to compact + keccak formats
         echo "  Converting VK for $circuit..."
-        python3 "$PROJECT_DIR/scripts/convert-vk.py" "$VK_PATH" "$VK_COMPACT" "$VK_KECCAK" || {
+        "${CONVERT_VK[@]}" "$VK_PATH" "$VK_COMPACT" "$VK_KECCAK" || {
             echo "    WARNING: VK conversion failed for $circuit"
             continue
         }
@@ -203,7 +205,7 @@ TABLE_ID=$(stellar contract invoke \
     --network "$NETWORK" \
     -- create_table \
     --admin "$COMMITTEE_ADDRESS" \
-    --config "{\"token\":\"$TOKEN_CONTRACT\",\"min_buy_in\":\"1000000000\",\"max_buy_in\":\"100000000000\",\"small_blind\":\"500000000\",\"big_blind\":\"1000000000\",\"max_players\":$MAX_PLAYERS,\"timeout_ledgers\":100,\"committee\":\"$COMMITTEE_ADDRESS\",\"verifier\":\"$ZK_VERIFIER\",\"game_hub\":\"$GAME_HUB\"}")
+    --config "{\"token\":\"$TOKEN_CONTRACT\",\"min_buy_in\":\"1000000000\",\"max_buy_in\":\"100000000000\",\"small_blind\":\"500000000\",\"big_blind\":\"1000000000\",\"max_players\":$MAX_PLAYERS,\"deal_timeout_ledgers\":100,\"reveal_timeout_ledgers\":100,\"showdown_timeout_ledgers\":100,\"betting_timeout_ledgers\":100,\"time_bank_ledgers\":60,\"committee\":[\"$COMMITTEE_ADDRESS\"],\"committee_threshold\":1,\"verifier\":\"$ZK_VERIFIER\",\"game_hub\":\"$GAME_HUB\",\"committee_registry\":\"$COMMITTEE_REGISTRY\",\"rake_bps\":0,\"rake_cap\":\"0\",\"chip_unit\":\"10000000\",\"jackpot_bps\":0}")
 echo "  Table ID: $TABLE_ID"
 
 # 10. Mint/wrap XLM for players and have them join
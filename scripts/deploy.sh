@@ -7,6 +7,14 @@ set -euo pipefail
 NETWORK="${NETWORK:-testnet}"
 SOROBAN_RPC="${SOROBAN_RPC:-https://soroban-testnet.stellar.org}"
 SOROBAN_NETWORK_PASSPHRASE="${SOROBAN_NETWORK_PASSPHRASE:-Test SDF Network ; September 2015}"
+# Committee members as identity=endpoint, in node order, e.g.
+# MPC_MEMBERS="node0=https://node0.example,node1=https://node1.example,node2=https://node2.example"
+MPC_MEMBERS="${MPC_MEMBERS:-}"
+
+if [ -z "$MPC_MEMBERS" ]; then
+  echo "MPC_MEMBERS not set (identity=endpoint,... for each MPC node)"
+  exit 1
+fi
 
 echo "=== Stellar Poker Deploy ==="
 echo "Network: $NETWORK"
@@ -21,10 +29,11 @@ echo "=== Building Soroban contracts ==="
 cargo build --release --target wasm32-unknown-unknown \
   -p poker-table \
   -p zk-verifier \
-  -p committee-registry
+  -p committee-registry \
+  -p game-hub
 
 echo "Optimizing WASM..."
-for contract in poker_table zk_verifier committee_registry; do
+for contract in poker_table zk_verifier committee_registry game_hub; do
   stellar contract optimize \
     --wasm "target/wasm32-unknown-unknown/release/${contract}.wasm" 2>/dev/null || true
 done
@@ -46,58 +55,31 @@ fi
 DEPLOYER=$(stellar keys address deployer)
 echo "Deployer: $DEPLOYER"
 
-# --- Step 4: Deploy contracts ---
+# --- Step 4: Deploy and wire up contracts ---
 echo ""
 echo "=== Deploying contracts ==="
 
-echo "Deploying zk-verifier..."
-ZK_VERIFIER_ID=$(stellar contract deploy \
-  --wasm target/wasm32-unknown-unknown/release/zk_verifier.wasm \
-  --source deployer \
-  --network "$NETWORK" 2>/dev/null)
-echo "  ZK Verifier: $ZK_VERIFIER_ID"
-
-echo "Deploying committee-registry..."
-COMMITTEE_ID=$(stellar contract deploy \
-  --wasm target/wasm32-unknown-unknown/release/committee_registry.wasm \
-  --source deployer \
-  --network "$NETWORK" 2>/dev/null)
-echo "  Committee Registry: $COMMITTEE_ID"
-
-echo "Deploying poker-table..."
-POKER_TABLE_ID=$(stellar contract deploy \
-  --wasm target/wasm32-unknown-unknown/release/poker_table.wasm \
-  --source deployer \
-  --network "$NETWORK" 2>/dev/null)
-echo "  Poker Table: $POKER_TABLE_ID"
-
-# --- Step 5: Initialize contracts ---
-echo ""
-echo "=== Initializing contracts ==="
-
-stellar contract invoke \
-  --id "$ZK_VERIFIER_ID" \
-  --source deployer \
-  --network "$NETWORK" \
-  -- initialize --admin "$DEPLOYER" 2>/dev/null
+MEMBER_ARGS=()
+IFS=',' read -ra MEMBERS <<< "$MPC_MEMBERS"
+for member in "${MEMBERS[@]}"; do
+  MEMBER_ARGS+=(--member "$member")
+done
 
-stellar contract invoke \
-  --id "$COMMITTEE_ID" \
+cargo run --quiet --release -p bootstrap -- \
   --source deployer \
-  --network "$NETWORK" \
-  -- initialize --admin "$DEPLOYER" 2>/dev/null
+  --rpc-url "$SOROBAN_RPC" \
+  --network-passphrase "$SOROBAN_NETWORK_PASSPHRASE" \
+  --wasm-dir target/wasm32-unknown-unknown/release \
+  --vk-dir circuits \
+  --out .env.local \
+  "${MEMBER_ARGS[@]}"
 
 echo ""
 echo "=== Deploy Complete ==="
 echo ""
-echo "Contract Addresses:"
-echo "  ZK_VERIFIER=$ZK_VERIFIER_ID"
-echo "  COMMITTEE_REGISTRY=$COMMITTEE_ID"
-echo "  POKER_TABLE=$POKER_TABLE_ID"
+echo "Contract addresses are in .env.local."
 echo ""
 echo "Next steps:"
-echo "  1. Set verification keys: stellar contract invoke --id $ZK_VERIFIER_ID -- set_verification_key ..."
-echo "  2. Register committee members: stellar contract invoke --id $COMMITTEE_ID -- register_member ..."
-echo "  3. Start MPC nodes: docker-compose up mpc-node-0 mpc-node-1 mpc-node-2"
-echo "  4. Start coordinator: docker-compose up coordinator"
-echo "  5. Start web app: cd app && npm run dev"
+echo "  1. Start MPC nodes: docker-compose up mpc-node-0 mpc-node-1 mpc-node-2"
+echo "  2. Start coordinator: docker-compose up coordinator"
+echo "  3. Start web app: cd app && npm run dev"
@@ -23,5 +23,10 @@ co-noir download-crs --crs "${CRS_DIR}/bn254_g1.dat" --num-points 4194304
 echo ""
 echo "=== CRS files downloaded ==="
 ls -lh "${CRS_DIR}"/*.dat 2>/dev/null || echo "Warning: no .dat files found in ${CRS_DIR}"
+if [ -f "${CRS_DIR}/bn254_g1.dat" ]; then
+    echo ""
+    echo "CRS_SHA256=$(sha256sum "${CRS_DIR}/bn254_g1.dat" | cut -d' ' -f1)"
+    echo "  (set this on each MPC node to verify its CRS)"
+fi
 echo ""
 echo "Done. CRS files are ready for MPC proof generation."
@@ -0,0 +1,132 @@
+#!/usr/bin/env python3
+"""Roll compiled circuit artifacts out to every MPC node.
+
+Uploads circuits/<name>/target/<name>.json to each node with
+PUT /circuits/<name>, then lists every node's artifacts and checks that all
+of them report the local SHA-256. Requests are signed with MPC_AUTH_SECRET
+when it is set, the same way the coordinator signs its node calls.
+
+Usage:
+    python3 scripts/push-circuits.py [circuit ...]   # default: all compiled circuits
+    python3 scripts/push-circuits.py --check         # only compare hashes
+
+Environment:
+    MPC_NODE_0, MPC_NODE_1, ...  node URLs (default: localhost:8101-8103)
+    MPC_AUTH_SECRET              shared node auth secret, if enabled
+"""
+
+import hashlib
+import hmac
+import os
+import secrets
+import sys
+import time
+from urllib.parse import urlparse
+
+import requests
+
+PROJECT_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..")
+CIRCUIT_DIR = os.path.join(PROJECT_DIR, "circuits")
+AUTH_DOMAIN = "stellar-poker-mpc-auth:v1"
+SENDER = "operator"
+
+
+def node_endpoints():
+    endpoints = []
+    i = 0
+    while os.environ.get(f"MPC_NODE_{i}"):
+        endpoints.append(os.environ[f"MPC_NODE_{i}"].rstrip("/"))
+        i += 1
+    return endpoints or [f"http://localhost:{8101 + n}" for n in range(3)]
+
+
+def auth_headers(method, url, body):
+    secret = os.environ.get("MPC_AUTH_SECRET", "").strip()
+    if not secret:
+        return {}
+    timestamp = str(int(time.time()))
+    nonce = secrets.token_hex(16)
+    message = "\n".join([
+        AUTH_DOMAIN,
+        method.upper(),
+        urlparse(url).path,
+        SENDER,
+        timestamp,
+        nonce,
+        hashlib.sha256(body).hexdigest(),
+    ])
+    signature = hmac.new(secret.encode(), message.encode(), hashlib.sha256).hexdigest()
+    return {
+        "x-mpc-auth-sender": SENDER,
+        "x-mpc-auth-timestamp": timestamp,
+        "x-mpc-auth-nonce": nonce,
+        "x-mpc-auth-signature": signature,
+    }
+
+
+def local_artifacts(names):
+    artifacts = {}
+    for name in names or sorted(os.listdir(CIRCUIT_DIR)):
+        path = os.path.join(CIRCUIT_DIR, name, "target", f"{name}.json")
+        if not os.path.isfile(path):
+            if names:
+                sys.exit(f"ERROR: {path} not found; run ./scripts/compile-circuits.sh")
+            continue
+        with open(path, "rb") as f:
+            artifacts[name] = f.read()
+    return artifacts
+
+
+def push(endpoint, name, body):
+    url = f"{endpoint}/circuits/{name}"
+    headers = {"content-type": "application/json", **auth_headers("PUT", url, body)}
+    resp = requests.put(url, data=body, headers=headers, timeout=60)
+    if resp.status_code != 200:
+        sys.exit(f"ERROR: {url} rejected upload: HTTP {resp.status_code}: {resp.text}")
+    print(f"  {endpoint}: {name} -> {resp.json()['sha256'][:16]}")
+
+
+def installed(endpoint):
+    url = f"{endpoint}/circuits"
+    resp = requests.get(url, headers=auth_headers("GET", url, b""), timeout=30)
+    resp.raise_for_status()
+    return {a["name"]: a for a in resp.json()["artifacts"]}
+
+
+def main():
+    args = sys.argv[1:]
+    check_only = "--check" in args
+    names = [a for a in args if not a.startswith("--")]
+    artifacts = local_artifacts(names)
+    endpoints = node_endpoints()
+
+    if not check_only:
+        print(f"Pushing {len(artifacts)} circuit(s) to {len(endpoints)} node(s)...")
+        for name, body in artifacts.items():
+            for endpoint in endpoints:
+                push(endpoint, name, body)
+
+    print("Checking installed artifacts...")
+    mismatches = 0
+    for endpoint in endpoints:
+        remote = installed(endpoint)
+        for name, body in artifacts.items():
+            expected = hashlib.sha256(body).hexdigest()
+            info = remote.get(name)
+            if info is None:
+                print(f"  {endpoint}: {name} MISSING")
+                mismatches += 1
+            elif info["sha256"] != expected or not info["compatible"]:
+                reason = info.get("error") or f"sha256 {info['sha256'][:16]}"
+                print(f"  {endpoint}: {name} MISMATCH ({reason})")
+                mismatches += 1
+            else:
+                print(f"  {endpoint}: {name} ok (noir {info['noir_version']})")
+
+    if mismatches:
+        sys.exit(f"{mismatches} artifact(s) out of sync")
+    print("All nodes in sync.")
+
+
+if __name__ == "__main__":
+    main()
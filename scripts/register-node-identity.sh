@@ -0,0 +1,60 @@
+#!/usr/bin/env bash
+# Register an MPC node's identity key in the committee registry.
+#
+# Reads the node's Ed25519 identity key from its /health endpoint and stores
+# it on-chain against the node's registry member address. The member must
+# already be registered (register_member) and signs the call itself.
+#
+# Prerequisites:
+#   - stellar CLI installed, with an identity for the member account
+#   - The node running and reachable
+#
+# Usage:
+#   ./scripts/register-node-identity.sh <node-url> <member-identity> [member-address]
+#
+# Environment:
+#   COMMITTEE_REGISTRY_CONTRACT  registry contract ID (or set in .env.local)
+#   NETWORK                      stellar CLI network (default: local)
+
+set -euo pipefail
+
+SCRIPT_DIR="$(cd "$(dirname "$0")" && pwd)"
+PROJECT_DIR="$(dirname "$SCRIPT_DIR")"
+
+if [ $# -lt 2 ]; then
+    echo "Usage: $0 <node-url> <member-identity> [member-address]" >&2
+    exit 1
+fi
+
+NODE_URL="${1%/}"
+MEMBER_IDENTITY="$2"
+MEMBER_ADDRESS="${3:-$(stellar keys address "$MEMBER_IDENTITY")}"
+NETWORK="${NETWORK:-local}"
+
+if [ -z "${COMMITTEE_REGISTRY_CONTRACT:-}" ] && [ -f "$PROJECT_DIR/.env.local" ]; then
+    COMMITTEE_REGISTRY_CONTRACT=$(grep '^COMMITTEE_REGISTRY_CONTRACT=' "$PROJECT_DIR/.env.local" | cut -d= -f2)
+fi
+if [ -z "${COMMITTEE_REGISTRY_CONTRACT:-}" ]; then
+    echo "ERROR: COMMITTEE_REGISTRY_CONTRACT not set" >&2
+    exit 1
+fi
+
+IDENTITY_KEY=$(curl -s "$NODE_URL/health" | python3 -c 'import json, sys; print(json.load(sys.stdin)["identity_key"])')
+if [ -z "$IDENTITY_KEY" ]; then
+    echo "ERROR: could not read identity key from $NODE_URL/health" >&2
+    exit 1
+fi
+
+echo "Registering identity key for $MEMBER_ADDRESS"
+echo "  Node:         $NODE_URL"
+echo "  Identity key: $IDENTITY_KEY"
+
+stellar contract invoke \
+    --id "$COMMITTEE_REGISTRY_CONTRACT" \
+    --source "$MEMBER_IDENTITY" \
+    --network "$NETWORK" \
+    -- set_identity_key \
+    --member "$MEMBER_ADDRESS" \
+    --identity_key "$IDENTITY_KEY"
+
+echo "Done. Add $MEMBER_ADDRESS to MPC_NODE_ADDRESSES (in node order) on the coordinator."
@@ -4,12 +4,15 @@ use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use sha2::{Digest, Sha256};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::AppState;
+use crate::{AppState, AuthState, RateLimitState};
 
 const AUTH_SKEW_SECS: i64 = 300;
 const RATE_LIMIT_WINDOW_SECS: u64 = 60;
 const RATE_LIMIT_MAX_REQUESTS: usize = 60;
 const ALLOW_INSECURE_DEV_AUTH_ENV: &str = "ALLOW_INSECURE_DEV_AUTH";
+const INVITE_CODE_HEADER: &str = "x-invite-code";
+const MIN_INVITE_CODE_LEN: usize = 4;
+const MAX_INVITE_CODE_LEN: usize = 64;
 
 pub(crate) struct AuthContext {
     pub address: String,
@@ -22,6 +25,7 @@ pub(crate) async fn enforce_rate_limit(
     action: &str,
 ) -> Result<(), StatusCode> {
     let now = now_unix_secs_u64()?;
+    super::reaper::touch_table(state, table_id).await;
     let ip = extract_ip(headers);
     let bucket_key = format!("{}:{}:{}", ip, table_id, action);
 
@@ -90,10 +94,85 @@ pub(crate) async fn validate_signed_request(
     auth_state
         .last_nonce_by_address
         .insert(address.clone(), nonce);
+    auth_state
+        .nonce_seen_at
+        .insert(address.clone(), now.max(0) as u64);
 
     Ok(AuthContext { address })
 }
 
+/// Drop rate-limit buckets with no requests left in the current window.
+pub(crate) fn prune_rate_limit_buckets(rl: &mut RateLimitState, now: u64) -> usize {
+    let before = rl.requests_by_bucket.len();
+    rl.requests_by_bucket.retain(|_, bucket| {
+        bucket.retain(|ts| now.saturating_sub(*ts) <= RATE_LIMIT_WINDOW_SECS);
+        !bucket.is_empty()
+    });
+    rl.requests_by_bucket.shrink_to_fit();
+    before - rl.requests_by_bucket.len()
+}
+
+/// Forget per-address nonces idle past `idle_ttl_secs`.
+///
+/// The TTL is floored at twice the signature timestamp skew so a forgotten
+/// nonce can never be replayed: any request reusing it carries a timestamp
+/// outside the accepted window.
+pub(crate) fn prune_auth_nonces(auth_state: &mut AuthState, now: u64, idle_ttl_secs: u64) -> usize {
+    let ttl = idle_ttl_secs.max(2 * AUTH_SKEW_SECS as u64);
+    let expired: Vec<String> = auth_state
+        .nonce_seen_at
+        .iter()
+        .filter(|(_, seen)| now.saturating_sub(**seen) > ttl)
+        .map(|(address, _)| address.clone())
+        .collect();
+    for address in &expired {
+        auth_state.nonce_seen_at.remove(address);
+        auth_state.last_nonce_by_address.remove(address);
+    }
+    auth_state.nonce_seen_at.shrink_to_fit();
+    auth_state.last_nonce_by_address.shrink_to_fit();
+    expired.len()
+}
+
+/// Validate an invite code and return its hash.
+pub(crate) fn hash_invite_code(code: &str) -> Result<[u8; 32], StatusCode> {
+    let code = code.trim();
+    if !(MIN_INVITE_CODE_LEN..=MAX_INVITE_CODE_LEN).contains(&code.len()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(b"stellar-poker-invite:");
+    hasher.update(code.as_bytes());
+    Ok(hasher.finalize().into())
+}
+
+/// Require the `x-invite-code` header for private tables. Public tables pass.
+pub(crate) async fn require_invite_code(
+    state: &AppState,
+    headers: &HeaderMap,
+    table_id: u32,
+) -> Result<(), StatusCode> {
+    let expected = match state.invite_codes.read().await.get(&table_id) {
+        Some(hash) => *hash,
+        None => return Ok(()),
+    };
+    let provided = headers
+        .get(INVITE_CODE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(StatusCode::FORBIDDEN)?;
+    let provided = hash_invite_code(provided).map_err(|_| StatusCode::FORBIDDEN)?;
+
+    // Constant-time comparison of the two digests.
+    let diff = expected
+        .iter()
+        .zip(provided.iter())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    if diff != 0 {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
 fn verify_signature(address: &str, message: &str, signature_raw: &str) -> Result<(), StatusCode> {
     let stellar_pk = stellar_strkey::ed25519::PublicKey::from_string(address)
         .map_err(|_| StatusCode::UNAUTHORIZED)?;
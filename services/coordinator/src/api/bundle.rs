@@ -0,0 +1,259 @@
+//! Per-hand audit bundle: everything about a settled hand in one signed
+//! download, so a player can check it without trusting the coordinator.
+//!
+//! Proofs and public inputs come from the table session, cards are parsed
+//! back out of those public inputs, and the hand's contract events are read
+//! from the RPC. The bundle is signed with the committee key, whose address
+//! the table contract records as its `committee`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::http::StatusCode;
+use ed25519_dalek::Signer;
+use poker_table_types::GamePhase;
+use proof_format::{convert_keccak_proof_to_soroban, field_to_bytes32};
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{Limits, ReadXdr, ScVal};
+
+use super::parsing::{parse_showdown_outputs, HIDDEN_CARD};
+use super::session::REHYDRATED_SESSION_ID;
+use super::types::*;
+use crate::events::{decode_topics, RawEvent, Rpc};
+use crate::{soroban, AppState, HandProof, TableSession};
+
+const BUNDLE_FORMAT: &str = "stellar-poker-hand-bundle/1";
+const STREETS: [(&str, usize); 3] = [("flop", 3), ("turn", 1), ("river", 1)];
+
+/// Whether the session's hand is over, locally or on chain.
+pub(crate) async fn hand_is_settled(state: &AppState, session: &TableSession) -> bool {
+    if session.phase == "settlement" {
+        return true;
+    }
+    // Fold wins and timeouts settle on chain without the session noticing.
+    super::session::fetch_onchain_table_view(&state.soroban_config, session.table_id)
+        .await
+        .map(|view| matches!(view.phase, GamePhase::Settlement | GamePhase::Waiting))
+        .unwrap_or(false)
+}
+
+/// Build and sign the bundle for the session's hand.
+pub(crate) async fn build_archive(
+    state: &AppState,
+    session: &TableSession,
+) -> Result<HandBundleArchive, StatusCode> {
+    let signing_key = state.soroban_config.committee_signing_key().map_err(|e| {
+        tracing::error!("hand bundle cannot be signed: {}", e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+    let signer = state
+        .soroban_config
+        .committee_address()
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+
+    let onchain_id = soroban::resolve_onchain_table_id(&state.soroban_config, session.table_id);
+    let events = Rpc::new(&state.soroban_config.rpc_url)
+        .table_events(
+            state.soroban_config.table_contract(session.table_id),
+            onchain_id,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("hand bundle: failed to read table events: {}", e);
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    let (hand_number, events) = hand_events(events, &session.deck_root);
+    let bundle = HandBundle {
+        format: BUNDLE_FORMAT,
+        table_id: session.table_id,
+        hand_number,
+        contract: state
+            .soroban_config
+            .table_contract(session.table_id)
+            .to_string(),
+        network_passphrase: state.soroban_config.network_passphrase.clone(),
+        players: session.player_order.clone(),
+        deck_root: session.deck_root.clone(),
+        hand_commitments: session.hand_commitments.clone(),
+        board: board(session),
+        showdown: showdown(session),
+        proofs: session
+            .proofs
+            .iter()
+            .map(bundle_proof)
+            .collect::<Result<_, _>>()?,
+        contributions: BundleContributions {
+            commitments: session.contribution_commitments.clone(),
+            revealed: session.revealed_contributions.clone(),
+        },
+        events,
+        logs: BundleLogRefs {
+            replica_id: state.leader.replica_id.clone(),
+            proof_sessions: proof_sessions(session),
+        },
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+
+    let bytes = serde_json::to_vec(&bundle).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let digest = Sha256::digest(&bytes);
+    Ok(HandBundleArchive {
+        bundle,
+        sha256: hex::encode(digest),
+        signer,
+        signature: hex::encode(signing_key.sign(&digest).to_bytes()),
+    })
+}
+
+/// The proof as the contract received it, so its digest matches the one
+/// in the hand's events.
+fn bundle_proof(hand_proof: &HandProof) -> Result<BundleProof, StatusCode> {
+    let submitted = convert_keccak_proof_to_soroban(&hand_proof.proof.proof).map_err(|e| {
+        tracing::error!(
+            "hand bundle: proof {} does not convert: {}",
+            hand_proof.proof.session_id,
+            e
+        );
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(BundleProof {
+        label: hand_proof.label.clone(),
+        circuit: hand_proof.circuit.to_string(),
+        session_id: hand_proof.proof.session_id.clone(),
+        sha256: hex::encode(Sha256::digest(&submitted)),
+        proof: hex::encode(&submitted),
+        public_inputs: hand_proof.proof.public_inputs.clone(),
+        tx_hash: hand_proof.tx_hash.clone(),
+    })
+}
+
+fn board(session: &TableSession) -> Vec<BundleStreet> {
+    let mut offset = 0;
+    let mut streets = Vec::new();
+    for (street, count) in STREETS {
+        let Some(cards) = session.revealed_cards_by_phase.get(street) else {
+            break;
+        };
+        let deck_indices = session
+            .board_indices
+            .get(offset..offset + count)
+            .map(<[u32]>::to_vec)
+            .unwrap_or_default();
+        offset += count;
+        streets.push(BundleStreet {
+            street: street.to_string(),
+            cards: cards.clone(),
+            deck_indices,
+            tx_hash: session.reveal_tx_hashes.get(street).cloned(),
+        });
+    }
+    streets
+}
+
+fn showdown(session: &TableSession) -> Option<BundleShowdown> {
+    session.showdown_session_id.as_ref()?;
+    let hole_cards = session
+        .proofs
+        .iter()
+        .find(|p| p.label == "showdown")
+        .and_then(|p| {
+            parse_showdown_outputs(&p.proof.public_inputs, session.player_order.len()).ok()
+        })
+        .map(|parsed| {
+            parsed
+                .hole_cards
+                .into_iter()
+                .map(|(c1, c2)| (c1 != HIDDEN_CARD).then_some([c1, c2]))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(BundleShowdown {
+        winner: session.showdown_result.as_ref().map(|(w, _)| w.clone()),
+        winner_index: session.showdown_result.as_ref().map(|(_, i)| *i),
+        hole_cards,
+        tx_hash: session.showdown_tx_hash.clone(),
+    })
+}
+
+fn proof_sessions(session: &TableSession) -> Vec<String> {
+    let mut ids = Vec::new();
+    if session.deal_session_id != REHYDRATED_SESSION_ID {
+        ids.push(session.deal_session_id.clone());
+    }
+    for (street, _) in STREETS {
+        ids.extend(session.reveal_session_ids.get(street).cloned());
+    }
+    ids.extend(session.showdown_session_id.clone());
+    ids
+}
+
+/// The value under `name` in an event's data map.
+fn data_field(event: &RawEvent, name: &str) -> Option<ScVal> {
+    match ScVal::from_xdr_base64(&event.value, Limits::none()) {
+        Ok(ScVal::Map(Some(entries))) => entries
+            .iter()
+            .find(
+                |entry| matches!(&entry.key, ScVal::Symbol(s) if s.0.as_slice() == name.as_bytes()),
+            )
+            .map(|entry| entry.val.clone()),
+        _ => None,
+    }
+}
+
+/// The on-chain hand number, from the `deal_committed` event that recorded
+/// this deck root.
+fn onchain_hand_number(events: &[(String, RawEvent)], deck_root: &str) -> Option<u32> {
+    let root = field_to_bytes32(deck_root).ok()?;
+    events.iter().rev().find_map(|(name, event)| {
+        if name != "deal_committed" {
+            return None;
+        }
+        match (
+            data_field(event, "hand_number")?,
+            data_field(event, "deck_root")?,
+        ) {
+            (ScVal::U32(hand_number), ScVal::Bytes(bytes)) if bytes.as_slice() == root => {
+                Some(hand_number)
+            }
+            _ => None,
+        }
+    })
+}
+
+/// The on-chain hand number and the events from its `hand_started` up to the
+/// next hand's. Neither is known if the deal is outside the RPC's retention
+/// window.
+fn hand_events(events: Vec<RawEvent>, deck_root: &str) -> (Option<u32>, Vec<BundleEvent>) {
+    let named: Vec<(String, RawEvent)> = events
+        .into_iter()
+        .filter_map(|event| decode_topics(&event).map(|(name, _)| (name, event)))
+        .collect();
+    let Some(hand_number) = onchain_hand_number(&named, deck_root) else {
+        return (None, Vec::new());
+    };
+
+    let mut in_hand = false;
+    let mut out = Vec::new();
+    for (name, event) in named {
+        if name == "hand_started" {
+            in_hand = matches!(
+                data_field(&event, "hand_number"),
+                Some(ScVal::U32(n)) if n == hand_number
+            );
+        }
+        if in_hand {
+            out.push(BundleEvent {
+                id: event.id,
+                name,
+                ledger: event.ledger,
+                closed_at: event.ledger_closed_at,
+                tx_hash: event.tx_hash,
+                topic: event.topic,
+                value: event.value,
+            });
+        }
+    }
+    (Some(hand_number), out)
+}
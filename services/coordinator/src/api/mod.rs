@@ -1,28 +1,42 @@
 //! REST API handlers for the coordinator service.
 
 mod auth;
+mod bundle;
 mod parsing;
+mod reaper;
 mod session;
 pub mod types;
 
+pub use reaper::spawn_session_reaper;
 pub use types::*;
 
 use axum::{
     extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
     Json,
 };
+use poker_table_types::{Action, GamePhase, PokerTableError};
 use std::collections::HashMap;
+use std::time::Instant;
 use uuid::Uuid;
 
-use crate::{mpc, soroban, AppState, TableSession};
-use auth::{allow_insecure_dev_auth, enforce_rate_limit, validate_signed_request};
+use crate::events::ReadyState;
+use crate::pipeline::{next_reveal, PipelineMode};
+use crate::stats::PROOF_KINDS;
+use crate::{mpc, soroban, AppState, HandProof, TableSession};
+use auth::{
+    allow_insecure_dev_auth, enforce_rate_limit, hash_invite_code, require_invite_code,
+    validate_signed_request,
+};
 use parsing::{
-    parse_deal_outputs, parse_requested_buy_in, parse_reveal_outputs, parse_showdown_outputs,
+    check_deal_is_fresh, parse_deal_outputs, parse_requested_buy_in, parse_reveal_outputs,
+    parse_showdown_outputs,
 };
 use session::{
-    ensure_session_exists, fetch_onchain_table_view, is_identity_missing_error,
-    next_proof_session_id, resolve_deal_players_from_lobby, validate_players,
+    ensure_session_exists, ensure_table_leader, fetch_onchain_table_view, fetch_table_state,
+    is_identity_missing_error, next_hand, next_proof_session_id, previous_deal,
+    record_settlement_from_chain, resolve_deal_players_from_lobby, validate_players,
     validate_reveal_phase, validate_table_id,
 };
 
@@ -44,6 +58,7 @@ pub async fn get_chain_config(
         rpc_url: state.soroban_config.rpc_url.clone(),
         network_passphrase: state.soroban_config.network_passphrase.clone(),
         poker_table_contract: state.soroban_config.poker_table_contract.clone(),
+        table_namespaces: state.soroban_config.table_namespaces.clone(),
     }))
 }
 
@@ -51,6 +66,8 @@ pub async fn get_chain_config(
 ///
 /// Creates a new empty on-chain table by copying config from the reference
 /// table. Players then join directly on-chain with their own wallet auth.
+/// An `invite_code` makes the table private to clients that present it; a
+/// `namespace` creates it in that contract, from its table 0.
 pub async fn create_table(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -81,8 +98,24 @@ pub async fn create_table(
             tracing::warn!("create_table invalid buy_in: {}", e);
             StatusCode::BAD_REQUEST
         })?;
+    let invite_hash = req
+        .invite_code
+        .as_deref()
+        .map(hash_invite_code)
+        .transpose()?;
 
-    let reference_table_id = state.soroban_config.onchain_table_id.unwrap_or(0);
+    let reference_table_id = match req.namespace.as_deref() {
+        None | Some(soroban::DEFAULT_NAMESPACE) => {
+            state.soroban_config.onchain_table_id.unwrap_or(0)
+        }
+        Some(name) => {
+            state
+                .soroban_config
+                .namespace(name)
+                .ok_or(StatusCode::BAD_REQUEST)?
+                .table_id_base
+        }
+    };
     let table_id = soroban::create_seeded_table(
         &state.soroban_config,
         reference_table_id,
@@ -95,6 +128,10 @@ pub async fn create_table(
         StatusCode::BAD_GATEWAY
     })?;
 
+    if let Some(hash) = invite_hash {
+        state.invite_codes.write().await.insert(table_id, hash);
+    }
+
     if solo_mode {
         let default_buy_in = std::env::var("LOBBY_BUY_IN")
             .ok()
@@ -133,12 +170,14 @@ pub async fn create_table(
         table_id,
         max_players: table_view.max_players,
         joined_wallets: table_view.seats.len(),
+        private: invite_hash.is_some(),
     }))
 }
 
 /// GET /api/tables/open
 ///
-/// List open tables (waiting phase) that still have unclaimed wallet slots.
+/// List open public tables (waiting phase) that still have unclaimed wallet slots.
+/// Each table contract is read a page of `get_table_page` summaries at a time.
 pub async fn list_open_tables(
     State(state): State<AppState>,
 ) -> Result<Json<OpenTablesResponse>, StatusCode> {
@@ -150,29 +189,58 @@ pub async fn list_open_tables(
         .ok()
         .and_then(|v| v.parse::<u32>().ok())
         .unwrap_or(32);
+    let private_tables: Vec<u32> = state.invite_codes.read().await.keys().copied().collect();
+    let config = &state.soroban_config;
+    let namespaces = std::iter::once((
+        soroban::DEFAULT_NAMESPACE,
+        config.poker_table_contract.as_str(),
+    ))
+    .chain(
+        config
+            .table_namespaces
+            .iter()
+            .map(|ns| (ns.name.as_str(), ns.contract.as_str())),
+    );
     let mut tables = Vec::new();
-    for table_id in 0..scan_max {
-        let Ok(view) = fetch_onchain_table_view(&state.soroban_config, table_id).await else {
-            continue;
-        };
+    for (namespace, contract) in namespaces {
+        // The first `scan_max` on-chain IDs, a page of summaries at a time.
+        let mut start = 0;
+        while start < scan_max {
+            let limit = (scan_max - start).min(soroban::TABLE_PAGE_SIZE);
+            let page = match soroban::get_table_page(config, contract, start, limit).await {
+                Ok(page) => page,
+                Err(e) => {
+                    tracing::warn!("Listing tables in namespace {}: {}", namespace, e);
+                    break;
+                }
+            };
+            start += limit;
+
+            for summary in page {
+                let Some(table_id) = config.coordinator_table_id(contract, summary.table_id) else {
+                    continue;
+                };
+                if private_tables.contains(&table_id) || summary.phase != GamePhase::Waiting {
+                    continue;
+                }
 
-        if view.phase != "Waiting" {
-            continue;
-        }
+                let joined_wallets = summary.player_count as usize;
+                let open_wallet_slots =
+                    summary.max_players.saturating_sub(summary.player_count) as usize;
+                if open_wallet_slots == 0 {
+                    continue;
+                }
 
-        let joined_wallets = view.seats.len();
-        let open_wallet_slots = view.max_players.saturating_sub(joined_wallets as u32) as usize;
-        if open_wallet_slots == 0 {
-            continue;
+                tables.push(OpenTableInfo {
+                    table_id,
+                    namespace: namespace.to_string(),
+                    phase: summary.phase.to_string(),
+                    max_players: summary.max_players,
+                    joined_wallets,
+                    open_wallet_slots,
+                });
+            }
         }
-
-        tables.push(OpenTableInfo {
-            table_id,
-            phase: view.phase.clone(),
-            max_players: view.max_players,
-            joined_wallets,
-            open_wallet_slots,
-        });
     }
 
     Ok(Json(OpenTablesResponse { tables }))
@@ -181,6 +249,7 @@ pub async fn list_open_tables(
 /// POST /api/table/{table_id}/join
 ///
 /// Register wallet-to-seat mapping for a wallet that already joined on-chain.
+/// Private tables also require the `x-invite-code` header.
 pub async fn join_table(
     State(state): State<AppState>,
     Path(table_id): Path<u32>,
@@ -189,11 +258,12 @@ pub async fn join_table(
     validate_table_id(table_id)?;
     enforce_rate_limit(&state, &headers, table_id, "join_table").await?;
     let auth = validate_signed_request(&state, &headers, table_id, "join_table", None).await?;
+    require_invite_code(&state, &headers, table_id).await?;
 
     let view = fetch_onchain_table_view(&state.soroban_config, table_id)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
-    if view.phase != "Waiting" {
+    if view.phase != GamePhase::Waiting {
         return Err(StatusCode::CONFLICT);
     }
 
@@ -223,11 +293,15 @@ pub async fn join_table(
 }
 
 /// GET /api/table/{table_id}/lobby
+///
+/// Private tables require the `x-invite-code` header.
 pub async fn get_table_lobby(
     State(state): State<AppState>,
     Path(table_id): Path<u32>,
+    headers: HeaderMap,
 ) -> Result<Json<TableLobbyResponse>, StatusCode> {
     validate_table_id(table_id)?;
+    require_invite_code(&state, &headers, table_id).await?;
     let view = fetch_onchain_table_view(&state.soroban_config, table_id)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
@@ -259,7 +333,7 @@ pub async fn get_table_lobby(
 
     Ok(Json(TableLobbyResponse {
         table_id,
-        phase: view.phase,
+        phase: view.phase.to_string(),
         max_players: view.max_players,
         joined_wallets: view.seats.len(),
         seats,
@@ -278,6 +352,7 @@ pub async fn request_deal(
 ) -> Result<Json<DealResponse>, StatusCode> {
     validate_table_id(table_id)?;
     enforce_rate_limit(&state, &headers, table_id, "request_deal").await?;
+    ensure_table_leader(&state, table_id).await?;
 
     let players = if req.players.is_empty() {
         resolve_deal_players_from_lobby(&state, table_id).await?
@@ -299,11 +374,16 @@ pub async fn request_deal(
         return Err(StatusCode::SERVICE_UNAVAILABLE);
     }
 
+    state.reveal_pipeline.discard(&[table_id]).await;
+    let (hand_number, hand_nonce) = next_hand(&state, table_id).await;
+    let proof_started = Instant::now();
     let prepared_deal = mpc::prepare_deal_from_nodes(
         &state.mpc_config.node_endpoints,
         &state.mpc_config.circuit_dir,
         table_id,
         &players,
+        hand_number,
+        hand_nonce,
     )
     .await
     .map_err(|e| {
@@ -311,6 +391,19 @@ pub async fn request_deal(
         StatusCode::BAD_GATEWAY
     })?;
 
+    // Nodes outside the committee threshold that prepared contribute nothing.
+    let contribution_commitments = prepared_deal
+        .commitments
+        .iter()
+        .zip(&prepared_deal.share_set_ids)
+        .filter(|(_, id)| !id.is_empty())
+        .map(|(commitment, _)| commitment.clone())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            tracing::error!("Deal preparation missing a node contribution commitment");
+            StatusCode::BAD_GATEWAY
+        })?;
+
     let proof_session_id = format!("table-{}-deal-{}", table_id, Uuid::new_v4());
     let deal_proof = mpc::generate_proof_from_share_sets(
         table_id,
@@ -325,12 +418,23 @@ pub async fn request_deal(
         tracing::error!("Deal proof generation failed: {}", e);
         StatusCode::BAD_GATEWAY
     })?;
+    let proof_ms = proof_started.elapsed().as_millis() as u64;
 
     let parsed_deal =
         parse_deal_outputs(&deal_proof.public_inputs, players.len()).map_err(|e| {
             tracing::error!("Deal public input parsing failed: {}", e);
             StatusCode::BAD_GATEWAY
         })?;
+    let (previous_roots, previous_commitments) = previous_deal(&state, table_id).await;
+    if let Err(e) = check_deal_is_fresh(&parsed_deal, &previous_roots, &previous_commitments) {
+        tracing::error!(
+            "Refusing to submit deal for table {} hand {}: {} — an MPC node may be reusing its contribution",
+            table_id,
+            hand_number,
+            e
+        );
+        return Err(StatusCode::BAD_GATEWAY);
+    }
 
     let tx_hash = match soroban::submit_deal_proof(
         &state.soroban_config,
@@ -339,6 +443,7 @@ pub async fn request_deal(
         &deal_proof.public_inputs,
         &parsed_deal.deck_root,
         &parsed_deal.hand_commitments,
+        &parsed_deal.dealt_indices,
     )
     .await
     {
@@ -363,7 +468,7 @@ pub async fn request_deal(
         })
         .collect();
 
-    let session = TableSession {
+    let mut session = TableSession {
         table_id,
         deck_root: parsed_deal.deck_root.clone(),
         hand_commitments: parsed_deal.hand_commitments.clone(),
@@ -381,8 +486,26 @@ pub async fn request_deal(
         showdown_session_id: None,
         showdown_result: None,
         proof_nonce: 0,
+        hand_number,
+        hand_nonce,
+        contribution_commitments,
+        revealed_contributions: Vec::new(),
+        proofs: vec![HandProof {
+            label: "deal".to_string(),
+            circuit: "deal_valid",
+            proof: deal_proof.clone(),
+            tx_hash: tx_hash.clone(),
+        }],
     };
 
+    {
+        let mut stats = state.table_stats.write().await;
+        let table_stats = stats.entry(table_id).or_default();
+        table_stats.record_proof_latency("deal", proof_ms);
+        table_stats.record_hand_dealt(session.hand_number, &session.player_order);
+    }
+
+    pipeline_next_reveal(&state, &mut session).await;
     state.tables.write().await.insert(table_id, session);
 
     Ok(Json(DealResponse {
@@ -406,6 +529,7 @@ pub async fn request_reveal(
 
     let action = format!("request_reveal:{}", phase);
     enforce_rate_limit(&state, &headers, table_id, &action).await?;
+    ensure_table_leader(&state, table_id).await?;
 
     if state.mpc_config.node_endpoints.is_empty() {
         return Err(StatusCode::SERVICE_UNAVAILABLE);
@@ -419,12 +543,7 @@ pub async fn request_reveal(
     // Any caller may trigger reveal progression.
     // Private card data remains protected by get_player_cards auth checks.
 
-    let expected_next_phase = match session.phase.as_str() {
-        "preflop" => "flop",
-        "flop" => "turn",
-        "turn" => "river",
-        _ => return Err(StatusCode::CONFLICT),
-    };
+    let expected_next_phase = next_reveal(&session.phase).ok_or(StatusCode::CONFLICT)?;
     if phase != expected_next_phase {
         return Err(StatusCode::CONFLICT);
     }
@@ -471,34 +590,59 @@ pub async fn request_reveal(
         }
     }
 
-    let prepared_reveal = mpc::prepare_reveal_from_nodes(
-        &state.mpc_config.node_endpoints,
-        &state.mpc_config.circuit_dir,
-        table_id,
-        &phase,
-        &session.dealt_indices,
-        &session.deck_root,
-    )
-    .await
-    .map_err(|e| {
-        tracing::error!("Reveal preparation failed: {}", e);
-        StatusCode::BAD_GATEWAY
-    })?;
+    let proof_started = Instant::now();
+    let speculation = state
+        .reveal_pipeline
+        .take(table_id, &phase, &session.dealt_indices, &session.deck_root)
+        .await;
+    let (share_set_ids, speculated_proof) = match speculation {
+        Some(speculation) => (speculation.share_set_ids, speculation.proof),
+        None => {
+            let prepared_reveal = mpc::prepare_reveal_from_nodes(
+                &state.mpc_config.node_endpoints,
+                &state.mpc_config.circuit_dir,
+                table_id,
+                &phase,
+                session.hand_nonce,
+                &session.dealt_indices,
+                &session.deck_root,
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Reveal preparation failed: {}", e);
+                StatusCode::BAD_GATEWAY
+            })?;
+            (prepared_reveal.share_set_ids, None)
+        }
+    };
 
-    let proof_session_id = next_proof_session_id(session, &format!("reveal-{}", phase));
-    let reveal_proof = mpc::generate_proof_from_share_sets(
-        table_id,
-        &prepared_reveal.share_set_ids,
-        &proof_session_id,
-        "reveal_board_valid",
-        &state.mpc_config.circuit_dir,
-        &state.mpc_config.node_endpoints,
-    )
-    .await
-    .map_err(|e| {
-        tracing::error!("Reveal proof generation failed: {}", e);
-        StatusCode::BAD_GATEWAY
-    })?;
+    let reveal_proof = match speculated_proof {
+        Some(proof) => proof,
+        None => {
+            let proof_session_id = next_proof_session_id(session, &format!("reveal-{}", phase));
+            mpc::generate_proof_from_share_sets(
+                table_id,
+                &share_set_ids,
+                &proof_session_id,
+                "reveal_board_valid",
+                &state.mpc_config.circuit_dir,
+                &state.mpc_config.node_endpoints,
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Reveal proof generation failed: {}", e);
+                StatusCode::BAD_GATEWAY
+            })?
+        }
+    };
+    // With pipelining this is the wait the request saw, not the proving time.
+    state
+        .table_stats
+        .write()
+        .await
+        .entry(table_id)
+        .or_default()
+        .record_proof_latency("reveal", proof_started.elapsed().as_millis() as u64);
 
     let num_revealed = match phase.as_str() {
         "flop" => 3usize,
@@ -550,6 +694,13 @@ pub async fn request_reveal(
     session
         .revealed_cards_by_phase
         .insert(phase.clone(), parsed_reveal.cards.clone());
+    session.proofs.push(HandProof {
+        label: format!("reveal-{}", phase),
+        circuit: "reveal_board_valid",
+        proof: reveal_proof.clone(),
+        tx_hash: tx_hash.clone(),
+    });
+    pipeline_next_reveal(&state, session).await;
 
     Ok(Json(RevealResponse {
         status: "revealed".to_string(),
@@ -560,6 +711,22 @@ pub async fn request_reveal(
     }))
 }
 
+/// With reveal pipelining on, start on the reveal that follows the betting
+/// street the session has just opened.
+async fn pipeline_next_reveal(state: &AppState, session: &mut TableSession) {
+    if state.reveal_pipeline.mode() == PipelineMode::Off {
+        return;
+    }
+    let Some(phase) = next_reveal(&session.phase) else {
+        return;
+    };
+    let proof_session_id = next_proof_session_id(session, &format!("reveal-{}", phase));
+    state
+        .reveal_pipeline
+        .start(&state.mpc_config, session, phase, proof_session_id)
+        .await;
+}
+
 /// POST /api/table/{table_id}/request-showdown
 pub async fn request_showdown(
     State(state): State<AppState>,
@@ -569,6 +736,7 @@ pub async fn request_showdown(
     validate_table_id(table_id)?;
 
     enforce_rate_limit(&state, &headers, table_id, "request_showdown").await?;
+    ensure_table_leader(&state, table_id).await?;
 
     if state.mpc_config.node_endpoints.is_empty() {
         return Err(StatusCode::SERVICE_UNAVAILABLE);
@@ -620,14 +788,23 @@ pub async fn request_showdown(
         }
     }
 
+    // Whose cards the proof shows follows who is still in, who mucks and
+    // what each put in, as the contract will check.
+    let seats = match fetch_table_state(&state, table_id).await {
+        Some(onchain) => mpc::ShowdownSeats::from_table(&onchain),
+        None => mpc::ShowdownSeats::all_showing(session.hand_commitments.len()),
+    };
+
+    let proof_started = Instant::now();
     let prepared_showdown = mpc::prepare_showdown_from_nodes(
         &state.mpc_config.node_endpoints,
         &state.mpc_config.circuit_dir,
         table_id,
+        session.hand_nonce,
         &session.board_indices,
-        session.player_order.len() as u32,
         &session.hand_commitments,
         &session.deck_root,
+        &seats,
     )
     .await
     .map_err(|e| {
@@ -649,6 +826,13 @@ pub async fn request_showdown(
         tracing::error!("Showdown proof generation failed: {}", e);
         StatusCode::BAD_GATEWAY
     })?;
+    state
+        .table_stats
+        .write()
+        .await
+        .entry(table_id)
+        .or_default()
+        .record_proof_latency("showdown", proof_started.elapsed().as_millis() as u64);
 
     let parsed_showdown =
         parse_showdown_outputs(&showdown_proof.public_inputs, session.player_order.len()).map_err(
@@ -713,6 +897,8 @@ pub async fn request_showdown(
         }
     };
 
+    record_settlement_from_chain(&state, table_id).await;
+
     session.phase = "settlement".to_string();
     session.showdown_tx_hash = tx_hash.clone();
     session.showdown_session_id = Some(showdown_proof.session_id.clone());
@@ -721,6 +907,12 @@ pub async fn request_showdown(
     } else {
         Some((winner.clone(), parsed_showdown.winner_index))
     };
+    session.proofs.push(HandProof {
+        label: "showdown".to_string(),
+        circuit: "showdown_valid",
+        proof: showdown_proof.clone(),
+        tx_hash: tx_hash.clone(),
+    });
 
     let (status, winner, winner_index) = if settled_by_timeout {
         ("settled_timeout".to_string(), String::new(), 0)
@@ -742,39 +934,79 @@ pub async fn request_showdown(
     }))
 }
 
+/// Error from a handler that submits to the contract: a bare status, or a
+/// contract rejection explained in the body so clients can show the reason.
+pub enum ActionError {
+    Status(StatusCode),
+    Contract(PokerTableError),
+}
+
+impl From<StatusCode> for ActionError {
+    fn from(status: StatusCode) -> Self {
+        ActionError::Status(status)
+    }
+}
+
+impl IntoResponse for ActionError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            ActionError::Status(status) => status.into_response(),
+            ActionError::Contract(error) => (
+                StatusCode::CONFLICT,
+                Json(ContractErrorResponse {
+                    error: error.message(),
+                    name: error.name(),
+                    code: error.code(),
+                }),
+            )
+                .into_response(),
+        }
+    }
+}
+
 /// POST /api/table/{table_id}/player-action
 ///
 /// Submit a player betting action to the on-chain poker-table contract.
 /// In lobby mode, authenticated wallet addresses are translated to their
-/// mapped on-chain seat address.
+/// mapped on-chain seat address. Actions the contract rejects come back as
+/// 409 with a `ContractErrorResponse`.
 pub async fn player_action(
     State(state): State<AppState>,
     Path(table_id): Path<u32>,
     headers: HeaderMap,
     Json(req): Json<PlayerActionRequest>,
-) -> Result<Json<PlayerActionResponse>, StatusCode> {
+) -> Result<Json<PlayerActionResponse>, ActionError> {
     validate_table_id(table_id)?;
 
     let normalized = req.action.trim().to_ascii_lowercase();
-    let amount = match normalized.as_str() {
-        "fold" | "check" | "call" | "allin" | "all_in" => None,
+    let action = match normalized.as_str() {
+        "fold" => Action::Fold,
+        "check" => Action::Check,
+        "call" => Action::Call,
+        "allin" | "all_in" => Action::AllIn,
         "bet" | "raise" => {
             let amount = req.amount.ok_or(StatusCode::BAD_REQUEST)?;
             if amount <= 0 {
-                return Err(StatusCode::BAD_REQUEST);
+                return Err(StatusCode::BAD_REQUEST.into());
+            }
+            if normalized == "bet" {
+                Action::Bet(amount)
+            } else {
+                Action::Raise(amount)
             }
-            Some(amount)
         }
-        _ => return Err(StatusCode::BAD_REQUEST),
+        _ => return Err(StatusCode::BAD_REQUEST.into()),
     };
+    let amount = action.amount();
 
     let action_key = format!("player_action:{}", normalized);
     enforce_rate_limit(&state, &headers, table_id, &action_key).await?;
     let auth = validate_signed_request(&state, &headers, table_id, &action_key, None).await?;
 
     if !state.soroban_config.is_configured() {
-        return Err(StatusCode::SERVICE_UNAVAILABLE);
+        return Err(StatusCode::SERVICE_UNAVAILABLE.into());
     }
+    ensure_table_leader(&state, table_id).await?;
 
     let mapped_player = {
         let lobby = state.lobby_assignments.read().await;
@@ -796,34 +1028,53 @@ pub async fn player_action(
     } else if state.soroban_config.has_identity_for_player(&auth.address) {
         auth.address.clone()
     } else {
-        return Err(StatusCode::UNAUTHORIZED);
+        return Err(StatusCode::UNAUTHORIZED.into());
     };
 
+    let is_fold = action == Action::Fold;
+
     let tx_hash = soroban::submit_player_action(
         &state.soroban_config,
         table_id,
         &player_address,
-        &normalized,
-        amount,
+        action,
     )
     .await
     .map_err(|e| {
+        let rejection = PokerTableError::from_invoke_error(&e);
         tracing::error!(
-            "player_action failed: table={}, caller={}, player={}, action={}, amount={:?}, err={}",
+            "player_action failed: table={}, caller={}, player={}, action={:?}, rejected={:?}, err={}",
             table_id,
             auth.address,
             player_address,
-            normalized,
-            amount,
+            action,
+            rejection,
             e
         );
-        if e.contains("Error(Contract,") {
-            StatusCode::CONFLICT
-        } else {
-            StatusCode::BAD_GATEWAY
+        match rejection {
+            Some(error) => ActionError::Contract(error),
+            None => StatusCode::BAD_GATEWAY.into(),
         }
     })?;
 
+    let local_phase = state
+        .tables
+        .read()
+        .await
+        .get(&table_id)
+        .map(|session| session.phase.clone())
+        .unwrap_or_default();
+    state
+        .table_stats
+        .write()
+        .await
+        .entry(table_id)
+        .or_default()
+        .record_action(&player_address, &normalized, &local_phase);
+    if is_fold {
+        record_settlement_from_chain(&state, table_id).await;
+    }
+
     let tx_hash = if tx_hash.is_empty() {
         None
     } else {
@@ -883,7 +1134,7 @@ pub async fn get_player_cards(
     let positions = vec![*pos1, *pos2];
     drop(tables); // release read lock before async call
 
-    let (cards, salts) = mpc::resolve_hole_cards(&node_endpoints, table_id, &positions)
+    let (cards, salts) = mpc::resolve_hole_cards(&node_endpoints, table_id, &address, &positions)
         .await
         .map_err(|e| {
             tracing::error!("Failed to resolve hole cards: {}", e);
@@ -902,7 +1153,149 @@ pub async fn get_player_cards(
     }))
 }
 
+/// POST /api/table/{table_id}/audit-contributions
+///
+/// After the hand, have every node reveal its deal contribution and check it
+/// against the signed commitment recorded at deal time. Revealing retires the
+/// contributions, so the result is cached for later requests.
+pub async fn audit_contributions(
+    State(state): State<AppState>,
+    Path(table_id): Path<u32>,
+    headers: HeaderMap,
+) -> Result<Json<AuditContributionsResponse>, StatusCode> {
+    validate_table_id(table_id)?;
+    enforce_rate_limit(&state, &headers, table_id, "audit_contributions").await?;
+    let auth =
+        validate_signed_request(&state, &headers, table_id, "audit_contributions", None).await?;
+    ensure_table_leader(&state, table_id).await?;
+
+    let (commitments, cached) = {
+        let tables = state.tables.read().await;
+        let session = tables.get(&table_id).ok_or(StatusCode::NOT_FOUND)?;
+        if !allow_insecure_dev_auth() && !session.player_order.iter().any(|p| p == &auth.address) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        if session.phase != "settlement" {
+            return Err(StatusCode::CONFLICT);
+        }
+        if session.contribution_commitments.is_empty() {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        (
+            session.contribution_commitments.clone(),
+            session.revealed_contributions.clone(),
+        )
+    };
+
+    let revealed = if cached.is_empty() {
+        let revealed = mpc::reveal_contributions_from_nodes(
+            &state.mpc_config.node_endpoints,
+            table_id,
+            &commitments,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Contribution reveal failed: {}", e);
+            StatusCode::BAD_GATEWAY
+        })?;
+        let mut tables = state.tables.write().await;
+        if let Some(session) = tables.get_mut(&table_id) {
+            session.revealed_contributions = revealed.clone();
+        }
+        revealed
+    } else {
+        cached
+    };
+    if revealed.len() != commitments.len() {
+        return Err(StatusCode::BAD_GATEWAY);
+    }
+
+    let nodes: Vec<ContributionAudit> = commitments
+        .into_iter()
+        .zip(revealed)
+        .map(|(commitment, contribution)| {
+            let vrf_verified = contribution.vrf.as_ref().map(|proof| {
+                proof
+                    .verify(table_id, commitment.node_id, contribution.permutation.len())
+                    .map(|derived| {
+                        derived.permutation == contribution.permutation
+                            && derived.salts == contribution.salts
+                    })
+                    .unwrap_or(false)
+            });
+            ContributionAudit {
+                node_id: commitment.node_id,
+                matches: contribution.node_id == commitment.node_id
+                    && commitment.matches(&contribution.permutation, &contribution.salts),
+                vrf_verified,
+                commitment,
+                permutation: contribution.permutation,
+                salts: contribution.salts,
+                vrf: contribution.vrf,
+            }
+        })
+        .collect();
+    let verified = nodes
+        .iter()
+        .all(|n| n.matches && n.vrf_verified != Some(false));
+    if !verified {
+        tracing::error!("Table {} contribution audit found a mismatch", table_id);
+    }
+
+    Ok(Json(AuditContributionsResponse {
+        table_id,
+        verified,
+        nodes,
+    }))
+}
+
+/// GET /api/table/{table_id}/hand-bundle
+///
+/// Signed audit bundle for the table's latest hand once it has settled:
+/// proofs, public inputs, parsed cards, tx hashes, contribution commitments,
+/// contract events and log references, served as a download. Restricted to
+/// the hand's players, like the contribution audit.
+pub async fn get_hand_bundle(
+    State(state): State<AppState>,
+    Path(table_id): Path<u32>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, StatusCode> {
+    validate_table_id(table_id)?;
+    if !state.soroban_config.is_configured() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    enforce_rate_limit(&state, &headers, table_id, "hand_bundle").await?;
+    let auth = validate_signed_request(&state, &headers, table_id, "hand_bundle", None).await?;
+    ensure_table_leader(&state, table_id).await?;
+    ensure_session_exists(&state, table_id).await?;
+
+    let session = state
+        .tables
+        .read()
+        .await
+        .get(&table_id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !allow_insecure_dev_auth() && !session.player_order.iter().any(|p| p == &auth.address) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    if !bundle::hand_is_settled(&state, &session).await {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let archive = bundle::build_archive(&state, &session).await?;
+    let disposition = format!(
+        "attachment; filename=\"table-{}-hand-{}.json\"",
+        table_id,
+        archive.bundle.hand_number.unwrap_or(session.hand_number)
+    );
+    Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(archive)))
+}
+
 /// GET /api/table/{table_id}/state
+///
+/// The contract's spectator-safe view of the table, which leaves out the
+/// deal's commitments and the committee's data.
 pub async fn get_table_state(
     State(state): State<AppState>,
     Path(table_id): Path<u32>,
@@ -917,13 +1310,169 @@ pub async fn get_table_state(
     Ok(Json(TableStateResponse { state: result }))
 }
 
+/// GET /api/table/{table_id}/stats
+///
+/// Aggregated hand, pot and proof-latency statistics for the table, with
+/// players ordered by chips won for leaderboard display.
+pub async fn get_table_stats(
+    State(state): State<AppState>,
+    Path(table_id): Path<u32>,
+) -> Result<Json<TableStatsResponse>, StatusCode> {
+    validate_table_id(table_id)?;
+
+    let stats = state.table_stats.read().await;
+    let table_stats = stats.get(&table_id).cloned().unwrap_or_default();
+
+    let mut players: Vec<PlayerStatsEntry> = table_stats
+        .players
+        .iter()
+        .map(|(address, p)| PlayerStatsEntry {
+            address: address.clone(),
+            hands_dealt: p.hands_dealt,
+            vpip_hands: p.vpip_hands,
+            vpip_pct: if p.hands_dealt == 0 {
+                0.0
+            } else {
+                p.vpip_hands as f64 * 100.0 / p.hands_dealt as f64
+            },
+            pots_won: p.pots_won,
+            chips_won: p.chips_won,
+            biggest_pot_won: p.biggest_pot_won,
+        })
+        .collect();
+    players.sort_by(|a, b| {
+        b.chips_won
+            .cmp(&a.chips_won)
+            .then_with(|| a.address.cmp(&b.address))
+    });
+
+    let proof_latencies = PROOF_KINDS
+        .iter()
+        .filter_map(|kind| {
+            let latency = table_stats.proof_latencies.get(kind)?;
+            Some(ProofLatencyEntry {
+                circuit: kind.to_string(),
+                count: latency.count,
+                avg_ms: latency.avg_ms(),
+                min_ms: latency.min_ms,
+                max_ms: latency.max_ms,
+                last_ms: latency.last_ms,
+            })
+        })
+        .collect();
+
+    Ok(Json(TableStatsResponse {
+        table_id,
+        hands_played: table_stats.hands_played,
+        hands_settled: table_stats.hands_settled(),
+        showdowns: table_stats.showdowns,
+        fold_wins: table_stats.fold_wins,
+        timeout_settlements: table_stats.timeout_settlements,
+        biggest_pot: table_stats.biggest_pot,
+        total_pot_volume: table_stats.total_pot_volume,
+        players,
+        proof_latencies,
+    }))
+}
+
+/// GET /api/table/{table_id}/ready-state
+///
+/// Whether the table is waiting on a player's action, a committee proof or a
+/// board reveal. Kept current from the contract's events, so clients can poll
+/// it rather than interpret the raw phase.
+pub async fn get_ready_state(
+    State(state): State<AppState>,
+    Path(table_id): Path<u32>,
+) -> Result<Json<ReadyStateResponse>, StatusCode> {
+    validate_table_id(table_id)?;
+    if !state.soroban_config.is_configured() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    reaper::touch_table(&state, table_id).await;
+
+    let ready = match state.ready_states.get(table_id).await {
+        Some(ready) => ready,
+        None => {
+            let table = soroban::get_table(&state.soroban_config, table_id)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("ready-state: failed to read table {}: {}", table_id, e);
+                    StatusCode::SERVICE_UNAVAILABLE
+                })?;
+            let ready = ReadyState::from_table(&table, None);
+            state.ready_states.insert(table_id, ready.clone()).await;
+            ready
+        }
+    };
+
+    Ok(Json(ReadyStateResponse {
+        table_id,
+        phase: ready.phase.to_string(),
+        hand_number: ready.hand_number,
+        waiting_on: ready.waiting_on,
+        timeout_ledger: ready.timeout_ledger,
+        latest_ledger: state.ready_states.latest_ledger(),
+        last_event: ready.last_event,
+    }))
+}
+
+/// GET /api/table/:table_id/positions
+pub async fn get_table_positions(
+    State(state): State<AppState>,
+    Path(table_id): Path<u32>,
+) -> Result<Json<TablePositionsResponse>, StatusCode> {
+    validate_table_id(table_id)?;
+    if !state.soroban_config.is_configured() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    let table = soroban::get_table(&state.soroban_config, table_id)
+        .await
+        .map_err(|e| {
+            tracing::warn!("positions: failed to read table {}: {}", table_id, e);
+            StatusCode::NOT_FOUND
+        })?;
+    let positions = table.positions();
+    let seat = |index: Option<u32>| {
+        let player = table.player(index?)?;
+        Some(PositionSeat {
+            seat_index: player.seat_index,
+            address: player.address.clone(),
+        })
+    };
+
+    Ok(Json(TablePositionsResponse {
+        table_id,
+        phase: table.phase.to_string(),
+        hand_number: positions.map_or(table.hand_number, |p| p.hand_number),
+        dealer: seat(positions.map(|p| p.dealer)),
+        small_blind: seat(positions.and_then(|p| p.small_blind)),
+        big_blind: seat(positions.map(|p| p.big_blind)),
+        next_to_act: seat(positions.and_then(|p| p.next_to_act)),
+    }))
+}
+
+/// GET /api/leader
+///
+/// Replica identity and the table leases this coordinator currently holds.
+pub async fn leader_status(State(state): State<AppState>) -> Json<LeaderStatusResponse> {
+    Json(LeaderStatusResponse {
+        replica_id: state.leader.replica_id.clone(),
+        distributed: state.leader.is_distributed(),
+        held_tables: state.leader.held_tables().await,
+    })
+}
+
 /// GET /api/committee/status
 pub async fn committee_status(State(state): State<AppState>) -> Json<CommitteeStatusResponse> {
     let healthy = mpc::check_node_health(&state.mpc_config.node_endpoints).await;
+    let identity_verified = mpc::verify_node_identities(&state.mpc_config.node_endpoints).await;
+    let attested = mpc::attestation_status(&state.mpc_config.node_endpoints).await;
 
     Json(CommitteeStatusResponse {
         nodes: state.mpc_config.node_endpoints.len(),
         healthy,
+        identity_verified,
+        attested,
         status: "active".to_string(),
     })
 }
@@ -1,8 +1,14 @@
-use ark_bn254::Fr;
-use ark_ff::PrimeField;
+use std::collections::HashSet;
+
+use poker_table_types::{GamePhase, TableState};
 
 use super::MAX_PLAYERS;
 
+const DECK_SIZE: u32 = 52;
+
+/// What the showdown proof outputs for a hole card it does not show.
+pub(crate) const HIDDEN_CARD: u32 = DECK_SIZE;
+
 pub(crate) struct ParsedDealOutputs {
     pub deck_root: String,
     pub hand_commitments: Vec<String>,
@@ -15,6 +21,7 @@ pub(crate) struct ParsedRevealOutputs {
 }
 
 pub(crate) struct ParsedShowdownOutputs {
+    /// `HIDDEN_CARD` twice for a player who folded or mucked.
     pub hole_cards: Vec<(u32, u32)>,
     pub winner_index: u32,
 }
@@ -61,6 +68,54 @@ pub(crate) fn parse_deal_outputs(
     })
 }
 
+/// Refuse a deal that repeats the previous hand's. Nodes that reuse their
+/// contributions, e.g. after a partial restart, reproduce the deck root and
+/// hand commitments, and the new hand would replay the old cards.
+pub(crate) fn check_deal_is_fresh(
+    deal: &ParsedDealOutputs,
+    previous_roots: &[String],
+    previous_commitments: &[String],
+) -> Result<(), String> {
+    let canonical =
+        |value: &str| normalize_field_value(value).unwrap_or_else(|_| value.to_string());
+    let previous_roots: HashSet<String> = previous_roots.iter().map(|r| canonical(r)).collect();
+    let previous_commitments: HashSet<String> =
+        previous_commitments.iter().map(|c| canonical(c)).collect();
+
+    if previous_roots.contains(&canonical(&deal.deck_root)) {
+        return Err(format!(
+            "deck root {} repeats the previous hand's",
+            deal.deck_root
+        ));
+    }
+    let mut commitments = HashSet::new();
+    for commitment in &deal.hand_commitments {
+        let canonical_commitment = canonical(commitment);
+        if previous_commitments.contains(&canonical_commitment) {
+            return Err(format!(
+                "hand commitment {} repeats the previous hand's",
+                commitment
+            ));
+        }
+        if !commitments.insert(canonical_commitment) {
+            return Err(format!(
+                "hand commitment {} is dealt more than once",
+                commitment
+            ));
+        }
+    }
+    let mut indices = HashSet::new();
+    for index in &deal.dealt_indices {
+        if *index >= DECK_SIZE || !indices.insert(*index) {
+            return Err(format!(
+                "deck position {} is out of range or dealt twice",
+                index
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn parse_reveal_outputs(
     public_inputs: &[String],
     num_revealed: usize,
@@ -149,51 +204,29 @@ pub(crate) fn parse_requested_buy_in(raw: &str) -> Result<i128, String> {
     Ok(value)
 }
 
-pub(crate) fn parse_u32_value(value: &serde_json::Value) -> Option<u32> {
-    if let Some(v) = value.as_u64() {
-        return u32::try_from(v).ok();
-    }
-    value.as_str().and_then(|s| s.parse::<u32>().ok())
-}
-
-pub(crate) fn normalize_field_value(raw: &str) -> Result<String, String> {
-    let s = raw.trim();
-    if s.is_empty() {
-        return Err("empty field string".to_string());
-    }
-
-    if s.chars().all(|c| c.is_ascii_digit()) {
-        return Ok(s.to_string());
-    }
-
-    let hex_str = s.strip_prefix("0x").unwrap_or(s);
-    if !hex_str.chars().all(|c| c.is_ascii_hexdigit()) {
-        return Err(format!("invalid field string '{}'", raw));
-    }
-    if hex_str.len() % 2 != 0 {
-        return Err(format!("hex field has odd length '{}'", raw));
-    }
-
-    let bytes = hex::decode(hex_str).map_err(|e| format!("invalid hex field '{}': {}", raw, e))?;
-    let fr = Fr::from_be_bytes_mod_order(&bytes);
-    Ok(fr.into_bigint().to_string())
-}
-
-pub(crate) fn map_onchain_phase_to_local(phase: &str) -> Option<&'static str> {
-    match phase {
-        "Waiting" => Some("waiting"),
-        "Dealing" => Some("dealing"),
-        "Preflop" => Some("preflop"),
-        "DealingFlop" => Some("preflop"),
-        "Flop" => Some("flop"),
-        "DealingTurn" => Some("flop"),
-        "Turn" => Some("turn"),
-        "DealingRiver" => Some("turn"),
-        "River" => Some("river"),
+pub(crate) use proof_format::normalize_field_value;
+
+pub(crate) fn map_onchain_phase_to_local(table: &TableState) -> Option<&'static str> {
+    match table.phase {
+        GamePhase::Waiting => Some("waiting"),
+        GamePhase::Dealing => Some("dealing"),
+        GamePhase::Preflop => Some("preflop"),
+        GamePhase::DealingFlop => Some("preflop"),
+        GamePhase::Flop => Some("flop"),
+        GamePhase::DealingTurn => Some("flop"),
+        GamePhase::Turn => Some("turn"),
+        GamePhase::DealingRiver => Some("turn"),
+        GamePhase::River => Some("river"),
+        // A run-out stays on the street before the one it reveals next.
+        GamePhase::RunOut => Some(match table.next_street() {
+            "flop" => "preflop",
+            "turn" => "flop",
+            _ => "turn",
+        }),
         // On-chain "Showdown" means betting is complete and the committee can
         // submit showdown proof next.
-        "Showdown" => Some("river"),
-        "Settlement" => Some("settlement"),
-        _ => None,
+        GamePhase::Showdown => Some("river"),
+        GamePhase::Settlement => Some("settlement"),
+        GamePhase::Dispute => None,
     }
 }
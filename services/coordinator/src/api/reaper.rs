@@ -0,0 +1,133 @@
+//! Background reaper that frees state for tables idle past a TTL.
+//!
+//! Every table touched through the API gets an activity timestamp. Tables
+//! idle longer than `SESSION_IDLE_TTL_SECS` lose their cached session,
+//! lobby assignments, ready state and leader lease; a later request rebuilds
+//! the session from on-chain state. Rate-limit buckets and auth nonces are
+//! pruned on the same pass.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::AppState;
+
+use super::auth::{prune_auth_nonces, prune_rate_limit_buckets};
+
+const DEFAULT_IDLE_TTL_SECS: u64 = 6 * 60 * 60;
+const DEFAULT_REAPER_INTERVAL_SECS: u64 = 5 * 60;
+
+#[derive(Clone, Copy, Debug)]
+struct ReaperConfig {
+    idle_ttl_secs: u64,
+    interval_secs: u64,
+}
+
+impl ReaperConfig {
+    fn from_env() -> Self {
+        let read = |key: &str, default: u64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+        Self {
+            idle_ttl_secs: read("SESSION_IDLE_TTL_SECS", DEFAULT_IDLE_TTL_SECS),
+            interval_secs: read("SESSION_REAPER_INTERVAL_SECS", DEFAULT_REAPER_INTERVAL_SECS),
+        }
+    }
+}
+
+/// Record activity on a table so the reaper keeps its state alive.
+pub(crate) async fn touch_table(state: &AppState, table_id: u32) {
+    state
+        .table_activity
+        .write()
+        .await
+        .insert(table_id, now_unix_secs());
+}
+
+/// Spawn the periodic reaper task.
+pub fn spawn_session_reaper(state: AppState) {
+    let config = ReaperConfig::from_env();
+    tracing::info!(
+        "Session reaper: idle_ttl={}s, interval={}s",
+        config.idle_ttl_secs,
+        config.interval_secs
+    );
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        loop {
+            interval.tick().await;
+            reap_idle_state(&state, config.idle_ttl_secs).await;
+        }
+    });
+}
+
+async fn reap_idle_state(state: &AppState, idle_ttl_secs: u64) {
+    let now = now_unix_secs();
+
+    let tracked: Vec<u32> = {
+        let tables = state.tables.read().await;
+        let lobby = state.lobby_assignments.read().await;
+        tables.keys().chain(lobby.keys()).copied().collect()
+    };
+
+    let idle_tables: Vec<u32> = {
+        let mut activity = state.table_activity.write().await;
+        // Tables that predate activity tracking start their idle clock now.
+        for table_id in tracked {
+            activity.entry(table_id).or_insert(now);
+        }
+
+        let idle: Vec<u32> = activity
+            .iter()
+            .filter(|(_, last)| now.saturating_sub(**last) > idle_ttl_secs)
+            .map(|(table_id, _)| *table_id)
+            .collect();
+        for table_id in &idle {
+            activity.remove(table_id);
+        }
+        idle
+    };
+
+    if !idle_tables.is_empty() {
+        let mut tables = state.tables.write().await;
+        let mut lobby = state.lobby_assignments.write().await;
+        for table_id in &idle_tables {
+            tables.remove(table_id);
+            lobby.remove(table_id);
+        }
+        tables.shrink_to_fit();
+        lobby.shrink_to_fit();
+    }
+    state.ready_states.forget(&idle_tables).await;
+    state.reveal_pipeline.discard(&idle_tables).await;
+    for table_id in &idle_tables {
+        state.leader.release_table(*table_id).await;
+    }
+
+    let buckets_removed = {
+        let mut rl = state.rate_limit_state.write().await;
+        prune_rate_limit_buckets(&mut rl, now)
+    };
+    let nonces_removed = {
+        let mut auth_state = state.auth_state.write().await;
+        prune_auth_nonces(&mut auth_state, now, idle_ttl_secs)
+    };
+
+    if !idle_tables.is_empty() || buckets_removed > 0 || nonces_removed > 0 {
+        tracing::info!(
+            "Session reaper: expired tables={:?}, rate_limit_buckets={}, auth_nonces={}",
+            idle_tables,
+            buckets_removed,
+            nonces_removed
+        );
+    }
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -1,13 +1,47 @@
 use axum::http::StatusCode;
-use serde_json::Value;
+use poker_table_types::{GamePhase, TableState};
 use std::collections::{HashMap, HashSet};
 
-use crate::{soroban, AppState, TableSession};
 use super::auth::is_valid_stellar_address;
-use super::parsing::{map_onchain_phase_to_local, normalize_field_value, parse_u32_value};
+use super::parsing::{map_onchain_phase_to_local, normalize_field_value};
 use super::{MAX_PLAYERS, MIN_PLAYERS};
+use crate::{leader::LeaseStatus, soroban, AppState, TableSession};
+
+/// Deal session ID of a session rebuilt from chain, whose deal proof was
+/// generated elsewhere.
+pub(crate) const REHYDRATED_SESSION_ID: &str = "rehydrated-from-chain";
+
+/// Require this replica to hold the table lease before driving MPC or chain
+/// submission. A freshly acquired lease drops any cached session so it is
+/// rebuilt from on-chain state written by the previous leader.
+pub(crate) async fn ensure_table_leader(state: &AppState, table_id: u32) -> Result<(), StatusCode> {
+    let status = state.leader.acquire_table(table_id).await.map_err(|e| {
+        tracing::error!("table {} lease check failed: {}", table_id, e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+
+    match status {
+        LeaseStatus::Renewed => Ok(()),
+        LeaseStatus::Acquired => {
+            if state.leader.is_distributed() {
+                tracing::info!(
+                    "replica {} acquired lease for table {}",
+                    state.leader.replica_id,
+                    table_id
+                );
+                state.tables.write().await.remove(&table_id);
+            }
+            Ok(())
+        }
+        LeaseStatus::HeldElsewhere => Err(StatusCode::MISDIRECTED_REQUEST),
+    }
+}
 
-pub(crate) async fn ensure_session_exists(state: &AppState, table_id: u32) -> Result<(), StatusCode> {
+pub(crate) async fn ensure_session_exists(
+    state: &AppState,
+    table_id: u32,
+) -> Result<(), StatusCode> {
+    super::reaper::touch_table(state, table_id).await;
     {
         let tables = state.tables.read().await;
         if tables.contains_key(&table_id) {
@@ -19,7 +53,7 @@ pub(crate) async fn ensure_session_exists(state: &AppState, table_id: u32) -> Re
         return Err(StatusCode::NOT_FOUND);
     }
 
-    let raw_state = soroban::get_table_state(&state.soroban_config, table_id)
+    let onchain = soroban::get_table(&state.soroban_config, table_id)
         .await
         .map_err(|e| {
             tracing::warn!(
@@ -30,7 +64,7 @@ pub(crate) async fn ensure_session_exists(state: &AppState, table_id: u32) -> Re
             StatusCode::SERVICE_UNAVAILABLE
         })?;
 
-    let restored = build_session_from_onchain_state(table_id, &raw_state).map_err(|e| {
+    let restored = build_session_from_onchain_state(table_id, &onchain).map_err(|e| {
         tracing::warn!(
             "failed to rehydrate table {} from on-chain state: {}",
             table_id,
@@ -44,56 +78,123 @@ pub(crate) async fn ensure_session_exists(state: &AppState, table_id: u32) -> Re
     Ok(())
 }
 
+pub(crate) async fn fetch_table_state(state: &AppState, table_id: u32) -> Option<TableState> {
+    if !state.soroban_config.is_configured() {
+        return None;
+    }
+    soroban::get_table(&state.soroban_config, table_id)
+        .await
+        .map_err(|e| tracing::warn!("failed to read table {}: {}", table_id, e))
+        .ok()
+}
+
+/// Attribute the pot if the on-chain table has settled the current hand.
+pub(crate) async fn record_settlement_from_chain(state: &AppState, table_id: u32) {
+    if !state.soroban_config.is_configured() {
+        return;
+    }
+    let settlement = match soroban::get_last_settlement(&state.soroban_config, table_id).await {
+        Ok(settlement) => settlement,
+        Err(e) => {
+            tracing::warn!("failed to read table {} settlement: {}", table_id, e);
+            return;
+        }
+    };
+    let settled = state
+        .table_stats
+        .write()
+        .await
+        .entry(table_id)
+        .or_default()
+        .record_settlement(&settlement);
+    if settled {
+        tracing::info!(
+            "stats: recorded {:?} settlement of hand {} for table {}",
+            settlement.source,
+            settlement.hand_number,
+            table_id
+        );
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct OnchainTableView {
-    pub phase: String,
+    pub phase: GamePhase,
     pub max_players: u32,
     pub seats: Vec<(u32, String)>,
+    pub hand_number: u32,
+    /// The hand's `session_id`, which its proofs carry as their nonce.
+    pub session_id: u32,
+}
+
+/// Seated players as (seat index, address), in seat order.
+fn seats_in_order(table: &TableState) -> Vec<(u32, String)> {
+    let mut seats: Vec<(u32, String)> = table
+        .seated()
+        .map(|p| (p.seat_index, p.address.clone()))
+        .collect();
+    seats.sort_by_key(|(seat, _)| *seat);
+    seats
 }
 
 pub(crate) async fn fetch_onchain_table_view(
     soroban_config: &soroban::SorobanConfig,
     table_id: u32,
 ) -> Result<OnchainTableView, String> {
-    let raw_state = soroban::get_table_state(soroban_config, table_id).await?;
-    let value: Value =
-        serde_json::from_str(&raw_state).map_err(|e| format!("invalid table json: {}", e))?;
-
-    let phase = value
-        .get("phase")
-        .and_then(|v| v.as_str())
-        .ok_or("missing phase")?
-        .to_string();
-
-    let mut seats: Vec<(u32, String)> = value
-        .get("players")
-        .and_then(|v| v.as_array())
-        .ok_or("missing players")?
-        .iter()
-        .filter_map(|player| {
-            let address = player.get("address")?.as_str()?.to_string();
-            let seat = player
-                .get("seat_index")
-                .and_then(parse_u32_value)
-                .unwrap_or(0);
-            Some((seat, address))
-        })
-        .collect();
-    seats.sort_by_key(|(seat, _)| *seat);
-
-    let max_players = value
-        .get("config")
-        .and_then(|cfg| cfg.get("max_players"))
-        .and_then(parse_u32_value)
-        .unwrap_or_else(|| seats.len() as u32);
-
+    let table = soroban::get_table(soroban_config, table_id).await?;
     Ok(OnchainTableView {
-        phase,
-        max_players,
-        seats,
+        phase: table.phase,
+        max_players: table.config.max_players,
+        seats: seats_in_order(&table),
+        hand_number: table.hand_number,
+        session_id: table.session_id,
     })
 }
 
+/// Hand number and proof nonce for the next deal: the on-chain values when
+/// the table can be read, otherwise one past the local session's hand and a
+/// zero nonce, as nothing is submitted on-chain.
+pub(crate) async fn next_hand(state: &AppState, table_id: u32) -> (u32, u32) {
+    if state.soroban_config.is_configured() {
+        match fetch_onchain_table_view(&state.soroban_config, table_id).await {
+            Ok(view) => return (view.hand_number, view.session_id),
+            Err(e) => tracing::warn!("Falling back to local hand number: {}", e),
+        }
+    }
+    let tables = state.tables.read().await;
+    let hand_number = tables
+        .get(&table_id)
+        .map(|session| session.hand_number.saturating_add(1))
+        .unwrap_or(1);
+    (hand_number, 0)
+}
+
+/// Deck roots and hand commitments of the table's previous hand, from the
+/// local session and from the chain, which keeps the last deck root across
+/// coordinator restarts.
+pub(crate) async fn previous_deal(state: &AppState, table_id: u32) -> (Vec<String>, Vec<String>) {
+    let mut roots = Vec::new();
+    let mut commitments = Vec::new();
+    if let Some(session) = state.tables.read().await.get(&table_id) {
+        roots.push(session.deck_root.clone());
+        commitments.extend(session.hand_commitments.iter().cloned());
+    }
+    if state.soroban_config.is_configured() {
+        match soroban::get_table(&state.soroban_config, table_id).await {
+            Ok(table) => {
+                roots.push(table.deck_root);
+                commitments.extend(table.hand_commitments);
+            }
+            Err(e) => tracing::warn!(
+                "Checking deal freshness against the local session only: {}",
+                e
+            ),
+        }
+    }
+    roots.retain(|root| !root.is_empty());
+    (roots, commitments)
+}
+
 pub(crate) async fn resolve_deal_players_from_lobby(
     state: &AppState,
     table_id: u32,
@@ -128,34 +229,15 @@ pub(crate) async fn resolve_deal_players_from_lobby(
 
 fn build_session_from_onchain_state(
     table_id: u32,
-    raw_state: &str,
+    onchain: &TableState,
 ) -> Result<TableSession, String> {
-    let value: serde_json::Value =
-        serde_json::from_str(raw_state).map_err(|e| format!("invalid table json: {}", e))?;
-
-    let phase_raw = value
-        .get("phase")
-        .and_then(|v| v.as_str())
-        .ok_or("missing phase")?;
-    let phase = map_onchain_phase_to_local(phase_raw)
-        .ok_or_else(|| format!("unsupported on-chain phase '{}'", phase_raw))?;
-
-    let mut seated: Vec<(u32, String)> = value
-        .get("players")
-        .and_then(|v| v.as_array())
-        .ok_or("missing players")?
-        .iter()
-        .filter_map(|player| {
-            let address = player.get("address")?.as_str()?.to_string();
-            let seat = player
-                .get("seat_index")
-                .and_then(parse_u32_value)
-                .unwrap_or(0);
-            Some((seat, address))
-        })
+    let phase = map_onchain_phase_to_local(onchain)
+        .ok_or_else(|| format!("unsupported on-chain phase '{}'", onchain.phase))?;
+
+    let player_order: Vec<String> = seats_in_order(onchain)
+        .into_iter()
+        .map(|(_, address)| address)
         .collect();
-    seated.sort_by_key(|(seat, _)| *seat);
-    let player_order: Vec<String> = seated.into_iter().map(|(_, address)| address).collect();
 
     if player_order.len() < MIN_PLAYERS {
         return Err(format!(
@@ -164,38 +246,23 @@ fn build_session_from_onchain_state(
         ));
     }
 
-    let deck_root_raw = value
-        .get("deck_root")
-        .and_then(|v| v.as_str())
-        .unwrap_or_default()
-        .to_string();
-    let deck_root = if deck_root_raw.is_empty() {
+    let deck_root = if onchain.deck_root.is_empty() {
         String::new()
     } else {
-        normalize_field_value(&deck_root_raw)?
+        normalize_field_value(&onchain.deck_root)?
     };
 
     if phase != "waiting" && phase != "dealing" && deck_root.is_empty() {
         return Err("missing deck_root for active hand".to_string());
     }
 
-    let hand_commitments: Vec<String> = value
-        .get("hand_commitments")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|item| item.as_str())
-                .map(normalize_field_value)
-                .collect::<Result<Vec<_>, String>>()
-        })
-        .transpose()?
-        .unwrap_or_default();
-
-    let board_cards: Vec<u32> = value
-        .get("board_cards")
-        .and_then(|v| v.as_array())
-        .map(|arr| arr.iter().filter_map(parse_u32_value).collect())
-        .unwrap_or_default();
+    let hand_commitments = onchain
+        .hand_commitments
+        .iter()
+        .map(|c| normalize_field_value(c))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let board_cards = &onchain.board_cards;
     let board_count = board_cards.len();
 
     let mut hole_indices = Vec::with_capacity(player_order.len() * 2);
@@ -208,11 +275,7 @@ fn build_session_from_onchain_state(
         hole_indices.push(c2);
     }
 
-    let chain_dealt_indices: Vec<u32> = value
-        .get("dealt_indices")
-        .and_then(|v| v.as_array())
-        .map(|arr| arr.iter().filter_map(parse_u32_value).collect())
-        .unwrap_or_default();
+    let chain_dealt_indices = onchain.dealt_indices.clone();
 
     let board_indices = if chain_dealt_indices.is_empty() {
         let start = (player_order.len() * 2) as u32;
@@ -257,7 +320,7 @@ fn build_session_from_onchain_state(
         player_card_positions,
         board_indices,
         phase: phase.to_string(),
-        deal_session_id: "rehydrated-from-chain".to_string(),
+        deal_session_id: REHYDRATED_SESSION_ID.to_string(),
         deal_tx_hash: None,
         reveal_tx_hashes: HashMap::new(),
         reveal_session_ids: HashMap::new(),
@@ -266,6 +329,11 @@ fn build_session_from_onchain_state(
         showdown_session_id: None,
         showdown_result: None,
         proof_nonce: 0,
+        hand_number: onchain.hand_number,
+        hand_nonce: onchain.session_id,
+        contribution_commitments: Vec::new(),
+        revealed_contributions: Vec::new(),
+        proofs: Vec::new(),
     })
 }
 
@@ -1,5 +1,11 @@
+use mpc_auth::commitment::ContributionCommitment;
+use mpc_auth::vrf::VrfProof;
 use serde::{Deserialize, Serialize};
 
+use crate::events::{TableEvent, WaitingOn};
+use crate::mpc::RevealedContribution;
+use crate::soroban;
+
 #[derive(Deserialize)]
 pub struct DealRequest {
     pub players: Vec<String>,
@@ -40,6 +46,17 @@ pub struct PlayerActionRequest {
     pub amount: Option<i128>,
 }
 
+/// Body of a request the table contract rejected.
+#[derive(Serialize)]
+pub struct ContractErrorResponse {
+    /// Human-readable reason.
+    pub error: &'static str,
+    /// `PokerTableError` variant name, e.g. `NotYourTurn`.
+    pub name: &'static str,
+    /// The contract's `Error(Contract, #code)` code.
+    pub code: u32,
+}
+
 #[derive(Serialize)]
 pub struct PlayerActionResponse {
     pub status: String,
@@ -62,18 +79,233 @@ pub struct PlayerCardsResponse {
     pub salt2: String,
 }
 
+#[derive(Serialize)]
+pub struct ContributionAudit {
+    pub node_id: u32,
+    pub commitment: ContributionCommitment,
+    /// Whether the revealed contribution hashes to the signed commitment.
+    pub matches: bool,
+    /// Whether it is the VRF output for the hand; `None` if not VRF-derived.
+    pub vrf_verified: Option<bool>,
+    pub permutation: Vec<u32>,
+    pub salts: Vec<String>,
+    pub vrf: Option<VrfProof>,
+}
+
+#[derive(Serialize)]
+pub struct AuditContributionsResponse {
+    pub table_id: u32,
+    pub verified: bool,
+    pub nodes: Vec<ContributionAudit>,
+}
+
+/// A settled hand packaged for independent verification, signed with the
+/// committee key that submitted its proofs.
+///
+/// `signature` is Ed25519 over the SHA-256 of `bundle` serialized as compact
+/// JSON in field order, which is what `JSON.stringify` gives back for it.
+#[derive(Serialize)]
+pub struct HandBundleArchive {
+    pub bundle: HandBundle,
+    pub sha256: String,
+    /// The committee address; matches the table's on-chain `committee`.
+    pub signer: String,
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+pub struct HandBundle {
+    pub format: &'static str,
+    pub table_id: u32,
+    /// From the deal's `deal_committed` event; `None` when that is outside
+    /// the RPC's retention window, and so are the hand's events.
+    pub hand_number: Option<u32>,
+    pub contract: String,
+    pub network_passphrase: String,
+    /// Players in seat order.
+    pub players: Vec<String>,
+    pub deck_root: String,
+    pub hand_commitments: Vec<String>,
+    pub board: Vec<BundleStreet>,
+    pub showdown: Option<BundleShowdown>,
+    /// Proofs generated by the replica that served the hand; a hand resumed
+    /// by another replica only has the proofs generated after the handover.
+    pub proofs: Vec<BundleProof>,
+    pub contributions: BundleContributions,
+    /// The table's contract events for the hand, as the RPC returned them.
+    pub events: Vec<BundleEvent>,
+    pub logs: BundleLogRefs,
+    /// Unix seconds.
+    pub generated_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct BundleStreet {
+    pub street: String,
+    pub cards: Vec<u32>,
+    pub deck_indices: Vec<u32>,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BundleShowdown {
+    /// `None` when the proof was rejected and the hand settled by timeout.
+    pub winner: Option<String>,
+    pub winner_index: Option<u32>,
+    /// Each seat's hole cards, from the showdown proof's public inputs;
+    /// `None` for a seat that folded or mucked.
+    pub hole_cards: Vec<Option<[u32; 2]>>,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BundleProof {
+    pub label: String,
+    pub circuit: String,
+    pub session_id: String,
+    /// Hex SHA-256 of `proof`, as the contract publishes it in events.
+    pub sha256: String,
+    /// The proof as submitted on chain, hex, in the Soroban verifier layout.
+    pub proof: String,
+    pub public_inputs: Vec<String>,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BundleContributions {
+    pub commitments: Vec<ContributionCommitment>,
+    /// Present once a player has run the contribution audit.
+    pub revealed: Vec<RevealedContribution>,
+}
+
+#[derive(Serialize)]
+pub struct BundleEvent {
+    pub id: String,
+    pub name: String,
+    pub ledger: u32,
+    pub closed_at: String,
+    pub tx_hash: String,
+    /// Base64 XDR, as `getEvents` returns them.
+    pub topic: Vec<String>,
+    pub value: String,
+}
+
+/// Where to look in the coordinator and node logs for this hand.
+#[derive(Serialize)]
+pub struct BundleLogRefs {
+    pub replica_id: String,
+    /// Proof session IDs, which the coordinator and node logs refer to
+    /// proofs by.
+    pub proof_sessions: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+    pub status: &'static str,
+    /// Balances of the committee and local player accounts; `None` when
+    /// Soroban is not configured.
+    pub funding: Option<soroban::funding::FundingStatus>,
+}
+
 #[derive(Serialize)]
 pub struct CommitteeStatusResponse {
     pub nodes: usize,
     pub healthy: Vec<bool>,
+    /// Identity handshake result per node; `None` when keys are not pinned.
+    pub identity_verified: Vec<Option<bool>>,
+    /// TEE attestation result per node; `None` when not required.
+    pub attested: Vec<Option<bool>>,
     pub status: String,
 }
 
+#[derive(Serialize)]
+pub struct TableStatsResponse {
+    pub table_id: u32,
+    pub hands_played: u64,
+    pub hands_settled: u64,
+    pub showdowns: u64,
+    pub fold_wins: u64,
+    pub timeout_settlements: u64,
+    pub biggest_pot: i128,
+    pub total_pot_volume: i128,
+    pub players: Vec<PlayerStatsEntry>,
+    pub proof_latencies: Vec<ProofLatencyEntry>,
+}
+
+/// What the table is waiting on, for "waiting for ..." displays.
+#[derive(Serialize)]
+pub struct ReadyStateResponse {
+    pub table_id: u32,
+    pub phase: String,
+    pub hand_number: u32,
+    #[serde(flatten)]
+    pub waiting_on: WaitingOn,
+    /// Ledger from which a timeout can be claimed against whoever is awaited.
+    pub timeout_ledger: Option<u32>,
+    /// Latest ledger the event watcher has seen, to compare against
+    /// `timeout_ledger`.
+    pub latest_ledger: Option<u32>,
+    /// The table's most recent contract event since it was first queried.
+    pub last_event: Option<TableEvent>,
+}
+
+/// Button, blinds and turn, computed from on-chain state the way the
+/// contract assigns them. Between hands, the positions for the next hand.
+#[derive(Serialize)]
+pub struct TablePositionsResponse {
+    pub table_id: u32,
+    pub phase: String,
+    pub hand_number: u32,
+    /// Unset with fewer than two players seated.
+    pub dealer: Option<PositionSeat>,
+    /// Also unset when the small blind is dead.
+    pub small_blind: Option<PositionSeat>,
+    pub big_blind: Option<PositionSeat>,
+    /// Only set during a betting round.
+    pub next_to_act: Option<PositionSeat>,
+}
+
+#[derive(Serialize)]
+pub struct PositionSeat {
+    pub seat_index: u32,
+    pub address: String,
+}
+
+#[derive(Serialize)]
+pub struct PlayerStatsEntry {
+    pub address: String,
+    pub hands_dealt: u64,
+    pub vpip_hands: u64,
+    pub vpip_pct: f64,
+    pub pots_won: u64,
+    pub chips_won: i128,
+    pub biggest_pot_won: i128,
+}
+
+#[derive(Serialize)]
+pub struct ProofLatencyEntry {
+    pub circuit: String,
+    pub count: u64,
+    pub avg_ms: u64,
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct LeaderStatusResponse {
+    pub replica_id: String,
+    pub distributed: bool,
+    pub held_tables: Vec<u32>,
+}
+
 #[derive(Serialize)]
 pub struct ChainConfigResponse {
     pub rpc_url: String,
     pub network_passphrase: String,
     pub poker_table_contract: String,
+    /// Further contracts and the table IDs they hold; see `TableNamespace`.
+    pub table_namespaces: Vec<soroban::TableNamespace>,
 }
 
 #[derive(Deserialize)]
@@ -81,6 +313,11 @@ pub struct CreateTableRequest {
     pub max_players: Option<u32>,
     pub solo: Option<bool>,
     pub buy_in: Option<String>,
+    /// Makes the table private: hidden from the open-table list and only
+    /// joinable by clients presenting the code in `x-invite-code`.
+    pub invite_code: Option<String>,
+    /// Contract namespace to create the table in; the default one if unset.
+    pub namespace: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -88,6 +325,7 @@ pub struct CreateTableResponse {
     pub table_id: u32,
     pub max_players: u32,
     pub joined_wallets: usize,
+    pub private: bool,
 }
 
 #[derive(Serialize)]
@@ -98,6 +336,7 @@ pub struct OpenTablesResponse {
 #[derive(Serialize)]
 pub struct OpenTableInfo {
     pub table_id: u32,
+    pub namespace: String,
     pub phase: String,
     pub max_players: u32,
     pub joined_wallets: usize,
@@ -0,0 +1,448 @@
+//! What each table is waiting on, kept current from the contract's events.
+//!
+//! The poker-table contract publishes an event for every state change
+//! (`action_taken`, `phase_change`, `deal_committed`, `board_revealed`, ...),
+//! each with the table ID as its second topic. A watcher polls the RPC's
+//! `getEvents` for every contract served and, whenever a table someone has asked
+//! about emits one, re-reads that table and recomputes its [`ReadyState`].
+//! `/api/table/:id/ready-state` answers from that cache, and reads the chain
+//! directly for a table it has not seen yet or while the watcher is failing.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use poker_table_types::{GamePhase, TableState};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use stellar_xdr::curr::{Limits, ReadXdr, ScVal, WriteXdr};
+use tokio::sync::RwLock;
+
+use crate::{soroban, AppState};
+
+const DEFAULT_POLL_INTERVAL_MS: u64 = 2_000;
+/// Events per `getEvents` page.
+const PAGE_LIMIT: usize = 200;
+
+/// Who the table needs to hear from next.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "waiting_on", rename_all = "snake_case")]
+pub enum WaitingOn {
+    /// No hand in progress; a deal can be requested once two players sit.
+    NextHand { seated: usize },
+    /// A betting round: the seat to act.
+    Player { seat: u32, address: String },
+    /// The committee's deal or showdown proof.
+    Committee { proof: &'static str },
+    /// The committee's proof revealing the next street.
+    Reveal { street: &'static str },
+    /// The committee timed out; the hand is being refunded.
+    Dispute,
+    /// The admin paused the table; nothing moves until it resumes.
+    Admin,
+}
+
+/// The latest contract event seen for a table.
+#[derive(Clone, Debug, Serialize)]
+pub struct TableEvent {
+    pub name: String,
+    pub ledger: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReadyState {
+    pub phase: GamePhase,
+    pub hand_number: u32,
+    pub waiting_on: WaitingOn,
+    /// First ledger at which `claim_timeout` applies to whoever is being
+    /// waited on, by the phase's own timeout plus, for a player, what is
+    /// left of their time bank; `None` between hands, in a dispute or while
+    /// the table is paused.
+    pub timeout_ledger: Option<u32>,
+    pub last_event: Option<TableEvent>,
+}
+
+impl ReadyState {
+    pub fn from_table(table: &TableState, last_event: Option<TableEvent>) -> Self {
+        let waiting_on = match table.phase {
+            _ if table.paused => WaitingOn::Admin,
+            GamePhase::Waiting | GamePhase::Settlement => WaitingOn::NextHand {
+                seated: table.seated().count(),
+            },
+            GamePhase::Dealing => WaitingOn::Committee { proof: "deal" },
+            GamePhase::Showdown => WaitingOn::Committee { proof: "showdown" },
+            GamePhase::DealingFlop => WaitingOn::Reveal { street: "flop" },
+            GamePhase::DealingTurn => WaitingOn::Reveal { street: "turn" },
+            GamePhase::DealingRiver => WaitingOn::Reveal { street: "river" },
+            GamePhase::RunOut => WaitingOn::Reveal {
+                street: table.next_street(),
+            },
+            GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
+                WaitingOn::Player {
+                    seat: table.current_turn,
+                    address: table
+                        .player_to_act()
+                        .map(|p| p.address.clone())
+                        .unwrap_or_default(),
+                }
+            }
+            GamePhase::Dispute => WaitingOn::Dispute,
+        };
+        let time_bank = match waiting_on {
+            WaitingOn::Player { .. } => table.player_to_act().map_or(0, |p| p.time_bank_ledgers),
+            _ => 0,
+        };
+        let timeout_ledger = match waiting_on {
+            WaitingOn::Admin => None,
+            _ => table.config.timeout_ledgers(table.phase).map(|ledgers| {
+                table
+                    .last_action_ledger
+                    .saturating_add(ledgers)
+                    .saturating_add(time_bank)
+            }),
+        };
+        Self {
+            phase: table.phase,
+            hand_number: table.hand_number,
+            waiting_on,
+            timeout_ledger,
+            last_event,
+        }
+    }
+}
+
+/// Cached ready states plus the watcher's view of the chain.
+#[derive(Clone, Default)]
+pub struct ReadyTracker {
+    /// Tables someone has asked about, keyed by API table ID.
+    tables: Arc<RwLock<HashMap<u32, ReadyState>>>,
+    /// Latest ledger the RPC reported on the last poll.
+    latest_ledger: Arc<AtomicU32>,
+    /// Whether the last poll succeeded, i.e. the cache can be trusted.
+    live: Arc<AtomicBool>,
+}
+
+impl ReadyTracker {
+    /// The cached state, if the watcher is keeping it current.
+    pub async fn get(&self, table_id: u32) -> Option<ReadyState> {
+        if !self.live.load(Ordering::Relaxed) {
+            return None;
+        }
+        self.tables.read().await.get(&table_id).cloned()
+    }
+
+    pub async fn insert(&self, table_id: u32, ready: ReadyState) {
+        self.tables.write().await.insert(table_id, ready);
+    }
+
+    /// Stop tracking tables, e.g. once the reaper finds them idle.
+    pub async fn forget(&self, table_ids: &[u32]) {
+        let mut tables = self.tables.write().await;
+        for table_id in table_ids {
+            tables.remove(table_id);
+        }
+    }
+
+    pub fn latest_ledger(&self) -> Option<u32> {
+        Some(self.latest_ledger.load(Ordering::Relaxed)).filter(|l| *l > 0)
+    }
+}
+
+/// A contract event as `getEvents` returns it, topics and value still XDR.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawEvent {
+    pub id: String,
+    #[serde(default)]
+    pub contract_id: String,
+    pub ledger: u32,
+    #[serde(default)]
+    pub ledger_closed_at: String,
+    #[serde(default)]
+    pub tx_hash: String,
+    pub topic: Vec<String>,
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventPage {
+    events: Vec<RawEvent>,
+    latest_ledger: u32,
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LatestLedger {
+    sequence: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Health {
+    oldest_ledger: u32,
+}
+
+/// The event's name and table, from its `(Symbol, u32)` topics.
+pub(crate) fn decode_topics(event: &RawEvent) -> Option<(String, u32)> {
+    let decode = |raw: &String| ScVal::from_xdr_base64(raw, Limits::none()).ok();
+    let name = match decode(event.topic.first()?)? {
+        ScVal::Symbol(symbol) => symbol.to_utf8_string().ok()?,
+        _ => return None,
+    };
+    match decode(event.topic.get(1)?)? {
+        ScVal::U32(table_id) => Some((name, table_id)),
+        _ => None,
+    }
+}
+
+/// The few Soroban RPC methods the coordinator reads directly (events, and
+/// account balances for `soroban::funding`), over plain JSON-RPC.
+pub(crate) struct Rpc {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Rpc {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+
+    pub async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T, String> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let resp: serde_json::Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| format!("{} failed: {}", method, e))?
+            .json()
+            .await
+            .map_err(|e| format!("{} returned invalid JSON: {}", method, e))?;
+        if let Some(error) = resp.get("error") {
+            return Err(format!("{} failed: {}", method, error));
+        }
+        serde_json::from_value(resp["result"].clone())
+            .map_err(|e| format!("{} returned an unexpected result: {}", method, e))
+    }
+
+    async fn latest_ledger(&self) -> Result<u32, String> {
+        let latest: LatestLedger = self
+            .call("getLatestLedger", serde_json::Value::Null)
+            .await?;
+        Ok(latest.sequence)
+    }
+
+    /// Every event the contract published for `table_id` that the RPC still
+    /// retains, oldest first.
+    pub async fn table_events(
+        &self,
+        contract: &str,
+        table_id: u32,
+    ) -> Result<Vec<RawEvent>, String> {
+        let health: Health = self.call("getHealth", serde_json::Value::Null).await?;
+        let table_topic = ScVal::U32(table_id)
+            .to_xdr_base64(Limits::none())
+            .map_err(|e| format!("encoding table topic: {}", e))?;
+        let filters = json!([{
+            "type": "contract",
+            "contractIds": [contract],
+            "topics": [["*", table_topic]],
+        }]);
+
+        let mut events = Vec::new();
+        let mut params = json!({
+            "startLedger": health.oldest_ledger,
+            "filters": filters,
+            "pagination": { "limit": PAGE_LIMIT },
+        });
+        loop {
+            let page: EventPage = self.call("getEvents", params).await?;
+            let full = page.events.len() == PAGE_LIMIT;
+            let cursor = page
+                .cursor
+                .or_else(|| page.events.last().map(|e| e.id.clone()));
+            events.extend(page.events);
+            match cursor {
+                Some(cursor) if full => {
+                    params = json!({
+                        "filters": filters,
+                        "pagination": { "cursor": cursor, "limit": PAGE_LIMIT },
+                    });
+                }
+                _ => return Ok(events),
+            }
+        }
+    }
+}
+
+struct EventWatcher {
+    rpc: Rpc,
+    contracts: Vec<String>,
+    /// Where the next poll resumes: a `getEvents` cursor, or a start ledger
+    /// before the first page.
+    cursor: Option<String>,
+    start_ledger: u32,
+}
+
+impl EventWatcher {
+    /// Start from the current ledger; earlier events are covered by reading
+    /// the table when it is first asked about.
+    async fn reset(&mut self) -> Result<(), String> {
+        self.start_ledger = self.rpc.latest_ledger().await?;
+        self.cursor = None;
+        Ok(())
+    }
+
+    /// Events since the last poll, oldest first, and the latest ledger.
+    async fn poll(&mut self) -> Result<(Vec<RawEvent>, u32), String> {
+        // The RPC takes at most five contracts per filter.
+        let filters: Vec<serde_json::Value> = self
+            .contracts
+            .chunks(5)
+            .map(|ids| json!({ "type": "contract", "contractIds": ids }))
+            .collect();
+        let mut events = Vec::new();
+        loop {
+            let params = match &self.cursor {
+                Some(cursor) => json!({
+                    "filters": filters,
+                    "pagination": { "cursor": cursor, "limit": PAGE_LIMIT },
+                }),
+                None => json!({
+                    "startLedger": self.start_ledger,
+                    "filters": filters,
+                    "pagination": { "limit": PAGE_LIMIT },
+                }),
+            };
+            let page: EventPage = self.rpc.call("getEvents", params).await?;
+            let full = page.events.len() == PAGE_LIMIT;
+            match page
+                .cursor
+                .or_else(|| page.events.last().map(|e| e.id.clone()))
+            {
+                Some(cursor) => self.cursor = Some(cursor),
+                // RPCs without cursors on empty pages: nothing happened up
+                // to the latest ledger, so look from there next time.
+                None if self.cursor.is_none() => self.start_ledger = page.latest_ledger,
+                None => {}
+            }
+            events.extend(page.events);
+            if !full {
+                return Ok((events, page.latest_ledger));
+            }
+        }
+    }
+}
+
+/// Spawn the event watcher, if Soroban is configured.
+pub fn spawn_event_watcher(state: AppState) {
+    if !state.soroban_config.is_configured() {
+        return;
+    }
+    let interval_ms = std::env::var("EVENT_POLL_INTERVAL_MS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|ms| *ms > 0)
+        .unwrap_or(DEFAULT_POLL_INTERVAL_MS);
+    tracing::info!("Event watcher: interval={}ms", interval_ms);
+
+    let mut watcher = EventWatcher {
+        rpc: Rpc::new(&state.soroban_config.rpc_url),
+        contracts: std::iter::once(state.soroban_config.poker_table_contract.clone())
+            .chain(
+                state
+                    .soroban_config
+                    .table_namespaces
+                    .iter()
+                    .map(|ns| ns.contract.clone()),
+            )
+            .collect(),
+        cursor: None,
+        start_ledger: 0,
+    };
+    tokio::spawn(async move {
+        let tracker = &state.ready_states;
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+        loop {
+            interval.tick().await;
+            if watcher.start_ledger == 0 {
+                if let Err(e) = watcher.reset().await {
+                    tracing::warn!("event watcher: {}", e);
+                    continue;
+                }
+                // Events before the new start ledger were never seen, so
+                // anything cached may be stale.
+                tracker.tables.write().await.clear();
+            }
+            let (events, latest_ledger) = match watcher.poll().await {
+                Ok(polled) => polled,
+                Err(e) => {
+                    // Most likely the cursor fell out of the RPC's retention
+                    // window; until a poll succeeds, requests read the chain.
+                    tracing::warn!("event watcher: {}; restarting from the latest ledger", e);
+                    tracker.live.store(false, Ordering::Relaxed);
+                    watcher.start_ledger = 0;
+                    continue;
+                }
+            };
+
+            let mut latest_by_table: HashMap<(String, u32), TableEvent> = HashMap::new();
+            for event in &events {
+                if let Some((name, table_id)) = decode_topics(event) {
+                    latest_by_table.insert(
+                        (event.contract_id.clone(), table_id),
+                        TableEvent {
+                            name,
+                            ledger: event.ledger,
+                        },
+                    );
+                }
+            }
+            refresh_tables(&state, latest_by_table).await;
+            tracker
+                .latest_ledger
+                .store(latest_ledger, Ordering::Relaxed);
+            tracker.live.store(true, Ordering::Relaxed);
+        }
+    });
+}
+
+/// Re-read the tracked tables that emitted events, keyed by contract and
+/// on-chain table ID.
+async fn refresh_tables(state: &AppState, latest_by_table: HashMap<(String, u32), TableEvent>) {
+    let config = &state.soroban_config;
+    let tracker = &state.ready_states;
+    let tracked: Vec<u32> = tracker.tables.read().await.keys().copied().collect();
+    for table_id in tracked {
+        let key = (
+            config.table_contract(table_id).to_string(),
+            soroban::resolve_onchain_table_id(config, table_id),
+        );
+        let Some(event) = latest_by_table.get(&key) else {
+            continue;
+        };
+        match soroban::get_table(config, table_id).await {
+            Ok(table) => {
+                let ready = ReadyState::from_table(&table, Some(event.clone()));
+                tracker.insert(table_id, ready).await;
+            }
+            Err(e) => {
+                // Drop it so the next request reads the chain itself.
+                tracing::warn!("event watcher: failed to read table {}: {}", table_id, e);
+                tracker.forget(&[table_id]).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Per-table leader election for running multiple coordinator replicas.
+//!
+//! Only the replica holding a table's lease drives MPC sessions and chain
+//! submission for that table. Leases live in Redis (`SET NX PX` plus
+//! owner-checked renew/release scripts) so replicas behind the same load
+//! balancer agree on ownership; a replica that loses or never acquires a
+//! lease answers with 421 Misdirected Request and the caller retries
+//! elsewhere. A newly elected leader rebuilds table sessions from on-chain
+//! state via `ensure_session_exists`.
+//!
+//! Without `LEADER_REDIS_URL` the coordinator runs single-replica and every
+//! lease is granted locally.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redis::aio::ConnectionManager;
+use tokio::sync::RwLock;
+
+const DEFAULT_LEASE_TTL_MS: u64 = 15_000;
+const LEASE_KEY_PREFIX: &str = "stellar-poker:coordinator:table-lease:";
+
+/// Renew the lease only if this replica still owns it.
+const RENEW_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Release the lease only if this replica still owns it.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+#[derive(Clone)]
+enum LeaseBackend {
+    /// Single replica: all leases are granted.
+    Local,
+    Redis(Box<ConnectionManager>),
+}
+
+/// Outcome of a lease acquisition attempt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaseStatus {
+    /// Lease was just acquired; any cached table session may be stale.
+    Acquired,
+    /// Lease was already held by this replica and has been renewed.
+    Renewed,
+    /// Another replica holds the lease.
+    HeldElsewhere,
+}
+
+#[derive(Clone)]
+pub struct LeaderElection {
+    pub replica_id: String,
+    lease_ttl_ms: u64,
+    backend: LeaseBackend,
+    /// Table leases held by this replica, keyed by table ID -> expiry (unix ms).
+    held: Arc<RwLock<HashMap<u32, u64>>>,
+}
+
+impl LeaderElection {
+    pub async fn from_env() -> Result<Self, String> {
+        let replica_id = std::env::var("COORDINATOR_REPLICA_ID")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .unwrap_or_else(|| format!("coordinator-{}", uuid::Uuid::new_v4()));
+        let lease_ttl_ms = std::env::var("LEADER_LEASE_TTL_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|ttl| *ttl >= 1_000)
+            .unwrap_or(DEFAULT_LEASE_TTL_MS);
+
+        let backend = match std::env::var("LEADER_REDIS_URL") {
+            Ok(url) if !url.trim().is_empty() => {
+                let client = redis::Client::open(url.as_str())
+                    .map_err(|e| format!("invalid LEADER_REDIS_URL: {}", e))?;
+                let manager = ConnectionManager::new(client)
+                    .await
+                    .map_err(|e| format!("failed to connect to leader redis: {}", e))?;
+                LeaseBackend::Redis(Box::new(manager))
+            }
+            _ => LeaseBackend::Local,
+        };
+
+        Ok(Self {
+            replica_id,
+            lease_ttl_ms,
+            backend,
+            held: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    pub fn is_distributed(&self) -> bool {
+        matches!(self.backend, LeaseBackend::Redis(_))
+    }
+
+    /// Acquire or renew the lease for `table_id`.
+    pub async fn acquire_table(&self, table_id: u32) -> Result<LeaseStatus, String> {
+        let now = now_ms();
+        let had_lease = self
+            .held
+            .read()
+            .await
+            .get(&table_id)
+            .is_some_and(|expiry| *expiry > now);
+
+        let granted = match &self.backend {
+            LeaseBackend::Local => true,
+            LeaseBackend::Redis(manager) => {
+                let mut conn = ConnectionManager::clone(manager);
+                let key = lease_key(table_id);
+                let renewed: i64 = if had_lease {
+                    redis::Script::new(RENEW_SCRIPT)
+                        .key(&key)
+                        .arg(&self.replica_id)
+                        .arg(self.lease_ttl_ms)
+                        .invoke_async(&mut conn)
+                        .await
+                        .map_err(|e| format!("lease renew failed: {}", e))?
+                } else {
+                    0
+                };
+                if renewed == 1 {
+                    true
+                } else {
+                    let set: Option<String> = redis::cmd("SET")
+                        .arg(&key)
+                        .arg(&self.replica_id)
+                        .arg("NX")
+                        .arg("PX")
+                        .arg(self.lease_ttl_ms)
+                        .query_async(&mut conn)
+                        .await
+                        .map_err(|e| format!("lease acquire failed: {}", e))?;
+                    if set.is_some() {
+                        true
+                    } else {
+                        // A previous incarnation of this replica may still own the key.
+                        let owner: Option<String> = redis::cmd("GET")
+                            .arg(&key)
+                            .query_async(&mut conn)
+                            .await
+                            .map_err(|e| format!("lease lookup failed: {}", e))?;
+                        owner.as_deref() == Some(self.replica_id.as_str())
+                    }
+                }
+            }
+        };
+
+        let mut held = self.held.write().await;
+        if !granted {
+            held.remove(&table_id);
+            return Ok(LeaseStatus::HeldElsewhere);
+        }
+        held.insert(table_id, now + self.lease_ttl_ms);
+        Ok(if had_lease {
+            LeaseStatus::Renewed
+        } else {
+            LeaseStatus::Acquired
+        })
+    }
+
+    /// Renew every lease this replica holds. Returns the tables whose lease was lost.
+    pub async fn renew_all(&self) -> Vec<u32> {
+        let table_ids: Vec<u32> = self.held.read().await.keys().copied().collect();
+        let mut lost = Vec::new();
+        for table_id in table_ids {
+            match self.acquire_table(table_id).await {
+                Ok(LeaseStatus::HeldElsewhere) => lost.push(table_id),
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("Lease renewal for table {} failed: {}", table_id, e);
+                    let expired = self
+                        .held
+                        .read()
+                        .await
+                        .get(&table_id)
+                        .is_some_and(|expiry| *expiry <= now_ms());
+                    if expired {
+                        self.held.write().await.remove(&table_id);
+                        lost.push(table_id);
+                    }
+                }
+            }
+        }
+        lost
+    }
+
+    /// Release the lease for a single table, e.g. once its session is reaped.
+    pub async fn release_table(&self, table_id: u32) {
+        if self.held.write().await.remove(&table_id).is_none() {
+            return;
+        }
+        let LeaseBackend::Redis(manager) = &self.backend else {
+            return;
+        };
+        let mut conn = ConnectionManager::clone(manager);
+        let result: Result<i64, _> = redis::Script::new(RELEASE_SCRIPT)
+            .key(lease_key(table_id))
+            .arg(&self.replica_id)
+            .invoke_async(&mut conn)
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Lease release for table {} failed: {}", table_id, e);
+        }
+    }
+
+    /// Release every lease this replica holds so peers can take over immediately.
+    pub async fn release_all(&self) {
+        let table_ids: Vec<u32> = self.held.write().await.drain().map(|(id, _)| id).collect();
+        let LeaseBackend::Redis(manager) = &self.backend else {
+            return;
+        };
+        let mut conn = ConnectionManager::clone(manager);
+        for table_id in table_ids {
+            let result: Result<i64, _> = redis::Script::new(RELEASE_SCRIPT)
+                .key(lease_key(table_id))
+                .arg(&self.replica_id)
+                .invoke_async(&mut conn)
+                .await;
+            if let Err(e) = result {
+                tracing::warn!("Lease release for table {} failed: {}", table_id, e);
+            }
+        }
+    }
+
+    pub async fn held_tables(&self) -> Vec<u32> {
+        let now = now_ms();
+        let mut ids: Vec<u32> = self
+            .held
+            .read()
+            .await
+            .iter()
+            .filter(|(_, expiry)| **expiry > now)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn renew_interval(&self) -> Duration {
+        Duration::from_millis(self.lease_ttl_ms / 3)
+    }
+}
+
+fn lease_key(table_id: u32) -> String {
+    format!("{}{}", LEASE_KEY_PREFIX, table_id)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
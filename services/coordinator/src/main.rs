@@ -15,16 +15,21 @@
 
 use axum::{
     routing::{get, post},
-    Router,
+    Json, Router,
 };
+use mpc_auth::commitment::ContributionCommitment;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
 
 mod api;
+mod events;
+mod leader;
 mod mpc;
+mod pipeline;
 mod soroban;
+mod stats;
 
 #[derive(Clone)]
 struct AppState {
@@ -34,6 +39,16 @@ struct AppState {
     soroban_config: soroban::SorobanConfig,
     auth_state: Arc<RwLock<AuthState>>,
     rate_limit_state: Arc<RwLock<RateLimitState>>,
+    leader: leader::LeaderElection,
+    table_stats: Arc<RwLock<HashMap<u32, stats::TableStats>>>,
+    /// Last API activity per table (unix secs), used by the idle-session reaper.
+    table_activity: Arc<RwLock<HashMap<u32, u64>>>,
+    /// Private tables: table ID -> SHA-256 of the invite code.
+    invite_codes: Arc<RwLock<HashMap<u32, [u8; 32]>>>,
+    /// What each watched table is waiting on, kept current from chain events.
+    ready_states: events::ReadyTracker,
+    /// Reveal work started ahead of `request-reveal`.
+    reveal_pipeline: pipeline::RevealPipeline,
 }
 
 #[derive(Clone)]
@@ -85,11 +100,32 @@ struct TableSession {
     showdown_result: Option<(String, u32)>,
     /// Monotonic nonce for unique proof session IDs.
     proof_nonce: u64,
+    /// Hand number the deal contributions were requested for.
+    hand_number: u32,
+    /// On-chain nonce of the hand, which every proof for it commits to.
+    hand_nonce: u32,
+    /// Node-signed commitments to this hand's deal contributions, in node order.
+    contribution_commitments: Vec<ContributionCommitment>,
+    /// Contributions disclosed by a post-hand audit, cached for repeat requests.
+    revealed_contributions: Vec<mpc::RevealedContribution>,
+    /// Proofs this replica generated for the hand, in order, for the audit bundle.
+    proofs: Vec<HandProof>,
+}
+
+#[derive(Clone, Debug)]
+struct HandProof {
+    /// "deal", "reveal-flop", "reveal-turn", "reveal-river" or "showdown".
+    label: String,
+    circuit: &'static str,
+    proof: mpc::MpcProofResult,
+    tx_hash: Option<String>,
 }
 
 #[derive(Clone, Debug, Default)]
 struct AuthState {
     last_nonce_by_address: HashMap<String, u64>,
+    /// When each address last presented a nonce (unix secs).
+    nonce_seen_at: HashMap<String, u64>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -123,6 +159,68 @@ async fn main() {
     } else {
         tracing::warn!("Soroban not configured — on-chain submission disabled");
     }
+    soroban::funding::init(&soroban_config).expect("invalid funding config");
+
+    if mpc::init_node_auth().expect("invalid MPC_AUTH_SECRET") {
+        tracing::info!("MPC node requests are HMAC-signed");
+    } else {
+        tracing::warn!("MPC_AUTH_SECRET not set — node requests are unauthenticated");
+    }
+    match mpc::init_node_identities(&soroban_config, mpc_config.node_endpoints.len())
+        .await
+        .expect("failed to load MPC node identity keys")
+    {
+        Some(source) => {
+            tracing::info!("MPC node identity keys pinned from {}", source);
+            let verified = mpc::verify_node_identities(&mpc_config.node_endpoints).await;
+            tracing::info!("MPC node identity handshake: {:?}", verified);
+        }
+        None => tracing::warn!(
+            "Node identity keys not pinned — set NODE_SIGNING_PUBLIC_KEYS or COMMITTEE_REGISTRY_CONTRACT and MPC_NODE_ADDRESSES"
+        ),
+    }
+    let threshold = mpc::init_committee_threshold(&soroban_config, mpc_config.node_endpoints.len())
+        .await
+        .expect("failed to load committee threshold");
+    tracing::info!(
+        "MPC requests finish once {} of {} nodes answer",
+        threshold,
+        mpc_config.node_endpoints.len()
+    );
+    match mpc::init_tee_attestation(&soroban_config, mpc_config.node_endpoints.len())
+        .await
+        .expect("failed to load TEE attestation policy")
+    {
+        Some(source) => {
+            tracing::info!("TEE attestation required: measurements from {}", source);
+            let attested = mpc::attestation_status(&mpc_config.node_endpoints).await;
+            tracing::info!("MPC node attestation: {:?}", attested);
+        }
+        None => tracing::info!("TEE attestation not required"),
+    }
+    match mpc::init_perm_lookup_auth().expect("invalid PERM_LOOKUP_SIGNING_KEY") {
+        Some(public_key) => {
+            tracing::info!(
+                "Perm-lookup authorization enabled: public_key={}",
+                public_key
+            )
+        }
+        None => {
+            tracing::warn!("PERM_LOOKUP_SIGNING_KEY not set — perm-lookups are unauthorized")
+        }
+    }
+
+    let leader = leader::LeaderElection::from_env()
+        .await
+        .expect("failed to initialize leader election");
+    if leader.is_distributed() {
+        tracing::info!("Leader election enabled: replica_id={}", leader.replica_id);
+    }
+
+    let reveal_pipeline = pipeline::RevealPipeline::from_env().expect("invalid REVEAL_PIPELINE");
+    if reveal_pipeline.mode() != pipeline::PipelineMode::Off {
+        tracing::info!("Reveal pipelining: {:?}", reveal_pipeline.mode());
+    }
 
     let state = AppState {
         tables: Arc::new(RwLock::new(HashMap::new())),
@@ -131,8 +229,19 @@ async fn main() {
         soroban_config,
         auth_state: Arc::new(RwLock::new(AuthState::default())),
         rate_limit_state: Arc::new(RwLock::new(RateLimitState::default())),
+        leader,
+        table_stats: Arc::new(RwLock::new(HashMap::new())),
+        table_activity: Arc::new(RwLock::new(HashMap::new())),
+        invite_codes: Arc::new(RwLock::new(HashMap::new())),
+        ready_states: events::ReadyTracker::default(),
+        reveal_pipeline,
     };
 
+    spawn_lease_renewal(state.clone());
+    api::spawn_session_reaper(state.clone());
+    events::spawn_event_watcher(state.clone());
+    let leader = state.leader.clone();
+
     let app = Router::new()
         .route("/api/health", get(health))
         .route("/api/tables/create", post(api::create_table))
@@ -157,8 +266,26 @@ async fn main() {
             "/api/table/:table_id/player/:address/cards",
             get(api::get_player_cards),
         )
+        .route(
+            "/api/table/:table_id/audit-contributions",
+            post(api::audit_contributions),
+        )
         .route("/api/table/:table_id/state", get(api::get_table_state))
+        .route(
+            "/api/table/:table_id/hand-bundle",
+            get(api::get_hand_bundle),
+        )
+        .route("/api/table/:table_id/stats", get(api::get_table_stats))
+        .route(
+            "/api/table/:table_id/positions",
+            get(api::get_table_positions),
+        )
+        .route(
+            "/api/table/:table_id/ready-state",
+            get(api::get_ready_state),
+        )
         .route("/api/committee/status", get(api::committee_status))
+        .route("/api/leader", get(api::leader_status))
         .layer(CorsLayer::permissive())
         .with_state(state);
 
@@ -166,9 +293,65 @@ async fn main() {
     tracing::info!("Coordinator listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .unwrap();
+
+    // Hand tables to peer replicas without waiting for lease expiry.
+    leader.release_all().await;
+}
+
+/// Keep held table leases alive and drop cached sessions for tables whose
+/// lease moved to another replica.
+fn spawn_lease_renewal(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(state.leader.renew_interval());
+        loop {
+            interval.tick().await;
+            let lost = state.leader.renew_all().await;
+            if lost.is_empty() {
+                continue;
+            }
+            let mut tables = state.tables.write().await;
+            for table_id in lost {
+                tracing::warn!(
+                    "replica {} lost lease for table {}",
+                    state.leader.replica_id,
+                    table_id
+                );
+                tables.remove(&table_id);
+            }
+        }
+    });
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        if let Ok(mut sig) =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        {
+            sig.recv().await;
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("Shutdown signal received, releasing table leases");
 }
 
-async fn health() -> &'static str {
-    "ok"
+async fn health() -> Json<api::HealthResponse> {
+    Json(api::HealthResponse {
+        status: "ok",
+        funding: soroban::funding::status(),
+    })
 }
@@ -4,9 +4,43 @@
 //! - Coordinator never generates or stores plaintext deck/salts.
 //! - Every MPC node prepares and dispatches only its own private contribution.
 //! - Nodes merge all source-party share fragments locally before proving.
+//!
+//! With `REQUIRE_TEE_ATTESTATION` set, every node must present a TEE quote
+//! bound to its pinned identity key and measuring a registered image before
+//! it is asked to prepare shares (see `mpc_auth::attestation`).
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 
 use base64::Engine;
+use ed25519_dalek::VerifyingKey;
+use mpc_auth::attestation::{parse_measurement_hash, Attestation};
+use mpc_auth::commitment::ContributionCommitment;
+use mpc_auth::identity::IdentityProof;
+use mpc_auth::lookup::LookupAuthorizer;
+use mpc_auth::vrf::VrfProof;
+use mpc_auth::RequestSigner;
+use poker_table_types::TableState;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::soroban;
+
+static NODE_SIGNER: OnceLock<Option<RequestSigner>> = OnceLock::new();
+static LOOKUP_AUTHORIZER: OnceLock<Option<LookupAuthorizer>> = OnceLock::new();
+static NODE_IDENTITY_KEYS: OnceLock<Option<Vec<VerifyingKey>>> = OnceLock::new();
+static TEE_POLICY: OnceLock<Option<TeePolicy>> = OnceLock::new();
+static COMMITTEE_THRESHOLD: OnceLock<usize> = OnceLock::new();
+
+/// What a node's attestation has to satisfy in TEE mode.
+struct TeePolicy {
+    /// Accepted measurement hashes, per node.
+    allowed: Vec<Vec<[u8; 32]>>,
+    /// `TEE_QUOTE_VERIFY_CMD`: checks a quote file's vendor signature chain.
+    verify_cmd: Option<String>,
+    /// Quote digest per node that already passed `verify_cmd`.
+    verified_quotes: Mutex<HashMap<usize, [u8; 32]>>,
+}
 
 /// Result from MPC proof generation.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -19,6 +53,19 @@ pub struct MpcProofResult {
 #[derive(Clone, Debug)]
 pub struct PreparedShareSets {
     pub share_set_ids: Vec<String>,
+    /// Signed contribution commitments, in node order (deal only).
+    pub commitments: Vec<Option<ContributionCommitment>>,
+}
+
+/// A node's contribution disclosed after the hand.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RevealedContribution {
+    pub table_id: u32,
+    pub node_id: u32,
+    pub permutation: Vec<u32>,
+    pub salts: Vec<String>,
+    #[serde(default)]
+    pub vrf: Option<VrfProof>,
 }
 
 #[derive(Deserialize)]
@@ -26,6 +73,32 @@ struct NodeStatusResponse {
     #[allow(dead_code)]
     session_id: String,
     status: String,
+    /// Absent on nodes that predate stage reporting.
+    #[serde(default)]
+    stage: Option<String>,
+    #[serde(default)]
+    stage_started_at_ms: Option<u64>,
+    #[serde(default)]
+    server_time_ms: Option<u64>,
+    /// Source parties whose fragments the node holds; absent on older nodes.
+    #[serde(default)]
+    fragments_received: Option<Vec<u32>>,
+}
+
+impl NodeStatusResponse {
+    /// How long the node has been in its current stage, by the node's clock.
+    fn stage_age_secs(&self) -> Option<u64> {
+        let age_ms = self
+            .server_time_ms?
+            .saturating_sub(self.stage_started_at_ms?);
+        Some(age_ms / 1000)
+    }
+}
+
+#[derive(Deserialize)]
+struct NodeHealthResponse {
+    #[serde(default)]
+    attestation: Option<Attestation>,
 }
 
 #[derive(Deserialize)]
@@ -40,6 +113,458 @@ struct NodeProofResponse {
 #[derive(Deserialize)]
 struct NodePreparedSharesResponse {
     share_set_id: String,
+    #[serde(default)]
+    commitment: Option<ContributionCommitment>,
+}
+
+/// Load the shared MPC auth secret used to sign every request to the nodes.
+/// Returns whether signing is enabled.
+pub fn init_node_auth() -> Result<bool, String> {
+    let signer = mpc_auth::secret_from_env()?.map(|s| RequestSigner::new("coordinator", s));
+    let enabled = signer.is_some();
+    NODE_SIGNER
+        .set(signer)
+        .map_err(|_| "node auth already initialized".to_string())?;
+    Ok(enabled)
+}
+
+/// Load the key used to authorize node perm-lookups (`PERM_LOOKUP_SIGNING_KEY`).
+/// Returns the hex public key to configure on the nodes, if enabled.
+pub fn init_perm_lookup_auth() -> Result<Option<String>, String> {
+    let authorizer = mpc_auth::lookup::signing_key_from_env()?;
+    let public_key = authorizer.as_ref().map(LookupAuthorizer::public_key_hex);
+    LOOKUP_AUTHORIZER
+        .set(authorizer)
+        .map_err(|_| "perm-lookup auth already initialized".to_string())?;
+    Ok(public_key)
+}
+
+/// Pin the nodes' identity keys, from `NODE_SIGNING_PUBLIC_KEYS` or else the
+/// committee registry. Returns where the keys came from, if pinned.
+pub async fn init_node_identities(
+    soroban_config: &soroban::SorobanConfig,
+    num_nodes: usize,
+) -> Result<Option<&'static str>, String> {
+    let (keys, source) = match mpc_auth::commitment::registered_keys_from_env()? {
+        Some(keys) => (Some(keys), "NODE_SIGNING_PUBLIC_KEYS"),
+        None => match soroban::get_node_identity_keys(soroban_config).await? {
+            Some(hex_keys) => (
+                Some(
+                    hex_keys
+                        .iter()
+                        .map(|k| mpc_auth::identity::parse_identity_key(k))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+                "committee registry",
+            ),
+            None => (None, ""),
+        },
+    };
+    if let Some(keys) = &keys {
+        if keys.len() != num_nodes {
+            return Err(format!(
+                "{} has {} identity keys for {} nodes",
+                source,
+                keys.len(),
+                num_nodes
+            ));
+        }
+    }
+    let pinned = keys.is_some().then_some(source);
+    NODE_IDENTITY_KEYS
+        .set(keys)
+        .map_err(|_| "node identity keys already initialized".to_string())?;
+    Ok(pinned)
+}
+
+/// Take the committee threshold from the registry's current epoch, or else
+/// require every node. Each round of node requests finishes once this many
+/// nodes have answered, so an offline member does not stall the hand.
+pub async fn init_committee_threshold(
+    soroban_config: &soroban::SorobanConfig,
+    num_nodes: usize,
+) -> Result<usize, String> {
+    let threshold = match soroban::get_committee_threshold(soroban_config).await? {
+        Some(t) => t as usize,
+        None => num_nodes,
+    };
+    if threshold == 0 || threshold > num_nodes {
+        return Err(format!(
+            "committee threshold {} out of range for {} nodes",
+            threshold, num_nodes
+        ));
+    }
+    COMMITTEE_THRESHOLD
+        .set(threshold)
+        .map_err(|_| "committee threshold already initialized".to_string())?;
+    Ok(threshold)
+}
+
+/// Nodes that must answer a request round; every node unless a threshold is set.
+fn quorum(num_nodes: usize) -> usize {
+    COMMITTEE_THRESHOLD
+        .get()
+        .copied()
+        .unwrap_or(num_nodes)
+        .min(num_nodes)
+}
+
+/// Await per-node tasks until `quorum` of them succeed, and fail once too
+/// many have failed for that. Failures short of that are logged; tasks still
+/// running are left to finish on their own.
+async fn collect_quorum<T>(
+    handles: Vec<tokio::task::JoinHandle<Result<T, String>>>,
+    quorum: usize,
+    operation_name: &str,
+) -> Result<Vec<T>, String> {
+    use futures::StreamExt;
+
+    let total = handles.len();
+    let mut pending: futures::stream::FuturesUnordered<_> = handles.into_iter().collect();
+    let mut done = Vec::with_capacity(quorum);
+    let mut failures = 0;
+    while done.len() < quorum {
+        let Some(joined) = pending.next().await else {
+            break;
+        };
+        match joined
+            .map_err(|e| format!("{} join error: {}", operation_name, e))
+            .and_then(|r| r)
+        {
+            Ok(value) => done.push(value),
+            Err(e) => {
+                failures += 1;
+                if total - failures < quorum {
+                    return Err(e);
+                }
+                tracing::warn!(
+                    "{} failed on a node, {} of {} still needed: {}",
+                    operation_name,
+                    quorum,
+                    total,
+                    e
+                );
+            }
+        }
+    }
+    if done.len() < quorum {
+        return Err(format!(
+            "{}: {} of {} nodes answered, {} needed",
+            operation_name,
+            done.len(),
+            total,
+            quorum
+        ));
+    }
+    Ok(done)
+}
+
+/// Challenge each node to prove it holds its pinned identity key.
+/// Entries are `None` when keys are not pinned.
+pub async fn verify_node_identities(node_endpoints: &[String]) -> Vec<Option<bool>> {
+    let Some(keys) = NODE_IDENTITY_KEYS.get().and_then(Option::as_ref) else {
+        return vec![None; node_endpoints.len()];
+    };
+    let client = reqwest::Client::new();
+    let mut results = Vec::with_capacity(node_endpoints.len());
+    for (idx, endpoint) in node_endpoints.iter().enumerate() {
+        let challenge = uuid::Uuid::new_v4().to_string();
+        let url = format!("{}/identity/challenge", endpoint);
+        let verified = match signed_post(
+            &client,
+            &url,
+            &serde_json::json!({ "challenge": challenge }),
+        )
+        .send()
+        .await
+        {
+            Ok(resp) if resp.status().is_success() => match resp.json::<IdentityProof>().await {
+                Ok(proof) => match proof.verify(&keys[idx], idx as u32, &challenge) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::error!("Node {} identity check failed: {}", idx, e);
+                        false
+                    }
+                },
+                Err(_) => false,
+            },
+            _ => false,
+        };
+        results.push(verified);
+    }
+    results.into_iter().map(Some).collect()
+}
+
+fn tee_attestation_required() -> bool {
+    match std::env::var("REQUIRE_TEE_ATTESTATION") {
+        Ok(value) => matches!(
+            value.to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ),
+        Err(_) => false,
+    }
+}
+
+/// Load the TEE attestation policy when `REQUIRE_TEE_ATTESTATION` is set:
+/// measurement hashes from `TEE_MEASUREMENT_HASHES` (accepted for every
+/// node) or else each node's hash in the committee registry. Needs pinned
+/// identity keys, which quotes are bound to. Returns where the hashes came
+/// from, if required.
+pub async fn init_tee_attestation(
+    soroban_config: &soroban::SorobanConfig,
+    num_nodes: usize,
+) -> Result<Option<&'static str>, String> {
+    if !tee_attestation_required() {
+        TEE_POLICY
+            .set(None)
+            .map_err(|_| "TEE attestation already initialized".to_string())?;
+        return Ok(None);
+    }
+    if NODE_IDENTITY_KEYS.get().and_then(Option::as_ref).is_none() {
+        return Err("REQUIRE_TEE_ATTESTATION needs pinned node identity keys".to_string());
+    }
+
+    let env_hashes = std::env::var("TEE_MEASUREMENT_HASHES")
+        .ok()
+        .filter(|raw| !raw.trim().is_empty());
+    let (allowed, source) = match env_hashes {
+        Some(raw) => {
+            let hashes = raw
+                .split(',')
+                .map(parse_measurement_hash)
+                .collect::<Result<Vec<_>, _>>()?;
+            (vec![hashes; num_nodes], "TEE_MEASUREMENT_HASHES")
+        }
+        None => {
+            let hashes = soroban::get_node_attestation_hashes(soroban_config)
+                .await?
+                .ok_or_else(|| {
+                    "REQUIRE_TEE_ATTESTATION needs TEE_MEASUREMENT_HASHES or the committee registry"
+                        .to_string()
+                })?;
+            if hashes.len() != num_nodes {
+                return Err(format!(
+                    "committee registry has {} attestation hashes for {} nodes",
+                    hashes.len(),
+                    num_nodes
+                ));
+            }
+            (
+                hashes
+                    .iter()
+                    .map(|h| parse_measurement_hash(h).map(|h| vec![h]))
+                    .collect::<Result<Vec<_>, _>>()?,
+                "committee registry",
+            )
+        }
+    };
+    let verify_cmd = std::env::var("TEE_QUOTE_VERIFY_CMD")
+        .ok()
+        .filter(|cmd| !cmd.trim().is_empty());
+    if verify_cmd.is_none() {
+        tracing::warn!(
+            "TEE_QUOTE_VERIFY_CMD not set — quote signatures are not checked against the vendor"
+        );
+    }
+    TEE_POLICY
+        .set(Some(TeePolicy {
+            allowed,
+            verify_cmd,
+            verified_quotes: Mutex::new(HashMap::new()),
+        }))
+        .map_err(|_| "TEE attestation already initialized".to_string())?;
+    Ok(Some(source))
+}
+
+/// Fetch node `idx`'s attestation from `/health` and check it against the
+/// policy.
+async fn attest_node(
+    client: &reqwest::Client,
+    policy: &TeePolicy,
+    idx: usize,
+    endpoint: &str,
+) -> Result<(), String> {
+    let key = NODE_IDENTITY_KEYS
+        .get()
+        .and_then(Option::as_ref)
+        .and_then(|keys| keys.get(idx))
+        .ok_or_else(|| format!("node {} has no pinned identity key", idx))?;
+    // `/health` carries the quote even while it reports the node unhealthy.
+    let health: NodeHealthResponse = client
+        .get(format!("{}/health", endpoint))
+        .send()
+        .await
+        .map_err(|e| format!("failed to reach node {}: {}", idx, e))?
+        .json()
+        .await
+        .map_err(|e| format!("failed to parse node {} health: {}", idx, e))?;
+    let attestation = health
+        .attestation
+        .ok_or_else(|| format!("node {} is not running in TEE mode", idx))?;
+    attestation.verify(idx as u32, key, &policy.allowed[idx])?;
+
+    let Some(cmd) = &policy.verify_cmd else {
+        return Ok(());
+    };
+    let quote = attestation.quote_bytes()?;
+    let digest: [u8; 32] = Sha256::digest(&quote).into();
+    let cached = policy
+        .verified_quotes
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&idx)
+        == Some(&digest);
+    if cached {
+        return Ok(());
+    }
+
+    let path =
+        std::env::temp_dir().join(format!("node-{}-quote-{}.bin", idx, uuid::Uuid::new_v4()));
+    tokio::fs::write(&path, &quote)
+        .await
+        .map_err(|e| format!("failed to write node {} quote: {}", idx, e))?;
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", cmd))
+        .arg("sh")
+        .arg(&path)
+        .status()
+        .await;
+    let _ = tokio::fs::remove_file(&path).await;
+    match status {
+        Ok(status) if status.success() => {
+            policy
+                .verified_quotes
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(idx, digest);
+            Ok(())
+        }
+        Ok(status) => Err(format!(
+            "node {} quote failed TEE_QUOTE_VERIFY_CMD ({})",
+            idx, status
+        )),
+        Err(e) => Err(format!("failed to run TEE_QUOTE_VERIFY_CMD: {}", e)),
+    }
+}
+
+/// Check every node's attestation; nodes that fail are not used for share
+/// preparation. Returns which nodes passed, and fails when fewer than the
+/// committee threshold did. Every node passes unless
+/// `REQUIRE_TEE_ATTESTATION` is set.
+pub async fn attest_nodes(node_endpoints: &[String]) -> Result<Vec<bool>, String> {
+    let Some(policy) = TEE_POLICY.get().and_then(Option::as_ref) else {
+        return Ok(vec![true; node_endpoints.len()]);
+    };
+    let client = reqwest::Client::new();
+    let mut attested = Vec::with_capacity(node_endpoints.len());
+    let mut last_error = None;
+    for (idx, endpoint) in node_endpoints.iter().enumerate() {
+        match attest_node(&client, policy, idx, endpoint).await {
+            Ok(()) => attested.push(true),
+            Err(e) => {
+                tracing::warn!("TEE attestation failed: {}", e);
+                attested.push(false);
+                last_error = Some(e);
+            }
+        }
+    }
+    let passed = attested.iter().filter(|ok| **ok).count();
+    match last_error {
+        Some(e) if passed < quorum(node_endpoints.len()) => {
+            Err(format!("TEE attestation failed: {}", e))
+        }
+        _ => Ok(attested),
+    }
+}
+
+/// Attestation result per node; `None` when attestation is not required.
+pub async fn attestation_status(node_endpoints: &[String]) -> Vec<Option<bool>> {
+    let Some(policy) = TEE_POLICY.get().and_then(Option::as_ref) else {
+        return vec![None; node_endpoints.len()];
+    };
+    let client = reqwest::Client::new();
+    let mut results = Vec::with_capacity(node_endpoints.len());
+    for (idx, endpoint) in node_endpoints.iter().enumerate() {
+        let attested = match attest_node(&client, policy, idx, endpoint).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::error!("Node {} attestation check failed: {}", idx, e);
+                false
+            }
+        };
+        results.push(Some(attested));
+    }
+    results
+}
+
+/// Check a node's commitment is for this table and node and correctly signed.
+fn verify_commitment(
+    commitment: &ContributionCommitment,
+    table_id: u32,
+    node_idx: usize,
+) -> Result<(), String> {
+    if commitment.table_id != table_id || commitment.node_id as usize != node_idx {
+        return Err(format!(
+            "node {} returned a commitment for table {} node {}",
+            node_idx, commitment.table_id, commitment.node_id
+        ));
+    }
+    let expected_key = NODE_IDENTITY_KEYS
+        .get()
+        .and_then(Option::as_ref)
+        .and_then(|keys| keys.get(node_idx));
+    commitment.verify(expected_key)
+}
+
+/// Request body for a perm-lookup on `node_id`, authorized for `player` when enabled.
+fn perm_lookup_body(
+    node_id: u32,
+    table_id: u32,
+    player: &str,
+    indices: &[u32],
+) -> serde_json::Value {
+    match LOOKUP_AUTHORIZER.get().and_then(Option::as_ref) {
+        Some(authorizer) => serde_json::json!({
+            "indices": indices,
+            "authorization": authorizer.authorize(node_id, table_id, player, indices),
+        }),
+        None => serde_json::json!({ "indices": indices }),
+    }
+}
+
+fn with_node_auth(
+    builder: reqwest::RequestBuilder,
+    method: &str,
+    url: &str,
+    body: &[u8],
+) -> reqwest::RequestBuilder {
+    let Some(signer) = NODE_SIGNER.get().and_then(Option::as_ref) else {
+        return builder;
+    };
+    let path = reqwest::Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_default();
+    signer
+        .sign(method, &path, body)
+        .into_iter()
+        .fold(builder, |b, (name, value)| b.header(name, value))
+}
+
+fn signed_post(
+    client: &reqwest::Client,
+    url: &str,
+    body: &serde_json::Value,
+) -> reqwest::RequestBuilder {
+    let bytes = serde_json::to_vec(body).unwrap_or_default();
+    let builder = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    with_node_auth(builder, "POST", url, &bytes).body(bytes)
+}
+
+fn signed_get(client: &reqwest::Client, url: &str) -> reqwest::RequestBuilder {
+    with_node_auth(client.get(url), "GET", url, &[])
 }
 
 /// Generic helper: POST a JSON body to each MPC node's URL and collect share set IDs.
@@ -50,18 +575,21 @@ async fn prepare_from_nodes(
     body: serde_json::Value,
     operation_name: &str,
 ) -> Result<PreparedShareSets, String> {
+    let attested = attest_nodes(node_endpoints).await?;
+
     let client = reqwest::Client::new();
     let mut handles = Vec::with_capacity(node_endpoints.len());
 
     for (idx, endpoint) in node_endpoints.iter().enumerate() {
+        if !attested[idx] {
+            continue;
+        }
         let url = url_builder(endpoint, table_id);
         let body = body.clone();
         let client = client.clone();
         let op = operation_name.to_string();
         let handle = tokio::spawn(async move {
-            let resp = client
-                .post(&url)
-                .json(&body)
+            let resp = signed_post(&client, &url, &body)
                 .send()
                 .await
                 .map_err(|e| format!("failed to call node {} {}: {}", idx, op, e))?;
@@ -78,16 +606,20 @@ async fn prepare_from_nodes(
                 ));
             }
 
-            let prepared: NodePreparedSharesResponse = resp.json().await.map_err(|e| {
-                format!("failed to parse node {} {} response: {}", idx, op, e)
-            })?;
+            let prepared: NodePreparedSharesResponse = resp
+                .json()
+                .await
+                .map_err(|e| format!("failed to parse node {} {} response: {}", idx, op, e))?;
+            if let Some(commitment) = &prepared.commitment {
+                verify_commitment(commitment, table_id, idx)?;
+            }
 
-            Ok::<(usize, String), String>((idx, prepared.share_set_id))
+            Ok::<PreparedShare, String>((idx, prepared.share_set_id, prepared.commitment))
         });
         handles.push(handle);
     }
 
-    collect_prepared_share_sets(handles, node_endpoints.len()).await
+    collect_prepared_share_sets(handles, node_endpoints.len(), operation_name).await
 }
 
 /// Ask all nodes to prepare deal share sets.
@@ -96,6 +628,8 @@ pub async fn prepare_deal_from_nodes(
     circuit_dir: &str,
     table_id: u32,
     players: &[String],
+    hand_number: u32,
+    hand_nonce: u32,
 ) -> Result<PreparedShareSets, String> {
     prepare_from_nodes(
         node_endpoints,
@@ -104,18 +638,62 @@ pub async fn prepare_deal_from_nodes(
         serde_json::json!({
             "players": players,
             "circuit_dir": circuit_dir,
+            "hand_number": hand_number,
+            "hand_nonce": hand_nonce,
         }),
         "prepare-deal",
     )
     .await
 }
 
+/// Ask every node that committed to a deal contribution for `table_id` to
+/// disclose and retire it, in `commitments` order.
+pub async fn reveal_contributions_from_nodes(
+    node_endpoints: &[String],
+    table_id: u32,
+    commitments: &[ContributionCommitment],
+) -> Result<Vec<RevealedContribution>, String> {
+    let client = reqwest::Client::new();
+    let mut revealed = Vec::with_capacity(commitments.len());
+    for commitment in commitments {
+        let idx = commitment.node_id as usize;
+        let endpoint = node_endpoints
+            .get(idx)
+            .ok_or_else(|| format!("no endpoint for node {}", idx))?;
+        let url = format!("{}/table/{}/reveal-contribution", endpoint, table_id);
+        let resp = signed_post(&client, &url, &serde_json::json!({}))
+            .send()
+            .await
+            .map_err(|e| format!("failed to call node {} reveal-contribution: {}", idx, e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|_| "unable to read response body".to_string());
+            return Err(format!(
+                "node {} reveal-contribution rejected request: HTTP {}: {}",
+                idx, status, body
+            ));
+        }
+        let contribution: RevealedContribution = resp.json().await.map_err(|e| {
+            format!(
+                "failed to parse node {} reveal-contribution response: {}",
+                idx, e
+            )
+        })?;
+        revealed.push(contribution);
+    }
+    Ok(revealed)
+}
+
 /// Ask all nodes to prepare reveal share sets.
 pub async fn prepare_reveal_from_nodes(
     node_endpoints: &[String],
     circuit_dir: &str,
     table_id: u32,
     phase: &str,
+    hand_nonce: u32,
     previously_used_indices: &[u32],
     deck_root: &str,
 ) -> Result<PreparedShareSets, String> {
@@ -126,6 +704,7 @@ pub async fn prepare_reveal_from_nodes(
         table_id,
         serde_json::json!({
             "circuit_dir": circuit_dir,
+            "hand_nonce": hand_nonce,
             "previously_used_indices": previously_used_indices,
             "deck_root": deck_root,
         }),
@@ -134,15 +713,54 @@ pub async fn prepare_reveal_from_nodes(
     .await
 }
 
-/// Ask all nodes to prepare showdown share sets.
+/// Per player dealt in, in deal order: whether they are still in the hand,
+/// whether they muck a losing hand, and the chips they put in. The showdown
+/// proof shows hands by them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ShowdownSeats {
+    pub contending: Vec<bool>,
+    pub muck_losing: Vec<bool>,
+    pub contributions: Vec<u64>,
+}
+
+impl ShowdownSeats {
+    /// As the contract will check them against `table`.
+    pub fn from_table(table: &TableState) -> Self {
+        let mut seats = ShowdownSeats::default();
+        for p in table.seated() {
+            seats.contending.push(!p.folded);
+            seats
+                .muck_losing
+                .push(p.muck_preference == poker_table_types::MuckPreference::Muck);
+            seats
+                .contributions
+                .push(u64::try_from(p.hand_start_stack - p.stack).unwrap_or_default());
+        }
+        seats
+    }
+
+    /// Everyone still in and showing, when there is no table to read.
+    pub fn all_showing(num_players: usize) -> Self {
+        ShowdownSeats {
+            contending: vec![true; num_players],
+            muck_losing: vec![false; num_players],
+            contributions: vec![0; num_players],
+        }
+    }
+}
+
+/// Ask all nodes to prepare showdown share sets for the players holding
+/// `hand_commitments`.
+#[allow(clippy::too_many_arguments)]
 pub async fn prepare_showdown_from_nodes(
     node_endpoints: &[String],
     circuit_dir: &str,
     table_id: u32,
+    hand_nonce: u32,
     board_indices: &[u32],
-    num_active_players: u32,
     hand_commitments: &[String],
     deck_root: &str,
+    seats: &ShowdownSeats,
 ) -> Result<PreparedShareSets, String> {
     prepare_from_nodes(
         node_endpoints,
@@ -150,10 +768,14 @@ pub async fn prepare_showdown_from_nodes(
         table_id,
         serde_json::json!({
             "circuit_dir": circuit_dir,
+            "hand_nonce": hand_nonce,
             "board_indices": board_indices,
-            "num_active_players": num_active_players,
+            "num_active_players": hand_commitments.len(),
             "hand_commitments": hand_commitments,
             "deck_root": deck_root,
+            "contending": seats.contending,
+            "muck_losing": seats.muck_losing,
+            "contributions": seats.contributions,
         }),
         "prepare-showdown",
     )
@@ -177,24 +799,64 @@ pub async fn generate_proof_from_share_sets(
         circuit_name,
     )
     .await?;
+    resume_missing_shares(
+        node_endpoints,
+        table_id,
+        share_set_ids,
+        session_id,
+        circuit_name,
+    )
+    .await?;
     trigger_and_collect_proof(session_id, circuit_name, circuit_dir, node_endpoints).await
 }
 
 #[derive(Deserialize)]
 struct NodePermLookupResponse {
+    #[serde(default)]
     mapped_indices: Vec<u32>,
+    #[serde(default)]
     salts: Vec<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct NodePermLookupBatchResponse {
+    results: Vec<NodePermLookupResponse>,
 }
 
+/// Card values and combined salts resolved for one player.
+pub type ResolvedHoleCards = (Vec<u32>, Vec<String>);
+
 /// Resolve hole cards for a player by chaining permutation lookups across nodes
 /// and summing salts from all nodes at the original dealt positions.
 ///
+/// Every lookup carries a single-use authorization bound to `player`.
+///
 /// Returns (card_values, combined_salts) for the given deck positions.
 pub async fn resolve_hole_cards(
     node_endpoints: &[String],
     table_id: u32,
+    player: &str,
     card_positions: &[u32],
-) -> Result<(Vec<u32>, Vec<String>), String> {
+) -> Result<ResolvedHoleCards, String> {
+    let request = [(player.to_string(), card_positions.to_vec())];
+    resolve_hole_cards_batch(node_endpoints, table_id, &request)
+        .await?
+        .pop()
+        .ok_or_else(|| "empty perm-lookup batch result".to_string())?
+}
+
+/// Resolve hole cards for several players at once.
+///
+/// Each step of the lookup chain is one batch request per node, so a full
+/// table costs the same three round-trips as a single player. Results are
+/// per player, in request order; a refused lookup fails only that player.
+pub async fn resolve_hole_cards_batch(
+    node_endpoints: &[String],
+    table_id: u32,
+    requests: &[(String, Vec<u32>)],
+) -> Result<Vec<Result<ResolvedHoleCards, String>>, String> {
     if node_endpoints.len() != 3 {
         return Err(format!(
             "expected 3 MPC nodes, got {}",
@@ -207,96 +869,109 @@ pub async fn resolve_hole_cards(
     // Step 1: Query all 3 nodes in parallel with original positions to get salts.
     // Also use node2's mapped_indices as the first step of the permutation chain.
     let mut salt_handles = Vec::with_capacity(3);
-    for (i, endpoint) in node_endpoints.iter().enumerate() {
-        let url = format!("{}/table/{}/perm-lookup", endpoint, table_id);
+    for i in 0..node_endpoints.len() {
         let client = client.clone();
-        let positions = card_positions.to_vec();
-        let handle = tokio::spawn(async move {
-            let resp = client
-                .post(&url)
-                .json(&serde_json::json!({ "indices": positions }))
-                .send()
-                .await
-                .map_err(|e| format!("node {} perm-lookup failed: {}", i, e))?;
-            if !resp.status().is_success() {
-                let status = resp.status();
-                let body = resp
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "unable to read body".to_string());
-                return Err(format!(
-                    "node {} perm-lookup rejected: HTTP {}: {}",
-                    i, status, body
-                ));
-            }
-            let data: NodePermLookupResponse = resp
-                .json()
-                .await
-                .map_err(|e| format!("node {} perm-lookup parse failed: {}", i, e))?;
-            Ok::<(usize, NodePermLookupResponse), String>((i, data))
-        });
-        salt_handles.push(handle);
+        let endpoints = node_endpoints.to_vec();
+        let lookups = requests.to_vec();
+        salt_handles.push(tokio::spawn(async move {
+            query_perm_lookup_batch(&client, &endpoints, i, table_id, &lookups).await
+        }));
     }
-
-    let mut node_responses: Vec<Option<NodePermLookupResponse>> = vec![None, None, None];
+    let mut node_responses = Vec::with_capacity(3);
     for handle in salt_handles {
-        let (idx, resp) = handle
-            .await
-            .map_err(|e| format!("perm-lookup join error: {}", e))??;
-        node_responses[idx] = Some(resp);
+        node_responses.push(
+            handle
+                .await
+                .map_err(|e| format!("perm-lookup join error: {}", e))??,
+        );
     }
 
-    let resp0 = node_responses[0].take().ok_or("missing node 0 response")?;
-    let resp1 = node_responses[1].take().ok_or("missing node 1 response")?;
-    let resp2 = node_responses[2].take().ok_or("missing node 2 response")?;
-
     // Sum salts from all 3 nodes (all at the same original positions).
     // Salts are u64 values; sum fits in u128, well below BN254 modulus.
-    let num_cards = card_positions.len();
-    let mut combined_salts = Vec::with_capacity(num_cards);
-    for i in 0..num_cards {
-        let s0: u128 = resp0.salts[i]
-            .parse::<u64>()
-            .map_err(|e| format!("node0 salt parse: {}", e))?
-            .into();
-        let s1: u128 = resp1.salts[i]
-            .parse::<u64>()
-            .map_err(|e| format!("node1 salt parse: {}", e))?
-            .into();
-        let s2: u128 = resp2.salts[i]
-            .parse::<u64>()
-            .map_err(|e| format!("node2 salt parse: {}", e))?
-            .into();
-        combined_salts.push(format!("{}", s0 + s1 + s2));
-    }
+    let mut chain: Vec<Result<ResolvedHoleCards, String>> = (0..requests.len())
+        .map(|entry| {
+            let num_cards = requests[entry].1.len();
+            let mut combined_salts = Vec::with_capacity(num_cards);
+            for card in 0..num_cards {
+                let mut sum: u128 = 0;
+                for (node, responses) in node_responses.iter().enumerate() {
+                    let resp = responses[entry].as_ref().map_err(Clone::clone)?;
+                    let salt = resp
+                        .salts
+                        .get(card)
+                        .ok_or_else(|| format!("node{} returned too few salts", node))?;
+                    let salt: u64 = salt
+                        .parse()
+                        .map_err(|e| format!("node{} salt parse: {}", node, e))?;
+                    sum += u128::from(salt);
+                }
+                combined_salts.push(format!("{}", sum));
+            }
+            let step1 = node_responses[2][entry]
+                .as_ref()
+                .map_err(Clone::clone)?
+                .mapped_indices
+                .clone();
+            Ok((step1, combined_salts))
+        })
+        .collect();
 
     // Step 2: Chain permutation lookups: node2 → node1 → node0.
     // We already have node2's mapped_indices from step 1.
-    let step1 = resp2.mapped_indices;
-
-    // Query node1 with node2's mapped indices.
-    let step2 = query_perm_lookup(&client, &node_endpoints[1], table_id, &step1)
-        .await?
-        .mapped_indices;
-
-    // Query node0 with node1's result → final card values.
-    let final_cards = query_perm_lookup(&client, &node_endpoints[0], table_id, &step2)
-        .await?
-        .mapped_indices;
+    for node_id in [1, 0] {
+        let pending: Vec<usize> = (0..requests.len())
+            .filter(|&entry| chain[entry].is_ok())
+            .collect();
+        if pending.is_empty() {
+            break;
+        }
+        let lookups: Vec<(String, Vec<u32>)> = pending
+            .iter()
+            .map(|&entry| {
+                let indices = chain[entry]
+                    .as_ref()
+                    .map(|(i, _)| i.clone())
+                    .unwrap_or_default();
+                (requests[entry].0.clone(), indices)
+            })
+            .collect();
+        let responses =
+            query_perm_lookup_batch(&client, node_endpoints, node_id, table_id, &lookups).await?;
+        for (entry, response) in pending.into_iter().zip(responses) {
+            match response {
+                Ok(resp) => {
+                    if let Ok((indices, _)) = &mut chain[entry] {
+                        *indices = resp.mapped_indices;
+                    }
+                }
+                Err(e) => chain[entry] = Err(e),
+            }
+        }
+    }
 
-    Ok((final_cards, combined_salts))
+    Ok(chain)
 }
 
-async fn query_perm_lookup(
+/// Send one batch perm-lookup to `node_id`, returning each lookup's result
+/// in order. Fails as a whole only when the request itself fails.
+async fn query_perm_lookup_batch(
     client: &reqwest::Client,
-    endpoint: &str,
+    node_endpoints: &[String],
+    node_id: usize,
     table_id: u32,
-    indices: &[u32],
-) -> Result<NodePermLookupResponse, String> {
-    let url = format!("{}/table/{}/perm-lookup", endpoint, table_id);
-    let resp = client
-        .post(&url)
-        .json(&serde_json::json!({ "indices": indices }))
+    lookups: &[(String, Vec<u32>)],
+) -> Result<Vec<Result<NodePermLookupResponse, String>>, String> {
+    let url = format!(
+        "{}/table/{}/perm-lookup-batch",
+        node_endpoints[node_id], table_id
+    );
+    let body = serde_json::json!({
+        "lookups": lookups
+            .iter()
+            .map(|(player, indices)| perm_lookup_body(node_id as u32, table_id, player, indices))
+            .collect::<Vec<_>>(),
+    });
+    let resp = signed_post(client, &url, &body)
         .send()
         .await
         .map_err(|e| format!("perm-lookup to {} failed: {}", url, e))?;
@@ -311,9 +986,26 @@ async fn query_perm_lookup(
             url, status, body
         ));
     }
-    resp.json()
+    let data: NodePermLookupBatchResponse = resp
+        .json()
         .await
-        .map_err(|e| format!("perm-lookup parse from {} failed: {}", url, e))
+        .map_err(|e| format!("perm-lookup parse from {} failed: {}", url, e))?;
+    if data.results.len() != lookups.len() {
+        return Err(format!(
+            "perm-lookup to {} returned {} results for {} lookups",
+            url,
+            data.results.len(),
+            lookups.len()
+        ));
+    }
+    Ok(data
+        .results
+        .into_iter()
+        .map(|result| match result.error {
+            Some(e) => Err(format!("node {} perm-lookup rejected: {}", node_id, e)),
+            None => Ok(result),
+        })
+        .collect())
 }
 
 /// Check health of all MPC nodes.
@@ -329,27 +1021,34 @@ pub async fn check_node_health(endpoints: &[String]) -> Vec<bool> {
     results
 }
 
+/// Node index, share set ID and optional contribution commitment.
+type PreparedShare = (usize, String, Option<ContributionCommitment>);
+
+/// Share set IDs in node order, once the committee threshold of nodes has
+/// prepared; nodes that have not are left with an empty ID.
 async fn collect_prepared_share_sets(
-    handles: Vec<tokio::task::JoinHandle<Result<(usize, String), String>>>,
+    handles: Vec<tokio::task::JoinHandle<Result<PreparedShare, String>>>,
     expected_len: usize,
+    operation_name: &str,
 ) -> Result<PreparedShareSets, String> {
     let mut ordered = vec![String::new(); expected_len];
-    for handle in handles {
-        let (idx, share_set_id) = handle
-            .await
-            .map_err(|e| format!("prepare task join error: {}", e))??;
+    let mut commitments = vec![None; expected_len];
+    for (idx, share_set_id, commitment) in
+        collect_quorum(handles, quorum(expected_len), operation_name).await?
+    {
         if idx >= ordered.len() {
             return Err(format!("prepare task returned out-of-range index {}", idx));
         }
+        if share_set_id.is_empty() {
+            return Err(format!("node {} returned an empty share_set_id", idx));
+        }
         ordered[idx] = share_set_id;
-    }
-
-    if ordered.iter().any(|id| id.is_empty()) {
-        return Err("missing share_set_id for one or more nodes".to_string());
+        commitments[idx] = commitment;
     }
 
     Ok(PreparedShareSets {
         share_set_ids: ordered,
+        commitments,
     })
 }
 
@@ -372,19 +1071,22 @@ async fn dispatch_share_sets_from_nodes(
     let mut handles = Vec::with_capacity(node_endpoints.len());
 
     for (idx, endpoint) in node_endpoints.iter().enumerate() {
+        // Nodes outside the threshold that prepared have nothing to send.
+        if share_set_ids[idx].is_empty() {
+            continue;
+        }
         let url = format!("{}/table/{}/dispatch-shares", endpoint, table_id);
         let share_set_id = share_set_ids[idx].clone();
         let session_id = session_id.to_string();
         let circuit_name = circuit_name.to_string();
         let client = client.clone();
         let handle = tokio::spawn(async move {
-            let resp = client
-                .post(&url)
-                .json(&serde_json::json!({
-                    "share_set_id": share_set_id,
-                    "proof_session_id": session_id,
-                    "circuit_name": circuit_name,
-                }))
+            let body = serde_json::json!({
+                "share_set_id": share_set_id,
+                "proof_session_id": session_id,
+                "circuit_name": circuit_name,
+            });
+            let resp = signed_post(&client, &url, &body)
                 .send()
                 .await
                 .map_err(|e| format!("failed to call node {} dispatch-shares: {}", idx, e))?;
@@ -405,12 +1107,107 @@ async fn dispatch_share_sets_from_nodes(
         handles.push(handle);
     }
 
-    for handle in handles {
-        handle
-            .await
-            .map_err(|e| format!("dispatch join error: {}", e))??;
+    collect_quorum(handles, quorum(node_endpoints.len()), "dispatch-shares").await?;
+    Ok(())
+}
+
+/// Check every node holds a fragment from every source before `generate`,
+/// and have sources re-dispatch to nodes that lost theirs (e.g. after a
+/// restart). The share sets are re-sent unchanged, so the session resumes
+/// without new contributions. Sources that did not prepare and nodes that
+/// cannot be reached are skipped.
+async fn resume_missing_shares(
+    node_endpoints: &[String],
+    table_id: u32,
+    share_set_ids: &[String],
+    session_id: &str,
+    circuit_name: &str,
+) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let sources: Vec<u32> = (0..node_endpoints.len() as u32)
+        .filter(|s| !share_set_ids[*s as usize].is_empty())
+        .collect();
+
+    // source -> recipients missing its fragment
+    let mut missing: Vec<Vec<u32>> = vec![Vec::new(); node_endpoints.len()];
+    for (recipient, endpoint) in node_endpoints.iter().enumerate() {
+        let url = format!("{}/session/{}/status", endpoint, session_id);
+        let resp = match signed_get(&client, &url).send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                tracing::warn!(
+                    "[{}] failed to check node {} shares: {}",
+                    session_id,
+                    recipient,
+                    e
+                );
+                continue;
+            }
+        };
+        let received = if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            Vec::new()
+        } else if resp.status().is_success() {
+            match resp.json::<NodeStatusResponse>().await {
+                Ok(status) => match status.fragments_received {
+                    Some(received) => received,
+                    // Older node: cannot tell, assume delivered.
+                    None => continue,
+                },
+                // Unreadable: re-dispatch everything, which is idempotent.
+                Err(e) => {
+                    tracing::warn!(
+                        "[{}] unreadable status from node {}: {}",
+                        session_id,
+                        recipient,
+                        e
+                    );
+                    Vec::new()
+                }
+            }
+        } else {
+            continue;
+        };
+        for &source in sources.iter().filter(|s| !received.contains(s)) {
+            missing[source as usize].push(recipient as u32);
+        }
     }
 
+    for (source, parties) in missing.into_iter().enumerate() {
+        if parties.is_empty() {
+            continue;
+        }
+        tracing::warn!(
+            "[{}] nodes {:?} are missing shares from node {}; re-dispatching",
+            session_id,
+            parties,
+            source
+        );
+        let url = format!(
+            "{}/table/{}/redispatch-shares",
+            node_endpoints[source], table_id
+        );
+        let body = serde_json::json!({
+            "share_set_id": share_set_ids[source],
+            "proof_session_id": session_id,
+            "circuit_name": circuit_name,
+            "parties": parties,
+        });
+        let resp = signed_post(&client, &url, &body)
+            .send()
+            .await
+            .map_err(|e| format!("failed to call node {} redispatch-shares: {}", source, e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|_| "unable to read response body".to_string());
+            return Err(format!(
+                "node {} redispatch-shares rejected request: HTTP {}: {}",
+                source, status, body
+            ));
+        }
+    }
     Ok(())
 }
 
@@ -436,12 +1233,11 @@ async fn trigger_and_collect_proof(
         let circuit_dir = circuit_dir.to_string();
         let crs_dir = crs_dir.clone();
         let handle = tokio::spawn(async move {
-            let resp = client
-                .post(&url)
-                .json(&serde_json::json!({
-                    "circuit_dir": circuit_dir,
-                    "crs_path": crs_dir,
-                }))
+            let body = serde_json::json!({
+                "circuit_dir": circuit_dir,
+                "crs_path": crs_dir,
+            });
+            let resp = signed_post(&client, &url, &body)
                 .send()
                 .await
                 .map_err(|e| format!("failed to trigger node {}: {}", i, e))?;
@@ -457,46 +1253,59 @@ async fn trigger_and_collect_proof(
                     i, status, body
                 ));
             }
-            Ok::<(), String>(())
+            Ok::<usize, String>(i)
         });
         handles.push(handle);
     }
 
-    for handle in handles {
-        handle.await.map_err(|e| format!("join error: {}", e))??;
-    }
-
-    // Poll node 0 for proof completion.
-    let proof_node = &node_endpoints[0];
+    // Poll the lowest-numbered node that took the trigger for the proof.
+    let triggered = collect_quorum(handles, quorum(node_endpoints.len()), "generate").await?;
+    let proof_idx = triggered.into_iter().min().unwrap_or_default();
+    let proof_node = &node_endpoints[proof_idx];
     let max_polls = if circuit_name == "showdown_valid" {
         900
     } else {
         300
     };
+    let mut last_stage: Option<(String, Option<u64>)> = None;
     for _ in 0..max_polls {
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
         let status_url = format!("{}/session/{}/status", proof_node, session_id);
-        let resp = client
-            .get(&status_url)
+        let resp = signed_get(&client, &status_url)
             .send()
             .await
-            .map_err(|e| format!("failed to poll node 0: {}", e))?;
+            .map_err(|e| format!("failed to poll node {}: {}", proof_idx, e))?;
 
         if !resp.status().is_success() {
             continue;
         }
 
-        let status: NodeStatusResponse = resp
-            .json()
-            .await
-            .map_err(|e| format!("failed to parse status: {}", e))?;
+        // A garbled status is just a missed poll; the next one decides.
+        let status: NodeStatusResponse = match resp.json().await {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!(
+                    "[{}] unreadable status from node {}: {}",
+                    session_id,
+                    proof_idx,
+                    e
+                );
+                continue;
+            }
+        };
+
+        if let Some(stage) = status.stage.clone() {
+            if last_stage.as_ref().map(|(s, _)| s) != Some(&stage) {
+                tracing::info!("[{}] {} stage: {}", session_id, circuit_name, stage);
+            }
+            last_stage = Some((stage, status.stage_age_secs()));
+        }
 
         match status.status.as_str() {
             "complete" => {
                 let proof_url = format!("{}/session/{}/proof", proof_node, session_id);
-                let proof_resp = client
-                    .get(&proof_url)
+                let proof_resp = signed_get(&client, &proof_url)
                     .send()
                     .await
                     .map_err(|e| format!("failed to fetch proof: {}", e))?;
@@ -532,8 +1341,18 @@ async fn trigger_and_collect_proof(
         }
     }
 
-    Err(format!(
-        "[{}] proof generation timed out after {} seconds",
-        session_id, max_polls
-    ))
+    match last_stage {
+        Some((stage, Some(age))) => Err(format!(
+            "[{}] proof generation timed out after {} seconds (in {} for {}s)",
+            session_id, max_polls, stage, age
+        )),
+        Some((stage, None)) => Err(format!(
+            "[{}] proof generation timed out after {} seconds (last stage {})",
+            session_id, max_polls, stage
+        )),
+        None => Err(format!(
+            "[{}] proof generation timed out after {} seconds",
+            session_id, max_polls
+        )),
+    }
 }
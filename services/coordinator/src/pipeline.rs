@@ -0,0 +1,200 @@
+//! Reveal pipelining: start on the next street's reveal while players are
+//! still betting on the current one.
+//!
+//! `REVEAL_PIPELINE` picks how far ahead the coordinator works:
+//! - `off` (default): nothing happens until `request-reveal`.
+//! - `prepare`: as soon as a betting street opens on chain, the nodes
+//!   prepare their shares for the next reveal, so the request only has to
+//!   prove and submit.
+//! - `prove`: the reveal proof is generated ahead of time too, leaving only
+//!   the submission. The proof's public inputs carry the board cards, so in
+//!   this mode the coordinator knows the next street while players bet.
+//!
+//! Work for a street the hand never reaches is thrown away: the nodes drop
+//! unused share sets when the next hand is dealt.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::mpc::{self, MpcProofResult};
+use crate::{MpcConfig, TableSession};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PipelineMode {
+    #[default]
+    Off,
+    Prepare,
+    Prove,
+}
+
+/// Reveal work done ahead of the request.
+pub struct Speculation {
+    pub share_set_ids: Vec<String>,
+    /// Set in `prove` mode.
+    pub proof: Option<MpcProofResult>,
+}
+
+struct Pending {
+    phase: String,
+    deck_root: String,
+    used_indices: Vec<u32>,
+    task: JoinHandle<Result<Speculation, String>>,
+}
+
+#[derive(Clone, Default)]
+pub struct RevealPipeline {
+    mode: PipelineMode,
+    /// At most one speculative reveal per table.
+    pending: Arc<Mutex<HashMap<u32, Pending>>>,
+}
+
+/// The street revealed once betting in `phase` completes.
+pub fn next_reveal(phase: &str) -> Option<&'static str> {
+    match phase {
+        "preflop" => Some("flop"),
+        "flop" => Some("turn"),
+        "turn" => Some("river"),
+        _ => None,
+    }
+}
+
+impl RevealPipeline {
+    pub fn from_env() -> Result<Self, String> {
+        let mode = match std::env::var("REVEAL_PIPELINE") {
+            Ok(raw) => match raw.trim().to_ascii_lowercase().as_str() {
+                "" | "off" => PipelineMode::Off,
+                "prepare" => PipelineMode::Prepare,
+                "prove" => PipelineMode::Prove,
+                other => {
+                    return Err(format!(
+                        "REVEAL_PIPELINE must be off, prepare or prove, got '{}'",
+                        other
+                    ))
+                }
+            },
+            Err(_) => PipelineMode::Off,
+        };
+        Ok(RevealPipeline {
+            mode,
+            pending: Arc::default(),
+        })
+    }
+
+    pub fn mode(&self) -> PipelineMode {
+        self.mode
+    }
+
+    /// Start working on `phase`'s reveal for `session`'s hand in the
+    /// background, replacing any earlier speculation for the table.
+    /// `proof_session_id` is only used in `prove` mode.
+    pub async fn start(
+        &self,
+        mpc_config: &MpcConfig,
+        session: &TableSession,
+        phase: &str,
+        proof_session_id: String,
+    ) {
+        if self.mode == PipelineMode::Off {
+            return;
+        }
+        let mode = self.mode;
+        let table_id = session.table_id;
+        let hand_nonce = session.hand_nonce;
+        let endpoints = mpc_config.node_endpoints.clone();
+        let circuit_dir = mpc_config.circuit_dir.clone();
+        let task_phase = phase.to_string();
+        let task_indices = session.dealt_indices.clone();
+        let task_root = session.deck_root.clone();
+        let task = tokio::spawn(async move {
+            let prepared = mpc::prepare_reveal_from_nodes(
+                &endpoints,
+                &circuit_dir,
+                table_id,
+                &task_phase,
+                hand_nonce,
+                &task_indices,
+                &task_root,
+            )
+            .await?;
+            if mode != PipelineMode::Prove {
+                return Ok(Speculation {
+                    share_set_ids: prepared.share_set_ids,
+                    proof: None,
+                });
+            }
+            let proof = mpc::generate_proof_from_share_sets(
+                table_id,
+                &prepared.share_set_ids,
+                &proof_session_id,
+                "reveal_board_valid",
+                &circuit_dir,
+                &endpoints,
+            )
+            .await?;
+            Ok(Speculation {
+                share_set_ids: prepared.share_set_ids,
+                proof: Some(proof),
+            })
+        });
+        tracing::debug!("Pipelining {} reveal for table {}", phase, table_id);
+
+        let previous = self.pending.lock().await.insert(
+            table_id,
+            Pending {
+                phase: phase.to_string(),
+                deck_root: session.deck_root.clone(),
+                used_indices: session.dealt_indices.clone(),
+                task,
+            },
+        );
+        if let Some(previous) = previous {
+            previous.task.abort();
+        }
+    }
+
+    /// The speculative work for `phase`, waiting for it if it is still
+    /// running. `None` if there is none, it failed, or it was started from
+    /// inputs that no longer match the table's.
+    pub async fn take(
+        &self,
+        table_id: u32,
+        phase: &str,
+        used_indices: &[u32],
+        deck_root: &str,
+    ) -> Option<Speculation> {
+        let pending = self.pending.lock().await.remove(&table_id)?;
+        if pending.phase != phase
+            || pending.deck_root != deck_root
+            || pending.used_indices != used_indices
+        {
+            pending.task.abort();
+            return None;
+        }
+        match pending.task.await {
+            Ok(Ok(speculation)) => Some(speculation),
+            Ok(Err(e)) => {
+                tracing::warn!(
+                    "Pipelined {} reveal for table {} failed, redoing it: {}",
+                    phase,
+                    table_id,
+                    e
+                );
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Drop speculative work for the tables, e.g. once a new hand is dealt.
+    pub async fn discard(&self, table_ids: &[u32]) {
+        let mut pending = self.pending.lock().await;
+        for table_id in table_ids {
+            if let Some(stale) = pending.remove(table_id) {
+                stale.task.abort();
+            }
+        }
+    }
+}
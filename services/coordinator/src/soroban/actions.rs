@@ -1,24 +1,16 @@
 use std::collections::HashSet;
 
+use poker_table_types::{Action, GamePhase, PokerTableError, Settlement, TableState, TableSummary};
 use tokio::process::Command;
 
 use super::{
-    invoke_contract_with_retries, invoke_contract_with_source_retries, parse_i128_value,
-    parse_tx_result, parse_u32_from_stdout, parse_u32_value, resolve_onchain_table_id,
-    SorobanConfig,
+    invoke_contract_with_retries, invoke_contract_with_source_retries, parse_tx_result,
+    parse_u32_from_stdout, resolve_onchain_table_id, SorobanConfig,
 };
 
-fn resolve_buy_in_from_table_state(state: &serde_json::Value, requested: i128) -> i128 {
-    let min_buy_in = state
-        .get("config")
-        .and_then(|cfg| cfg.get("min_buy_in"))
-        .and_then(parse_i128_value)
-        .unwrap_or(requested.max(1));
-    let max_buy_in = state
-        .get("config")
-        .and_then(|cfg| cfg.get("max_buy_in"))
-        .and_then(parse_i128_value)
-        .unwrap_or(min_buy_in);
+fn resolve_buy_in_from_table_state(state: &TableState, requested: i128) -> i128 {
+    let min_buy_in = state.config.min_buy_in;
+    let max_buy_in = state.config.max_buy_in;
 
     if min_buy_in <= max_buy_in {
         requested.clamp(min_buy_in, max_buy_in)
@@ -27,48 +19,10 @@ fn resolve_buy_in_from_table_state(state: &serde_json::Value, requested: i128) -
     }
 }
 
-fn friendbot_url(config: &SorobanConfig) -> Option<String> {
-    if let Ok(url) = std::env::var("FRIENDBOT_URL") {
-        let trimmed = url.trim();
-        if !trimmed.is_empty() {
-            return Some(trimmed.to_string());
-        }
-    }
-
-    if config.rpc_url.contains("localhost:8000") || config.rpc_url.contains("127.0.0.1:8000") {
-        return Some("http://localhost:8000/friendbot".to_string());
-    }
-
-    None
-}
-
-async fn maybe_friendbot_top_up(config: &SorobanConfig, address: &str) {
-    let Some(base) = friendbot_url(config) else {
-        return;
-    };
-    let url = format!("{}?addr={}", base, address);
-    match Command::new("curl")
-        .args(["-sfL", &url])
-        .output()
-        .await
-    {
-        Ok(output) if output.status.success() => {
-            tracing::info!("friendbot topped up {}", address);
-        }
-        Ok(output) => {
-            tracing::warn!(
-                "friendbot top-up failed for {}: {}",
-                address,
-                String::from_utf8_lossy(&output.stderr).trim()
-            );
-        }
-        Err(e) => {
-            tracing::warn!("friendbot top-up failed for {}: {}", address, e);
-        }
-    }
-}
-
 fn looks_like_insufficient_balance(error: &str) -> bool {
+    if PokerTableError::from_invoke_error(error) == Some(PokerTableError::InsufficientBalance) {
+        return true;
+    }
     let e = error.to_ascii_lowercase();
     e.contains("resulting balance is not within the allowed range")
         || (e.contains("error(contract, #10)") && e.contains("transfer"))
@@ -86,9 +40,9 @@ pub async fn maybe_auto_advance_betting_for_reveal(
     }
 
     let expected = match reveal_phase {
-        "flop" => "Preflop",
-        "turn" => "Flop",
-        "river" => "Turn",
+        "flop" => GamePhase::Preflop,
+        "turn" => GamePhase::Flop,
+        "river" => GamePhase::Turn,
         _ => return Ok(()),
     };
 
@@ -104,48 +58,27 @@ pub async fn maybe_auto_advance_betting_for_showdown(
     if !config.is_configured() {
         return Ok(());
     }
-    maybe_auto_advance_betting_if_phase(config, table_id, "River", "showdown").await
+    maybe_auto_advance_betting_if_phase(config, table_id, GamePhase::River, "showdown").await
 }
 
 async fn maybe_auto_advance_betting_if_phase(
     config: &SorobanConfig,
     table_id: u32,
-    expected_phase: &str,
+    expected_phase: GamePhase,
     reason: &str,
 ) -> Result<(), String> {
     const MAX_AUTO_ACTIONS: usize = 24;
 
     for step in 0..MAX_AUTO_ACTIONS {
-        let state_raw = get_table_state(config, table_id).await?;
-        let state: serde_json::Value = serde_json::from_str(&state_raw)
-            .map_err(|e| format!("failed to parse on-chain table state: {}", e))?;
-
-        let phase = state
-            .get("phase")
-            .and_then(|v| v.as_str())
-            .ok_or("missing phase in on-chain table state")?;
-
-        if phase != expected_phase {
+        let state = get_table(config, table_id).await?;
+        if state.phase != expected_phase {
             return Ok(());
         }
 
-        let players = state
-            .get("players")
-            .and_then(|v| v.as_array())
-            .ok_or("missing players in on-chain table state")?;
-        let current_turn = state
-            .get("current_turn")
-            .and_then(|v| v.as_u64())
-            .ok_or("missing current_turn in on-chain table state")?
-            as usize;
-
-        let current_player = players
-            .get(current_turn)
+        let current_player = state
+            .player_to_act()
             .ok_or("current_turn out of range for on-chain players")?;
-        let player_address = current_player
-            .get("address")
-            .and_then(|v| v.as_str())
-            .ok_or("missing current player address")?;
+        let player_address = current_player.address.as_str();
         let source_identity = config.identity_for_player(player_address).ok_or_else(|| {
             format!(
                 "no local identity configured for player {} (set PLAYERn_ADDRESS/PLAYERn_IDENTITY)",
@@ -153,34 +86,24 @@ async fn maybe_auto_advance_betting_if_phase(
             )
         })?;
 
-        let current_bet = players
-            .iter()
-            .filter_map(|p| p.get("bet_this_round"))
-            .filter_map(parse_i128_value)
-            .max()
-            .unwrap_or(0);
-        let my_bet = current_player
-            .get("bet_this_round")
-            .and_then(parse_i128_value)
-            .unwrap_or(0);
-
-        let action_json = if my_bet < current_bet {
-            "\"Call\""
+        let action = if current_player.bet_this_round < state.max_bet() {
+            Action::Call
         } else {
-            "\"Check\""
+            Action::Check
         };
         let onchain_table_id = resolve_onchain_table_id(config, table_id);
         tracing::info!(
-            "Auto-advancing betting before {}: phase={}, action={}, player={}, step={}",
+            "Auto-advancing betting before {}: phase={}, action={:?}, player={}, step={}",
             reason,
-            phase,
-            action_json,
+            state.phase,
+            action,
             player_address,
             step + 1
         );
 
         let output = invoke_contract_with_source_retries(
             config,
+            config.table_contract(table_id),
             source_identity,
             vec![
                 "player_action".to_string(),
@@ -189,7 +112,7 @@ async fn maybe_auto_advance_betting_if_phase(
                 "--player".to_string(),
                 player_address.to_string(),
                 "--action".to_string(),
-                action_json.to_string(),
+                action.to_cli_arg(),
             ],
         )
         .await?;
@@ -210,8 +133,7 @@ pub async fn submit_player_action(
     config: &SorobanConfig,
     table_id: u32,
     player_address: &str,
-    action: &str,
-    amount: Option<i128>,
+    action: Action,
 ) -> Result<String, String> {
     if !config.is_configured() {
         return Err("Soroban not configured".to_string());
@@ -224,32 +146,10 @@ pub async fn submit_player_action(
         )
     })?;
 
-    let action_lower = action.to_ascii_lowercase();
-    let action_json = match action_lower.as_str() {
-        "fold" => "\"Fold\"".to_string(),
-        "check" => "\"Check\"".to_string(),
-        "call" => "\"Call\"".to_string(),
-        "allin" | "all_in" => "\"AllIn\"".to_string(),
-        "bet" => {
-            let value = amount.ok_or("bet requires amount")?;
-            if value <= 0 {
-                return Err("bet amount must be positive".to_string());
-            }
-            format!("{{\"Bet\":\"{}\"}}", value)
-        }
-        "raise" => {
-            let value = amount.ok_or("raise requires amount")?;
-            if value <= 0 {
-                return Err("raise amount must be positive".to_string());
-            }
-            format!("{{\"Raise\":\"{}\"}}", value)
-        }
-        _ => return Err(format!("unsupported action '{}'", action)),
-    };
-
     let onchain_table_id = resolve_onchain_table_id(config, table_id);
     let output = invoke_contract_with_source_retries(
         config,
+        config.table_contract(table_id),
         source_identity,
         vec![
             "player_action".to_string(),
@@ -258,7 +158,7 @@ pub async fn submit_player_action(
             "--player".to_string(),
             player_address.to_string(),
             "--action".to_string(),
-            action_json,
+            action.to_cli_arg(),
         ],
     )
     .await?;
@@ -276,6 +176,7 @@ pub async fn claim_timeout(config: &SorobanConfig, table_id: u32) -> Result<Stri
     let claimer = config.committee_address()?;
     let output = invoke_contract_with_retries(
         config,
+        config.table_contract(table_id),
         vec![
             "claim_timeout".to_string(),
             "--table_id".to_string(),
@@ -289,7 +190,9 @@ pub async fn claim_timeout(config: &SorobanConfig, table_id: u32) -> Result<Stri
     parse_tx_result(output)
 }
 
-/// Create a new table by cloning the reference table config.
+/// Create a new table by cloning the reference table config. Both IDs are
+/// coordinator table IDs, so the table is created in the reference table's
+/// namespace.
 pub async fn create_seeded_table(
     config: &SorobanConfig,
     reference_table_id: u32,
@@ -303,46 +206,30 @@ pub async fn create_seeded_table(
         return Err(format!("max_players out of range: {}", max_players));
     }
 
-    let raw = get_table_state(config, reference_table_id).await?;
-    let value: serde_json::Value = serde_json::from_str(&raw)
-        .map_err(|e| format!("failed to parse reference table: {}", e))?;
-    let mut cfg = value
-        .get("config")
-        .cloned()
-        .ok_or("reference table missing config")?;
-
-    if let Some(obj) = cfg.as_object_mut() {
-        obj.insert(
-            "max_players".to_string(),
-            serde_json::Value::Number(serde_json::Number::from(max_players)),
-        );
-        obj.insert(
-            "committee".to_string(),
-            serde_json::Value::String(config.committee_address()?),
-        );
-        if let Some(buy_in) = buy_in_override {
-            if buy_in <= 0 {
-                return Err(format!("buy_in must be > 0 (got {})", buy_in));
-            }
-            // Enforce exact buy-in for newly created tables when requested.
-            obj.insert(
-                "min_buy_in".to_string(),
-                serde_json::Value::String(buy_in.to_string()),
-            );
-            obj.insert(
-                "max_buy_in".to_string(),
-                serde_json::Value::String(buy_in.to_string()),
-            );
+    let mut cfg = get_table(config, reference_table_id).await?.config;
+    cfg.max_players = max_players;
+    // The new table keeps the reference committee if this coordinator is in
+    // it, and is otherwise the coordinator's alone.
+    let committee_addr = config.committee_address()?;
+    if !cfg.committee.contains(&committee_addr) {
+        cfg.committee = vec![committee_addr.clone()];
+        cfg.committee_threshold = 1;
+    }
+    if let Some(buy_in) = buy_in_override {
+        if buy_in <= 0 {
+            return Err(format!("buy_in must be > 0 (got {})", buy_in));
         }
-    } else {
-        return Err("reference config is not an object".to_string());
+        // Enforce exact buy-in for newly created tables when requested.
+        cfg.min_buy_in = buy_in;
+        cfg.max_buy_in = buy_in;
     }
     let cfg_json = serde_json::to_string(&cfg)
         .map_err(|e| format!("failed to serialize table config: {}", e))?;
 
-    let committee_addr = config.committee_address()?;
+    let contract = config.table_contract(reference_table_id);
     let output = invoke_contract_with_retries(
         config,
+        contract,
         vec![
             "create_table".to_string(),
             "--admin".to_string(),
@@ -359,10 +246,12 @@ pub async fn create_seeded_table(
             String::from_utf8_lossy(&output.stderr).trim()
         ));
     }
-    let table_id = parse_u32_from_stdout(&String::from_utf8_lossy(&output.stdout))
+    let onchain_id = parse_u32_from_stdout(&String::from_utf8_lossy(&output.stdout))
         .ok_or_else(|| "failed to parse table id from create_table output".to_string())?;
 
-    Ok(table_id)
+    config
+        .coordinator_table_id(contract, onchain_id)
+        .ok_or_else(|| format!("table {} is beyond its namespace's ID range", onchain_id))
 }
 
 /// Join the next unseated configured local identity to the table.
@@ -375,48 +264,29 @@ pub async fn join_next_available_local_player(
         return Err("Soroban not configured".to_string());
     }
 
-    let state_raw = get_table_state(config, table_id).await?;
-    let state: serde_json::Value = serde_json::from_str(&state_raw)
-        .map_err(|e| format!("failed to parse on-chain table state: {}", e))?;
-
-    let phase = state
-        .get("phase")
-        .and_then(|v| v.as_str())
-        .ok_or("missing phase in on-chain table state")?;
-    if phase != "Waiting" {
+    let state = get_table(config, table_id).await?;
+    if state.phase != GamePhase::Waiting {
         return Err(format!(
             "table {} is not accepting joins (phase={})",
-            table_id, phase
+            table_id, state.phase
         ));
     }
 
-    let players = state
-        .get("players")
-        .and_then(|v| v.as_array())
-        .ok_or("missing players in on-chain table state")?;
-    let max_players = state
-        .get("config")
-        .and_then(|cfg| cfg.get("max_players"))
-        .and_then(parse_u32_value)
-        .unwrap_or(players.len() as u32);
-    if players.len() as u32 >= max_players {
+    if state.seated().count() as u32 >= state.config.max_players {
         return Err(format!("table {} is full", table_id));
     }
 
-    let seated: HashSet<String> = players
-        .iter()
-        .filter_map(|p| p.get("address").and_then(|v| v.as_str()))
-        .map(|s| s.to_string())
-        .collect();
+    let seated: HashSet<&str> = state.seated().map(|p| p.address.as_str()).collect();
 
     let (player_address, identity) = config
         .player_identities
         .iter()
-        .find(|(address, _)| !seated.contains(address))
+        .find(|(address, _)| !seated.contains(address.as_str()))
         .ok_or("no unseated local identity available")?;
 
     let resolved_buy_in = resolve_buy_in_from_table_state(&state, buy_in);
     let onchain_table_id = resolve_onchain_table_id(config, table_id);
+    let contract = config.table_contract(table_id);
     let join_args = vec![
         "join_table".to_string(),
         "--table_id".to_string(),
@@ -429,18 +299,19 @@ pub async fn join_next_available_local_player(
 
     // Keep local identities liquid so repeated solo-table creation does not
     // fail with transfer underflow after many test hands/tables.
-    maybe_friendbot_top_up(config, player_address).await;
+    super::funding::ensure_funded(player_address).await;
 
-    let output = invoke_contract_with_source_retries(config, identity, join_args.clone()).await?;
+    let output =
+        invoke_contract_with_source_retries(config, contract, identity, join_args.clone()).await?;
     if let Err(first_error) = parse_tx_result(output) {
         if looks_like_insufficient_balance(&first_error) {
             tracing::warn!(
                 "join_table for {} failed due to balance; topping up and retrying once",
                 player_address
             );
-            maybe_friendbot_top_up(config, player_address).await;
+            super::funding::top_up(player_address).await;
             let retry_output =
-                invoke_contract_with_source_retries(config, identity, join_args).await?;
+                invoke_contract_with_source_retries(config, contract, identity, join_args).await?;
             parse_tx_result(retry_output)?;
         } else {
             return Err(first_error);
@@ -459,8 +330,191 @@ pub async fn join_single_bot_player(
     join_next_available_local_player(config, table_id, buy_in).await
 }
 
-/// Read on-chain table state via `stellar contract invoke -- get_table`.
+/// Fetch each MPC node's identity key from the committee registry, in node
+/// order. `None` when no registry or member addresses are configured.
+pub async fn get_node_identity_keys(config: &SorobanConfig) -> Result<Option<Vec<String>>, String> {
+    let Some(keys) = get_member_registry_values(config, "get_identity_key").await? else {
+        return Ok(None);
+    };
+    keys.into_iter()
+        .zip(&config.node_member_addresses)
+        .map(|(key, member)| {
+            key.ok_or_else(|| format!("member {} has no registered identity key", member))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// Fetch each MPC node's TEE measurement hash from the committee registry, in
+/// node order. `None` when no registry or member addresses are configured.
+pub async fn get_node_attestation_hashes(
+    config: &SorobanConfig,
+) -> Result<Option<Vec<String>>, String> {
+    let Some(hashes) = get_member_registry_values(config, "get_attestation_hash").await? else {
+        return Ok(None);
+    };
+    hashes
+        .into_iter()
+        .zip(&config.node_member_addresses)
+        .map(|(hash, member)| {
+            hash.ok_or_else(|| format!("member {} has no registered attestation hash", member))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// Fetch the current committee epoch's `threshold` from the committee
+/// registry. `None` when no registry is configured or no epoch is open.
+pub async fn get_committee_threshold(config: &SorobanConfig) -> Result<Option<u32>, String> {
+    if !config.is_configured() || config.committee_registry_contract.is_empty() {
+        return Ok(None);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Epoch {
+        threshold: u32,
+    }
+
+    let output = Command::new("stellar")
+        .args([
+            "contract",
+            "invoke",
+            "--id",
+            &config.committee_registry_contract,
+            "--source",
+            &config.secret_key,
+            "--rpc-url",
+            &config.rpc_url,
+            "--network-passphrase",
+            &config.network_passphrase,
+            "--",
+            "get_current_epoch",
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to invoke stellar CLI: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let epoch: Option<Epoch> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("invalid get_current_epoch output: {}", e))?;
+    Ok(epoch.map(|e| e.threshold))
+}
+
+/// Call a per-member `Option<BytesN<32>>` getter on the committee registry
+/// for each node's member address.
+async fn get_member_registry_values(
+    config: &SorobanConfig,
+    function: &str,
+) -> Result<Option<Vec<Option<String>>>, String> {
+    if !config.is_configured()
+        || config.committee_registry_contract.is_empty()
+        || config.node_member_addresses.is_empty()
+    {
+        return Ok(None);
+    }
+
+    let mut values = Vec::with_capacity(config.node_member_addresses.len());
+    for member in &config.node_member_addresses {
+        let output = Command::new("stellar")
+            .args([
+                "contract",
+                "invoke",
+                "--id",
+                &config.committee_registry_contract,
+                "--source",
+                &config.secret_key,
+                "--rpc-url",
+                &config.rpc_url,
+                "--network-passphrase",
+                &config.network_passphrase,
+                "--",
+                function,
+                "--member",
+                member,
+            ])
+            .output()
+            .await
+            .map_err(|e| format!("Failed to invoke stellar CLI: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        let value: Option<String> = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("invalid {} output: {}", function, e))?;
+        values.push(value);
+    }
+    Ok(Some(values))
+}
+
+/// Most tables one `get_table_page` call returns; the contract caps pages here.
+pub const TABLE_PAGE_SIZE: u32 = 50;
+
+/// Lobby listings of `contract`'s tables with on-chain IDs from `start`, at
+/// most `limit` of them, via `stellar contract invoke -- get_table_page`.
+pub async fn get_table_page(
+    config: &SorobanConfig,
+    contract: &str,
+    start: u32,
+    limit: u32,
+) -> Result<Vec<TableSummary>, String> {
+    if !config.is_configured() {
+        return Err("Soroban not configured".to_string());
+    }
+
+    let output = Command::new("stellar")
+        .args([
+            "contract",
+            "invoke",
+            "--id",
+            contract,
+            "--source",
+            &config.secret_key,
+            "--rpc-url",
+            &config.rpc_url,
+            "--network-passphrase",
+            &config.network_passphrase,
+            "--",
+            "get_table_page",
+            "--start",
+            &start.to_string(),
+            "--limit",
+            &limit.to_string(),
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to invoke stellar CLI: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("invalid get_table_page output: {}", e))
+}
+
+/// Read and parse on-chain table state.
+pub async fn get_table(config: &SorobanConfig, table_id: u32) -> Result<TableState, String> {
+    TableState::from_json(&read_table_view(config, table_id, "get_table").await?)
+}
+
+/// Read how the table's last settled hand was paid out.
+pub async fn get_last_settlement(
+    config: &SorobanConfig,
+    table_id: u32,
+) -> Result<Settlement, String> {
+    Settlement::from_json(&read_table_view(config, table_id, "get_last_settlement").await?)
+}
+
+/// Read the spectator-safe view of a table via `stellar contract invoke --
+/// get_public_table`, as the raw JSON the CLI prints.
 pub async fn get_table_state(config: &SorobanConfig, table_id: u32) -> Result<String, String> {
+    read_table_view(config, table_id, "get_public_table").await
+}
+
+/// Invoke the table view `function` for `table_id` and return its raw JSON.
+async fn read_table_view(
+    config: &SorobanConfig,
+    table_id: u32,
+    function: &str,
+) -> Result<String, String> {
     if !config.is_configured() {
         return Err("Soroban not configured".to_string());
     }
@@ -471,7 +525,7 @@ pub async fn get_table_state(config: &SorobanConfig, table_id: u32) -> Result<St
             "contract",
             "invoke",
             "--id",
-            &config.poker_table_contract,
+            config.table_contract(table_id),
             "--source",
             &config.secret_key,
             "--rpc-url",
@@ -479,7 +533,7 @@ pub async fn get_table_state(config: &SorobanConfig, table_id: u32) -> Result<St
             "--network-passphrase",
             &config.network_passphrase,
             "--",
-            "get_table",
+            function,
             "--table_id",
             &onchain_table_id.to_string(),
         ])
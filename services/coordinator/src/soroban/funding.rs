@@ -0,0 +1,333 @@
+//! Funding of the accounts the coordinator signs with.
+//!
+//! The committee account pays for every proof submission and the local
+//! player identities (`PLAYERn_ADDRESS`) buy into solo tables, so both run
+//! dry on long-lived test deployments. The funding manager reads their
+//! native balances over RPC every `FUNDING_CHECK_INTERVAL_SECS` and, on test
+//! networks, tops up any account below `FUNDING_MIN_BALANCE_XLM` from
+//! friendbot. On mainnet it only reports balances: nothing is ever topped
+//! up, whatever `FRIENDBOT_URL` says.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use stellar_xdr::curr::{
+    AccountId, LedgerEntryData, LedgerKey, LedgerKeyAccount, Limits, PublicKey, ReadXdr, Uint256,
+    WriteXdr,
+};
+
+use super::SorobanConfig;
+use crate::events::Rpc;
+
+const MAINNET_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+const FUTURENET_PASSPHRASE: &str = "Test SDF Future Network ; October 2022";
+const STANDALONE_PASSPHRASE: &str = "Standalone Network ; February 2017";
+
+const STROOPS_PER_XLM: i64 = 10_000_000;
+const DEFAULT_MIN_BALANCE_XLM: i64 = 100;
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 300;
+
+static FUNDING: OnceLock<FundingManager> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Futurenet,
+    Local,
+    Other,
+}
+
+impl Network {
+    fn from_config(config: &SorobanConfig) -> Network {
+        match config.network_passphrase.as_str() {
+            MAINNET_PASSPHRASE => Network::Mainnet,
+            _ if is_local_rpc(&config.rpc_url) => Network::Local,
+            TESTNET_PASSPHRASE => Network::Testnet,
+            FUTURENET_PASSPHRASE => Network::Futurenet,
+            STANDALONE_PASSPHRASE => Network::Local,
+            _ => Network::Other,
+        }
+    }
+
+    fn default_friendbot(self) -> Option<&'static str> {
+        match self {
+            Network::Testnet => Some("https://friendbot.stellar.org"),
+            Network::Futurenet => Some("https://friendbot-futurenet.stellar.org"),
+            Network::Local => Some("http://localhost:8000/friendbot"),
+            Network::Mainnet | Network::Other => None,
+        }
+    }
+}
+
+fn is_local_rpc(rpc_url: &str) -> bool {
+    rpc_url.contains("localhost:8000") || rpc_url.contains("127.0.0.1:8000")
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AccountFunding {
+    /// `committee` or the `PLAYERn` the address is configured as.
+    pub role: String,
+    pub address: String,
+    /// Native balance; `None` until first read. Accounts that do not exist
+    /// yet read as zero.
+    pub balance_stroops: Option<i64>,
+    pub last_top_up_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FundingStatus {
+    pub network: Network,
+    /// Where top-ups come from; `None` when they are off.
+    pub friendbot_url: Option<String>,
+    pub min_balance_stroops: i64,
+    pub accounts: Vec<AccountFunding>,
+    pub last_checked_at: Option<u64>,
+}
+
+struct FundingManager {
+    rpc: Rpc,
+    client: reqwest::Client,
+    status: Mutex<FundingStatus>,
+}
+
+/// Set up the funding manager for the committee and local player accounts
+/// and start its balance checks. Does nothing unless Soroban is configured.
+pub fn init(config: &SorobanConfig) -> Result<(), String> {
+    if !config.is_configured() {
+        return Ok(());
+    }
+    let network = Network::from_config(config);
+    let configured_url = std::env::var("FRIENDBOT_URL")
+        .ok()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    let friendbot_url = match network {
+        Network::Mainnet => {
+            if configured_url.is_some() {
+                tracing::warn!("FRIENDBOT_URL ignored on mainnet");
+            }
+            None
+        }
+        _ => configured_url.or_else(|| network.default_friendbot().map(str::to_string)),
+    };
+    let min_balance_xlm = match std::env::var("FUNDING_MIN_BALANCE_XLM") {
+        Ok(raw) if !raw.trim().is_empty() => raw
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .filter(|xlm| *xlm >= 0)
+            .ok_or_else(|| "FUNDING_MIN_BALANCE_XLM must be a whole number of XLM".to_string())?,
+        _ => DEFAULT_MIN_BALANCE_XLM,
+    };
+    let interval_secs = std::env::var("FUNDING_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS);
+
+    let mut accounts = vec![account("committee", config.committee_address()?)];
+    for (idx, (address, _)) in config.player_identities.iter().enumerate() {
+        accounts.push(account(&format!("player{}", idx + 1), address.clone()));
+    }
+    match &friendbot_url {
+        Some(url) => tracing::info!(
+            "Funding: {:?}, topping up {} accounts below {} XLM from {}",
+            network,
+            accounts.len(),
+            min_balance_xlm,
+            url
+        ),
+        None => tracing::info!(
+            "Funding: {:?}, balances reported only — no friendbot",
+            network
+        ),
+    }
+
+    FUNDING
+        .set(FundingManager {
+            rpc: Rpc::new(&config.rpc_url),
+            client: reqwest::Client::new(),
+            status: Mutex::new(FundingStatus {
+                network,
+                friendbot_url,
+                min_balance_stroops: min_balance_xlm.saturating_mul(STROOPS_PER_XLM),
+                accounts,
+                last_checked_at: None,
+            }),
+        })
+        .map_err(|_| "funding manager already initialized".to_string())?;
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            check_all().await;
+        }
+    });
+    Ok(())
+}
+
+fn account(role: &str, address: String) -> AccountFunding {
+    AccountFunding {
+        role: role.to_string(),
+        address,
+        balance_stroops: None,
+        last_top_up_at: None,
+        error: None,
+    }
+}
+
+/// The latest balances and top-ups; `None` when Soroban is not configured.
+pub fn status() -> Option<FundingStatus> {
+    FUNDING.get().map(|manager| manager.status().clone())
+}
+
+impl FundingManager {
+    fn status(&self) -> std::sync::MutexGuard<'_, FundingStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update(&self, address: &str, f: impl FnOnce(&mut AccountFunding)) {
+        let mut status = self.status();
+        if let Some(account) = status.accounts.iter_mut().find(|a| a.address == address) {
+            f(account);
+        }
+    }
+
+    async fn read_balance(&self, address: &str) -> Result<i64, String> {
+        let result = native_balance(&self.rpc, address).await;
+        self.update(address, |account| match &result {
+            Ok(balance) => {
+                account.balance_stroops = Some(*balance);
+                account.error = None;
+            }
+            Err(e) => account.error = Some(e.clone()),
+        });
+        result
+    }
+
+    async fn friendbot(&self, address: &str) -> Result<(), String> {
+        let Some(base) = self.status().friendbot_url.clone() else {
+            return Ok(());
+        };
+        let resp = self
+            .client
+            .get(&base)
+            .query(&[("addr", address)])
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(|e| format!("friendbot request failed: {}", e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(format!("friendbot returned {}: {}", status, body.trim()));
+        }
+        tracing::info!("friendbot topped up {}", address);
+        self.update(address, |account| {
+            account.last_top_up_at = Some(now_unix_secs())
+        });
+        Ok(())
+    }
+
+    /// Top up `address` if it is below the minimum balance, and re-read it.
+    async fn ensure_funded(&self, address: &str) {
+        let min_balance = self.status().min_balance_stroops;
+        match self.read_balance(address).await {
+            Ok(balance) if balance >= min_balance => return,
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!("funding: failed to read balance of {}: {}", address, e);
+                return;
+            }
+        }
+        if let Err(e) = self.friendbot(address).await {
+            tracing::warn!("friendbot top-up failed for {}: {}", address, e);
+            self.update(address, |account| account.error = Some(e));
+            return;
+        }
+        let _ = self.read_balance(address).await;
+    }
+}
+
+async fn check_all() {
+    let Some(manager) = FUNDING.get() else {
+        return;
+    };
+    let addresses: Vec<String> = manager
+        .status()
+        .accounts
+        .iter()
+        .map(|a| a.address.clone())
+        .collect();
+    for address in addresses {
+        manager.ensure_funded(&address).await;
+    }
+    manager.status().last_checked_at = Some(now_unix_secs());
+}
+
+/// Top up `address` if it is low. A no-op without a friendbot (mainnet).
+pub async fn ensure_funded(address: &str) {
+    if let Some(manager) = FUNDING.get() {
+        manager.ensure_funded(address).await;
+    }
+}
+
+/// Top up `address` now, e.g. after a transfer failed for lack of funds.
+pub async fn top_up(address: &str) {
+    let Some(manager) = FUNDING.get() else {
+        return;
+    };
+    if let Err(e) = manager.friendbot(address).await {
+        tracing::warn!("friendbot top-up failed for {}: {}", address, e);
+        return;
+    }
+    let _ = manager.read_balance(address).await;
+}
+
+#[derive(Deserialize)]
+struct LedgerEntries {
+    #[serde(default)]
+    entries: Option<Vec<LedgerEntryResult>>,
+}
+
+#[derive(Deserialize)]
+struct LedgerEntryResult {
+    xdr: String,
+}
+
+/// The account's native balance in stroops, or zero if it does not exist.
+async fn native_balance(rpc: &Rpc, address: &str) -> Result<i64, String> {
+    let public_key = stellar_strkey::ed25519::PublicKey::from_string(address)
+        .map_err(|e| format!("invalid account address {}: {:?}", address, e))?;
+    let key = LedgerKey::Account(LedgerKeyAccount {
+        account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(public_key.0))),
+    })
+    .to_xdr_base64(Limits::none())
+    .map_err(|e| format!("encoding account key: {}", e))?;
+    let result: LedgerEntries = rpc
+        .call("getLedgerEntries", json!({ "keys": [key] }))
+        .await?;
+    let Some(entry) = result.entries.unwrap_or_default().into_iter().next() else {
+        return Ok(0);
+    };
+    match LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none()) {
+        Ok(LedgerEntryData::Account(account)) => Ok(account.balance),
+        Ok(_) => Err("getLedgerEntries returned a non-account entry".to_string()),
+        Err(e) => Err(format!("invalid account entry: {}", e)),
+    }
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
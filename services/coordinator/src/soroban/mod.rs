@@ -5,6 +5,7 @@
 //! pattern as `mpc.rs` for co-noir subprocess execution.
 
 mod actions;
+pub mod funding;
 mod proofs;
 
 pub use actions::*;
@@ -14,15 +15,75 @@ use ed25519_dalek::SigningKey;
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 
+/// Name of the namespace served by `POKER_TABLE_CONTRACT`.
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// A further poker-table contract the coordinator serves, e.g. another
+/// operator's deployment, stakes tier or contract version.
+///
+/// Coordinator table IDs from `table_id_base` up to the next namespace's base
+/// are this contract's tables, on-chain ID `table_id - table_id_base`; IDs
+/// below every base belong to `POKER_TABLE_CONTRACT`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TableNamespace {
+    pub name: String,
+    pub contract: String,
+    pub table_id_base: u32,
+}
+
+/// Parse `POKER_TABLE_NAMESPACES`: comma-separated `name=CONTRACT@base`.
+fn parse_table_namespaces(raw: &str) -> Result<Vec<TableNamespace>, String> {
+    let mut namespaces: Vec<TableNamespace> = Vec::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, rest) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("namespace '{}' is not name=CONTRACT@base", entry))?;
+        let (contract, base) = rest
+            .split_once('@')
+            .ok_or_else(|| format!("namespace '{}' is not name=CONTRACT@base", entry))?;
+        let name = name.trim().to_string();
+        let table_id_base: u32 = base
+            .trim()
+            .parse()
+            .map_err(|_| format!("namespace '{}' has invalid base '{}'", name, base))?;
+        if name.is_empty() || name == DEFAULT_NAMESPACE {
+            return Err(format!("invalid namespace name '{}'", name));
+        }
+        if table_id_base == 0 {
+            return Err(format!("namespace '{}' needs a base above 0", name));
+        }
+        if namespaces
+            .iter()
+            .any(|ns| ns.name == name || ns.table_id_base == table_id_base)
+        {
+            return Err(format!("namespace '{}' repeats a name or base", name));
+        }
+        namespaces.push(TableNamespace {
+            name,
+            contract: contract.trim().to_string(),
+            table_id_base,
+        });
+    }
+    namespaces.sort_by_key(|ns| ns.table_id_base);
+    Ok(namespaces)
+}
+
 /// Configuration for Soroban interactions.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SorobanConfig {
     pub rpc_url: String,
     pub secret_key: String,
+    /// Contract of the default namespace.
     pub poker_table_contract: String,
+    /// Further contracts, by ascending `table_id_base`.
+    pub table_namespaces: Vec<TableNamespace>,
     pub network_passphrase: String,
     pub onchain_table_id: Option<u32>,
     pub player_identities: Vec<(String, String)>,
+    /// Committee registry holding the MPC nodes' identity keys.
+    pub committee_registry_contract: String,
+    /// Registry member address of each MPC node, in node order.
+    pub node_member_addresses: Vec<String>,
 }
 
 impl SorobanConfig {
@@ -48,6 +109,10 @@ impl SorobanConfig {
                 .unwrap_or_else(|_| "test_secret".to_string()),
             poker_table_contract: std::env::var("POKER_TABLE_CONTRACT")
                 .unwrap_or_else(|_| String::new()),
+            table_namespaces: parse_table_namespaces(
+                &std::env::var("POKER_TABLE_NAMESPACES").unwrap_or_default(),
+            )
+            .expect("invalid POKER_TABLE_NAMESPACES"),
             network_passphrase: std::env::var("NETWORK_PASSPHRASE")
                 .unwrap_or_else(|_| "Test SDF Network ; September 2015".to_string()),
             onchain_table_id: std::env::var("ONCHAIN_TABLE_ID")
@@ -55,6 +120,14 @@ impl SorobanConfig {
                 .or_else(|| std::env::var("TABLE_ID").ok())
                 .and_then(|s| s.parse().ok()),
             player_identities,
+            committee_registry_contract: std::env::var("COMMITTEE_REGISTRY_CONTRACT")
+                .unwrap_or_default(),
+            node_member_addresses: std::env::var("MPC_NODE_ADDRESSES")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
         }
     }
 
@@ -62,15 +135,50 @@ impl SorobanConfig {
         !self.poker_table_contract.is_empty() && self.secret_key != "test_secret"
     }
 
-    /// Derive the Stellar public address (G...) from the committee secret key (S...).
-    pub fn committee_address(&self) -> Result<String, String> {
+    /// The committee's Ed25519 key, from its secret key (S...).
+    pub fn committee_signing_key(&self) -> Result<SigningKey, String> {
         let sk = stellar_strkey::ed25519::PrivateKey::from_string(&self.secret_key)
             .map_err(|e| format!("invalid committee secret key: {:?}", e))?;
-        let signing_key = SigningKey::from_bytes(&sk.0);
-        let public_key = signing_key.verifying_key().to_bytes();
+        Ok(SigningKey::from_bytes(&sk.0))
+    }
+
+    /// Derive the Stellar public address (G...) from the committee secret key (S...).
+    pub fn committee_address(&self) -> Result<String, String> {
+        let public_key = self.committee_signing_key()?.verifying_key().to_bytes();
         Ok(stellar_strkey::ed25519::PublicKey(public_key).to_string())
     }
 
+    /// The namespace `table_id` falls in; `None` for the default one.
+    fn namespace_for_table(&self, table_id: u32) -> Option<&TableNamespace> {
+        self.table_namespaces
+            .iter()
+            .rev()
+            .find(|ns| ns.table_id_base <= table_id)
+    }
+
+    pub fn namespace(&self, name: &str) -> Option<&TableNamespace> {
+        self.table_namespaces.iter().find(|ns| ns.name == name)
+    }
+
+    /// The poker-table contract holding `table_id`.
+    pub fn table_contract(&self, table_id: u32) -> &str {
+        self.namespace_for_table(table_id)
+            .map_or(&self.poker_table_contract, |ns| &ns.contract)
+    }
+
+    /// The coordinator table ID of `contract`'s table `onchain_id`, if the
+    /// contract is one this coordinator serves.
+    pub fn coordinator_table_id(&self, contract: &str, onchain_id: u32) -> Option<u32> {
+        if contract == self.poker_table_contract {
+            return Some(onchain_id);
+        }
+        let ns = self
+            .table_namespaces
+            .iter()
+            .find(|ns| ns.contract == contract)?;
+        ns.table_id_base.checked_add(onchain_id)
+    }
+
     pub(crate) fn identity_for_player(&self, player_address: &str) -> Option<&str> {
         self.player_identities
             .iter()
@@ -101,6 +209,7 @@ fn is_transient_invoke_error(output: &std::process::Output) -> bool {
 
 pub(crate) async fn invoke_contract_with_retries(
     config: &SorobanConfig,
+    contract: &str,
     contract_args: Vec<String>,
 ) -> Result<std::process::Output, String> {
     let mut last_output: Option<std::process::Output> = None;
@@ -110,7 +219,7 @@ pub(crate) async fn invoke_contract_with_retries(
             "contract".to_string(),
             "invoke".to_string(),
             "--id".to_string(),
-            config.poker_table_contract.clone(),
+            contract.to_string(),
             "--source".to_string(),
             config.secret_key.clone(),
             "--rpc-url".to_string(),
@@ -158,6 +267,9 @@ pub(crate) async fn invoke_contract_with_retries(
 }
 
 pub(crate) fn resolve_onchain_table_id(config: &SorobanConfig, table_id: u32) -> u32 {
+    if let Some(ns) = config.namespace_for_table(table_id) {
+        return table_id - ns.table_id_base;
+    }
     if table_id == 0 {
         config.onchain_table_id.unwrap_or(0)
     } else {
@@ -167,6 +279,7 @@ pub(crate) fn resolve_onchain_table_id(config: &SorobanConfig, table_id: u32) ->
 
 pub(crate) async fn invoke_contract_with_source(
     config: &SorobanConfig,
+    contract: &str,
     source: &str,
     contract_args: Vec<String>,
 ) -> Result<std::process::Output, String> {
@@ -174,7 +287,7 @@ pub(crate) async fn invoke_contract_with_source(
         "contract".to_string(),
         "invoke".to_string(),
         "--id".to_string(),
-        config.poker_table_contract.clone(),
+        contract.to_string(),
         "--source".to_string(),
         source.to_string(),
         "--rpc-url".to_string(),
@@ -194,6 +307,7 @@ pub(crate) async fn invoke_contract_with_source(
 
 pub(crate) async fn invoke_contract_with_source_retries(
     config: &SorobanConfig,
+    contract: &str,
     source: &str,
     contract_args: Vec<String>,
 ) -> Result<std::process::Output, String> {
@@ -201,7 +315,8 @@ pub(crate) async fn invoke_contract_with_source_retries(
     let mut last_output: Option<std::process::Output> = None;
 
     for attempt in 1..=MAX_RETRIES {
-        let output = invoke_contract_with_source(config, source, contract_args.clone()).await?;
+        let output =
+            invoke_contract_with_source(config, contract, source, contract_args.clone()).await?;
         if output.status.success() {
             return Ok(output);
         }
@@ -228,22 +343,6 @@ pub(crate) async fn invoke_contract_with_source_retries(
     last_output.ok_or_else(|| "stellar invoke failed before any attempt completed".to_string())
 }
 
-pub(crate) fn parse_i128_value(value: &serde_json::Value) -> Option<i128> {
-    match value {
-        serde_json::Value::String(s) => s.parse::<i128>().ok(),
-        serde_json::Value::Number(n) => n.as_i64().map(|v| v as i128),
-        _ => None,
-    }
-}
-
-pub(crate) fn parse_u32_value(value: &serde_json::Value) -> Option<u32> {
-    match value {
-        serde_json::Value::String(s) => s.parse::<u32>().ok(),
-        serde_json::Value::Number(n) => n.as_u64().and_then(|v| u32::try_from(v).ok()),
-        _ => None,
-    }
-}
-
 /// Parse the tx hash from stellar CLI output.
 pub(crate) fn parse_tx_result(output: std::process::Output) -> Result<String, String> {
     let stdout = String::from_utf8_lossy(&output.stdout);
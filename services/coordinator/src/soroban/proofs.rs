@@ -1,7 +1,8 @@
-use std::str::FromStr;
-
-use ark_bn254::Fr;
-use ark_ff::{BigInteger, PrimeField};
+use poker_table_types::GamePhase;
+use proof_format::{
+    convert_keccak_proof_to_soroban, field_to_bytes32_hex, fields_to_bytes32_json,
+    public_inputs_to_hex,
+};
 
 use super::{
     invoke_contract_with_retries, parse_tx_result, resolve_onchain_table_id, SorobanConfig,
@@ -15,6 +16,7 @@ pub async fn submit_deal_proof(
     public_inputs: &[String],
     deck_root: &str,
     hand_commitments: &[String],
+    dealt_indices: &[u32],
 ) -> Result<String, String> {
     if !config.is_configured() {
         tracing::warn!("Soroban not configured, skipping deal proof submission");
@@ -30,6 +32,8 @@ pub async fn submit_deal_proof(
     let pi_hex = public_inputs_to_hex(public_inputs)?;
     let deck_root_hex = field_to_bytes32_hex(deck_root)?;
     let commitments_hex_json = fields_to_bytes32_json(hand_commitments)?;
+    let dealt_json = serde_json::to_string(dealt_indices)
+        .map_err(|e| format!("Failed to serialize dealt indices: {}", e))?;
 
     tracing::info!(
         "Soroban deal proof: raw_bytes={}, converted_bytes={}, public_inputs_count={}, pi_hex_bytes={}, deck_root_hex={}, commitments_json={}",
@@ -43,6 +47,7 @@ pub async fn submit_deal_proof(
 
     let output = invoke_contract_with_retries(
         config,
+        config.table_contract(table_id),
         vec![
             "commit_deal".to_string(),
             "--table_id".to_string(),
@@ -54,7 +59,7 @@ pub async fn submit_deal_proof(
             "--hand_commitments".to_string(),
             commitments_hex_json,
             "--dealt_indices".to_string(),
-            "[]".to_string(),
+            dealt_json,
             "--proof".to_string(),
             proof_hex,
             "--public_inputs".to_string(),
@@ -67,18 +72,10 @@ pub async fn submit_deal_proof(
 }
 
 async fn maybe_start_hand_for_deal(config: &SorobanConfig, table_id: u32) -> Result<(), String> {
-    let state_raw = super::get_table_state(config, table_id).await?;
-    let state: serde_json::Value = serde_json::from_str(&state_raw)
-        .map_err(|e| format!("failed to parse on-chain table state: {}", e))?;
-
-    let phase = state
-        .get("phase")
-        .and_then(|v| v.as_str())
-        .ok_or("missing phase in on-chain table state")?;
-
+    let phase = super::get_table(config, table_id).await?.phase;
     match phase {
-        "Dealing" => return Ok(()),
-        "Waiting" | "Settlement" => {}
+        GamePhase::Dealing => return Ok(()),
+        GamePhase::Waiting | GamePhase::Settlement => {}
         _ => {
             return Err(format!(
                 "table {} not ready for new deal; current phase is {}",
@@ -95,6 +92,7 @@ async fn maybe_start_hand_for_deal(config: &SorobanConfig, table_id: u32) -> Res
     );
     let output = invoke_contract_with_retries(
         config,
+        config.table_contract(table_id),
         vec![
             "start_hand".to_string(),
             "--table_id".to_string(),
@@ -131,6 +129,7 @@ pub async fn submit_reveal_proof(
 
     let output = invoke_contract_with_retries(
         config,
+        config.table_contract(table_id),
         vec![
             "reveal_board".to_string(),
             "--table_id".to_string(),
@@ -175,6 +174,7 @@ pub async fn submit_showdown_proof(
 
     let output = invoke_contract_with_retries(
         config,
+        config.table_contract(table_id),
         vec![
             "submit_showdown".to_string(),
             "--table_id".to_string(),
@@ -183,8 +183,6 @@ pub async fn submit_showdown_proof(
             committee_addr,
             "--hole_cards".to_string(),
             hole_cards_json,
-            "--salts".to_string(),
-            "[]".to_string(),
             "--proof".to_string(),
             proof_hex,
             "--public_inputs".to_string(),
@@ -195,236 +193,3 @@ pub async fn submit_showdown_proof(
 
     parse_tx_result(output)
 }
-
-/// Convert co-noir keccak proof format to the Soroban/BB UltraHonk verifier format.
-///
-/// co-noir keccak format (variable size, raw G1 coordinates):
-///   [pairing_points(16 Fr), G1_raw(8×2), sumcheck_uni(log_n×8),
-///    sumcheck_eval(41), gemini_fold_raw((log_n-1)×2), gemini_eval(log_n),
-///    shplonk_raw(1×2), kzg_raw(1×2)]
-///
-/// Soroban verifier format (fixed 458 fields, limb-encoded G1):
-///   [pairing_points(16), G1_limb(8×4), sumcheck_uni(28×8),
-///    sumcheck_eval(41), gemini_fold_limb(27×4), gemini_eval(28),
-///    shplonk_limb(1×4), kzg_limb(1×4), log_n(1)]
-fn convert_keccak_proof_to_soroban(proof_bytes: &[u8]) -> Result<Vec<u8>, String> {
-    const FIELD_SIZE: usize = 32;
-    const SOROBAN_PROOF_FIELDS: usize = 458;
-    const SOROBAN_PROOF_BYTES: usize = SOROBAN_PROOF_FIELDS * FIELD_SIZE;
-    const CONST_PROOF_SIZE_LOG_N: usize = 28;
-    const BATCHED_RELATION_PARTIAL_LENGTH: usize = 8;
-    const NUMBER_OF_ENTITIES: usize = 41;
-    const NUM_G1_WIRE_POINTS: usize = 8;
-    const NUM_FINAL_G1: usize = 2;
-    const PAIRING_POINTS_SIZE: usize = 16;
-
-    if proof_bytes.len() % FIELD_SIZE != 0 {
-        return Err(format!(
-            "proof not 32-byte aligned: {} bytes",
-            proof_bytes.len()
-        ));
-    }
-
-    let num_fields = proof_bytes.len() / FIELD_SIZE;
-
-    // Derive log_n from proof size:
-    // total = PAIRING + G1_RAW + SUMCHECK + EVALS + GEMINI_FOLD + GEMINI_EVAL + FINAL_G1
-    // total = 16 + 16 + log_n*8 + 41 + (log_n-1)*2 + log_n + 4
-    // total = 77 + log_n*8 + (log_n-1)*2 + log_n
-    // total = 77 + 11*log_n - 2
-    // total = 75 + 11*log_n
-    // log_n = (total - 75) / 11
-    let log_n_calc = num_fields as i64 - 75;
-    if log_n_calc <= 0 || log_n_calc % 11 != 0 {
-        return Err(format!(
-            "cannot derive log_n from proof size: {} fields (remainder {})",
-            num_fields,
-            log_n_calc % 11
-        ));
-    }
-    let log_n = (log_n_calc / 11) as usize;
-
-    // Verify derived log_n is reasonable
-    if log_n < 10 || log_n > 25 {
-        return Err(format!(
-            "derived log_n={} out of reasonable range [10,25]",
-            log_n
-        ));
-    }
-
-    // Verify total
-    let expected = PAIRING_POINTS_SIZE
-        + NUM_G1_WIRE_POINTS * 2
-        + log_n * BATCHED_RELATION_PARTIAL_LENGTH
-        + NUMBER_OF_ENTITIES
-        + (log_n - 1) * 2
-        + log_n
-        + NUM_FINAL_G1 * 2;
-    if num_fields != expected {
-        return Err(format!(
-            "proof size mismatch: got {} fields, expected {} (log_n={})",
-            num_fields, expected, log_n
-        ));
-    }
-
-    tracing::info!(
-        "Proof conversion: {} fields, derived log_n={}",
-        num_fields,
-        log_n
-    );
-
-    let mut out = Vec::with_capacity(SOROBAN_PROOF_BYTES);
-    let mut offset = 0usize;
-
-    // Helper: read 32 bytes from proof
-    let read_fr = |off: &mut usize| -> &[u8] {
-        let start = *off;
-        *off += FIELD_SIZE;
-        &proof_bytes[start..start + FIELD_SIZE]
-    };
-
-    // Helper: split a 32-byte big-endian coordinate into (lo136, hi) limb pair
-    fn coord_to_limbs(coord: &[u8]) -> ([u8; 32], [u8; 32]) {
-        let mut lo = [0u8; 32];
-        let mut hi = [0u8; 32];
-        lo[15..].copy_from_slice(&coord[15..]); // lower 17 bytes
-        hi[17..].copy_from_slice(&coord[..15]); // upper 15 bytes
-        (lo, hi)
-    }
-
-    // Helper: convert raw G1 (x, y) to limb-encoded (x_lo, x_hi, y_lo, y_hi)
-    let convert_g1_raw_to_limb = |off: &mut usize, out: &mut Vec<u8>| {
-        let x = &proof_bytes[*off..*off + FIELD_SIZE];
-        *off += FIELD_SIZE;
-        let y = &proof_bytes[*off..*off + FIELD_SIZE];
-        *off += FIELD_SIZE;
-        let (x_lo, x_hi) = coord_to_limbs(x);
-        let (y_lo, y_hi) = coord_to_limbs(y);
-        out.extend_from_slice(&x_lo);
-        out.extend_from_slice(&x_hi);
-        out.extend_from_slice(&y_lo);
-        out.extend_from_slice(&y_hi);
-    };
-
-    // 1) Pairing point object: 16 Fr values — these are limb-encoded accumulator
-    //    coordinates in both formats, copy directly
-    for _ in 0..PAIRING_POINTS_SIZE {
-        out.extend_from_slice(read_fr(&mut offset));
-    }
-
-    // 2) 8 G1 wire commitments: convert from raw (x,y) to limb (x_lo,x_hi,y_lo,y_hi)
-    for _ in 0..NUM_G1_WIRE_POINTS {
-        convert_g1_raw_to_limb(&mut offset, &mut out);
-    }
-
-    // 3) Sumcheck univariates: log_n rounds → pad to CONST_PROOF_SIZE_LOG_N
-    for _ in 0..log_n {
-        for _ in 0..BATCHED_RELATION_PARTIAL_LENGTH {
-            out.extend_from_slice(read_fr(&mut offset));
-        }
-    }
-    let pad_rounds = CONST_PROOF_SIZE_LOG_N - log_n;
-    out.extend(vec![
-        0u8;
-        pad_rounds
-            * BATCHED_RELATION_PARTIAL_LENGTH
-            * FIELD_SIZE
-    ]);
-
-    // 4) Sumcheck evaluations: 41 Fr (copy directly)
-    for _ in 0..NUMBER_OF_ENTITIES {
-        out.extend_from_slice(read_fr(&mut offset));
-    }
-
-    // 5) Gemini fold comms: (log_n-1) raw G1 → limb-encode, pad to 27
-    for _ in 0..(log_n - 1) {
-        convert_g1_raw_to_limb(&mut offset, &mut out);
-    }
-    let pad_gemini = (CONST_PROOF_SIZE_LOG_N - 1) - (log_n - 1);
-    out.extend(vec![0u8; pad_gemini * 4 * FIELD_SIZE]);
-
-    // 6) Gemini a evaluations: log_n Fr → pad to CONST_PROOF_SIZE_LOG_N
-    for _ in 0..log_n {
-        out.extend_from_slice(read_fr(&mut offset));
-    }
-    out.extend(vec![0u8; (CONST_PROOF_SIZE_LOG_N - log_n) * FIELD_SIZE]);
-
-    // 7) Shplonk Q and KZG quotient: 2 raw G1 → limb-encode
-    for _ in 0..NUM_FINAL_G1 {
-        convert_g1_raw_to_limb(&mut offset, &mut out);
-    }
-
-    // 8) Append log_n as final field (big-endian u256)
-    let mut log_n_field = [0u8; 32];
-    log_n_field[31] = log_n as u8;
-    if log_n > 255 {
-        log_n_field[30] = (log_n >> 8) as u8;
-    }
-    out.extend_from_slice(&log_n_field);
-
-    // Verify we consumed all input (except preamble already skipped)
-    if offset != proof_bytes.len() {
-        return Err(format!(
-            "proof conversion: consumed {} of {} bytes ({} fields leftover)",
-            offset,
-            proof_bytes.len(),
-            (proof_bytes.len() - offset) / FIELD_SIZE
-        ));
-    }
-
-    if out.len() != SOROBAN_PROOF_BYTES {
-        return Err(format!(
-            "converted proof size mismatch: got {} bytes, expected {}",
-            out.len(),
-            SOROBAN_PROOF_BYTES
-        ));
-    }
-
-    tracing::info!(
-        "Proof converted: {} bytes (keccak, log_n={}) → {} bytes (soroban)",
-        proof_bytes.len(),
-        log_n,
-        out.len()
-    );
-
-    Ok(out)
-}
-
-/// Convert a BN254 field element (decimal string) to a 32-byte big-endian hex string.
-/// This is needed because Soroban `BytesN<32>` expects hex-encoded bytes, but
-/// MPC proof outputs are decimal field element strings.
-fn field_to_bytes32_hex(field_str: &str) -> Result<String, String> {
-    let fr = Fr::from_str(field_str)
-        .map_err(|_| format!("failed to parse field element: '{}'", field_str))?;
-    let bytes = fr.into_bigint().to_bytes_be();
-    // Pad to exactly 32 bytes (should already be, but be safe)
-    if bytes.len() > 32 {
-        return Err(format!("field element too large: {} bytes", bytes.len()));
-    }
-    let mut padded = vec![0u8; 32 - bytes.len()];
-    padded.extend_from_slice(&bytes);
-    Ok(hex::encode(padded))
-}
-
-/// Convert a slice of field element strings to a JSON array of hex-encoded BytesN<32>.
-fn fields_to_bytes32_json(fields: &[String]) -> Result<String, String> {
-    let hex_strings: Vec<String> = fields
-        .iter()
-        .map(|f| field_to_bytes32_hex(f))
-        .collect::<Result<Vec<_>, _>>()?;
-    serde_json::to_string(&hex_strings).map_err(|e| format!("failed to serialize hex array: {}", e))
-}
-
-/// Convert proof public inputs (field element strings) to concatenated 32-byte big-endian
-/// representations suitable for the on-chain verifier.
-fn public_inputs_to_hex(public_inputs: &[String]) -> Result<String, String> {
-    let mut all_bytes = Vec::with_capacity(public_inputs.len() * 32);
-    for pi in public_inputs {
-        let fr = Fr::from_str(pi).map_err(|_| format!("failed to parse public input: '{}'", pi))?;
-        let bytes = fr.into_bigint().to_bytes_be();
-        let mut padded = vec![0u8; 32 - bytes.len()];
-        padded.extend_from_slice(&bytes);
-        all_bytes.extend_from_slice(&padded);
-    }
-    Ok(hex::encode(all_bytes))
-}
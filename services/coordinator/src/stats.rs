@@ -0,0 +1,147 @@
+//! Per-table and per-player statistics for lobby display and leaderboards.
+//!
+//! Hands and VPIP are counted from the deals and player actions the
+//! coordinator drives. Pots are attributed from the contract's report of
+//! the hand's settlement (`get_last_settlement`): what settled it and what
+//! each player was paid.
+
+use std::collections::{HashMap, HashSet};
+
+use poker_table_types::{Settlement, SettlementSource};
+
+/// Circuits whose proof latency is tracked.
+pub(crate) const PROOF_KINDS: [&str; 3] = ["deal", "reveal", "showdown"];
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlayerStats {
+    pub hands_dealt: u64,
+    pub vpip_hands: u64,
+    pub pots_won: u64,
+    pub chips_won: i128,
+    pub biggest_pot_won: i128,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LatencyStats {
+    pub count: u64,
+    pub total_ms: u64,
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+}
+
+impl LatencyStats {
+    fn record(&mut self, ms: u64) {
+        self.min_ms = if self.count == 0 {
+            ms
+        } else {
+            self.min_ms.min(ms)
+        };
+        self.max_ms = self.max_ms.max(ms);
+        self.count += 1;
+        self.total_ms += ms;
+        self.last_ms = ms;
+    }
+
+    pub fn avg_ms(&self) -> u64 {
+        self.total_ms.checked_div(self.count).unwrap_or(0)
+    }
+}
+
+/// Tracking for the hand currently in progress.
+#[derive(Clone, Debug, Default)]
+struct HandTracker {
+    hand_number: u32,
+    vpip: HashSet<String>,
+    settled: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TableStats {
+    pub hands_played: u64,
+    pub showdowns: u64,
+    pub fold_wins: u64,
+    pub timeout_settlements: u64,
+    pub biggest_pot: i128,
+    pub total_pot_volume: i128,
+    pub players: HashMap<String, PlayerStats>,
+    pub proof_latencies: HashMap<&'static str, LatencyStats>,
+    current_hand: Option<HandTracker>,
+}
+
+impl TableStats {
+    pub fn hands_settled(&self) -> u64 {
+        self.showdowns + self.fold_wins + self.timeout_settlements
+    }
+
+    pub fn record_hand_dealt(&mut self, hand_number: u32, players: &[String]) {
+        self.hands_played += 1;
+        for player in players {
+            self.players.entry(player.clone()).or_default().hands_dealt += 1;
+        }
+        self.current_hand = Some(HandTracker {
+            hand_number,
+            ..HandTracker::default()
+        });
+    }
+
+    /// Count a voluntary preflop contribution towards the player's VPIP.
+    pub fn record_action(&mut self, player: &str, action: &str, local_phase: &str) {
+        if local_phase != "preflop" {
+            return;
+        }
+        if !matches!(action, "call" | "bet" | "raise" | "allin" | "all_in") {
+            return;
+        }
+        let Some(hand) = self.current_hand.as_mut() else {
+            return;
+        };
+        if hand.vpip.insert(player.to_string()) {
+            self.players
+                .entry(player.to_string())
+                .or_default()
+                .vpip_hands += 1;
+        }
+    }
+
+    pub fn record_proof_latency(&mut self, kind: &'static str, ms: u64) {
+        self.proof_latencies.entry(kind).or_default().record(ms);
+    }
+
+    /// Attribute the pot from the table's last settlement. Returns false
+    /// when it is not the current hand's, i.e. the hand has not settled yet,
+    /// or it was already counted.
+    pub fn record_settlement(&mut self, settlement: &Settlement) -> bool {
+        let Some(hand) = self.current_hand.as_mut() else {
+            return false;
+        };
+        if hand.settled || hand.hand_number != settlement.hand_number {
+            return false;
+        }
+        hand.settled = true;
+
+        self.total_pot_volume += settlement.pot;
+        self.biggest_pot = self.biggest_pot.max(settlement.pot);
+
+        match settlement.source {
+            SettlementSource::Showdown => self.showdowns += 1,
+            SettlementSource::Fold => self.fold_wins += 1,
+            SettlementSource::Timeout => {
+                // Emergency refunds return chips; nobody wins the pot.
+                self.timeout_settlements += 1;
+                return true;
+            }
+        }
+
+        for (address, won) in settlement.winnings() {
+            if won <= 0 {
+                continue;
+            }
+            let entry = self.players.entry(address.to_string()).or_default();
+            entry.pots_won += 1;
+            entry.chips_won += won;
+            entry.biggest_pot_won = entry.biggest_pot_won.max(won);
+        }
+        true
+    }
+}
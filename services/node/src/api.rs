@@ -1,28 +1,48 @@
 //! HTTP API handlers for the MPC node.
 
 use axum::{
-    extract::{Path, State},
+    body::Bytes,
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-
-use crate::private_table::{self, DealPreparation, RevealPreparation, ShowdownPreparation};
-use crate::session::{self, MpcSessionState, SessionStatus};
+use tracing::Instrument;
+
+use crate::circuits;
+use crate::conoir;
+use crate::crs;
+use crate::drain;
+use crate::private_table::{
+    self, DealPreparation, PrepareError, RevealPreparation, RevealedContribution,
+    ShowdownPreparation, ShowdownSeats,
+};
+use crate::proof_archive::{ArchivedProof, ArchivedProofSummary, ProofArchive};
+use crate::session::{self, MpcSessionState, SessionStatus, StageTiming};
+use crate::workdir;
 use crate::NodeState;
+use mpc_auth::attestation::Attestation;
+use mpc_auth::identity::IdentityProof;
+use mpc_auth::lookup::PermLookupAuthorization;
 
 #[derive(Deserialize)]
 pub struct PrepareDealRequest {
     pub players: Vec<String>,
     pub circuit_dir: String,
+    /// On-chain hand number; required when contributions are VRF-derived.
+    #[serde(default)]
+    pub hand_number: Option<u64>,
+    /// The hand's on-chain nonce, which every proof for it commits to.
+    pub hand_nonce: u32,
 }
 
 #[derive(Deserialize)]
 pub struct PrepareRevealRequest {
     pub circuit_dir: String,
+    pub hand_nonce: u32,
     pub previously_used_indices: Vec<u32>,
     pub deck_root: String,
 }
@@ -30,10 +50,13 @@ pub struct PrepareRevealRequest {
 #[derive(Deserialize)]
 pub struct PrepareShowdownRequest {
     pub circuit_dir: String,
+    pub hand_nonce: u32,
     pub board_indices: Vec<u32>,
     pub num_active_players: u32,
     pub hand_commitments: Vec<String>,
     pub deck_root: String,
+    #[serde(flatten)]
+    pub seats: ShowdownSeats,
 }
 
 #[derive(Deserialize)]
@@ -43,10 +66,21 @@ pub struct DispatchSharesRequest {
     pub circuit_name: String,
 }
 
+#[derive(Deserialize)]
+pub struct RedispatchSharesRequest {
+    pub share_set_id: String,
+    pub proof_session_id: String,
+    pub circuit_name: String,
+    /// Recipient parties to send to again.
+    pub parties: Vec<u32>,
+}
+
 #[derive(Deserialize)]
 pub struct SharesRequest {
     pub circuit_name: String,
-    pub share_data: String, // base64-encoded share file
+    pub share_data: String, // base64-encoded share file, or sealed box when `encrypted`
+    #[serde(default)]
+    pub encrypted: bool,
     pub source_party_id: u32,
     pub total_parties: u32,
 }
@@ -54,6 +88,8 @@ pub struct SharesRequest {
 #[derive(Deserialize)]
 pub struct PermLookupRequest {
     pub indices: Vec<u32>,
+    #[serde(default)]
+    pub authorization: Option<PermLookupAuthorization>,
 }
 
 #[derive(Serialize)]
@@ -62,10 +98,41 @@ pub struct PermLookupResponse {
     pub salts: Vec<String>,
 }
 
+/// Most lookups a single batch may carry: one per seat.
+const MAX_BATCH_LOOKUPS: usize = 6;
+
+#[derive(Deserialize)]
+pub struct PermLookupBatchRequest {
+    pub lookups: Vec<PermLookupRequest>,
+}
+
+/// Result of one lookup in a batch; `error` is set instead of the values
+/// when that lookup was refused.
+#[derive(Serialize, Default)]
+pub struct PermLookupBatchResult {
+    pub mapped_indices: Vec<u32>,
+    pub salts: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PermLookupBatchResponse {
+    pub results: Vec<PermLookupBatchResult>,
+}
+
 #[derive(Serialize)]
 pub struct StatusResponse {
     pub session_id: String,
     pub status: String,
+    /// Current stage without failure detail.
+    pub stage: &'static str,
+    pub stage_started_at_ms: Option<u64>,
+    pub stages: Vec<StageTiming>,
+    /// Source parties whose share fragments this node holds, sorted.
+    pub fragments_received: Vec<u32>,
+    /// Node clock at response time, for computing stage ages without clock sync.
+    pub server_time_ms: u64,
 }
 
 #[derive(Deserialize)]
@@ -74,6 +141,17 @@ pub struct GenerateRequest {
     pub crs_path: String,
 }
 
+fn prepare_error(e: PrepareError) -> (StatusCode, String) {
+    match e {
+        PrepareError::Invalid(input) => {
+            tracing::warn!("Rejected prepare input {}: {}", input.field, input.reason);
+            let body = serde_json::to_string(&input).unwrap_or(input.reason);
+            (StatusCode::UNPROCESSABLE_ENTITY, body)
+        }
+        PrepareError::Failed(e) => (StatusCode::BAD_REQUEST, e),
+    }
+}
+
 /// POST /table/:id/prepare-deal
 ///
 /// Node prepares its own private contribution and returns a share-set handle.
@@ -82,33 +160,63 @@ pub async fn post_prepare_deal(
     Path(table_id): Path<u32>,
     Json(req): Json<PrepareDealRequest>,
 ) -> Result<Json<DealPreparation>, (StatusCode, String)> {
-    let mut seen = HashSet::new();
-    for player in &req.players {
-        if player.trim().is_empty() {
-            return Err((StatusCode::BAD_REQUEST, "empty player address".to_string()));
-        }
-        if !seen.insert(player) {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                "duplicate player address".to_string(),
-            ));
-        }
-    }
-
     let mut tables = state.tables.write().await;
     let prepared = private_table::prepare_deal(
         table_id,
         state.node_id,
         &req.players,
+        req.hand_number,
+        req.hand_nonce,
         &req.circuit_dir,
+        &state.contribution_keys,
         &mut tables,
     )
     .await
-    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    .map_err(prepare_error)?;
+
+    // Persist before handing out the share set: a hand must never proceed
+    // on a contribution that would not survive a restart.
+    let contribution = tables
+        .get(&table_id)
+        .and_then(|t| t.contribution())
+        .ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "missing local party contribution".to_string(),
+        ))?;
+    state
+        .contribution_store
+        .save(table_id, contribution)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     Ok(Json(prepared))
 }
 
+/// POST /table/:id/reveal-contribution
+///
+/// Disclose this node's deal contribution after the hand for audit against
+/// its signed commitment. The contribution is retired and deleted.
+pub async fn post_reveal_contribution(
+    State(state): State<NodeState>,
+    Path(table_id): Path<u32>,
+) -> Result<Json<RevealedContribution>, (StatusCode, String)> {
+    let mut tables = state.tables.write().await;
+    let revealed = private_table::reveal_contribution(
+        table_id,
+        state.node_id,
+        state.contribution_keys.vrf.as_ref(),
+        &mut tables,
+    )
+    .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    state
+        .contribution_store
+        .remove(table_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    tracing::info!("Revealed contribution for table {} for audit", table_id);
+    Ok(Json(revealed))
+}
+
 /// POST /table/:id/prepare-reveal/:phase
 ///
 /// Node prepares reveal contribution shares and returns a share-set handle.
@@ -122,13 +230,14 @@ pub async fn post_prepare_reveal(
         table_id,
         state.node_id,
         &phase,
+        req.hand_nonce,
         &req.previously_used_indices,
         &req.deck_root,
         &req.circuit_dir,
         &mut tables,
     )
     .await
-    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    .map_err(prepare_error)?;
 
     Ok(Json(prepared))
 }
@@ -145,15 +254,17 @@ pub async fn post_prepare_showdown(
     let prepared = private_table::prepare_showdown(
         table_id,
         state.node_id,
+        req.hand_nonce,
         &req.board_indices,
         req.num_active_players,
         &req.hand_commitments,
         &req.deck_root,
+        &req.seats,
         &req.circuit_dir,
         &mut tables,
     )
     .await
-    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    .map_err(prepare_error)?;
 
     Ok(Json(prepared))
 }
@@ -161,58 +272,170 @@ pub async fn post_prepare_showdown(
 /// POST /table/:id/dispatch-shares
 ///
 /// Node sends this source party's per-recipient shares directly to MPC peers.
+/// The share set stays bound to `proof_session_id`, so repeating the call
+/// re-sends the same shares.
 pub async fn post_dispatch_shares(
     State(state): State<NodeState>,
     Path(table_id): Path<u32>,
     Json(req): Json<DispatchSharesRequest>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    if req.share_set_id.trim().is_empty() {
+    dispatch_shares(
+        &state,
+        table_id,
+        &req.share_set_id,
+        &req.proof_session_id,
+        &req.circuit_name,
+        None,
+    )
+    .await
+}
+
+/// POST /table/:id/redispatch-shares
+///
+/// Re-send a dispatched share set to the given parties, e.g. a peer that
+/// restarted before proof generation. Idempotent: recipients ignore
+/// fragments they already hold.
+pub async fn post_redispatch_shares(
+    State(state): State<NodeState>,
+    Path(table_id): Path<u32>,
+    Json(req): Json<RedispatchSharesRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if req.parties.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "missing parties".to_string()));
+    }
+    tracing::info!(
+        "Re-dispatching share set {} for session {} to parties {:?}",
+        req.share_set_id,
+        req.proof_session_id,
+        req.parties
+    );
+    dispatch_shares(
+        &state,
+        table_id,
+        &req.share_set_id,
+        &req.proof_session_id,
+        &req.circuit_name,
+        Some(&req.parties),
+    )
+    .await
+}
+
+async fn dispatch_shares(
+    state: &NodeState,
+    table_id: u32,
+    share_set_id: &str,
+    proof_session_id: &str,
+    circuit_name: &str,
+    parties: Option<&[u32]>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if share_set_id.trim().is_empty() {
         return Err((StatusCode::BAD_REQUEST, "missing share_set_id".to_string()));
     }
-    if req.proof_session_id.trim().is_empty() {
+    if proof_session_id.trim().is_empty() {
         return Err((
             StatusCode::BAD_REQUEST,
             "missing proof_session_id".to_string(),
         ));
     }
-    if req.circuit_name.trim().is_empty() {
+    if circuit_name.trim().is_empty() {
         return Err((StatusCode::BAD_REQUEST, "missing circuit_name".to_string()));
     }
 
-    let share_data_by_party = {
-        let tables = state.tables.read().await;
-        private_table::clone_share_set(table_id, &req.share_set_id, &tables)
-            .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+    let mut share_data_by_party = {
+        let mut tables = state.tables.write().await;
+        private_table::bind_share_set(
+            table_id,
+            share_set_id,
+            proof_session_id,
+            circuit_name,
+            &mut tables,
+        )
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?
     };
+    if let Some(parties) = parties {
+        if let Some(party) = parties
+            .iter()
+            .find(|p| !share_data_by_party.contains_key(p))
+        {
+            return Err((StatusCode::BAD_REQUEST, format!("unknown party {}", party)));
+        }
+        share_data_by_party.retain(|party, _| parties.contains(party));
+    }
 
     private_table::dispatch_share_payloads(
-        &req.proof_session_id,
-        &req.circuit_name,
+        proof_session_id,
+        circuit_name,
         &state.peer_http_endpoints,
         state.node_id,
         &share_data_by_party,
+        state.auth.as_ref().map(|a| &a.signer),
+        &state.share_cipher,
     )
     .await
     .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
 
-    {
-        let mut tables = state.tables.write().await;
-        private_table::remove_share_set(table_id, &req.share_set_id, &mut tables)
-            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
-    }
-
     Ok(StatusCode::OK)
 }
 
 /// POST /table/:table_id/perm-lookup
 ///
 /// Look up permutation mappings and salts for given deck positions.
-/// Used by the coordinator to resolve hole cards after a deal.
+/// Used by the coordinator to resolve hole cards after a deal. Every call is
+/// authorized, rate-limited and audit-logged by the node's `PermLookupGuard`.
 pub async fn post_perm_lookup(
     State(state): State<NodeState>,
     Path(table_id): Path<u32>,
     Json(req): Json<PermLookupRequest>,
 ) -> Result<Json<PermLookupResponse>, (StatusCode, String)> {
+    let tables = state.tables.read().await;
+    perm_lookup(&state, table_id, &req, &tables).map(Json)
+}
+
+/// POST /table/:table_id/perm-lookup-batch
+///
+/// Several independently authorized lookups in one request, typically one
+/// per player. Each lookup is checked on its own and a refused one does not
+/// fail the rest of the batch.
+pub async fn post_perm_lookup_batch(
+    State(state): State<NodeState>,
+    Path(table_id): Path<u32>,
+    Json(req): Json<PermLookupBatchRequest>,
+) -> Result<Json<PermLookupBatchResponse>, (StatusCode, String)> {
+    if req.lookups.is_empty() || req.lookups.len() > MAX_BATCH_LOOKUPS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("batch must hold 1 to {} lookups", MAX_BATCH_LOOKUPS),
+        ));
+    }
+
+    let tables = state.tables.read().await;
+    let results = req
+        .lookups
+        .iter()
+        .map(
+            |lookup| match perm_lookup(&state, table_id, lookup, &tables) {
+                Ok(resp) => PermLookupBatchResult {
+                    mapped_indices: resp.mapped_indices,
+                    salts: resp.salts,
+                    error: None,
+                },
+                Err((status, reason)) => PermLookupBatchResult {
+                    error: Some(format!("HTTP {}: {}", status.as_u16(), reason)),
+                    ..Default::default()
+                },
+            },
+        )
+        .collect();
+
+    Ok(Json(PermLookupBatchResponse { results }))
+}
+
+fn perm_lookup(
+    state: &NodeState,
+    table_id: u32,
+    req: &PermLookupRequest,
+    tables: &HashMap<u32, private_table::PrivateTableState>,
+) -> Result<PermLookupResponse, (StatusCode, String)> {
     if req.indices.is_empty() {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -220,15 +443,195 @@ pub async fn post_perm_lookup(
         ));
     }
 
-    let tables = state.tables.read().await;
-    let mapped_indices = private_table::perm_lookup(table_id, &req.indices, &tables)
+    state.perm_lookup_guard.check(
+        state.node_id,
+        table_id,
+        &req.indices,
+        req.authorization.as_ref(),
+    )?;
+
+    let mapped_indices = private_table::perm_lookup(table_id, &req.indices, tables)
         .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
-    let salts = private_table::salt_lookup(table_id, &req.indices, &tables)
+    let salts = private_table::salt_lookup(table_id, &req.indices, tables)
         .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
 
-    Ok(Json(PermLookupResponse {
+    Ok(PermLookupResponse {
         mapped_indices,
         salts,
+    })
+}
+
+#[derive(Serialize)]
+pub struct CircuitsResponse {
+    pub circuit_dir: String,
+    /// Noir version prefix artifacts must carry to be accepted.
+    pub noir_version_prefix: &'static str,
+    pub artifacts: Vec<circuits::ArtifactInfo>,
+}
+
+/// GET /circuits
+///
+/// Installed circuit artifacts with their hashes and Noir versions.
+pub async fn get_circuits() -> Result<Json<CircuitsResponse>, (StatusCode, String)> {
+    let dir = circuits::circuit_dir();
+    let artifacts =
+        circuits::list_artifacts(&dir).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(Json(CircuitsResponse {
+        circuit_dir: dir.display().to_string(),
+        noir_version_prefix: conoir::expected_noir_version_prefix(),
+        artifacts,
+    }))
+}
+
+/// PUT /circuits/:name
+///
+/// Replace a compiled circuit artifact with the request body. Rejected when
+/// the artifact fails the co-noir compatibility check, or while a proof
+/// session for that circuit is still running.
+pub async fn put_circuit(
+    State(state): State<NodeState>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Result<Json<circuits::ArtifactInfo>, (StatusCode, String)> {
+    circuits::validate_circuit_name(&name).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let sessions = state.sessions.read().await;
+    for session in sessions.values() {
+        let session = session.read().await;
+        if session.circuit_name == name && !session.is_finished() {
+            return Err((
+                StatusCode::CONFLICT,
+                format!(
+                    "proof session {} is still using circuit {}",
+                    session.session_id, name
+                ),
+            ));
+        }
+    }
+
+    let info = circuits::install_artifact(&circuits::circuit_dir(), &name, &body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    tracing::info!(
+        "Installed circuit {} (sha256 {}, noir {})",
+        name,
+        info.sha256,
+        info.noir_version.as_deref().unwrap_or("?")
+    );
+    Ok(Json(info))
+}
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+    pub status: &'static str,
+    pub node_id: u32,
+    /// Hex Ed25519 identity key, as registered in the committee registry.
+    pub identity_key: String,
+    pub crs: crs::CrsState,
+    pub draining: bool,
+    /// TEE attestation bound to `identity_key`; absent outside TEE mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<Attestation>,
+}
+
+/// GET /health
+///
+/// 503 until the CRS has been checked and is usable.
+pub async fn get_health(State(state): State<NodeState>) -> (StatusCode, Json<HealthResponse>) {
+    let crs = crs::status();
+    let (code, status) = if crs.is_usable() {
+        (StatusCode::OK, "ok")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "crs_unavailable")
+    };
+    (
+        code,
+        Json(HealthResponse {
+            status,
+            node_id: state.node_id,
+            identity_key: state.contribution_keys.signer.public_key_hex(),
+            crs: crs.state,
+            draining: drain::is_draining(&state),
+            attestation: state.attestation.as_deref().cloned(),
+        }),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct DrainRequest {
+    pub draining: bool,
+}
+
+#[derive(Serialize)]
+pub struct DrainResponse {
+    pub draining: bool,
+    /// Proof sessions still running; safe to stop once this reaches zero.
+    pub active_sessions: usize,
+}
+
+/// GET /admin/drain
+pub async fn get_drain(State(state): State<NodeState>) -> Json<DrainResponse> {
+    Json(DrainResponse {
+        draining: drain::is_draining(&state),
+        active_sessions: drain::active_sessions(&state).await,
+    })
+}
+
+/// POST /admin/drain
+///
+/// Enter or leave drain mode.
+pub async fn post_drain(
+    State(state): State<NodeState>,
+    Json(req): Json<DrainRequest>,
+) -> Json<DrainResponse> {
+    let was_draining = state
+        .draining
+        .swap(req.draining, std::sync::atomic::Ordering::Relaxed);
+    if was_draining != req.draining {
+        if req.draining {
+            tracing::warn!("Drain mode on — refusing new prepare/generate requests");
+        } else {
+            tracing::info!("Drain mode off — accepting new work");
+        }
+    }
+    get_drain(State(state)).await
+}
+
+/// GET /crs
+pub async fn get_crs() -> Json<crs::CrsStatus> {
+    Json(crs::status())
+}
+
+/// POST /crs/repair
+///
+/// Download the CRS again from `CRS_URL` in the background; poll `GET /crs`.
+pub async fn post_crs_repair() -> Result<StatusCode, (StatusCode, String)> {
+    crs::start_repair().map_err(|e| (StatusCode::CONFLICT, e))?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[derive(Deserialize)]
+pub struct IdentityChallengeRequest {
+    pub challenge: String,
+}
+
+/// POST /identity/challenge
+///
+/// Prove possession of the identity key by signing a caller-chosen challenge.
+pub async fn post_identity_challenge(
+    State(state): State<NodeState>,
+    Json(req): Json<IdentityChallengeRequest>,
+) -> Result<Json<IdentityProof>, (StatusCode, String)> {
+    if req.challenge.is_empty() || req.challenge.len() > 256 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "challenge must be 1-256 bytes".to_string(),
+        ));
+    }
+    let signer = &state.contribution_keys.signer;
+    Ok(Json(IdentityProof {
+        node_id: state.node_id,
+        identity_key: signer.public_key_hex(),
+        signature: signer.sign_challenge(state.node_id, &req.challenge),
     }))
 }
 
@@ -246,22 +649,42 @@ pub async fn post_shares(
             "total_parties must be > 0".to_string(),
         ));
     }
+    let share_data = state
+        .share_cipher
+        .open(&req.share_data, req.encrypted)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let table_id = workdir::table_id_from_session(&session_id).ok_or((
+        StatusCode::BAD_REQUEST,
+        format!("session id '{}' does not name a table", session_id),
+    ))?;
 
     let session_lock = {
         let mut sessions = state.sessions.write().await;
+        // A finished session whose ID is reused belongs to an earlier run.
+        let stale = match sessions.get(&session_id) {
+            Some(existing) => existing.read().await.is_finished(),
+            None => false,
+        };
+        if stale {
+            if let Some(old) = sessions.remove(&session_id) {
+                workdir::remove_session_dir(old.read().await.work_dir.clone());
+            }
+        }
         if let Some(existing) = sessions.get(&session_id) {
             existing.clone()
         } else {
-            let work_dir = tempfile::tempdir()
-                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("tmpdir: {}", e)))?;
-            let work_path = work_dir.keep();
+            workdir::check_session_quota(table_id).map_err(quota_error)?;
+            let work_dir = workdir::create_session_dir(table_id, &session_id)
+                .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
             let session =
-                MpcSessionState::new(session_id.clone(), req.circuit_name.clone(), work_path);
+                MpcSessionState::new(session_id.clone(), req.circuit_name.clone(), work_dir);
             let lock = Arc::new(RwLock::new(session));
             sessions.insert(session_id.clone(), lock.clone());
             lock
         }
     };
+    workdir::check_disk_quota(table_id).map_err(quota_error)?;
 
     let mut session = session_lock.write().await;
     if session.circuit_name != req.circuit_name {
@@ -276,7 +699,7 @@ pub async fn post_shares(
 
     session::receive_share_fragment(
         &mut session,
-        &req.share_data,
+        &share_data,
         req.source_party_id,
         req.total_parties,
     )
@@ -299,62 +722,82 @@ pub async fn post_generate(
         .ok_or((StatusCode::NOT_FOUND, "session not found".to_string()))?
         .clone();
 
+    if !crs::is_usable() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("CRS not usable: {:?}", crs::status().state),
+        ));
+    }
+
     let mut session = session_lock.write().await;
-    let expected_total_parties = session.expected_total_parties.ok_or((
-        StatusCode::BAD_REQUEST,
-        "no share fragments received".to_string(),
-    ))?;
-    let partial_share_paths = session
-        .partial_share_paths
-        .iter()
-        .map(|(source, path)| (*source, path.clone()))
-        .collect::<Vec<_>>();
-    session.status = SessionStatus::WitnessGenerating;
+    if session.expected_total_parties.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "no share fragments received".to_string(),
+        ));
+    }
+    session.set_status(SessionStatus::MergingShares);
 
     let sid = session_id.clone();
     let circuit_dir = req.circuit_dir.clone();
-    let circuit_name = session.circuit_name.clone();
     let work_dir = session.work_dir.clone();
     let node_id = state.node_id;
     let party_config = state.party_config_path.clone();
     let crs_path = req.crs_path.clone();
+    let proof_archive = state.proof_archive.clone();
 
     let session_lock_bg = session_lock.clone();
     drop(session); // release write lock before spawning
 
-    tokio::spawn(async move {
-        let result = session::run_proof_generation(
-            sid.clone(),
-            circuit_dir,
-            circuit_name,
-            work_dir.clone(),
-            node_id,
-            partial_share_paths,
-            expected_total_parties,
-            party_config,
-            crs_path,
-        )
-        .await;
-
-        let mut session = session_lock_bg.write().await;
-        match result {
-            Ok((proof_bytes, public_inputs)) => {
-                let proof_path = work_dir.join("proof.bin");
-                if let Err(e) = std::fs::write(&proof_path, &proof_bytes) {
-                    session.status = SessionStatus::Failed(format!("write proof: {}", e));
-                    return;
+    // Keep the request's table/session span on the background task.
+    tokio::spawn(
+        async move {
+            let result = session::run_proof_generation(
+                session_lock_bg.clone(),
+                circuit_dir,
+                node_id,
+                party_config,
+                crs_path,
+            )
+            .await;
+
+            let mut session = session_lock_bg.write().await;
+            match result {
+                Ok((proof_bytes, public_inputs)) => {
+                    let proof_path = work_dir.join("proof.bin");
+                    if let Err(e) = std::fs::write(&proof_path, &proof_bytes) {
+                        session.set_status(SessionStatus::Failed(format!("write proof: {}", e)));
+                        return;
+                    }
+                    // Archive before reporting completion: serving the proof
+                    // deletes the work dir holding the shares we hash.
+                    if let Some(archive) = &proof_archive {
+                        if let Err(e) = archive
+                            .record(
+                                &sid,
+                                &session.circuit_name,
+                                &work_dir,
+                                &proof_bytes,
+                                &public_inputs,
+                            )
+                            .await
+                        {
+                            tracing::warn!("[{}] Failed to archive proof: {}", sid, e);
+                        }
+                    }
+                    session.proof_path = Some(proof_path);
+                    session.public_inputs = Some(public_inputs);
+                    session.set_status(SessionStatus::Complete);
+                    tracing::info!("[{}] Proof generation complete (node {})", sid, node_id);
+                }
+                Err(e) => {
+                    session.set_status(SessionStatus::Failed(e.clone()));
+                    tracing::error!("[{}] Proof generation failed: {}", sid, e);
                 }
-                session.proof_path = Some(proof_path);
-                session.public_inputs = Some(public_inputs);
-                session.status = SessionStatus::Complete;
-                tracing::info!("[{}] Proof generation complete (node {})", sid, node_id);
-            }
-            Err(e) => {
-                session.status = SessionStatus::Failed(e.clone());
-                tracing::error!("[{}] Proof generation failed: {}", sid, e);
             }
         }
-    });
+        .in_current_span(),
+    );
 
     Ok(StatusCode::ACCEPTED)
 }
@@ -368,29 +811,70 @@ pub async fn get_status(
     let session_lock = sessions.get(&session_id).ok_or(StatusCode::NOT_FOUND)?;
     let session = session_lock.read().await;
 
+    let mut fragments_received: Vec<u32> = session.partial_share_paths.keys().copied().collect();
+    fragments_received.sort_unstable();
     let status_str = match &session.status {
-        SessionStatus::SharesReceived => "shares_received".to_string(),
-        SessionStatus::WitnessGenerating => "witness_generating".to_string(),
-        SessionStatus::ProofGenerating => "proof_generating".to_string(),
-        SessionStatus::Complete => "complete".to_string(),
         SessionStatus::Failed(e) => format!("failed: {}", e),
+        status => status.stage().to_string(),
     };
 
     Ok(Json(StatusResponse {
         session_id: session.session_id.clone(),
         status: status_str,
+        stage: session.status.stage(),
+        stage_started_at_ms: session.stages.last().map(|s| s.started_at_ms),
+        stages: session.stages.clone(),
+        fragments_received,
+        server_time_ms: session::now_unix_ms(),
     }))
 }
 
+/// Most co-noir output returned by `/session/:id/logs`; older output is cut.
+const MAX_SESSION_LOG_BYTES: usize = 1024 * 1024;
+
+/// GET /session/:id/logs
+///
+/// co-noir stdout/stderr captured for the session, newest output last.
+/// Available until the session is cleaned up.
+pub async fn get_session_logs(
+    State(state): State<NodeState>,
+    Path(session_id): Path<String>,
+) -> Result<String, (StatusCode, String)> {
+    let work_dir = {
+        let sessions = state.sessions.read().await;
+        let session_lock = sessions
+            .get(&session_id)
+            .ok_or((StatusCode::NOT_FOUND, "session not found".to_string()))?;
+        let work_dir = session_lock.read().await.work_dir.clone();
+        work_dir
+    };
+    let bytes = match tokio::fs::read(work_dir.join(conoir::SESSION_LOG_FILE)).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to read session log: {}", e),
+            ))
+        }
+    };
+    let start = bytes.len().saturating_sub(MAX_SESSION_LOG_BYTES);
+    Ok(String::from_utf8_lossy(&bytes[start..]).into_owned())
+}
+
 /// GET /session/:id/proof
+///
+/// Serving the proof ends the session: its state and work directory are
+/// deleted.
 pub async fn get_proof(
     State(state): State<NodeState>,
     Path(session_id): Path<String>,
 ) -> Result<Json<ProofResponse>, (StatusCode, String)> {
-    let sessions = state.sessions.read().await;
+    let mut sessions = state.sessions.write().await;
     let session_lock = sessions
         .get(&session_id)
-        .ok_or((StatusCode::NOT_FOUND, "session not found".to_string()))?;
+        .ok_or((StatusCode::NOT_FOUND, "session not found".to_string()))?
+        .clone();
     let session = session_lock.read().await;
 
     if session.status != SessionStatus::Complete {
@@ -406,13 +890,95 @@ pub async fn get_proof(
     use base64::Engine;
     let proof_b64 = base64::engine::general_purpose::STANDARD.encode(&proof_bytes);
 
-    Ok(Json(ProofResponse {
+    let response = ProofResponse {
         session_id: session.session_id.clone(),
         proof: proof_b64,
         public_inputs: session.public_inputs.clone().unwrap_or_default(),
+    };
+    workdir::remove_session_dir(session.work_dir.clone());
+    drop(session);
+    sessions.remove(&session_id);
+
+    Ok(Json(response))
+}
+
+const DEFAULT_PROOF_PAGE: usize = 50;
+const MAX_PROOF_PAGE: usize = 500;
+
+#[derive(Deserialize)]
+pub struct ProofListQuery {
+    pub table_id: Option<u32>,
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct ProofListResponse {
+    /// Archived proofs matching the query, across all pages.
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub retention_hours: u64,
+    pub proofs: Vec<ArchivedProofSummary>,
+}
+
+fn proof_archive(state: &NodeState) -> Result<&ProofArchive, (StatusCode, String)> {
+    state.proof_archive.as_ref().ok_or((
+        StatusCode::NOT_FOUND,
+        "proof archive disabled (PROOF_RETENTION_HOURS=0)".to_string(),
+    ))
+}
+
+/// GET /proofs?table_id=&offset=&limit=
+///
+/// Proofs this node helped generate within the retention window, newest
+/// first, without the proof bytes.
+pub async fn get_proofs(
+    State(state): State<NodeState>,
+    Query(query): Query<ProofListQuery>,
+) -> Result<Json<ProofListResponse>, (StatusCode, String)> {
+    let archive = proof_archive(&state)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PROOF_PAGE)
+        .clamp(1, MAX_PROOF_PAGE);
+    let (total, proofs) = archive.list(query.table_id, query.offset, limit);
+    Ok(Json(ProofListResponse {
+        total,
+        offset: query.offset,
+        limit,
+        retention_hours: archive.retention_hours(),
+        proofs,
     }))
 }
 
+/// GET /proofs/:session_id
+///
+/// One archived proof with its public inputs and share hashes.
+pub async fn get_archived_proof(
+    State(state): State<NodeState>,
+    Path(session_id): Path<String>,
+) -> Result<Json<ArchivedProof>, (StatusCode, String)> {
+    proof_archive(&state)?
+        .get(&session_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .map(Json)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            "archived proof not found".to_string(),
+        ))
+}
+
+fn quota_error(e: workdir::QuotaError) -> (StatusCode, String) {
+    let status = match e {
+        workdir::QuotaError::TooManySessions(_) => StatusCode::TOO_MANY_REQUESTS,
+        workdir::QuotaError::DiskQuota { .. } => StatusCode::INSUFFICIENT_STORAGE,
+    };
+    (status, e.to_string())
+}
+
 #[derive(Serialize)]
 pub struct ProofResponse {
     pub session_id: String,
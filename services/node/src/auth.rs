@@ -0,0 +1,223 @@
+//! Signed-request authentication for node routes.
+//!
+//! With `MPC_AUTH_SECRET` set, every route except `/health` requires an
+//! HMAC signature from the coordinator or a peer node (see `mpc-auth`), and
+//! this node signs its own share dispatches to peers with the same secret.
+//!
+//! `perm-lookup` is further gated by [`PermLookupGuard`]: a single-use
+//! coordinator authorization per call, a per-player rate limit, and an audit
+//! log line for every lookup attempt.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use mpc_auth::lookup::{LookupVerifier, PermLookupAuthorization};
+use mpc_auth::{
+    RequestSigner, RequestVerifier, SignedHeaders, NONCE_HEADER, SENDER_HEADER, SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+};
+
+use crate::NodeState;
+
+/// Upper bound on buffered request bodies (share payloads are the largest).
+const MAX_SIGNED_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+const PERM_LOOKUP_WINDOW_SECS: u64 = 60;
+const PERM_LOOKUP_MAX_PER_WINDOW: usize = 30;
+
+/// (table, player) -> lookup timestamps within the rate-limit window.
+type LookupHits = HashMap<(u32, String), Vec<u64>>;
+
+#[derive(Clone)]
+pub struct NodeAuth {
+    pub signer: RequestSigner,
+    verifier: Arc<RequestVerifier>,
+}
+
+impl NodeAuth {
+    /// Load node auth from `MPC_AUTH_SECRET`. `Ok(None)` when unset.
+    pub fn from_env(node_id: u32) -> Result<Option<Self>, String> {
+        Ok(mpc_auth::secret_from_env()?.map(|secret| Self {
+            signer: RequestSigner::new(format!("node-{}", node_id), secret.clone()),
+            verifier: Arc::new(RequestVerifier::new(secret)),
+        }))
+    }
+}
+
+#[derive(Clone)]
+pub struct PermLookupGuard {
+    verifier: Option<Arc<LookupVerifier>>,
+    recent: Arc<Mutex<LookupHits>>,
+}
+
+impl PermLookupGuard {
+    /// Load the coordinator's lookup key from `PERM_LOOKUP_PUBLIC_KEY`.
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Self {
+            verifier: mpc_auth::lookup::verifying_key_from_env()?.map(Arc::new),
+            recent: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    pub fn is_enforced(&self) -> bool {
+        self.verifier.is_some()
+    }
+
+    /// Authorize, rate-limit and audit-log a lookup. Returns the player it is bound to.
+    pub fn check(
+        &self,
+        node_id: u32,
+        table_id: u32,
+        indices: &[u32],
+        authorization: Option<&PermLookupAuthorization>,
+    ) -> Result<String, (StatusCode, String)> {
+        let player = authorization
+            .map(|a| a.player.clone())
+            .unwrap_or_else(|| "<unauthorized>".to_string());
+
+        let result = self.authorize(node_id, table_id, indices, authorization);
+        let result = result.and_then(|_| self.rate_limit(table_id, &player));
+        match &result {
+            Ok(()) => tracing::info!(
+                target: "perm_lookup_audit",
+                "perm-lookup granted table={} player={} indices={:?}",
+                table_id,
+                player,
+                indices
+            ),
+            Err((status, reason)) => tracing::warn!(
+                target: "perm_lookup_audit",
+                "perm-lookup denied table={} player={} indices={:?} status={} reason={}",
+                table_id,
+                player,
+                indices,
+                status.as_u16(),
+                reason
+            ),
+        }
+        result.map(|_| player)
+    }
+
+    fn authorize(
+        &self,
+        node_id: u32,
+        table_id: u32,
+        indices: &[u32],
+        authorization: Option<&PermLookupAuthorization>,
+    ) -> Result<(), (StatusCode, String)> {
+        let Some(verifier) = self.verifier.as_ref() else {
+            return Ok(());
+        };
+        let authorization = authorization.ok_or((
+            StatusCode::UNAUTHORIZED,
+            "missing lookup authorization".to_string(),
+        ))?;
+        verifier
+            .verify(node_id, table_id, indices, authorization)
+            .map_err(|e| (StatusCode::FORBIDDEN, e))
+    }
+
+    fn rate_limit(&self, table_id: u32, player: &str) -> Result<(), (StatusCode, String)> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut recent = self.recent.lock().map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "rate limiter poisoned".to_string(),
+            )
+        })?;
+        recent.retain(|_, hits| {
+            hits.retain(|ts| now.saturating_sub(*ts) < PERM_LOOKUP_WINDOW_SECS);
+            !hits.is_empty()
+        });
+        let hits = recent.entry((table_id, player.to_string())).or_default();
+        if hits.len() >= PERM_LOOKUP_MAX_PER_WINDOW {
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                "perm-lookup rate limit exceeded".to_string(),
+            ));
+        }
+        hits.push(now);
+        Ok(())
+    }
+}
+
+/// Attach auth headers for `url` when signing is enabled.
+pub fn sign_request(
+    builder: reqwest::RequestBuilder,
+    signer: Option<&RequestSigner>,
+    method: &str,
+    url: &str,
+    body: &[u8],
+) -> reqwest::RequestBuilder {
+    let Some(signer) = signer else {
+        return builder;
+    };
+    let path = reqwest::Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_default();
+    signer
+        .sign(method, &path, body)
+        .into_iter()
+        .fold(builder, |b, (name, value)| b.header(name, value))
+}
+
+/// Middleware rejecting unsigned or badly signed requests.
+pub async fn require_signed_request(
+    State(state): State<NodeState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let Some(auth) = state.auth.as_ref() else {
+        return Ok(next.run(request).await);
+    };
+
+    let (parts, body) = request.into_parts();
+    let bytes = to_bytes(body, MAX_SIGNED_BODY_BYTES)
+        .await
+        .map_err(|_| (StatusCode::PAYLOAD_TOO_LARGE, "body too large".to_string()))?;
+
+    let signed = signed_headers(&parts.headers)?;
+    let sender = auth
+        .verifier
+        .verify(parts.method.as_str(), parts.uri.path(), &signed, &bytes)
+        .map_err(|e| {
+            tracing::warn!("Rejected {} {}: {}", parts.method, parts.uri.path(), e);
+            (StatusCode::UNAUTHORIZED, e)
+        })?;
+    tracing::debug!(
+        "Authenticated {} {} from {}",
+        parts.method,
+        parts.uri.path(),
+        sender
+    );
+
+    Ok(next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await)
+}
+
+fn signed_headers(headers: &HeaderMap) -> Result<SignedHeaders<'_>, (StatusCode, String)> {
+    let get = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| (StatusCode::UNAUTHORIZED, format!("missing {} header", name)))
+    };
+    Ok(SignedHeaders {
+        sender: get(SENDER_HEADER)?,
+        timestamp: get(TIMESTAMP_HEADER)?,
+        nonce: get(NONCE_HEADER)?,
+        signature: get(SIGNATURE_HEADER)?,
+    })
+}
@@ -0,0 +1,127 @@
+//! Compiled circuit artifacts on this node.
+//!
+//! Artifacts live at `CIRCUIT_DIR/<name>/target/<name>.json` (default
+//! `./circuits`), the same layout the coordinator's `circuit_dir` points at.
+//! Operators list them with their SHA-256 and `noir_version`, and replace
+//! them over the API so a circuit upgrade can be rolled out to every node
+//! without shell access. Uploads must pass the same `noir_version` check
+//! co-noir calls are guarded by.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::conoir;
+
+const MAX_CIRCUIT_NAME_LEN: usize = 64;
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactInfo {
+    pub name: String,
+    /// Hex SHA-256 of the artifact file.
+    pub sha256: String,
+    pub noir_version: Option<String>,
+    /// Whether co-noir on this node accepts the artifact.
+    pub compatible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn circuit_dir() -> PathBuf {
+    PathBuf::from(std::env::var("CIRCUIT_DIR").unwrap_or_else(|_| "./circuits".to_string()))
+}
+
+fn artifact_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(name).join("target").join(format!("{}.json", name))
+}
+
+/// Reject names that are not a plain circuit directory name.
+pub fn validate_circuit_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.len() > MAX_CIRCUIT_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(format!("invalid circuit name '{}'", name));
+    }
+    Ok(())
+}
+
+/// Describe every compiled artifact under `dir`, sorted by name.
+pub fn list_artifacts(dir: &Path) -> Result<Vec<ArtifactInfo>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+    let mut artifacts = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = artifact_path(dir, &name);
+        if validate_circuit_name(&name).is_err() || !path.is_file() {
+            continue;
+        }
+        let info = match std::fs::read(&path) {
+            Ok(bytes) => inspect(&name, &path, &bytes),
+            Err(e) => ArtifactInfo {
+                name,
+                sha256: String::new(),
+                noir_version: None,
+                compatible: false,
+                error: Some(format!("failed to read artifact: {}", e)),
+            },
+        };
+        artifacts.push(info);
+    }
+    artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(artifacts)
+}
+
+/// Check an uploaded artifact and atomically replace the installed one.
+pub fn install_artifact(dir: &Path, name: &str, bytes: &[u8]) -> Result<ArtifactInfo, String> {
+    validate_circuit_name(name)?;
+    let path = artifact_path(dir, name);
+    let info = inspect(name, &path, bytes);
+    if let Some(error) = &info.error {
+        return Err(error.clone());
+    }
+
+    let target_dir = path.parent().ok_or("artifact path has no parent")?;
+    std::fs::create_dir_all(target_dir)
+        .map_err(|e| format!("failed to create {}: {}", target_dir.display(), e))?;
+    let tmp_path = target_dir.join(format!(".{}.json.upload", name));
+    std::fs::write(&tmp_path, bytes)
+        .map_err(|e| format!("failed to write {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("failed to install {}: {}", path.display(), e))?;
+    Ok(info)
+}
+
+fn inspect(name: &str, path: &Path, bytes: &[u8]) -> ArtifactInfo {
+    let label = path.display().to_string();
+    let mut info = ArtifactInfo {
+        name: name.to_string(),
+        sha256: hex::encode(Sha256::digest(bytes)),
+        noir_version: None,
+        compatible: false,
+        error: None,
+    };
+    let json = match serde_json::from_slice::<serde_json::Value>(bytes) {
+        Ok(json) => json,
+        Err(e) => {
+            info.error = Some(format!(
+                "circuit artifact '{}' is not valid json: {}",
+                label, e
+            ));
+            return info;
+        }
+    };
+    info.noir_version = json
+        .get("noir_version")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    match conoir::artifact_noir_version(&json, &label) {
+        Ok(_) => info.compatible = true,
+        Err(e) => info.error = Some(e),
+    }
+    info
+}
@@ -0,0 +1,371 @@
+//! co-noir backend for share splitting, witness extension and proving.
+//!
+//! All interaction with TACEO's co-noir goes through this module so the rest
+//! of the node deals in circuits, share files and proofs rather than command
+//! lines. It currently drives the `co-noir` CLI; an in-process backend using
+//! the co-snarks crates (`co-noir`, `mpc-core`) replaces these functions
+//! one-for-one once those crates are vendored, since they are not published
+//! to crates.io.
+//!
+//! The sharing protocol is REP3 by default. `MPC_PROTOCOL=shamir` with
+//! `MPC_THRESHOLD=t` selects t-of-n Shamir sharing instead, where n is the
+//! committee size; Shamir multiplication needs an honest majority, so
+//! n >= 2(t-1)+1 (e.g. 2-of-3, 3-of-5).
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::OnceLock;
+
+use tokio::process::Command;
+use tokio::time::{sleep, Duration};
+
+/// Per-session file in the work directory collecting co-noir output.
+pub const SESSION_LOG_FILE: &str = "co-noir.log";
+
+const EXPECTED_NOIR_VERSION_PREFIX: &str = "1.0.0-beta.17";
+const PROOF_ATTEMPTS: u32 = 3;
+const REP3_PARTIES: usize = 3;
+
+static PROTOCOL: OnceLock<(MpcProtocol, usize)> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MpcProtocol {
+    /// Replicated secret sharing among exactly three parties.
+    Rep3,
+    /// Shamir sharing where any `threshold` parties can reconstruct.
+    Shamir { threshold: usize },
+}
+
+impl std::fmt::Display for MpcProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MpcProtocol::Rep3 => write!(f, "REP3"),
+            MpcProtocol::Shamir { threshold } => write!(f, "SHAMIR (threshold {})", threshold),
+        }
+    }
+}
+
+/// Select the sharing protocol from `MPC_PROTOCOL` / `MPC_THRESHOLD` for a
+/// committee of `num_parties`. Must be called once at startup.
+pub fn init_protocol(num_parties: usize) -> Result<MpcProtocol, String> {
+    let protocol = match std::env::var("MPC_PROTOCOL")
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "" | "rep3" => MpcProtocol::Rep3,
+        "shamir" => {
+            let threshold: usize = std::env::var("MPC_THRESHOLD")
+                .map_err(|_| "MPC_THRESHOLD is required with MPC_PROTOCOL=shamir".to_string())?
+                .trim()
+                .parse()
+                .map_err(|e| format!("invalid MPC_THRESHOLD: {}", e))?;
+            MpcProtocol::Shamir { threshold }
+        }
+        other => return Err(format!("unknown MPC_PROTOCOL '{}'", other)),
+    };
+
+    match protocol {
+        MpcProtocol::Rep3 if num_parties != REP3_PARTIES => {
+            return Err(format!(
+                "REP3 needs exactly {} parties, got {}",
+                REP3_PARTIES, num_parties
+            ));
+        }
+        MpcProtocol::Shamir { threshold } => {
+            if threshold < 2 || threshold > num_parties {
+                return Err(format!(
+                    "MPC_THRESHOLD must be between 2 and {}, got {}",
+                    num_parties, threshold
+                ));
+            }
+            if num_parties < 2 * (threshold - 1) + 1 {
+                return Err(format!(
+                    "Shamir {}-of-{} lacks an honest majority (need at least {} parties)",
+                    threshold,
+                    num_parties,
+                    2 * (threshold - 1) + 1
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    PROTOCOL
+        .set((protocol, num_parties))
+        .map_err(|_| "MPC protocol already initialized".to_string())?;
+    Ok(protocol)
+}
+
+/// `--protocol` and related flags for the configured protocol.
+fn protocol_args(cmd: &mut Command) {
+    let (protocol, _) = PROTOCOL
+        .get()
+        .copied()
+        .unwrap_or((MpcProtocol::Rep3, REP3_PARTIES));
+    match protocol {
+        MpcProtocol::Rep3 => {
+            cmd.arg("--protocol").arg("REP3");
+        }
+        MpcProtocol::Shamir { threshold } => {
+            // co-noir takes the polynomial degree, one less than the
+            // number of parties needed to reconstruct.
+            cmd.arg("--protocol")
+                .arg("SHAMIR")
+                .arg("--threshold")
+                .arg((threshold - 1).to_string());
+        }
+    }
+}
+
+/// Compiled circuit artifact path for `circuit_name` under `circuit_dir`.
+pub fn circuit_path(circuit_dir: &str, circuit_name: &str) -> String {
+    format!(
+        "{}/{}/target/{}.json",
+        circuit_dir, circuit_name, circuit_name
+    )
+}
+
+/// Keccak verification key path for `circuit_name` under `circuit_dir`.
+pub fn vk_path(circuit_dir: &str, circuit_name: &str) -> String {
+    format!("{}/{}/target/vk_keccak", circuit_dir, circuit_name)
+}
+
+/// Inputs for an MPC UltraHonk proof over an extended witness share.
+pub struct ProofJob<'a> {
+    pub circuit_path: &'a str,
+    pub witness_path: &'a Path,
+    pub party_config_path: &'a str,
+    pub crs_file: &'a str,
+    pub vk_path: &'a str,
+    pub proof_path: &'a Path,
+    pub public_inputs_path: &'a Path,
+    /// Session log that subprocess output is appended to.
+    pub log_path: &'a Path,
+}
+
+/// Secret-share a partial Prover.toml into one `.shared` file per party in `out_dir`.
+pub async fn split_input(
+    circuit_path: &str,
+    input_path: &Path,
+    out_dir: &Path,
+) -> Result<(), String> {
+    validate_circuit_artifact_compatibility(circuit_path)?;
+    let mut cmd = Command::new("co-noir");
+    cmd.arg("split-input")
+        .arg("--circuit")
+        .arg(circuit_path)
+        .arg("--input")
+        .arg(input_path);
+    protocol_args(&mut cmd);
+    if let Some((MpcProtocol::Shamir { .. }, num_parties)) = PROTOCOL.get() {
+        cmd.arg("--num-parties").arg(num_parties.to_string());
+    }
+    cmd.arg("--out-dir").arg(out_dir);
+    run("split-input", cmd, None, None).await
+}
+
+/// Merge the per-source share fragments into a single input share.
+pub async fn merge_input_shares(
+    circuit_path: &str,
+    party_config_path: &str,
+    fragments: &[PathBuf],
+    out: &Path,
+    node_id: u32,
+    log_path: &Path,
+) -> Result<(), String> {
+    let mut cmd = Command::new("co-noir");
+    cmd.arg("merge-input-shares")
+        .arg("--circuit")
+        .arg(circuit_path);
+    protocol_args(&mut cmd);
+    cmd.arg("--config").arg(party_config_path);
+    for path in fragments {
+        cmd.arg("--inputs").arg(path);
+    }
+    cmd.arg("--out").arg(out);
+    run("merge-input-shares", cmd, Some(node_id), Some(log_path)).await
+}
+
+/// Extend the witness in MPC with the other parties.
+pub async fn generate_witness(
+    circuit_path: &str,
+    input_path: &Path,
+    party_config_path: &str,
+    out: &Path,
+    node_id: u32,
+    log_path: &Path,
+) -> Result<(), String> {
+    let mut cmd = Command::new("co-noir");
+    cmd.arg("generate-witness")
+        .arg("--circuit")
+        .arg(circuit_path)
+        .arg("--input")
+        .arg(input_path);
+    protocol_args(&mut cmd);
+    cmd.arg("--config")
+        .arg(party_config_path)
+        .arg("--out")
+        .arg(out);
+    run("generate-witness", cmd, Some(node_id), Some(log_path)).await
+}
+
+/// Build the proving key and generate a keccak UltraHonk proof in MPC.
+///
+/// Transient socket exhaustion on the MPC network is retried.
+pub async fn build_and_generate_proof(
+    job: &ProofJob<'_>,
+    session_id: &str,
+    node_id: u32,
+) -> Result<(), String> {
+    for attempt in 1..=PROOF_ATTEMPTS {
+        let mut cmd = Command::new("co-noir");
+        cmd.arg("build-and-generate-proof")
+            .arg("--circuit")
+            .arg(job.circuit_path)
+            .arg("--witness")
+            .arg(job.witness_path);
+        protocol_args(&mut cmd);
+        cmd.arg("--config")
+            .arg(job.party_config_path)
+            .arg("--crs")
+            .arg(job.crs_file)
+            .arg("--hasher")
+            .arg("keccak")
+            .arg("--vk")
+            .arg(job.vk_path)
+            .arg("--out")
+            .arg(job.proof_path)
+            .arg("--public-input")
+            .arg(job.public_inputs_path)
+            .arg("--fields-as-json");
+
+        let output = spawn("build-and-generate-proof", cmd, Some(job.log_path)).await?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let is_transient_resource_error =
+            stderr.contains("No buffer space available") || stderr.contains("os error 55");
+        if is_transient_resource_error && attempt < PROOF_ATTEMPTS {
+            tracing::warn!(
+                "[{}] co-noir build-and-generate-proof transient failure on node {} (attempt {}/{}): {}",
+                session_id,
+                node_id,
+                attempt,
+                PROOF_ATTEMPTS,
+                stderr.trim()
+            );
+            sleep(Duration::from_millis((attempt as u64) * 500)).await;
+            continue;
+        }
+        return Err(failure("build-and-generate-proof", Some(node_id), &output));
+    }
+
+    Err(format!(
+        "co-noir build-and-generate-proof failed after retries (node {})",
+        node_id
+    ))
+}
+
+async fn spawn(step: &str, mut cmd: Command, log_path: Option<&Path>) -> Result<Output, String> {
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("failed to spawn co-noir {}: {}", step, e))?;
+    if let Some(log_path) = log_path {
+        if let Err(e) = append_log(log_path, step, &output) {
+            tracing::warn!("Failed to write {}: {}", log_path.display(), e);
+        }
+    }
+    Ok(output)
+}
+
+async fn run(
+    step: &str,
+    cmd: Command,
+    node_id: Option<u32>,
+    log_path: Option<&Path>,
+) -> Result<(), String> {
+    let output = spawn(step, cmd, log_path).await?;
+    if !output.status.success() {
+        return Err(failure(step, node_id, &output));
+    }
+    Ok(())
+}
+
+fn append_log(log_path: &Path, step: &str, output: &Output) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)?;
+    writeln!(file, "=== co-noir {} ({}) ===", step, output.status)?;
+    writeln!(file, "--- stdout ---")?;
+    file.write_all(&output.stdout)?;
+    writeln!(file, "\n--- stderr ---")?;
+    file.write_all(&output.stderr)?;
+    writeln!(file)
+}
+
+fn failure(step: &str, node_id: Option<u32>, output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match node_id {
+        Some(node_id) => format!(
+            "co-noir {} failed (node {}):\nstderr: {}\nstdout: {}",
+            step, node_id, stderr, stdout
+        ),
+        None => format!(
+            "co-noir {} failed:\nstderr: {}\nstdout: {}",
+            step, stderr, stdout
+        ),
+    }
+}
+
+fn validate_circuit_artifact_compatibility(circuit_path: &str) -> Result<(), String> {
+    let artifact_raw = std::fs::read_to_string(circuit_path)
+        .map_err(|e| format!("failed to read circuit artifact '{}': {}", circuit_path, e))?;
+    let artifact_json: serde_json::Value = serde_json::from_str(&artifact_raw).map_err(|e| {
+        format!(
+            "failed to parse circuit artifact '{}' as json: {}",
+            circuit_path, e
+        )
+    })?;
+    artifact_noir_version(&artifact_json, circuit_path)?;
+    Ok(())
+}
+
+/// The artifact's `noir_version`, checked against what co-noir can parse.
+pub fn artifact_noir_version(
+    artifact_json: &serde_json::Value,
+    circuit_path: &str,
+) -> Result<String, String> {
+    let noir_version = artifact_json
+        .get("noir_version")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| {
+            format!(
+                "circuit artifact '{}' is missing noir_version metadata",
+                circuit_path
+            )
+        })?;
+
+    if !noir_version.starts_with(EXPECTED_NOIR_VERSION_PREFIX) {
+        return Err(format!(
+            "circuit artifact '{}' noir_version='{}' is incompatible with co-noir parser expectations (need '{}*'). Recompile with ./scripts/compile-circuits.sh",
+            circuit_path,
+            noir_version,
+            EXPECTED_NOIR_VERSION_PREFIX
+        ));
+    }
+
+    Ok(noir_version.to_string())
+}
+
+/// Noir version prefix that compiled artifacts must carry.
+pub fn expected_noir_version_prefix() -> &'static str {
+    EXPECTED_NOIR_VERSION_PREFIX
+}
@@ -0,0 +1,170 @@
+//! Encrypted at-rest persistence of per-table party contributions.
+//!
+//! A node's permutation and salt shares are the only copy of its part of the
+//! deck secret. Losing them mid-hand (restart, crash, redeploy) makes hole
+//! cards unrecoverable, so every new contribution is written to disk with
+//! AES-256-GCM under a node keyfile and reloaded on startup.
+//!
+//! VRF-derived contributions are stored as just their hand number and
+//! re-derived from the node's VRF key on load.
+//!
+//! Layout: one `table_<id>.bin` per table containing `nonce || ciphertext`.
+//! The node and table IDs are bound as associated data so a file cannot be
+//! replayed onto another table or node.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+
+use crate::keystore::load_or_create_key;
+use crate::private_table::PartyContribution;
+
+const NONCE_LEN: usize = 12;
+const AAD_DOMAIN: &str = "stellar-poker-contribution:v1";
+
+#[derive(Clone)]
+pub struct ContributionStore {
+    dir: PathBuf,
+    node_id: u32,
+    cipher: Arc<Aes256Gcm>,
+}
+
+impl ContributionStore {
+    /// Open the store from `CONTRIBUTION_STORE_DIR` / `CONTRIBUTION_KEY_FILE`,
+    /// generating a fresh keyfile if none exists yet.
+    pub fn from_env(node_id: u32) -> Result<Self, String> {
+        let dir = std::env::var("CONTRIBUTION_STORE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(format!("./state/node_{}", node_id)));
+        let key_path = std::env::var("CONTRIBUTION_KEY_FILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| dir.join("contribution.key"));
+
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+        let key = load_or_create_key(&key_path)?;
+
+        Ok(Self {
+            dir,
+            node_id,
+            cipher: Arc::new(Aes256Gcm::new(&Key::<Aes256Gcm>::from(key))),
+        })
+    }
+
+    /// Encrypt and atomically write the contribution for `table_id`.
+    pub(crate) async fn save(
+        &self,
+        table_id: u32,
+        contribution: &PartyContribution,
+    ) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(&contribution.persisted())
+            .map_err(|e| format!("failed to serialize contribution: {}", e))?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let aad = self.aad(table_id);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| "failed to encrypt contribution".to_string())?;
+
+        let mut blob = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&ciphertext);
+
+        let path = self.table_path(table_id);
+        let tmp = path.with_extension("bin.tmp");
+        tokio::fs::write(&tmp, &blob)
+            .await
+            .map_err(|e| format!("failed to write {}: {}", tmp.display(), e))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| format!("failed to persist {}: {}", path.display(), e))
+    }
+
+    /// Delete the stored contribution for `table_id`, if any.
+    pub async fn remove(&self, table_id: u32) -> Result<(), String> {
+        let path = self.table_path(table_id);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("failed to remove {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Decrypt every stored contribution. Unreadable files are skipped with a warning.
+    pub(crate) fn load_all(&self) -> Result<HashMap<u32, PartyContribution>, String> {
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|e| format!("failed to read {}: {}", self.dir.display(), e))?;
+
+        let mut out = HashMap::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(table_id) = parse_table_id(&path) else {
+                continue;
+            };
+            match self.load_file(table_id, &path) {
+                Ok(contribution) => {
+                    out.insert(table_id, contribution);
+                }
+                Err(e) => tracing::warn!("Skipping stored contribution {}: {}", path.display(), e),
+            }
+        }
+        Ok(out)
+    }
+
+    fn load_file(&self, table_id: u32, path: &Path) -> Result<PartyContribution, String> {
+        let blob = std::fs::read(path).map_err(|e| format!("read failed: {}", e))?;
+        if blob.len() <= NONCE_LEN {
+            return Err("file too short".to_string());
+        }
+        let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().map_err(|_| "invalid nonce")?;
+        let aad = self.aad(table_id);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| "decryption failed (wrong key or tampered file)".to_string())?;
+        serde_json::from_slice(&plaintext).map_err(|e| format!("invalid contribution: {}", e))
+    }
+
+    /// Directory holding node state (contributions and, by default, keys).
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn table_path(&self, table_id: u32) -> PathBuf {
+        self.dir.join(format!("table_{}.bin", table_id))
+    }
+
+    fn aad(&self, table_id: u32) -> String {
+        format!("{}:{}:{}", AAD_DOMAIN, self.node_id, table_id)
+    }
+}
+
+fn parse_table_id(path: &Path) -> Option<u32> {
+    if path.extension()? != "bin" {
+        return None;
+    }
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix("table_")?
+        .parse()
+        .ok()
+}
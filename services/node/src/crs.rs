@@ -0,0 +1,261 @@
+//! Check and repair of the CRS file co-noir proves with.
+//!
+//! co-noir reads the BN254 points from `CRS_DIR/bn254_g1.dat`. With
+//! `CRS_SHA256` set, the node hashes that file at startup and reports itself
+//! unhealthy, refusing to generate proofs, until the digest matches. With
+//! `CRS_URL` set, a missing or mismatched file is downloaded again: into a
+//! temporary file first, and only moved into place once its digest matches.
+//! Without an expected digest the file is only required to exist.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+const CRS_FILE: &str = "bn254_g1.dat";
+
+static CRS: OnceLock<CrsManager> = OnceLock::new();
+
+struct CrsManager {
+    path: PathBuf,
+    expected_sha256: Option<String>,
+    url: Option<String>,
+    status: Mutex<CrsStatus>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrsState {
+    Checking,
+    /// Digest matches `CRS_SHA256`.
+    Valid,
+    /// Present, but there is no expected digest to check it against.
+    Unverified,
+    Missing,
+    Mismatch,
+    Repairing,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CrsStatus {
+    pub path: String,
+    pub state: CrsState,
+    pub sha256: Option<String>,
+    pub expected_sha256: Option<String>,
+    pub repair_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl CrsStatus {
+    pub fn is_usable(&self) -> bool {
+        matches!(self.state, CrsState::Valid | CrsState::Unverified)
+    }
+}
+
+/// Read the CRS location, digest and repair URL from the environment and
+/// start the initial check. Must be called once at startup.
+pub fn init_from_env() -> Result<(), String> {
+    let dir = std::env::var("CRS_DIR").unwrap_or_else(|_| "./crs".to_string());
+    let expected_sha256 = match std::env::var("CRS_SHA256") {
+        Ok(raw) if !raw.trim().is_empty() => {
+            let digest = raw.trim().to_ascii_lowercase();
+            if digest.len() != 64 || hex::decode(&digest).is_err() {
+                return Err("CRS_SHA256 must be a hex SHA-256 digest".to_string());
+            }
+            Some(digest)
+        }
+        _ => None,
+    };
+    let url = std::env::var("CRS_URL")
+        .ok()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty());
+    let path = Path::new(&dir).join(CRS_FILE);
+    let status = CrsStatus {
+        path: path.display().to_string(),
+        state: CrsState::Checking,
+        sha256: None,
+        expected_sha256: expected_sha256.clone(),
+        repair_url: url.clone(),
+        detail: None,
+    };
+    CRS.set(CrsManager {
+        path,
+        expected_sha256,
+        url,
+        status: Mutex::new(status),
+    })
+    .map_err(|_| "CRS already initialized".to_string())?;
+
+    tokio::spawn(check_and_repair());
+    Ok(())
+}
+
+fn manager() -> &'static CrsManager {
+    CRS.get().expect("CRS not initialized")
+}
+
+pub fn status() -> CrsStatus {
+    manager()
+        .status
+        .lock()
+        .map(|s| s.clone())
+        .unwrap_or_else(|e| e.into_inner().clone())
+}
+
+pub fn is_usable() -> bool {
+    status().is_usable()
+}
+
+fn update(state: CrsState, sha256: Option<String>, detail: Option<String>) {
+    let mut status = manager().status.lock().unwrap_or_else(|e| e.into_inner());
+    status.state = state;
+    if sha256.is_some() || state == CrsState::Missing {
+        status.sha256 = sha256;
+    }
+    status.detail = detail;
+}
+
+/// Start a repair from `CRS_URL` in the background.
+pub fn start_repair() -> Result<(), String> {
+    let manager = manager();
+    if manager.url.is_none() {
+        return Err("CRS_URL not set".to_string());
+    }
+    {
+        let mut status = manager.status.lock().unwrap_or_else(|e| e.into_inner());
+        if matches!(status.state, CrsState::Checking | CrsState::Repairing) {
+            return Err("CRS check or repair already running".to_string());
+        }
+        status.state = CrsState::Repairing;
+    }
+    tokio::spawn(run_repair());
+    Ok(())
+}
+
+async fn run_repair() {
+    if let Err(e) = repair().await {
+        tracing::error!("CRS repair failed: {}", e);
+        // Report on the file still in place.
+        let (state, sha256, _) = verify(&manager().path).await;
+        update(state, sha256, Some(e));
+    }
+}
+
+/// Verify the CRS file, then download it again when it is bad and a repair
+/// URL is configured.
+async fn check_and_repair() {
+    let manager = manager();
+    let (state, sha256, detail) = verify(&manager.path).await;
+    update(state, sha256, detail.clone());
+    match state {
+        CrsState::Valid => tracing::info!("CRS {} verified", manager.path.display()),
+        CrsState::Unverified => tracing::warn!(
+            "CRS_SHA256 not set — CRS {} is not verified",
+            manager.path.display()
+        ),
+        _ => {
+            tracing::error!(
+                "CRS {} unusable: {}",
+                manager.path.display(),
+                detail.unwrap_or_default()
+            );
+            if manager.url.is_some() {
+                update(CrsState::Repairing, None, None);
+                run_repair().await;
+            }
+        }
+    }
+}
+
+async fn verify(path: &Path) -> (CrsState, Option<String>, Option<String>) {
+    let expected = manager().expected_sha256.clone();
+    let hashed = {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || sha256_file(&path)).await
+    };
+    match hashed {
+        Ok(Ok(digest)) => match expected {
+            Some(expected) if expected != digest => (
+                CrsState::Mismatch,
+                Some(digest),
+                Some(format!("sha256 does not match expected {}", expected)),
+            ),
+            Some(_) => (CrsState::Valid, Some(digest), None),
+            None => (CrsState::Unverified, Some(digest), None),
+        },
+        Ok(Err(e)) => (CrsState::Missing, None, Some(e)),
+        Err(e) => (CrsState::Missing, None, Some(format!("hash task: {}", e))),
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Download the CRS next to its final path and move it into place once it
+/// verifies.
+async fn repair() -> Result<(), String> {
+    let manager = manager();
+    let url = manager.url.as_deref().ok_or("CRS_URL not set")?;
+    let dir = manager.path.parent().ok_or("CRS path has no parent")?;
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let tmp_path = dir.join(format!("{}.download", CRS_FILE));
+    tracing::info!("Downloading CRS from {}", url);
+
+    let mut resp = reqwest::get(url)
+        .await
+        .map_err(|e| format!("CRS download failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("CRS download failed: {}", e))?;
+    let mut file = tokio::fs::File::create(&tmp_path)
+        .await
+        .map_err(|e| format!("failed to create {}: {}", tmp_path.display(), e))?;
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| format!("CRS download failed: {}", e))?
+    {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("failed to write {}: {}", tmp_path.display(), e))?;
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("failed to write {}: {}", tmp_path.display(), e))?;
+    drop(file);
+
+    let (state, sha256, detail) = verify(&tmp_path).await;
+    if !matches!(state, CrsState::Valid | CrsState::Unverified) {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(format!(
+            "downloaded CRS rejected: {}",
+            detail.unwrap_or_default()
+        ));
+    }
+    tokio::fs::rename(&tmp_path, &manager.path)
+        .await
+        .map_err(|e| format!("failed to install {}: {}", manager.path.display(), e))?;
+    update(state, sha256, None);
+    tracing::info!("CRS repaired from {}", url);
+    Ok(())
+}
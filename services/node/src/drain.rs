@@ -0,0 +1,54 @@
+//! Maintenance drain mode.
+//!
+//! While draining, the node refuses requests that start new work (prepare
+//! and generate) with `503` and a `Retry-After` hint, but keeps serving
+//! everything an in-flight session needs: share dispatch, status, proofs and
+//! lookups. Operators wait for `active_sessions` to reach zero before taking
+//! the machine down.
+
+use std::sync::atomic::Ordering;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::NodeState;
+
+/// Seconds callers are told to wait before retrying a drained request.
+const RETRY_AFTER_SECS: u64 = 30;
+
+pub fn is_draining(state: &NodeState) -> bool {
+    state.draining.load(Ordering::Relaxed)
+}
+
+/// Proof sessions that have not completed or failed yet.
+pub async fn active_sessions(state: &NodeState) -> usize {
+    let sessions = state.sessions.read().await;
+    let mut active = 0;
+    for session in sessions.values() {
+        if !session.read().await.is_finished() {
+            active += 1;
+        }
+    }
+    active
+}
+
+/// Reject the wrapped routes while the node is draining.
+pub async fn reject_when_draining(
+    State(state): State<NodeState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_draining(&state) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+            "node is draining for maintenance",
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
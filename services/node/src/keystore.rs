@@ -0,0 +1,277 @@
+//! Loading and storage of the node's 32-byte secret keys.
+//!
+//! The contribution, signing, VRF and share keys are hex files generated on
+//! first start. With a keystore passphrase configured they are stored
+//! encrypted instead: AES-256-GCM under a key derived from the passphrase
+//! with PBKDF2-HMAC-SHA256, as
+//!
+//! ```text
+//! keystore:v1:pbkdf2-sha256:<iterations>:<salt hex>:<nonce hex>:<ciphertext hex>
+//! ```
+//!
+//! The passphrase is read at startup from `NODE_KEYSTORE_PASSPHRASE_FILE`,
+//! `NODE_KEYSTORE_PASSPHRASE`, or, with `NODE_KEYSTORE_PROMPT=true`, the
+//! terminal. Plaintext key files found while a passphrase is set are
+//! rewritten encrypted. Key material only passes through [`Protection`], so
+//! an HSM-backed variant can be added without touching the callers.
+//!
+//! co-noir's party TLS keys are read by co-noir itself from the party config
+//! and are not covered yet.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const HEADER: &str = "keystore:v1:pbkdf2-sha256";
+
+static KEYSTORE: OnceLock<Protection> = OnceLock::new();
+
+/// How key files are protected at rest.
+pub enum Protection {
+    /// Hex-encoded key files.
+    Plaintext,
+    /// Key files encrypted under a passphrase-derived key.
+    Passphrase(String),
+}
+
+/// Read the keystore passphrase, if any. Must be called once at startup,
+/// before any key is loaded.
+pub fn init_from_env() -> Result<&'static Protection, String> {
+    let passphrase = match std::env::var("NODE_KEYSTORE_PASSPHRASE_FILE") {
+        Ok(path) if !path.trim().is_empty() => Some(
+            std::fs::read_to_string(path.trim())
+                .map_err(|e| format!("failed to read {}: {}", path.trim(), e))?
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+        ),
+        _ => match std::env::var("NODE_KEYSTORE_PASSPHRASE") {
+            Ok(passphrase) if !passphrase.is_empty() => Some(passphrase),
+            _ if prompt_enabled() => Some(prompt_passphrase()?),
+            _ => None,
+        },
+    };
+    let protection = match passphrase {
+        Some(passphrase) if passphrase.is_empty() => {
+            return Err("keystore passphrase is empty".to_string())
+        }
+        Some(passphrase) => Protection::Passphrase(passphrase),
+        None => Protection::Plaintext,
+    };
+    KEYSTORE
+        .set(protection)
+        .map_err(|_| "keystore already initialized".to_string())?;
+    Ok(protection_mode())
+}
+
+fn protection_mode() -> &'static Protection {
+    KEYSTORE.get().expect("keystore not initialized")
+}
+
+impl Protection {
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, Protection::Passphrase(_))
+    }
+}
+
+fn prompt_enabled() -> bool {
+    match std::env::var("NODE_KEYSTORE_PROMPT") {
+        Ok(value) => matches!(
+            value.to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ),
+        Err(_) => false,
+    }
+}
+
+/// Ask for the passphrase on the terminal, with echo turned off.
+fn prompt_passphrase() -> Result<String, String> {
+    if !std::io::stdin().is_terminal() {
+        return Err("NODE_KEYSTORE_PROMPT set but stdin is not a terminal".to_string());
+    }
+    let stty = |arg: &str| {
+        std::process::Command::new("stty")
+            .arg(arg)
+            .stdin(std::process::Stdio::inherit())
+            .status()
+    };
+    eprint!("Keystore passphrase: ");
+    let _ = std::io::stderr().flush();
+    let _ = stty("-echo");
+    let mut line = String::new();
+    let read = std::io::stdin().lock().read_line(&mut line);
+    let _ = stty("echo");
+    eprintln!();
+    read.map_err(|e| format!("failed to read passphrase: {}", e))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Load a 32-byte key, creating one (mode 0600) if the file is absent.
+pub(crate) fn load_or_create_key(path: &Path) -> Result<[u8; KEY_LEN], String> {
+    let protection = protection_mode();
+    match std::fs::read_to_string(path) {
+        Ok(raw) => {
+            let raw = raw.trim();
+            if raw.starts_with(HEADER) {
+                let Protection::Passphrase(passphrase) = protection else {
+                    return Err(format!(
+                        "key file {} is encrypted but no keystore passphrase is set",
+                        path.display()
+                    ));
+                };
+                return decrypt_key(raw, passphrase)
+                    .map_err(|e| format!("key file {}: {}", path.display(), e));
+            }
+            let key = parse_hex_key(raw)
+                .map_err(|e| format!("invalid key file {}: {}", path.display(), e))?;
+            if let Protection::Passphrase(passphrase) = protection {
+                replace_key_file(path, &encrypt_key(&key, passphrase)?)?;
+                tracing::info!("Encrypted key file {}", path.display());
+            }
+            Ok(key)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let mut key = [0u8; KEY_LEN];
+            rand::thread_rng().fill_bytes(&mut key);
+            let contents = match protection {
+                Protection::Plaintext => hex::encode(key),
+                Protection::Passphrase(passphrase) => encrypt_key(&key, passphrase)?,
+            };
+            write_key_file(path, &contents)?;
+            tracing::info!("Generated key file {}", path.display());
+            Ok(key)
+        }
+        Err(e) => Err(format!("failed to read key file {}: {}", path.display(), e)),
+    }
+}
+
+fn parse_hex_key(raw: &str) -> Result<[u8; KEY_LEN], String> {
+    let bytes = hex::decode(raw).map_err(|e| e.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("must contain {} hex-encoded bytes", KEY_LEN))
+}
+
+fn encrypt_key(key: &[u8; KEY_LEN], passphrase: &str) -> Result<String, String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from(derive_key(
+        passphrase,
+        &salt,
+        PBKDF2_ITERATIONS,
+    )));
+    let ciphertext = cipher
+        .encrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: key,
+                aad: HEADER.as_bytes(),
+            },
+        )
+        .map_err(|_| "failed to encrypt key".to_string())?;
+    Ok(format!(
+        "{}:{}:{}:{}:{}",
+        HEADER,
+        PBKDF2_ITERATIONS,
+        hex::encode(salt),
+        hex::encode(nonce),
+        hex::encode(ciphertext)
+    ))
+}
+
+fn decrypt_key(raw: &str, passphrase: &str) -> Result<[u8; KEY_LEN], String> {
+    let fields: Vec<&str> = raw
+        .strip_prefix(HEADER)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .ok_or("not a keystore entry")?
+        .split(':')
+        .collect();
+    let [iterations, salt, nonce, ciphertext] = fields[..] else {
+        return Err("malformed keystore entry".to_string());
+    };
+    let iterations: u32 = iterations
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or("invalid iteration count")?;
+    let salt = hex::decode(salt).map_err(|_| "invalid salt")?;
+    let nonce: [u8; NONCE_LEN] = hex::decode(nonce)
+        .ok()
+        .and_then(|n| n.try_into().ok())
+        .ok_or("invalid nonce")?;
+    let ciphertext = hex::decode(ciphertext).map_err(|_| "invalid ciphertext")?;
+
+    let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from(derive_key(
+        passphrase, &salt, iterations,
+    )));
+    let plaintext = cipher
+        .decrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: HEADER.as_bytes(),
+            },
+        )
+        .map_err(|_| "decryption failed (wrong passphrase or tampered file)".to_string())?;
+    plaintext
+        .try_into()
+        .map_err(|_| format!("decrypted key is not {} bytes", KEY_LEN))
+}
+
+/// PBKDF2-HMAC-SHA256 with a single output block, which covers a 32-byte key.
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; KEY_LEN] {
+    let prf = <Hmac<Sha256> as Mac>::new_from_slice(passphrase.as_bytes()).expect("hmac key");
+    let mut mac = prf.clone();
+    mac.update(salt);
+    mac.update(&1u32.to_be_bytes());
+    let mut u: [u8; KEY_LEN] = mac.finalize().into_bytes().into();
+    let mut out = u;
+    for _ in 1..iterations {
+        let mut mac = prf.clone();
+        mac.update(&u);
+        u = mac.finalize().into_bytes().into();
+        for (o, b) in out.iter_mut().zip(u) {
+            *o ^= b;
+        }
+    }
+    out
+}
+
+fn write_key_file(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("failed to create key file {}: {}", path.display(), e))?;
+    file.write_all(contents.as_bytes())
+        .map_err(|e| format!("failed to write key file {}: {}", path.display(), e))
+}
+
+/// Atomically replace an existing key file.
+fn replace_key_file(path: &Path, contents: &str) -> Result<(), String> {
+    let tmp = path.with_extension("key.tmp");
+    let _ = std::fs::remove_file(&tmp);
+    write_key_file(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+        .map_err(|e| format!("failed to replace key file {}: {}", path.display(), e))
+}
@@ -0,0 +1,36 @@
+//! Log correlation for requests and proof sessions.
+//!
+//! Every request runs inside a span carrying the table and proof session it
+//! concerns (parsed from the path), so each log line it emits is tagged with
+//! `table=` / `session=`. Background proof generation re-enters the same
+//! span. co-noir subprocess output is kept per session in its work
+//! directory (see `conoir::SESSION_LOG_FILE`) and served on
+//! `/session/:id/logs`.
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use tracing::Instrument;
+
+use crate::workdir;
+
+/// Span for work on a proof session.
+pub fn session_span(session_id: &str) -> tracing::Span {
+    match workdir::table_id_from_session(session_id) {
+        Some(table_id) => tracing::info_span!("session", table = table_id, session = %session_id),
+        None => tracing::info_span!("session", session = %session_id),
+    }
+}
+
+/// Run the request inside a span naming its table and/or session.
+pub async fn request_span(request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let mut segments = path.trim_start_matches('/').split('/');
+    let span = match (segments.next(), segments.next()) {
+        (Some("session"), Some(session_id)) => session_span(session_id),
+        (Some("table"), Some(table_id)) => match table_id.parse::<u32>() {
+            Ok(table_id) => tracing::info_span!("table", table = table_id),
+            Err(_) => tracing::Span::none(),
+        },
+        _ => tracing::Span::none(),
+    };
+    next.run(request).instrument(span).await
+}
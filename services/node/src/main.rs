@@ -1,6 +1,6 @@
 //! Stellar Poker MPC Node
 //!
-//! Each node is a participant in the REP3 MPC protocol via TACEO's co-noir.
+//! Each node is a participant in the REP3 (or Shamir) MPC protocol via TACEO's co-noir.
 //! It holds secret shares and participates in collaborative proof generation.
 //!
 //! Lifecycle:
@@ -11,21 +11,69 @@
 //! 5. Coordinator polls GET /session/:id/status and retrieves proof via GET /session/:id/proof
 //!
 //! co-noir handles peer-to-peer MPC communication internally via TCP (ports 10000-10002).
+//! Its output is kept per session and served on GET /session/:id/logs; node
+//! log lines carry the table and session they concern (see `logging`).
+//!
+//! The node's Ed25519 identity key (also used for contribution commitments) is
+//! published on `/health` and registered in the committee registry; callers
+//! authenticate the node by having it sign a challenge on `/identity/challenge`.
+//!
+//! Completed proofs, their public inputs and this node's share hashes are
+//! kept for a retention window and listed on `/proofs` (see `proof_archive`).
+//!
+//! `/admin/drain` puts the node in maintenance mode: new prepare/generate
+//! requests get a retriable 503 while in-flight sessions finish.
+//!
+//! `/health` fails until the CRS file has been verified (see `crs`).
+//!
+//! Compiled circuit artifacts can be inspected and replaced over the signed
+//! API (`/circuits`), so circuit upgrades roll out without shell access.
+//!
+//! In TEE mode the node also publishes a remote-attestation quote bound to
+//! its identity key on `/health` (see `tee`).
 
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post, put},
     Router,
 };
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 mod api;
+mod auth;
+mod circuits;
+mod conoir;
+mod contribution_store;
+mod crs;
+mod drain;
+mod keystore;
+mod logging;
 mod private_table;
+mod proof_archive;
+mod proof_check;
 mod session;
+mod share_crypto;
+mod tee;
+mod workdir;
 
-use private_table::PrivateTableState;
+use auth::{NodeAuth, PermLookupGuard};
+use contribution_store::ContributionStore;
+use keystore::load_or_create_key;
+use mpc_auth::attestation::Attestation;
+use mpc_auth::commitment::CommitmentSigner;
+use mpc_auth::vrf::ContributionVrf;
+use private_table::{ContributionKeys, PrivateTableState};
+use proof_archive::ProofArchive;
 use session::MpcSessionState;
+use share_crypto::ShareCipher;
+
+/// Largest circuit artifact accepted by `PUT /circuits/:name`.
+const MAX_CIRCUIT_ARTIFACT_BYTES: usize = 64 * 1024 * 1024;
 
 #[derive(Clone)]
 pub struct NodeState {
@@ -34,6 +82,17 @@ pub struct NodeState {
     pub tables: Arc<RwLock<HashMap<u32, PrivateTableState>>>,
     pub party_config_path: String,
     pub peer_http_endpoints: Vec<String>,
+    pub contribution_store: ContributionStore,
+    pub auth: Option<NodeAuth>,
+    pub perm_lookup_guard: PermLookupGuard,
+    pub share_cipher: ShareCipher,
+    pub contribution_keys: ContributionKeys,
+    /// Retained proofs for `/proofs`; `None` when the archive is disabled.
+    pub proof_archive: Option<ProofArchive>,
+    /// Set by `/admin/drain`: refuse new prepare/generate work.
+    pub draining: Arc<AtomicBool>,
+    /// Attestation quote published on `/health`; `None` outside TEE mode.
+    pub attestation: Option<Arc<Attestation>>,
 }
 
 #[tokio::main]
@@ -69,18 +128,152 @@ async fn main() {
 
     tracing::info!("MPC Node {} starting on port {}", node_id, port);
     tracing::info!("Party config: {}", party_config_path);
+    tracing::info!("Circuit dir: {}", circuits::circuit_dir().display());
     tracing::info!("Peer HTTP endpoints: {:?}", peer_http_endpoints);
 
+    let protocol =
+        conoir::init_protocol(peer_http_endpoints.len()).expect("invalid MPC protocol config");
+    tracing::info!(
+        "MPC protocol: {} across {} parties",
+        protocol,
+        peer_http_endpoints.len()
+    );
+
+    crs::init_from_env().expect("invalid CRS config");
+
+    let work_layout = workdir::init_from_env(node_id).expect("invalid work directory config");
+    tracing::info!(
+        "Work dir {}: {} sessions and {} MB per table",
+        work_layout.root().display(),
+        work_layout.max_sessions_per_table(),
+        work_layout.table_quota_bytes() / (1024 * 1024)
+    );
+
+    let mut sessions = HashMap::new();
+    for session in workdir::restore_sessions().expect("failed to restore proof sessions") {
+        tracing::info!(
+            "Restored proof session {} with {} share fragments",
+            session.session_id,
+            session.partial_share_paths.len()
+        );
+        sessions.insert(session.session_id.clone(), Arc::new(RwLock::new(session)));
+    }
+
+    if keystore::init_from_env()
+        .expect("failed to read keystore passphrase")
+        .is_encrypted()
+    {
+        tracing::info!("Node key files are passphrase-encrypted");
+    } else {
+        tracing::warn!("NODE_KEYSTORE_PASSPHRASE not set — node key files are stored in plaintext");
+    }
+
+    let contribution_store =
+        ContributionStore::from_env(node_id).expect("failed to open contribution store");
+
+    let proof_archive =
+        ProofArchive::from_env(contribution_store.dir()).expect("invalid proof archive config");
+    match &proof_archive {
+        Some(archive) => tracing::info!(
+            "Proof archive {}: retained for {} hours",
+            archive.dir().display(),
+            archive.retention_hours()
+        ),
+        None => tracing::warn!("PROOF_RETENTION_HOURS=0 — completed proofs are not archived"),
+    }
+
+    let signing_key_path = std::env::var("NODE_SIGNING_KEY_FILE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| contribution_store.dir().join("signing.key"));
+    let commitment_signer = CommitmentSigner::from_seed(
+        &load_or_create_key(&signing_key_path).expect("failed to load node signing key"),
+    );
+    tracing::info!("Node identity key: {}", commitment_signer.public_key_hex());
+    let vrf = vrf_contributions_enabled().then(|| {
+        let path = std::env::var("NODE_VRF_KEY_FILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| contribution_store.dir().join("vrf.key"));
+        ContributionVrf::from_seed(&load_or_create_key(&path).expect("failed to load VRF key"))
+    });
+    match &vrf {
+        Some(vrf) => tracing::info!("VRF contributions enabled: {}", vrf.public_key_hex()),
+        None => tracing::info!("Contributions drawn from the OS RNG"),
+    }
+    let attestation =
+        tee::attest_from_env(node_id, &commitment_signer).expect("failed to fetch TEE attestation");
+    match &attestation {
+        Some(attestation) => tracing::info!(
+            "TEE mode ({:?}): measurement hash {}",
+            attestation.provider,
+            attestation.measurement_hash
+        ),
+        None => tracing::info!("TEE mode off — no attestation quote published"),
+    }
+    let contribution_keys = ContributionKeys {
+        signer: commitment_signer,
+        vrf,
+    };
+
+    let mut tables: HashMap<u32, PrivateTableState> = HashMap::new();
+    for (table_id, contribution) in contribution_store
+        .load_all()
+        .expect("failed to load stored contributions")
+    {
+        match contribution.restore(table_id, node_id, contribution_keys.vrf.as_ref()) {
+            Ok(contribution) => {
+                tables.insert(table_id, PrivateTableState::restored(contribution));
+            }
+            Err(e) => tracing::warn!("Skipping stored contribution for table {}: {}", table_id, e),
+        }
+    }
+    if !tables.is_empty() {
+        let mut ids: Vec<u32> = tables.keys().copied().collect();
+        ids.sort_unstable();
+        tracing::info!("Restored contributions for tables {:?}", ids);
+    }
+
+    let auth = NodeAuth::from_env(node_id).expect("invalid MPC_AUTH_SECRET");
+    if auth.is_some() {
+        tracing::info!("Signed-request authentication enabled");
+    } else {
+        tracing::warn!("MPC_AUTH_SECRET not set — node API is unauthenticated");
+    }
+    let perm_lookup_guard = PermLookupGuard::from_env().expect("invalid PERM_LOOKUP_PUBLIC_KEY");
+    if !perm_lookup_guard.is_enforced() {
+        tracing::warn!("PERM_LOOKUP_PUBLIC_KEY not set — perm-lookup accepts unauthorized calls");
+    }
+
+    let share_cipher =
+        ShareCipher::from_env(node_id, contribution_store.dir(), peer_http_endpoints.len())
+            .expect("failed to load share encryption keys");
+    tracing::info!("Share public key: {}", share_cipher.public_key_hex());
+    if !share_cipher.is_enforced() {
+        tracing::warn!("NODE_SHARE_PUBLIC_KEYS not set — share fragments travel unencrypted");
+    }
+
     let state = NodeState {
         node_id,
-        sessions: Arc::new(RwLock::new(HashMap::new())),
-        tables: Arc::new(RwLock::new(HashMap::new())),
+        sessions: Arc::new(RwLock::new(sessions)),
+        tables: Arc::new(RwLock::new(tables)),
         party_config_path,
         peer_http_endpoints,
+        contribution_store,
+        auth,
+        perm_lookup_guard,
+        share_cipher,
+        contribution_keys,
+        proof_archive,
+        draining: Arc::new(AtomicBool::new(false)),
+        attestation: attestation.map(Arc::new),
     };
 
-    let app = Router::new()
-        .route("/health", get(|| async { "ok" }))
+    workdir::spawn_session_reaper(state.clone());
+    if let Some(archive) = &state.proof_archive {
+        archive.spawn_pruner();
+    }
+
+    // Routes that start new work, refused while draining.
+    let new_work = Router::new()
         .route(
             "/table/:table_id/prepare-deal",
             post(api::post_prepare_deal),
@@ -93,18 +286,65 @@ async fn main() {
             "/table/:table_id/prepare-showdown",
             post(api::post_prepare_showdown),
         )
+        .route("/session/:id/generate", post(api::post_generate))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            drain::reject_when_draining,
+        ));
+
+    let app = Router::new()
+        .merge(new_work)
         .route(
             "/table/:table_id/dispatch-shares",
             post(api::post_dispatch_shares),
         )
+        .route(
+            "/table/:table_id/redispatch-shares",
+            post(api::post_redispatch_shares),
+        )
         .route("/table/:table_id/perm-lookup", post(api::post_perm_lookup))
+        .route(
+            "/table/:table_id/perm-lookup-batch",
+            post(api::post_perm_lookup_batch),
+        )
+        .route(
+            "/table/:table_id/reveal-contribution",
+            post(api::post_reveal_contribution),
+        )
         .route("/session/:id/shares", post(api::post_shares))
-        .route("/session/:id/generate", post(api::post_generate))
         .route("/session/:id/status", get(api::get_status))
         .route("/session/:id/proof", get(api::get_proof))
+        .route("/session/:id/logs", get(api::get_session_logs))
+        .route("/proofs", get(api::get_proofs))
+        .route("/proofs/:session_id", get(api::get_archived_proof))
+        .route("/admin/drain", get(api::get_drain).post(api::post_drain))
+        .route("/crs", get(api::get_crs))
+        .route("/crs/repair", post(api::post_crs_repair))
+        .route("/circuits", get(api::get_circuits))
+        .route(
+            "/circuits/:name",
+            put(api::put_circuit).layer(DefaultBodyLimit::max(MAX_CIRCUIT_ARTIFACT_BYTES)),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_signed_request,
+        ))
+        .route("/health", get(api::get_health))
+        .route("/identity/challenge", post(api::post_identity_challenge))
+        .layer(middleware::from_fn(logging::request_span))
         .with_state(state);
 
     let addr = format!("0.0.0.0:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
 }
+
+fn vrf_contributions_enabled() -> bool {
+    match std::env::var("VRF_CONTRIBUTIONS") {
+        Ok(value) => matches!(
+            value.to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ),
+        Err(_) => false,
+    }
+}
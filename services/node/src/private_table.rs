@@ -7,34 +7,169 @@
 //! The full deck/salts are derived inside Noir from all party contributions.
 //! No single node needs plaintext full-deck witness material.
 
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
 use base64::Engine;
 use rand::seq::SliceRandom;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
+use std::str::FromStr;
+
+use crate::auth::sign_request;
+use crate::conoir;
+use crate::share_crypto::ShareCipher;
+use crate::workdir;
+use mpc_auth::commitment::{CommitmentSigner, ContributionCommitment};
+use mpc_auth::vrf::{ContributionVrf, VrfProof};
+use mpc_auth::RequestSigner;
 
 const DECK_SIZE: usize = 52;
 const MAX_PLAYERS: usize = 6;
 const MAX_USED_INDICES: usize = 16;
 const MAX_BOARD_INDICES: usize = 5;
-const EXPECTED_NOIR_VERSION_PREFIX: &str = "1.0.0-beta.17";
 
 #[derive(Clone, Debug, Default)]
 pub struct PrivateTableState {
     contribution: Option<PartyContribution>,
     pending_share_sets: HashMap<String, HashMap<u32, String>>,
+    /// Share sets already sent for a proof session, kept so they can be
+    /// re-sent to a peer that lost them.
+    dispatched_share_sets: HashMap<String, DispatchedShareSet>,
 }
 
 #[derive(Clone, Debug)]
-struct PartyContribution {
+struct DispatchedShareSet {
+    proof_session_id: String,
+    circuit_name: String,
+    share_data_by_party: HashMap<u32, String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct PartyContribution {
+    /// Empty when persisted for a VRF-derived contribution, which is
+    /// re-derived from `hand_number` on restore.
+    #[serde(default)]
     permutation: Vec<u32>,
+    #[serde(default)]
     salts: Vec<String>,
+    /// Players dealt in the hand this contribution belongs to; 0 when unknown
+    /// (contributions persisted before it was recorded).
+    #[serde(default)]
+    num_players: u32,
+    /// Hand the contribution was VRF-derived for; `None` when drawn at random.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hand_number: Option<u64>,
+}
+
+/// Keys a node uses to produce and vouch for its deal contributions.
+#[derive(Clone)]
+pub struct ContributionKeys {
+    pub signer: CommitmentSigner,
+    /// When set, contributions are derived from a VRF instead of the OS RNG.
+    pub vrf: Option<ContributionVrf>,
+}
+
+/// A rejected prepare request.
+#[derive(Debug)]
+pub enum PrepareError {
+    /// Coordinator-supplied input failed validation.
+    Invalid(InputError),
+    /// Missing table state or a co-noir failure.
+    Failed(String),
+}
+
+/// Which request field was rejected and why.
+#[derive(Debug, Serialize)]
+pub struct InputError {
+    pub field: &'static str,
+    pub reason: String,
+}
+
+impl From<String> for PrepareError {
+    fn from(e: String) -> Self {
+        PrepareError::Failed(e)
+    }
+}
+
+impl From<&str> for PrepareError {
+    fn from(e: &str) -> Self {
+        PrepareError::Failed(e.to_string())
+    }
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> PrepareError {
+    PrepareError::Invalid(InputError {
+        field,
+        reason: reason.into(),
+    })
+}
+
+impl PrivateTableState {
+    /// Rebuild table state from a contribution persisted before a restart.
+    pub(crate) fn restored(contribution: PartyContribution) -> Self {
+        Self {
+            contribution: Some(contribution),
+            pending_share_sets: HashMap::new(),
+            dispatched_share_sets: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn contribution(&self) -> Option<&PartyContribution> {
+        self.contribution.as_ref()
+    }
+}
+
+impl PartyContribution {
+    /// What to write to disk: VRF-derived contributions keep only their hand
+    /// number, since the node can re-derive the rest from its VRF key.
+    pub(crate) fn persisted(&self) -> Self {
+        match self.hand_number {
+            Some(_) => Self {
+                permutation: Vec::new(),
+                salts: Vec::new(),
+                ..self.clone()
+            },
+            None => self.clone(),
+        }
+    }
+
+    /// Re-derive a contribution persisted by [`Self::persisted`].
+    pub(crate) fn restore(
+        self,
+        table_id: u32,
+        node_id: u32,
+        vrf: Option<&ContributionVrf>,
+    ) -> Result<Self, String> {
+        let Some(hand_number) = self.hand_number.filter(|_| self.permutation.is_empty()) else {
+            return Ok(self);
+        };
+        let vrf = vrf.ok_or("VRF-derived contribution but no VRF key configured")?;
+        let (derived, _) = vrf.derive(table_id, node_id, hand_number, DECK_SIZE);
+        Ok(Self {
+            permutation: derived.permutation,
+            salts: derived.salts,
+            ..self
+        })
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct DealPreparation {
     pub share_set_id: String,
+    pub commitment: ContributionCommitment,
+}
+
+/// A retired contribution disclosed for post-hand audit.
+#[derive(Clone, Debug, Serialize)]
+pub struct RevealedContribution {
+    pub table_id: u32,
+    pub node_id: u32,
+    pub permutation: Vec<u32>,
+    pub salts: Vec<String>,
+    /// Proof the contribution is the VRF output for its hand, when VRF-derived.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vrf: Option<VrfProof>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -51,60 +186,136 @@ pub async fn prepare_deal(
     table_id: u32,
     node_id: u32,
     players: &[String],
+    hand_number: Option<u64>,
+    hand_nonce: u32,
     circuit_dir: &str,
+    keys: &ContributionKeys,
     tables: &mut HashMap<u32, PrivateTableState>,
-) -> Result<DealPreparation, String> {
+) -> Result<DealPreparation, PrepareError> {
     if players.len() < 2 || players.len() > MAX_PLAYERS {
-        return Err(format!(
-            "expected 2..={} players, got {}",
-            MAX_PLAYERS,
-            players.len()
+        return Err(invalid(
+            "players",
+            format!(
+                "expected 2..={} players, got {}",
+                MAX_PLAYERS,
+                players.len()
+            ),
         ));
     }
+    let mut seen = HashSet::new();
+    for player in players {
+        if player.trim().is_empty() {
+            return Err(invalid("players", "empty player address"));
+        }
+        if !seen.insert(player.as_str()) {
+            return Err(invalid("players", format!("duplicate player {}", player)));
+        }
+    }
+
+    let num_players = players.len() as u32;
+    let contribution = match &keys.vrf {
+        Some(vrf) => {
+            let hand_number = hand_number
+                .ok_or_else(|| invalid("hand_number", "required for VRF contributions"))?;
+            derive_party_contribution(vrf, table_id, node_id, hand_number, num_players)
+        }
+        None => generate_party_contribution(num_players),
+    };
 
     let state = tables.entry(table_id).or_default();
     state.pending_share_sets.clear();
-    state.contribution = Some(generate_party_contribution());
+    state.dispatched_share_sets.clear();
+    state.contribution = Some(contribution);
 
     let contribution = state
         .contribution
         .as_ref()
         .ok_or("missing local party contribution")?;
 
-    let input_toml = build_deal_partial_toml(node_id, contribution, players.len() as u32);
-    let share_data_by_party = split_partial_input(circuit_dir, "deal_valid", &input_toml).await?;
+    let input_toml =
+        build_deal_partial_toml(node_id, contribution, hand_nonce, players.len() as u32);
+    let share_data_by_party =
+        split_partial_input(table_id, circuit_dir, "deal_valid", &input_toml).await?;
+    let commitment = keys.signer.commit(
+        table_id,
+        node_id,
+        &contribution.permutation,
+        &contribution.salts,
+    );
 
     let share_set_id = new_share_set_id(table_id);
     state
         .pending_share_sets
         .insert(share_set_id.clone(), share_data_by_party);
 
-    Ok(DealPreparation { share_set_id })
+    Ok(DealPreparation {
+        share_set_id,
+        commitment,
+    })
+}
+
+/// Disclose and retire the table's contribution once its hand is over.
+///
+/// The contribution can no longer be used for reveals or showdowns.
+pub fn reveal_contribution(
+    table_id: u32,
+    node_id: u32,
+    vrf: Option<&ContributionVrf>,
+    tables: &mut HashMap<u32, PrivateTableState>,
+) -> Result<RevealedContribution, String> {
+    let contribution = tables
+        .remove(&table_id)
+        .and_then(|state| state.contribution)
+        .ok_or_else(|| format!("table {} has no active deal contribution", table_id))?;
+    let vrf_proof = match (contribution.hand_number, vrf) {
+        (Some(hand_number), Some(vrf)) => {
+            Some(vrf.derive(table_id, node_id, hand_number, DECK_SIZE).1)
+        }
+        _ => None,
+    };
+    Ok(RevealedContribution {
+        table_id,
+        node_id,
+        permutation: contribution.permutation,
+        salts: contribution.salts,
+        vrf: vrf_proof,
+    })
 }
 
 pub async fn prepare_reveal(
     table_id: u32,
     node_id: u32,
     phase: &str,
+    hand_nonce: u32,
     previously_used_indices: &[u32],
     deck_root: &str,
     circuit_dir: &str,
     tables: &mut HashMap<u32, PrivateTableState>,
-) -> Result<RevealPreparation, String> {
-    let num_revealed = match phase {
-        "flop" => 3u32,
-        "turn" => 1u32,
-        "river" => 1u32,
-        _ => return Err(format!("unsupported reveal phase '{}'", phase)),
+) -> Result<RevealPreparation, PrepareError> {
+    let (num_revealed, board_already_revealed) = match phase {
+        "flop" => (3u32, 0usize),
+        "turn" => (1u32, 3usize),
+        "river" => (1u32, 4usize),
+        _ => {
+            return Err(invalid(
+                "phase",
+                format!("unsupported reveal phase '{}'", phase),
+            ))
+        }
     };
 
     if previously_used_indices.len() > MAX_USED_INDICES {
-        return Err(format!(
-            "too many previously used indices: {} > {}",
-            previously_used_indices.len(),
-            MAX_USED_INDICES
+        return Err(invalid(
+            "previously_used_indices",
+            format!(
+                "too many previously used indices: {} > {}",
+                previously_used_indices.len(),
+                MAX_USED_INDICES
+            ),
         ));
     }
+    validate_deck_indices("previously_used_indices", previously_used_indices)?;
+    validate_field_element("deck_root", deck_root)?;
 
     let state = tables
         .get_mut(&table_id)
@@ -115,15 +326,42 @@ pub async fn prepare_reveal(
         .as_ref()
         .ok_or_else(|| format!("table {} has no active deal contribution", table_id))?;
 
+    // The circuit reveals the lowest unused positions, so every hole card
+    // must be marked used or the "board" would expose a player's hand.
+    if contribution.num_players > 0 {
+        let hole_cards = 2 * contribution.num_players as usize;
+        let expected = hole_cards + board_already_revealed;
+        if previously_used_indices.len() != expected {
+            return Err(invalid(
+                "previously_used_indices",
+                format!(
+                    "{} reveal with {} players needs {} used indices, got {}",
+                    phase,
+                    contribution.num_players,
+                    expected,
+                    previously_used_indices.len()
+                ),
+            ));
+        }
+        let used: HashSet<u32> = previously_used_indices.iter().copied().collect();
+        if let Some(missing) = (0..hole_cards as u32).find(|idx| !used.contains(idx)) {
+            return Err(invalid(
+                "previously_used_indices",
+                format!("hole card position {} is not marked used", missing),
+            ));
+        }
+    }
+
     let input_toml = build_reveal_partial_toml(
         node_id,
         contribution,
+        hand_nonce,
         num_revealed,
         previously_used_indices,
         deck_root,
     )?;
     let share_data_by_party =
-        split_partial_input(circuit_dir, "reveal_board_valid", &input_toml).await?;
+        split_partial_input(table_id, circuit_dir, "reveal_board_valid", &input_toml).await?;
 
     let share_set_id = new_share_set_id(table_id);
     state
@@ -133,38 +371,84 @@ pub async fn prepare_reveal(
     Ok(RevealPreparation { share_set_id })
 }
 
+/// Per player dealt in, in deal order: whether they are still in the hand,
+/// whether they muck a losing hand, and the chips they put in. The showdown
+/// proof shows hands by them.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ShowdownSeats {
+    pub contending: Vec<bool>,
+    pub muck_losing: Vec<bool>,
+    pub contributions: Vec<u64>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn prepare_showdown(
     table_id: u32,
     node_id: u32,
+    hand_nonce: u32,
     board_indices: &[u32],
     num_active_players: u32,
     hand_commitments: &[String],
     deck_root: &str,
+    seats: &ShowdownSeats,
     circuit_dir: &str,
     tables: &mut HashMap<u32, PrivateTableState>,
-) -> Result<ShowdownPreparation, String> {
+) -> Result<ShowdownPreparation, PrepareError> {
     if board_indices.len() != MAX_BOARD_INDICES {
-        return Err(format!(
-            "showdown requires {} board indices, got {}",
-            MAX_BOARD_INDICES,
-            board_indices.len()
+        return Err(invalid(
+            "board_indices",
+            format!(
+                "showdown requires {} board indices, got {}",
+                MAX_BOARD_INDICES,
+                board_indices.len()
+            ),
         ));
     }
+    validate_deck_indices("board_indices", board_indices)?;
 
     if !(2..=MAX_PLAYERS as u32).contains(&num_active_players) {
-        return Err(format!(
-            "num_active_players must be 2..={}, got {}",
-            MAX_PLAYERS, num_active_players
+        return Err(invalid(
+            "num_active_players",
+            format!(
+                "num_active_players must be 2..={}, got {}",
+                MAX_PLAYERS, num_active_players
+            ),
         ));
     }
 
     if hand_commitments.len() != num_active_players as usize {
-        return Err(format!(
-            "hand commitment count {} does not match num_active_players {}",
-            hand_commitments.len(),
-            num_active_players
+        return Err(invalid(
+            "hand_commitments",
+            format!(
+                "hand commitment count {} does not match num_active_players {}",
+                hand_commitments.len(),
+                num_active_players
+            ),
         ));
     }
+    for commitment in hand_commitments {
+        validate_field_element("hand_commitments", commitment)?;
+    }
+    validate_field_element("deck_root", deck_root)?;
+
+    for (field, len) in [
+        ("contending", seats.contending.len()),
+        ("muck_losing", seats.muck_losing.len()),
+        ("contributions", seats.contributions.len()),
+    ] {
+        if len != num_active_players as usize {
+            return Err(invalid(
+                field,
+                format!(
+                    "{} entries but num_active_players is {}",
+                    len, num_active_players
+                ),
+            ));
+        }
+    }
+    if !seats.contending.contains(&true) {
+        return Err(invalid("contending", "no player is still in the hand"));
+    }
 
     let state = tables
         .get_mut(&table_id)
@@ -175,16 +459,37 @@ pub async fn prepare_showdown(
         .as_ref()
         .ok_or_else(|| format!("table {} has no active deal contribution", table_id))?;
 
+    if contribution.num_players > 0 {
+        if num_active_players > contribution.num_players {
+            return Err(invalid(
+                "num_active_players",
+                format!(
+                    "{} active players but only {} were dealt in",
+                    num_active_players, contribution.num_players
+                ),
+            ));
+        }
+        let hole_cards = 2 * contribution.num_players;
+        if let Some(idx) = board_indices.iter().find(|idx| **idx < hole_cards) {
+            return Err(invalid(
+                "board_indices",
+                format!("board index {} is a hole card position", idx),
+            ));
+        }
+    }
+
     let input_toml = build_showdown_partial_toml(
         node_id,
         contribution,
+        hand_nonce,
         board_indices,
         num_active_players,
         hand_commitments,
         deck_root,
+        seats,
     )?;
     let share_data_by_party =
-        split_partial_input(circuit_dir, "showdown_valid", &input_toml).await?;
+        split_partial_input(table_id, circuit_dir, "showdown_valid", &input_toml).await?;
 
     let share_set_id = new_share_set_id(table_id);
     state
@@ -242,68 +547,81 @@ pub fn salt_lookup(
         .collect()
 }
 
-pub fn clone_share_set(
-    table_id: u32,
-    share_set_id: &str,
-    tables: &HashMap<u32, PrivateTableState>,
-) -> Result<HashMap<u32, String>, String> {
-    let table = tables
-        .get(&table_id)
-        .ok_or_else(|| format!("unknown table {}", table_id))?;
-    table
-        .pending_share_sets
-        .get(share_set_id)
-        .cloned()
-        .ok_or_else(|| format!("unknown share_set_id '{}'", share_set_id))
-}
-
-pub fn remove_share_set(
+/// Bind a share set to the proof session it is dispatched for and return
+/// its per-recipient payloads.
+///
+/// Binding again to the same session returns the same payloads, so a
+/// dispatch can be repeated; a share set is never sent to a second session.
+pub fn bind_share_set(
     table_id: u32,
     share_set_id: &str,
+    proof_session_id: &str,
+    circuit_name: &str,
     tables: &mut HashMap<u32, PrivateTableState>,
-) -> Result<(), String> {
+) -> Result<HashMap<u32, String>, String> {
     let table = tables
         .get_mut(&table_id)
         .ok_or_else(|| format!("unknown table {}", table_id))?;
-    table
-        .pending_share_sets
-        .remove(share_set_id)
+    if let Some(share_data_by_party) = table.pending_share_sets.remove(share_set_id) {
+        table.dispatched_share_sets.insert(
+            share_set_id.to_string(),
+            DispatchedShareSet {
+                proof_session_id: proof_session_id.to_string(),
+                circuit_name: circuit_name.to_string(),
+                share_data_by_party: share_data_by_party.clone(),
+            },
+        );
+        return Ok(share_data_by_party);
+    }
+    let dispatched = table
+        .dispatched_share_sets
+        .get(share_set_id)
         .ok_or_else(|| format!("unknown share_set_id '{}'", share_set_id))?;
-    Ok(())
+    if dispatched.proof_session_id != proof_session_id || dispatched.circuit_name != circuit_name {
+        return Err(format!(
+            "share_set_id '{}' was dispatched for session {} ({})",
+            share_set_id, dispatched.proof_session_id, dispatched.circuit_name
+        ));
+    }
+    Ok(dispatched.share_data_by_party.clone())
 }
 
+/// Send each payload to its recipient party's `/session/:id/shares`.
 pub async fn dispatch_share_payloads(
     proof_session_id: &str,
     circuit_name: &str,
     peer_http_endpoints: &[String],
     source_party_id: u32,
     share_data_by_party: &HashMap<u32, String>,
+    signer: Option<&RequestSigner>,
+    cipher: &ShareCipher,
 ) -> Result<(), String> {
     let total_parties = u32::try_from(peer_http_endpoints.len())
         .map_err(|_| "too many peer endpoints".to_string())?;
     let client = reqwest::Client::new();
 
-    let mut handles = Vec::with_capacity(peer_http_endpoints.len());
-    for (party_id_usize, endpoint) in peer_http_endpoints.iter().enumerate() {
-        let party_id = u32::try_from(party_id_usize)
-            .map_err(|_| format!("party index {} out of range", party_id_usize))?;
-        let share_data = share_data_by_party
-            .get(&party_id)
-            .cloned()
-            .ok_or_else(|| format!("missing share payload for party {}", party_id))?;
+    let mut handles = Vec::with_capacity(share_data_by_party.len());
+    for (&party_id, share_data) in share_data_by_party {
+        let endpoint = peer_http_endpoints
+            .get(party_id as usize)
+            .ok_or_else(|| format!("no endpoint for party {}", party_id))?;
+        let (share_data, encrypted) = cipher.seal_for(party_id, share_data)?;
 
         let url = format!("{}/session/{}/shares", endpoint, proof_session_id);
-        let circuit_name = circuit_name.to_string();
-        let client = client.clone();
+        let body = serde_json::to_vec(&serde_json::json!({
+            "circuit_name": circuit_name,
+            "share_data": share_data,
+            "encrypted": encrypted,
+            "source_party_id": source_party_id,
+            "total_parties": total_parties,
+        }))
+        .map_err(|e| format!("failed to encode share payload: {}", e))?;
+        let request = client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        let request = sign_request(request, signer, "POST", &url, &body).body(body);
         let handle = tokio::spawn(async move {
-            let response = client
-                .post(&url)
-                .json(&serde_json::json!({
-                    "circuit_name": circuit_name,
-                    "share_data": share_data,
-                    "source_party_id": source_party_id,
-                    "total_parties": total_parties,
-                }))
+            let response = request
                 .send()
                 .await
                 .map_err(|e| format!("dispatch to {} failed: {}", url, e))?;
@@ -333,7 +651,7 @@ pub async fn dispatch_share_payloads(
     Ok(())
 }
 
-fn generate_party_contribution() -> PartyContribution {
+fn generate_party_contribution(num_players: u32) -> PartyContribution {
     let mut rng = rand::thread_rng();
     let mut permutation: Vec<u32> = (0..DECK_SIZE as u32).collect();
     permutation.shuffle(&mut rng);
@@ -342,12 +660,34 @@ fn generate_party_contribution() -> PartyContribution {
         .map(|_| format!("{}", rand::random::<u64>()))
         .collect();
 
-    PartyContribution { permutation, salts }
+    PartyContribution {
+        permutation,
+        salts,
+        num_players,
+        hand_number: None,
+    }
+}
+
+fn derive_party_contribution(
+    vrf: &ContributionVrf,
+    table_id: u32,
+    node_id: u32,
+    hand_number: u64,
+    num_players: u32,
+) -> PartyContribution {
+    let (derived, _) = vrf.derive(table_id, node_id, hand_number, DECK_SIZE);
+    PartyContribution {
+        permutation: derived.permutation,
+        salts: derived.salts,
+        num_players,
+        hand_number: Some(hand_number),
+    }
 }
 
 fn build_deal_partial_toml(
     node_id: u32,
     contribution: &PartyContribution,
+    hand_nonce: u32,
     num_players: u32,
 ) -> String {
     let mut lines = vec![
@@ -364,6 +704,7 @@ fn build_deal_partial_toml(
     ];
 
     if node_id == 0 {
+        lines.push(format!("hand_nonce = {}", hand_nonce));
         lines.push(format!("num_players = {}", num_players));
     }
 
@@ -373,6 +714,7 @@ fn build_deal_partial_toml(
 fn build_reveal_partial_toml(
     node_id: u32,
     contribution: &PartyContribution,
+    hand_nonce: u32,
     num_revealed: u32,
     previously_used_indices: &[u32],
     deck_root: &str,
@@ -399,6 +741,7 @@ fn build_reveal_partial_toml(
     ];
 
     if node_id == 0 {
+        lines.push(format!("hand_nonce = {}", hand_nonce));
         lines.push(format!("deck_root = \"{}\"", deck_root));
         lines.push(format!("num_revealed = {}", num_revealed));
         lines.push(format!(
@@ -414,13 +757,16 @@ fn build_reveal_partial_toml(
     Ok(lines.join("\n") + "\n")
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_showdown_partial_toml(
     node_id: u32,
     contribution: &PartyContribution,
+    hand_nonce: u32,
     board_indices: &[u32],
     num_active_players: u32,
     hand_commitments: &[String],
     deck_root: &str,
+    seats: &ShowdownSeats,
 ) -> Result<String, String> {
     if board_indices.len() != MAX_BOARD_INDICES {
         return Err(format!(
@@ -455,6 +801,7 @@ fn build_showdown_partial_toml(
     ];
 
     if node_id == 0 {
+        lines.push(format!("hand_nonce = {}", hand_nonce));
         lines.push(format!("num_active_players = {}", num_active_players));
         lines.push(format!(
             "hand_commitments = {}",
@@ -465,50 +812,40 @@ fn build_showdown_partial_toml(
             format_u32_array(board_indices)
         ));
         lines.push(format!("deck_root = \"{}\"", deck_root));
+
+        let mut contending = [false; MAX_PLAYERS];
+        let mut muck_losing = [false; MAX_PLAYERS];
+        let mut contributions = [0u64; MAX_PLAYERS];
+        for p in 0..seats.contending.len().min(MAX_PLAYERS) {
+            contending[p] = seats.contending[p];
+            muck_losing[p] = seats.muck_losing[p];
+            contributions[p] = seats.contributions[p];
+        }
+        lines.push(format!("contending = {}", format_bool_array(&contending)));
+        lines.push(format!("muck_losing = {}", format_bool_array(&muck_losing)));
+        lines.push(format!(
+            "contributions = {}",
+            format_u64_array(&contributions)
+        ));
     }
 
     Ok(lines.join("\n") + "\n")
 }
 
 async fn split_partial_input(
+    table_id: u32,
     circuit_dir: &str,
     circuit_name: &str,
     input_toml: &str,
 ) -> Result<HashMap<u32, String>, String> {
-    let tmp = tempfile::tempdir().map_err(|e| format!("tmpdir: {}", e))?;
+    let tmp = workdir::scratch_dir(table_id)?;
     let input_path = tmp.path().join("partial.toml");
     let out_dir = tmp.path().join("split");
     std::fs::create_dir_all(&out_dir).map_err(|e| format!("create out dir: {}", e))?;
     std::fs::write(&input_path, input_toml).map_err(|e| format!("write partial input: {}", e))?;
 
-    let circuit_path = format!(
-        "{}/{}/target/{}.json",
-        circuit_dir, circuit_name, circuit_name
-    );
-    validate_circuit_artifact_compatibility(&circuit_path)?;
-
-    let output = Command::new("co-noir")
-        .arg("split-input")
-        .arg("--circuit")
-        .arg(&circuit_path)
-        .arg("--input")
-        .arg(&input_path)
-        .arg("--protocol")
-        .arg("REP3")
-        .arg("--out-dir")
-        .arg(&out_dir)
-        .output()
-        .await
-        .map_err(|e| format!("failed to spawn co-noir split-input: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        return Err(format!(
-            "co-noir split-input failed:\nstderr: {}\nstdout: {}",
-            stderr, stdout
-        ));
-    }
+    let circuit_path = conoir::circuit_path(circuit_dir, circuit_name);
+    conoir::split_input(&circuit_path, &input_path, &out_dir).await?;
 
     collect_split_shares(&out_dir)
 }
@@ -558,35 +895,50 @@ fn parse_party_id_from_share_filename(path: &Path) -> Option<u32> {
     idx.parse::<u32>().ok()
 }
 
-fn validate_circuit_artifact_compatibility(circuit_path: &str) -> Result<(), String> {
-    let artifact_raw = std::fs::read_to_string(circuit_path)
-        .map_err(|e| format!("failed to read circuit artifact '{}': {}", circuit_path, e))?;
-    let artifact_json: serde_json::Value = serde_json::from_str(&artifact_raw).map_err(|e| {
-        format!(
-            "failed to parse circuit artifact '{}' as json: {}",
-            circuit_path, e
-        )
-    })?;
-
-    let noir_version = artifact_json
-        .get("noir_version")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            format!(
-                "circuit artifact '{}' is missing noir_version metadata",
-                circuit_path
-            )
-        })?;
-
-    if !noir_version.starts_with(EXPECTED_NOIR_VERSION_PREFIX) {
-        return Err(format!(
-            "circuit artifact '{}' noir_version='{}' is incompatible with co-noir parser expectations (need '{}*'). Recompile with ./scripts/compile-circuits.sh",
-            circuit_path,
-            noir_version,
-            EXPECTED_NOIR_VERSION_PREFIX
-        ));
+/// Deck positions must be in range and distinct.
+fn validate_deck_indices(field: &'static str, indices: &[u32]) -> Result<(), PrepareError> {
+    let mut seen = HashSet::new();
+    for idx in indices {
+        if *idx as usize >= DECK_SIZE {
+            return Err(invalid(
+                field,
+                format!("index {} out of range 0..{}", idx, DECK_SIZE),
+            ));
+        }
+        if !seen.insert(*idx) {
+            return Err(invalid(field, format!("duplicate index {}", idx)));
+        }
     }
+    Ok(())
+}
 
+/// Values are written into Prover.toml verbatim, so only canonical BN254
+/// field elements (decimal or `0x` hex, below the modulus) are accepted.
+fn validate_field_element(field: &'static str, value: &str) -> Result<(), PrepareError> {
+    let in_range = if let Some(digits) = value.strip_prefix("0x") {
+        if digits.is_empty() || digits.len() > 64 || !digits.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return Err(invalid(field, "expected a hex field element"));
+        }
+        let bytes = hex::decode(format!("{:0>64}", digits))
+            .map_err(|_| invalid(field, "expected a hex field element"))?;
+        Fr::from_be_bytes_mod_order(&bytes)
+            .into_bigint()
+            .to_bytes_be()
+            == bytes
+    } else {
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid(field, "expected a decimal field element"));
+        }
+        let canonical = Fr::from_str(value)
+            .map(|fr| fr.into_bigint().to_string())
+            .map_err(|_| invalid(field, "expected a decimal field element"))?;
+        let trimmed = value.trim_start_matches('0');
+        canonical == if trimmed.is_empty() { "0" } else { trimmed }
+    };
+    if !in_range {
+        return Err(invalid(field, "field element exceeds the BN254 modulus"));
+    }
     Ok(())
 }
 
@@ -599,6 +951,24 @@ fn format_u32_array(values: &[u32]) -> String {
     format!("[{}]", joined)
 }
 
+fn format_u64_array(values: &[u64]) -> String {
+    let joined = values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("[{}]", joined)
+}
+
+fn format_bool_array(values: &[bool]) -> String {
+    let joined = values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("[{}]", joined)
+}
+
 fn format_field_array(values: &[String]) -> String {
     let joined = values
         .iter()
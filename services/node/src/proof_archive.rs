@@ -0,0 +1,244 @@
+//! Retained copies of the proofs this node helped generate.
+//!
+//! When a proof session completes, the proof, its public inputs and the
+//! SHA-256 of this node's merged input share and witness share are written
+//! to `PROOF_ARCHIVE_DIR` (default `<CONTRIBUTION_STORE_DIR>/proofs`), one
+//! `<session_id>.json` per proof. Records outlive the session work directory
+//! and are pruned after `PROOF_RETENTION_HOURS` (default a week; `0`
+//! disables the archive), so committee members keep an audit trail of what
+//! they proved that does not depend on the coordinator.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::session::now_unix_ms;
+use crate::workdir;
+
+const DEFAULT_RETENTION_HOURS: u64 = 7 * 24;
+const PRUNE_INTERVAL_SECS: u64 = 10 * 60;
+
+/// A completed proof as this node produced it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedProof {
+    #[serde(flatten)]
+    pub summary: ArchivedProofSummary,
+    /// Base64-encoded proof bytes.
+    pub proof: String,
+    pub public_inputs: Vec<String>,
+}
+
+/// Listing entry for an archived proof.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedProofSummary {
+    pub session_id: String,
+    pub table_id: Option<u32>,
+    pub circuit_name: String,
+    pub completed_at_ms: u64,
+    pub proof_sha256: String,
+    /// Hash of this node's merged input share (`Prover.toml`).
+    pub input_share_sha256: Option<String>,
+    /// Hash of this node's witness share.
+    pub witness_sha256: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct ProofArchive {
+    dir: PathBuf,
+    retention_ms: u64,
+    /// Summaries of every record on disk, oldest first.
+    index: Arc<Mutex<Vec<ArchivedProofSummary>>>,
+}
+
+impl ProofArchive {
+    /// Open the archive from `PROOF_ARCHIVE_DIR` / `PROOF_RETENTION_HOURS`
+    /// and index the records already on disk. `None` when retention is 0.
+    pub fn from_env(state_dir: &Path) -> Result<Option<Self>, String> {
+        let retention_hours = match std::env::var("PROOF_RETENTION_HOURS") {
+            Ok(raw) if !raw.trim().is_empty() => raw
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| "PROOF_RETENTION_HOURS must be a non-negative integer".to_string())?,
+            _ => DEFAULT_RETENTION_HOURS,
+        };
+        if retention_hours == 0 {
+            return Ok(None);
+        }
+        let dir = std::env::var("PROOF_ARCHIVE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| state_dir.join("proofs"));
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+
+        let archive = Self {
+            index: Arc::new(Mutex::new(load_index(&dir)?)),
+            dir,
+            retention_ms: retention_hours * 60 * 60 * 1000,
+        };
+        Ok(Some(archive))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn retention_hours(&self) -> u64 {
+        self.retention_ms / (60 * 60 * 1000)
+    }
+
+    /// Archive the proof of a completed session. `work_dir` is read for the
+    /// input and witness shares, so this must run before it is deleted.
+    pub async fn record(
+        &self,
+        session_id: &str,
+        circuit_name: &str,
+        work_dir: &Path,
+        proof: &[u8],
+        public_inputs: &[String],
+    ) -> Result<(), String> {
+        use base64::Engine;
+
+        let record = ArchivedProof {
+            summary: ArchivedProofSummary {
+                session_id: session_id.to_string(),
+                table_id: workdir::table_id_from_session(session_id),
+                circuit_name: circuit_name.to_string(),
+                completed_at_ms: now_unix_ms(),
+                proof_sha256: hex::encode(Sha256::digest(proof)),
+                input_share_sha256: sha256_file(&work_dir.join("Prover.toml")).await,
+                witness_sha256: sha256_file(&work_dir.join("witness.gz")).await,
+            },
+            proof: base64::engine::general_purpose::STANDARD.encode(proof),
+            public_inputs: public_inputs.to_vec(),
+        };
+        let bytes = serde_json::to_vec(&record)
+            .map_err(|e| format!("failed to serialize proof record: {}", e))?;
+        let path = self.record_path(session_id);
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, &bytes)
+            .await
+            .map_err(|e| format!("failed to write {}: {}", tmp.display(), e))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| format!("failed to persist {}: {}", path.display(), e))?;
+
+        let mut index = self.index.lock().unwrap_or_else(|e| e.into_inner());
+        index.retain(|s| s.session_id != session_id);
+        index.push(record.summary);
+        Ok(())
+    }
+
+    /// Archived proofs, newest first, optionally for one table only.
+    /// Returns the total number of matches along with the requested page.
+    pub fn list(
+        &self,
+        table_id: Option<u32>,
+        offset: usize,
+        limit: usize,
+    ) -> (usize, Vec<ArchivedProofSummary>) {
+        let index = self.index.lock().unwrap_or_else(|e| e.into_inner());
+        let matching: Vec<&ArchivedProofSummary> = index
+            .iter()
+            .rev()
+            .filter(|s| table_id.is_none() || s.table_id == table_id)
+            .collect();
+        let page = matching
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|s| (*s).clone())
+            .collect();
+        (matching.len(), page)
+    }
+
+    /// Full record for one archived proof.
+    pub async fn get(&self, session_id: &str) -> Result<Option<ArchivedProof>, String> {
+        let known = self
+            .index
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|s| s.session_id == session_id);
+        if !known {
+            return Ok(None);
+        }
+        let path = self.record_path(session_id);
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+        };
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| format!("invalid proof record {}: {}", path.display(), e))
+    }
+
+    fn record_path(&self, session_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", session_id))
+    }
+
+    /// Spawn the periodic task that deletes records past the retention window.
+    pub fn spawn_pruner(&self) {
+        let archive = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(PRUNE_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                archive.prune().await;
+            }
+        });
+    }
+
+    async fn prune(&self) {
+        let cutoff = now_unix_ms().saturating_sub(self.retention_ms);
+        let expired: Vec<String> = {
+            let mut index = self.index.lock().unwrap_or_else(|e| e.into_inner());
+            let (expired, kept): (Vec<_>, Vec<_>) =
+                index.drain(..).partition(|s| s.completed_at_ms < cutoff);
+            *index = kept;
+            expired.into_iter().map(|s| s.session_id).collect()
+        };
+        for session_id in expired {
+            let path = self.record_path(&session_id);
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to remove {}: {}", path.display(), e);
+                }
+            }
+            tracing::info!("Pruned archived proof {}", session_id);
+        }
+    }
+}
+
+/// Read the summary of every record under `dir`, oldest first. Unreadable
+/// records are skipped with a warning.
+fn load_index(dir: &Path) -> Result<Vec<ArchivedProofSummary>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+    let mut index = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let record = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                serde_json::from_slice::<ArchivedProof>(&bytes).map_err(|e| e.to_string())
+            });
+        match record {
+            Ok(record) => index.push(record.summary),
+            Err(e) => tracing::warn!("Skipping archived proof {}: {}", path.display(), e),
+        }
+    }
+    index.sort_by_key(|s| s.completed_at_ms);
+    Ok(index)
+}
+
+async fn sha256_file(path: &Path) -> Option<String> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    Some(hex::encode(Sha256::digest(&bytes)))
+}
@@ -0,0 +1,40 @@
+//! Local verification of generated proofs before they are handed out.
+//!
+//! Node 0 runs the same UltraHonk verifier as the on-chain `zk-verifier`
+//! contract, off-chain on its arkworks backend, against the circuit's
+//! compact VK (`target/vk.compact`, produced by the verifier crate's
+//! `convert-vk` binary), so a malformed proof fails its session immediately
+//! rather than on submission.
+
+use std::path::Path;
+
+use ultrahonk_soroban_verifier::{ArkBackend, UltraHonkVerifier};
+
+/// Compact (Soroban-format) VK path for `circuit_name` under `circuit_dir`.
+pub fn compact_vk_path(circuit_dir: &str, circuit_name: &str) -> String {
+    format!("{}/{}/target/vk.compact", circuit_dir, circuit_name)
+}
+
+/// Verify a co-noir keccak proof and its public inputs against `vk_path`.
+/// The verifier reads co-noir's variable-size layout directly.
+///
+/// Returns `Ok(false)` when no VK is available to check against.
+pub fn verify_proof(
+    vk_path: &Path,
+    proof: &[u8],
+    public_inputs: &[String],
+) -> Result<bool, String> {
+    let vk = match std::fs::read(vk_path) {
+        Ok(vk) => vk,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(format!("failed to read VK {:?}: {}", vk_path, e)),
+    };
+    let public_inputs = proof_format::public_inputs_to_bytes(public_inputs)?;
+
+    let verifier = UltraHonkVerifier::from_vk_bytes(ArkBackend, &vk)
+        .map_err(|e| format!("invalid VK {:?}: {:?}", vk_path, e))?;
+    verifier
+        .verify_slices(proof, &public_inputs)
+        .map_err(|e| format!("local proof verification failed: {:?}", e))?;
+    Ok(true)
+}
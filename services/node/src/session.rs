@@ -4,29 +4,70 @@
 //! The lifecycle:
 //! 1. Coordinator sends shares via POST /session/:id/shares
 //! 2. Coordinator triggers proof gen via POST /session/:id/generate
-//! 3. Node runs co-noir witness extension + proof generation as subprocesses
+//! 3. Node runs co-noir witness extension + proof generation (see `conoir`)
 //! 4. Coordinator polls GET /session/:id/status and retrieves proof
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use tokio::process::Command;
-use tokio::time::{sleep, Duration};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+use crate::conoir;
+use crate::proof_check;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum SessionStatus {
     /// Shares received, waiting for generate trigger
     SharesReceived,
+    /// Merging per-source share fragments into one input share
+    MergingShares,
     /// Witness extension in progress
     WitnessGenerating,
     /// Proof generation in progress
     ProofGenerating,
+    /// Node 0 checking the proof against the circuit VK
+    VerifyingProof,
+    /// Persisting the generated proof and public inputs
+    WritingProof,
     /// Proof generation complete
     Complete,
     /// Something failed
     Failed(String),
 }
 
+impl SessionStatus {
+    /// Stage name reported over the API.
+    pub fn stage(&self) -> &'static str {
+        match self {
+            SessionStatus::SharesReceived => "shares_received",
+            SessionStatus::MergingShares => "merging_shares",
+            SessionStatus::WitnessGenerating => "witness_generating",
+            SessionStatus::ProofGenerating => "proof_generating",
+            SessionStatus::VerifyingProof => "verifying_proof",
+            SessionStatus::WritingProof => "writing_proof",
+            SessionStatus::Complete => "complete",
+            SessionStatus::Failed(_) => "failed",
+        }
+    }
+}
+
+/// When a session entered (and left) one stage, in unix milliseconds.
+#[derive(Clone, Debug, Serialize)]
+pub struct StageTiming {
+    pub stage: &'static str,
+    pub started_at_ms: u64,
+    pub finished_at_ms: Option<u64>,
+}
+
+pub fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 #[derive(Clone, Debug)]
 pub struct MpcSessionState {
     pub session_id: String,
@@ -46,6 +87,8 @@ pub struct MpcSessionState {
     pub proof_path: Option<PathBuf>,
     /// Public inputs emitted by co-noir for the generated proof.
     pub public_inputs: Option<Vec<String>>,
+    /// Stages entered so far, oldest first.
+    pub stages: Vec<StageTiming>,
 }
 
 impl MpcSessionState {
@@ -61,8 +104,40 @@ impl MpcSessionState {
             witness_path: None,
             proof_path: None,
             public_inputs: None,
+            stages: vec![StageTiming {
+                stage: SessionStatus::SharesReceived.stage(),
+                started_at_ms: now_unix_ms(),
+                finished_at_ms: None,
+            }],
         }
     }
+
+    /// Whether proof generation has completed or failed.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            SessionStatus::Complete | SessionStatus::Failed(_)
+        )
+    }
+
+    /// Move to `status`, closing the current stage and opening a new one.
+    /// Terminal statuses are recorded as already finished.
+    pub fn set_status(&mut self, status: SessionStatus) {
+        if self.status == status {
+            return;
+        }
+        let now = now_unix_ms();
+        if let Some(current) = self.stages.last_mut() {
+            current.finished_at_ms.get_or_insert(now);
+        }
+        let terminal = matches!(status, SessionStatus::Complete | SessionStatus::Failed(_));
+        self.stages.push(StageTiming {
+            stage: status.stage(),
+            started_at_ms: now,
+            finished_at_ms: terminal.then_some(now),
+        });
+        self.status = status;
+    }
 }
 
 /// Save one base64-decoded share fragment from a source party.
@@ -95,6 +170,20 @@ pub fn receive_share_fragment(
         .decode(share_data_b64)
         .map_err(|e| format!("base64 decode error: {}", e))?;
 
+    // Re-dispatched fragments arrive again; an identical one is a no-op.
+    if let Some(existing) = session.partial_share_paths.get(&source_party_id) {
+        if std::fs::read(existing).ok().as_deref() == Some(bytes.as_slice()) {
+            return Ok(());
+        }
+    }
+    if session.status != SessionStatus::SharesReceived {
+        return Err(format!(
+            "session already {}; cannot accept new fragment from source {}",
+            session.status.stage(),
+            source_party_id
+        ));
+    }
+
     let share_path = session
         .work_dir
         .join(format!("share_source_{}.shared", source_party_id));
@@ -104,36 +193,92 @@ pub fn receive_share_fragment(
     session
         .partial_share_paths
         .insert(source_party_id, share_path);
-    session.status = SessionStatus::SharesReceived;
+    write_session_meta(session)?;
     Ok(())
 }
 
-/// Run co-noir proof generation as async subprocesses.
+/// What a session directory needs besides its fragments to be restored.
+#[derive(Serialize, Deserialize)]
+struct SessionMeta {
+    session_id: String,
+    circuit_name: String,
+    total_parties: u32,
+}
+
+const SESSION_META_FILE: &str = "session.json";
+
+fn write_session_meta(session: &MpcSessionState) -> Result<(), String> {
+    let meta = SessionMeta {
+        session_id: session.session_id.clone(),
+        circuit_name: session.circuit_name.clone(),
+        total_parties: session.expected_total_parties.unwrap_or(0),
+    };
+    let json = serde_json::to_vec(&meta).map_err(|e| format!("encode session meta: {}", e))?;
+    std::fs::write(session.work_dir.join(SESSION_META_FILE), json)
+        .map_err(|e| format!("failed to write session meta: {}", e))
+}
+
+/// Rebuild a session from its work directory after a restart, with the
+/// fragments it had received. Returns `None` when the directory holds no
+/// usable session.
+pub fn restore_session(work_dir: &Path) -> Option<MpcSessionState> {
+    let meta: SessionMeta =
+        serde_json::from_slice(&std::fs::read(work_dir.join(SESSION_META_FILE)).ok()?).ok()?;
+    let mut session =
+        MpcSessionState::new(meta.session_id, meta.circuit_name, work_dir.to_path_buf());
+    session.expected_total_parties = Some(meta.total_parties);
+    for entry in std::fs::read_dir(work_dir).ok()?.flatten() {
+        let name = entry.file_name();
+        let source = name
+            .to_str()
+            .and_then(|n| n.strip_prefix("share_source_"))
+            .and_then(|n| n.strip_suffix(".shared"))
+            .and_then(|n| n.parse::<u32>().ok());
+        if let Some(source) = source {
+            session.partial_share_paths.insert(source, entry.path());
+        }
+    }
+    (!session.partial_share_paths.is_empty()).then_some(session)
+}
+
+/// Run co-noir proof generation for a session.
 ///
-/// This spawns two sequential commands:
-/// 1. `co-noir generate-witness` — extends the witness in MPC
-/// 2. `co-noir build-and-generate-proof` — generates the UltraHonk proof in MPC
+/// Three sequential steps:
+/// 1. merge the per-source input share fragments
+/// 2. extend the witness in MPC
+/// 3. generate the UltraHonk proof in MPC
 ///
 /// co-noir handles all peer-to-peer MPC communication internally via TCP.
+///
+/// Each step is recorded on `session` as it starts, so status polls report
+/// the current stage and how long it has been running.
 pub async fn run_proof_generation(
-    session_id: String,
+    session: Arc<RwLock<MpcSessionState>>,
     circuit_dir: String,
-    circuit_name: String,
-    work_dir: PathBuf,
     node_id: u32,
-    partial_share_paths: Vec<(u32, PathBuf)>,
-    expected_total_parties: u32,
     party_config_path: String,
     crs_path: String,
 ) -> Result<(Vec<u8>, Vec<String>), String> {
-    let circuit_path = format!(
-        "{}/{}/target/{}.json",
-        circuit_dir, circuit_name, circuit_name
-    );
+    let (session_id, circuit_name, work_dir, partial_share_paths, expected_total_parties) = {
+        let s = session.read().await;
+        (
+            s.session_id.clone(),
+            s.circuit_name.clone(),
+            s.work_dir.clone(),
+            s.partial_share_paths
+                .iter()
+                .map(|(source, path)| (*source, path.clone()))
+                .collect::<Vec<_>>(),
+            s.expected_total_parties
+                .ok_or_else(|| "no share fragments received".to_string())?,
+        )
+    };
+    let circuit_path = conoir::circuit_path(&circuit_dir, &circuit_name);
     let share_path = work_dir.join("Prover.toml");
     let witness_path = work_dir.join("witness.gz");
     let proof_path = work_dir.join("proof.bin");
     let public_inputs_path = work_dir.join("public_inputs.json");
+    let log_path = work_dir.join(conoir::SESSION_LOG_FILE);
     // Use the CRS file (bn254_g1.dat) from the CRS directory
     let crs_file = format!("{}/bn254_g1.dat", crs_path);
 
@@ -148,6 +293,10 @@ pub async fn run_proof_generation(
     let mut sorted_fragments = partial_share_paths;
     sorted_fragments.sort_by_key(|(source, _)| *source);
 
+    session
+        .write()
+        .await
+        .set_status(SessionStatus::MergingShares);
     tracing::info!(
         "[{}] Merging {} share fragments for circuit {} (node {})",
         session_id,
@@ -156,34 +305,21 @@ pub async fn run_proof_generation(
         node_id
     );
 
-    let mut merge_cmd = Command::new("co-noir");
-    merge_cmd
-        .arg("merge-input-shares")
-        .arg("--circuit")
-        .arg(&circuit_path)
-        .arg("--protocol")
-        .arg("REP3")
-        .arg("--config")
-        .arg(&party_config_path);
-    for (_, path) in &sorted_fragments {
-        merge_cmd.arg("--inputs").arg(path);
-    }
-    merge_cmd.arg("--out").arg(&share_path);
+    let fragment_paths: Vec<PathBuf> = sorted_fragments.into_iter().map(|(_, path)| path).collect();
+    conoir::merge_input_shares(
+        &circuit_path,
+        &party_config_path,
+        &fragment_paths,
+        &share_path,
+        node_id,
+        &log_path,
+    )
+    .await?;
 
-    let merge_output = merge_cmd
-        .output()
+    session
+        .write()
         .await
-        .map_err(|e| format!("failed to spawn co-noir merge-input-shares: {}", e))?;
-
-    if !merge_output.status.success() {
-        let stderr = String::from_utf8_lossy(&merge_output.stderr);
-        let stdout = String::from_utf8_lossy(&merge_output.stdout);
-        return Err(format!(
-            "co-noir merge-input-shares failed (node {}):\nstderr: {}\nstdout: {}",
-            node_id, stderr, stdout
-        ));
-    }
-
+        .set_status(SessionStatus::WitnessGenerating);
     tracing::info!(
         "[{}] Starting witness generation for circuit {} (node {})",
         session_id,
@@ -191,101 +327,40 @@ pub async fn run_proof_generation(
         node_id
     );
 
-    // Step 1: Generate witness in MPC
-    let witness_output = Command::new("co-noir")
-        .arg("generate-witness")
-        .arg("--circuit")
-        .arg(&circuit_path)
-        .arg("--input")
-        .arg(&share_path)
-        .arg("--protocol")
-        .arg("REP3")
-        .arg("--config")
-        .arg(&party_config_path)
-        .arg("--out")
-        .arg(&witness_path)
-        .output()
-        .await
-        .map_err(|e| format!("failed to spawn co-noir generate-witness: {}", e))?;
-
-    if !witness_output.status.success() {
-        let stderr = String::from_utf8_lossy(&witness_output.stderr);
-        let stdout = String::from_utf8_lossy(&witness_output.stdout);
-        return Err(format!(
-            "co-noir generate-witness failed (node {}):\nstderr: {}\nstdout: {}",
-            node_id, stderr, stdout
-        ));
-    }
+    // Step 2: Generate witness in MPC
+    conoir::generate_witness(
+        &circuit_path,
+        &share_path,
+        &party_config_path,
+        &witness_path,
+        node_id,
+        &log_path,
+    )
+    .await?;
 
+    session
+        .write()
+        .await
+        .set_status(SessionStatus::ProofGenerating);
     tracing::info!(
         "[{}] Witness generated, starting proof generation (node {})",
         session_id,
         node_id
     );
 
-    // Step 2: Build and generate proof in MPC
-    let vk_path = format!("{}/{}/target/vk_keccak", circuit_dir, circuit_name);
-    let mut last_proof_output: Option<std::process::Output> = None;
-    for attempt in 1..=3 {
-        let proof_output = Command::new("co-noir")
-            .arg("build-and-generate-proof")
-            .arg("--circuit")
-            .arg(&circuit_path)
-            .arg("--witness")
-            .arg(&witness_path)
-            .arg("--protocol")
-            .arg("REP3")
-            .arg("--config")
-            .arg(&party_config_path)
-            .arg("--crs")
-            .arg(&crs_file)
-            .arg("--hasher")
-            .arg("keccak")
-            .arg("--vk")
-            .arg(&vk_path)
-            .arg("--out")
-            .arg(&proof_path)
-            .arg("--public-input")
-            .arg(&public_inputs_path)
-            .arg("--fields-as-json")
-            .output()
-            .await
-            .map_err(|e| format!("failed to spawn co-noir build-and-generate-proof: {}", e))?;
-
-        if proof_output.status.success() {
-            last_proof_output = Some(proof_output);
-            break;
-        }
-
-        let stderr = String::from_utf8_lossy(&proof_output.stderr);
-        let is_transient_resource_error =
-            stderr.contains("No buffer space available") || stderr.contains("os error 55");
-
-        if is_transient_resource_error && attempt < 3 {
-            tracing::warn!(
-                "[{}] co-noir build-and-generate-proof transient failure on node {} (attempt {}/3): {}",
-                session_id,
-                node_id,
-                attempt,
-                stderr.trim()
-            );
-            sleep(Duration::from_millis((attempt as u64) * 500)).await;
-            continue;
-        }
-
-        let stdout = String::from_utf8_lossy(&proof_output.stdout);
-        return Err(format!(
-            "co-noir build-and-generate-proof failed (node {}):\nstderr: {}\nstdout: {}",
-            node_id, stderr, stdout
-        ));
-    }
-
-    if last_proof_output.is_none() {
-        return Err(format!(
-            "co-noir build-and-generate-proof failed after retries (node {})",
-            node_id
-        ));
-    }
+    // Step 3: Build and generate proof in MPC
+    let vk_path = conoir::vk_path(&circuit_dir, &circuit_name);
+    let job = conoir::ProofJob {
+        circuit_path: &circuit_path,
+        witness_path: &witness_path,
+        party_config_path: &party_config_path,
+        crs_file: &crs_file,
+        vk_path: &vk_path,
+        proof_path: &proof_path,
+        public_inputs_path: &public_inputs_path,
+        log_path: &log_path,
+    };
+    conoir::build_and_generate_proof(&job, &session_id, node_id).await?;
 
     tracing::info!(
         "[{}] Proof generated successfully (node {})",
@@ -301,6 +376,36 @@ pub async fn run_proof_generation(
     let public_inputs: Vec<String> = serde_json::from_slice(&public_inputs_bytes)
         .map_err(|e| format!("failed to parse public inputs json: {}", e))?;
 
+    // Node 0 serves proofs to the coordinator, so it refuses to expose one
+    // that would not verify on-chain.
+    if node_id == 0 {
+        session
+            .write()
+            .await
+            .set_status(SessionStatus::VerifyingProof);
+        let vk_path = PathBuf::from(proof_check::compact_vk_path(&circuit_dir, &circuit_name));
+        let (proof, inputs) = (proof_bytes.clone(), public_inputs.clone());
+        let verified = tokio::task::spawn_blocking(move || {
+            proof_check::verify_proof(&vk_path, &proof, &inputs)
+        })
+        .await
+        .map_err(|e| format!("verification task failed: {}", e))??;
+        if verified {
+            tracing::info!("[{}] Proof verified locally (node {})", session_id, node_id);
+        } else {
+            tracing::warn!(
+                "[{}] No compact VK for {}, skipping local proof verification",
+                session_id,
+                circuit_name
+            );
+        }
+    }
+
+    session
+        .write()
+        .await
+        .set_status(SessionStatus::WritingProof);
+
     Ok((proof_bytes, public_inputs))
 }
 
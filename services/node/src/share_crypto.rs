@@ -0,0 +1,107 @@
+//! Sealed-box encryption of share fragments in transit between nodes.
+//!
+//! Each node holds an X25519 keypair. When the committee's public keys are
+//! registered via `NODE_SHARE_PUBLIC_KEYS` (hex, in party order), every
+//! fragment is sealed to its destination party before dispatch and nodes
+//! refuse plaintext fragments, so a network observer or a misrouted request
+//! never sees share material.
+
+use std::path::Path;
+
+use base64::Engine;
+use crypto_box::{PublicKey, SecretKey};
+
+use crate::keystore::load_or_create_key;
+
+#[derive(Clone)]
+pub struct ShareCipher {
+    secret: SecretKey,
+    /// Registered share public keys of all parties, indexed by party ID.
+    peer_keys: Option<Vec<PublicKey>>,
+}
+
+impl ShareCipher {
+    /// Load this node's share key from `SHARE_KEY_FILE` (generated on first
+    /// start) and the committee's keys from `NODE_SHARE_PUBLIC_KEYS`.
+    pub fn from_env(node_id: u32, state_dir: &Path, num_parties: usize) -> Result<Self, String> {
+        let key_path = std::env::var("SHARE_KEY_FILE")
+            .map(Into::into)
+            .unwrap_or_else(|_| state_dir.join("share.key"));
+        let secret = SecretKey::from_bytes(load_or_create_key(&key_path)?);
+
+        let peer_keys = match std::env::var("NODE_SHARE_PUBLIC_KEYS") {
+            Ok(raw) if !raw.trim().is_empty() => {
+                let keys = raw
+                    .split(',')
+                    .map(|s| {
+                        hex::decode(s.trim())
+                            .ok()
+                            .and_then(|b| PublicKey::from_slice(&b).ok())
+                            .ok_or_else(|| format!("invalid share public key '{}'", s.trim()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if keys.len() != num_parties {
+                    return Err(format!(
+                        "NODE_SHARE_PUBLIC_KEYS has {} keys, expected {}",
+                        keys.len(),
+                        num_parties
+                    ));
+                }
+                if keys.get(node_id as usize) != Some(&secret.public_key()) {
+                    return Err(format!(
+                        "NODE_SHARE_PUBLIC_KEYS entry {} does not match this node's share key",
+                        node_id
+                    ));
+                }
+                Some(keys)
+            }
+            _ => None,
+        };
+
+        Ok(Self { secret, peer_keys })
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.secret.public_key().as_bytes())
+    }
+
+    pub fn is_enforced(&self) -> bool {
+        self.peer_keys.is_some()
+    }
+
+    /// Seal a base64 share payload to `party_id`. Returns the payload to send
+    /// and whether it is encrypted.
+    pub fn seal_for(&self, party_id: u32, share_b64: &str) -> Result<(String, bool), String> {
+        let Some(keys) = self.peer_keys.as_ref() else {
+            return Ok((share_b64.to_string(), false));
+        };
+        let key = keys
+            .get(party_id as usize)
+            .ok_or_else(|| format!("no share public key for party {}", party_id))?;
+        let sealed = key
+            .seal(&mut rand::rngs::OsRng, share_b64.as_bytes())
+            .map_err(|_| format!("failed to seal share for party {}", party_id))?;
+        Ok((
+            base64::engine::general_purpose::STANDARD.encode(sealed),
+            true,
+        ))
+    }
+
+    /// Recover the base64 share payload from a received fragment.
+    pub fn open(&self, share_data: &str, encrypted: bool) -> Result<String, String> {
+        if !encrypted {
+            if self.is_enforced() {
+                return Err("plaintext share fragments are not accepted".to_string());
+            }
+            return Ok(share_data.to_string());
+        }
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(share_data)
+            .map_err(|e| format!("invalid sealed share encoding: {}", e))?;
+        let plaintext = self
+            .secret
+            .unseal(&sealed)
+            .map_err(|_| "failed to open sealed share (wrong recipient?)".to_string())?;
+        String::from_utf8(plaintext).map_err(|_| "sealed share is not valid base64".to_string())
+    }
+}
@@ -0,0 +1,65 @@
+//! TEE-attested node mode.
+//!
+//! With `TEE_MODE` set, the node runs inside a confidential VM (SEV-SNP or
+//! TDX) and fetches an attestation quote at startup through the kernel's
+//! configfs-tsm interface (`TEE_TSM_REPORT_DIR`, default
+//! `/sys/kernel/config/tsm/report`). The quote's report data binds this
+//! node's ID and identity key (see `mpc_auth::attestation`); it is published
+//! on `/health` for the coordinator to check before using the node.
+
+use std::path::{Path, PathBuf};
+
+use mpc_auth::attestation::{report_data, Attestation, TeeProvider};
+use mpc_auth::commitment::CommitmentSigner;
+
+const DEFAULT_TSM_REPORT_DIR: &str = "/sys/kernel/config/tsm/report";
+
+fn tee_mode_enabled() -> bool {
+    match std::env::var("TEE_MODE") {
+        Ok(value) => matches!(
+            value.to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ),
+        Err(_) => false,
+    }
+}
+
+/// Fetch the node's attestation if TEE mode is on. `Ok(None)` when off.
+pub fn attest_from_env(
+    node_id: u32,
+    signer: &CommitmentSigner,
+) -> Result<Option<Attestation>, String> {
+    if !tee_mode_enabled() {
+        return Ok(None);
+    }
+    let dir = std::env::var("TEE_TSM_REPORT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_TSM_REPORT_DIR));
+    let data = report_data(node_id, &signer.verifying_key());
+    let (provider, quote) = tsm_report(&dir, &format!("stellar-poker-node-{}", node_id), &data)?;
+    Attestation::new(provider, &quote).map(Some)
+}
+
+/// Request a report through configfs-tsm: create an entry, write the report
+/// data to `inblob`, and read the platform's quote back from `outblob`.
+fn tsm_report(dir: &Path, name: &str, data: &[u8]) -> Result<(TeeProvider, Vec<u8>), String> {
+    let entry = dir.join(name);
+    if let Err(e) = std::fs::create_dir(&entry) {
+        if e.kind() != std::io::ErrorKind::AlreadyExists {
+            return Err(format!("failed to create TSM report {:?}: {}", entry, e));
+        }
+    }
+    let result = (|| {
+        std::fs::write(entry.join("inblob"), data)
+            .map_err(|e| format!("failed to write TSM report data: {}", e))?;
+        let provider = std::fs::read_to_string(entry.join("provider"))
+            .map_err(|e| format!("failed to read TSM provider: {}", e))?;
+        let quote = std::fs::read(entry.join("outblob"))
+            .map_err(|e| format!("failed to read TSM quote: {}", e))?;
+        Ok((TeeProvider::parse(&provider)?, quote))
+    })();
+    // configfs entries are removed with rmdir; a leftover one only costs a
+    // kernel object, so failure here is not fatal.
+    let _ = std::fs::remove_dir(&entry);
+    result
+}
@@ -0,0 +1,282 @@
+//! Per-table working directories for proof sessions and share splits.
+//!
+//! Layout under `NODE_WORK_DIR` (default `./work/node_<id>`):
+//!
+//! ```text
+//! table_<id>/session_<session_id>/   share fragments, witness and proof
+//! table_<id>/split-<random>/         scratch for one split-input, removed on drop
+//! ```
+//!
+//! Each table is capped at `NODE_MAX_SESSIONS_PER_TABLE` live sessions and
+//! `NODE_TABLE_QUOTA_MB` on disk. A session's directory is deleted once its
+//! proof has been fetched; a background reaper removes finished sessions
+//! after `NODE_SESSION_RETENTION_SECS` and abandoned ones after an hour.
+//! On startup, sessions that had received share fragments are restored from
+//! their directories so a restarted node can still take part in them;
+//! everything else under the table directories is cleared.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tempfile::TempDir;
+
+use crate::session::{self, now_unix_ms, MpcSessionState};
+use crate::NodeState;
+
+const DEFAULT_MAX_SESSIONS_PER_TABLE: usize = 8;
+const DEFAULT_TABLE_QUOTA_MB: u64 = 1024;
+const DEFAULT_RETENTION_SECS: u64 = 10 * 60;
+const STALLED_SESSION_SECS: u64 = 60 * 60;
+const REAPER_INTERVAL_SECS: u64 = 60;
+const MAX_SESSION_ID_LEN: usize = 128;
+
+static LAYOUT: OnceLock<WorkLayout> = OnceLock::new();
+
+#[derive(Debug)]
+pub struct WorkLayout {
+    root: PathBuf,
+    max_sessions_per_table: usize,
+    table_quota_bytes: u64,
+    retention_secs: u64,
+}
+
+/// Why a table cannot take on more work.
+#[derive(Debug)]
+pub enum QuotaError {
+    TooManySessions(usize),
+    DiskQuota { used: u64, limit: u64 },
+}
+
+impl std::fmt::Display for QuotaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaError::TooManySessions(max) => {
+                write!(f, "table already has {} live proof sessions", max)
+            }
+            QuotaError::DiskQuota { used, limit } => {
+                write!(f, "table work dir uses {} of {} bytes", used, limit)
+            }
+        }
+    }
+}
+
+/// Read the layout from the environment. Must be called once at startup,
+/// before [`restore_sessions`].
+pub fn init_from_env(node_id: u32) -> Result<&'static WorkLayout, String> {
+    let read = |key: &str, default: u64| -> Result<u64, String> {
+        match std::env::var(key) {
+            Ok(raw) if !raw.trim().is_empty() => raw
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|v| *v > 0)
+                .ok_or_else(|| format!("{} must be a positive integer", key)),
+            _ => Ok(default),
+        }
+    };
+    let root = std::env::var("NODE_WORK_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(format!("./work/node_{}", node_id)));
+    let config = WorkLayout {
+        max_sessions_per_table: read(
+            "NODE_MAX_SESSIONS_PER_TABLE",
+            DEFAULT_MAX_SESSIONS_PER_TABLE as u64,
+        )? as usize,
+        table_quota_bytes: read("NODE_TABLE_QUOTA_MB", DEFAULT_TABLE_QUOTA_MB)? * 1024 * 1024,
+        retention_secs: read("NODE_SESSION_RETENTION_SECS", DEFAULT_RETENTION_SECS)?,
+        root,
+    };
+
+    std::fs::create_dir_all(&config.root)
+        .map_err(|e| format!("failed to create {}: {}", config.root.display(), e))?;
+
+    LAYOUT
+        .set(config)
+        .map_err(|_| "work directories already initialized".to_string())?;
+    Ok(layout())
+}
+
+/// Restore proof sessions left by a previous run and delete everything
+/// else under the table directories (scratch dirs, unusable sessions).
+pub fn restore_sessions() -> Result<Vec<MpcSessionState>, String> {
+    let root = &layout().root;
+    let entries =
+        std::fs::read_dir(root).map_err(|e| format!("failed to read {}: {}", root.display(), e))?;
+    let mut restored = Vec::new();
+    for table in entries.flatten() {
+        if !table.file_name().to_string_lossy().starts_with("table_") {
+            continue;
+        }
+        let Ok(children) = std::fs::read_dir(table.path()) else {
+            continue;
+        };
+        for child in children.flatten() {
+            let path = child.path();
+            let session = child
+                .file_name()
+                .to_string_lossy()
+                .starts_with("session_")
+                .then(|| session::restore_session(&path))
+                .flatten();
+            match session {
+                Some(session) => restored.push(session),
+                None => std::fs::remove_dir_all(&path)
+                    .map_err(|e| format!("failed to clear {}: {}", path.display(), e))?,
+            }
+        }
+    }
+    Ok(restored)
+}
+
+fn layout() -> &'static WorkLayout {
+    LAYOUT.get().expect("work directories not initialized")
+}
+
+impl WorkLayout {
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn table_quota_bytes(&self) -> u64 {
+        self.table_quota_bytes
+    }
+
+    pub fn max_sessions_per_table(&self) -> usize {
+        self.max_sessions_per_table
+    }
+}
+
+fn table_dir(table_id: u32) -> PathBuf {
+    layout().root.join(format!("table_{}", table_id))
+}
+
+/// Table a proof session belongs to, from its `table-<id>-...` ID.
+pub fn table_id_from_session(session_id: &str) -> Option<u32> {
+    session_id
+        .strip_prefix("table-")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Create the working directory for a proof session.
+pub fn create_session_dir(table_id: u32, session_id: &str) -> Result<PathBuf, String> {
+    if session_id.is_empty()
+        || session_id.len() > MAX_SESSION_ID_LEN
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("invalid session id '{}'", session_id));
+    }
+    let dir = table_dir(table_id).join(format!("session_{}", session_id));
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Scratch directory for one share split, deleted when dropped.
+pub fn scratch_dir(table_id: u32) -> Result<TempDir, String> {
+    let dir = table_dir(table_id);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    tempfile::Builder::new()
+        .prefix("split-")
+        .tempdir_in(&dir)
+        .map_err(|e| format!("tmpdir: {}", e))
+}
+
+/// Check a table may open another proof session.
+pub fn check_session_quota(table_id: u32) -> Result<(), QuotaError> {
+    let max = layout().max_sessions_per_table;
+    let live = std::fs::read_dir(table_dir(table_id))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with("session_"))
+                .count()
+        })
+        .unwrap_or(0);
+    if live >= max {
+        return Err(QuotaError::TooManySessions(max));
+    }
+    Ok(())
+}
+
+/// Check a table is still under its disk quota.
+pub fn check_disk_quota(table_id: u32) -> Result<(), QuotaError> {
+    let limit = layout().table_quota_bytes;
+    let used = dir_size(&table_dir(table_id));
+    if used >= limit {
+        return Err(QuotaError::DiskQuota { used, limit });
+    }
+    Ok(())
+}
+
+/// Delete a session directory in the background.
+pub fn remove_session_dir(dir: PathBuf) {
+    tokio::spawn(async move {
+        if let Err(e) = tokio::fs::remove_dir_all(&dir).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove {}: {}", dir.display(), e);
+            }
+        }
+    });
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Spawn the periodic task that drops expired sessions and their files.
+pub fn spawn_session_reaper(state: NodeState) {
+    let retention_ms = layout().retention_secs * 1000;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(REAPER_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            reap_sessions(&state, retention_ms).await;
+        }
+    });
+}
+
+async fn reap_sessions(state: &NodeState, retention_ms: u64) {
+    let now = now_unix_ms();
+    let mut sessions = state.sessions.write().await;
+    let mut expired = Vec::new();
+    for (session_id, lock) in sessions.iter() {
+        // A locked session is busy, so it is not due for reaping.
+        let Ok(session) = lock.try_read() else {
+            continue;
+        };
+        let terminal = session.is_finished();
+        let last_change = session
+            .stages
+            .last()
+            .map(|s| s.finished_at_ms.unwrap_or(s.started_at_ms))
+            .unwrap_or(0);
+        let created = session.stages.first().map(|s| s.started_at_ms).unwrap_or(0);
+        if (terminal && now.saturating_sub(last_change) >= retention_ms)
+            || now.saturating_sub(created) >= STALLED_SESSION_SECS * 1000
+        {
+            expired.push((session_id.clone(), session.work_dir.clone()));
+        }
+    }
+    for (session_id, work_dir) in expired {
+        sessions.remove(&session_id);
+        remove_session_dir(work_dir);
+        tracing::info!("Reaped proof session {}", session_id);
+    }
+}
@@ -0,0 +1,39 @@
+//! Hand evaluation throughput.
+//!
+//! Every evaluator is benchmarked in the `evaluate_7` group over the same
+//! seeded seven-card hands, so alternatives such as a table-driven evaluator
+//! are compared like for like by adding them to that group.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use stellar_zk_cards::{evaluate_hand, shuffle_deck};
+
+/// Hands dealt from decks shuffled with seeds `0..count`.
+fn seeded_hands(count: u64) -> Vec<[u32; 7]> {
+    (0..count)
+        .map(|seed| {
+            let deck = shuffle_deck(seed);
+            let mut hand = [0u32; 7];
+            hand.copy_from_slice(&deck[..7]);
+            hand
+        })
+        .collect()
+}
+
+fn evaluate(c: &mut Criterion) {
+    let hands = seeded_hands(1_000);
+    let mut group = c.benchmark_group("evaluate_7");
+    group.throughput(Throughput::Elements(hands.len() as u64));
+    group.bench_function("evaluate_hand", |b| {
+        b.iter(|| {
+            for hand in &hands {
+                black_box(evaluate_hand(black_box(hand)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, evaluate);
+criterion_main!(benches);
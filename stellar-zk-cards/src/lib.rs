@@ -37,6 +37,27 @@ impl Card {
     }
 }
 
+/// Deterministically shuffle a full deck from `seed` (Fisher-Yates over a
+/// SplitMix64 stream). For local simulation and tests: real decks are
+/// shuffled by the MPC committee.
+pub fn shuffle_deck(seed: u64) -> [u32; DECK_SIZE as usize] {
+    let mut deck = [0u32; DECK_SIZE as usize];
+    for (i, card) in deck.iter_mut().enumerate() {
+        *card = i as u32;
+    }
+
+    let mut state = seed;
+    for i in (1..deck.len()).rev() {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        deck.swap(i, (z % (i as u64 + 1)) as usize);
+    }
+    deck
+}
+
 /// Hand ranking categories (higher = better)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -285,6 +306,18 @@ mod test {
         assert_eq!(card.rank(), 12);
     }
 
+    #[test]
+    fn test_shuffle_is_a_seeded_permutation() {
+        let deck = shuffle_deck(7);
+        let mut seen = [false; DECK_SIZE as usize];
+        for &card in deck.iter() {
+            assert!(!seen[card as usize]);
+            seen[card as usize] = true;
+        }
+        assert_eq!(deck, shuffle_deck(7));
+        assert_ne!(deck, shuffle_deck(8));
+    }
+
     #[test]
     fn test_royal_flush_beats_straight_flush() {
         // Royal flush: 10♣ J♣ Q♣ K♣ A♣ + 2♦ 3♦
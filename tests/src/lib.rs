@@ -0,0 +1,243 @@
+//! End-to-end harness for the poker contracts.
+//!
+//! [`Harness::new`] deploys the poker table, the zk-verifier, the committee
+//! registry and the mock Game Hub into one Soroban `Env` and seats players at
+//! a table. The MPC committee is mocked: the verifier runs with
+//! `zk_verifier::TEST_MODE_VK`, so the committee's calls carry canned proofs
+//! and the harness chooses the cards they commit to.
+
+use committee_registry::{CommitteeRegistryContract, CommitteeRegistryContractClient};
+use game_hub::{MockGameHub, MockGameHubClient};
+use poker_table::{
+    public_inputs, Action, PokerTableContract, PokerTableContractClient, TableConfig, TableState,
+};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    Address, Bytes, BytesN, Env, String, Vec,
+};
+use zk_verifier::{CircuitType, ZkVerifierContract, ZkVerifierContractClient, TEST_MODE_VK};
+
+pub const SMALL_BLIND: i128 = 5;
+pub const BIG_BLIND: i128 = 10;
+const COMMITTEE_STAKE: i128 = 1_000;
+
+pub struct Harness {
+    pub env: Env,
+    pub token: TokenClient<'static>,
+    pub table: PokerTableContractClient<'static>,
+    pub verifier: ZkVerifierContractClient<'static>,
+    pub registry: CommitteeRegistryContractClient<'static>,
+    pub hub: MockGameHubClient<'static>,
+    pub admin: Address,
+    pub committee: Address,
+    pub players: std::vec::Vec<Address>,
+    pub table_id: u32,
+    /// Sum of the buy-ins: every chip on the table.
+    pub total_chips: i128,
+}
+
+impl Harness {
+    /// Deploy every contract and seat one player per entry of `buy_ins`.
+    pub fn new(buy_ins: &[i128]) -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let sac = env.register_stellar_asset_contract_v2(admin.clone());
+        let token = TokenClient::new(&env, &sac.address());
+        let minter = StellarAssetClient::new(&env, &sac.address());
+
+        let verifier_id = env.register(ZkVerifierContract, ());
+        let verifier = ZkVerifierContractClient::new(&env, &verifier_id);
+        verifier.initialize(&admin);
+        let test_vk = Bytes::from_slice(&env, TEST_MODE_VK);
+        for circuit in [
+            CircuitType::DealValid,
+            CircuitType::RevealBoardValid,
+            CircuitType::ShowdownValid,
+        ] {
+            verifier.set_verification_key(&admin, &circuit, &test_vk);
+        }
+
+        // Three staked members form the epoch the committee account acts for.
+        let registry_id = env.register(CommitteeRegistryContract, ());
+        let registry = CommitteeRegistryContractClient::new(&env, &registry_id);
+        registry.initialize(&admin, &sac.address(), &COMMITTEE_STAKE);
+        let mut members = Vec::new(&env);
+        for i in 0..3 {
+            let member = Address::generate(&env);
+            minter.mint(&member, &COMMITTEE_STAKE);
+            let endpoint = String::from_str(&env, ["node-0", "node-1", "node-2"][i]);
+            registry.register_member(&member, &COMMITTEE_STAKE, &endpoint);
+            members.push_back(member);
+        }
+        registry.create_epoch(&admin, &members, &2);
+        let committee = Address::generate(&env);
+
+        let hub_id = env.register(MockGameHub, ());
+        let hub = MockGameHubClient::new(&env, &hub_id);
+
+        let table_contract = env.register(PokerTableContract, ());
+        let table = PokerTableContractClient::new(&env, &table_contract);
+        let table_id = table.create_table(
+            &admin,
+            &TableConfig {
+                token: sac.address(),
+                min_buy_in: 100,
+                max_buy_in: 10_000,
+                small_blind: SMALL_BLIND,
+                big_blind: BIG_BLIND,
+                max_players: 6,
+                deal_timeout_ledgers: 100,
+                reveal_timeout_ledgers: 100,
+                showdown_timeout_ledgers: 100,
+                betting_timeout_ledgers: 100,
+                time_bank_ledgers: 0,
+                committee: Vec::from_array(&env, [committee.clone()]),
+                committee_threshold: 1,
+                verifier: verifier_id,
+                game_hub: hub_id,
+                committee_registry: registry_id,
+                rake_bps: 0,
+                rake_cap: 0,
+                chip_unit: 1,
+                jackpot_bps: 0,
+            },
+        );
+
+        let mut players = std::vec::Vec::new();
+        for &buy_in in buy_ins {
+            let player = Address::generate(&env);
+            minter.mint(&player, &buy_in);
+            table.join_table(&table_id, &player, &buy_in);
+            players.push(player);
+        }
+
+        Harness {
+            env,
+            token,
+            table,
+            verifier,
+            registry,
+            hub,
+            admin,
+            committee,
+            players,
+            table_id,
+            total_chips: buy_ins.iter().sum(),
+        }
+    }
+
+    pub fn state(&self) -> TableState {
+        self.table.get_table(&self.table_id)
+    }
+
+    pub fn stacks(&self) -> std::vec::Vec<i128> {
+        self.state()
+            .players
+            .iter()
+            .flatten()
+            .map(|p| p.stack)
+            .collect()
+    }
+
+    /// A canned proof the test-mode verifier accepts. `tag` keeps the proofs
+    /// of one hand distinct.
+    pub fn proof(&self, tag: u8) -> Bytes {
+        Bytes::from_slice(&self.env, &[tag; 64])
+    }
+
+    pub fn start_hand(&self) {
+        self.table.start_hand(&self.table_id);
+    }
+
+    /// Commit a deal of two hole cards per player from the top of the deck.
+    pub fn commit_deal(&self) {
+        let n = self.players.len() as u32;
+        let mut commitments = Vec::new(&self.env);
+        for seat in 0..n {
+            commitments.push_back(BytesN::from_array(&self.env, &[seat as u8 + 1; 32]));
+        }
+        let mut dealt = Vec::new(&self.env);
+        for i in 0..2 * n {
+            dealt.push_back(i);
+        }
+        let deck_root = BytesN::from_array(&self.env, &[0xdd; 32]);
+        let nonce = self.state().session_id;
+        self.table.commit_deal(
+            &self.table_id,
+            &self.committee,
+            &deck_root,
+            &commitments,
+            &dealt,
+            &self.proof(1),
+            &public_inputs::encode_deal(&self.env, nonce, &deck_root, &commitments, &dealt),
+        );
+    }
+
+    /// Reveal board `cards`, taken from deck positions `indices`.
+    pub fn reveal(&self, cards: &[u32], indices: &[u32]) {
+        let state = self.state();
+        let tag = 2 + state.board_cards.len() as u8;
+        let cards = Vec::from_slice(&self.env, cards);
+        let indices = Vec::from_slice(&self.env, indices);
+        self.table.reveal_board(
+            &self.table_id,
+            &self.committee,
+            &cards,
+            &indices,
+            &self.proof(tag),
+            &public_inputs::encode_reveal(
+                &self.env,
+                state.session_id,
+                &state.deck_root,
+                &cards,
+                &indices,
+            ),
+        );
+    }
+
+    /// Act for the player in `seat`, which must be the seat to act.
+    pub fn act(&self, seat: u32, action: Action) {
+        assert_eq!(
+            self.state().current_turn,
+            seat,
+            "seat {} acting out of turn",
+            seat
+        );
+        self.table
+            .player_action(&self.table_id, &self.players[seat as usize], &action);
+        self.assert_chips_conserved();
+    }
+
+    /// Submit the showdown with the hole cards of every player dealt in, in
+    /// seat order.
+    pub fn showdown(&self, hole_cards: &[(u32, u32)]) {
+        let hole_cards = Vec::from_slice(&self.env, hole_cards);
+        let state = self.state();
+        self.table.submit_showdown(
+            &self.table_id,
+            &self.committee,
+            &public_inputs::shown_hole_cards(&self.env, &state, &hole_cards),
+            &self.proof(9),
+            &public_inputs::encode_showdown(&self.env, &state, &hole_cards),
+        );
+    }
+
+    /// Stacks plus pot add up to the buy-ins, which the table contract holds.
+    pub fn assert_chips_conserved(&self) {
+        let state = self.state();
+        let stacks: i128 = state.players.iter().flatten().map(|p| p.stack).sum();
+        assert!(
+            state.players.iter().flatten().all(|p| p.stack >= 0),
+            "negative stack"
+        );
+        assert_eq!(stacks + state.pot, self.total_chips, "chips not conserved");
+        assert_eq!(
+            self.token.balance(&self.table.address),
+            self.total_chips,
+            "table balance differs from chips in play"
+        );
+    }
+}
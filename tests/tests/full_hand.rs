@@ -0,0 +1,241 @@
+use integration_tests::{Harness, BIG_BLIND, SMALL_BLIND};
+use poker_table::{public_inputs, Action, GamePhase};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Bytes, BytesN,
+};
+
+/// Card index: suit * 13 + rank (rank 0 = deuce, 12 = ace).
+fn card(suit: u32, rank: u32) -> u32 {
+    suit * 13 + rank
+}
+
+#[test]
+fn full_hand_conserves_chips_and_settles_everywhere() {
+    let h = Harness::new(&[1_000, 1_000, 1_000]);
+    h.assert_chips_conserved();
+    assert_eq!(
+        h.registry.get_current_epoch().expect("epoch").members.len(),
+        3
+    );
+
+    // Hand 1: seat 1 deals, seat 2 posts the small blind and seat 0 the big.
+    h.start_hand();
+    let state = h.state();
+    assert_eq!(state.dealer_seat, 1);
+    assert_eq!(state.pot, SMALL_BLIND + BIG_BLIND);
+    h.assert_chips_conserved();
+
+    h.commit_deal();
+    assert_eq!(h.state().phase, GamePhase::Preflop);
+
+    // Preflop: seat 1 and seat 2 call the big blind, which checks.
+    h.act(1, Action::Call);
+    h.act(2, Action::Call);
+    h.act(0, Action::Check);
+    assert_eq!(h.state().phase, GamePhase::DealingFlop);
+
+    // Flop: 2c 7d 9h; seat 2 bets, both call.
+    h.reveal(&[card(0, 0), card(1, 5), card(2, 7)], &[6, 7, 8]);
+    h.act(2, Action::Bet(20));
+    h.act(0, Action::Call);
+    h.act(1, Action::Call);
+    assert_eq!(h.state().phase, GamePhase::DealingTurn);
+
+    // Turn: Ks; seat 2 bets, seat 0 folds, seat 1 calls.
+    h.reveal(&[card(3, 11)], &[9]);
+    h.act(2, Action::Bet(50));
+    h.act(0, Action::Fold);
+    h.act(1, Action::Call);
+    assert_eq!(h.state().phase, GamePhase::DealingRiver);
+
+    // River: 4c; seat 2 bets, seat 1 calls.
+    h.reveal(&[card(0, 2)], &[10]);
+    h.act(2, Action::Bet(10));
+    h.act(1, Action::Call);
+    let state = h.state();
+    assert_eq!(state.phase, GamePhase::Showdown);
+    assert_eq!(state.pot, 210);
+    assert_eq!(state.board_cards.len(), 5);
+
+    // Seat 0 folded 8c 8d, seat 1 holds 3d 5h (king high), seat 2 pocket
+    // aces.
+    h.showdown(&[
+        (card(0, 6), card(1, 6)),
+        (card(1, 1), card(2, 3)),
+        (card(3, 12), card(2, 12)),
+    ]);
+    let state = h.state();
+    assert_eq!(state.phase, GamePhase::Settlement);
+    assert_eq!(state.pot, 0);
+    assert_eq!(h.stacks(), [970, 910, 1_120]);
+    h.assert_chips_conserved();
+
+    // The hub saw the same per-player results.
+    assert_eq!(h.hub.get_points(&h.players[0]), -30);
+    assert_eq!(h.hub.get_points(&h.players[1]), -90);
+    assert_eq!(h.hub.get_points(&h.players[2]), 120);
+    let leader = h.hub.get_leaderboard(&1).get(0).expect("leader");
+    assert_eq!(leader, (h.players[2].clone(), 120));
+
+    // Every proof the committee submitted was recorded by the verifier.
+    for tag in [1u8, 2, 5, 6, 9] {
+        let hash: BytesN<32> = h.env.crypto().keccak256(&h.proof(tag)).into();
+        assert!(
+            h.verifier.is_proof_verified(&hash),
+            "proof {} not recorded",
+            tag
+        );
+    }
+
+    // Everyone cashes out exactly their final stack.
+    for (player, stack) in h.players.iter().zip(h.stacks()) {
+        assert_eq!(h.table.leave_table(&h.table_id, player), stack);
+        assert_eq!(h.token.balance(player), stack);
+    }
+    assert_eq!(h.token.balance(&h.table.address), 0);
+}
+
+#[test]
+fn fold_win_pays_the_blinds_to_the_last_player() {
+    let h = Harness::new(&[500, 500]);
+    h.start_hand();
+    h.commit_deal();
+
+    // Heads-up: seat 1 deals, posts the small blind and acts first.
+    let state = h.state();
+    let to_act = state.current_turn;
+    let other = 1 - to_act;
+    h.act(to_act, Action::Fold);
+
+    let state = h.state();
+    assert_eq!(state.phase, GamePhase::Settlement);
+    let stacks = h.stacks();
+    assert_eq!(stacks.iter().sum::<i128>(), 1_000);
+    assert!(stacks[other as usize] > 500);
+    assert_eq!(
+        h.hub.get_points(&h.players[other as usize]),
+        stacks[other as usize] - 500
+    );
+}
+
+#[test]
+fn deal_without_a_proof_is_rejected() {
+    let h = Harness::new(&[500, 500]);
+    h.start_hand();
+    let deck_root = BytesN::from_array(&h.env, &[0xdd; 32]);
+    let commitments = soroban_sdk::vec![
+        &h.env,
+        BytesN::from_array(&h.env, &[1; 32]),
+        BytesN::from_array(&h.env, &[2; 32]),
+    ];
+    let dealt = soroban_sdk::vec![&h.env, 0, 1, 2, 3];
+    let result = h.table.try_commit_deal(
+        &h.table_id,
+        &h.committee,
+        &deck_root,
+        &commitments,
+        &dealt,
+        &Bytes::new(&h.env),
+        &public_inputs::encode_deal(
+            &h.env,
+            h.state().session_id,
+            &deck_root,
+            &commitments,
+            &dealt,
+        ),
+    );
+    assert!(result.is_err());
+    assert_eq!(h.state().phase, GamePhase::Dealing);
+    h.assert_chips_conserved();
+}
+
+#[test]
+fn six_handed_hand_reports_every_player_to_the_hub() {
+    let h = Harness::new(&[1_000; 6]);
+    h.start_hand();
+    let state = h.state();
+    let (small_blind, big_blind) = (state.small_blind_seat, state.big_blind_seat);
+    h.commit_deal();
+
+    // Everyone folds to the big blind.
+    while h.state().phase == GamePhase::Preflop {
+        h.act(h.state().current_turn, Action::Fold);
+    }
+    assert_eq!(h.state().phase, GamePhase::Settlement);
+
+    for (seat, player) in h.players.iter().enumerate() {
+        let expected = match seat as u32 {
+            s if s == small_blind => -SMALL_BLIND,
+            s if s == big_blind => SMALL_BLIND,
+            _ => 0,
+        };
+        assert_eq!(h.hub.get_points(player), expected, "seat {}", seat);
+    }
+    assert_eq!(h.hub.get_leaderboard(&6).len(), 6);
+}
+
+#[test]
+fn missed_deal_reports_every_committee_member() {
+    let h = Harness::new(&[500, 500]);
+    // Timeouts report the table's own committee, so seat the epoch first.
+    h.table.sync_committee(&h.table_id);
+    h.start_hand();
+    h.env.ledger().with_mut(|l| l.sequence_number += 100);
+    let claimer = Address::generate(&h.env);
+    h.table.claim_timeout(&h.table_id, &claimer);
+
+    assert_eq!(h.state().phase, GamePhase::Waiting);
+    h.assert_chips_conserved();
+    for member in h
+        .registry
+        .get_current_epoch()
+        .expect("epoch")
+        .members
+        .iter()
+    {
+        assert_eq!(h.registry.get_member(&member).slash_count, 1);
+    }
+}
+
+#[test]
+fn table_follows_the_registry_epoch_between_hands() {
+    let h = Harness::new(&[500, 500]);
+    let epoch = h.registry.get_current_epoch().expect("epoch");
+    h.table.sync_committee(&h.table_id);
+    assert_eq!(h.state().committee, epoch.members);
+    assert_eq!(h.state().config.committee_threshold, epoch.threshold);
+
+    // The old committee account is out; an epoch member deals instead.
+    h.start_hand();
+    let deck_root = BytesN::from_array(&h.env, &[0xdd; 32]);
+    let commitments = soroban_sdk::vec![
+        &h.env,
+        BytesN::from_array(&h.env, &[1; 32]),
+        BytesN::from_array(&h.env, &[2; 32]),
+    ];
+    let dealt = soroban_sdk::vec![&h.env, 0, 1, 2, 3];
+    let public_inputs = public_inputs::encode_deal(
+        &h.env,
+        h.state().session_id,
+        &deck_root,
+        &commitments,
+        &dealt,
+    );
+    for (committee, dealt_ok) in [
+        (h.committee.clone(), false),
+        (epoch.members.get(1).unwrap(), true),
+    ] {
+        let result = h.table.try_commit_deal(
+            &h.table_id,
+            &committee,
+            &deck_root,
+            &commitments,
+            &dealt,
+            &h.proof(1),
+            &public_inputs,
+        );
+        assert_eq!(result.is_ok(), dealt_ok);
+    }
+    assert_eq!(h.state().phase, GamePhase::Preflop);
+}
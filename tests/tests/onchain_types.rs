@@ -0,0 +1,83 @@
+//! The services read the table through `poker-table-types`' serde mirrors;
+//! check the mirrored enums still match the contract's.
+
+use std::collections::HashSet;
+
+use integration_tests::Harness;
+use poker_table::{Action, GamePhase};
+use poker_table_types as mirror;
+
+fn mirrored(phase: &GamePhase) -> mirror::GamePhase {
+    let name = format!("{:?}", phase);
+    mirror::GamePhase::ALL
+        .into_iter()
+        .find(|p| p.as_str() == name)
+        .unwrap_or_else(|| panic!("phase {} is not mirrored", name))
+}
+
+#[test]
+fn error_codes_match_the_contract() {
+    for error in mirror::PokerTableError::ALL {
+        let contract = poker_table::PokerTableError::try_from(
+            soroban_sdk::Error::from_contract_error(error.code()),
+        )
+        .unwrap_or_else(|_| panic!("contract has no error #{}", error.code()));
+        assert_eq!(format!("{:?}", contract), format!("{:?}", error));
+    }
+
+    let next = mirror::PokerTableError::ALL.len() as u32 + 1;
+    assert!(
+        poker_table::PokerTableError::try_from(soroban_sdk::Error::from_contract_error(next))
+            .is_err(),
+        "contract error #{} is not mirrored",
+        next
+    );
+}
+
+/// Plays a hand checked down, then one both players shove preflop and run
+/// out.
+#[test]
+fn every_phase_of_a_hand_is_mirrored() {
+    let h = Harness::new(&[1_000, 1_000]);
+    let mut seen = HashSet::new();
+    loop {
+        let state = h.state();
+        seen.insert(mirrored(&state.phase));
+        match state.phase {
+            GamePhase::Waiting => h.start_hand(),
+            GamePhase::Dealing => h.commit_deal(),
+            GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
+                let seat = state.current_turn;
+                let high = state
+                    .players
+                    .iter()
+                    .flatten()
+                    .map(|p| p.bet_this_round)
+                    .max();
+                let to_act = state.players.get(seat).unwrap().unwrap();
+                let action = if state.hand_number == 2 {
+                    Action::AllIn
+                } else if Some(to_act.bet_this_round) < high {
+                    Action::Call
+                } else {
+                    Action::Check
+                };
+                h.act(seat, action);
+            }
+            GamePhase::DealingFlop => h.reveal(&[10, 20, 30], &[4, 5, 6]),
+            GamePhase::DealingTurn => h.reveal(&[40], &[7]),
+            GamePhase::DealingRiver => h.reveal(&[50], &[8]),
+            GamePhase::RunOut => match state.board_cards.len() {
+                0 => h.reveal(&[10, 20, 30], &[4, 5, 6]),
+                3 => h.reveal(&[40], &[7]),
+                _ => h.reveal(&[50], &[8]),
+            },
+            GamePhase::Showdown => h.showdown(&[(0, 1), (2, 3)]),
+            GamePhase::Settlement if state.hand_number == 1 => h.start_hand(),
+            GamePhase::Settlement => break,
+            GamePhase::Dispute => panic!("hand went to dispute"),
+        }
+    }
+    // Every phase but Dispute.
+    assert_eq!(seen.len(), mirror::GamePhase::ALL.len() - 1);
+}
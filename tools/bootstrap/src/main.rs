@@ -0,0 +1,392 @@
+//! Deploy and wire up the poker contracts in one step.
+//!
+//! Shells out to the `stellar` CLI, as the coordinator does, to:
+//! 1. deploy the zk-verifier, game-hub, poker-table and committee-registry
+//!    contracts and the native XLM token contract,
+//! 2. initialize the verifier and upload the circuits' VKs,
+//! 3. initialize the registry, stake each committee member and create the
+//!    first epoch,
+//! 4. create a reference table, and
+//! 5. write every address to the env file the coordinator is started with.
+//!
+//! The source identity is the admin of every contract and the table's
+//! committee account.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+use ultrahonk_soroban_verifier::bb_vk::{vk_from_artifact, vk_to_bytes};
+
+const USAGE: &str = "\
+usage: bootstrap --member IDENTITY=ENDPOINT [--member ...] [options]
+
+  --member IDENTITY=ENDPOINT  stellar identity and URL of an MPC node, in node order
+  --source IDENTITY           admin and committee identity (default committee-local)
+  --rpc-url URL               Soroban RPC (default $SOROBAN_RPC or the local quickstart)
+  --network-passphrase TEXT   (default $NETWORK_PASSPHRASE or the standalone network)
+  --wasm-dir DIR              built contracts (default target/wasm32v1-none/release)
+  --vk-dir DIR                VKs as DIR/<circuit>/target/vk or DIR/<circuit>.vk (default circuits)
+  --stake STROOPS             stake per member, also the registry minimum (default 1000000000)
+  --threshold N               epoch threshold (default a majority of the members)
+  --max-players N             seats at the reference table (default 6)
+  --out FILE                  env file to write (default .env.local)";
+
+const CIRCUITS: [(&str, &str); 3] = [
+    ("deal_valid", "DealValid"),
+    ("reveal_board_valid", "RevealBoardValid"),
+    ("showdown_valid", "ShowdownValid"),
+];
+
+struct Member {
+    identity: String,
+    endpoint: String,
+}
+
+struct Options {
+    source: String,
+    rpc_url: String,
+    network_passphrase: String,
+    wasm_dir: PathBuf,
+    vk_dir: PathBuf,
+    members: Vec<Member>,
+    stake: i128,
+    threshold: Option<u32>,
+    max_players: u32,
+    out: PathBuf,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut opts = Options {
+        source: "committee-local".to_string(),
+        rpc_url: std::env::var("SOROBAN_RPC")
+            .unwrap_or_else(|_| "http://localhost:8000/soroban/rpc".to_string()),
+        network_passphrase: std::env::var("NETWORK_PASSPHRASE")
+            .unwrap_or_else(|_| "Standalone Network ; February 2017".to_string()),
+        wasm_dir: PathBuf::from("target/wasm32v1-none/release"),
+        vk_dir: PathBuf::from("circuits"),
+        members: Vec::new(),
+        stake: 1_000_000_000,
+        threshold: None,
+        max_players: 6,
+        out: PathBuf::from(".env.local"),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--member" => {
+                let spec = value()?;
+                let (identity, endpoint) = spec
+                    .split_once('=')
+                    .ok_or(format!("--member {}: expected IDENTITY=ENDPOINT", spec))?;
+                opts.members.push(Member {
+                    identity: identity.to_string(),
+                    endpoint: endpoint.to_string(),
+                });
+            }
+            "--source" => opts.source = value()?,
+            "--rpc-url" => opts.rpc_url = value()?,
+            "--network-passphrase" => opts.network_passphrase = value()?,
+            "--wasm-dir" => opts.wasm_dir = value()?.into(),
+            "--vk-dir" => opts.vk_dir = value()?.into(),
+            "--stake" => opts.stake = value()?.parse().map_err(|_| "bad --stake")?,
+            "--threshold" => {
+                opts.threshold = Some(value()?.parse().map_err(|_| "bad --threshold")?)
+            }
+            "--max-players" => {
+                opts.max_players = value()?.parse().map_err(|_| "bad --max-players")?
+            }
+            "--out" => opts.out = value()?.into(),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    if opts.members.is_empty() {
+        return Err("at least one --member is required".to_string());
+    }
+    if !(2..=6).contains(&opts.max_players) {
+        return Err("--max-players must be between 2 and 6".to_string());
+    }
+    Ok(opts)
+}
+
+/// Runs the `stellar` CLI against one network.
+struct Stellar {
+    rpc_url: String,
+    network_passphrase: String,
+}
+
+impl Stellar {
+    /// Run `stellar <args>` and return its trimmed stdout.
+    fn run(&self, args: &[&str]) -> Result<String, String> {
+        let output = Command::new("stellar")
+            .args(args)
+            .output()
+            .map_err(|e| format!("failed to run stellar: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "stellar {} failed: {}",
+                args[..2.min(args.len())].join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn network_args<'a>(&'a self, source: &'a str) -> [&'a str; 6] {
+        [
+            "--source",
+            source,
+            "--rpc-url",
+            &self.rpc_url,
+            "--network-passphrase",
+            &self.network_passphrase,
+        ]
+    }
+
+    fn address(&self, identity: &str) -> Result<String, String> {
+        self.run(&["keys", "address", identity])
+    }
+
+    fn secret(&self, identity: &str) -> Result<String, String> {
+        self.run(&["keys", "show", identity])
+    }
+
+    fn deploy(&self, source: &str, wasm: &Path) -> Result<String, String> {
+        let wasm = wasm.to_str().ok_or("non-UTF-8 wasm path")?;
+        let mut args = vec!["contract", "deploy", "--wasm", wasm];
+        args.extend(self.network_args(source));
+        self.run(&args)
+    }
+
+    /// The native XLM token contract, deploying it if this network has none.
+    fn native_token(&self, source: &str) -> Result<String, String> {
+        let mut deploy = vec!["contract", "asset", "deploy", "--asset", "native"];
+        deploy.extend(self.network_args(source));
+        self.run(&deploy).or_else(|_| {
+            self.run(&[
+                "contract",
+                "asset",
+                "id",
+                "--asset",
+                "native",
+                "--network-passphrase",
+                &self.network_passphrase,
+            ])
+        })
+    }
+
+    /// Invoke `function` on `contract` with `--name value` arguments.
+    fn invoke(
+        &self,
+        source: &str,
+        contract: &str,
+        function: &str,
+        fn_args: &[(&str, &str)],
+    ) -> Result<String, String> {
+        let flags: Vec<String> = fn_args
+            .iter()
+            .map(|(name, _)| format!("--{}", name))
+            .collect();
+        let mut args = vec!["contract", "invoke", "--id", contract];
+        args.extend(self.network_args(source));
+        args.extend(["--", function]);
+        for (flag, (_, value)) in flags.iter().zip(fn_args) {
+            args.push(flag);
+            args.push(value);
+        }
+        self.run(&args)
+            .map_err(|e| format!("{}.{}: {}", contract, function, e))
+    }
+}
+
+/// Read a circuit's VK from `vk_dir` and convert it to the verifier's layout.
+fn load_vk(vk_dir: &Path, circuit: &str) -> Result<Vec<u8>, String> {
+    let nested = vk_dir.join(circuit).join("target").join("vk");
+    let flat = vk_dir.join(format!("{}.vk", circuit));
+    let path = if nested.exists() { nested } else { flat };
+    let data = std::fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let vk = vk_from_artifact(&data).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(vk_to_bytes(&vk))
+}
+
+fn run(opts: Options) -> Result<(), String> {
+    let cli = Stellar {
+        rpc_url: opts.rpc_url.clone(),
+        network_passphrase: opts.network_passphrase.clone(),
+    };
+    let source = opts.source.as_str();
+    let admin = cli.address(source)?;
+    let committee_secret = cli.secret(source)?;
+    println!("Admin and committee: {}", admin);
+
+    // Fail on missing artifacts before anything is deployed.
+    let mut vks = Vec::new();
+    for (circuit, circuit_type) in CIRCUITS {
+        vks.push((circuit_type, hex::encode(load_vk(&opts.vk_dir, circuit)?)));
+    }
+    let wasm = |name: &str| -> Result<PathBuf, String> {
+        let path = opts.wasm_dir.join(format!("{}.wasm", name));
+        if path.exists() {
+            Ok(path)
+        } else {
+            Err(format!(
+                "{} not found (run `stellar contract build` first)",
+                path.display()
+            ))
+        }
+    };
+    let wasms = [
+        wasm("zk_verifier")?,
+        wasm("game_hub")?,
+        wasm("poker_table")?,
+        wasm("committee_registry")?,
+    ];
+
+    println!("Deploying contracts...");
+    let mut ids = Vec::new();
+    for path in &wasms {
+        let id = cli.deploy(source, path)?;
+        println!("  {}: {}", path.display(), id);
+        ids.push(id);
+    }
+    let [verifier, game_hub, poker_table, registry] = <[String; 4]>::try_from(ids).unwrap();
+    let token = cli.native_token(source)?;
+    println!("  native XLM token: {}", token);
+
+    println!("Initializing the verifier...");
+    cli.invoke(source, &verifier, "initialize", &[("admin", &admin)])?;
+    for (circuit_type, vk_hex) in &vks {
+        println!("  uploading the {} VK", circuit_type);
+        cli.invoke(
+            source,
+            &verifier,
+            "set_verification_key",
+            &[
+                ("admin", &admin),
+                ("circuit", &format!("\"{}\"", circuit_type)),
+                ("vk_data", vk_hex),
+            ],
+        )?;
+    }
+
+    println!("Registering the committee...");
+    let stake = opts.stake.to_string();
+    cli.invoke(
+        source,
+        &registry,
+        "initialize",
+        &[
+            ("admin", &admin),
+            ("stake_token", &token),
+            ("min_stake", &stake),
+        ],
+    )?;
+    let mut member_addresses = Vec::new();
+    for member in &opts.members {
+        let address = cli.address(&member.identity)?;
+        println!("  {} ({}) at {}", member.identity, address, member.endpoint);
+        cli.invoke(
+            &member.identity,
+            &registry,
+            "register_member",
+            &[
+                ("member", &address),
+                ("stake", &stake),
+                ("endpoint", &member.endpoint),
+            ],
+        )?;
+        member_addresses.push(address);
+    }
+    let threshold = opts
+        .threshold
+        .unwrap_or(member_addresses.len() as u32 / 2 + 1);
+    let members_json = format!("[\"{}\"]", member_addresses.join("\",\""));
+    let epoch = cli.invoke(
+        source,
+        &registry,
+        "create_epoch",
+        &[
+            ("admin", &admin),
+            ("members", &members_json),
+            ("threshold", &threshold.to_string()),
+        ],
+    )?;
+    println!("  epoch {} (threshold {})", epoch, threshold);
+
+    println!("Creating the reference table...");
+    let config = format!(
+        "{{\"token\":\"{token}\",\"min_buy_in\":\"1000000000\",\"max_buy_in\":\"100000000000\",\
+         \"small_blind\":\"500000000\",\"big_blind\":\"1000000000\",\"max_players\":{},\
+         \"deal_timeout_ledgers\":100,\"reveal_timeout_ledgers\":100,\
+         \"showdown_timeout_ledgers\":100,\"betting_timeout_ledgers\":100,\"time_bank_ledgers\":60,\
+         \"committee\":[\"{admin}\"],\"committee_threshold\":1,\"verifier\":\"{verifier}\",\
+         \"game_hub\":\"{game_hub}\",\"committee_registry\":\"{registry}\",\
+         \"rake_bps\":0,\"rake_cap\":\"0\",\"chip_unit\":\"10000000\",\"jackpot_bps\":0}}",
+        opts.max_players
+    );
+    let table_id = cli
+        .invoke(
+            source,
+            &poker_table,
+            "create_table",
+            &[("admin", &admin), ("config", &config)],
+        )?
+        .trim_matches('"')
+        .to_string();
+    println!("  table {}", table_id);
+
+    let env = format!(
+        "# Generated by bootstrap\n\
+         SOROBAN_RPC={}\n\
+         NETWORK_PASSPHRASE=\"{}\"\n\
+         POKER_TABLE_CONTRACT={}\n\
+         ZK_VERIFIER_CONTRACT={}\n\
+         COMMITTEE_REGISTRY_CONTRACT={}\n\
+         GAME_HUB_CONTRACT={}\n\
+         TOKEN_CONTRACT={}\n\
+         TABLE_ID={}\n\
+         ONCHAIN_TABLE_ID={}\n\
+         MAX_PLAYERS={}\n\
+         COMMITTEE_SECRET={}\n\
+         COMMITTEE_ADDRESS={}\n\
+         MPC_NODE_ADDRESSES={}\n",
+        opts.rpc_url,
+        opts.network_passphrase,
+        poker_table,
+        verifier,
+        registry,
+        game_hub,
+        token,
+        table_id,
+        table_id,
+        opts.max_players,
+        committee_secret,
+        admin,
+        member_addresses.join(","),
+    );
+    std::fs::write(&opts.out, env)
+        .map_err(|e| format!("failed to write {}: {}", opts.out.display(), e))?;
+    println!("Coordinator config written to {}", opts.out.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let opts = match parse_args() {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("bootstrap: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(opts) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("bootstrap: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Playing one hand through the coordinator the way the web app does:
+//! deal, reveal the flop, turn and river, then request the showdown.
+//!
+//! A step that fails with a server error or a dropped connection is retried
+//! once the nodes are healthy again, up to `retries` times, so a hand can
+//! survive a node that was killed under it. Every response is checked for
+//! consistency before moving on.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::proxy::Chaos;
+
+/// How long a restarted node gets to come back before a retry.
+const RECOVERY_TIMEOUT: Duration = Duration::from_secs(180);
+
+#[derive(Deserialize)]
+struct DealResponse {
+    status: String,
+    hand_commitments: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RevealResponse {
+    status: String,
+    cards: Vec<u32>,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub struct ShowdownResponse {
+    pub status: String,
+    pub winner: String,
+    pub winner_index: u32,
+}
+
+/// A hand that settled.
+pub struct Played {
+    /// Each step and how many attempts it took.
+    pub steps: Vec<(String, usize)>,
+    pub showdown: ShowdownResponse,
+}
+
+impl Played {
+    /// Steps that needed more than one attempt.
+    pub fn retried(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .filter(|(_, attempts)| *attempts > 1)
+            .map(|(step, attempts)| format!("{} ({} attempts)", step, attempts))
+            .collect()
+    }
+}
+
+pub struct Driver {
+    pub client: reqwest::Client,
+    pub coordinator: String,
+    pub retries: usize,
+    pub chaos: Arc<Chaos>,
+}
+
+impl Driver {
+    async fn post<T: DeserializeOwned>(
+        &self,
+        table_id: u32,
+        path: &str,
+        body: &serde_json::Value,
+        steps: &mut Vec<(String, usize)>,
+    ) -> Result<T, String> {
+        let url = format!("{}/api/table/{}/{}", self.coordinator, table_id, path);
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let error = match self.client.post(&url).json(body).send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    steps.push((path.to_string(), attempts));
+                    return resp
+                        .json()
+                        .await
+                        .map_err(|e| format!("{} returned an unexpected body: {}", path, e));
+                }
+                Ok(resp) if resp.status().is_client_error() => {
+                    return Err(format!("{} was rejected: {}", path, resp.status()))
+                }
+                Ok(resp) => resp.status().to_string(),
+                Err(e) => e.to_string(),
+            };
+            if attempts > self.retries {
+                return Err(format!(
+                    "{} failed after {} attempts: {}",
+                    path, attempts, error
+                ));
+            }
+            self.chaos.wait_healthy(RECOVERY_TIMEOUT).await?;
+            tokio::time::sleep(Duration::from_secs(2 * attempts as u64)).await;
+        }
+    }
+
+    /// Play a hand at `table_id`. With no `players` the coordinator deals
+    /// to whoever is seated on chain.
+    pub async fn play(&self, table_id: u32, players: &[String]) -> Result<Played, String> {
+        let mut steps = Vec::new();
+
+        let body = serde_json::json!({ "players": players });
+        let deal: DealResponse = self
+            .post(table_id, "request-deal", &body, &mut steps)
+            .await?;
+        if deal.status != "dealt" {
+            return Err(format!("deal returned status '{}'", deal.status));
+        }
+        let committed = deal.hand_commitments.len();
+        if committed < 2 || (!players.is_empty() && committed != players.len()) {
+            return Err(format!(
+                "deal committed {} hands for {} players",
+                committed,
+                players.len()
+            ));
+        }
+
+        let mut board = Vec::new();
+        for (phase, count) in [("flop", 3), ("turn", 1), ("river", 1)] {
+            let path = format!("request-reveal/{}", phase);
+            let reveal: RevealResponse = self
+                .post(table_id, &path, &serde_json::Value::Null, &mut steps)
+                .await?;
+            if reveal.status != "revealed" || reveal.cards.len() != count {
+                return Err(format!(
+                    "{} returned status '{}' with {} cards",
+                    phase,
+                    reveal.status,
+                    reveal.cards.len()
+                ));
+            }
+            board.extend(reveal.cards);
+        }
+        for (i, card) in board.iter().enumerate() {
+            if *card >= 52 || board[..i].contains(card) {
+                return Err(format!("board {:?} is not five distinct cards", board));
+            }
+        }
+
+        let showdown: ShowdownResponse = self
+            .post(
+                table_id,
+                "request-showdown",
+                &serde_json::Value::Null,
+                &mut steps,
+            )
+            .await?;
+        match showdown.status.as_str() {
+            "showdown_complete" => {
+                let seat_matches = players.is_empty()
+                    || players.get(showdown.winner_index as usize) == Some(&showdown.winner);
+                if showdown.winner.is_empty() || !seat_matches {
+                    return Err(format!(
+                        "winner {} at index {} is not that seat",
+                        showdown.winner, showdown.winner_index
+                    ));
+                }
+            }
+            "settled_timeout" => {}
+            other => return Err(format!("showdown returned status '{}'", other)),
+        }
+
+        // A settled hand must answer a repeated showdown with the same result.
+        let again: ShowdownResponse = self
+            .post(
+                table_id,
+                "request-showdown",
+                &serde_json::Value::Null,
+                &mut steps,
+            )
+            .await?;
+        if again != showdown {
+            return Err(format!(
+                "repeated showdown returned {:?} after {:?}",
+                again, showdown
+            ));
+        }
+
+        Ok(Played { steps, showdown })
+    }
+}
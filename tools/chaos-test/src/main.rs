@@ -0,0 +1,243 @@
+//! Chaos-test the coordinator against a real MPC committee.
+//!
+//! Puts a fault-injecting proxy (see [`proxy`]) in front of each node and
+//! plays one hand per scenario (see [`scenario`]) through the coordinator,
+//! each on its own table: a lost share fragment, a slow share dispatch,
+//! garbage status responses, a node killed mid-proof and a corrupted
+//! showdown proof. A scenario passes when the hand still settles
+//! consistently and the coordinator recovered through the path meant for
+//! that failure: redispatch, polling on, a client retry or the timeout
+//! fallback.
+//!
+//! Both the coordinator and the nodes' share delivery must go through the
+//! proxies, which listen on `--proxy-port` onwards. With the compose nodes
+//! and the coordinator on the host:
+//!
+//! ```text
+//! NODE_HTTP_ENDPOINTS=http://host.docker.internal:8201,http://host.docker.internal:8202,http://host.docker.internal:8203 \
+//!   docker compose up -d mpc-node-0 mpc-node-1 mpc-node-2
+//! MPC_NODE_0=http://127.0.0.1:8201 MPC_NODE_1=http://127.0.0.1:8202 \
+//!   MPC_NODE_2=http://127.0.0.1:8203 cargo run -p coordinator &
+//! cargo run -p chaos-test -- --bind 0.0.0.0 \
+//!   --kill-cmd 'docker compose kill mpc-node-{node}' \
+//!   --start-cmd 'docker compose start mpc-node-{node}'
+//! ```
+
+mod hand;
+mod proxy;
+mod scenario;
+
+use std::process::ExitCode;
+use std::time::Duration;
+
+use scenario::{Scenario, Tuning};
+
+const USAGE: &str = "\
+usage: chaos-test [options]
+
+  --coordinator URL   coordinator under test (default http://localhost:8080)
+  --node URL          MPC node, in committee order; give all three
+                      (default http://localhost:8101 to 8103)
+  --bind ADDR         address the proxies listen on (default 127.0.0.1)
+  --proxy-port PORT   proxy for node i listens on PORT+i (default 8201)
+  --scenario NAME     run only this scenario; repeatable (default all that can run)
+                      baseline, drop-shares, delay-dispatch, corrupt-status,
+                      kill-node, bad-proof
+  --first-table ID    table of the first scenario; the rest follow (default 2000)
+  --players N         players per hand, 2-6 (default 2)
+  --chain             deal to the players seated on chain instead of random
+                      addresses; the coordinator must have a table contract
+  --kill-cmd CMD      shell command that kills node {node}, e.g.
+                      'docker compose kill mpc-node-{node}'
+  --start-cmd CMD     shell command that starts node {node} again
+  --kill-after-ms MS  how far into the river proof to kill the node (default 2000)
+  --delay-ms MS       how long to hold the slow share dispatch (default 5000)
+  --retries N         client retries per step after a failure (default 3)";
+
+struct Options {
+    coordinator: String,
+    nodes: Vec<String>,
+    bind: String,
+    proxy_port: u16,
+    scenarios: Vec<Scenario>,
+    first_table: u32,
+    players: usize,
+    chain: bool,
+    kill_cmd: Option<String>,
+    start_cmd: Option<String>,
+    tuning: Tuning,
+    retries: usize,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut opts = Options {
+        coordinator: "http://localhost:8080".to_string(),
+        nodes: Vec::new(),
+        bind: "127.0.0.1".to_string(),
+        proxy_port: 8201,
+        scenarios: Vec::new(),
+        first_table: 2000,
+        players: 2,
+        chain: false,
+        kill_cmd: None,
+        start_cmd: None,
+        tuning: Tuning {
+            dispatch_delay: Duration::from_millis(5000),
+            kill_after: Duration::from_millis(2000),
+        },
+        retries: 3,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--coordinator" => opts.coordinator = value()?.trim_end_matches('/').to_string(),
+            "--node" => opts.nodes.push(value()?.trim_end_matches('/').to_string()),
+            "--bind" => opts.bind = value()?,
+            "--proxy-port" => opts.proxy_port = value()?.parse().map_err(|_| "bad --proxy-port")?,
+            "--scenario" => {
+                let name = value()?;
+                let scenario =
+                    Scenario::parse(&name).ok_or_else(|| format!("unknown scenario '{}'", name))?;
+                opts.scenarios.push(scenario);
+            }
+            "--first-table" => {
+                opts.first_table = value()?.parse().map_err(|_| "bad --first-table")?
+            }
+            "--players" => opts.players = value()?.parse().map_err(|_| "bad --players")?,
+            "--chain" => opts.chain = true,
+            "--kill-cmd" => opts.kill_cmd = Some(value()?),
+            "--start-cmd" => opts.start_cmd = Some(value()?),
+            "--kill-after-ms" => {
+                let ms = value()?.parse().map_err(|_| "bad --kill-after-ms")?;
+                opts.tuning.kill_after = Duration::from_millis(ms);
+            }
+            "--delay-ms" => {
+                let ms = value()?.parse().map_err(|_| "bad --delay-ms")?;
+                opts.tuning.dispatch_delay = Duration::from_millis(ms);
+            }
+            "--retries" => opts.retries = value()?.parse().map_err(|_| "bad --retries")?,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    if opts.nodes.is_empty() {
+        opts.nodes = (1..=3)
+            .map(|i| format!("http://localhost:810{}", i))
+            .collect();
+    }
+    if opts.nodes.len() != 3 {
+        return Err("give all three --node endpoints".to_string());
+    }
+    if opts.kill_cmd.is_some() != opts.start_cmd.is_some() {
+        return Err("--kill-cmd and --start-cmd go together".to_string());
+    }
+    if !(2..=6).contains(&opts.players) {
+        return Err("--players must be between 2 and 6".to_string());
+    }
+    Ok(opts)
+}
+
+/// A random Stellar address; the coordinator deals to it without a signature.
+fn random_address() -> String {
+    stellar_strkey::ed25519::PublicKey(rand::random()).to_string()
+}
+
+async fn run(opts: Options) -> Result<bool, String> {
+    let control = match (opts.kill_cmd, opts.start_cmd) {
+        (Some(kill), Some(start)) => Some(proxy::NodeControl { kill, start }),
+        _ => None,
+    };
+    let can_kill = control.is_some();
+    let (chaos, endpoints) = proxy::spawn(opts.nodes, &opts.bind, opts.proxy_port, control).await?;
+    println!("node proxies: {}", endpoints.join(" "));
+
+    let client = reqwest::Client::new();
+    client
+        .get(format!("{}/api/health", opts.coordinator))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("coordinator at {} is not up: {}", opts.coordinator, e))?;
+    chaos.wait_healthy(Duration::from_secs(10)).await?;
+
+    let driver = hand::Driver {
+        client,
+        coordinator: opts.coordinator,
+        retries: opts.retries,
+        chaos: chaos.clone(),
+    };
+    let scenarios = if opts.scenarios.is_empty() {
+        Scenario::ALL
+            .into_iter()
+            .filter(|s| s.unsupported(can_kill, opts.chain).is_none())
+            .collect()
+    } else {
+        opts.scenarios
+    };
+
+    let mut failed = 0;
+    for (i, scenario) in scenarios.iter().enumerate() {
+        let table_id = opts.first_table + i as u32;
+        if let Some(reason) = scenario.unsupported(can_kill, opts.chain) {
+            println!("SKIP {:<15} {}", scenario.name(), reason);
+            continue;
+        }
+
+        let players: Vec<String> = if opts.chain {
+            Vec::new()
+        } else {
+            (0..opts.players).map(|_| random_address()).collect()
+        };
+        if let Some(fault) = scenario.fault(table_id, &opts.tuning) {
+            chaos.arm(fault);
+        }
+        let result = driver.play(table_id, &players).await;
+        let unfired = chaos.disarm();
+        let fired = chaos.take_fired();
+
+        let verdict = result.and_then(|played| {
+            if unfired > 0 {
+                return Err("the fault never fired".to_string());
+            }
+            scenario.check(&chaos, table_id, &played)
+        });
+        match verdict {
+            Ok(()) => println!("PASS {:<15} table {}", scenario.name(), table_id),
+            Err(e) => {
+                failed += 1;
+                println!("FAIL {:<15} table {}: {}", scenario.name(), table_id, e);
+            }
+        }
+        for fault in fired {
+            println!("     injected {}", fault);
+        }
+        // Let a killed node finish restarting before the next scenario.
+        chaos.wait_healthy(Duration::from_secs(180)).await?;
+    }
+
+    println!("{} of {} scenarios failed", failed, scenarios.len());
+    Ok(failed == 0)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let opts = match parse_args() {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("chaos-test: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(opts).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("chaos-test: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
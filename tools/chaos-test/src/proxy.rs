@@ -0,0 +1,318 @@
+//! Fault-injecting proxies in front of the MPC nodes.
+//!
+//! The coordinator reaches node `i` through proxy `i`, and so do the other
+//! nodes when they deliver share fragments (`NODE_HTTP_ENDPOINTS`). A proxy
+//! forwards every request unchanged unless an armed [`Fault`] matches it, and
+//! records each proof-session request it sees so a scenario can check which
+//! recovery path the coordinator took.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use base64::Engine;
+
+/// Share payloads carry whole witness shares.
+const MAX_BODY: usize = 256 * 1024 * 1024;
+
+/// The node endpoints a fault can target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Route {
+    Dispatch,
+    Redispatch,
+    /// A fragment delivered by another node.
+    Shares,
+    Generate,
+    Status,
+    Proof,
+}
+
+impl Route {
+    fn of(path: &[&str]) -> Option<Route> {
+        match path {
+            ["table", _, "dispatch-shares"] => Some(Route::Dispatch),
+            ["table", _, "redispatch-shares"] => Some(Route::Redispatch),
+            ["session", _, "shares"] => Some(Route::Shares),
+            ["session", _, "generate"] => Some(Route::Generate),
+            ["session", _, "status"] => Some(Route::Status),
+            ["session", _, "proof"] => Some(Route::Proof),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Effect {
+    /// Answer 200 without forwarding, as if the node took the request and
+    /// then restarted and forgot it.
+    Swallow,
+    Delay(Duration),
+    /// Forward, then replace a successful response with invalid JSON.
+    CorruptBody,
+    /// Forward, then flip a byte of the proof in a successful response.
+    CorruptProof,
+    /// Forward, then kill the node after the delay and start it again.
+    Kill(Duration),
+}
+
+#[derive(Clone, Debug)]
+pub struct Fault {
+    pub node: usize,
+    pub route: Route,
+    /// Proof sessions of this table and label (`deal`, `reveal-flop`,
+    /// `showdown`, ...) only.
+    pub table_id: u32,
+    pub proof: &'static str,
+    pub effect: Effect,
+    /// How many matching requests to hit.
+    pub times: usize,
+}
+
+impl Fault {
+    fn matches(&self, node: usize, route: Route, session: &str) -> bool {
+        self.times > 0
+            && self.node == node
+            && self.route == route
+            && session.starts_with(&format!("table-{}-{}-", self.table_id, self.proof))
+    }
+}
+
+/// A proof-session request that reached a proxy.
+pub struct Hit {
+    pub route: Route,
+    pub session: String,
+}
+
+/// Commands that kill and start a node; `{node}` is replaced by its index.
+pub struct NodeControl {
+    pub kill: String,
+    pub start: String,
+}
+
+pub struct Chaos {
+    upstreams: Vec<String>,
+    control: Option<NodeControl>,
+    client: reqwest::Client,
+    faults: Mutex<Vec<Fault>>,
+    hits: Mutex<Vec<Hit>>,
+    /// One line per fault that fired.
+    fired: Mutex<Vec<String>>,
+}
+
+impl Chaos {
+    pub fn arm(&self, fault: Fault) {
+        self.faults.lock().unwrap().push(fault);
+    }
+
+    /// Disarm every fault and return how many never fired.
+    pub fn disarm(&self) -> usize {
+        let mut faults = self.faults.lock().unwrap();
+        let unfired = faults.iter().filter(|f| f.times > 0).count();
+        faults.clear();
+        unfired
+    }
+
+    pub fn take_fired(&self) -> Vec<String> {
+        std::mem::take(&mut self.fired.lock().unwrap())
+    }
+
+    /// Requests to `route` for proof sessions of `table_id` and `proof`.
+    pub fn hits(&self, route: Route, table_id: u32, proof: &str) -> usize {
+        let prefix = format!("table-{}-{}-", table_id, proof);
+        self.hits
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|h| h.route == route && h.session.starts_with(&prefix))
+            .count()
+    }
+
+    /// Wait until every node answers its health check, e.g. after a restart.
+    pub async fn wait_healthy(&self, timeout: Duration) -> Result<(), String> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        for (node, upstream) in self.upstreams.iter().enumerate() {
+            loop {
+                let healthy = self
+                    .client
+                    .get(format!("{}/health", upstream))
+                    .send()
+                    .await
+                    .is_ok_and(|r| r.status().is_success());
+                if healthy {
+                    break;
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    return Err(format!("node {} is not healthy at {}", node, upstream));
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+        Ok(())
+    }
+
+    fn take_fault(&self, node: usize, route: Route, session: &str) -> Option<Effect> {
+        let mut faults = self.faults.lock().unwrap();
+        let fault = faults
+            .iter_mut()
+            .find(|f| f.matches(node, route, session))?;
+        fault.times -= 1;
+        self.fired.lock().unwrap().push(format!(
+            "node {} {:?} {:?} on {}",
+            node, route, fault.effect, session
+        ));
+        Some(fault.effect)
+    }
+
+    async fn run_control(&self, node: usize, template: &str) {
+        let command = template.replace("{node}", &node.to_string());
+        match tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .status()
+            .await
+        {
+            Ok(status) if status.success() => {}
+            Ok(status) => eprintln!("chaos-test: '{}' exited with {}", command, status),
+            Err(e) => eprintln!("chaos-test: failed to run '{}': {}", command, e),
+        }
+    }
+
+    async fn kill_and_restart(self: Arc<Self>, node: usize, after: Duration) {
+        let Some(control) = &self.control else {
+            return;
+        };
+        tokio::time::sleep(after).await;
+        self.run_control(node, &control.kill).await;
+        self.run_control(node, &control.start).await;
+    }
+}
+
+/// Start one proxy per upstream node on `bind:base_port + i` and return the
+/// addresses they listen on, in node order.
+pub async fn spawn(
+    upstreams: Vec<String>,
+    bind: &str,
+    base_port: u16,
+    control: Option<NodeControl>,
+) -> Result<(Arc<Chaos>, Vec<String>), String> {
+    let chaos = Arc::new(Chaos {
+        upstreams,
+        control,
+        client: reqwest::Client::new(),
+        faults: Mutex::new(Vec::new()),
+        hits: Mutex::new(Vec::new()),
+        fired: Mutex::new(Vec::new()),
+    });
+    let mut endpoints = Vec::new();
+    for node in 0..chaos.upstreams.len() {
+        let addr = format!("{}:{}", bind, base_port + node as u16);
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .map_err(|e| format!("failed to bind proxy {} on {}: {}", node, addr, e))?;
+        let router = Router::new()
+            .fallback(forward)
+            .with_state((chaos.clone(), node));
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        endpoints.push(addr);
+    }
+    Ok((chaos, endpoints))
+}
+
+/// The proof session a request belongs to: in the path, or for (re)dispatch
+/// in the body.
+fn session_of(route: Route, path: &[&str], body: &[u8]) -> Option<String> {
+    match route {
+        Route::Dispatch | Route::Redispatch => serde_json::from_slice::<serde_json::Value>(body)
+            .ok()?
+            .get("proof_session_id")?
+            .as_str()
+            .map(str::to_string),
+        _ => path.get(1).map(|s| s.to_string()),
+    }
+}
+
+fn bad_gateway(e: impl std::fmt::Display) -> Response {
+    (StatusCode::BAD_GATEWAY, format!("chaos proxy: {}", e)).into_response()
+}
+
+async fn forward(State((chaos, node)): State<(Arc<Chaos>, usize)>, req: Request) -> Response {
+    let (parts, body) = req.into_parts();
+    let body = match to_bytes(body, MAX_BODY).await {
+        Ok(body) => body,
+        Err(e) => return bad_gateway(e),
+    };
+    let path: Vec<&str> = parts
+        .uri
+        .path()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    let target = Route::of(&path).and_then(|route| Some((route, session_of(route, &path, &body)?)));
+
+    let effect = target.as_ref().and_then(|(route, session)| {
+        chaos.hits.lock().unwrap().push(Hit {
+            route: *route,
+            session: session.clone(),
+        });
+        chaos.take_fault(node, *route, session)
+    });
+    match effect {
+        Some(Effect::Swallow) => return StatusCode::OK.into_response(),
+        Some(Effect::Delay(delay)) => tokio::time::sleep(delay).await,
+        Some(Effect::Kill(after)) => {
+            tokio::spawn(chaos.clone().kill_and_restart(node, after));
+        }
+        _ => {}
+    }
+
+    let url = format!(
+        "{}{}",
+        chaos.upstreams[node],
+        parts.uri.path_and_query().map_or("/", |p| p.as_str())
+    );
+    let mut request = chaos.client.request(parts.method, url).body(body);
+    for (name, value) in &parts.headers {
+        if name != header::HOST && name != header::CONTENT_LENGTH {
+            request = request.header(name, value);
+        }
+    }
+    let resp = match request.send().await {
+        Ok(resp) => resp,
+        Err(e) => return bad_gateway(e),
+    };
+    let status = resp.status();
+    let mut headers = HeaderMap::new();
+    for (name, value) in resp.headers() {
+        if name != header::CONTENT_LENGTH && name != header::TRANSFER_ENCODING {
+            headers.insert(name, value.clone());
+        }
+    }
+    let mut body = match resp.bytes().await {
+        Ok(body) => body.to_vec(),
+        Err(e) => return bad_gateway(e),
+    };
+
+    if status.is_success() {
+        match effect {
+            Some(Effect::CorruptBody) => body = b"{\"status\":".to_vec(),
+            Some(Effect::CorruptProof) => body = corrupt_proof(&body).unwrap_or(body),
+            _ => {}
+        }
+    }
+    (status, headers, Body::from(body)).into_response()
+}
+
+/// Flip one byte in the middle of a proof response's base64 `proof`.
+fn corrupt_proof(body: &[u8]) -> Option<Vec<u8>> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let mut json: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let mut proof = engine.decode(json.get("proof")?.as_str()?).ok()?;
+    let mid = proof.len() / 2;
+    *proof.get_mut(mid)? ^= 0xff;
+    json["proof"] = engine.encode(proof).into();
+    serde_json::to_vec(&json).ok()
+}
@@ -0,0 +1,137 @@
+//! The failures the harness injects and what the hand must look like after
+//! each one.
+
+use std::time::Duration;
+
+use crate::hand::Played;
+use crate::proxy::{Chaos, Effect, Fault, Route};
+
+/// Timings the faults are built with.
+pub struct Tuning {
+    pub dispatch_delay: Duration,
+    pub kill_after: Duration,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Scenario {
+    /// No faults; the other scenarios are only meaningful if this passes.
+    Baseline,
+    /// Node 1 loses a flop share fragment and must get it redispatched.
+    DropShares,
+    /// Node 2 is slow to dispatch its deal shares.
+    DelayDispatch,
+    /// Node 0 answers the first turn status polls with garbage.
+    CorruptStatus,
+    /// Node 1 is killed while proving the river and restarted.
+    KillNode,
+    /// The showdown proof is corrupted, so the chain rejects it and the
+    /// coordinator settles the hand through the timeout fallback.
+    BadProof,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 6] = [
+        Scenario::Baseline,
+        Scenario::DropShares,
+        Scenario::DelayDispatch,
+        Scenario::CorruptStatus,
+        Scenario::KillNode,
+        Scenario::BadProof,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scenario::Baseline => "baseline",
+            Scenario::DropShares => "drop-shares",
+            Scenario::DelayDispatch => "delay-dispatch",
+            Scenario::CorruptStatus => "corrupt-status",
+            Scenario::KillNode => "kill-node",
+            Scenario::BadProof => "bad-proof",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Scenario> {
+        Scenario::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    /// Why the scenario cannot run with these options, if it cannot.
+    pub fn unsupported(self, can_kill: bool, on_chain: bool) -> Option<&'static str> {
+        match self {
+            Scenario::KillNode if !can_kill => Some("needs --kill-cmd and --start-cmd"),
+            Scenario::BadProof if !on_chain => {
+                Some("needs --chain; only the table contract rejects a bad proof")
+            }
+            _ => None,
+        }
+    }
+
+    pub fn fault(self, table_id: u32, tuning: &Tuning) -> Option<Fault> {
+        let (node, route, proof, effect, times) = match self {
+            Scenario::Baseline => return None,
+            Scenario::DropShares => (1, Route::Shares, "reveal-flop", Effect::Swallow, 1),
+            Scenario::DelayDispatch => (
+                2,
+                Route::Dispatch,
+                "deal",
+                Effect::Delay(tuning.dispatch_delay),
+                1,
+            ),
+            Scenario::CorruptStatus => (0, Route::Status, "reveal-turn", Effect::CorruptBody, 3),
+            Scenario::KillNode => (
+                1,
+                Route::Generate,
+                "reveal-river",
+                Effect::Kill(tuning.kill_after),
+                1,
+            ),
+            Scenario::BadProof => (0, Route::Proof, "showdown", Effect::CorruptProof, 1),
+        };
+        Some(Fault {
+            node,
+            route,
+            table_id,
+            proof,
+            effect,
+            times,
+        })
+    }
+
+    /// Check that the coordinator recovered the way this failure calls for.
+    /// The hand itself has already been checked to settle consistently.
+    pub fn check(self, chaos: &Chaos, table_id: u32, played: &Played) -> Result<(), String> {
+        let settles_as = if self == Scenario::BadProof {
+            "settled_timeout"
+        } else {
+            "showdown_complete"
+        };
+        if played.showdown.status != settles_as {
+            return Err(format!(
+                "hand settled as '{}', expected '{}'",
+                played.showdown.status, settles_as
+            ));
+        }
+
+        // Everything but a dead node has to be absorbed inside the
+        // coordinator, without the client seeing an error.
+        let retried = played.retried();
+        match self {
+            Scenario::KillNode if retried.is_empty() => {
+                return Err("no request failed; the kill landed after the river proof \
+                     finished, lower --kill-after-ms"
+                    .to_string())
+            }
+            Scenario::KillNode => {}
+            _ if !retried.is_empty() => {
+                return Err(format!("client had to retry: {}", retried.join(", ")))
+            }
+            _ => {}
+        }
+
+        if self == Scenario::DropShares
+            && chaos.hits(Route::Redispatch, table_id, "reveal-flop") == 0
+        {
+            return Err("lost fragment was not redispatched".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! Rebuild a table's hands from the poker-table contract's events.
+//!
+//! Reads the table's event history from Soroban RPC and prints every hand
+//! in it as JSON: seats and stacks, each action by street, the board with
+//! the deck positions it came from, hole cards at showdown, the winner and
+//! pot, and for each deal, reveal and showdown the digest and transaction
+//! of the proof the contract verified. With `--with-proofs` the proofs and
+//! public inputs themselves are fetched from those transactions and checked
+//! against the published digests, so the output stands on its own as
+//! dispute evidence.
+//!
+//! The RPC only serves events inside its retention window, so hands older
+//! than that cannot be replayed.
+
+mod replay;
+mod rpc;
+mod scval;
+
+use std::process::ExitCode;
+
+use replay::{Event, Hand, Origin};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+const USAGE: &str = "\
+usage: hand-replay --table ID [options]
+
+  --table ID           on-chain table to replay
+  --hand N             only replay hand N
+  --rpc URL            Soroban RPC (default $SOROBAN_RPC or http://localhost:8000/soroban/rpc)
+  --contract ID        poker-table contract (default $POKER_TABLE_CONTRACT)
+  --start-ledger SEQ   first ledger to scan (default the oldest the RPC keeps)
+  --with-proofs        include each proof and its public inputs, checked against the events";
+
+struct Options {
+    table: u32,
+    hand: Option<u32>,
+    rpc: String,
+    contract: String,
+    start_ledger: Option<u32>,
+    with_proofs: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut table = None;
+    let mut opts = Options {
+        table: 0,
+        hand: None,
+        rpc: std::env::var("SOROBAN_RPC")
+            .unwrap_or_else(|_| "http://localhost:8000/soroban/rpc".to_string()),
+        contract: std::env::var("POKER_TABLE_CONTRACT").unwrap_or_default(),
+        start_ledger: None,
+        with_proofs: false,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--table" => table = Some(value()?.parse().map_err(|_| "bad --table")?),
+            "--hand" => opts.hand = Some(value()?.parse().map_err(|_| "bad --hand")?),
+            "--rpc" => opts.rpc = value()?,
+            "--contract" => opts.contract = value()?,
+            "--start-ledger" => {
+                opts.start_ledger = Some(value()?.parse().map_err(|_| "bad --start-ledger")?)
+            }
+            "--with-proofs" => opts.with_proofs = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    opts.table = table.ok_or("--table is required")?;
+    if opts.contract.is_empty() {
+        return Err("--contract or POKER_TABLE_CONTRACT is required".to_string());
+    }
+    Ok(opts)
+}
+
+#[derive(Serialize)]
+struct Replay {
+    contract: String,
+    table_id: u32,
+    first_ledger: u32,
+    last_ledger: u32,
+    hands: Vec<Hand>,
+}
+
+fn decode_event(raw: &rpc::RawEvent) -> Result<Event, String> {
+    let name = raw
+        .topic
+        .first()
+        .ok_or_else(|| format!("event {} has no topics", raw.id))?;
+    Ok(Event {
+        name: scval::symbol(&scval::decode(name)?)?,
+        data: scval::decode(&raw.value)?,
+        origin: Origin {
+            ledger: raw.ledger,
+            closed_at: raw.ledger_closed_at.clone(),
+            tx_hash: raw.tx_hash.clone(),
+        },
+    })
+}
+
+/// Fill in a hand's proofs from the transactions that submitted them. Every
+/// proof-carrying entry point takes the proof and its public inputs last.
+async fn attach_proofs(rpc: &rpc::Rpc, hand: &mut Hand) -> Result<(), String> {
+    for proof in hand.proofs_mut() {
+        let args = rpc.invocation_args(&proof.origin.tx_hash).await?;
+        let [.., bytes, inputs] = args.as_slice() else {
+            return Err(format!(
+                "transaction {} has too few arguments",
+                proof.origin.tx_hash
+            ));
+        };
+        let bytes = scval::bytes(bytes)?;
+        let digest = Sha256::digest(hex::decode(&bytes).map_err(|e| e.to_string())?);
+        if hex::encode(digest) != proof.sha256 {
+            return Err(format!(
+                "proof in transaction {} does not match the published digest {}",
+                proof.origin.tx_hash, proof.sha256
+            ));
+        }
+        proof.proof = Some(bytes);
+        proof.public_inputs = Some(scval::bytes(inputs)?);
+    }
+    Ok(())
+}
+
+async fn run(opts: Options) -> Result<Replay, String> {
+    let rpc = rpc::Rpc::new(&opts.rpc);
+    let first_ledger = match opts.start_ledger {
+        Some(ledger) => ledger,
+        None => rpc.health().await?.oldest_ledger,
+    };
+    let (raw, last_ledger) = rpc
+        .table_events(&opts.contract, opts.table, first_ledger)
+        .await?;
+    let events = raw
+        .iter()
+        .map(decode_event)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (mut hands, orphaned) = replay::build(&events)?;
+    if orphaned > 0 {
+        eprintln!(
+            "hand-replay: skipped {} events of a hand that started before ledger {}",
+            orphaned, first_ledger
+        );
+    }
+    if let Some(n) = opts.hand {
+        hands.retain(|h| h.hand_number == n);
+        if hands.is_empty() {
+            return Err(format!(
+                "hand {} of table {} is not in ledgers {}..={}",
+                n, opts.table, first_ledger, last_ledger
+            ));
+        }
+    }
+    if opts.with_proofs {
+        for hand in &mut hands {
+            attach_proofs(&rpc, hand).await?;
+        }
+    }
+
+    Ok(Replay {
+        contract: opts.contract,
+        table_id: opts.table,
+        first_ledger,
+        last_ledger,
+        hands,
+    })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let opts = match parse_args() {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("hand-replay: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(opts).await {
+        Ok(replay) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&replay).expect("replays always serialize")
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("hand-replay: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
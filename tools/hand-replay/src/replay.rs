@@ -0,0 +1,284 @@
+//! Folds a table's events into hands.
+//!
+//! Each hand opens at `hand_started`, which carries the seats, stacks,
+//! button and blinds, and collects everything published until the next one.
+//! Every proof event only exists because the table's verifier accepted the
+//! proof, so the recorded digests and transactions pin down the exact proofs
+//! the hand was settled on.
+
+use poker_table_types::{Action, GamePhase};
+use serde::{Serialize, Serializer};
+use stellar_xdr::curr::ScVal;
+
+use crate::scval;
+
+fn as_string<S: Serializer>(value: &i128, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(value)
+}
+
+/// Where an event was published.
+#[derive(Clone, Debug, Serialize)]
+pub struct Origin {
+    pub ledger: u32,
+    pub closed_at: String,
+    pub tx_hash: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Seat {
+    pub seat: u32,
+    pub address: String,
+    /// Stack before the blinds.
+    #[serde(serialize_with = "as_string")]
+    pub stack: i128,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Proof {
+    /// SHA-256 of the proof bytes, as the contract published it.
+    pub sha256: String,
+    pub origin: Origin,
+    /// The proof and its public inputs, hex, with `--with-proofs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_inputs: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Deal {
+    pub deck_root: String,
+    pub hand_commitments: Vec<String>,
+    pub proof: Proof,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Move {
+    pub street: GamePhase,
+    pub seat: u32,
+    pub player: String,
+    pub action: Action,
+    /// Folded by `claim_timeout` rather than by the player.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    pub origin: Origin,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Reveal {
+    pub street: GamePhase,
+    pub cards: Vec<u32>,
+    pub deck_indices: Vec<u32>,
+    pub proof: Proof,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Showdown {
+    /// Each seat's hole cards, in seat order; `None` for a seat that folded
+    /// or mucked.
+    pub hole_cards: Vec<Option<[u32; 2]>>,
+    pub proof: Proof,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ending {
+    Showdown,
+    Fold,
+    /// The committee stalled; stacks were refunded to their start.
+    CommitteeTimeout,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Outcome {
+    pub ending: Ending,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner: Option<String>,
+    #[serde(serialize_with = "as_string")]
+    pub pot: i128,
+    pub origin: Origin,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Hand {
+    pub hand_number: u32,
+    pub started: Origin,
+    pub dealer_seat: u32,
+    #[serde(serialize_with = "as_string")]
+    pub small_blind: i128,
+    #[serde(serialize_with = "as_string")]
+    pub big_blind: i128,
+    pub seats: Vec<Seat>,
+    pub deal: Option<Deal>,
+    pub actions: Vec<Move>,
+    pub board: Vec<u32>,
+    pub reveals: Vec<Reveal>,
+    pub showdown: Option<Showdown>,
+    /// `None` while the hand is still being played.
+    pub outcome: Option<Outcome>,
+}
+
+impl Hand {
+    fn street(&self) -> GamePhase {
+        match self.board.len() {
+            0 => GamePhase::Preflop,
+            3 => GamePhase::Flop,
+            4 => GamePhase::Turn,
+            _ => GamePhase::River,
+        }
+    }
+
+    pub fn proofs_mut(&mut self) -> impl Iterator<Item = &mut Proof> {
+        self.deal
+            .iter_mut()
+            .map(|d| &mut d.proof)
+            .chain(self.reveals.iter_mut().map(|r| &mut r.proof))
+            .chain(self.showdown.iter_mut().map(|s| &mut s.proof))
+    }
+}
+
+/// A decoded table event: its name (the first topic), data and origin.
+pub struct Event {
+    pub name: String,
+    pub data: ScVal,
+    pub origin: Origin,
+}
+
+fn proof(digest: &ScVal, origin: &Origin) -> Result<Proof, String> {
+    Ok(Proof {
+        sha256: scval::bytes(digest)?,
+        origin: origin.clone(),
+        proof: None,
+        public_inputs: None,
+    })
+}
+
+/// Rebuild hands from events in publication order. Events before the first
+/// `hand_started` belong to a hand that began before the scanned range and
+/// are counted in the second return value instead.
+pub fn build(events: &[Event]) -> Result<(Vec<Hand>, usize), String> {
+    let mut hands: Vec<Hand> = Vec::new();
+    let mut orphaned = 0;
+
+    for event in events {
+        if event.name == "hand_started" {
+            let f = |name| scval::field(&event.data, name);
+            let seats = scval::list(f("seats")?)?
+                .iter()
+                .map(|s| {
+                    let s = scval::tuple(s, 3)?;
+                    Ok(Seat {
+                        seat: scval::u32(&s[0])?,
+                        address: scval::address(&s[1])?,
+                        stack: scval::i128(&s[2])?,
+                    })
+                })
+                .collect::<Result<_, String>>()?;
+            hands.push(Hand {
+                hand_number: scval::u32(f("hand_number")?)?,
+                started: event.origin.clone(),
+                dealer_seat: scval::u32(f("dealer_seat")?)?,
+                small_blind: scval::i128(f("small_blind")?)?,
+                big_blind: scval::i128(f("big_blind")?)?,
+                seats,
+                deal: None,
+                actions: Vec::new(),
+                board: Vec::new(),
+                reveals: Vec::new(),
+                showdown: None,
+                outcome: None,
+            });
+            continue;
+        }
+
+        let Some(hand) = hands.last_mut() else {
+            orphaned += 1;
+            continue;
+        };
+        let origin = event.origin.clone();
+        let f = |name| scval::field(&event.data, name);
+        match event.name.as_str() {
+            "deal_committed" => {
+                hand.deal = Some(Deal {
+                    deck_root: scval::bytes(f("deck_root")?)?,
+                    hand_commitments: scval::list(f("hand_commitments")?)?
+                        .iter()
+                        .map(scval::bytes)
+                        .collect::<Result<_, _>>()?,
+                    proof: proof(f("proof_digest")?, &origin)?,
+                });
+            }
+            "action_taken" => {
+                hand.actions.push(Move {
+                    street: hand.street(),
+                    seat: scval::u32(f("seat")?)?,
+                    player: scval::address(f("player")?)?,
+                    action: scval::action(f("action")?)?,
+                    timed_out: false,
+                    origin,
+                });
+            }
+            "timeout_fold" => {
+                hand.actions.push(Move {
+                    street: hand.street(),
+                    seat: scval::u32(f("seat")?)?,
+                    player: scval::address(f("player")?)?,
+                    action: Action::Fold,
+                    timed_out: true,
+                    origin,
+                });
+            }
+            "board_revealed" => {
+                let cards = scval::u32_list(f("cards")?)?;
+                hand.board.extend(&cards);
+                hand.reveals.push(Reveal {
+                    street: hand.street(),
+                    cards,
+                    deck_indices: scval::u32_list(f("indices")?)?,
+                    proof: proof(f("proof_digest")?, &origin)?,
+                });
+            }
+            "showdown_revealed" => {
+                let hole_cards = scval::list(f("hole_cards")?)?
+                    .iter()
+                    .map(|pair| {
+                        scval::option(pair)
+                            .map(|pair| {
+                                let pair = scval::tuple(pair, 2)?;
+                                Ok([scval::u32(&pair[0])?, scval::u32(&pair[1])?])
+                            })
+                            .transpose()
+                    })
+                    .collect::<Result<_, String>>()?;
+                hand.showdown = Some(Showdown {
+                    hole_cards,
+                    proof: proof(f("proof_digest")?, &origin)?,
+                });
+            }
+            "hand_settled" | "fold_win" => {
+                hand.outcome = Some(Outcome {
+                    ending: if event.name == "fold_win" {
+                        Ending::Fold
+                    } else {
+                        Ending::Showdown
+                    },
+                    winner: Some(scval::address(f("winner")?)?),
+                    pot: scval::i128(f("pot")?)?,
+                    origin,
+                });
+            }
+            "committee_timeout" => {
+                hand.outcome = Some(Outcome {
+                    ending: Ending::CommitteeTimeout,
+                    winner: None,
+                    pot: scval::i128(f("pot")?)?,
+                    origin,
+                });
+            }
+            // Seating changes and phase transitions are implied by the rest.
+            _ => {}
+        }
+    }
+
+    Ok((hands, orphaned))
+}
@@ -0,0 +1,163 @@
+//! The few Soroban RPC methods the replay needs, over plain JSON-RPC.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use stellar_xdr::curr::{
+    FeeBumpTransactionInnerTx, HostFunction, Limits, OperationBody, ReadXdr, ScVal,
+    TransactionEnvelope, WriteXdr,
+};
+
+/// Events per `getEvents` page.
+const PAGE_LIMIT: usize = 200;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    pub oldest_ledger: u32,
+}
+
+/// A contract event as `getEvents` returns it, topics and value still XDR.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawEvent {
+    pub id: String,
+    pub ledger: u32,
+    pub ledger_closed_at: String,
+    pub tx_hash: String,
+    pub topic: Vec<String>,
+    pub value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventPage {
+    events: Vec<RawEvent>,
+    latest_ledger: u32,
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Transaction {
+    status: String,
+    envelope_xdr: Option<String>,
+}
+
+pub struct Rpc {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Rpc {
+    pub fn new(url: &str) -> Self {
+        Rpc {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T, String> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let resp: serde_json::Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("{} failed: {}", method, e))?
+            .json()
+            .await
+            .map_err(|e| format!("{} returned invalid JSON: {}", method, e))?;
+        if let Some(error) = resp.get("error") {
+            return Err(format!("{} failed: {}", method, error));
+        }
+        serde_json::from_value(resp["result"].clone())
+            .map_err(|e| format!("{} returned an unexpected result: {}", method, e))
+    }
+
+    pub async fn health(&self) -> Result<Health, String> {
+        self.call("getHealth", serde_json::Value::Null).await
+    }
+
+    /// Every event the contract published for `table_id` from
+    /// `start_ledger` on, oldest first, and the latest ledger the RPC knows.
+    pub async fn table_events(
+        &self,
+        contract: &str,
+        table_id: u32,
+        start_ledger: u32,
+    ) -> Result<(Vec<RawEvent>, u32), String> {
+        let table_topic = ScVal::U32(table_id)
+            .to_xdr_base64(Limits::none())
+            .map_err(|e| format!("encoding table topic: {}", e))?;
+        let filters = json!([{
+            "type": "contract",
+            "contractIds": [contract],
+            "topics": [["*", table_topic]],
+        }]);
+
+        let mut events = Vec::new();
+        let mut params = json!({
+            "startLedger": start_ledger,
+            "filters": filters,
+            "pagination": { "limit": PAGE_LIMIT },
+        });
+        loop {
+            let page: EventPage = self.call("getEvents", params).await?;
+            let full = page.events.len() == PAGE_LIMIT;
+            let cursor = page
+                .cursor
+                .or_else(|| page.events.last().map(|e| e.id.clone()));
+            events.extend(page.events);
+            match cursor {
+                Some(cursor) if full => {
+                    params = json!({
+                        "filters": filters,
+                        "pagination": { "cursor": cursor, "limit": PAGE_LIMIT },
+                    });
+                }
+                _ => return Ok((events, page.latest_ledger)),
+            }
+        }
+    }
+
+    /// The arguments `tx_hash` passed to the contract function it invoked.
+    pub async fn invocation_args(&self, tx_hash: &str) -> Result<Vec<ScVal>, String> {
+        let tx: Transaction = self
+            .call("getTransaction", json!({ "hash": tx_hash }))
+            .await?;
+        let envelope = match (tx.status.as_str(), tx.envelope_xdr) {
+            ("SUCCESS", Some(xdr)) => xdr,
+            (status, _) => {
+                return Err(format!(
+                    "transaction {} is {} (outside the RPC's retention window?)",
+                    tx_hash, status
+                ))
+            }
+        };
+        let envelope = TransactionEnvelope::from_xdr_base64(envelope, Limits::none())
+            .map_err(|e| format!("transaction {} has an invalid envelope: {}", tx_hash, e))?;
+        let operations = match envelope {
+            TransactionEnvelope::Tx(env) => env.tx.operations,
+            TransactionEnvelope::TxFeeBump(env) => match env.tx.inner_tx {
+                FeeBumpTransactionInnerTx::Tx(inner) => inner.tx.operations,
+            },
+            TransactionEnvelope::TxV0(env) => env.tx.operations,
+        };
+        operations
+            .iter()
+            .find_map(|op| match &op.body {
+                OperationBody::InvokeHostFunction(invoke) => match &invoke.host_function {
+                    HostFunction::InvokeContract(call) => Some(call.args.to_vec()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .ok_or_else(|| format!("transaction {} does not invoke a contract", tx_hash))
+    }
+}
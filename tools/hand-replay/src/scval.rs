@@ -0,0 +1,111 @@
+//! Reading event topics and data back into Rust values.
+//!
+//! Event data are maps from field name to value. Tuples inside them are
+//! `ScVal::Vec`, and so are contract enums: the variant name as a symbol,
+//! then its fields.
+
+use poker_table_types::Action;
+use serde_json::json;
+use stellar_xdr::curr::{Limits, ReadXdr, ScVal};
+
+pub fn decode(xdr: &str) -> Result<ScVal, String> {
+    ScVal::from_xdr_base64(xdr, Limits::none()).map_err(|e| format!("invalid ScVal: {}", e))
+}
+
+pub fn list(val: &ScVal) -> Result<&[ScVal], String> {
+    match val {
+        ScVal::Vec(Some(items)) => Ok(items.0.as_slice()),
+        ScVal::Vec(None) => Ok(&[]),
+        other => Err(format!("expected a vec, got {:?}", other.discriminant())),
+    }
+}
+
+/// The fields of an `n`-tuple.
+pub fn tuple(val: &ScVal, n: usize) -> Result<&[ScVal], String> {
+    let fields = list(val)?;
+    if fields.len() != n {
+        return Err(format!("expected {} fields, got {}", n, fields.len()));
+    }
+    Ok(fields)
+}
+
+/// A contract `Option`, which is `Void` when `None`.
+pub fn option(val: &ScVal) -> Option<&ScVal> {
+    match val {
+        ScVal::Void => None,
+        other => Some(other),
+    }
+}
+
+/// The value under `name` in an event's data map.
+pub fn field<'a>(val: &'a ScVal, name: &str) -> Result<&'a ScVal, String> {
+    let ScVal::Map(Some(entries)) = val else {
+        return Err(format!("expected a map, got {:?}", val.discriminant()));
+    };
+    entries
+        .iter()
+        .find(|entry| matches!(&entry.key, ScVal::Symbol(s) if s.0.as_slice() == name.as_bytes()))
+        .map(|entry| &entry.val)
+        .ok_or_else(|| format!("missing field {}", name))
+}
+
+pub fn symbol(val: &ScVal) -> Result<String, String> {
+    match val {
+        ScVal::Symbol(s) => Ok(s.0.to_utf8_string_lossy()),
+        other => Err(format!("expected a symbol, got {:?}", other.discriminant())),
+    }
+}
+
+pub fn u32(val: &ScVal) -> Result<u32, String> {
+    match val {
+        ScVal::U32(v) => Ok(*v),
+        other => Err(format!("expected a u32, got {:?}", other.discriminant())),
+    }
+}
+
+pub fn i128(val: &ScVal) -> Result<i128, String> {
+    match val {
+        ScVal::I128(parts) => Ok(parts.into()),
+        other => Err(format!("expected an i128, got {:?}", other.discriminant())),
+    }
+}
+
+pub fn address(val: &ScVal) -> Result<String, String> {
+    match val {
+        ScVal::Address(a) => Ok(a.to_string()),
+        other => Err(format!(
+            "expected an address, got {:?}",
+            other.discriminant()
+        )),
+    }
+}
+
+/// `Bytes` or `BytesN` as hex.
+pub fn bytes(val: &ScVal) -> Result<String, String> {
+    match val {
+        ScVal::Bytes(b) => Ok(hex::encode(b.0.as_slice())),
+        other => Err(format!("expected bytes, got {:?}", other.discriminant())),
+    }
+}
+
+pub fn u32_list(val: &ScVal) -> Result<Vec<u32>, String> {
+    list(val)?.iter().map(u32).collect()
+}
+
+/// A contract enum in the CLI's JSON rendering, which the
+/// `poker-table-types` mirrors deserialize. Only the `Action` shapes occur.
+fn enum_json(val: &ScVal) -> Result<serde_json::Value, String> {
+    let (name, fields) = list(val)?
+        .split_first()
+        .ok_or("expected an enum, got an empty vec")?;
+    let name = symbol(name)?;
+    match fields {
+        [] => Ok(json!(name)),
+        [field] => Ok(json!({ name: i128(field)?.to_string() })),
+        _ => Err(format!("enum variant {} has {} fields", name, fields.len())),
+    }
+}
+
+pub fn action(val: &ScVal) -> Result<Action, String> {
+    serde_json::from_value(enum_json(val)?).map_err(|e| format!("unknown action: {}", e))
+}
@@ -0,0 +1,293 @@
+//! Load-test the coordinator with many tables playing at once.
+//!
+//! Starts a stub MPC committee in-process (see [`stub`]) and runs `--tables`
+//! concurrent tables through the coordinator, each dealing, revealing the
+//! flop, turn and river and settling `--hands` hands with signed requests.
+//! Reports latency percentiles per endpoint and how much the tables waited
+//! on each other inside the coordinator before reaching the committee.
+//!
+//! Start the coordinator against the stubs and without a table contract, so
+//! proofs are not submitted on chain:
+//!
+//! ```text
+//! MPC_NODE_0=http://127.0.0.1:8101 MPC_NODE_1=http://127.0.0.1:8102 \
+//!   MPC_NODE_2=http://127.0.0.1:8103 cargo run -p coordinator
+//! ```
+
+mod report;
+mod stub;
+
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::{Signer, SigningKey};
+use report::Sample;
+
+const USAGE: &str = "\
+usage: load-test [options]
+
+  --coordinator URL  coordinator under test (default http://localhost:8080)
+  --tables N         tables playing concurrently (default 8)
+  --hands N          hands per table (default 3)
+  --players N        players per table, 2-6 (default 2)
+  --first-table ID   ID of the first table; the rest follow (default 1000)
+  --stub-port PORT   stub node i listens on PORT+i (default 8101)
+  --prove-ms MS      simulated proving time per proof (default 0)";
+
+const STUB_NODES: u32 = 3;
+
+struct Options {
+    coordinator: String,
+    tables: u32,
+    hands: u32,
+    players: usize,
+    first_table: u32,
+    stub_port: u16,
+    prove_delay: Duration,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut opts = Options {
+        coordinator: "http://localhost:8080".to_string(),
+        tables: 8,
+        hands: 3,
+        players: 2,
+        first_table: 1000,
+        stub_port: 8101,
+        prove_delay: Duration::ZERO,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--coordinator" => opts.coordinator = value()?.trim_end_matches('/').to_string(),
+            "--tables" => opts.tables = value()?.parse().map_err(|_| "bad --tables")?,
+            "--hands" => opts.hands = value()?.parse().map_err(|_| "bad --hands")?,
+            "--players" => opts.players = value()?.parse().map_err(|_| "bad --players")?,
+            "--first-table" => {
+                opts.first_table = value()?.parse().map_err(|_| "bad --first-table")?
+            }
+            "--stub-port" => opts.stub_port = value()?.parse().map_err(|_| "bad --stub-port")?,
+            "--prove-ms" => {
+                let ms = value()?.parse().map_err(|_| "bad --prove-ms")?;
+                opts.prove_delay = Duration::from_millis(ms);
+            }
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    if opts.tables == 0 {
+        return Err("--tables must be at least 1".to_string());
+    }
+    if !(2..=6).contains(&opts.players) {
+        return Err("--players must be between 2 and 6".to_string());
+    }
+    Ok(opts)
+}
+
+/// A wallet that signs coordinator requests the way the web app does.
+struct Player {
+    key: SigningKey,
+    address: String,
+}
+
+impl Player {
+    fn generate() -> Self {
+        let key = SigningKey::from_bytes(&rand::random());
+        let address =
+            stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
+        Player { key, address }
+    }
+
+    fn sign(
+        &self,
+        request: reqwest::RequestBuilder,
+        table_id: u32,
+        action: &str,
+        nonce: u64,
+    ) -> reqwest::RequestBuilder {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let message = format!(
+            "stellar-poker|{}|{}|{}|{}|{}",
+            self.address, table_id, action, nonce, timestamp
+        );
+        request
+            .header("x-player-address", &self.address)
+            .header(
+                "x-auth-signature",
+                hex::encode(self.key.sign(message.as_bytes()).to_bytes()),
+            )
+            .header("x-auth-nonce", nonce.to_string())
+            .header("x-auth-timestamp", timestamp.to_string())
+    }
+}
+
+/// One table's worth of players, playing its hands in order.
+struct Table {
+    id: u32,
+    players: Vec<Player>,
+    nonce: u64,
+    client: reqwest::Client,
+    coordinator: String,
+    probe: Arc<stub::Probe>,
+    samples: Vec<Sample>,
+}
+
+impl Table {
+    /// Send one signed request as the first player and record it. Returns
+    /// whether it succeeded.
+    async fn request(
+        &mut self,
+        endpoint: &'static str,
+        path: &str,
+        action: &str,
+        body: serde_json::Value,
+    ) -> bool {
+        self.nonce += 1;
+        let url = format!("{}/api/table/{}/{}", self.coordinator, self.id, path);
+        let request = self.players[0].sign(
+            self.client.post(&url).json(&body),
+            self.id,
+            action,
+            self.nonce,
+        );
+
+        let sent = Instant::now();
+        let result = request.send().await;
+        let latency = sent.elapsed();
+        let lock_wait = self
+            .probe
+            .reached_at(self.id)
+            .filter(|at| *at >= sent)
+            .map(|at| at - sent);
+        let error = match result {
+            Ok(resp) if resp.status().is_success() => None,
+            Ok(resp) => Some(resp.status().to_string()),
+            Err(e) if e.is_timeout() => Some("timed out".to_string()),
+            Err(_) => Some("connection failed".to_string()),
+        };
+        let ok = error.is_none();
+        self.samples.push(Sample {
+            endpoint,
+            latency,
+            lock_wait,
+            error,
+        });
+        ok
+    }
+
+    /// Deal, reveal and settle one hand, stopping at the first failure.
+    async fn play_hand(&mut self) -> bool {
+        let players: Vec<&str> = self.players.iter().map(|p| p.address.as_str()).collect();
+        let deal = serde_json::json!({ "players": players });
+        if !self
+            .request("deal", "request-deal", "request_deal", deal)
+            .await
+        {
+            return false;
+        }
+        for phase in ["flop", "turn", "river"] {
+            let path = format!("request-reveal/{}", phase);
+            let action = format!("request_reveal:{}", phase);
+            if !self
+                .request("reveal", &path, &action, serde_json::Value::Null)
+                .await
+            {
+                return false;
+            }
+        }
+        self.request(
+            "showdown",
+            "request-showdown",
+            "request_showdown",
+            serde_json::Value::Null,
+        )
+        .await
+    }
+}
+
+async fn run(opts: Options) -> Result<bool, String> {
+    let probe = Arc::new(stub::Probe::default());
+    let endpoints =
+        stub::spawn(STUB_NODES, opts.stub_port, opts.prove_delay, probe.clone()).await?;
+    println!("stub MPC nodes: {}", endpoints.join(" "));
+
+    let client = reqwest::Client::new();
+    client
+        .get(format!("{}/api/health", opts.coordinator))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("coordinator at {} is not up: {}", opts.coordinator, e))?;
+
+    println!(
+        "{} tables x {} hands, {} players each",
+        opts.tables, opts.hands, opts.players
+    );
+    let started = Instant::now();
+    let mut handles = Vec::new();
+    for i in 0..opts.tables {
+        let mut table = Table {
+            id: opts.first_table + i,
+            players: (0..opts.players).map(|_| Player::generate()).collect(),
+            nonce: 0,
+            client: client.clone(),
+            coordinator: opts.coordinator.clone(),
+            probe: probe.clone(),
+            samples: Vec::new(),
+        };
+        let hands = opts.hands;
+        handles.push(tokio::spawn(async move {
+            let mut played = 0;
+            while played < hands && table.play_hand().await {
+                played += 1;
+            }
+            (played as usize, table.samples)
+        }));
+    }
+
+    let mut samples = Vec::new();
+    let mut hands = 0;
+    for handle in handles {
+        let (played, table_samples) = handle
+            .await
+            .map_err(|e| format!("table task failed: {}", e))?;
+        hands += played;
+        samples.extend(table_samples);
+    }
+
+    report::print(
+        &samples,
+        opts.tables as usize,
+        probe.peak(),
+        hands,
+        started.elapsed(),
+    );
+    Ok(samples.iter().all(|s| s.error.is_none()))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let opts = match parse_args() {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("load-test: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(opts).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("load-test: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Latency percentiles and error counts over a run's samples.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// One coordinator request.
+pub struct Sample {
+    pub endpoint: &'static str,
+    pub latency: Duration,
+    /// From sending the request to the committee receiving its prepare
+    /// call; `None` if the request never reached the committee.
+    pub lock_wait: Option<Duration>,
+    pub error: Option<String>,
+}
+
+/// Nearest-rank percentile of sorted durations.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn ms(d: Duration) -> String {
+    format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+}
+
+fn row(label: &str, ok: usize, err: usize, mut durations: Vec<Duration>) {
+    durations.sort_unstable();
+    println!(
+        "{:<10} {:>6} {:>5} {:>10} {:>10} {:>10} {:>10}",
+        label,
+        ok,
+        err,
+        ms(percentile(&durations, 50)),
+        ms(percentile(&durations, 95)),
+        ms(percentile(&durations, 99)),
+        ms(durations.last().copied().unwrap_or_default()),
+    );
+}
+
+pub fn print(samples: &[Sample], tables: usize, peak: usize, hands: usize, elapsed: Duration) {
+    println!(
+        "{:<10} {:>6} {:>5} {:>10} {:>10} {:>10} {:>10}",
+        "endpoint", "ok", "err", "p50", "p95", "p99", "max"
+    );
+    for endpoint in ["deal", "reveal", "showdown"] {
+        let of_endpoint: Vec<&Sample> = samples.iter().filter(|s| s.endpoint == endpoint).collect();
+        let errors = of_endpoint.iter().filter(|s| s.error.is_some()).count();
+        let latencies = of_endpoint
+            .iter()
+            .filter(|s| s.error.is_none())
+            .map(|s| s.latency)
+            .collect();
+        row(endpoint, of_endpoint.len() - errors, errors, latencies);
+    }
+    let waits: Vec<Duration> = samples.iter().filter_map(|s| s.lock_wait).collect();
+    row("lock wait", waits.len(), 0, waits);
+
+    println!();
+    println!(
+        "peak tables in the committee at once: {} of {}",
+        peak, tables
+    );
+    println!(
+        "hands completed: {} in {:.1}s ({:.2} hands/s)",
+        hands,
+        elapsed.as_secs_f64(),
+        hands as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+
+    let mut errors: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for s in samples {
+        if let Some(e) = &s.error {
+            *errors.entry((s.endpoint, e.as_str())).or_default() += 1;
+        }
+    }
+    if !errors.is_empty() {
+        println!("errors:");
+        for ((endpoint, error), count) in errors {
+            println!("  {:<10} {} x{}", endpoint, error, count);
+        }
+    }
+}
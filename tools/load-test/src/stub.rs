@@ -0,0 +1,289 @@
+//! Stub MPC committee.
+//!
+//! Serves the node endpoints the coordinator calls for a deal, reveal or
+//! showdown, without shares or proving: each prepare call precomputes the
+//! public inputs the real proof would carry, and a session completes
+//! `prove_delay` after `generate`. Cards are dealt in deck order, so seat `p`
+//! holds positions `2p` and `2p + 1` and the board follows the hole cards.
+//!
+//! Node 0 also records when each table's request reached the committee and
+//! how many tables it is serving at once. The coordinator does its locking
+//! before calling the nodes, so those two numbers are what the load test
+//! reports as lock contention.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::Engine;
+use mpc_auth::commitment::CommitmentSigner;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Fixed size of the per-seat public inputs, as in the circuits.
+const MAX_PLAYERS: usize = 6;
+const MAX_REVEAL: usize = 3;
+
+/// Committee-wide view of the load, recorded by node 0.
+#[derive(Default)]
+pub struct Probe {
+    /// When the latest prepare call for each table reached the committee.
+    reached: Mutex<HashMap<u32, Instant>>,
+    in_flight: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl Probe {
+    pub fn reached_at(&self, table_id: u32) -> Option<Instant> {
+        self.reached.lock().unwrap().get(&table_id).copied()
+    }
+
+    /// Most tables the committee was working for at the same time.
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    fn enter(&self, table_id: u32) {
+        self.reached
+            .lock()
+            .unwrap()
+            .insert(table_id, Instant::now());
+        let now = self.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak.fetch_max(now, Ordering::Relaxed);
+    }
+
+    fn leave(&self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+struct Session {
+    public_inputs: Vec<String>,
+    ready_at: Option<Instant>,
+}
+
+struct Node {
+    id: u32,
+    parties: u32,
+    signer: CommitmentSigner,
+    prove_delay: Duration,
+    probe: Arc<Probe>,
+    /// Share set ID -> public inputs of the proof it will feed.
+    share_sets: Mutex<HashMap<String, Vec<String>>>,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+type Shared = Arc<Node>;
+
+/// Start `count` stub nodes on `127.0.0.1:base_port + i` and return their
+/// endpoints, in node order.
+pub async fn spawn(
+    count: u32,
+    base_port: u16,
+    prove_delay: Duration,
+    probe: Arc<Probe>,
+) -> Result<Vec<String>, String> {
+    let mut endpoints = Vec::new();
+    for id in 0..count {
+        let node = Arc::new(Node {
+            id,
+            parties: count,
+            signer: CommitmentSigner::from_seed(&[id as u8 + 1; 32]),
+            prove_delay,
+            probe: probe.clone(),
+            share_sets: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+        });
+        let addr = format!("127.0.0.1:{}", base_port + id as u16);
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .map_err(|e| format!("failed to bind stub node {} on {}: {}", id, addr, e))?;
+        tokio::spawn(async move { axum::serve(listener, router(node)).await });
+        endpoints.push(format!("http://{}", addr));
+    }
+    Ok(endpoints)
+}
+
+fn router(node: Shared) -> Router {
+    Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .route("/table/:table_id/prepare-deal", post(prepare_deal))
+        .route(
+            "/table/:table_id/prepare-reveal/:phase",
+            post(prepare_reveal),
+        )
+        .route("/table/:table_id/prepare-showdown", post(prepare_showdown))
+        .route("/table/:table_id/dispatch-shares", post(dispatch_shares))
+        .route("/table/:table_id/redispatch-shares", post(dispatch_shares))
+        .route("/session/:session_id/generate", post(generate))
+        .route("/session/:session_id/status", get(status))
+        .route("/session/:session_id/proof", get(proof))
+        .with_state(node)
+}
+
+fn field(value: u64) -> String {
+    format!("0x{:064x}", value)
+}
+
+/// Pad to the circuit's fixed width with zeros.
+fn padded(values: impl Iterator<Item = u32>, width: usize) -> Vec<String> {
+    let mut out: Vec<String> = values.map(|v| v.to_string()).collect();
+    out.resize(width, "0".to_string());
+    out
+}
+
+impl Node {
+    fn prepare(&self, table_id: u32, public_inputs: Vec<String>) -> String {
+        if self.id == 0 {
+            self.probe.enter(table_id);
+        }
+        let share_set_id = format!("stub-{}-{}-{}", self.id, table_id, uuid::Uuid::new_v4());
+        self.share_sets
+            .lock()
+            .unwrap()
+            .insert(share_set_id.clone(), public_inputs);
+        share_set_id
+    }
+}
+
+#[derive(Deserialize)]
+struct PrepareDeal {
+    players: Vec<String>,
+    #[serde(default)]
+    hand_number: u32,
+}
+
+async fn prepare_deal(
+    State(node): State<Shared>,
+    Path(table_id): Path<u32>,
+    Json(req): Json<PrepareDeal>,
+) -> Json<Value> {
+    let seats = req.players.len() as u32;
+    let hand = (u64::from(table_id) << 32) | u64::from(req.hand_number);
+    let mut public_inputs = vec![field(hand)];
+    public_inputs.extend((0..MAX_PLAYERS as u64).map(|p| field((hand << 3) | (p + 1))));
+    public_inputs.extend(padded((0..seats).map(|p| 2 * p), MAX_PLAYERS));
+    public_inputs.extend(padded((0..seats).map(|p| 2 * p + 1), MAX_PLAYERS));
+
+    let share_set_id = node.prepare(table_id, public_inputs);
+    let commitment = node.signer.commit(table_id, node.id, &[], &[]);
+    Json(json!({ "share_set_id": share_set_id, "commitment": commitment }))
+}
+
+#[derive(Deserialize)]
+struct PrepareReveal {
+    previously_used_indices: Vec<u32>,
+}
+
+async fn prepare_reveal(
+    State(node): State<Shared>,
+    Path((table_id, phase)): Path<(u32, String)>,
+    Json(req): Json<PrepareReveal>,
+) -> Json<Value> {
+    let count = if phase == "flop" { 3 } else { 1 };
+    let next = req.previously_used_indices.len() as u32;
+    let mut public_inputs = padded(next..next + count, MAX_REVEAL);
+    public_inputs.extend(padded(next..next + count, MAX_REVEAL));
+
+    Json(json!({ "share_set_id": node.prepare(table_id, public_inputs) }))
+}
+
+#[derive(Deserialize)]
+struct PrepareShowdown {
+    num_active_players: u32,
+}
+
+async fn prepare_showdown(
+    State(node): State<Shared>,
+    Path(table_id): Path<u32>,
+    Json(req): Json<PrepareShowdown>,
+) -> Json<Value> {
+    let seats = req.num_active_players.max(1);
+    let mut public_inputs = padded((0..seats).map(|p| 2 * p), MAX_PLAYERS);
+    public_inputs.extend(padded((0..seats).map(|p| 2 * p + 1), MAX_PLAYERS));
+    public_inputs.push((table_id % seats).to_string());
+
+    Json(json!({ "share_set_id": node.prepare(table_id, public_inputs) }))
+}
+
+#[derive(Deserialize)]
+struct Dispatch {
+    share_set_id: String,
+    proof_session_id: String,
+}
+
+async fn dispatch_shares(
+    State(node): State<Shared>,
+    Json(req): Json<Dispatch>,
+) -> Result<Json<Value>, StatusCode> {
+    let public_inputs = node
+        .share_sets
+        .lock()
+        .unwrap()
+        .remove(&req.share_set_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    node.sessions.lock().unwrap().insert(
+        req.proof_session_id,
+        Session {
+            public_inputs,
+            ready_at: None,
+        },
+    );
+    Ok(Json(json!({ "status": "dispatched" })))
+}
+
+async fn generate(
+    State(node): State<Shared>,
+    Path(session_id): Path<String>,
+) -> Result<Json<Value>, StatusCode> {
+    let mut sessions = node.sessions.lock().unwrap();
+    // Only node 0 is polled for the proof; the others are done.
+    if node.id != 0 {
+        sessions.remove(&session_id).ok_or(StatusCode::NOT_FOUND)?;
+        return Ok(Json(json!({ "status": "generating" })));
+    }
+    let session = sessions.get_mut(&session_id).ok_or(StatusCode::NOT_FOUND)?;
+    session.ready_at = Some(Instant::now() + node.prove_delay);
+    Ok(Json(json!({ "status": "generating" })))
+}
+
+async fn status(
+    State(node): State<Shared>,
+    Path(session_id): Path<String>,
+) -> Result<Json<Value>, StatusCode> {
+    let sessions = node.sessions.lock().unwrap();
+    let session = sessions.get(&session_id).ok_or(StatusCode::NOT_FOUND)?;
+    let status = match session.ready_at {
+        Some(at) if at <= Instant::now() => "complete",
+        Some(_) => "generating",
+        None => "waiting",
+    };
+    Ok(Json(json!({
+        "session_id": session_id,
+        "status": status,
+        "fragments_received": (0..node.parties).collect::<Vec<u32>>(),
+    })))
+}
+
+async fn proof(
+    State(node): State<Shared>,
+    Path(session_id): Path<String>,
+) -> Result<Json<Value>, StatusCode> {
+    let session = node
+        .sessions
+        .lock()
+        .unwrap()
+        .remove(&session_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    node.probe.leave();
+    Ok(Json(json!({
+        "session_id": session_id,
+        "proof": base64::engine::general_purpose::STANDARD.encode([0u8; 64]),
+        "public_inputs": session.public_inputs,
+    })))
+}
@@ -0,0 +1,272 @@
+//! Play complete hands locally, with no chain and no MPC.
+//!
+//! The table contract runs in an in-process Soroban `Env` set up by
+//! `integration_tests::Harness`, so betting and settlement go through the
+//! same state machine as on-chain. Decks come from
+//! `stellar_zk_cards::shuffle_deck`, and the mocked committee deals and
+//! reveals from them with canned proofs.
+
+mod player;
+
+use std::time::Instant;
+
+use integration_tests::{Harness, BIG_BLIND};
+use player::{action_name, ask_human, card_name, scripted_action, Strategy, View};
+use poker_table::{Action, GamePhase, TableState};
+use rand::{rngs::StdRng, SeedableRng};
+
+const USAGE: &str = "\
+usage: poker-sim [--hands N] [--seed N] [--stack CHIPS] [--players LIST] [--quiet]
+
+Plays up to N hands (default 100) at one table with blinds 5/10, stopping
+early if a player busts. LIST is 2-6 comma-separated player types from
+passive, aggressive, random and human (default passive,aggressive,random).
+Each player buys in for CHIPS (100-10000, default 1000).";
+
+const CATEGORIES: [&str; 10] = [
+    "high card",
+    "one pair",
+    "two pair",
+    "three of a kind",
+    "straight",
+    "flush",
+    "full house",
+    "four of a kind",
+    "straight flush",
+    "royal flush",
+];
+
+struct Options {
+    hands: u32,
+    seed: u64,
+    stack: i128,
+    players: Vec<Strategy>,
+    quiet: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut opts = Options {
+        hands: 100,
+        seed: 0,
+        stack: 1_000,
+        players: vec![Strategy::Passive, Strategy::Aggressive, Strategy::Random],
+        quiet: false,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--hands" => opts.hands = value()?.parse().map_err(|_| "bad --hands")?,
+            "--seed" => opts.seed = value()?.parse().map_err(|_| "bad --seed")?,
+            "--stack" => opts.stack = value()?.parse().map_err(|_| "bad --stack")?,
+            "--players" => {
+                opts.players = value()?
+                    .split(',')
+                    .map(Strategy::parse)
+                    .collect::<Result<_, _>>()?
+            }
+            "--quiet" => opts.quiet = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    if !(2..=6).contains(&opts.players.len()) {
+        return Err("the table seats 2-6 players".to_string());
+    }
+    if !(100..=10_000).contains(&opts.stack) {
+        return Err("--stack must be between 100 and 10000".to_string());
+    }
+    Ok(opts)
+}
+
+fn main() {
+    let opts = parse_args().unwrap_or_else(|e| {
+        eprintln!("poker-sim: {}\n\n{}", e, USAGE);
+        std::process::exit(2);
+    });
+    // Scripted play is only worth narrating when someone is watching it.
+    let log = !opts.quiet || opts.players.contains(&Strategy::Human);
+
+    let h = Harness::new(&vec![opts.stack; opts.players.len()]);
+    let mut rng = StdRng::seed_from_u64(opts.seed);
+    let started = Instant::now();
+
+    let mut played = 0;
+    for hand in 0..opts.hands {
+        let deck = stellar_zk_cards::shuffle_deck(opts.seed.wrapping_add(hand as u64));
+        play_hand(&h, &opts.players, &deck, &mut rng, log);
+        played += 1;
+
+        if let Some(seat) = h.stacks().iter().position(|&s| s == 0) {
+            // The table has no way to sit a busted player out.
+            println!("seat {} busted after hand {}", seat, played);
+            break;
+        }
+    }
+
+    println!("played {} hands in {:.2?}", played, started.elapsed());
+    for (seat, (stack, strategy)) in h.stacks().into_iter().zip(&opts.players).enumerate() {
+        println!(
+            "  seat {} {:<10} stack {:>6}  net {:>+6}  hub points {:>+6}",
+            seat,
+            strategy.name(),
+            stack,
+            stack - opts.stack,
+            h.hub.get_points(&h.players[seat])
+        );
+    }
+}
+
+/// Play one hand from the blinds to settlement. The harness deals deck
+/// positions `2s` and `2s + 1` to seat `s`; the board comes off the deck
+/// after the hole cards.
+fn play_hand(h: &Harness, players: &[Strategy], deck: &[u32; 52], rng: &mut StdRng, log: bool) {
+    h.start_hand();
+    h.commit_deal();
+    let start = h.stacks();
+    let state = h.state();
+    let hole: Vec<(u32, u32)> = (0..players.len())
+        .map(|s| (deck[2 * s], deck[2 * s + 1]))
+        .collect();
+    let mut next_card = 2 * players.len();
+
+    if log {
+        println!(
+            "hand {} (dealer seat {})",
+            state.hand_number, state.dealer_seat
+        );
+        for (seat, (c1, c2)) in hole.iter().enumerate() {
+            println!(
+                "  seat {} {:<10} {} {}  stack {}",
+                seat,
+                players[seat].name(),
+                card_name(*c1),
+                card_name(*c2),
+                state.players.get(seat as u32).unwrap().unwrap().stack
+            );
+        }
+    }
+
+    loop {
+        let state = h.state();
+        let reveal = match state.phase {
+            GamePhase::Preflop | GamePhase::Flop | GamePhase::Turn | GamePhase::River => {
+                act(h, players, &hole, &state, rng, log);
+                continue;
+            }
+            GamePhase::DealingFlop => 3,
+            GamePhase::DealingTurn | GamePhase::DealingRiver => 1,
+            GamePhase::RunOut if state.board_cards.is_empty() => 3,
+            GamePhase::RunOut => 1,
+            GamePhase::Showdown => {
+                let live: Vec<(u32, u32)> = state
+                    .players
+                    .iter()
+                    .flatten()
+                    .filter(|p| !p.folded)
+                    .map(|p| hole[p.seat_index as usize])
+                    .collect();
+                h.showdown(&live);
+                if log {
+                    for p in state.players.iter().flatten().filter(|p| !p.folded) {
+                        let (c1, c2) = hole[p.seat_index as usize];
+                        let mut cards = [c1, c2, 0, 0, 0, 0, 0];
+                        for (i, c) in state.board_cards.iter().enumerate() {
+                            cards[2 + i] = c;
+                        }
+                        let rank = stellar_zk_cards::evaluate_hand(&cards);
+                        println!(
+                            "  seat {} shows {}",
+                            p.seat_index,
+                            CATEGORIES[rank.category() as usize]
+                        );
+                    }
+                }
+                continue;
+            }
+            GamePhase::Settlement => break,
+            other => panic!("hand {} stuck in {:?}", state.hand_number, other),
+        };
+
+        let cards = &deck[next_card..next_card + reveal];
+        let indices: Vec<u32> = (next_card..next_card + reveal).map(|i| i as u32).collect();
+        h.reveal(cards, &indices);
+        next_card += reveal;
+        if log {
+            let names: Vec<String> = cards.iter().map(|&c| card_name(c)).collect();
+            println!("  board {}", names.join(" "));
+        }
+    }
+
+    h.assert_chips_conserved();
+    if log {
+        for (seat, (before, after)) in start.iter().zip(h.stacks()).enumerate() {
+            if after > *before {
+                println!("  seat {} wins {}", seat, after - before);
+            }
+        }
+    }
+}
+
+/// Get an action from the seat to act and apply it. Scripted seats must
+/// only produce actions the table accepts; a human is asked again.
+fn act(
+    h: &Harness,
+    players: &[Strategy],
+    hole: &[(u32, u32)],
+    state: &TableState,
+    rng: &mut StdRng,
+    log: bool,
+) {
+    let seat = state.current_turn;
+    let me = state.players.get(seat).unwrap().unwrap();
+    let view = View {
+        seat,
+        hole: hole[seat as usize],
+        board: state.board_cards.iter().collect(),
+        pot: state.pot,
+        stack: me.stack,
+        bet_this_round: me.bet_this_round,
+        max_bet: state
+            .players
+            .iter()
+            .flatten()
+            .map(|p| p.bet_this_round)
+            .max()
+            .unwrap_or(0),
+        big_blind: BIG_BLIND,
+        min_raise: state.min_raise,
+        may_raise: !me.acted,
+    };
+    let strategy = players[seat as usize];
+
+    loop {
+        let action: Action = match strategy {
+            Strategy::Human => ask_human(&view),
+            _ => scripted_action(strategy, &view, rng),
+        };
+        match h
+            .table
+            .try_player_action(&h.table_id, &h.players[seat as usize], &action)
+        {
+            Ok(_) => {
+                if log {
+                    println!("  seat {} {}", seat, action_name(&action));
+                }
+                h.assert_chips_conserved();
+                return;
+            }
+            Err(Ok(e)) if strategy == Strategy::Human => println!("  rejected: {:?}", e),
+            Err(e) => panic!(
+                "seat {} ({}) played {:?}, which the table rejected: {:?}",
+                seat,
+                strategy.name(),
+                action,
+                e
+            ),
+        }
+    }
+}
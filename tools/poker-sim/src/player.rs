@@ -0,0 +1,178 @@
+use std::io::{self, BufRead, Write};
+
+use poker_table::Action;
+use rand::{rngs::StdRng, Rng};
+
+/// How a seat picks its actions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// Checks when it can, otherwise calls.
+    Passive,
+    /// Bets or raises the minimum until it has put four big blinds in this
+    /// round, then calls.
+    Aggressive,
+    /// Picks a legal action at random, going all-in now and then.
+    Random,
+    /// Asks on stdin.
+    Human,
+}
+
+impl Strategy {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "passive" => Ok(Strategy::Passive),
+            "aggressive" => Ok(Strategy::Aggressive),
+            "random" => Ok(Strategy::Random),
+            "human" => Ok(Strategy::Human),
+            other => Err(format!("unknown player type '{}'", other)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Passive => "passive",
+            Strategy::Aggressive => "aggressive",
+            Strategy::Random => "random",
+            Strategy::Human => "human",
+        }
+    }
+}
+
+/// What the seat to act can see.
+pub struct View {
+    pub seat: u32,
+    pub hole: (u32, u32),
+    pub board: Vec<u32>,
+    pub pot: i128,
+    pub stack: i128,
+    pub bet_this_round: i128,
+    /// Highest bet any player has made this round.
+    pub max_bet: i128,
+    pub big_blind: i128,
+    /// Smallest raise the table accepts.
+    pub min_raise: i128,
+    /// False once the seat has acted and nobody has made a full raise since.
+    pub may_raise: bool,
+}
+
+impl View {
+    fn to_call(&self) -> i128 {
+        self.max_bet - self.bet_this_round
+    }
+
+    /// Actions the table accepts from this seat. Bets and raises are the
+    /// minimum size.
+    fn legal_actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        if self.to_call() == 0 {
+            actions.push(Action::Check);
+        } else {
+            actions.push(Action::Fold);
+            actions.push(Action::Call);
+        }
+        if self.max_bet == 0 && self.stack >= self.big_blind {
+            actions.push(Action::Bet(self.big_blind));
+        }
+        if self.max_bet > 0 && self.may_raise && self.stack >= self.to_call() + self.min_raise {
+            actions.push(Action::Raise(self.min_raise));
+        }
+        actions
+    }
+}
+
+/// Choose the action of a scripted seat. Panics for `Strategy::Human`.
+pub fn scripted_action(strategy: Strategy, view: &View, rng: &mut StdRng) -> Action {
+    let check_or_call = if view.to_call() == 0 {
+        Action::Check
+    } else {
+        Action::Call
+    };
+    match strategy {
+        Strategy::Passive => check_or_call,
+        Strategy::Aggressive => {
+            if view.bet_this_round >= 4 * view.big_blind {
+                return check_or_call;
+            }
+            view.legal_actions()
+                .into_iter()
+                .find(|a| matches!(a, Action::Bet(_) | Action::Raise(_)))
+                .unwrap_or(check_or_call)
+        }
+        Strategy::Random => {
+            // Going all-in for more than the call is a raise.
+            if rng.gen_ratio(1, 20) && (view.may_raise || view.stack <= view.to_call()) {
+                return Action::AllIn;
+            }
+            let actions = view.legal_actions();
+            actions[rng.gen_range(0..actions.len())].clone()
+        }
+        Strategy::Human => unreachable!("human seats are asked on stdin"),
+    }
+}
+
+/// Prompt on stdin until a well-formed action is entered. Exits the process
+/// on end of input.
+pub fn ask_human(view: &View) -> Action {
+    let board: Vec<String> = view.board.iter().map(|&c| card_name(c)).collect();
+    println!(
+        "  seat {}: you hold {} {} | board [{}] | pot {} | stack {} | to call {}",
+        view.seat,
+        card_name(view.hole.0),
+        card_name(view.hole.1),
+        board.join(" "),
+        view.pot,
+        view.stack,
+        view.to_call()
+    );
+    let stdin = io::stdin();
+    loop {
+        print!("  action (f)old (k)check (c)all (b)et N (r)aise N (a)ll-in > ");
+        io::stdout().flush().ok();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            println!();
+            std::process::exit(0);
+        }
+        match parse_action(line.trim()) {
+            Some(action) => return action,
+            None => println!("  could not read '{}'", line.trim()),
+        }
+    }
+}
+
+fn parse_action(input: &str) -> Option<Action> {
+    let mut words = input.split_whitespace();
+    let verb = words.next()?;
+    let amount = words.next().map(str::parse::<i128>);
+    match (verb, amount) {
+        ("f" | "fold", None) => Some(Action::Fold),
+        ("k" | "check", None) => Some(Action::Check),
+        ("c" | "call", None) => Some(Action::Call),
+        ("a" | "allin" | "all-in", None) => Some(Action::AllIn),
+        ("b" | "bet", Some(Ok(n))) => Some(Action::Bet(n)),
+        ("r" | "raise", Some(Ok(n))) => Some(Action::Raise(n)),
+        _ => None,
+    }
+}
+
+/// Short card name such as `As` or `Td`.
+pub fn card_name(card: u32) -> String {
+    const RANKS: &[u8] = b"23456789TJQKA";
+    const SUITS: &[u8] = b"cdhs";
+    format!(
+        "{}{}",
+        RANKS[(card % 13) as usize] as char,
+        SUITS[(card / 13) as usize] as char
+    )
+}
+
+pub fn action_name(action: &Action) -> String {
+    match action {
+        Action::Fold => "folds".to_string(),
+        Action::Check => "checks".to_string(),
+        Action::Call => "calls".to_string(),
+        Action::Bet(n) => format!("bets {}", n),
+        Action::Raise(n) => format!("raises {}", n),
+        Action::AllIn => "goes all-in".to_string(),
+    }
+}